@@ -1,15 +1,21 @@
+use crate::agent::compactor::{needs_compaction, LlmContextCompactor, SUMMARY_PREFIX};
 use crate::agent::dispatcher::{
-    NativeToolDispatcher, ParsedToolCall, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
+    ParsedToolCall, ToolDispatchMode, ToolDispatcher, ToolExecutionResult,
 };
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
+use crate::agent::plan::{self, Plan, PlannedStep, StepOutcome};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
+use crate::agent::traits::{CompactMessage, ContextCompactor};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
+use crate::security::grants::PathGrants;
 use crate::security::SecurityPolicy;
+use crate::sessions::{SessionFilter, SessionKey, SessionStore, TranscriptEntry};
 use crate::tools::{self, Tool, ToolSpec};
+use crate::util::i18n::{self, Text};
 use anyhow::Result;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
@@ -29,7 +35,9 @@ pub struct Agent {
     temperature: f64,
     workspace_dir: std::path::PathBuf,
     auto_save: bool,
+    compactor: Option<Box<dyn ContextCompactor>>,
     history: Vec<ConversationMessage>,
+    pending_plan: Option<Plan>,
 }
 
 pub struct AgentBuilder {
@@ -45,6 +53,7 @@ pub struct AgentBuilder {
     temperature: Option<f64>,
     workspace_dir: Option<std::path::PathBuf>,
     auto_save: Option<bool>,
+    compactor: Option<Box<dyn ContextCompactor>>,
 }
 
 impl AgentBuilder {
@@ -62,6 +71,7 @@ impl AgentBuilder {
             temperature: None,
             workspace_dir: None,
             auto_save: None,
+            compactor: None,
         }
     }
 
@@ -125,6 +135,12 @@ impl AgentBuilder {
         self
     }
 
+    /// Summarize older turns instead of dropping them when history overflows.
+    pub fn compactor(mut self, compactor: Box<dyn ContextCompactor>) -> Self {
+        self.compactor = Some(compactor);
+        self
+    }
+
     pub fn build(self) -> Result<Agent> {
         let tools = self
             .tools
@@ -161,7 +177,9 @@ impl AgentBuilder {
                 .workspace_dir
                 .unwrap_or_else(|| std::path::PathBuf::from(".")),
             auto_save: self.auto_save.unwrap_or(false),
+            compactor: self.compactor,
             history: Vec::new(),
+            pending_plan: None,
         })
     }
 }
@@ -184,18 +202,24 @@ impl Agent {
             Arc::from(observability::create_observer(&config.observability));
         let runtime: Arc<dyn runtime::RuntimeAdapter> =
             Arc::from(runtime::create_runtime(&config.runtime)?);
-        let security = Arc::new(SecurityPolicy::from_config(
-            &config.autonomy,
-            &config.workspace_dir,
-        ));
+        let security = Arc::new(
+            SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+                .with_path_grants(PathGrants::for_config(config)),
+        );
 
-        let memory: Arc<dyn Memory> = Arc::from(memory::create_memory(
+        let memory: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
             &config.memory,
+            &config.embedding_routes,
             &config.workspace_dir,
             config.api_key.as_deref(),
         )?);
 
-        let tools = tools::default_tools_with_runtime(security, runtime, memory.clone());
+        let tools = tools::all_tools_with_runtime(
+            Arc::new(config.clone()),
+            &security,
+            runtime,
+            memory.clone(),
+        );
 
         let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
 
@@ -211,13 +235,19 @@ impl Agent {
             config.api_url.as_deref(),
         )?;
 
-        let dispatcher_choice = config.agent.tool_dispatcher.as_str();
-        let tool_dispatcher: Box<dyn ToolDispatcher> = match dispatcher_choice {
-            "native" => Box::new(NativeToolDispatcher),
-            "xml" => Box::new(XmlToolDispatcher),
-            _ if provider.supports_native_tools() => Box::new(NativeToolDispatcher),
-            _ => Box::new(XmlToolDispatcher),
-        };
+        // The compactor gets its own client so summaries don't contend with turns.
+        let compactor = LlmContextCompactor::new(
+            Arc::from(providers::create_provider_with_url(
+                provider_name,
+                config.api_key.as_deref(),
+                config.api_url.as_deref(),
+            )?),
+            model_name.clone(),
+        )
+        .with_memory(memory.clone());
+
+        let tool_dispatcher = ToolDispatchMode::from_config(&config.agent.tool_dispatcher)
+            .create_dispatcher(provider.supports_native_tools());
 
         Agent::builder()
             .provider(provider)
@@ -235,6 +265,7 @@ impl Agent {
             .temperature(config.default_temperature)
             .workspace_dir(config.workspace_dir.clone())
             .auto_save(config.memory.auto_save)
+            .compactor(Box::new(compactor))
             .build()
     }
 
@@ -265,6 +296,67 @@ impl Agent {
         self.history.extend(other_messages);
     }
 
+    /// Fold older turns into a summary once history exceeds
+    /// `max_history_messages` or `max_history_tokens`. The kept window starts
+    /// at a user message so tool calls stay paired with their results. Falls
+    /// back to [`Self::trim_history`] without a compactor or on failure.
+    async fn compact_history(&mut self) {
+        let Some(compactor) = &self.compactor else {
+            self.trim_history();
+            return;
+        };
+
+        let as_compact: Vec<CompactMessage> = self.history.iter().map(to_compact_message).collect();
+        if !needs_compaction(
+            &as_compact,
+            self.config.max_history_messages,
+            self.config.max_history_tokens,
+        ) {
+            return;
+        }
+
+        let start = self
+            .history
+            .iter()
+            .take_while(|m| matches!(m, ConversationMessage::Chat(c) if c.role == "system"))
+            .count();
+        let keep = self.config.max_history_messages / 2;
+        let split = (start..=self.history.len().saturating_sub(keep))
+            .rev()
+            .find(|&i| self.history.get(i).is_some_and(is_user_message))
+            .unwrap_or(start);
+        if split == start {
+            self.trim_history();
+            return;
+        }
+
+        // The window is kept here, so the compactor summarizes all of `older`.
+        let older = &as_compact[start..split];
+        match compactor.compact(older, 0).await {
+            Ok(compacted) => {
+                // Summaries go in as assistant notes: some providers only read
+                // the first system message.
+                let notes = compacted.into_iter().map(|m| {
+                    let chat = if m.is_summary {
+                        ChatMessage::assistant(m.content)
+                    } else {
+                        ChatMessage {
+                            role: m.role,
+                            content: m.content,
+                            parts: Vec::new(),
+                        }
+                    };
+                    ConversationMessage::Chat(chat)
+                });
+                self.history.splice(start..split, notes);
+            }
+            Err(e) => {
+                tracing::warn!("History compaction failed, trimming instead: {e}");
+                self.trim_history();
+            }
+        }
+    }
+
     fn build_system_prompt(&self) -> Result<String> {
         let instructions = self.tool_dispatcher.prompt_instructions(&self.tools);
         let ctx = PromptContext {
@@ -277,43 +369,61 @@ impl Agent {
     }
 
     async fn execute_tool_call(&self, call: &ParsedToolCall) -> ToolExecutionResult {
-        let start = Instant::now();
-
-        let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
-                Ok(r) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: r.success,
-                    });
-                    if r.success {
-                        r.output
-                    } else {
-                        format!("Error: {}", r.error.unwrap_or(r.output))
-                    }
-                }
-                Err(e) => {
-                    self.observer.record_event(&ObserverEvent::ToolCall {
-                        tool: call.name.clone(),
-                        duration: start.elapsed(),
-                        success: false,
-                    });
-                    format!("Error executing {}: {e}", call.name)
-                }
-            }
-        } else {
-            format!("Unknown tool: {}", call.name)
-        };
-
+        let (output, _) = self.run_tool_call(call).await;
         ToolExecutionResult {
             name: call.name.clone(),
-            output: result,
+            output,
             success: true,
             tool_call_id: call.tool_call_id.clone(),
         }
     }
 
+    /// Run one call and return its scrubbed output and whether it succeeded.
+    async fn run_tool_call(&self, call: &ParsedToolCall) -> (String, bool) {
+        let start = Instant::now();
+
+        let (result, succeeded) =
+            if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
+                match tool.execute(call.arguments.clone()).await {
+                    Ok(r) => {
+                        self.observer.record_event(&ObserverEvent::ToolCall {
+                            tool: call.name.clone(),
+                            duration: start.elapsed(),
+                            success: r.success,
+                            error_kind: r
+                                .error
+                                .as_ref()
+                                .filter(|_| !r.success)
+                                .map(|e| e.kind.as_str().to_string()),
+                        });
+                        let output = if r.success {
+                            r.output
+                        } else if let Some(error) = r.error {
+                            error.to_tool_output()
+                        } else {
+                            format!("Error: {}", r.output)
+                        };
+                        (output, r.success)
+                    }
+                    Err(e) => {
+                        self.observer.record_event(&ObserverEvent::ToolCall {
+                            tool: call.name.clone(),
+                            duration: start.elapsed(),
+                            success: false,
+                            error_kind: Some(
+                                tools::ToolErrorKind::ExecutionFailed.as_str().to_string(),
+                            ),
+                        });
+                        (format!("Error executing {}: {e}", call.name), false)
+                    }
+                }
+            } else {
+                (format!("Unknown tool: {}", call.name), false)
+            };
+
+        (crate::agent::loop_::scrub_credentials(&result), succeeded)
+    }
+
     async fn execute_tools(&self, calls: &[ParsedToolCall]) -> Vec<ToolExecutionResult> {
         if !self.config.parallel_tools {
             let mut results = Vec::with_capacity(calls.len());
@@ -330,11 +440,100 @@ impl Agent {
         futures_util::future::join_all(futs).await
     }
 
+    /// Planning counterpart of [`Self::execute_tools`]: tools that report
+    /// [`Tool::is_read_only`] run, every other call is appended to `steps` and
+    /// answered with a stub. Autonomy settings such as `auto_approve` play no
+    /// part, so an auto-approved `shell` is still only planned.
+    async fn plan_tools(
+        &self,
+        calls: &[ParsedToolCall],
+        steps: &mut Vec<PlannedStep>,
+    ) -> Vec<ToolExecutionResult> {
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            let read_only = self
+                .tools
+                .iter()
+                .any(|tool| tool.name() == call.name && tool.is_read_only());
+            if read_only {
+                results.push(self.execute_tool_call(call).await);
+                continue;
+            }
+            steps.push(PlannedStep {
+                tool: call.name.clone(),
+                arguments: call.arguments.clone(),
+            });
+            results.push(ToolExecutionResult {
+                name: call.name.clone(),
+                output: plan::not_executed_output(steps.len()),
+                success: true,
+                tool_call_id: call.tool_call_id.clone(),
+            });
+        }
+        results
+    }
+
     fn classify_model(&self, _user_message: &str) -> String {
         self.model_name.clone()
     }
 
     pub async fn turn(&mut self, user_message: &str) -> Result<String> {
+        self.pending_plan = None;
+        self.push_user_message(user_message, "").await?;
+        let effective_model = self.classify_model(user_message);
+        self.respond(&effective_model, self.temperature, None).await
+    }
+
+    /// Plan `user_message` instead of carrying it out (see [`crate::agent::plan`]). A plan
+    /// with steps stays pending until [`Self::execute_plan`] or the next turn.
+    pub async fn plan(&mut self, user_message: &str) -> Result<Plan> {
+        self.pending_plan = None;
+        self.push_user_message(user_message, plan::PLAN_MODE_PREFIX)
+            .await?;
+        let effective_model = self.classify_model(user_message);
+        self.respond_with_plan(user_message, &effective_model, self.temperature)
+            .await
+    }
+
+    /// The plan awaiting approval, if any.
+    pub fn pending_plan(&self) -> Option<&Plan> {
+        self.pending_plan.as_ref()
+    }
+
+    /// Run the pending plan's steps verbatim, in order, stopping at the first
+    /// failure. The report is added to history so later turns see the results.
+    pub async fn execute_plan(&mut self) -> Result<Vec<StepOutcome>> {
+        let Some(plan) = self.pending_plan.take() else {
+            anyhow::bail!("No plan to approve; use /plan <message> first");
+        };
+        let mut outcomes = Vec::with_capacity(plan.steps.len());
+        for step in &plan.steps {
+            let call = ParsedToolCall {
+                name: step.tool.clone(),
+                arguments: step.arguments.clone(),
+                tool_call_id: None,
+            };
+            let (output, success) = self.run_tool_call(&call).await;
+            outcomes.push(StepOutcome {
+                tool: step.tool.clone(),
+                output,
+                success,
+            });
+            if !success {
+                break;
+            }
+        }
+        self.history
+            .push(ConversationMessage::Chat(ChatMessage::assistant(
+                plan::render_outcomes(&outcomes, plan.steps.len()),
+            )));
+        self.compact_history().await;
+        Ok(outcomes)
+    }
+
+    /// Start an exchange: the system prompt on the first turn, then the user
+    /// message enriched with memory context and prefixed with `prefix`.
+    async fn push_user_message(&mut self, user_message: &str, prefix: &str) -> Result<()> {
         if self.history.is_empty() {
             let system_prompt = self.build_system_prompt()?;
             self.history
@@ -356,17 +555,90 @@ impl Agent {
             .await
             .unwrap_or_default();
 
-        let enriched = if context.is_empty() {
-            user_message.to_string()
-        } else {
-            format!("{context}{user_message}")
-        };
-
+        let enriched = format!("{prefix}{context}{user_message}");
         self.history
             .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
+        Ok(())
+    }
 
-        let effective_model = self.classify_model(user_message);
+    /// Index of the latest user message, where the last exchange starts.
+    fn last_exchange_start(&self) -> Option<usize> {
+        self.history.iter().rposition(is_user_message)
+    }
+
+    /// Drop the latest user message and everything after it. Tool side
+    /// effects are not reverted. Returns `false` when there is nothing to drop.
+    pub fn undo(&mut self) -> bool {
+        match self.last_exchange_start() {
+            Some(start) => {
+                self.history.truncate(start);
+                self.pending_plan = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Regenerate the answer to the latest user message, optionally with a
+    /// different model and/or temperature for this attempt only.
+    pub async fn retry(&mut self, model: Option<&str>, temperature: Option<f64>) -> Result<String> {
+        let Some(start) = self.last_exchange_start() else {
+            anyhow::bail!("Nothing to retry yet");
+        };
+        self.history.truncate(start + 1);
+        let ConversationMessage::Chat(user) = &self.history[start] else {
+            unreachable!("last_exchange_start points at a user message");
+        };
+        let planning = user
+            .content
+            .strip_prefix(plan::PLAN_MODE_PREFIX)
+            .map(str::to_string);
+        let model = match model {
+            Some(model) => model.to_string(),
+            None => self.classify_model(&user.content),
+        };
+        let temperature = temperature.unwrap_or(self.temperature);
+        // A retried plan is re-planned, never executed.
+        match planning {
+            Some(request) => {
+                self.pending_plan = None;
+                let plan = self
+                    .respond_with_plan(&request, &model, temperature)
+                    .await?;
+                Ok(plan.render())
+            }
+            None => self.respond(&model, temperature, None).await,
+        }
+    }
 
+    async fn respond_with_plan(
+        &mut self,
+        request: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<Plan> {
+        let mut steps = Vec::new();
+        let summary = self.respond(model, temperature, Some(&mut steps)).await?;
+        let plan = Plan {
+            request: request.to_string(),
+            summary,
+            steps,
+        };
+        if !plan.steps.is_empty() {
+            self.pending_plan = Some(plan.clone());
+        }
+        Ok(plan)
+    }
+
+    /// Run the model/tool loop on the current history until the model
+    /// answers without tool calls. With `plan`, state-changing calls are
+    /// recorded there instead of executed.
+    async fn respond(
+        &mut self,
+        model: &str,
+        temperature: f64,
+        mut plan: Option<&mut Vec<PlannedStep>>,
+    ) -> Result<String> {
         for _ in 0..self.config.max_tool_iterations {
             let messages = self.tool_dispatcher.to_provider_messages(&self.history);
             let response = match self
@@ -380,8 +652,8 @@ impl Agent {
                             None
                         },
                     },
-                    &effective_model,
-                    self.temperature,
+                    model,
+                    temperature,
                 )
                 .await
             {
@@ -401,7 +673,7 @@ impl Agent {
                     .push(ConversationMessage::Chat(ChatMessage::assistant(
                         final_text.clone(),
                     )));
-                self.trim_history();
+                self.compact_history().await;
 
                 return Ok(final_text);
             }
@@ -421,10 +693,13 @@ impl Agent {
                 reasoning_content: response.reasoning_content.clone(),
             });
 
-            let results = self.execute_tools(&calls).await;
+            let results = match plan.as_deref_mut() {
+                Some(steps) => self.plan_tools(&calls, steps).await,
+                None => self.execute_tools(&calls).await,
+            };
             let formatted = self.tool_dispatcher.format_results(&results);
             self.history.push(formatted);
-            self.trim_history();
+            self.compact_history().await;
         }
 
         anyhow::bail!(
@@ -437,9 +712,70 @@ impl Agent {
         self.turn(message).await
     }
 
-    pub async fn run_interactive(&mut self) -> Result<()> {
-        println!("🦀 ZeroClaw Interactive Mode");
-        println!("Type /quit to exit.\n");
+    /// Seed an empty history from a stored transcript: the system prompt,
+    /// then the user and assistant turns from the first user message on
+    /// (plans are not replayed). Returns the number of restored messages.
+    fn restore_transcript(&mut self, entries: &[TranscriptEntry]) -> Result<usize> {
+        let start = entries
+            .iter()
+            .position(|entry| entry.role == "user")
+            .unwrap_or(entries.len());
+        let messages: Vec<ChatMessage> = entries[start..]
+            .iter()
+            .filter_map(|entry| match entry.role.as_str() {
+                "user" => Some(ChatMessage::user(entry.content.clone())),
+                "assistant" => Some(ChatMessage::assistant(entry.content.clone())),
+                _ => None,
+            })
+            .collect();
+        if messages.is_empty() {
+            return Ok(0);
+        }
+        let system_prompt = self.build_system_prompt()?;
+        self.history = std::iter::once(ChatMessage::system(system_prompt))
+            .chain(messages)
+            .map(ConversationMessage::Chat)
+            .collect();
+        Ok(self.history.len() - 1)
+    }
+
+    /// Plan `message`, then ask on stdin whether to execute the plan.
+    async fn run_planned(&mut self, session: &CliSession, message: &str) -> Result<()> {
+        session.record("user", message).await?;
+        let plan = self.plan(message).await?;
+        session.record_plan(&plan).await?;
+        println!("{}", plan.render());
+        if plan.steps.is_empty() {
+            return Ok(());
+        }
+
+        let locale = i18n::runtime_locale();
+        print!("\n{}", locale.text(Text::PlanConfirm));
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !locale.is_yes(&answer) {
+            println!("{}", locale.text(Text::PlanNotExecuted));
+            return Ok(());
+        }
+        let outcomes = self.execute_plan().await?;
+        let report = session.record_execution(&plan, &outcomes).await?;
+        println!("{report}");
+        Ok(())
+    }
+
+    /// Interactive REPL. With `plan_mode`, every message is planned instead
+    /// of executed until `/plan` toggles it off.
+    async fn run_interactive(
+        &mut self,
+        mut session: CliSession,
+        mut plan_mode: bool,
+    ) -> Result<()> {
+        println!("{}", i18n::text(Text::InteractiveBanner));
+        println!("{}\n", i18n::text(Text::InteractiveHelp));
+        if plan_mode {
+            println!("{}\n", i18n::text(Text::PlanModeOn));
+        }
 
         let stdin = tokio::io::BufReader::new(tokio::io::stdin());
         use tokio::io::AsyncBufReadExt;
@@ -453,32 +789,389 @@ impl Agent {
                 None => break,
             };
             let trimmed = line.trim();
-            if trimmed == "/quit" || trimmed == "/exit" {
-                break;
-            }
             if trimmed.is_empty() {
                 continue;
             }
-            let response = match self.turn(trimmed).await {
-                Ok(resp) => resp,
-                Err(e) => {
-                    eprintln!("\nError: {e}\n");
+            let command = match parse_interactive_command(trimmed) {
+                Some(Ok(command)) => command,
+                Some(Err(usage)) => {
+                    eprintln!("{usage}\n");
+                    continue;
+                }
+                None if plan_mode => {
+                    self.plan_and_record(&session, trimmed).await?;
+                    continue;
+                }
+                None => {
+                    session.record("user", trimmed).await?;
+                    match self.turn(trimmed).await {
+                        Ok(response) => {
+                            session.record("assistant", &response).await?;
+                            println!("\n{response}\n");
+                        }
+                        Err(e) => eprintln!("\nError: {e} (use /retry to try again)\n"),
+                    }
                     continue;
                 }
             };
-            println!("\n{response}\n");
+
+            match command {
+                InteractiveCommand::Quit => break,
+                InteractiveCommand::Help => println!("{INTERACTIVE_HELP}\n"),
+                InteractiveCommand::Retry { model, temperature } => {
+                    match self.retry(model, temperature).await {
+                        Ok(response) => {
+                            match &self.pending_plan {
+                                Some(plan) => session.replace_plan(plan).await?,
+                                None => session.replace_answer(&response).await?,
+                            }
+                            println!("\n{response}\n");
+                        }
+                        Err(e) => eprintln!("\nError: {e}\n"),
+                    }
+                }
+                InteractiveCommand::Undo => {
+                    if self.undo() {
+                        session.drop_last_exchange().await?;
+                        println!("Dropped the last exchange.\n");
+                    } else {
+                        println!("Nothing to undo.\n");
+                    }
+                }
+                InteractiveCommand::Branch(name) => match session.branch(name).await {
+                    Ok(copied) => {
+                        println!("Branched into session '{name}' ({copied} messages).\n");
+                    }
+                    Err(e) => eprintln!("{e}\n"),
+                },
+                InteractiveCommand::Plan(Some(message)) => {
+                    self.plan_and_record(&session, message).await?;
+                }
+                InteractiveCommand::Plan(None) => {
+                    plan_mode = !plan_mode;
+                    if plan_mode {
+                        println!("Plan mode on: messages produce a plan; /approve executes it.\n");
+                    } else {
+                        println!("Plan mode off.\n");
+                    }
+                }
+                InteractiveCommand::Approve => {
+                    let Some(plan) = self.pending_plan.clone() else {
+                        println!("No plan to approve; use /plan <message> first.\n");
+                        continue;
+                    };
+                    match self.execute_plan().await {
+                        Ok(outcomes) => {
+                            let report = session.record_execution(&plan, &outcomes).await?;
+                            println!("\n{report}\n");
+                        }
+                        Err(e) => eprintln!("\nError: {e}\n"),
+                    }
+                }
+            }
         }
 
         Ok(())
     }
+
+    async fn plan_and_record(&mut self, session: &CliSession, message: &str) -> Result<()> {
+        session.record("user", message).await?;
+        match self.plan(message).await {
+            Ok(plan) => {
+                session.record_plan(&plan).await?;
+                println!("\n{}\n", plan.render());
+                if !plan.steps.is_empty() {
+                    println!("Type /approve to execute these steps exactly as listed.\n");
+                }
+            }
+            Err(e) => eprintln!("\nError: {e} (use /retry to try again)\n"),
+        }
+        Ok(())
+    }
+}
+
+/// Session key owner used for interactive CLI sessions.
+const CLI_SESSION_AGENT_ID: &str = "cli";
+
+const INTERACTIVE_HELP: &str = "\
+Commands:
+  /retry [model] [temperature]  Regenerate the last answer (overrides apply to this attempt only)
+  /undo                         Drop the last exchange from history (tool side effects stay)
+  /branch <name>                Fork the conversation into a new session and continue there
+  /plan [message]               Plan a message without running state-changing tools (no message: toggle plan mode)
+  /approve                      Execute the pending plan verbatim
+  /quit, /exit                  Leave interactive mode";
+
+/// Slash commands understood by [`Agent::run_interactive`].
+#[derive(Debug, PartialEq)]
+enum InteractiveCommand<'a> {
+    Quit,
+    Help,
+    Retry {
+        model: Option<&'a str>,
+        temperature: Option<f64>,
+    },
+    Undo,
+    Branch(&'a str),
+    Plan(Option<&'a str>),
+    Approve,
 }
 
+/// Parse a REPL line. `None` means the line is a message for the model
+/// (including unknown `/...` input); `Some(Err)` carries a usage hint.
+fn parse_interactive_command(
+    line: &str,
+) -> Option<std::result::Result<InteractiveCommand<'_>, String>> {
+    let mut words = line.split_whitespace();
+    let command = match words.next()? {
+        "/quit" | "/exit" => InteractiveCommand::Quit,
+        "/help" => InteractiveCommand::Help,
+        "/undo" => InteractiveCommand::Undo,
+        "/approve" => InteractiveCommand::Approve,
+        "/plan" => {
+            let message = line["/plan".len()..].trim();
+            InteractiveCommand::Plan((!message.is_empty()).then_some(message))
+        }
+        "/branch" => match (words.next(), words.next()) {
+            (Some(name), None) => InteractiveCommand::Branch(name),
+            _ => return Some(Err("Usage: /branch <name>".into())),
+        },
+        "/retry" => {
+            let mut model = None;
+            let mut temperature = None;
+            for word in words {
+                match word.parse::<f64>() {
+                    Ok(t) if temperature.is_none() && (0.0..=2.0).contains(&t) => {
+                        temperature = Some(t);
+                    }
+                    Err(_) if model.is_none() => model = Some(word),
+                    _ => return Some(Err("Usage: /retry [model] [temperature 0.0-2.0]".into())),
+                }
+            }
+            InteractiveCommand::Retry { model, temperature }
+        }
+        _ => return None,
+    };
+    Some(Ok(command))
+}
+
+/// Mirrors the interactive conversation into the session store so exchanges
+/// can be dropped, the conversation forked under a new session key, and a
+/// later run resumed from `<workspace>/state/sessions.db`.
+struct CliSession {
+    store: Box<dyn SessionStore>,
+    key: SessionKey,
+}
+
+impl CliSession {
+    /// Open session `name`, creating it if it does not exist yet.
+    async fn start(store: Box<dyn SessionStore>, name: &str) -> Result<Self> {
+        let key = SessionKey {
+            agent_id: CLI_SESSION_AGENT_ID.into(),
+            context: name.into(),
+        };
+        store.create(&key).await?;
+        Ok(Self { store, key })
+    }
+
+    /// Pick the session for a run: `name` if given, the most recently active
+    /// CLI session with `resume`, otherwise a fresh timestamped one.
+    async fn select(
+        store: Box<dyn SessionStore>,
+        name: Option<&str>,
+        resume: bool,
+    ) -> Result<Self> {
+        if let Some(name) = name {
+            return Self::start(store, name).await;
+        }
+        if resume {
+            let latest = store
+                .list(&SessionFilter {
+                    agent_id: Some(CLI_SESSION_AGENT_ID.into()),
+                    since: None,
+                    limit: Some(1),
+                })
+                .await?;
+            if let Some(session) = latest.into_iter().next() {
+                return Self::start(store, &session.key.context).await;
+            }
+        }
+        let base = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while store
+            .get(&SessionKey {
+                agent_id: CLI_SESSION_AGENT_ID.into(),
+                context: name.clone(),
+            })
+            .await?
+            .is_some()
+        {
+            suffix += 1;
+            name = format!("{base}-{suffix}");
+        }
+        Self::start(store, &name).await
+    }
+
+    fn name(&self) -> &str {
+        &self.key.context
+    }
+
+    /// The newest `limit` transcript entries, oldest first.
+    async fn recent(&self, limit: usize) -> Result<Vec<TranscriptEntry>> {
+        self.store.get_transcript(&self.key, Some(limit)).await
+    }
+
+    async fn record(&self, role: &str, content: &str) -> Result<()> {
+        self.append(TranscriptEntry {
+            role: role.into(),
+            content: content.into(),
+            timestamp: chrono::Utc::now(),
+            tool_calls: None,
+        })
+        .await
+    }
+
+    async fn append(&self, entry: TranscriptEntry) -> Result<()> {
+        self.store.append_transcript(&self.key, entry).await?;
+        self.store.update_activity(&self.key).await
+    }
+
+    async fn record_plan(&self, plan: &Plan) -> Result<()> {
+        self.append(plan.to_transcript_entry()).await
+    }
+
+    /// Record an approved plan's execution report with the calls that ran.
+    /// Returns the report.
+    async fn record_execution(&self, plan: &Plan, outcomes: &[StepOutcome]) -> Result<String> {
+        let report = plan::render_outcomes(outcomes, plan.steps.len());
+        self.append(TranscriptEntry {
+            role: "assistant".into(),
+            content: report.clone(),
+            timestamp: chrono::Utc::now(),
+            tool_calls: Some(
+                plan.steps
+                    .iter()
+                    .take(outcomes.len())
+                    .map(plan::transcript_call)
+                    .collect(),
+            ),
+        })
+        .await?;
+        Ok(report)
+    }
+
+    /// Replace the answer to the latest user message (there is none yet when
+    /// the original attempt failed).
+    async fn replace_answer(&self, content: &str) -> Result<()> {
+        self.drop_answer().await?;
+        self.record("assistant", content).await
+    }
+
+    async fn replace_plan(&self, plan: &Plan) -> Result<()> {
+        self.drop_answer().await?;
+        self.record_plan(plan).await
+    }
+
+    async fn drop_answer(&self) -> Result<()> {
+        let transcript = self.store.get_transcript(&self.key, None).await?;
+        if transcript
+            .last()
+            .is_some_and(|entry| entry.role == "assistant" || entry.role == plan::PLAN_ROLE)
+        {
+            self.store
+                .truncate_transcript(&self.key, transcript.len() - 1)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn drop_last_exchange(&self) -> Result<()> {
+        let transcript = self.store.get_transcript(&self.key, None).await?;
+        if let Some(start) = transcript.iter().rposition(|entry| entry.role == "user") {
+            self.store.truncate_transcript(&self.key, start).await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the current transcript into a new session `name` and switch to
+    /// it. Returns the number of copied entries.
+    async fn branch(&mut self, name: &str) -> Result<usize> {
+        let key = SessionKey {
+            agent_id: CLI_SESSION_AGENT_ID.into(),
+            context: name.into(),
+        };
+        if self.store.get(&key).await?.is_some() {
+            anyhow::bail!("Session '{name}' already exists");
+        }
+        let transcript = self.store.get_transcript(&self.key, None).await?;
+        self.store.create(&key).await?;
+        let copied = transcript.len();
+        for entry in transcript {
+            self.store.append_transcript(&key, entry).await?;
+        }
+        self.key = key;
+        Ok(copied)
+    }
+}
+
+fn is_user_message(message: &ConversationMessage) -> bool {
+    matches!(message, ConversationMessage::Chat(chat) if chat.role == "user")
+}
+
+fn to_compact_message(message: &ConversationMessage) -> CompactMessage {
+    let (role, content) = match message {
+        ConversationMessage::Chat(chat) => (chat.role.clone(), chat.content.clone()),
+        ConversationMessage::AssistantToolCalls {
+            text, tool_calls, ..
+        } => {
+            let names: Vec<&str> = tool_calls.iter().map(|c| c.name.as_str()).collect();
+            (
+                "assistant".into(),
+                format!(
+                    "{}[called tools: {}]",
+                    text.as_deref()
+                        .map(|t| format!("{t}\n"))
+                        .unwrap_or_default(),
+                    names.join(", ")
+                ),
+            )
+        }
+        ConversationMessage::ToolResults(results) => (
+            "tool".into(),
+            results
+                .iter()
+                .map(|r| r.content.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+    };
+    CompactMessage {
+        is_summary: role == "assistant" && content.starts_with(SUMMARY_PREFIX),
+        role,
+        content,
+    }
+}
+
+/// Open the workspace session store, falling back to an in-memory one (the
+/// run still works, it just cannot be resumed later).
+fn open_cli_session_store(workspace_dir: &std::path::Path) -> Box<dyn SessionStore> {
+    crate::sessions::create_persistent_session_store(workspace_dir).unwrap_or_else(|e| {
+        tracing::warn!("Session history will not be saved: {e:#}");
+        crate::sessions::create_session_store()
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     config: Config,
     message: Option<String>,
     provider_override: Option<String>,
     model_override: Option<String>,
     temperature: f64,
+    plan: bool,
+    session_name: Option<String>,
+    resume: bool,
 ) -> Result<()> {
     let start = Instant::now();
 
@@ -509,11 +1202,32 @@ pub async fn run(
         model: model_name.clone(),
     });
 
-    if let Some(msg) = message {
-        let response = agent.run_single(&msg).await?;
-        println!("{response}");
-    } else {
-        agent.run_interactive().await?;
+    let session = CliSession::select(
+        open_cli_session_store(&effective_config.workspace_dir),
+        session_name.as_deref(),
+        resume,
+    )
+    .await?;
+    let recent = session
+        .recent(effective_config.agent.max_history_messages)
+        .await?;
+    match agent.restore_transcript(&recent)? {
+        0 => eprintln!(
+            "Session '{0}' (continue later with --session {0} or --resume)",
+            session.name()
+        ),
+        restored => eprintln!("Resumed session '{}' ({restored} messages)", session.name()),
+    }
+
+    match message {
+        Some(msg) if plan => agent.run_planned(&session, &msg).await?,
+        Some(msg) => {
+            session.record("user", &msg).await?;
+            let response = agent.run_single(&msg).await?;
+            session.record("assistant", &response).await?;
+            println!("{response}");
+        }
+        None => agent.run_interactive(session, plan).await?,
     }
 
     agent.observer.record_event(&ObserverEvent::AgentEnd {
@@ -530,6 +1244,7 @@ pub async fn run(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agent::dispatcher::{NativeToolDispatcher, XmlToolDispatcher};
     use async_trait::async_trait;
     use parking_lot::Mutex;
 
@@ -562,6 +1277,7 @@ mod tests {
                     tool_calls: vec![],
                     usage: None,
                     reasoning_content: None,
+                    reasoning: None,
                 });
             }
             Ok(guard.remove(0))
@@ -601,6 +1317,7 @@ mod tests {
                 tool_calls: vec![],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             }]),
         });
 
@@ -641,12 +1358,14 @@ mod tests {
                     }],
                     usage: None,
                     reasoning_content: None,
+                    reasoning: None,
                 },
                 crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
                     usage: None,
                     reasoning_content: None,
+                    reasoning: None,
                 },
             ]),
         });
@@ -678,4 +1397,335 @@ mod tests {
             .iter()
             .any(|msg| matches!(msg, ConversationMessage::ToolResults(_))));
     }
+
+    fn text_response(text: &str) -> crate::providers::ChatResponse {
+        crate::providers::ChatResponse {
+            text: Some(text.into()),
+            tool_calls: vec![],
+            usage: None,
+            reasoning_content: None,
+            reasoning: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_records_mutating_calls_and_approval_runs_them() {
+        let tool_call_response = || crate::providers::ChatResponse {
+            text: Some(String::new()),
+            tool_calls: vec![crate::providers::ToolCall {
+                id: "tc1".into(),
+                name: "echo".into(),
+                arguments: r#"{"value":"x"}"#.into(),
+            }],
+            usage: None,
+            reasoning_content: None,
+            reasoning: None,
+        };
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![tool_call_response(), text_response("Echo x.")]),
+        });
+
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None)
+                .expect("memory creation should succeed with valid config"),
+        );
+
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![Box::new(MockTool)])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(NativeToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .expect("agent builder should succeed with valid config");
+
+        let plan = agent.plan("echo x").await.unwrap();
+        assert_eq!(plan.summary, "Echo x.");
+        assert_eq!(
+            plan.steps,
+            vec![PlannedStep {
+                tool: "echo".into(),
+                arguments: serde_json::json!({"value": "x"}),
+            }]
+        );
+        let stubbed = agent.history().iter().any(|msg| match msg {
+            ConversationMessage::ToolResults(results) => results
+                .iter()
+                .all(|r| r.content.starts_with("Not executed (plan mode)")),
+            _ => false,
+        });
+        assert!(stubbed, "planned calls must not run");
+        assert_eq!(agent.pending_plan(), Some(&plan));
+
+        let outcomes = agent.execute_plan().await.unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert!(outcomes[0].success);
+        assert_eq!(outcomes[0].output, "tool-out");
+        assert!(agent.pending_plan().is_none());
+        assert!(agent.execute_plan().await.is_err());
+    }
+
+    struct ReadOnlyMockTool;
+
+    #[async_trait]
+    impl Tool for ReadOnlyMockTool {
+        fn name(&self) -> &str {
+            "lookup"
+        }
+
+        fn description(&self) -> &str {
+            "lookup"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        fn is_read_only(&self) -> bool {
+            true
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: "looked-up".into(),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_runs_only_read_only_tools() {
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![
+                crate::providers::ChatResponse {
+                    text: Some(String::new()),
+                    tool_calls: vec![
+                        crate::providers::ToolCall {
+                            id: "tc1".into(),
+                            name: "lookup".into(),
+                            arguments: "{}".into(),
+                        },
+                        crate::providers::ToolCall {
+                            id: "tc2".into(),
+                            name: "echo".into(),
+                            arguments: "{}".into(),
+                        },
+                    ],
+                    usage: None,
+                    reasoning_content: None,
+                    reasoning: None,
+                },
+                text_response("Echo after lookup."),
+            ]),
+        });
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None)
+                .expect("memory creation should succeed with valid config"),
+        );
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![Box::new(ReadOnlyMockTool), Box::new(MockTool)])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(NativeToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .expect("agent builder should succeed with valid config");
+
+        let plan = agent.plan("look up, then echo").await.unwrap();
+        let planned: Vec<&str> = plan.steps.iter().map(|s| s.tool.as_str()).collect();
+        assert_eq!(planned, ["echo"]);
+        let ran_lookup = agent.history().iter().any(|msg| match msg {
+            ConversationMessage::ToolResults(results) => {
+                results.iter().any(|r| r.content == "looked-up")
+            }
+            _ => false,
+        });
+        assert!(ran_lookup, "read-only tools run while planning");
+    }
+
+    #[test]
+    fn interactive_commands_parse_with_usage_errors() {
+        assert_eq!(
+            parse_interactive_command("/retry gpt-4o 0.2"),
+            Some(Ok(InteractiveCommand::Retry {
+                model: Some("gpt-4o"),
+                temperature: Some(0.2),
+            }))
+        );
+        assert_eq!(
+            parse_interactive_command("/branch experiment"),
+            Some(Ok(InteractiveCommand::Branch("experiment")))
+        );
+        assert_eq!(
+            parse_interactive_command("/undo"),
+            Some(Ok(InteractiveCommand::Undo))
+        );
+        assert!(matches!(parse_interactive_command("/branch"), Some(Err(_))));
+        assert!(matches!(
+            parse_interactive_command("/retry 5.0"),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse_interactive_command("/plan  delete tmp files "),
+            Some(Ok(InteractiveCommand::Plan(Some("delete tmp files"))))
+        );
+        assert_eq!(
+            parse_interactive_command("/plan"),
+            Some(Ok(InteractiveCommand::Plan(None)))
+        );
+        assert_eq!(
+            parse_interactive_command("/approve"),
+            Some(Ok(InteractiveCommand::Approve))
+        );
+        assert_eq!(parse_interactive_command("/etc/hosts looks odd"), None);
+        assert_eq!(parse_interactive_command("hello"), None);
+    }
+
+    #[tokio::test]
+    async fn retry_replaces_and_undo_drops_the_last_exchange() {
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![text_response("first"), text_response("second")]),
+        });
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None)
+                .expect("memory creation should succeed with valid config"),
+        );
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![Box::new(MockTool)])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(XmlToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .expect("agent builder should succeed with valid config");
+
+        assert_eq!(agent.turn("hi").await.unwrap(), "first");
+        assert_eq!(
+            agent.retry(Some("other-model"), Some(0.2)).await.unwrap(),
+            "second"
+        );
+        let users = agent
+            .history()
+            .iter()
+            .filter(|m| is_user_message(m))
+            .count();
+        assert_eq!(users, 1);
+        assert!(matches!(
+            agent.history().last(),
+            Some(ConversationMessage::Chat(chat)) if chat.content == "second"
+        ));
+
+        assert!(agent.undo());
+        assert!(!agent.history().iter().any(is_user_message));
+        assert!(!agent.undo());
+        assert!(agent.retry(None, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cli_session_branch_copies_transcript_and_switches_key() {
+        let mut session = CliSession::start(crate::sessions::create_session_store(), "main")
+            .await
+            .unwrap();
+        session.record("user", "hi").await.unwrap();
+        session.record("assistant", "hello").await.unwrap();
+        session.replace_answer("hello again").await.unwrap();
+
+        assert_eq!(session.branch("alt").await.unwrap(), 2);
+        assert_eq!(session.key.context, "alt");
+        assert!(session.branch("alt").await.is_err());
+
+        session.drop_last_exchange().await.unwrap();
+        let main = SessionKey {
+            agent_id: CLI_SESSION_AGENT_ID.into(),
+            context: "main".into(),
+        };
+        let original = session.store.get_transcript(&main, None).await.unwrap();
+        assert_eq!(original.len(), 2);
+        assert_eq!(original[1].content, "hello again");
+        assert!(session
+            .store
+            .get_transcript(&session.key, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn resumed_session_restores_recent_transcript_into_history() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = || crate::sessions::create_persistent_session_store(tmp.path()).unwrap();
+        {
+            let session = CliSession::select(store(), Some("research"), false)
+                .await
+                .unwrap();
+            session.record("user", "what is rust?").await.unwrap();
+            session.record("assistant", "A language.").await.unwrap();
+            session.record(plan::PLAN_ROLE, "1. echo").await.unwrap();
+        }
+
+        let session = CliSession::select(store(), None, true).await.unwrap();
+        assert_eq!(session.name(), "research");
+        let fresh = CliSession::select(store(), None, false).await.unwrap();
+        assert_ne!(fresh.name(), "research");
+
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None)
+                .expect("memory creation should succeed with valid config"),
+        );
+        let mut agent = Agent::builder()
+            .provider(Box::new(MockProvider {
+                responses: Mutex::new(vec![]),
+            }))
+            .tools(vec![Box::new(MockTool)])
+            .memory(mem)
+            .observer(Arc::from(crate::observability::NoopObserver {}))
+            .tool_dispatcher(Box::new(XmlToolDispatcher))
+            .workspace_dir(std::path::PathBuf::from("/tmp"))
+            .build()
+            .expect("agent builder should succeed with valid config");
+
+        // A window that starts mid-exchange is trimmed to the next user turn.
+        let recent = session.recent(2).await.unwrap();
+        assert_eq!(agent.restore_transcript(&recent).unwrap(), 0);
+        assert!(agent.history().is_empty());
+
+        let all = session.recent(50).await.unwrap();
+        assert_eq!(agent.restore_transcript(&all).unwrap(), 2);
+        let roles: Vec<&str> = agent
+            .history()
+            .iter()
+            .filter_map(|msg| match msg {
+                ConversationMessage::Chat(chat) => Some(chat.role.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+        assert!(matches!(
+            agent.history().last(),
+            Some(ConversationMessage::Chat(chat)) if chat.content == "A language."
+        ));
+    }
 }
@@ -1,3 +1,5 @@
+use super::traits::{QueryClassification, QueryClassifier};
+
 /// Classify a user message — currently a no-op after query classification
 /// config was removed.
 ///
@@ -6,6 +8,58 @@ pub fn classify(_message: &str) -> Option<String> {
     None
 }
 
+const CODE_KEYWORDS: &[&str] = &["code", "function", "implement", "refactor", "script", "bug"];
+const SEARCH_KEYWORDS: &[&str] = &["search", "find", "look up", "lookup", "latest", "news"];
+const ANALYSIS_KEYWORDS: &[&str] = &["analyze", "compare", "review", "explain", "summarize"];
+const ACTION_KEYWORDS: &[&str] = &["run", "deploy", "send", "create", "delete", "schedule"];
+
+/// Keyword rules checked in order; the first category with a hit wins.
+const KEYWORD_RULES: &[(&[&str], QueryClassification)] = &[
+    (CODE_KEYWORDS, QueryClassification::CodeGeneration),
+    (SEARCH_KEYWORDS, QueryClassification::Search),
+    (ANALYSIS_KEYWORDS, QueryClassification::Analysis),
+    (ACTION_KEYWORDS, QueryClassification::Action),
+];
+
+/// Cheap, offline query classifier based on keyword matches.
+///
+/// Short follow-ups ("what do you mean?") classify as `Clarification`;
+/// anything without a keyword hit is `General`.
+#[derive(Debug, Clone, Default)]
+pub struct KeywordQueryClassifier;
+
+impl QueryClassifier for KeywordQueryClassifier {
+    fn classify(&self, query: &str) -> QueryClassification {
+        let lower = query.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        for (keywords, category) in KEYWORD_RULES {
+            let hit = keywords.iter().any(|kw| {
+                if kw.contains(' ') {
+                    lower.contains(kw)
+                } else {
+                    words.contains(kw)
+                }
+            });
+            if hit {
+                return category.clone();
+            }
+        }
+
+        if words.len() <= 4 && lower.trim_end().ends_with('?') {
+            return QueryClassification::Clarification;
+        }
+        QueryClassification::General
+    }
+
+    fn name(&self) -> &str {
+        "keyword"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,4 +69,38 @@ mod tests {
         assert_eq!(classify("hello"), None);
         assert_eq!(classify("write some code"), None);
     }
+
+    #[test]
+    fn keyword_classifier_maps_common_intents() {
+        let classifier = KeywordQueryClassifier;
+        assert_eq!(
+            classifier.classify("Refactor this function please"),
+            QueryClassification::CodeGeneration
+        );
+        assert_eq!(
+            classifier.classify("look up the weather in Oslo"),
+            QueryClassification::Search
+        );
+        assert_eq!(
+            classifier.classify("Compare these two plans"),
+            QueryClassification::Analysis
+        );
+        assert_eq!(
+            classifier.classify("deploy the staging build"),
+            QueryClassification::Action
+        );
+        assert_eq!(
+            classifier.classify("what do you mean?"),
+            QueryClassification::Clarification
+        );
+        assert_eq!(
+            classifier.classify("good morning"),
+            QueryClassification::General
+        );
+        // Substrings of longer words don't count.
+        assert_eq!(
+            classifier.classify("running late today"),
+            QueryClassification::General
+        );
+    }
 }
@@ -0,0 +1,326 @@
+//! LLM-backed conversation compaction.
+//!
+//! Older turns are folded into a single summary note while the most recent
+//! window is kept verbatim. Summaries are also written to memory so the
+//! context survives beyond the in-process history.
+
+use super::traits::{CompactMessage, ContextCompactor};
+use crate::memory::{Memory, MemoryCategory};
+use crate::providers::Provider;
+use crate::util::tokenizer::estimate_tokens;
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Most-recent messages kept verbatim by default.
+pub const DEFAULT_KEEP_RECENT_MESSAGES: usize = 20;
+
+/// Safety cap for the transcript passed to the summarizer.
+const MAX_SOURCE_CHARS: usize = 12_000;
+
+/// Max characters retained in a summary.
+const MAX_SUMMARY_CHARS: usize = 2_000;
+
+/// Prefix that marks a summary note in history.
+pub const SUMMARY_PREFIX: &str = "[Conversation summary]";
+
+const SUMMARIZER_SYSTEM: &str = "You are a conversation compaction engine. Summarize older chat history into concise context for future turns. Preserve: user preferences, commitments, decisions, unresolved tasks, key facts. Omit: filler, repeated chit-chat, verbose tool logs. Output plain text bullet points only.";
+
+/// Whether `messages` exceed `max_messages` non-system turns or, when
+/// `max_tokens > 0`, the estimated token budget.
+pub fn needs_compaction(
+    messages: &[CompactMessage],
+    max_messages: usize,
+    max_tokens: usize,
+) -> bool {
+    let turns = messages.iter().filter(|m| m.role != "system").count();
+    let tokens: usize = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+    turns > max_messages || (max_tokens > 0 && tokens > max_tokens)
+}
+
+/// Summarizes older turns with the configured provider.
+pub struct LlmContextCompactor {
+    provider: Arc<dyn Provider>,
+    model: String,
+    memory: Option<Arc<dyn Memory>>,
+    keep_recent: usize,
+}
+
+impl LlmContextCompactor {
+    pub fn new(provider: Arc<dyn Provider>, model: impl Into<String>) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            memory: None,
+            keep_recent: DEFAULT_KEEP_RECENT_MESSAGES,
+        }
+    }
+
+    /// Store each summary in `memory` under the Conversation category.
+    #[must_use]
+    pub fn with_memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Keep at most `keep_recent` messages verbatim.
+    #[must_use]
+    pub fn with_keep_recent(mut self, keep_recent: usize) -> Self {
+        self.keep_recent = keep_recent;
+        self
+    }
+
+    async fn summarize(&self, messages: &[CompactMessage]) -> String {
+        let transcript = build_transcript(messages);
+        let prompt = format!(
+            "Summarize the following conversation history for context preservation. Keep it short (max 12 bullet points).\n\n{transcript}"
+        );
+        let summary = self
+            .provider
+            .chat_with_system(Some(SUMMARIZER_SYSTEM), &prompt, &self.model, 0.2)
+            .await
+            .unwrap_or_else(|e| {
+                // Fall back to deterministic truncation when summarization fails.
+                tracing::warn!("Conversation summarization failed: {e}");
+                transcript.clone()
+            });
+        truncate_with_ellipsis(summary.trim(), MAX_SUMMARY_CHARS)
+    }
+}
+
+fn build_transcript(messages: &[CompactMessage]) -> String {
+    let mut transcript = String::new();
+    for msg in messages {
+        let role = if msg.is_summary {
+            "EARLIER SUMMARY".to_string()
+        } else {
+            msg.role.to_uppercase()
+        };
+        let _ = writeln!(transcript, "{role}: {}", msg.content.trim());
+    }
+    truncate_with_ellipsis(&transcript, MAX_SOURCE_CHARS)
+}
+
+#[async_trait]
+impl ContextCompactor for LlmContextCompactor {
+    /// Keep system prompts, then keep the newest messages verbatim while they
+    /// fit in `max_tokens` (and `keep_recent`); everything older, including
+    /// earlier summaries, becomes one summary note placed before them.
+    async fn compact(
+        &self,
+        messages: &[CompactMessage],
+        max_tokens: usize,
+    ) -> Result<Vec<CompactMessage>> {
+        let (system, turns): (Vec<&CompactMessage>, Vec<&CompactMessage>) = messages
+            .iter()
+            .partition(|m| m.role == "system" && !m.is_summary);
+
+        let mut budget = max_tokens.saturating_sub(
+            system
+                .iter()
+                .map(|m| estimate_tokens(&m.content))
+                .sum::<usize>(),
+        );
+        let mut split = turns.len();
+        while split > 0 && turns.len() - split < self.keep_recent {
+            let cost = estimate_tokens(&turns[split - 1].content);
+            if cost > budget {
+                break;
+            }
+            budget -= cost;
+            split -= 1;
+        }
+
+        let older: Vec<CompactMessage> = turns[..split].iter().map(|m| (*m).clone()).collect();
+        if older.is_empty() || (older.len() == 1 && older[0].is_summary) {
+            return Ok(messages.to_vec());
+        }
+
+        let summary = self.summarize(&older).await;
+        if let Some(memory) = &self.memory {
+            let key = format!("conversation_summary_{}", uuid::Uuid::new_v4());
+            if let Err(e) = memory
+                .store(&key, &summary, MemoryCategory::Conversation, None)
+                .await
+            {
+                tracing::warn!("Failed to store conversation summary: {e}");
+            }
+        }
+
+        let mut compacted: Vec<CompactMessage> = system.into_iter().cloned().collect();
+        compacted.push(CompactMessage {
+            role: "system".into(),
+            content: format!("{SUMMARY_PREFIX}\n{summary}"),
+            is_summary: true,
+        });
+        compacted.extend(turns[split..].iter().map(|m| (*m).clone()));
+        Ok(compacted)
+    }
+
+    fn name(&self) -> &str {
+        "llm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryEntry;
+    use parking_lot::Mutex;
+
+    struct SummaryProvider;
+
+    #[async_trait]
+    impl Provider for SummaryProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            let turns = message.lines().filter(|l| l.contains(": ")).count();
+            Ok(format!("- {turns} earlier turns"))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingMemory {
+        stored: Mutex<Vec<(String, MemoryCategory)>>,
+    }
+
+    #[async_trait]
+    impl Memory for RecordingMemory {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn store(
+            &self,
+            _key: &str,
+            content: &str,
+            category: MemoryCategory,
+            _session_id: Option<&str>,
+        ) -> Result<()> {
+            self.stored.lock().push((content.to_string(), category));
+            Ok(())
+        }
+
+        async fn recall(
+            &self,
+            _query: &str,
+            _limit: usize,
+            _session_id: Option<&str>,
+        ) -> Result<Vec<MemoryEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn get(&self, _key: &str) -> Result<Option<MemoryEntry>> {
+            Ok(None)
+        }
+
+        async fn list(
+            &self,
+            _category: Option<&MemoryCategory>,
+            _session_id: Option<&str>,
+        ) -> Result<Vec<MemoryEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn forget(&self, _key: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    fn msg(role: &str, content: &str) -> CompactMessage {
+        CompactMessage {
+            role: role.into(),
+            content: content.into(),
+            is_summary: false,
+        }
+    }
+
+    fn conversation(turns: usize) -> Vec<CompactMessage> {
+        let mut messages = vec![msg("system", "You are ZeroClaw.")];
+        for i in 0..turns {
+            let role = if i % 2 == 0 { "user" } else { "assistant" };
+            messages.push(msg(role, &format!("message {i}")));
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn compact_summarizes_old_turns_and_keeps_recent_window() {
+        let memory = Arc::new(RecordingMemory::default());
+        let compactor = LlmContextCompactor::new(Arc::new(SummaryProvider), "test-model")
+            .with_memory(memory.clone())
+            .with_keep_recent(4);
+
+        let compacted = compactor
+            .compact(&conversation(10), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(compacted.len(), 6);
+        assert_eq!(compacted[0].content, "You are ZeroClaw.");
+        assert!(compacted[1].is_summary);
+        assert_eq!(
+            compacted[1].content,
+            format!("{SUMMARY_PREFIX}\n- 6 earlier turns")
+        );
+        let recent: Vec<&str> = compacted[2..].iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(recent, ["message 6", "message 7", "message 8", "message 9"]);
+
+        let stored = memory.stored.lock();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].0, "- 6 earlier turns");
+        assert_eq!(stored[0].1, MemoryCategory::Conversation);
+    }
+
+    #[tokio::test]
+    async fn compact_respects_token_budget_and_folds_prior_summaries() {
+        let compactor = LlmContextCompactor::new(Arc::new(SummaryProvider), "test-model");
+        let mut messages = conversation(4);
+        messages.insert(
+            1,
+            CompactMessage {
+                role: "system".into(),
+                content: format!("{SUMMARY_PREFIX}\n- earlier"),
+                is_summary: true,
+            },
+        );
+
+        // System prompt (5 tokens) + one 3-token message fit in 8.
+        let compacted = compactor.compact(&messages, 8).await.unwrap();
+        assert_eq!(compacted.len(), 3);
+        assert_eq!(
+            compacted[1].content,
+            format!("{SUMMARY_PREFIX}\n- 4 earlier turns")
+        );
+        assert_eq!(compacted[2].content, "message 3");
+
+        // Nothing older than the window: returned unchanged.
+        let short = conversation(2);
+        let unchanged = compactor.compact(&short, usize::MAX).await.unwrap();
+        assert_eq!(unchanged.len(), short.len());
+    }
+
+    #[test]
+    fn needs_compaction_checks_turns_and_tokens() {
+        let messages = conversation(4);
+        assert!(!needs_compaction(&messages, 4, 0));
+        assert!(needs_compaction(&messages, 3, 0));
+        assert!(needs_compaction(&messages, 10, 5));
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+}
@@ -1,7 +1,9 @@
 use crate::providers::{ChatMessage, ChatResponse, ConversationMessage, ToolResultMessage};
 use crate::tools::{Tool, ToolSpec};
+use regex::Regex;
 use serde_json::Value;
 use std::fmt::Write;
+use std::sync::LazyLock;
 
 #[derive(Debug, Clone)]
 pub struct ParsedToolCall {
@@ -18,6 +20,111 @@ pub struct ToolExecutionResult {
     pub tool_call_id: Option<String>,
 }
 
+/// How tool calls are exchanged with the model (`[agent] tool_dispatcher`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToolDispatchMode {
+    /// Native function calling when the provider supports it, XML tags otherwise.
+    #[default]
+    Auto,
+    /// Always send tool specs through the provider's function-calling API.
+    Native,
+    /// Always describe tools in the system prompt and parse `<tool_call>` tags.
+    Xml,
+}
+
+impl ToolDispatchMode {
+    pub const VALID: [&'static str; 3] = ["auto", "native", "xml"];
+
+    /// Parse the config value. Unknown values fall back to `Auto`.
+    pub fn from_config(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "native" => Self::Native,
+            "xml" => Self::Xml,
+            "auto" | "" => Self::Auto,
+            other => {
+                tracing::warn!("Unknown tool_dispatcher '{other}'; using auto");
+                Self::Auto
+            }
+        }
+    }
+
+    /// Whether tools go through native function calling for a provider with
+    /// the given capability.
+    pub fn uses_native(self, provider_supports_native: bool) -> bool {
+        match self {
+            Self::Native => true,
+            Self::Xml => false,
+            Self::Auto => provider_supports_native,
+        }
+    }
+
+    pub fn create_dispatcher(self, provider_supports_native: bool) -> Box<dyn ToolDispatcher> {
+        if self.uses_native(provider_supports_native) {
+            Box::new(NativeToolDispatcher)
+        } else {
+            Box::new(XmlToolDispatcher)
+        }
+    }
+}
+
+/// Fenced `<tool_call>` example for `tool`, filled with placeholders for its
+/// required parameters (or its first parameter when none are required).
+pub fn tool_call_example(tool: &dyn Tool) -> String {
+    let schema = tool.parameters_schema();
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let mut names: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if names.is_empty() {
+        names.extend(properties.and_then(|p| p.keys().next()).map(String::as_str));
+    }
+
+    let mut arguments = serde_json::Map::new();
+    for name in names {
+        let property = properties.and_then(|p| p.get(name));
+        arguments.insert(name.to_string(), example_value(name, property));
+    }
+    let payload = serde_json::json!({ "name": tool.name(), "arguments": arguments });
+    format!("```xml\n<tool_call>\n{payload}\n</tool_call>\n```")
+}
+
+fn example_value(name: &str, property: Option<&Value>) -> Value {
+    if let Some(first) = property
+        .and_then(|p| p.get("enum"))
+        .and_then(Value::as_array)
+        .and_then(|values| values.first())
+    {
+        return first.clone();
+    }
+    match property.and_then(|p| p.get("type")).and_then(Value::as_str) {
+        Some("integer" | "number") => Value::from(1),
+        Some("boolean") => Value::Bool(true),
+        Some("array") => Value::Array(Vec::new()),
+        Some("object") => Value::Object(serde_json::Map::new()),
+        _ => Value::String(format!("<{name}>")),
+    }
+}
+
+/// Split `response` before a tool-call tag opened after the last closing
+/// tag, if any.
+fn split_unterminated_tag(response: &str) -> (&str, &str) {
+    static OPEN_TAG_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"<(?:minimax:)?(?:tool[_-]?call|invoke)\b[^>]*>").unwrap());
+    static CLOSE_TAG_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"</(?:minimax:)?(?:tool[_-]?call|invoke)>").unwrap());
+
+    let tail_start = CLOSE_TAG_RE
+        .find_iter(response)
+        .last()
+        .map_or(0, |close| close.end());
+    match OPEN_TAG_RE.find(&response[tail_start..]) {
+        Some(open) => response.split_at(tail_start + open.start()),
+        None => (response, ""),
+    }
+}
+
 pub trait ToolDispatcher: Send + Sync {
     fn parse_response(&self, response: &ChatResponse) -> (String, Vec<ParsedToolCall>);
     fn format_results(&self, results: &[ToolExecutionResult]) -> ConversationMessage;
@@ -30,55 +137,21 @@ pub trait ToolDispatcher: Send + Sync {
 pub struct XmlToolDispatcher;
 
 impl XmlToolDispatcher {
+    /// Extract `<tool_call>` payloads from `response`. Uses the same tolerant
+    /// parser as the tool-call loop: tag aliases, fenced bodies, attribute
+    /// forms and stringified arguments. A trailing tag that is never closed
+    /// stays text: a half-written call is not a request to run a tool.
     fn parse_xml_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
-        let mut text_parts = Vec::new();
-        let mut calls = Vec::new();
-        let mut remaining = response;
-
-        while let Some(start) = remaining.find("<tool_call>") {
-            let before = &remaining[..start];
-            if !before.trim().is_empty() {
-                text_parts.push(before.trim().to_string());
+        let (complete, unterminated) = split_unterminated_tag(response);
+        let (mut text, calls) = super::loop_::parse_tool_calls(complete);
+        let unterminated = unterminated.trim();
+        if !unterminated.is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
             }
-
-            if let Some(end) = remaining[start..].find("</tool_call>") {
-                let inner = &remaining[start + 11..start + end];
-                match serde_json::from_str::<Value>(inner.trim()) {
-                    Ok(parsed) => {
-                        let name = parsed
-                            .get("name")
-                            .and_then(Value::as_str)
-                            .unwrap_or("")
-                            .to_string();
-                        if name.is_empty() {
-                            remaining = &remaining[start + end + 12..];
-                            continue;
-                        }
-                        let arguments = parsed
-                            .get("arguments")
-                            .cloned()
-                            .unwrap_or_else(|| Value::Object(serde_json::Map::new()));
-                        calls.push(ParsedToolCall {
-                            name,
-                            arguments,
-                            tool_call_id: None,
-                        });
-                    }
-                    Err(e) => {
-                        tracing::warn!("Malformed <tool_call> JSON: {e}");
-                    }
-                }
-                remaining = &remaining[start + end + 12..];
-            } else {
-                break;
-            }
-        }
-
-        if !remaining.trim().is_empty() {
-            text_parts.push(remaining.trim().to_string());
+            text.push_str(unterminated);
         }
-
-        (text_parts.join("\n"), calls)
+        (text, calls)
     }
 
     pub fn tool_specs(tools: &[Box<dyn Tool>]) -> Vec<ToolSpec> {
@@ -118,10 +191,11 @@ impl ToolDispatcher for XmlToolDispatcher {
         for tool in tools {
             let _ = writeln!(
                 instructions,
-                "- **{}**: {}\n  Parameters: `{}`",
+                "- **{}**: {}\n  Parameters: `{}`\n{}\n",
                 tool.name(),
                 tool.description(),
-                tool.parameters_schema()
+                tool.parameters_schema(),
+                tool_call_example(tool.as_ref())
             );
         }
 
@@ -207,27 +281,14 @@ impl ToolDispatcher for NativeToolDispatcher {
                     text,
                     tool_calls,
                     reasoning_content,
-                } => {
-                    let mut payload = serde_json::json!({
-                        "content": text,
-                        "tool_calls": tool_calls,
-                    });
-                    if let Some(rc) = reasoning_content {
-                        payload["reasoning_content"] = serde_json::json!(rc);
-                    }
-                    vec![ChatMessage::assistant(payload.to_string())]
-                }
+                } => vec![ChatMessage::assistant_tool_calls(
+                    text.as_deref().unwrap_or_default(),
+                    tool_calls,
+                    reasoning_content.as_deref(),
+                )],
                 ConversationMessage::ToolResults(results) => results
                     .iter()
-                    .map(|result| {
-                        ChatMessage::tool(
-                            serde_json::json!({
-                                "tool_call_id": result.tool_call_id,
-                                "content": result.content,
-                            })
-                            .to_string(),
-                        )
-                    })
+                    .map(|result| ChatMessage::tool_result(&result.tool_call_id, &result.content))
                     .collect(),
             })
             .collect()
@@ -252,6 +313,7 @@ mod tests {
             tool_calls: vec![],
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         };
         let dispatcher = XmlToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -259,6 +321,106 @@ mod tests {
         assert_eq!(calls[0].name, "shell");
     }
 
+    #[test]
+    fn xml_dispatcher_parses_fenced_and_named_calls() {
+        let dispatcher = XmlToolDispatcher;
+        let response = |text: &str| ChatResponse {
+            text: Some(text.into()),
+            tool_calls: vec![],
+            usage: None,
+            reasoning_content: None,
+            reasoning: None,
+        };
+
+        let fenced = "<tool_call>\n```json\n{\"name\":\"shell\",\"args\":\"{\\\"command\\\":\\\"ls\\\"}\"}\n```\n</tool_call>";
+        let (_, calls) = dispatcher.parse_response(&response(fenced));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].arguments["command"], "ls");
+
+        let named = "Reading it.\n<tool_call name=\"file_read\">{\"path\":\"a.txt\"}</tool_call>";
+        let (text, calls) = dispatcher.parse_response(&response(named));
+        assert_eq!(text, "Reading it.");
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(calls[0].arguments["path"], "a.txt");
+
+        let unterminated = "<tool_call name=\"file_read\">{\"path\":\"a.txt\"}</tool_call>\n\
+                            <tool_call>\n{\"name\":\"shell\",\"parameters\":{\"command\":\"pwd\"}}";
+        let (text, calls) = dispatcher.parse_response(&response(unterminated));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert!(text.contains("\"command\":\"pwd\""));
+    }
+
+    #[test]
+    fn dispatch_mode_follows_config_and_provider_capability() {
+        assert_eq!(ToolDispatchMode::from_config("XML"), ToolDispatchMode::Xml);
+        assert_eq!(
+            ToolDispatchMode::from_config("native"),
+            ToolDispatchMode::Native
+        );
+        assert_eq!(
+            ToolDispatchMode::from_config("bogus"),
+            ToolDispatchMode::Auto
+        );
+
+        assert!(ToolDispatchMode::Auto.uses_native(true));
+        assert!(!ToolDispatchMode::Auto.uses_native(false));
+        assert!(!ToolDispatchMode::Xml.uses_native(true));
+        assert!(ToolDispatchMode::Native.uses_native(false));
+        assert!(ToolDispatchMode::Auto
+            .create_dispatcher(true)
+            .should_send_tool_specs());
+        assert!(!ToolDispatchMode::Xml
+            .create_dispatcher(true)
+            .should_send_tool_specs());
+    }
+
+    struct ExampleTool;
+
+    #[async_trait::async_trait]
+    impl Tool for ExampleTool {
+        fn name(&self) -> &str {
+            "http_request"
+        }
+
+        fn description(&self) -> &str {
+            "Send an HTTP request"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string" },
+                    "method": { "type": "string", "enum": ["GET", "POST"] },
+                    "timeout_secs": { "type": "integer" }
+                },
+                "required": ["url", "method"]
+            })
+        }
+
+        async fn execute(&self, _args: Value) -> anyhow::Result<crate::tools::ToolResult> {
+            unreachable!("not executed in prompt tests")
+        }
+    }
+
+    #[test]
+    fn prompt_instructions_include_fenced_example_per_tool() {
+        let example = tool_call_example(&ExampleTool);
+        assert!(example.starts_with("```xml\n<tool_call>\n"));
+        assert!(example.ends_with("</tool_call>\n```"));
+
+        let (_, calls) = XmlToolDispatcher::parse_xml_tool_calls(&example);
+        assert_eq!(calls[0].name, "http_request");
+        assert_eq!(calls[0].arguments["url"], "<url>");
+        assert_eq!(calls[0].arguments["method"], "GET");
+        assert!(calls[0].arguments.get("timeout_secs").is_none());
+
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(ExampleTool)];
+        let instructions = XmlToolDispatcher.prompt_instructions(&tools);
+        assert!(instructions.contains(&example));
+    }
+
     #[test]
     fn native_dispatcher_roundtrip() {
         let response = ChatResponse {
@@ -270,6 +432,7 @@ mod tests {
             }],
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         };
         let dispatcher = NativeToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -1,13 +1,18 @@
-use crate::config::Config;
+use crate::agent::dispatcher::{tool_call_example, ParsedToolCall, ToolDispatchMode};
+use crate::agent::registry::AgentRegistry;
+use crate::agent::trace;
+use crate::config::{Config, MultimodalConfig};
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, runtime_trace, Observer, ObserverEvent};
+use crate::plugins::{fire_hook, HookAction, HookEventType, PluginManager};
 use crate::providers::{
-    self, ChatMessage, ChatRequest, Provider, ToolCall,
+    self, ChatMessage, ChatRequest, Provider, ProviderErrorKind, ToolCall,
 };
 use crate::runtime;
+use crate::security::grants::PathGrants;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
-use crate::util::truncate_with_ellipsis;
+use crate::util::{tokenizer, truncate_with_ellipsis};
 use anyhow::Result;
 use regex::{Regex, RegexSet};
 use std::collections::HashSet;
@@ -48,9 +53,10 @@ static SENSITIVE_KV_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 
 /// Scrub credentials from tool output to prevent accidental exfiltration.
 /// Replaces known credential patterns with a redacted placeholder while preserving
-/// a small prefix for context.
+/// a small prefix for context, then applies the runtime secret redactor
+/// (configured secret values and common token formats).
 pub(crate) fn scrub_credentials(input: &str) -> String {
-    SENSITIVE_KV_REGEX
+    let scrubbed = SENSITIVE_KV_REGEX
         .replace_all(input, |caps: &regex::Captures| {
             let full_match = &caps[0];
             let key = &caps[1];
@@ -80,7 +86,8 @@ pub(crate) fn scrub_credentials(input: &str) -> String {
                 format!("{}: {}*[REDACTED]", key, prefix)
             }
         })
-        .to_string()
+        .to_string();
+    crate::security::redaction::redact_outbound(&scrubbed)
 }
 
 /// Default trigger for auto-compaction when non-system message count exceeds this threshold.
@@ -161,6 +168,37 @@ fn trim_history(history: &mut Vec<ChatMessage>, max_history: usize) {
     history.drain(start..start + to_remove);
 }
 
+/// Drop the oldest non-system messages until `history` fits the prompt
+/// budget of `model`, always keeping the newest message. Orphaned tool
+/// results left at the front are dropped with their call. Returns the
+/// prompt size in tokens.
+fn fit_history_to_context_window(history: &mut Vec<ChatMessage>, model: &str) -> usize {
+    let budget = tokenizer::prompt_budget(model);
+    let mut tokens = tokenizer::count_message_tokens(model, history);
+    let mut dropped = 0;
+    while tokens > budget {
+        let Some(oldest) = history.iter().position(|m| m.role != "system") else {
+            break;
+        };
+        if oldest + 1 >= history.len() {
+            break;
+        }
+        history.remove(oldest);
+        dropped += 1;
+        while history.len() > oldest + 1 && history[oldest].role == "tool" {
+            history.remove(oldest);
+            dropped += 1;
+        }
+        tokens = tokenizer::count_message_tokens(model, history);
+    }
+    if dropped > 0 {
+        tracing::warn!(
+            "Dropped {dropped} oldest messages to fit {model}'s context window ({tokens}/{budget} tokens)"
+        );
+    }
+    tokens
+}
+
 fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
     let mut transcript = String::new();
     for msg in messages {
@@ -364,8 +402,12 @@ fn parse_tool_call_value(value: &serde_json::Value) -> Option<ParsedToolCall> {
         return None;
     }
 
-    let arguments =
-        parse_arguments_value(value.get("arguments").or_else(|| value.get("parameters")));
+    let arguments = parse_arguments_value(
+        value
+            .get("arguments")
+            .or_else(|| value.get("parameters"))
+            .or_else(|| value.get("args")),
+    );
     Some(ParsedToolCall {
         name,
         arguments,
@@ -834,6 +876,66 @@ fn parse_xml_attribute_tool_calls(response: &str) -> Vec<ParsedToolCall> {
     calls
 }
 
+/// Parse tool calls whose tag names the tool and whose body is the argument
+/// object (optionally fenced). A final tag with no closing tag runs to the end
+/// of the response:
+/// ```text
+/// <tool_call name="file_read">
+/// {"path": "README.md"}
+/// </tool_call>
+/// ```
+fn parse_named_tag_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
+    static NAMED_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r#"(?s)<tool[_-]?call\s+name\s*=\s*["']([^"']+)["']\s*>(.*?)(?:</tool[_-]?call>|$)"#,
+        )
+        .unwrap()
+    });
+
+    let mut calls = Vec::new();
+    let mut text_parts = Vec::new();
+    let mut last_end = 0;
+    for cap in NAMED_TAG_RE.captures_iter(response) {
+        let full_match = cap.get(0).unwrap();
+        let before = response[last_end..full_match.start()].trim();
+        if !before.is_empty() {
+            text_parts.push(before.to_string());
+        }
+        last_end = full_match.end();
+
+        let name = cap[1].trim().to_string();
+        let body = cap[2].trim();
+        let arguments = if body.is_empty() {
+            serde_json::Value::Object(serde_json::Map::new())
+        } else if let Some(value) = extract_json_values(body)
+            .into_iter()
+            .find(|v| v.is_object())
+        {
+            match value.get("arguments").or_else(|| value.get("args")) {
+                Some(inner) => parse_arguments_value(Some(inner)),
+                None => value,
+            }
+        } else {
+            tracing::warn!(
+                tool = %name,
+                "Malformed <tool_call name=...> body: expected JSON object"
+            );
+            continue;
+        };
+        calls.push(ParsedToolCall {
+            name,
+            arguments,
+            tool_call_id: None,
+        });
+    }
+
+    let after = response[last_end..].trim();
+    if !after.is_empty() {
+        text_parts.push(after.to_string());
+    }
+    (text_parts.join("\n"), calls)
+}
+
 /// Parse Perl/hash-ref style tool calls from response text.
 /// This handles formats like:
 /// ```text
@@ -1257,7 +1359,7 @@ fn parse_glm_shortened_body(body: &str) -> Option<ParsedToolCall> {
 /// compatibility.
 ///
 /// Also supports JSON with `tool_calls` array from OpenAI-format responses.
-fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
+pub(crate) fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
     let mut text_parts = Vec::new();
     let mut calls = Vec::new();
     let mut remaining = response;
@@ -1484,6 +1586,19 @@ fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
         }
     }
 
+    // Name-attribute tool calls:
+    // <tool_call name="shell">{"command": "ls"}</tool_call>
+    if calls.is_empty() {
+        let (named_text, named_calls) = parse_named_tag_tool_calls(remaining);
+        if !named_calls.is_empty() {
+            calls = named_calls;
+            if !named_text.is_empty() {
+                text_parts.push(named_text);
+            }
+            remaining = "";
+        }
+    }
+
     // Perl/hash-ref style tool calls:
     // TOOL_CALL
     // {tool => "shell", args => {
@@ -1627,43 +1742,14 @@ fn parse_structured_tool_calls(tool_calls: &[ToolCall]) -> Vec<ParsedToolCall> {
 }
 
 /// Build assistant history entry in JSON format for native tool-call APIs.
-/// `convert_messages` in the OpenRouter provider parses this JSON to reconstruct
+/// `convert_messages` in the OpenAI provider parses this JSON to reconstruct
 /// the proper `NativeMessage` with structured `tool_calls`.
 fn build_native_assistant_history(
     text: &str,
     tool_calls: &[ToolCall],
     reasoning_content: Option<&str>,
 ) -> String {
-    let calls_json: Vec<serde_json::Value> = tool_calls
-        .iter()
-        .map(|tc| {
-            serde_json::json!({
-                "id": tc.id,
-                "name": tc.name,
-                "arguments": tc.arguments,
-            })
-        })
-        .collect();
-
-    let content = if text.trim().is_empty() {
-        serde_json::Value::Null
-    } else {
-        serde_json::Value::String(text.trim().to_string())
-    };
-
-    let mut obj = serde_json::json!({
-        "content": content,
-        "tool_calls": calls_json,
-    });
-
-    if let Some(rc) = reasoning_content {
-        obj.as_object_mut().unwrap().insert(
-            "reasoning_content".to_string(),
-            serde_json::Value::String(rc.to_string()),
-        );
-    }
-
-    obj.to_string()
+    ChatMessage::assistant_tool_calls(text, tool_calls, reasoning_content).content
 }
 
 fn build_native_assistant_history_from_parsed_calls(
@@ -1671,36 +1757,23 @@ fn build_native_assistant_history_from_parsed_calls(
     tool_calls: &[ParsedToolCall],
     reasoning_content: Option<&str>,
 ) -> Option<String> {
-    let calls_json = tool_calls
+    let calls = tool_calls
         .iter()
         .map(|tc| {
-            Some(serde_json::json!({
-                "id": tc.tool_call_id.clone()?,
-                "name": tc.name,
-                "arguments": serde_json::to_string(&tc.arguments).unwrap_or_else(|_| "{}".to_string()),
-            }))
+            Some(ToolCall {
+                id: tc.tool_call_id.clone()?,
+                name: tc.name.clone(),
+                arguments: serde_json::to_string(&tc.arguments)
+                    .unwrap_or_else(|_| "{}".to_string()),
+            })
         })
         .collect::<Option<Vec<_>>>()?;
 
-    let content = if text.trim().is_empty() {
-        serde_json::Value::Null
-    } else {
-        serde_json::Value::String(text.trim().to_string())
-    };
-
-    let mut obj = serde_json::json!({
-        "content": content,
-        "tool_calls": calls_json,
-    });
-
-    if let Some(rc) = reasoning_content {
-        obj.as_object_mut().unwrap().insert(
-            "reasoning_content".to_string(),
-            serde_json::Value::String(rc.to_string()),
-        );
-    }
-
-    Some(obj.to_string())
+    Some(build_native_assistant_history(
+        text,
+        &calls,
+        reasoning_content,
+    ))
 }
 
 fn build_assistant_history_with_tool_calls(text: &str, tool_calls: &[ToolCall]) -> String {
@@ -1724,13 +1797,6 @@ fn build_assistant_history_with_tool_calls(text: &str, tool_calls: &[ToolCall])
     parts.join("\n")
 }
 
-#[derive(Debug, Clone)]
-struct ParsedToolCall {
-    name: String,
-    arguments: serde_json::Value,
-    tool_call_id: Option<String>,
-}
-
 #[derive(Debug)]
 pub(crate) struct ToolLoopCancelled;
 
@@ -1760,6 +1826,8 @@ pub(crate) async fn agent_turn(
     temperature: f64,
     silent: bool,
     max_tool_iterations: usize,
+    dispatch_mode: ToolDispatchMode,
+    multimodal: &MultimodalConfig,
 ) -> Result<String> {
     run_tool_call_loop(
         provider,
@@ -1768,13 +1836,17 @@ pub(crate) async fn agent_turn(
         observer,
         provider_name,
         model,
+        &[],
         temperature,
         silent,
         "channel",
         max_tool_iterations,
+        dispatch_mode,
         None,
         None,
         &[],
+        None,
+        multimodal,
     )
     .await
 }
@@ -1798,11 +1870,13 @@ async fn execute_one_tool(
             tool: call_name.to_string(),
             duration,
             success: false,
+            error_kind: Some(tools::ToolErrorKind::NotFound.as_str().to_string()),
         });
         return Ok(ToolExecutionOutcome {
             output: reason.clone(),
             success: false,
             error_reason: Some(scrub_credentials(&reason)),
+            error_kind: Some(tools::ToolErrorKind::NotFound),
             duration,
         });
     };
@@ -1820,24 +1894,33 @@ async fn execute_one_tool(
     match tool_result {
         Ok(r) => {
             let duration = start.elapsed();
+            let error_kind = r.error.as_ref().filter(|_| !r.success).map(|e| e.kind);
             observer.record_event(&ObserverEvent::ToolCall {
                 tool: call_name.to_string(),
                 duration,
                 success: r.success,
+                error_kind: error_kind.map(|kind| kind.as_str().to_string()),
             });
             if r.success {
                 Ok(ToolExecutionOutcome {
                     output: scrub_credentials(&r.output),
                     success: true,
                     error_reason: None,
+                    error_kind: None,
                     duration,
                 })
             } else {
-                let reason = r.error.unwrap_or(r.output);
+                // Structured errors carry their class and retry hint into the
+                // transcript so the model can retry, ask for approval, or stop.
+                let (output, reason) = match r.error {
+                    Some(error) => (error.to_tool_output(), error.message),
+                    None => (format!("Error: {}", r.output), r.output),
+                };
                 Ok(ToolExecutionOutcome {
-                    output: format!("Error: {reason}"),
+                    output: scrub_credentials(&output),
                     success: false,
                     error_reason: Some(scrub_credentials(&reason)),
+                    error_kind,
                     duration,
                 })
             }
@@ -1848,12 +1931,14 @@ async fn execute_one_tool(
                 tool: call_name.to_string(),
                 duration,
                 success: false,
+                error_kind: Some(tools::ToolErrorKind::ExecutionFailed.as_str().to_string()),
             });
-            let reason = format!("Error executing {call_name}: {e}");
+            let reason = scrub_credentials(&format!("Error executing {call_name}: {e}"));
             Ok(ToolExecutionOutcome {
                 output: reason.clone(),
                 success: false,
-                error_reason: Some(scrub_credentials(&reason)),
+                error_reason: Some(reason),
+                error_kind: Some(tools::ToolErrorKind::ExecutionFailed),
                 duration,
             })
         }
@@ -1864,6 +1949,7 @@ struct ToolExecutionOutcome {
     output: String,
     success: bool,
     error_reason: Option<String>,
+    error_kind: Option<tools::ToolErrorKind>,
     duration: Duration,
 }
 
@@ -1936,6 +2022,35 @@ async fn execute_tools_sequential(
 //   • max_iterations is reached (runaway safety), or
 //   • the cancellation token fires (external abort).
 
+/// Provider failure that `[agent] fallback_models` can recover from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModelFallbackReason {
+    RateLimited,
+    ContextLengthExceeded,
+}
+
+impl std::fmt::Display for ModelFallbackReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::RateLimited => "is rate limited",
+            Self::ContextLengthExceeded => "exceeded its context window",
+        })
+    }
+}
+
+fn model_fallback_reason(err: &anyhow::Error) -> Option<ModelFallbackReason> {
+    match providers::traits::classify_provider_error(&format!("{err:#}")) {
+        ProviderErrorKind::RateLimit => Some(ModelFallbackReason::RateLimited),
+        ProviderErrorKind::ContextLength => Some(ModelFallbackReason::ContextLengthExceeded),
+        _ => None,
+    }
+}
+
+/// Transcript annotation recorded when the loop switches models mid-turn.
+fn model_fallback_note(from: &str, to: &str, reason: ModelFallbackReason) -> String {
+    format!("[Model fallback] {from} {reason}; continuing with {to}.")
+}
+
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
 #[allow(clippy::too_many_arguments)]
@@ -1946,13 +2061,17 @@ pub(crate) async fn run_tool_call_loop(
     observer: &dyn Observer,
     provider_name: &str,
     model: &str,
+    fallback_models: &[String],
     temperature: f64,
     silent: bool,
     channel_name: &str,
     max_tool_iterations: usize,
+    dispatch_mode: ToolDispatchMode,
     cancellation_token: Option<CancellationToken>,
     on_delta: Option<tokio::sync::mpsc::Sender<String>>,
     excluded_tools: &[String],
+    hooks: Option<&dyn PluginManager>,
+    multimodal: &MultimodalConfig,
 ) -> Result<String> {
     let max_iterations = if max_tool_iterations == 0 {
         DEFAULT_MAX_TOOL_ITERATIONS
@@ -1965,9 +2084,13 @@ pub(crate) async fn run_tool_call_loop(
         .filter(|tool| !excluded_tools.iter().any(|ex| ex == tool.name()))
         .map(|tool| tool.spec())
         .collect();
-    let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
+    let use_native_tools =
+        dispatch_mode.uses_native(provider.supports_native_tools()) && !tool_specs.is_empty();
     let turn_id = Uuid::new_v4().to_string();
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
+    // Once a fallback kicks in, later iterations of this turn stay on it.
+    let mut active_model = model.to_string();
+    let mut remaining_fallbacks = fallback_models.iter();
 
     for iteration in 0..max_iterations {
         if cancellation_token
@@ -2006,7 +2129,30 @@ pub(crate) async fn run_tool_call_loop(
             }),
         );
 
-        let llm_started_at = Instant::now();
+        let mut call_model = active_model.clone();
+        match fire_hook(
+            hooks,
+            HookEventType::LlmRequest,
+            serde_json::json!({
+                "channel": channel_name,
+                "provider": provider_name,
+                "model": model,
+                "iteration": iteration + 1,
+                "messages_count": history.len(),
+            }),
+        )
+        .await
+        {
+            HookAction::Cancel { reason } => {
+                anyhow::bail!("Provider call blocked by plugin hook: {reason}")
+            }
+            HookAction::Modify(data) => {
+                if let Some(override_model) = data.get("model").and_then(|v| v.as_str()) {
+                    call_model = override_model.to_string();
+                }
+            }
+            HookAction::Continue => {}
+        }
 
         // Unified path via Provider::chat so provider-specific native tool logic
         // (OpenAI/Anthropic/OpenRouter/compatible adapters) is honored.
@@ -2016,32 +2162,101 @@ pub(crate) async fn run_tool_call_loop(
             None
         };
 
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: history,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
+        let (prompt_tokens, llm_started_at, chat_result) = loop {
+            let prompt_tokens = fit_history_to_context_window(history, &call_model);
+            let llm_started_at = Instant::now();
+
+            let chat_result = {
+                // Lift `[IMAGE:...]` attachment markers into content parts; fails with
+                // a `vision` capability error when the provider cannot accept images.
+                let request_messages = providers::multimodal::prepare_messages(
+                    history,
+                    provider_name,
+                    provider.supports_vision(),
+                    multimodal,
+                )
+                .await?;
+                let chat_future = provider.chat(
+                    ChatRequest {
+                        messages: &request_messages,
+                        tools: request_tools,
+                    },
+                    &call_model,
+                    temperature,
+                );
+
+                if let Some(token) = cancellation_token.as_ref() {
+                    tokio::select! {
+                        () = token.cancelled() => return Err(ToolLoopCancelled.into()),
+                        result = chat_future => result,
+                    }
+                } else {
+                    chat_future.await
+                }
+            };
+
+            let fallback = match &chat_result {
+                Err(e) => model_fallback_reason(e).and_then(|reason| {
+                    remaining_fallbacks
+                        .by_ref()
+                        .find(|candidate| **candidate != call_model)
+                        .map(|next| (reason, next.clone()))
+                }),
+                Ok(_) => None,
+            };
+            let Some((reason, next_model)) = fallback else {
+                break (prompt_tokens, llm_started_at, chat_result);
+            };
 
-        let chat_result = if let Some(token) = cancellation_token.as_ref() {
-            tokio::select! {
-                () = token.cancelled() => return Err(ToolLoopCancelled.into()),
-                result = chat_future => result,
+            tracing::warn!(
+                provider = provider_name,
+                from = call_model.as_str(),
+                to = next_model.as_str(),
+                "Model {reason}; retrying with fallback model"
+            );
+            runtime_trace::record_event(
+                "model_fallback",
+                Some(channel_name),
+                Some(provider_name),
+                Some(&next_model),
+                Some(&turn_id),
+                None,
+                None,
+                serde_json::json!({
+                    "iteration": iteration + 1,
+                    "from": call_model,
+                    "reason": reason.to_string(),
+                }),
+            );
+            if let Some(ref tx) = on_delta {
+                let _ = tx
+                    .send(format!(
+                        "\u{21aa}\u{fe0f} {call_model} {reason}, switching to {next_model}\n"
+                    ))
+                    .await;
             }
-        } else {
-            chat_future.await
+            history.push(ChatMessage::system(model_fallback_note(
+                &call_model,
+                &next_model,
+                reason,
+            )));
+            call_model = next_model.clone();
+            active_model = next_model;
         };
 
         let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
             match chat_result {
                 Ok(resp) => {
+                    // Count locally when the provider does not report usage.
                     let (resp_input_tokens, resp_output_tokens) = resp
                         .usage
                         .as_ref()
                         .map(|u| (u.input_tokens, u.output_tokens))
                         .unwrap_or((None, None));
+                    let resp_input_tokens = resp_input_tokens.or(Some(prompt_tokens as u64));
+                    let resp_output_tokens = resp_output_tokens.or_else(|| {
+                        Some(tokenizer::count_tokens(&call_model, resp.text_or_empty()) as u64)
+                    });
 
                     observer.record_event(&ObserverEvent::LlmResponse {
                         provider: provider_name.to_string(),
@@ -2052,6 +2267,7 @@ pub(crate) async fn run_tool_call_loop(
                         input_tokens: resp_input_tokens,
                         output_tokens: resp_output_tokens,
                     });
+                    trace::record_llm_call(resp_input_tokens, resp_output_tokens);
 
                     let response_text = resp.text_or_empty().to_string();
                     // First try native structured tool calls (OpenAI-format).
@@ -2103,6 +2319,7 @@ pub(crate) async fn run_tool_call_loop(
                             "input_tokens": resp_input_tokens,
                             "output_tokens": resp_output_tokens,
                             "raw_response": scrub_credentials(&response_text),
+                            "reasoning": resp.reasoning.as_deref().map(scrub_credentials),
                             "native_tool_calls": resp.tool_calls.len(),
                             "parsed_tool_calls": calls.len(),
                         }),
@@ -2252,7 +2469,56 @@ pub(crate) async fn run_tool_call_loop(
 
         for (idx, call) in tool_calls.iter().enumerate() {
             let tool_name = call.name.clone();
-            let tool_args = call.arguments.clone();
+            let mut tool_args = call.arguments.clone();
+
+            match fire_hook(
+                hooks,
+                HookEventType::ToolCallBefore,
+                serde_json::json!({
+                    "channel": channel_name,
+                    "tool": tool_name,
+                    "arguments": tool_args,
+                }),
+            )
+            .await
+            {
+                HookAction::Cancel { reason } => {
+                    let denied =
+                        format!("Tool '{tool_name}' was blocked by a plugin hook: {reason}");
+                    runtime_trace::record_event(
+                        "tool_call_result",
+                        Some(channel_name),
+                        Some(provider_name),
+                        Some(model),
+                        Some(&turn_id),
+                        Some(false),
+                        Some(&denied),
+                        serde_json::json!({
+                            "iteration": iteration + 1,
+                            "tool": tool_name.clone(),
+                            "blocked_by_hook": true,
+                        }),
+                    );
+                    ordered_results[idx] = Some((
+                        tool_name.clone(),
+                        call.tool_call_id.clone(),
+                        ToolExecutionOutcome {
+                            output: denied.clone(),
+                            success: false,
+                            error_reason: Some(denied),
+                            error_kind: Some(tools::ToolErrorKind::PolicyBlocked),
+                            duration: Duration::ZERO,
+                        },
+                    ));
+                    continue;
+                }
+                HookAction::Modify(mut data) => {
+                    if let Some(arguments) = data.remove("arguments") {
+                        tool_args = arguments;
+                    }
+                }
+                HookAction::Continue => {}
+            }
 
             let signature = tool_call_signature(&tool_name, &tool_args);
             if !seen_tool_signatures.insert(signature) {
@@ -2281,6 +2547,7 @@ pub(crate) async fn run_tool_call_loop(
                         output: duplicate.clone(),
                         success: false,
                         error_reason: Some(duplicate),
+                        error_kind: None,
                         duration: Duration::ZERO,
                     },
                 ));
@@ -2340,11 +2607,35 @@ pub(crate) async fn run_tool_call_loop(
             .await?
         };
 
-        for ((idx, call), outcome) in executable_indices
+        for ((idx, call), mut outcome) in executable_indices
             .iter()
             .zip(executable_calls.iter())
             .zip(executed_outcomes.into_iter())
         {
+            match fire_hook(
+                hooks,
+                HookEventType::ToolCallAfter,
+                serde_json::json!({
+                    "channel": channel_name,
+                    "tool": call.name,
+                    "arguments": call.arguments,
+                    "success": outcome.success,
+                    "output": outcome.output,
+                }),
+            )
+            .await
+            {
+                HookAction::Cancel { reason } => {
+                    outcome.output = format!("Tool output withheld by a plugin hook: {reason}");
+                }
+                HookAction::Modify(data) => {
+                    if let Some(output) = data.get("output").and_then(|v| v.as_str()) {
+                        outcome.output = output.to_string();
+                    }
+                }
+                HookAction::Continue => {}
+            }
+
             runtime_trace::record_event(
                 "tool_call_result",
                 Some(channel_name),
@@ -2357,6 +2648,7 @@ pub(crate) async fn run_tool_call_loop(
                     "iteration": iteration + 1,
                     "tool": call.name.clone(),
                     "duration_ms": outcome.duration.as_millis(),
+                    "error_kind": outcome.error_kind.map(tools::ToolErrorKind::as_str),
                     "output": scrub_credentials(&outcome.output),
                 }),
             );
@@ -2376,7 +2668,20 @@ pub(crate) async fn run_tool_call_loop(
             ordered_results[*idx] = Some((call.name.clone(), call.tool_call_id.clone(), outcome));
         }
 
-        for (tool_name, tool_call_id, outcome) in ordered_results.into_iter().flatten() {
+        for (idx, (tool_name, tool_call_id, outcome)) in ordered_results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, result)| Some((idx, result?)))
+        {
+            trace::record_tool_call(|| trace::ToolCallTrace {
+                iteration: iteration + 1,
+                name: tool_name.clone(),
+                arguments: tool_calls[idx].arguments.clone(),
+                output: outcome.output.clone(),
+                success: outcome.success,
+                error_kind: outcome.error_kind.map(|kind| kind.as_str().to_string()),
+                duration_ms: u64::try_from(outcome.duration.as_millis()).unwrap_or(u64::MAX),
+            });
             individual_results.push((tool_call_id, outcome.output.clone()));
             let _ = writeln!(
                 tool_results,
@@ -2398,11 +2703,10 @@ pub(crate) async fn run_tool_call_loop(
                     .all(|(tool_call_id, _)| tool_call_id.is_some());
             if all_results_have_ids {
                 for (tool_call_id, result) in &individual_results {
-                    let tool_msg = serde_json::json!({
-                        "tool_call_id": tool_call_id,
-                        "content": result,
-                    });
-                    history.push(ChatMessage::tool(tool_msg.to_string()));
+                    history.push(ChatMessage::tool_result(
+                        tool_call_id.as_deref().unwrap_or_default(),
+                        result,
+                    ));
                 }
             } else {
                 history.push(ChatMessage::user(format!("[Tool results]\n{tool_results}")));
@@ -2411,11 +2715,7 @@ pub(crate) async fn run_tool_call_loop(
             for (native_call, (_, result)) in
                 native_tool_calls.iter().zip(individual_results.iter())
             {
-                let tool_msg = serde_json::json!({
-                    "tool_call_id": native_call.id,
-                    "content": result,
-                });
-                history.push(ChatMessage::tool(tool_msg.to_string()));
+                history.push(ChatMessage::tool_result(&native_call.id, result));
             }
         }
     }
@@ -2455,10 +2755,11 @@ pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> Strin
     for tool in tools_registry {
         let _ = writeln!(
             instructions,
-            "**{}**: {}\nParameters: `{}`\n",
+            "**{}**: {}\nParameters: `{}`\n{}\n",
             tool.name(),
             tool.description(),
-            tool.parameters_schema()
+            tool.parameters_schema(),
+            tool_call_example(tool.as_ref())
         );
     }
 
@@ -2471,48 +2772,113 @@ pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> Strin
 // interactive REPL mode. The interactive loop manages history compaction
 // and hard trimming to keep the context window bounded.
 
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 pub async fn run(
-    config: Config,
+    mut config: Config,
     message: Option<String>,
     provider_override: Option<String>,
     model_override: Option<String>,
     temperature: f64,
+    agent_id: Option<String>,
+    autonomy: Option<String>,
+    plan: bool,
+    session: Option<String>,
+    resume: bool,
 ) -> Result<String> {
+    // `--autonomy` wins over the profile of the `--agent` entry.
+    if let Some(profile) = autonomy.as_deref() {
+        config.autonomy = config.autonomy.resolve(profile)?;
+    }
+
+    // Interactive mode runs the stateful REPL (`/retry`, `/undo`, `/branch`),
+    // plan mode needs its planning turn and approval step, and `--resume` /
+    // `--session` continue a stored transcript.
+    let continues_session = resume || session.is_some();
+    if message.is_none() || plan || continues_session {
+        if agent_id.is_some() && plan {
+            anyhow::bail!("--agent cannot be combined with --plan; plan mode uses the default agent");
+        }
+        if agent_id.is_some() && continues_session {
+            anyhow::bail!(
+                "--agent cannot be combined with --resume or --session; sessions use the default agent"
+            );
+        }
+        if agent_id.is_some() {
+            anyhow::bail!("--agent requires --message; interactive mode uses the default agent");
+        }
+        super::agent::run(
+            config,
+            message,
+            provider_override,
+            model_override,
+            temperature,
+            plan,
+            session,
+            resume,
+        )
+        .await?;
+        return Ok(String::new());
+    }
+
+    let agent = match agent_id.as_deref() {
+        Some(id) => Some(AgentRegistry::from_config(&config).require(id)?),
+        None => None,
+    };
+    if autonomy.is_none() {
+        if let Some(profile) = agent.as_ref().and_then(|agent| agent.autonomy.as_deref()) {
+            config.autonomy = config.autonomy.resolve(profile)?;
+        }
+    }
+
+    crate::infra::checkpoint::auto_checkpoint(&config, "zeroclaw agent -m").await;
+
     // ── Wire up agnostic subsystems ──────────────────────────────
+    observability::stats::install(&config);
     let base_observer = observability::create_observer(&config.observability);
     let observer: Arc<dyn Observer> = Arc::from(base_observer);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_path_grants(PathGrants::for_config(&config)),
+    );
 
     // ── Memory (the brain) ────────────────────────────────────────
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+    let mut mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
         &config.memory,
+        &config.embedding_routes,
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
+    if let Some(agent) = &agent {
+        mem = agent.scope_memory(mem);
+    }
     tracing::info!(backend = mem.name(), "Memory initialized");
 
     // ── Tools ────────────────────────────────────────────────────
-    let tools_registry = tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
         mem.clone(),
     );
+    if let Some(agent) = &agent {
+        agent.restrict_tools(&mut tools_registry);
+    }
+
+    // ── Plugins (lifecycle hooks) ────────────────────────────────
+    let plugins = crate::plugins::load_plugins(&config, mem.clone());
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
         .as_deref()
+        .or(agent.as_ref().and_then(|a| a.provider.as_deref()))
         .or(config.default_provider.as_deref())
         .unwrap_or("openai");
 
     let model_name = model_override
         .as_deref()
+        .or(agent.as_ref().and_then(|a| a.model.as_deref()))
         .or(config.default_model.as_deref())
         .unwrap_or("gpt-4o");
 
@@ -2521,6 +2887,8 @@ pub async fn run(
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        azure_openai: config.azure_openai.clone(),
+        middleware: config.provider_middleware.clone(),
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -2536,11 +2904,15 @@ pub async fn run(
     });
 
     // ── Build system prompt from workspace MD files ──────────────
-    let tool_descs: Vec<(&str, &str)> = vec![
+    let mut tool_descs: Vec<(&str, &str)> = vec![
         (
             "shell",
             "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
         ),
+        (
+            "process",
+            "Run long-lived commands in the background (start/list/logs/stop). Use when: starting dev servers, watchers, or jobs you need to check on later. Don't use when: a command finishes quickly (use shell).",
+        ),
         (
             "file_read",
             "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
@@ -2549,6 +2921,10 @@ pub async fn run(
             "file_write",
             "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
         ),
+        (
+            "code_search",
+            "Search workspace files by regex or literal text. Use when: locating definitions, usages, config keys, error strings. Don't use when: you already know the exact file to read.",
+        ),
         (
             "memory_store",
             "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
@@ -2558,24 +2934,82 @@ pub async fn run(
             "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
         ),
     ];
+    #[cfg(feature = "git-tool")]
+    tool_descs.push((
+        "git",
+        "Structured git operations (status/diff/log/commit/branch/push/reset) returning JSON. Use when: inspecting or committing repository changes. Don't use when: push/reset is not explicitly requested (they require approval).",
+    ));
+    for (name, desc) in [
+        (
+            "email_search",
+            "Search the user's email (read-only; approved mailboxes only). Use when: the user asks about messages, e.g. finding an invoice or a reply. Don't use when: the answer is already in the conversation.",
+        ),
+        (
+            "calendar_list",
+            "List the user's calendar events for a day or range (read-only). Use when: the user asks about meetings, appointments or free time.",
+        ),
+        (
+            "clipboard_read",
+            "Read the text on the user's clipboard. Use when: the user refers to something they copied.",
+        ),
+        (
+            "clipboard_write",
+            "Copy text to the user's clipboard. Use when: the user asks to copy something.",
+        ),
+        (
+            "notify",
+            "Show a desktop notification on the user's machine. Use when: a result needs the user's attention outside the chat.",
+        ),
+        (
+            "db_query",
+            "Run one SQL statement against a configured database and get rows as JSON. Use when: the user asks a question answered by their data. Don't use when: the database is read-only and the statement is not a SELECT.",
+        ),
+        (
+            "k8s",
+            "Inspect a Kubernetes cluster (get, logs, describe, top) returning JSON. Use when: the user asks why a pod or deployment is unhealthy. Don't use when: delete_pod/restart/scale is not explicitly requested (they require approval).",
+        ),
+        (
+            "file_undo",
+            "Revert the most recent file_write call(s) from automatic backups. Use when: a write you made was wrong or the user asks to undo it. Don't use when: the change came from shell commands (only file_write is backed up).",
+        ),
+        (
+            "memory_graph",
+            "Look up a person, project or place in memory and follow its relations. Use when: the user asks what you know about someone or something, or who works on what. Don't use when: a plain keyword search (memory_recall) is enough.",
+        ),
+        (
+            "artifact_read",
+            "Page through a large tool output that was truncated and saved as an artifact. Use when: a tool result ends with an artifact ID and you need more of it. Don't use when: the preview already answers the question.",
+        ),
+    ] {
+        if tools_registry.iter().any(|tool| tool.name() == name) {
+            tool_descs.push((name, desc));
+        }
+    }
+    tool_descs.retain(|(name, _)| agent.as_ref().is_none_or(|a| a.allows_tool(name)));
     let bootstrap_max_chars = if config.agent.compact_context {
         Some(6000)
     } else {
         None
     };
-    let native_tools = provider.supports_native_tools();
-    let mut system_prompt = crate::channels::build_system_prompt_with_mode(
+    let dispatch_mode = ToolDispatchMode::from_config(&config.agent.tool_dispatcher);
+    let native_tools = dispatch_mode.uses_native(provider.supports_native_tools());
+    let skills_prompt = skills_prompt_for(&config, message.as_deref().unwrap_or_default()).await;
+    let mut system_prompt = crate::channels::build_system_prompt_with_skills(
         &config.workspace_dir,
         model_name,
         &tool_descs,
         bootstrap_max_chars,
         native_tools,
+        &skills_prompt,
     );
 
     // Append structured tool-use instructions with schemas (only for non-native providers)
     if !native_tools {
         system_prompt.push_str(&build_tool_instructions(&tools_registry));
     }
+    if let Some(agent) = &agent {
+        agent.apply_system_prompt(&mut system_prompt);
+    }
 
     let channel_name = "daemon";
 
@@ -2615,13 +3049,17 @@ pub async fn run(
             observer.as_ref(),
             provider_name,
             model_name,
+            &config.agent.fallback_models,
             temperature,
             false,
             channel_name,
             config.agent.max_tool_iterations,
+            dispatch_mode,
             None,
             None,
             &[],
+            Some(&plugins),
+            &config.multimodal,
         )
         .await?;
         final_output = response.clone();
@@ -2637,44 +3075,124 @@ pub async fn run(
         tokens_used: None,
         cost_usd: None,
     });
+    observer.flush();
 
     Ok(final_output)
 }
 
+/// Skills block for a prompt answering `message`: every skill, or only the
+/// most relevant ones when `[agent] max_skills` / `compact_context` is set.
+async fn skills_prompt_for(config: &Config, message: &str) -> String {
+    match crate::skills::SkillSelector::from_config(&config.agent) {
+        Some(selector) => {
+            crate::skills::render_selected_skills_prompt(&config.workspace_dir, &selector, message)
+                .await
+        }
+        None => crate::skills::render_skills_prompt(&config.workspace_dir),
+    }
+}
+
 /// Process a single message through the full agent (with tools, peripherals, memory).
 /// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
 pub async fn process_message(config: Config, message: &str) -> Result<String> {
+    process_message_as(config, message, None).await
+}
+
+/// [`process_message`] handled by the `[[agents]]` entry `agent_id` (its
+/// provider, model, prompt, tools and memory namespace).
+pub async fn process_message_as(
+    config: Config,
+    message: &str,
+    agent_id: Option<&str>,
+) -> Result<String> {
+    let options = RunOptions {
+        agent_id: agent_id.map(str::to_string),
+        ..RunOptions::default()
+    };
+    process_message_with(config, message, &options).await
+}
+
+/// Per-request overrides for [`process_message_with`].
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// `[[agents]]` entry that handles the message.
+    pub agent_id: Option<String>,
+    /// Model instead of the agent's or the configured default.
+    pub model: Option<String>,
+    /// Offer only these tools (further narrowing the agent's own list).
+    pub tools: Option<Vec<String>>,
+    /// Earlier user/assistant turns, oldest first.
+    pub history: Vec<ChatMessage>,
+    /// Working directories kept from earlier turns, looked up by the
+    /// [`with_session`](crate::security::cwd::with_session) key the call runs in.
+    pub working_dirs: Option<crate::security::cwd::WorkingDirs>,
+}
+
+/// [`process_message`] with per-request overrides. Wrap the call in
+/// [`trace::traced`] to also get the tool calls and token usage.
+pub async fn process_message_with(
+    mut config: Config,
+    message: &str,
+    options: &RunOptions,
+) -> Result<String> {
+    let agent = match options.agent_id.as_deref() {
+        Some(id) => Some(AgentRegistry::from_config(&config).require(id)?),
+        None => None,
+    };
+    if let Some(profile) = agent.as_ref().and_then(|agent| agent.autonomy.as_deref()) {
+        config.autonomy = config.autonomy.resolve(profile)?;
+    }
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
+    let mut security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+        .with_path_grants(PathGrants::for_config(&config));
+    if let Some(working_dirs) = &options.working_dirs {
+        security = security.with_working_dirs(working_dirs.clone());
+    }
+    let security = Arc::new(security);
+    let mut mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
         &config.memory,
+        &config.embedding_routes,
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
+    if let Some(agent) = &agent {
+        mem = agent.scope_memory(mem);
+    }
 
-    let tools_registry = tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
         runtime,
         mem.clone(),
     );
+    if let Some(agent) = &agent {
+        agent.restrict_tools(&mut tools_registry);
+    }
+    if let Some(allowed) = &options.tools {
+        tools_registry.retain(|tool| allowed.iter().any(|name| name == tool.name()));
+    }
 
-    let provider_name = config.default_provider.as_deref().unwrap_or("openai");
-    let model_name = config
-        .default_model
+    let provider_name = agent
+        .as_ref()
+        .and_then(|a| a.provider.as_deref())
+        .or(config.default_provider.as_deref())
+        .unwrap_or("openai");
+    let model_name = options
+        .model
         .clone()
+        .or_else(|| agent.as_ref().and_then(|a| a.model.clone()))
+        .or_else(|| config.default_model.clone())
         .unwrap_or_else(|| "gpt-4o".into());
     let provider_runtime_options = providers::ProviderRuntimeOptions {
         auth_profile_override: None,
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        azure_openai: config.azure_openai.clone(),
+        middleware: config.provider_middleware.clone(),
     };
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
@@ -2683,29 +3201,59 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         &provider_runtime_options,
     )?;
 
-    let tool_descs: Vec<(&str, &str)> = vec![
+    let mut tool_descs: Vec<(&str, &str)> = vec![
         ("shell", "Execute terminal commands."),
+        ("process", "Manage background processes."),
         ("file_read", "Read file contents."),
         ("file_write", "Write file contents."),
+        ("code_search", "Search workspace files."),
         ("memory_store", "Save to memory."),
         ("memory_recall", "Search memory."),
     ];
+    #[cfg(feature = "git-tool")]
+    tool_descs.push(("git", "Run structured git operations."));
+    for (name, desc) in [
+        ("email_search", "Search the user's email (read-only)."),
+        ("calendar_list", "List the user's calendar events."),
+        ("clipboard_read", "Read the user's clipboard."),
+        ("clipboard_write", "Copy text to the user's clipboard."),
+        ("notify", "Show a desktop notification."),
+        ("db_query", "Run SQL against a configured database."),
+        ("k8s", "Inspect a Kubernetes cluster."),
+        ("file_undo", "Revert recent file_write calls."),
+        ("memory_graph", "Follow entity relations in memory."),
+        ("artifact_read", "Read more of a truncated tool output."),
+    ] {
+        if tools_registry.iter().any(|tool| tool.name() == name) {
+            tool_descs.push((name, desc));
+        }
+    }
+    tool_descs.retain(|(name, _)| {
+        agent.as_ref().is_none_or(|a| a.allows_tool(name))
+            && tools_registry.iter().any(|tool| tool.name() == *name)
+    });
     let bootstrap_max_chars = if config.agent.compact_context {
         Some(6000)
     } else {
         None
     };
-    let native_tools = provider.supports_native_tools();
-    let mut system_prompt = crate::channels::build_system_prompt_with_mode(
+    let dispatch_mode = ToolDispatchMode::from_config(&config.agent.tool_dispatcher);
+    let native_tools = dispatch_mode.uses_native(provider.supports_native_tools());
+    let skills_prompt = skills_prompt_for(&config, message).await;
+    let mut system_prompt = crate::channels::build_system_prompt_with_skills(
         &config.workspace_dir,
         &model_name,
         &tool_descs,
         bootstrap_max_chars,
         native_tools,
+        &skills_prompt,
     );
     if !native_tools {
         system_prompt.push_str(&build_tool_instructions(&tools_registry));
     }
+    if let Some(agent) = &agent {
+        agent.apply_system_prompt(&mut system_prompt);
+    }
 
     let mem_context = build_context(mem.as_ref(), message, config.memory.min_relevance_score).await;
     let context = mem_context;
@@ -2715,12 +3263,12 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         format!("{context}{message}")
     };
 
-    let mut history = vec![
-        ChatMessage::system(&system_prompt),
-        ChatMessage::user(&enriched),
-    ];
+    let mut history = Vec::with_capacity(options.history.len() + 2);
+    history.push(ChatMessage::system(&system_prompt));
+    history.extend(options.history.iter().cloned());
+    history.push(ChatMessage::user(&enriched));
 
-    agent_turn(
+    let response = agent_turn(
         provider.as_ref(),
         &mut history,
         &tools_registry,
@@ -2730,8 +3278,12 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         config.default_temperature,
         true,
         config.agent.max_tool_iterations,
+        dispatch_mode,
+        &config.multimodal,
     )
-    .await
+    .await?;
+    observer.record_event(&ObserverEvent::TurnComplete);
+    Ok(response)
 }
 
 #[cfg(test)]
@@ -2826,6 +3378,25 @@ After text."#;
         assert_eq!(calls.len(), 1);
     }
 
+    #[test]
+    fn parse_tool_calls_handles_name_attribute_tags() {
+        let response = r#"Let me check.
+<tool_call name="shell">
+```json
+{"command": "uname -a"}
+```
+</tool_call>
+<tool_call name='memory_recall'>{"args": {"query": "deploy"}}"#;
+
+        let (text, calls) = parse_tool_calls(response);
+        assert_eq!(text, "Let me check.");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments["command"], "uname -a");
+        assert_eq!(calls[1].name, "memory_recall");
+        assert_eq!(calls[1].arguments["query"], "deploy");
+    }
+
     #[test]
     fn parse_tool_calls_handles_openai_format() {
         // OpenAI-style response with tool_calls array
@@ -3262,6 +3833,28 @@ Tail"#;
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn fit_history_to_context_window_drops_oldest_turns_and_orphaned_tool_results() {
+        let big = "a".repeat(12_000);
+        let mut history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::assistant(big.clone()),
+            ChatMessage::tool("ok"),
+            ChatMessage::user(big.clone()),
+            ChatMessage::assistant(big.clone()),
+            ChatMessage::user(big),
+            ChatMessage::user("latest"),
+        ];
+
+        let tokens = fit_history_to_context_window(&mut history, "gpt-4");
+
+        assert!(tokens <= tokenizer::prompt_budget("gpt-4"));
+        assert_eq!(tokens, tokenizer::count_message_tokens("gpt-4", &history));
+        let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user", "user"]);
+        assert_eq!(history.last().unwrap().content, "latest");
+    }
+
     #[test]
     fn build_compaction_transcript_formats_roles() {
         let messages = vec![
@@ -4160,4 +4753,71 @@ Let me check the result."#;
         assert_eq!(parsed["content"].as_str(), Some("answer"));
         assert!(parsed.get("reasoning_content").is_none());
     }
+
+    struct RateLimitedSmallModelProvider {
+        models_called: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for RateLimitedSmallModelProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.models_called.lock().push(model.to_string());
+            if model == "small" {
+                anyhow::bail!("openai API error (429 Too Many Requests): slow down");
+            }
+            Ok(format!("answered by {model}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_switches_to_fallback_model_on_rate_limit() {
+        let provider = RateLimitedSmallModelProvider {
+            models_called: parking_lot::Mutex::new(Vec::new()),
+        };
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+
+        let response = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "openai",
+            "small",
+            &["small".to_string(), "large".to_string()],
+            0.0,
+            true,
+            "cli",
+            1,
+            ToolDispatchMode::Auto,
+            None,
+            None,
+            &[],
+            None,
+            &MultimodalConfig::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response, "answered by large");
+        assert_eq!(*provider.models_called.lock(), vec!["small", "large"]);
+        assert!(history.iter().any(|m| m.role == "system"
+            && m.content == "[Model fallback] small is rate limited; continuing with large."));
+    }
+
+    #[test]
+    fn model_fallback_reason_ignores_unrelated_errors() {
+        let context = anyhow::anyhow!("This model's maximum context length is 8192 tokens");
+        assert_eq!(
+            model_fallback_reason(&context),
+            Some(ModelFallbackReason::ContextLengthExceeded)
+        );
+        let auth = anyhow::anyhow!("401 Unauthorized");
+        assert_eq!(model_fallback_reason(&auth), None);
+    }
 }
@@ -1,10 +1,14 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
 pub mod classifier;
+pub mod compactor;
 pub mod dispatcher;
 pub mod loop_;
 pub mod memory_loader;
+pub mod plan;
 pub mod prompt;
+pub mod registry;
+pub mod trace;
 pub mod traits;
 
 #[cfg(test)]
@@ -13,7 +17,11 @@ mod tests;
 #[allow(unused_imports)]
 pub use agent::{Agent, AgentBuilder};
 #[allow(unused_imports)]
-pub use loop_::{process_message, run};
+pub use compactor::LlmContextCompactor;
+#[allow(unused_imports)]
+pub use loop_::{process_message, process_message_as, process_message_with, run, RunOptions};
+#[allow(unused_imports)]
+pub use registry::{AgentProfile, AgentRegistry};
 #[allow(unused_imports)]
 pub use traits::{
     AgentInput, AgentOrchestrator, AgentOutput, CompactMessage, ContextCompactor,
@@ -0,0 +1,170 @@
+//! Plan mode: the agent works out the tool calls a request needs without
+//! running anything that could change state.
+//!
+//! During a planning turn, tools listed in `autonomy.auto_approve` (read-only
+//! by convention) run normally so the plan can be grounded in real data; every
+//! other call is recorded as a [`PlannedStep`] and answered with a stub. An
+//! approved plan is executed verbatim: the recorded calls, in order, with the
+//! recorded arguments.
+
+use crate::sessions::TranscriptEntry;
+use crate::util::truncate_with_ellipsis;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Prepended to the user message of a planning turn. Also how a retry
+/// recognizes that the exchange it regenerates was a plan.
+pub(crate) const PLAN_MODE_PREFIX: &str = "[Plan mode] Do not carry out this request yet. \
+Make the tool calls it needs exactly as you would to do the work. Read-only tools run \
+normally; every other call is recorded as a plan step and NOT executed. Finish with a \
+short summary of the plan for the user to approve.\n\n";
+
+/// Transcript role under which plans are persisted in the session.
+pub const PLAN_ROLE: &str = "plan";
+
+/// Longest tool output shown per step in an execution report.
+const MAX_REPORT_OUTPUT_CHARS: usize = 500;
+
+/// One tool call the agent intends to make.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedStep {
+    pub tool: String,
+    pub arguments: serde_json::Value,
+}
+
+/// A plan produced by a planning turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plan {
+    /// The user request the plan answers.
+    pub request: String,
+    /// The model's closing summary.
+    pub summary: String,
+    pub steps: Vec<PlannedStep>,
+}
+
+/// Result of running one approved step.
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    pub tool: String,
+    pub output: String,
+    pub success: bool,
+}
+
+/// Stub result the model sees for a call that was recorded, not executed.
+pub(crate) fn not_executed_output(step: usize) -> String {
+    format!("Not executed (plan mode): recorded as step {step}. Assume it succeeds and continue planning.")
+}
+
+impl Plan {
+    /// Human-readable plan: the summary followed by numbered steps.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if !self.summary.trim().is_empty() {
+            out.push_str(self.summary.trim());
+            out.push_str("\n\n");
+        }
+        if self.steps.is_empty() {
+            out.push_str("Plan: no state-changing tool calls needed.");
+            return out;
+        }
+        let _ = write!(
+            out,
+            "Plan ({} step{}, nothing executed yet):",
+            self.steps.len(),
+            if self.steps.len() == 1 { "" } else { "s" }
+        );
+        for (idx, step) in self.steps.iter().enumerate() {
+            let _ = write!(out, "\n  {}. {} {}", idx + 1, step.tool, step.arguments);
+        }
+        out
+    }
+
+    /// Session transcript entry recording the plan and its steps.
+    pub fn to_transcript_entry(&self) -> TranscriptEntry {
+        TranscriptEntry {
+            role: PLAN_ROLE.into(),
+            content: self.render(),
+            timestamp: chrono::Utc::now(),
+            tool_calls: Some(self.steps.iter().map(transcript_call).collect()),
+        }
+    }
+}
+
+/// Tool call in the `{"name", "arguments"}` shape used by session transcripts.
+pub(crate) fn transcript_call(step: &PlannedStep) -> String {
+    serde_json::json!({ "name": step.tool, "arguments": step.arguments }).to_string()
+}
+
+/// Report for an approved plan. Execution stops at the first failed step, so
+/// `outcomes` may be shorter than `total`.
+pub fn render_outcomes(outcomes: &[StepOutcome], total: usize) -> String {
+    let mut out = format!("Executed plan: {}/{total} steps run.", outcomes.len());
+    for (idx, outcome) in outcomes.iter().enumerate() {
+        let _ = write!(
+            out,
+            "\n  {} {}. {}: {}",
+            if outcome.success { "✅" } else { "❌" },
+            idx + 1,
+            outcome.tool,
+            truncate_with_ellipsis(outcome.output.trim(), MAX_REPORT_OUTPUT_CHARS)
+        );
+    }
+    if outcomes.last().is_some_and(|outcome| !outcome.success) && outcomes.len() < total {
+        let _ = write!(
+            out,
+            "\nStopped at step {}; the remaining steps were not run.",
+            outcomes.len()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_renders_steps_and_persists_them_as_tool_calls() {
+        let plan = Plan {
+            request: "clean up".into(),
+            summary: "Remove the temp file.".into(),
+            steps: vec![PlannedStep {
+                tool: "shell".into(),
+                arguments: serde_json::json!({"command": "rm tmp.txt"}),
+            }],
+        };
+
+        let rendered = plan.render();
+        assert!(rendered.starts_with("Remove the temp file."));
+        assert!(rendered.contains("Plan (1 step, nothing executed yet):"));
+        assert!(rendered.contains(r#"1. shell {"command":"rm tmp.txt"}"#));
+
+        let entry = plan.to_transcript_entry();
+        assert_eq!(entry.role, PLAN_ROLE);
+        let calls = entry.tool_calls.unwrap();
+        let call: serde_json::Value = serde_json::from_str(&calls[0]).unwrap();
+        assert_eq!(call["name"], "shell");
+        assert_eq!(call["arguments"]["command"], "rm tmp.txt");
+    }
+
+    #[test]
+    fn outcome_report_notes_where_execution_stopped() {
+        let outcomes = vec![
+            StepOutcome {
+                tool: "file_write".into(),
+                output: "wrote 3 bytes".into(),
+                success: true,
+            },
+            StepOutcome {
+                tool: "shell".into(),
+                output: "Error: exit 1".into(),
+                success: false,
+            },
+        ];
+        let report = render_outcomes(&outcomes, 3);
+        assert!(report.starts_with("Executed plan: 2/3 steps run."));
+        assert!(report.contains("❌ 2. shell: Error: exit 1"));
+        assert!(report.contains("Stopped at step 2"));
+        assert!(!render_outcomes(&outcomes[..1], 1).contains("Stopped"));
+    }
+}
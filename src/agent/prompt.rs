@@ -1,11 +1,76 @@
+use crate::config::ChannelPersonaConfig;
 use crate::tools::Tool;
 use anyhow::Result;
 use chrono::Local;
 use std::fmt::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const BOOTSTRAP_MAX_CHARS: usize = 20_000;
 
+/// User-editable prompt files, merged into the project context in this order
+/// after `AGENTS.md`, `SOUL.md` and `TOOLS.md`. `IDENTITY.md` is always listed
+/// (with a missing-file marker); the others only when present.
+pub const PROMPT_FILES: [&str; 3] = ["IDENTITY.md", "INSTRUCTIONS.md", "CONTEXT.md"];
+
+/// Workspace directory holding per-channel overrides:
+/// `prompts/<channel>/<file>` replaces `<file>` for that channel.
+pub const PROMPT_OVERRIDES_DIR: &str = "prompts";
+
+/// Path of prompt file `filename` for `channel`: the channel override when one
+/// exists, otherwise the workspace copy.
+pub fn prompt_file_path(workspace_dir: &Path, channel: Option<&str>, filename: &str) -> PathBuf {
+    if let Some(channel) = channel {
+        let path = workspace_dir
+            .join(PROMPT_OVERRIDES_DIR)
+            .join(channel)
+            .join(filename);
+        if path.is_file() {
+            return path;
+        }
+    }
+    workspace_dir.join(filename)
+}
+
+/// Whether `filename` from [`PROMPT_FILES`] belongs in the prompt at `path`.
+pub fn include_prompt_file(path: &Path, filename: &str) -> bool {
+    filename == PROMPT_FILES[0] || path.is_file()
+}
+
+/// Channels with a `prompts/<channel>/` override directory, sorted.
+pub fn channels_with_prompt_overrides(workspace_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(workspace_dir.join(PROMPT_OVERRIDES_DIR)) else {
+        return Vec::new();
+    };
+    let mut channels: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect();
+    channels.sort();
+    channels
+}
+
+/// `[channels_config.personas.<channel>]` as prompt sections, with
+/// `{channel}` and `{user}` filled in. Empty when neither text is set.
+pub fn channel_persona_section(
+    persona: &ChannelPersonaConfig,
+    channel: &str,
+    user: &str,
+) -> String {
+    let mut section = String::new();
+    for (heading, text) in [
+        ("Channel Persona", &persona.persona),
+        ("Channel Instructions", &persona.system_prompt),
+    ] {
+        let Some(text) = text.as_deref().map(str::trim).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        let rendered = text.replace("{channel}", channel).replace("{user}", user);
+        let _ = write!(section, "\n\n## {heading}\n\n{rendered}");
+    }
+    section
+}
+
 pub struct PromptContext<'a> {
     pub workspace_dir: &'a Path,
     pub model_name: &'a str,
@@ -30,8 +95,10 @@ impl SystemPromptBuilder {
                 Box::new(IdentitySection),
                 Box::new(ToolsSection),
                 Box::new(SafetySection),
+                Box::new(SkillsSection),
                 Box::new(WorkspaceSection),
                 Box::new(DateTimeSection),
+                Box::new(LanguageSection),
                 Box::new(RuntimeSection),
             ],
         }
@@ -59,9 +126,11 @@ impl SystemPromptBuilder {
 pub struct IdentitySection;
 pub struct ToolsSection;
 pub struct SafetySection;
+pub struct SkillsSection;
 pub struct WorkspaceSection;
 pub struct RuntimeSection;
 pub struct DateTimeSection;
+pub struct LanguageSection;
 
 impl PromptSection for IdentitySection {
     fn name(&self) -> &str {
@@ -73,16 +142,15 @@ impl PromptSection for IdentitySection {
         prompt.push_str(
             "The following workspace files define your identity, behavior, and context.\n\n",
         );
-        for file in [
-            "AGENTS.md",
-            "SOUL.md",
-            "TOOLS.md",
-            "IDENTITY.md",
-            "USER.md",
-            "HEARTBEAT.md",
-            "BOOTSTRAP.md",
-            "MEMORY.md",
-        ] {
+        for file in ["AGENTS.md", "SOUL.md", "TOOLS.md"] {
+            inject_workspace_file(&mut prompt, ctx.workspace_dir, file);
+        }
+        for file in PROMPT_FILES {
+            if include_prompt_file(&ctx.workspace_dir.join(file), file) {
+                inject_workspace_file(&mut prompt, ctx.workspace_dir, file);
+            }
+        }
+        for file in ["USER.md", "HEARTBEAT.md", "BOOTSTRAP.md", "MEMORY.md"] {
             inject_workspace_file(&mut prompt, ctx.workspace_dir, file);
         }
 
@@ -124,6 +192,16 @@ impl PromptSection for SafetySection {
     }
 }
 
+impl PromptSection for SkillsSection {
+    fn name(&self) -> &str {
+        "skills"
+    }
+
+    fn build(&self, ctx: &PromptContext<'_>) -> Result<String> {
+        Ok(crate::skills::render_skills_prompt(ctx.workspace_dir))
+    }
+}
+
 impl PromptSection for WorkspaceSection {
     fn name(&self) -> &str {
         "workspace"
@@ -168,6 +246,16 @@ impl PromptSection for DateTimeSection {
     }
 }
 
+impl PromptSection for LanguageSection {
+    fn name(&self) -> &str {
+        "language"
+    }
+
+    fn build(&self, _ctx: &PromptContext<'_>) -> Result<String> {
+        Ok(crate::util::i18n::runtime_locale().prompt_section())
+    }
+}
+
 fn inject_workspace_file(prompt: &mut String, workspace_dir: &Path, filename: &str) {
     let path = workspace_dir.join(filename);
     match std::fs::read_to_string(&path) {
@@ -266,6 +354,43 @@ mod tests {
         let _ = std::fs::remove_dir_all(workspace);
     }
 
+    #[test]
+    fn prompt_files_merge_in_order_with_channel_overrides() {
+        let workspace =
+            std::env::temp_dir().join(format!("zeroclaw_prompt_test_{}", uuid::Uuid::new_v4()));
+        let overrides = workspace.join(PROMPT_OVERRIDES_DIR).join("telegram");
+        std::fs::create_dir_all(&overrides).unwrap();
+        std::fs::write(workspace.join("INSTRUCTIONS.md"), "Be brief.").unwrap();
+        std::fs::write(workspace.join("CONTEXT.md"), "Team: infra").unwrap();
+        std::fs::write(overrides.join("INSTRUCTIONS.md"), "Use emoji.").unwrap();
+
+        let tools: Vec<Box<dyn Tool>> = vec![];
+        let ctx = PromptContext {
+            workspace_dir: &workspace,
+            model_name: "test-model",
+            tools: &tools,
+            dispatcher_instructions: "",
+        };
+        let output = IdentitySection.build(&ctx).unwrap();
+        let identity = output.find("### IDENTITY.md").unwrap();
+        let instructions = output.find("Be brief.").unwrap();
+        let context = output.find("Team: infra").unwrap();
+        let user = output.find("### USER.md").unwrap();
+        assert!(identity < instructions && instructions < context && context < user);
+
+        assert_eq!(
+            prompt_file_path(&workspace, Some("telegram"), "INSTRUCTIONS.md"),
+            overrides.join("INSTRUCTIONS.md")
+        );
+        assert_eq!(
+            prompt_file_path(&workspace, Some("telegram"), "CONTEXT.md"),
+            workspace.join("CONTEXT.md")
+        );
+        assert_eq!(channels_with_prompt_overrides(&workspace), ["telegram"]);
+
+        let _ = std::fs::remove_dir_all(workspace);
+    }
+
     #[test]
     fn prompt_builder_assembles_sections() {
         let tools: Vec<Box<dyn Tool>> = vec![Box::new(TestTool)];
@@ -299,4 +424,22 @@ mod tests {
         assert!(payload.contains(" ("));
         assert!(payload.ends_with(')'));
     }
+
+    #[test]
+    fn channel_persona_section_fills_in_channel_and_user() {
+        let persona = ChannelPersonaConfig {
+            persona: Some("Terse. No emoji.".into()),
+            system_prompt: Some(" You are talking to {user} on {channel}. ".into()),
+        };
+        assert_eq!(
+            channel_persona_section(&persona, "slack", "U123"),
+            "\n\n## Channel Persona\n\nTerse. No emoji.\n\n## Channel Instructions\n\nYou are talking to U123 on slack."
+        );
+
+        let blank = ChannelPersonaConfig {
+            persona: Some("  ".into()),
+            system_prompt: None,
+        };
+        assert_eq!(channel_persona_section(&blank, "slack", "U123"), "");
+    }
 }
@@ -0,0 +1,335 @@
+//! Agent registry built from `[[agents]]` config blocks.
+//!
+//! Every runtime has a `default` agent that uses the top-level provider and
+//! model. Additional agents override provider, model, prompt, tools and
+//! memory namespace, and claim channel conversations through routes that are
+//! resolved with [`crate::routing::DefaultRouter`]. Routes come from
+//! `[[agents.routes]]` and from the `[[routes]]` bindings managed by
+//! `zeroclaw route`.
+
+use crate::config::Config;
+use crate::memory::{Memory, NamespacedMemory};
+use crate::routing::{ChatType, DefaultRouter, RouteBinding, RouteContext, RouteMatch, Router};
+use crate::tools::Tool;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// ID of the agent built from the top-level config.
+pub const DEFAULT_AGENT_ID: &str = "default";
+
+/// One configured agent. `None` provider/model use the caller's defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentProfile {
+    pub id: String,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub system_prompt: Option<String>,
+    pub tools: Vec<String>,
+    pub memory_namespace: Option<String>,
+    /// Autonomy profile name; `None` uses `[autonomy]`.
+    pub autonomy: Option<String>,
+}
+
+impl AgentProfile {
+    /// Whether the tool allowlist permits `name`. An empty list allows all.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|tool| tool == name)
+    }
+
+    /// Drop tools outside the allowlist.
+    pub fn restrict_tools(&self, tools: &mut Vec<Box<dyn Tool>>) {
+        tools.retain(|tool| self.allows_tool(tool.name()));
+    }
+
+    /// Names of tools in `tools` this agent may not use.
+    pub fn disallowed_tools(&self, tools: &[Box<dyn Tool>]) -> Vec<String> {
+        tools
+            .iter()
+            .filter(|tool| !self.allows_tool(tool.name()))
+            .map(|tool| tool.name().to_string())
+            .collect()
+    }
+
+    /// `memory` scoped to this agent's namespace, if it has one.
+    pub fn scope_memory(&self, memory: Arc<dyn Memory>) -> Arc<dyn Memory> {
+        match self.memory_namespace.as_deref().map(str::trim) {
+            Some(namespace) if !namespace.is_empty() => {
+                Arc::new(NamespacedMemory::new(memory, namespace))
+            }
+            _ => memory,
+        }
+    }
+
+    /// Append this agent's instructions to a system prompt.
+    pub fn apply_system_prompt(&self, system_prompt: &mut String) {
+        if let Some(extra) = self.system_prompt.as_deref().map(str::trim) {
+            if !extra.is_empty() {
+                system_prompt.push_str("\n\n## Agent Instructions\n\n");
+                system_prompt.push_str(extra);
+                system_prompt.push('\n');
+            }
+        }
+    }
+}
+
+/// Agents by ID plus the router that maps conversations to them.
+pub struct AgentRegistry {
+    agents: HashMap<String, Arc<AgentProfile>>,
+    router: DefaultRouter,
+}
+
+impl AgentRegistry {
+    pub fn from_config(config: &Config) -> Self {
+        let mut agents = HashMap::new();
+        agents.insert(
+            DEFAULT_AGENT_ID.to_string(),
+            Arc::new(AgentProfile {
+                id: DEFAULT_AGENT_ID.to_string(),
+                provider: None,
+                model: None,
+                system_prompt: None,
+                tools: Vec::new(),
+                memory_namespace: None,
+                autonomy: None,
+            }),
+        );
+
+        for agent in &config.agents {
+            let id = agent.id.trim().to_string();
+            agents.insert(
+                id.clone(),
+                Arc::new(AgentProfile {
+                    id,
+                    provider: agent.provider.clone(),
+                    model: agent.model.clone(),
+                    system_prompt: agent.system_prompt.clone(),
+                    tools: agent.tools.clone(),
+                    memory_namespace: agent.memory_namespace.clone(),
+                    autonomy: agent.autonomy.clone(),
+                }),
+            );
+        }
+
+        Self {
+            agents,
+            router: DefaultRouter::with_bindings(DEFAULT_AGENT_ID, route_bindings(config)),
+        }
+    }
+
+    /// Whether `config` defines agents or routes, i.e. whether channel
+    /// messages need routing at all.
+    pub fn is_configured(config: &Config) -> bool {
+        !config.agents.is_empty() || !config.routes.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<AgentProfile>> {
+        self.agents.get(id.trim()).cloned()
+    }
+
+    /// Look up `id`, failing with the list of known agents.
+    pub fn require(&self, id: &str) -> Result<Arc<AgentProfile>> {
+        self.get(id).ok_or_else(|| {
+            let mut known: Vec<&str> = self.agents.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            anyhow::anyhow!(
+                "Unknown agent '{id}'. Configured agents: {}",
+                known.join(", ")
+            )
+        })
+    }
+
+    pub fn default_agent(&self) -> Arc<AgentProfile> {
+        self.agents[DEFAULT_AGENT_ID].clone()
+    }
+
+    /// Resolve the agent for a conversation. Routes pointing at an unknown
+    /// agent fall back to the default agent.
+    pub async fn resolve(&self, context: &RouteContext) -> Result<(Arc<AgentProfile>, RouteMatch)> {
+        let route = self.router.resolve_route(context).await?;
+        tracing::debug!(
+            channel = %context.channel,
+            sender = %context.sender,
+            agent = %route.agent_id,
+            binding = route.binding_id.as_deref().unwrap_or("none"),
+            matched_by = ?route.matched_by,
+            "Resolved agent route"
+        );
+        let agent = self.get(&route.agent_id).unwrap_or_else(|| {
+            tracing::warn!(agent = %route.agent_id, "Route targets unknown agent; using default");
+            self.default_agent()
+        });
+        Ok((agent, route))
+    }
+}
+
+/// Every route binding in `config`: `[[agents.routes]]` entries (IDs
+/// `<agent>:<n>`) followed by `[[routes]]`.
+pub fn route_bindings(config: &Config) -> Vec<RouteBinding> {
+    let mut bindings = Vec::new();
+    for agent in &config.agents {
+        let id = agent.id.trim();
+        for (i, route) in agent.routes.iter().enumerate() {
+            bindings.push(RouteBinding {
+                id: format!("{id}:{i}"),
+                channel: route.channel.trim().to_string(),
+                pattern: route.pattern.trim().to_string(),
+                agent_id: id.to_string(),
+                priority: route.priority,
+            });
+        }
+    }
+    bindings.extend(config.routes.iter().map(|route| RouteBinding {
+        id: route.id.trim().to_string(),
+        channel: route.channel.trim().to_string(),
+        pattern: route.pattern.trim().to_string(),
+        agent_id: route.agent.trim().to_string(),
+        priority: route.priority,
+    }));
+    bindings
+}
+
+/// Route context for a direct or group conversation on `channel`.
+pub fn route_context(channel: &str, sender: &str, reply_target: &str) -> RouteContext {
+    RouteContext {
+        channel: channel.to_string(),
+        sender: sender.to_string(),
+        recipient: Some(reply_target.to_string()),
+        chat_type: if reply_target == sender {
+            ChatType::Direct
+        } else {
+            ChatType::Group
+        },
+        account_id: None,
+        guild_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AgentProfileConfig, AgentRouteConfig, RouteBindingConfig};
+
+    fn config_with_agents() -> Config {
+        Config {
+            agents: vec![
+                AgentProfileConfig {
+                    id: "research".into(),
+                    provider: Some("anthropic".into()),
+                    model: None,
+                    system_prompt: Some("Cite sources.".into()),
+                    tools: vec!["web_search".into()],
+                    memory_namespace: Some("research".into()),
+                    autonomy: None,
+                    routes: vec![AgentRouteConfig {
+                        channel: "telegram".into(),
+                        pattern: "alice".into(),
+                        priority: 0,
+                    }],
+                },
+                AgentProfileConfig {
+                    id: "support".into(),
+                    provider: None,
+                    model: Some("support-model".into()),
+                    system_prompt: None,
+                    tools: Vec::new(),
+                    memory_namespace: None,
+                    autonomy: None,
+                    routes: vec![AgentRouteConfig {
+                        channel: "telegram".into(),
+                        pattern: "*".into(),
+                        priority: 10,
+                    }],
+                },
+            ],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn profiles_keep_overrides_and_allowlists() {
+        let registry = AgentRegistry::from_config(&config_with_agents());
+
+        let research = registry.require("research").unwrap();
+        assert_eq!(research.provider.as_deref(), Some("anthropic"));
+        assert_eq!(research.model, None);
+        assert!(research.allows_tool("web_search"));
+        assert!(!research.allows_tool("shell"));
+
+        let support = registry.require("support").unwrap();
+        assert_eq!(support.provider, None);
+        assert_eq!(support.model.as_deref(), Some("support-model"));
+        assert!(support.allows_tool("shell"));
+
+        assert_eq!(registry.default_agent().id, DEFAULT_AGENT_ID);
+        let err = registry.require("nope").unwrap_err().to_string();
+        assert!(err.contains("default, research, support"));
+    }
+
+    #[tokio::test]
+    async fn routes_select_agent_by_sender_then_channel() {
+        let registry = AgentRegistry::from_config(&config_with_agents());
+
+        let (agent, route) = registry
+            .resolve(&route_context("telegram", "alice", "alice"))
+            .await
+            .unwrap();
+        assert_eq!(agent.id, "research");
+        assert_eq!(route.agent_id, "research");
+
+        let (agent, _) = registry
+            .resolve(&route_context("telegram", "bob", "bob"))
+            .await
+            .unwrap();
+        assert_eq!(agent.id, "support");
+
+        let (agent, _) = registry
+            .resolve(&route_context("discord", "alice", "alice"))
+            .await
+            .unwrap();
+        assert_eq!(agent.id, DEFAULT_AGENT_ID);
+    }
+
+    #[tokio::test]
+    async fn standalone_routes_are_loaded_and_reported() {
+        let mut config = config_with_agents();
+        config.routes.push(RouteBindingConfig {
+            id: "route-1".into(),
+            channel: "telegram".into(),
+            pattern: "bob".into(),
+            agent: DEFAULT_AGENT_ID.into(),
+            priority: 1,
+        });
+        assert!(AgentRegistry::is_configured(&config));
+        assert!(!AgentRegistry::is_configured(&Config::default()));
+
+        let ids: Vec<String> = route_bindings(&config).into_iter().map(|b| b.id).collect();
+        assert_eq!(ids, ["research:0", "support:0", "route-1"]);
+
+        let registry = AgentRegistry::from_config(&config);
+        let (agent, route) = registry
+            .resolve(&route_context("telegram", "bob", "bob"))
+            .await
+            .unwrap();
+        assert_eq!(agent.id, DEFAULT_AGENT_ID);
+        assert_eq!(route.binding_id.as_deref(), Some("route-1"));
+    }
+
+    #[test]
+    fn system_prompt_is_appended_as_section() {
+        let registry = AgentRegistry::from_config(&config_with_agents());
+        let mut prompt = String::from("Base prompt.");
+        registry
+            .require("research")
+            .unwrap()
+            .apply_system_prompt(&mut prompt);
+        assert_eq!(
+            prompt,
+            "Base prompt.\n\n## Agent Instructions\n\nCite sources.\n"
+        );
+
+        let mut unchanged = String::from("Base prompt.");
+        registry.default_agent().apply_system_prompt(&mut unchanged);
+        assert_eq!(unchanged, "Base prompt.");
+    }
+}
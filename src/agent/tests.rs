@@ -10,7 +10,7 @@
 //!   5. Unknown tool name recovery
 //!   6. Tool execution failure recovery
 //!   7. Parallel tool dispatch
-//!   8. History trimming during long conversations
+//!   8. History trimming and compaction during long conversations
 //!   9. Memory auto-save round-trip
 //!  10. Native vs XML dispatcher integration
 //!  11. Empty / whitespace-only LLM responses
@@ -25,6 +25,7 @@
 //!  20. Idempotent system prompt insertion
 
 use crate::agent::agent::Agent;
+use crate::agent::compactor::LlmContextCompactor;
 use crate::agent::dispatcher::{
     NativeToolDispatcher, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
 };
@@ -95,6 +96,7 @@ impl Provider for ScriptedProvider {
                 tool_calls: vec![],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             });
         }
         Ok(guard.remove(0))
@@ -183,7 +185,7 @@ impl Tool for FailingTool {
         Ok(ToolResult {
             success: false,
             output: String::new(),
-            error: Some("intentional failure".into()),
+            error: Some(crate::tools::ToolError::failed("intentional failure")),
         })
     }
 }
@@ -332,6 +334,7 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
         tool_calls: calls,
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }
 }
 
@@ -342,6 +345,7 @@ fn text_response(text: &str) -> ChatResponse {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }
 }
 
@@ -354,6 +358,7 @@ fn xml_tool_response(name: &str, args: &str) -> ChatResponse {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }
 }
 
@@ -590,7 +595,7 @@ async fn turn_propagates_provider_error() {
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 8. History trimming during long conversations
+// 8. History trimming and compaction during long conversations
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[tokio::test]
@@ -627,6 +632,52 @@ async fn history_trims_after_max_messages() {
     assert!(matches!(first, ConversationMessage::Chat(c) if c.role == "system"));
 }
 
+#[tokio::test]
+async fn history_compacts_into_summary_with_compactor() {
+    let (summary_mem, _tmp) = make_sqlite_memory();
+    let compactor = LlmContextCompactor::new(Arc::new(ScriptedProvider::new(vec![])), "test")
+        .with_memory(Arc::clone(&summary_mem));
+    let responses = (0..5).map(|_| text_response("ok")).collect();
+    let mut agent = Agent::builder()
+        .provider(Box::new(ScriptedProvider::new(responses)))
+        .tools(vec![])
+        .memory(make_memory())
+        .observer(make_observer())
+        .tool_dispatcher(Box::new(NativeToolDispatcher))
+        .workspace_dir(std::env::temp_dir())
+        .config(AgentConfig {
+            max_history_messages: 6,
+            ..AgentConfig::default()
+        })
+        .compactor(Box::new(compactor))
+        .build()
+        .unwrap();
+
+    for i in 0..5 {
+        agent.turn(&format!("msg {i}")).await.unwrap();
+    }
+
+    let chats: Vec<(&str, &str)> = agent
+        .history()
+        .iter()
+        .filter_map(|m| match m {
+            ConversationMessage::Chat(c) => Some((c.role.as_str(), c.content.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(chats[0].0, "system");
+    assert_eq!(chats[1], ("assistant", "[Conversation summary]\nfallback"));
+    assert_eq!(chats.last(), Some(&("assistant", "ok")));
+    assert!(chats.iter().any(|c| c == &("user", "msg 4")));
+    assert!(agent.history().len() <= 7);
+
+    let stored = summary_mem
+        .list(Some(&memory::MemoryCategory::Conversation), None)
+        .await
+        .unwrap();
+    assert!(!stored.is_empty());
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // 9. Memory auto-save round-trip
 // ═══════════════════════════════════════════════════════════════════════════
@@ -744,6 +795,7 @@ async fn turn_handles_empty_text_response() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -759,6 +811,7 @@ async fn turn_handles_none_text_response() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }]));
 
     let mut agent = build_agent_with(provider, vec![], Box::new(NativeToolDispatcher));
@@ -784,6 +837,7 @@ async fn turn_preserves_text_alongside_tool_calls() {
             }],
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         },
         text_response("Here are the results"),
     ]));
@@ -1022,6 +1076,7 @@ async fn native_dispatcher_handles_stringified_arguments() {
         }],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     let (_, calls) = dispatcher.parse_response(&response);
@@ -1049,6 +1104,7 @@ fn xml_dispatcher_handles_nested_json() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1068,6 +1124,7 @@ fn xml_dispatcher_handles_empty_tool_call_tag() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -1083,6 +1140,7 @@ fn xml_dispatcher_handles_unclosed_tool_call() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     let dispatcher = XmlToolDispatcher;
@@ -0,0 +1,99 @@
+//! Per-run record of tool calls and token usage.
+//!
+//! [`traced`] scopes a trace to one agent run (e.g. `POST /api/agent/run`);
+//! the tool-call loop appends to it while the scope is active and does
+//! nothing otherwise.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    static TRACE: Arc<Mutex<RunTrace>>;
+}
+
+/// Everything recorded while a traced run was active.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunTrace {
+    pub tool_calls: Vec<ToolCallTrace>,
+    pub usage: TokenUsage,
+}
+
+/// One tool call: the arguments the model sent and the output it saw.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallTrace {
+    /// 1-based tool-loop iteration the call was made in.
+    pub iteration: usize,
+    pub name: String,
+    pub arguments: serde_json::Value,
+    pub output: String,
+    pub success: bool,
+    /// `ToolErrorKind` (snake_case) when the call failed.
+    pub error_kind: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Token counts summed over every provider call in the run. Counts are the
+/// provider's when it reports them and local estimates otherwise.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct TokenUsage {
+    pub llm_calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Run `future` with a fresh trace and return its output with the trace.
+pub async fn traced<F: Future>(future: F) -> (F::Output, RunTrace) {
+    let trace = Arc::new(Mutex::new(RunTrace::default()));
+    let output = TRACE.scope(trace.clone(), future).await;
+    let recorded = std::mem::take(&mut *trace.lock());
+    (output, recorded)
+}
+
+/// Append a tool call to the active trace. `entry` is only built when a
+/// trace is active.
+pub(crate) fn record_tool_call(entry: impl FnOnce() -> ToolCallTrace) {
+    let _ = TRACE.try_with(|trace| trace.lock().tool_calls.push(entry()));
+}
+
+/// Count one provider call in the active trace.
+pub(crate) fn record_llm_call(input_tokens: Option<u64>, output_tokens: Option<u64>) {
+    let _ = TRACE.try_with(|trace| {
+        let usage = &mut trace.lock().usage;
+        usage.llm_calls += 1;
+        usage.input_tokens += input_tokens.unwrap_or(0);
+        usage.output_tokens += output_tokens.unwrap_or(0);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_only_inside_traced_scope() {
+        record_llm_call(Some(10), Some(5));
+
+        let ((), trace) = traced(async {
+            record_llm_call(Some(10), Some(5));
+            record_llm_call(None, Some(1));
+            record_tool_call(|| ToolCallTrace {
+                iteration: 1,
+                name: "shell".into(),
+                arguments: serde_json::json!({"command": "ls"}),
+                output: "README.md".into(),
+                success: true,
+                error_kind: None,
+                duration_ms: 3,
+            });
+        })
+        .await;
+
+        assert_eq!(trace.usage.llm_calls, 2);
+        assert_eq!(trace.usage.input_tokens, 10);
+        assert_eq!(trace.usage.output_tokens, 6);
+        assert_eq!(trace.tool_calls.len(), 1);
+        assert_eq!(trace.tool_calls[0].name, "shell");
+    }
+}
@@ -4,14 +4,11 @@ pub mod dispatcher;
 pub mod reply;
 pub mod traits;
 
-pub use commands::HelpCommand;
 pub use directives::DefaultDirectiveParser;
 pub use dispatcher::DefaultDispatcher;
-pub use reply::DefaultReplyDispatcher;
+pub use reply::{ChannelReplyDispatcher, DefaultReplyDispatcher};
 pub use traits::{
-    CommandContext, CommandResult, DirectiveParser, DispatchContext, DispatchResult, Dispatcher,
-    InboundMessage, ParsedDirective, ParsedMessage, ReplyContext, ReplyDispatcher, ReplyMessage,
-    SlashCommandHandler,
+    DirectiveParser, Dispatcher, ReplyContext, ReplyDispatcher, ReplyMessage, SlashCommandHandler,
 };
 
 pub fn create_directive_parser() -> Box<dyn DirectiveParser> {
@@ -26,5 +23,5 @@ pub fn create_dispatcher(
 }
 
 pub fn create_reply_dispatcher() -> Box<dyn ReplyDispatcher> {
-    Box::new(DefaultReplyDispatcher)
+    Box::new(DefaultReplyDispatcher::new())
 }
@@ -1,18 +1,206 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use super::traits::{ReplyContext, ReplyDispatcher, ReplyMessage};
+use crate::channels::traits::{Channel, SendMessage};
+use crate::media::SpeechSynthesizer;
+
+/// [`ReplyMessage::metadata`] key holding the platform id of the message
+/// being answered. Channels that support inline replies thread the first
+/// chunk of the reply onto it.
+pub const REPLY_TO_METADATA_KEY: &str = "reply_to";
+
+const CODE_FENCE: &str = "```";
+
+/// Maximum message length (in characters) for platforms that enforce one.
+pub fn channel_message_limit(channel: &str) -> Option<usize> {
+    match channel {
+        "telegram" | "whatsapp" => Some(4096),
+        "discord" => Some(2000),
+        _ => None,
+    }
+}
+
+/// Split `text` into chunks of at most `limit` characters.
+///
+/// Splits happen at line breaks where possible; a single line longer than
+/// the limit is cut mid-line. A fenced code block that spans a split is
+/// closed at the end of one chunk and reopened (with its language tag) at
+/// the start of the next, so every chunk renders on its own.
+pub fn split_reply(text: &str, limit: usize) -> Vec<String> {
+    if text.chars().count() <= limit {
+        return vec![text.to_string()];
+    }
+    let mut chunker = Chunker::new(limit);
+    for line in text.split('\n') {
+        chunker.push_line(line);
+    }
+    chunker.finish()
+}
+
+struct Chunker {
+    limit: usize,
+    chunks: Vec<String>,
+    current: String,
+    len: usize,
+    /// Length of the reopened fence line at the start of `current`.
+    header_len: usize,
+    /// Opening line of the code block `current` is inside, if any.
+    fence: Option<String>,
+}
+
+impl Chunker {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(CODE_FENCE.len() * 2 + 3),
+            chunks: Vec::new(),
+            current: String::new(),
+            len: 0,
+            header_len: 0,
+            fence: None,
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let is_fence = line.trim_start().starts_with(CODE_FENCE);
+        let open_after = match (&self.fence, is_fence) {
+            (None, true) => true,
+            (Some(_), true) => false,
+            (open, false) => open.is_some(),
+        };
+        // Room for the "\n```" that closes a block left open at a split.
+        let reserve = if open_after { CODE_FENCE.len() + 1 } else { 0 };
+
+        let mut rest: Vec<char> = line.chars().collect();
+        loop {
+            let sep = usize::from(!self.current.is_empty());
+            if self.len + sep + rest.len() + reserve <= self.limit {
+                self.append(&rest.iter().collect::<String>(), sep);
+                break;
+            }
+            if self.len > self.header_len {
+                self.flush();
+                continue;
+            }
+            let room = self.limit.saturating_sub(self.len + sep + reserve).max(1);
+            let piece: String = rest.drain(..room.min(rest.len())).collect();
+            self.append(&piece, sep);
+            if rest.is_empty() {
+                break;
+            }
+            self.flush();
+        }
 
-/// Simple pass-through reply dispatcher that logs replies.
-pub struct DefaultReplyDispatcher;
+        if is_fence {
+            self.fence = if self.fence.is_some() {
+                None
+            } else {
+                Some(line.trim_start().to_string())
+            };
+        }
+    }
+
+    fn append(&mut self, piece: &str, sep: usize) {
+        if sep == 1 {
+            self.current.push('\n');
+        }
+        self.current.push_str(piece);
+        self.len += sep + piece.chars().count();
+    }
+
+    fn flush(&mut self) {
+        let mut chunk = std::mem::take(&mut self.current);
+        if self.fence.is_some() {
+            chunk.push('\n');
+            chunk.push_str(CODE_FENCE);
+        }
+        self.chunks.push(chunk);
+        self.len = 0;
+        self.header_len = 0;
+        if let Some(fence) = &self.fence {
+            self.current.clone_from(fence);
+            self.len = fence.chars().count();
+            self.header_len = self.len;
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        if self.len > self.header_len {
+            let chunk = std::mem::take(&mut self.current);
+            self.chunks.push(chunk);
+        }
+        self.chunks
+    }
+}
+
+/// Pass-through reply dispatcher that logs replies, split to the
+/// destination channel's message limit.
+pub struct DefaultReplyDispatcher {
+    limits: HashMap<String, usize>,
+}
+
+impl Default for DefaultReplyDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultReplyDispatcher {
+    pub fn new() -> Self {
+        Self {
+            limits: HashMap::new(),
+        }
+    }
+
+    /// Override the message limit for `channel`.
+    pub fn with_limit(mut self, channel: impl Into<String>, limit: usize) -> Self {
+        self.limits.insert(channel.into(), limit);
+        self
+    }
+
+    /// Message limit for `channel`: an override, else the platform default.
+    pub fn limit_for(&self, channel: &str) -> Option<usize> {
+        self.limits
+            .get(channel)
+            .copied()
+            .or_else(|| channel_message_limit(channel))
+    }
+
+    /// `reply` as one or more messages within the channel's limit. Only the
+    /// first is threaded onto the originating message.
+    pub fn outgoing_messages(&self, reply: &ReplyMessage) -> Vec<SendMessage> {
+        let chunks = match self.limit_for(&reply.channel) {
+            Some(limit) => split_reply(&reply.content, limit),
+            None => vec![reply.content.clone()],
+        };
+        let quoted = reply.metadata.get(REPLY_TO_METADATA_KEY).cloned();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let message = SendMessage::new(chunk, &reply.recipient);
+                if index == 0 {
+                    message.quoting(quoted.clone())
+                } else {
+                    message
+                }
+            })
+            .collect()
+    }
+}
 
 #[async_trait]
 impl ReplyDispatcher for DefaultReplyDispatcher {
     async fn send(&self, reply: ReplyMessage, _context: &ReplyContext) -> Result<()> {
+        let messages = self.outgoing_messages(&reply);
         tracing::info!(
             recipient = %reply.recipient,
             channel = %reply.channel,
             streaming = reply.is_streaming,
+            chunks = messages.len(),
             "dispatching reply"
         );
         Ok(())
@@ -27,14 +215,156 @@ impl ReplyDispatcher for DefaultReplyDispatcher {
     }
 }
 
+/// Delivers replies through a channel, optionally as synthesized speech.
+///
+/// When a [`SpeechSynthesizer`] is attached (the channel's `voice_replies`
+/// setting), non-streaming replies are spoken and sent with
+/// [`Channel::send_audio`]. Any synthesis or delivery failure falls back to a
+/// plain text message so the reply is never lost.
+///
+/// Text replies are split to the channel's message limit (see
+/// [`DefaultReplyDispatcher`]) and sent in order.
+pub struct ChannelReplyDispatcher {
+    channel: Arc<dyn Channel>,
+    synthesizer: Option<Arc<dyn SpeechSynthesizer>>,
+    chunker: DefaultReplyDispatcher,
+}
+
+impl ChannelReplyDispatcher {
+    pub fn new(channel: Arc<dyn Channel>) -> Self {
+        Self {
+            channel,
+            synthesizer: None,
+            chunker: DefaultReplyDispatcher::new(),
+        }
+    }
+
+    /// Enable voice replies through `synthesizer`.
+    pub fn with_voice(mut self, synthesizer: Arc<dyn SpeechSynthesizer>) -> Self {
+        self.synthesizer = Some(synthesizer);
+        self
+    }
+
+    async fn send_voice(
+        &self,
+        synthesizer: &dyn SpeechSynthesizer,
+        reply: &ReplyMessage,
+    ) -> Result<()> {
+        let speech = synthesizer.synthesize(&reply.content).await?;
+        self.channel
+            .send_audio(&reply.recipient, &speech.audio, &speech.mime_type)
+            .await
+    }
+}
+
+#[async_trait]
+impl ReplyDispatcher for ChannelReplyDispatcher {
+    async fn send(&self, reply: ReplyMessage, _context: &ReplyContext) -> Result<()> {
+        if let Some(synthesizer) = self.synthesizer.as_deref() {
+            if !reply.is_streaming {
+                match self.send_voice(synthesizer, &reply).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => tracing::warn!(
+                        channel = %reply.channel,
+                        backend = synthesizer.name(),
+                        "Voice reply failed, sending text instead: {e:#}"
+                    ),
+                }
+            }
+        }
+        for message in self.chunker.outgoing_messages(&reply) {
+            self.channel.send(&message).await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        "channel"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use crate::media::tts::SynthesizedSpeech;
     use std::collections::HashMap;
 
+    #[derive(Default)]
+    struct RecordingChannel {
+        texts: parking_lot::Mutex<Vec<String>>,
+        quoted: parking_lot::Mutex<Vec<Option<String>>>,
+        audio: parking_lot::Mutex<Vec<(Vec<u8>, String)>>,
+        audio_supported: bool,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            self.texts.lock().push(message.content.clone());
+            self.quoted.lock().push(message.quoted_message_id.clone());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn send_audio(&self, _recipient: &str, audio: &[u8], mime_type: &str) -> Result<()> {
+            if !self.audio_supported {
+                anyhow::bail!("no audio");
+            }
+            self.audio
+                .lock()
+                .push((audio.to_vec(), mime_type.to_string()));
+            Ok(())
+        }
+    }
+
+    struct FixedSynthesizer;
+
+    #[async_trait]
+    impl SpeechSynthesizer for FixedSynthesizer {
+        async fn synthesize(&self, text: &str) -> Result<SynthesizedSpeech> {
+            Ok(SynthesizedSpeech {
+                audio: format!("spoken:{text}").into_bytes(),
+                mime_type: "audio/ogg".into(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    fn reply(content: &str) -> ReplyMessage {
+        ReplyMessage {
+            content: content.to_string(),
+            recipient: "+1555".to_string(),
+            channel: "recording".to_string(),
+            is_streaming: false,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn reply_context() -> ReplyContext {
+        ReplyContext {
+            session_key: "s".to_string(),
+            rate_limit_ms: 0,
+        }
+    }
+
     #[tokio::test]
     async fn send_reply_succeeds() {
-        let dispatcher = DefaultReplyDispatcher;
+        let dispatcher = DefaultReplyDispatcher::new();
         let reply = ReplyMessage {
             content: "test reply".to_string(),
             recipient: "zeroclaw_user".to_string(),
@@ -51,7 +381,110 @@ mod tests {
 
     #[tokio::test]
     async fn flush_is_noop() {
-        let dispatcher = DefaultReplyDispatcher;
+        let dispatcher = DefaultReplyDispatcher::new();
         assert!(dispatcher.flush().await.is_ok());
     }
+
+    #[test]
+    fn split_reply_prefers_line_breaks() {
+        assert_eq!(split_reply("short", 10), vec!["short".to_string()]);
+        let chunks = split_reply("first line\nsecond line\nthird", 24);
+        assert_eq!(chunks, vec!["first line\nsecond line", "third"]);
+
+        let long = "x".repeat(25);
+        let chunks = split_reply(&long, 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 10));
+        assert_eq!(chunks.concat(), long);
+    }
+
+    #[test]
+    fn split_reply_reopens_code_fences() {
+        let text = "Here:\n```rust\nlet a = 1;\nlet b = 2;\nlet c = 3;\n```\nDone.";
+        let chunks = split_reply(text, 30);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 30, "{chunk:?}");
+            assert_eq!(chunk.matches("```").count() % 2, 0, "{chunk:?}");
+        }
+        assert!(chunks[1].starts_with("```rust\n"));
+        let code: Vec<&str> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.lines())
+            .filter(|line| line.starts_with("let"))
+            .collect();
+        assert_eq!(code, vec!["let a = 1;", "let b = 2;", "let c = 3;"]);
+    }
+
+    #[test]
+    fn default_dispatcher_uses_per_channel_limits() {
+        let dispatcher = DefaultReplyDispatcher::new().with_limit("recording", 16);
+        assert_eq!(dispatcher.limit_for("discord"), Some(2000));
+        assert_eq!(dispatcher.limit_for("telegram"), Some(4096));
+        assert_eq!(dispatcher.limit_for("recording"), Some(16));
+        assert_eq!(dispatcher.limit_for("cli"), None);
+
+        let mut long = reply(&"word ".repeat(600));
+        long.channel = "discord".into();
+        let messages = dispatcher.outgoing_messages(&long);
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| m.content.chars().count() <= 2000));
+    }
+
+    #[tokio::test]
+    async fn long_replies_are_chunked_and_threaded_once() {
+        let channel = Arc::new(RecordingChannel::default());
+        let dispatcher = ChannelReplyDispatcher::new(channel.clone());
+        let mut long = reply(&"line\n".repeat(500));
+        long.channel = "discord".into();
+        long.metadata
+            .insert(REPLY_TO_METADATA_KEY.to_string(), "wamid.1".to_string());
+
+        dispatcher.send(long, &reply_context()).await.unwrap();
+        assert_eq!(channel.texts.lock().len(), 2);
+        assert_eq!(
+            *channel.quoted.lock(),
+            vec![Some("wamid.1".to_string()), None]
+        );
+    }
+
+    #[tokio::test]
+    async fn voice_replies_are_sent_as_audio() {
+        let channel = Arc::new(RecordingChannel {
+            audio_supported: true,
+            ..RecordingChannel::default()
+        });
+        let dispatcher =
+            ChannelReplyDispatcher::new(channel.clone()).with_voice(Arc::new(FixedSynthesizer));
+
+        dispatcher
+            .send(reply("hi there"), &reply_context())
+            .await
+            .unwrap();
+        let audio = channel.audio.lock();
+        assert_eq!(audio.len(), 1);
+        assert_eq!(audio[0].0, b"spoken:hi there");
+        assert_eq!(audio[0].1, "audio/ogg");
+        assert!(channel.texts.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn voice_reply_failure_falls_back_to_text() {
+        let channel = Arc::new(RecordingChannel::default());
+        let dispatcher =
+            ChannelReplyDispatcher::new(channel.clone()).with_voice(Arc::new(FixedSynthesizer));
+
+        dispatcher
+            .send(reply("hi there"), &reply_context())
+            .await
+            .unwrap();
+        assert_eq!(*channel.texts.lock(), vec!["hi there".to_string()]);
+
+        let text_only = ChannelReplyDispatcher::new(channel.clone());
+        text_only
+            .send(reply("plain"), &reply_context())
+            .await
+            .unwrap();
+        assert_eq!(channel.texts.lock().len(), 2);
+    }
 }
@@ -0,0 +1,716 @@
+use super::traits::{Channel, ChannelMessage, GroupInfo, SendMessage};
+use crate::config::{GatewayTlsConfig, GitHubConfig};
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use ring::signature::RsaKeyPair;
+use std::fmt::Write;
+use std::path::Path;
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+const API_VERSION: &str = "2022-11-28";
+/// GitHub rejects comment bodies above 65 536 characters.
+const MAX_COMMENT_CHARS: usize = 65_000;
+/// Diff context included for review comments.
+const MAX_DIFF_HUNK_CHARS: usize = 2_000;
+/// Installation tokens are refreshed this long before they expire.
+const TOKEN_REFRESH_MARGIN_SECS: i64 = 300;
+
+/// GitHub channel — answers issue and pull request comments that mention the bot.
+///
+/// Webhook mode only: deliveries arrive at the gateway's `/github` endpoint,
+/// which verifies them and calls [`GitHubChannel::parse_webhook_event`].
+/// Replies are posted as issue comments, or as review thread replies for
+/// pull request review comments.
+///
+/// Messages use `owner/repo#number` as reply target, so each issue or pull
+/// request maps to its own conversation.
+pub struct GitHubChannel {
+    api_url: String,
+    auth: GitHubAuth,
+    mention: String,
+    allowed_repos: Vec<String>,
+    allowed_users: Vec<String>,
+}
+
+enum GitHubAuth {
+    Token(String),
+    App(Box<GitHubApp>),
+}
+
+/// GitHub App credentials; installation tokens are minted on demand.
+struct GitHubApp {
+    app_id: u64,
+    installation_id: u64,
+    key: RsaKeyPair,
+    cached: tokio::sync::Mutex<Option<InstallationToken>>,
+}
+
+struct InstallationToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(serde::Deserialize)]
+struct InstallationTokenResponse {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl GitHubChannel {
+    pub fn new(
+        api_url: Option<&str>,
+        access_token: String,
+        mention: String,
+        allowed_repos: Vec<String>,
+        allowed_users: Vec<String>,
+    ) -> Self {
+        Self {
+            api_url: normalize_api_url(api_url),
+            auth: GitHubAuth::Token(access_token),
+            mention,
+            allowed_repos,
+            allowed_users,
+        }
+    }
+
+    /// Build the channel from `[channels_config.github]`, loading the App
+    /// private key when App credentials are configured.
+    pub fn from_config(config: &GitHubConfig, config_dir: &Path) -> anyhow::Result<Self> {
+        let auth = match (
+            config.app_id,
+            config.installation_id,
+            config.private_key_path.as_deref(),
+        ) {
+            (Some(app_id), Some(installation_id), Some(key_path)) => {
+                let path = GatewayTlsConfig::resolve_path(key_path, config_dir);
+                let pem = std::fs::read_to_string(&path).with_context(|| {
+                    format!("failed to read GitHub App private key {}", path.display())
+                })?;
+                GitHubAuth::App(Box::new(GitHubApp {
+                    app_id,
+                    installation_id,
+                    key: parse_rsa_private_key(&pem)?,
+                    cached: tokio::sync::Mutex::new(None),
+                }))
+            }
+            _ => match config.access_token.as_deref().map(str::trim) {
+                Some(token) if !token.is_empty() => GitHubAuth::Token(token.to_string()),
+                _ => bail!(
+                    "channels_config.github needs access_token or app_id, installation_id and private_key_path"
+                ),
+            },
+        };
+        Ok(Self {
+            api_url: normalize_api_url(config.api_url.as_deref()),
+            auth,
+            mention: config.mention.trim().to_string(),
+            allowed_repos: config.allowed_repos.clone(),
+            allowed_users: config.allowed_users.clone(),
+        })
+    }
+
+    fn http_client(&self) -> reqwest::Client {
+        crate::config::build_runtime_proxy_client("channel.github")
+    }
+
+    fn is_repo_allowed(&self, repo: &str) -> bool {
+        self.allowed_repos
+            .iter()
+            .any(|r| r == "*" || r.eq_ignore_ascii_case(repo))
+    }
+
+    fn is_user_allowed(&self, login: &str) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|u| u == "*" || u.eq_ignore_ascii_case(login))
+    }
+
+    /// Strip the bot mention from `body`; `None` when it is not mentioned.
+    fn strip_mention(&self, body: &str) -> Option<String> {
+        let start = find_mention(body, &self.mention)?;
+        let mut text = String::with_capacity(body.len());
+        text.push_str(&body[..start]);
+        text.push_str(&body[start + self.mention.len()..]);
+        Some(text.trim().to_string())
+    }
+
+    /// Turn an `issue_comment` or `pull_request_review_comment` delivery
+    /// (`X-GitHub-Event`) into a message when it is a new comment that
+    /// mentions the bot from an allowed user and repository.
+    pub fn parse_webhook_event(
+        &self,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> Option<ChannelMessage> {
+        let (subject, is_review_comment) = match event {
+            "issue_comment" => (payload.get("issue")?, false),
+            "pull_request_review_comment" => (payload.get("pull_request")?, true),
+            _ => return None,
+        };
+        if payload.get("action").and_then(|a| a.as_str()) != Some("created") {
+            return None;
+        }
+        let comment = payload.get("comment")?;
+        let user = comment.get("user")?;
+        let login = user.get("login").and_then(|l| l.as_str())?;
+        // Never answer bots, including this one's own replies.
+        if user.get("type").and_then(|t| t.as_str()) == Some("Bot") {
+            return None;
+        }
+        let body = comment.get("body").and_then(|b| b.as_str()).unwrap_or("");
+        let text = self.strip_mention(body)?;
+
+        let repo = payload
+            .get("repository")
+            .and_then(|r| r.get("full_name"))
+            .and_then(|n| n.as_str())?;
+        if !self.is_repo_allowed(repo) {
+            tracing::warn!(
+                "GitHub: ignoring mention in {repo}. Add it to channels_config.github.allowed_repos."
+            );
+            return None;
+        }
+        if !self.is_user_allowed(login) {
+            tracing::warn!(
+                "GitHub: ignoring mention from unauthorized user {login}. \
+                Add it to channels_config.github.allowed_users."
+            );
+            return None;
+        }
+
+        let number = subject.get("number").and_then(serde_json::Value::as_u64)?;
+        let title = subject.get("title").and_then(|t| t.as_str()).unwrap_or("");
+        let is_pull_request = is_review_comment || subject.get("pull_request").is_some();
+        let kind = if is_pull_request {
+            "pull request"
+        } else {
+            "issue"
+        };
+        let target = format!("{repo}#{number}");
+
+        let mut content = format!("GitHub comment by @{login} on {kind} {target} \"{title}\"");
+        if is_review_comment {
+            if let Some(path) = comment.get("path").and_then(|p| p.as_str()) {
+                let _ = write!(content, " (review comment on {path})");
+            }
+        }
+        content.push_str(":\n");
+        content.push_str(if text.is_empty() {
+            "(mentioned without a message)"
+        } else {
+            &text
+        });
+        if is_review_comment {
+            if let Some(hunk) = comment.get("diff_hunk").and_then(|h| h.as_str()) {
+                content.push_str("\n\nDiff context:\n```diff\n");
+                content.push_str(&truncate_with_ellipsis(hunk, MAX_DIFF_HUNK_CHARS));
+                content.push_str("\n```");
+            }
+        }
+
+        let comment_id = comment.get("id").and_then(serde_json::Value::as_u64)?;
+        // Review replies go to the thread's top-level comment.
+        let thread_ts = is_review_comment.then(|| {
+            comment
+                .get("in_reply_to_id")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(comment_id)
+                .to_string()
+        });
+        let timestamp = comment
+            .get("created_at")
+            .and_then(|t| t.as_str())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .and_then(|t| u64::try_from(t.timestamp()).ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            });
+
+        Some(ChannelMessage {
+            id: comment_id.to_string(),
+            sender: login.to_string(),
+            reply_target: target,
+            content,
+            channel: "github".to_string(),
+            timestamp,
+            thread_ts,
+            group: Some(GroupInfo {
+                mentions_bot: true,
+                replies_to_bot: false,
+            }),
+        })
+    }
+
+    /// Token for API calls: the configured token, or a cached installation token.
+    async fn access_token(&self) -> anyhow::Result<String> {
+        match &self.auth {
+            GitHubAuth::Token(token) => Ok(token.clone()),
+            GitHubAuth::App(app) => {
+                let mut cached = app.cached.lock().await;
+                if let Some(token) = cached.as_ref() {
+                    let remaining = token.expires_at - Utc::now();
+                    if remaining.num_seconds() > TOKEN_REFRESH_MARGIN_SECS {
+                        return Ok(token.token.clone());
+                    }
+                }
+                let minted = self.mint_installation_token(app).await?;
+                let token = minted.token.clone();
+                *cached = Some(minted);
+                Ok(token)
+            }
+        }
+    }
+
+    async fn mint_installation_token(&self, app: &GitHubApp) -> anyhow::Result<InstallationToken> {
+        let jwt = app_jwt(app.app_id, &app.key, Utc::now().timestamp())?;
+        let url = format!(
+            "{}/app/installations/{}/access_tokens",
+            self.api_url, app.installation_id
+        );
+        let resp = self
+            .request(reqwest::Method::POST, &url, &jwt)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("GitHub installation token request failed: {status} — {error_body}");
+            bail!("GitHub API error: {status}");
+        }
+        let body: InstallationTokenResponse = resp.json().await?;
+        Ok(InstallationToken {
+            token: body.token,
+            expires_at: body.expires_at,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str, token: &str) -> reqwest::RequestBuilder {
+        self.http_client()
+            .request(method, url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION)
+            .header("User-Agent", "zeroclaw")
+    }
+
+    /// Post `message` as a comment and return the created comment's draft id
+    /// (`issue:<id>` or `review:<id>`, so edits hit the matching endpoint).
+    async fn post_comment(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let (repo, number) = parse_target(&message.recipient)?;
+        let (kind, url) = match message.thread_ts.as_deref() {
+            Some(comment_id) => (
+                "review",
+                format!(
+                    "{}/repos/{repo}/pulls/{number}/comments/{comment_id}/replies",
+                    self.api_url
+                ),
+            ),
+            None => (
+                "issue",
+                format!("{}/repos/{repo}/issues/{number}/comments", self.api_url),
+            ),
+        };
+        let body = serde_json::json!({
+            "body": truncate_with_ellipsis(&message.content, MAX_COMMENT_CHARS),
+        });
+
+        let token = self.access_token().await?;
+        let resp = self
+            .request(reqwest::Method::POST, &url, &token)
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("GitHub comment failed: {status} — {error_body}");
+            bail!("GitHub API error: {status}");
+        }
+        let created: serde_json::Value = resp.json().await.unwrap_or_default();
+        Ok(created
+            .get("id")
+            .and_then(serde_json::Value::as_u64)
+            .map(|id| format!("{kind}:{id}")))
+    }
+
+    /// Edit (`PATCH`) or delete a comment previously created by [`Self::post_comment`].
+    async fn modify_comment(
+        &self,
+        method: reqwest::Method,
+        recipient: &str,
+        draft_id: &str,
+        text: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let (repo, _) = parse_target(recipient)?;
+        let url = match draft_id.split_once(':') {
+            Some(("issue", id)) => format!("{}/repos/{repo}/issues/comments/{id}", self.api_url),
+            Some(("review", id)) => format!("{}/repos/{repo}/pulls/comments/{id}", self.api_url),
+            _ => bail!("Unknown GitHub draft id '{draft_id}'"),
+        };
+
+        let token = self.access_token().await?;
+        let mut request = self.request(method, &url, &token);
+        if let Some(text) = text {
+            request = request.json(&serde_json::json!({
+                "body": truncate_with_ellipsis(text, MAX_COMMENT_CHARS),
+            }));
+        }
+        let resp = request.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::debug!("GitHub comment edit failed: {status} — {error_body}");
+            bail!("GitHub API error: {status}");
+        }
+        Ok(())
+    }
+}
+
+fn normalize_api_url(api_url: Option<&str>) -> String {
+    api_url
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .unwrap_or(DEFAULT_API_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Byte offset of `mention` in `body` as a whole word (so `@zeroclaw` does
+/// not match `@zeroclaw-dev`), ignoring ASCII case.
+fn find_mention(body: &str, mention: &str) -> Option<usize> {
+    if mention.is_empty() {
+        return None;
+    }
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let haystack = body.to_ascii_lowercase();
+    let needle = mention.to_ascii_lowercase();
+    haystack.match_indices(&needle).map(|(i, _)| i).find(|&i| {
+        let before = body[..i].chars().next_back();
+        let after = body[i + needle.len()..].chars().next();
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+/// Split a reply target (`owner/repo#number`).
+fn parse_target(target: &str) -> anyhow::Result<(&str, u64)> {
+    let Some((repo, number)) = target.rsplit_once('#') else {
+        bail!("GitHub recipient must look like owner/repo#number, got '{target}'");
+    };
+    let valid_repo = repo
+        .split_once('/')
+        .is_some_and(|(owner, name)| !owner.is_empty() && !name.is_empty() && !name.contains('/'));
+    match number.parse::<u64>() {
+        Ok(number) if valid_repo => Ok((repo, number)),
+        _ => bail!("GitHub recipient must look like owner/repo#number, got '{target}'"),
+    }
+}
+
+/// Load an RSA key from PEM (`RSA PRIVATE KEY` as downloaded from GitHub, or PKCS#8).
+fn parse_rsa_private_key(pem: &str) -> anyhow::Result<RsaKeyPair> {
+    let pkcs1 = pem.contains("BEGIN RSA PRIVATE KEY");
+    let encoded: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .context("GitHub App private key is not valid PEM")?;
+    let key = if pkcs1 {
+        RsaKeyPair::from_der(&der)
+    } else {
+        RsaKeyPair::from_pkcs8(&der)
+    };
+    key.map_err(|e| anyhow::anyhow!("GitHub App private key rejected: {e}"))
+}
+
+/// RS256 JWT identifying the App, valid for ten minutes (backdated for clock drift).
+fn app_jwt(app_id: u64, key: &RsaKeyPair, now: i64) -> anyhow::Result<String> {
+    let encode = |value: &serde_json::Value| {
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(value.to_string())
+    };
+    let header = encode(&serde_json::json!({"alg": "RS256", "typ": "JWT"}));
+    let claims = encode(&serde_json::json!({
+        "iat": now - 60,
+        "exp": now + 540,
+        "iss": app_id.to_string(),
+    }));
+    let signing_input = format!("{header}.{claims}");
+    let mut signature = vec![0; key.public().modulus_len()];
+    key.sign(
+        &ring::signature::RSA_PKCS1_SHA256,
+        &ring::rand::SystemRandom::new(),
+        signing_input.as_bytes(),
+        &mut signature,
+    )
+    .map_err(|_| anyhow::anyhow!("failed to sign GitHub App JWT"))?;
+    Ok(format!(
+        "{signing_input}.{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+#[async_trait]
+impl Channel for GitHubChannel {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.post_comment(message).await.map(|_| ())
+    }
+
+    async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        // Comments arrive via the gateway's /github webhook endpoint.
+        tracing::info!(
+            "GitHub channel active (webhook mode). \
+            Point the repository or App webhook at your gateway's /github endpoint."
+        );
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+        }
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        self.post_comment(message).await
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.modify_comment(reqwest::Method::PATCH, recipient, message_id, Some(text))
+            .await
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.modify_comment(reqwest::Method::PATCH, recipient, message_id, Some(text))
+            .await
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.modify_comment(reqwest::Method::DELETE, recipient, message_id, None)
+            .await
+    }
+
+    async fn health_check(&self) -> bool {
+        let Ok(token) = self.access_token().await else {
+            return false;
+        };
+        self.request(
+            reqwest::Method::GET,
+            &format!("{}/rate_limit", self.api_url),
+            &token,
+        )
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_channel(api_url: Option<&str>) -> GitHubChannel {
+        GitHubChannel::new(
+            api_url,
+            "ghs_test".into(),
+            "@zeroclaw".into(),
+            vec!["acme/api".into()],
+            vec!["alice".into()],
+        )
+    }
+
+    fn issue_comment(body: &str, login: &str, user_type: &str) -> serde_json::Value {
+        serde_json::json!({
+            "action": "created",
+            "issue": {"number": 12, "title": "Crash on start", "pull_request": {}},
+            "comment": {
+                "id": 99,
+                "body": body,
+                "created_at": "2026-01-02T03:04:05Z",
+                "user": {"login": login, "type": user_type}
+            },
+            "repository": {"full_name": "acme/api"}
+        })
+    }
+
+    #[test]
+    fn github_parses_mentioning_issue_comment() {
+        let ch = make_channel(None);
+        let msg = ch
+            .parse_webhook_event(
+                "issue_comment",
+                &issue_comment("@ZeroClaw why does this crash?", "alice", "User"),
+            )
+            .unwrap();
+        assert_eq!(msg.channel, "github");
+        assert_eq!(msg.id, "99");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "acme/api#12");
+        assert_eq!(msg.thread_ts, None);
+        assert_eq!(msg.timestamp, 1_767_323_045);
+        assert!(msg
+            .content
+            .starts_with("GitHub comment by @alice on pull request acme/api#12"));
+        assert!(msg.content.ends_with(":\nwhy does this crash?"));
+    }
+
+    #[test]
+    fn github_ignores_unaddressed_or_unauthorized_comments() {
+        let ch = make_channel(None);
+        let cases = [
+            issue_comment("no mention here", "alice", "User"),
+            issue_comment("@zeroclaw-dev please look", "alice", "User"),
+            issue_comment("@zeroclaw hi", "mallory", "User"),
+            issue_comment("@zeroclaw hi", "alice", "Bot"),
+        ];
+        for payload in &cases {
+            assert!(ch.parse_webhook_event("issue_comment", payload).is_none());
+        }
+
+        let mut edited = issue_comment("@zeroclaw hi", "alice", "User");
+        edited["action"] = "edited".into();
+        assert!(ch.parse_webhook_event("issue_comment", &edited).is_none());
+
+        let mut other_repo = issue_comment("@zeroclaw hi", "alice", "User");
+        other_repo["repository"]["full_name"] = "acme/web".into();
+        assert!(ch
+            .parse_webhook_event("issue_comment", &other_repo)
+            .is_none());
+
+        assert!(ch
+            .parse_webhook_event("push", &issue_comment("@zeroclaw hi", "alice", "User"))
+            .is_none());
+    }
+
+    #[test]
+    fn github_review_comment_replies_in_thread() {
+        let ch = make_channel(None);
+        let payload = serde_json::json!({
+            "action": "created",
+            "pull_request": {"number": 7, "title": "Add cache"},
+            "comment": {
+                "id": 501,
+                "in_reply_to_id": 500,
+                "body": "@zeroclaw is this safe?",
+                "path": "src/cache.rs",
+                "diff_hunk": "@@ -1,2 +1,3 @@\n+let x = 1;",
+                "user": {"login": "alice", "type": "User"}
+            },
+            "repository": {"full_name": "acme/api"}
+        });
+        let msg = ch
+            .parse_webhook_event("pull_request_review_comment", &payload)
+            .unwrap();
+        assert_eq!(msg.reply_target, "acme/api#7");
+        assert_eq!(msg.thread_ts.as_deref(), Some("500"));
+        assert!(msg.content.contains("(review comment on src/cache.rs)"));
+        assert!(msg.content.contains("+let x = 1;"));
+    }
+
+    #[test]
+    fn github_parse_target_rejects_malformed_recipients() {
+        assert_eq!(parse_target("acme/api#12").unwrap(), ("acme/api", 12));
+        assert!(parse_target("acme/api").is_err());
+        assert!(parse_target("acme#12").is_err());
+        assert!(parse_target("acme/api/x#12").is_err());
+        assert!(parse_target("acme/api#twelve").is_err());
+    }
+
+    #[tokio::test]
+    async fn github_send_posts_issue_and_review_replies() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/issues/12/comments"))
+            .and(header("authorization", "Bearer ghs_test"))
+            .and(body_json(serde_json::json!({"body": "Looking into it."})))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/pulls/7/comments/500/replies"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let ch = make_channel(Some(&server.uri()));
+        ch.send(&SendMessage::new("Looking into it.", "acme/api#12"))
+            .await
+            .unwrap();
+        ch.send(&SendMessage::new("Yes.", "acme/api#7").in_thread(Some("500".into())))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn github_drafts_edit_and_delete_the_created_comment() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/issues/12/comments"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": 900})))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/repos/acme/api/issues/comments/900"))
+            .and(body_json(serde_json::json!({"body": "Done."})))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/api/pulls/7/comments/500/replies"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": 901})))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/repos/acme/api/pulls/comments/901"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let ch = make_channel(Some(&server.uri()));
+        let draft = ch
+            .send_draft(&SendMessage::new("...", "acme/api#12"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(draft, "issue:900");
+        ch.finalize_draft("acme/api#12", &draft, "Done.")
+            .await
+            .unwrap();
+
+        let review = ch
+            .send_draft(&SendMessage::new("...", "acme/api#7").in_thread(Some("500".into())))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(review, "review:901");
+        ch.cancel_draft("acme/api#7", &review).await.unwrap();
+        assert!(ch.update_draft("acme/api#7", "bogus", "x").await.is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Group-chat mention gating and read-only context (`[channels_config.groups]`).
+//!
+//! For channels with a `groups` entry, the dispatcher passes every group
+//! message through [`GroupChats::admit`] before queueing it: messages that
+//! neither mention the bot nor reply to it are dropped when `mention_only`
+//! is set. Every group message (answered or not) is kept in a small
+//! per-chat buffer so answered messages can carry the last few messages of
+//! the conversation as context.
+
+use super::traits::ChannelMessage;
+use crate::config::GroupChatConfig;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Sender label for the bot's own replies in the context block.
+const OWN_REPLY_SENDER: &str = "(you)";
+
+struct RecentMessage {
+    id: String,
+    sender: String,
+    content: String,
+}
+
+pub struct GroupChats {
+    configs: HashMap<String, GroupChatConfig>,
+    recent: Mutex<HashMap<String, VecDeque<RecentMessage>>>,
+}
+
+fn chat_key(msg: &ChannelMessage) -> String {
+    format!("{}:{}", msg.channel, msg.reply_target)
+}
+
+/// Whether `content` names the bot by one of `names` (case-insensitive).
+fn mentions_name(content: &str, names: &[String]) -> bool {
+    let content = content.to_lowercase();
+    names
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .any(|name| !name.is_empty() && content.contains(&name))
+}
+
+impl GroupChats {
+    pub fn new(configs: HashMap<String, GroupChatConfig>) -> Self {
+        Self {
+            configs,
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The group settings that apply to `msg`, or `None` for direct messages
+    /// and channels without a `groups` entry.
+    fn config_for(&self, msg: &ChannelMessage) -> Option<&GroupChatConfig> {
+        msg.group.as_ref()?;
+        self.configs.get(&msg.channel)
+    }
+
+    /// Remember `msg` for context and decide whether the agent should answer it.
+    pub fn admit(&self, msg: &ChannelMessage) -> bool {
+        let Some(config) = self.config_for(msg) else {
+            return true;
+        };
+        self.remember(
+            msg,
+            config,
+            RecentMessage {
+                id: msg.id.clone(),
+                sender: msg.sender.clone(),
+                content: msg.content.clone(),
+            },
+        );
+        let addressed = msg
+            .group
+            .as_ref()
+            .is_some_and(|group| group.mentions_bot || group.replies_to_bot);
+        !config.mention_only || addressed || mentions_name(&msg.content, &config.mention_names)
+    }
+
+    /// Record the bot's answer to `msg` so later context includes it.
+    pub fn record_reply(&self, msg: &ChannelMessage, reply: &str) {
+        let Some(config) = self.config_for(msg) else {
+            return;
+        };
+        self.remember(
+            msg,
+            config,
+            RecentMessage {
+                id: String::new(),
+                sender: OWN_REPLY_SENDER.to_string(),
+                content: reply.to_string(),
+            },
+        );
+    }
+
+    fn remember(&self, msg: &ChannelMessage, config: &GroupChatConfig, entry: RecentMessage) {
+        if config.context_messages == 0 {
+            return;
+        }
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let buffer = recent.entry(chat_key(msg)).or_default();
+        buffer.push_back(entry);
+        // Keep the message being answered plus `context_messages` before it.
+        while buffer.len() > config.context_messages + 1 {
+            buffer.pop_front();
+        }
+    }
+
+    /// The group messages preceding `msg` as a read-only context block, or an
+    /// empty string when there are none or context is disabled.
+    pub fn context_for(&self, msg: &ChannelMessage) -> String {
+        let Some(config) = self.config_for(msg) else {
+            return String::new();
+        };
+        if config.context_messages == 0 {
+            return String::new();
+        }
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(buffer) = recent.get(&chat_key(msg)) else {
+            return String::new();
+        };
+        let end = buffer
+            .iter()
+            .position(|entry| entry.id == msg.id)
+            .unwrap_or(buffer.len());
+        let start = end.saturating_sub(config.context_messages);
+        if start == end {
+            return String::new();
+        }
+        let mut context =
+            String::from("[Recent group messages — read-only context, do not answer them]\n");
+        for entry in buffer.range(start..end) {
+            let _ = writeln!(context, "{}: {}", entry.sender, entry.content);
+        }
+        context.push('\n');
+        context
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::GroupInfo;
+
+    fn group_message(id: &str, sender: &str, content: &str, mentions_bot: bool) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: sender.into(),
+            reply_target: "room-1".into(),
+            content: content.into(),
+            channel: "discord".into(),
+            timestamp: 0,
+            thread_ts: None,
+            group: Some(GroupInfo {
+                mentions_bot,
+                replies_to_bot: false,
+            }),
+        }
+    }
+
+    fn group_chats(context_messages: usize) -> GroupChats {
+        GroupChats::new(HashMap::from([(
+            "discord".to_string(),
+            GroupChatConfig {
+                mention_only: true,
+                mention_names: vec!["ZeroClaw".into()],
+                context_messages,
+            },
+        )]))
+    }
+
+    #[test]
+    fn mention_only_gates_group_messages_but_not_dms() {
+        let groups = group_chats(0);
+        assert!(!groups.admit(&group_message("1", "alice", "lunch?", false)));
+        assert!(groups.admit(&group_message("2", "alice", "@bot lunch?", true)));
+        assert!(groups.admit(&group_message("3", "alice", "zeroclaw, lunch?", false)));
+
+        let mut dm = group_message("4", "alice", "lunch?", false);
+        dm.group = None;
+        assert!(groups.admit(&dm));
+        let mut other_channel = group_message("5", "alice", "lunch?", false);
+        other_channel.channel = "slack".into();
+        assert!(groups.admit(&other_channel));
+    }
+
+    #[test]
+    fn context_includes_recent_messages_before_the_answered_one() {
+        let groups = group_chats(2);
+        groups.admit(&group_message("1", "alice", "pizza or tacos?", false));
+        groups.admit(&group_message("2", "bob", "tacos", false));
+        groups.admit(&group_message("3", "carol", "agreed, tacos", false));
+        let question = group_message("4", "alice", "@bot what did we decide?", true);
+        assert!(groups.admit(&question));
+
+        let context = groups.context_for(&question);
+        assert!(!context.contains("pizza"));
+        assert!(context.contains("bob: tacos\n"));
+        assert!(context.contains("carol: agreed, tacos\n"));
+        assert!(!context.contains("what did we decide"));
+
+        groups.record_reply(&question, "You picked tacos.");
+        let follow_up = group_message("5", "bob", "@bot thanks", true);
+        groups.admit(&follow_up);
+        assert!(groups
+            .context_for(&follow_up)
+            .contains("(you): You picked tacos.\n"));
+        assert!(group_chats(0).context_for(&follow_up).is_empty());
+    }
+}
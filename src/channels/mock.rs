@@ -0,0 +1,224 @@
+//! JSONL-file channel for end-to-end tests (`[channels_config.mock]`,
+//! `--features mock`).
+//!
+//! Reads inbound messages from an inbox file and appends every reply to an
+//! outbox file, so a test can drive the full channel pipeline (routing,
+//! autonomy, tools) and assert on the transcript without any network.
+
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::config::{GatewayTlsConfig, MockChannelConfig};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Inbox line.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InboxLine {
+    sender: String,
+    content: String,
+    #[serde(default)]
+    thread: Option<String>,
+}
+
+/// Outbox line.
+#[derive(Debug, Serialize)]
+struct OutboxLine<'a> {
+    recipient: &'a str,
+    content: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread: Option<&'a str>,
+}
+
+pub struct MockChannel {
+    inbox: PathBuf,
+    outbox: PathBuf,
+    poll_interval: Duration,
+    /// Serializes outbox appends so concurrent replies never interleave.
+    write_lock: tokio::sync::Mutex<()>,
+}
+
+impl MockChannel {
+    pub fn new(inbox: PathBuf, outbox: PathBuf, poll_interval: Duration) -> Self {
+        Self {
+            inbox,
+            outbox,
+            poll_interval,
+            write_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn from_config(config: &MockChannelConfig, config_dir: &Path) -> Self {
+        Self::new(
+            GatewayTlsConfig::resolve_path(&config.inbox, config_dir),
+            GatewayTlsConfig::resolve_path(&config.outbox, config_dir),
+            Duration::from_millis(config.poll_interval_ms.max(10)),
+        )
+    }
+
+    /// Parse complete lines of `raw` after byte `offset`. Returns the messages
+    /// and the offset just past the last complete line; a partially written
+    /// last line is left for the next poll.
+    fn read_new_lines(
+        &self,
+        raw: &str,
+        offset: usize,
+        line_number: &mut usize,
+    ) -> (Vec<ChannelMessage>, usize) {
+        let Some(pending) = raw.get(offset..) else {
+            return (Vec::new(), offset);
+        };
+        let Some(complete) = pending.rfind('\n').map(|end| &pending[..=end]) else {
+            return (Vec::new(), offset);
+        };
+        let mut messages = Vec::new();
+        for line in complete.lines() {
+            *line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<InboxLine>(line) {
+                Ok(inbound) => messages.push(ChannelMessage {
+                    id: format!("mock_{line_number}"),
+                    reply_target: inbound.sender.clone(),
+                    sender: inbound.sender,
+                    content: inbound.content,
+                    channel: "mock".into(),
+                    timestamp: std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                    thread_ts: inbound.thread,
+                    group: None,
+                }),
+                Err(e) => tracing::warn!(
+                    "Skipping invalid mock inbox line {line_number} in {}: {e}",
+                    self.inbox.display()
+                ),
+            }
+        }
+        (messages, offset + complete.len())
+    }
+}
+
+#[async_trait]
+impl Channel for MockChannel {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&OutboxLine {
+            recipient: &message.recipient,
+            content: &message.content,
+            thread: message.thread_ts.as_deref(),
+        })?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.outbox.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.outbox)
+            .await
+            .with_context(|| format!("Failed to open mock outbox {}", self.outbox.display()))?;
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        tracing::info!("Mock channel reading {}", self.inbox.display());
+        let mut offset = 0;
+        let mut line_number = 0;
+        loop {
+            match tokio::fs::read_to_string(&self.inbox).await {
+                Ok(raw) => {
+                    let (messages, next_offset) =
+                        self.read_new_lines(&raw, offset, &mut line_number);
+                    offset = next_offset;
+                    for message in messages {
+                        if tx.send(message).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+                // The inbox may be created after the channel starts.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| {
+                        format!("Failed to read mock inbox {}", self.inbox.display())
+                    })
+                }
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn reads_inbox_lines_and_appends_replies() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = MockChannelConfig {
+            inbox: "inbox.jsonl".into(),
+            outbox: "out/outbox.jsonl".into(),
+            poll_interval_ms: 10,
+        };
+        std::fs::write(
+            dir.path().join("inbox.jsonl"),
+            "{\"sender\": \"alice\", \"content\": \"hi\"}\nnot json\n",
+        )
+        .unwrap();
+        let channel = Arc::new(MockChannel::from_config(&config, dir.path()));
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let listener = tokio::spawn({
+            let channel = Arc::clone(&channel);
+            async move { channel.listen(tx).await }
+        });
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.id, "mock_1");
+        assert_eq!(first.sender, "alice");
+        assert_eq!(first.content, "hi");
+
+        // Lines appended while listening are picked up; a partial line waits.
+        let mut inbox = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join("inbox.jsonl"))
+            .unwrap();
+        std::io::Write::write_all(
+            &mut inbox,
+            b"{\"sender\": \"bob\", \"content\": \"yo\", \"thread\": \"t1\"}\n{\"sender\"",
+        )
+        .unwrap();
+        let second = rx.recv().await.unwrap();
+        assert_eq!(second.id, "mock_3");
+        assert_eq!(second.thread_ts.as_deref(), Some("t1"));
+        listener.abort();
+
+        channel
+            .send(&SendMessage::new("hello alice", "alice"))
+            .await
+            .unwrap();
+        channel
+            .send(&SendMessage::new("hey", "bob").in_thread(Some("t1".into())))
+            .await
+            .unwrap();
+        let outbox = std::fs::read_to_string(dir.path().join("out/outbox.jsonl")).unwrap();
+        assert_eq!(
+            outbox,
+            "{\"recipient\":\"alice\",\"content\":\"hello alice\"}\n\
+             {\"recipient\":\"bob\",\"content\":\"hey\",\"thread\":\"t1\"}\n"
+        );
+    }
+}
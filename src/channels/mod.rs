@@ -14,28 +14,47 @@
 //! To add a new channel, implement [`Channel`] in a new submodule and wire it into
 //! [`start_channels`]. See `AGENTS.md` §7.2 for the full change playbook.
 
+pub mod github;
+pub mod groups;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod preferences;
+pub mod queue;
+pub mod setup;
+pub mod supervisor;
 pub mod traits;
 pub mod whatsapp;
 
+pub use github::GitHubChannel;
+pub use groups::GroupChats;
+pub use preferences::{RoutePreference, RoutePreferences};
+use queue::{Enqueued, SessionQueues};
 pub use traits::{Channel, SendMessage};
 pub use whatsapp::WhatsAppChannel;
 
+use crate::agent::dispatcher::ToolDispatchMode;
 use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop, scrub_credentials};
+use crate::agent::prompt::{self, PROMPT_FILES};
+use crate::agent::registry::{self, AgentProfile, AgentRegistry};
 use crate::config::Config;
+use crate::identity::IdentityLinks;
 use crate::memory::{self, Memory};
-use crate::observability::{self, runtime_trace, Observer};
+use crate::observability::{self, correlation, runtime_trace, Observer, ObserverEvent};
+use crate::plugins::{fire_hook, HookAction, HookEventType, PluginManager};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::runtime;
+use crate::security::dm_policy::{ChannelDmPolicyManager, DmDecision};
+use crate::security::grants::PathGrants;
+use crate::security::redaction::redact_outbound;
 use crate::security::SecurityPolicy;
 use crate::tools::{self, Tool};
+use crate::util::i18n::{self, Text};
 use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
-use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant, SystemTime};
 use tokio_util::sync::CancellationToken;
@@ -65,7 +84,6 @@ const CHANNEL_MIN_IN_FLIGHT_MESSAGES: usize = 8;
 const CHANNEL_MAX_IN_FLIGHT_MESSAGES: usize = 64;
 const CHANNEL_TYPING_REFRESH_INTERVAL_SECS: u64 = 4;
 const CHANNEL_HEALTH_HEARTBEAT_SECS: u64 = 30;
-const MODEL_CACHE_FILE: &str = "models_cache.json";
 const MODEL_CACHE_PREVIEW_LIMIT: usize = 10;
 const MEMORY_CONTEXT_MAX_ENTRIES: usize = 4;
 const MEMORY_CONTEXT_ENTRY_MAX_CHARS: usize = 800;
@@ -89,10 +107,11 @@ fn channel_message_timeout_budget_secs(
     message_timeout_secs.saturating_mul(scale)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 struct ChannelRouteSelection {
     provider: String,
     model: String,
+    temperature: f64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -101,17 +120,13 @@ enum ChannelRuntimeCommand {
     SetProvider(String),
     ShowModel,
     SetModel(String),
-}
-
-#[derive(Debug, Clone, Default, Deserialize)]
-struct ModelCacheState {
-    entries: Vec<ModelCacheEntry>,
-}
-
-#[derive(Debug, Clone, Default, Deserialize)]
-struct ModelCacheEntry {
-    provider: String,
-    models: Vec<String>,
+    ShowTemperature,
+    SetTemperature(String),
+    Pin,
+    Unpin,
+    /// `/link` issues a code; `/link <code>` redeems one.
+    Link(Option<String>),
+    Unlink,
 }
 
 #[derive(Debug, Clone)]
@@ -154,6 +169,8 @@ struct ChannelRuntimeContext {
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
     observer: Arc<dyn Observer>,
     system_prompt: Arc<String>,
+    /// System prompts for channels with `prompts/<channel>/` overrides.
+    channel_prompts: Arc<HashMap<String, String>>,
     model: Arc<String>,
     temperature: f64,
     auto_save_memory: bool,
@@ -169,38 +186,56 @@ struct ChannelRuntimeContext {
     message_timeout_secs: u64,
     interrupt_on_new_message: bool,
     non_cli_excluded_tools: Arc<Vec<String>>,
+    hooks: Option<Arc<dyn PluginManager>>,
+    skill_selector: Option<Arc<crate::skills::SkillSelector>>,
+    tool_dispatch: ToolDispatchMode,
+    /// `[[agents]]` registry; `None` when no extra agents are configured.
+    agents: Option<Arc<AgentRegistry>>,
+    /// Image marker limits (`[multimodal]`).
+    multimodal: crate::config::MultimodalConfig,
+    /// Per-channel DM access policy; `None` when no channel configures one.
+    dm_policy: Option<Arc<ChannelDmPolicyManager>>,
+    /// `[[route_preferences]]` and `/pin` overrides; `None` disables both.
+    route_preferences: Option<Arc<RoutePreferences>>,
+    /// Per-session queueing (`[channels_config.queue]`).
+    message_queue: crate::config::MessageQueueConfig,
+    /// Progressive reply editing (`[channels_config.streaming]`).
+    draft_streaming: crate::config::DraftStreamingConfig,
+    /// Group-chat gating and context; `None` when no channel configures it.
+    groups: Option<Arc<GroupChats>>,
+    /// Cross-channel identity links and `/link` codes; `None` when the
+    /// identity database is unavailable.
+    identities: Option<Arc<IdentityLinks>>,
+    /// `[agent] fallback_models`, tried in order on rate-limit and context-length errors.
+    fallback_models: Arc<Vec<String>>,
+    /// Tools built under named autonomy profiles; `None` when no agent or
+    /// channel selects one.
+    autonomy_profiles: Option<Arc<AutonomyProfiles>>,
+    /// `[channels_config.personas]`, by channel name.
+    channel_personas: Arc<HashMap<String, crate::config::ChannelPersonaConfig>>,
 }
 
-#[derive(Clone)]
-struct InFlightSenderTaskState {
-    task_id: u64,
-    cancellation: CancellationToken,
-    completion: Arc<InFlightTaskCompletion>,
+/// Tool registry built under one `[autonomy.profiles.<name>]` policy.
+struct ProfileTools {
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    non_cli_excluded_tools: Arc<Vec<String>>,
 }
 
-struct InFlightTaskCompletion {
-    done: AtomicBool,
-    notify: tokio::sync::Notify,
+/// Tool registries per autonomy profile, and which profile each channel
+/// (`[channels_config.autonomy]`) uses.
+struct AutonomyProfiles {
+    profiles: HashMap<String, ProfileTools>,
+    channels: HashMap<String, String>,
 }
 
-impl InFlightTaskCompletion {
-    fn new() -> Self {
-        Self {
-            done: AtomicBool::new(false),
-            notify: tokio::sync::Notify::new(),
-        }
-    }
-
-    fn mark_done(&self) {
-        self.done.store(true, Ordering::Release);
-        self.notify.notify_waiters();
-    }
-
-    async fn wait(&self) {
-        if self.done.load(Ordering::Acquire) {
-            return;
-        }
-        self.notify.notified().await;
+impl AutonomyProfiles {
+    /// The routed agent's profile, else the channel's; `None` means the
+    /// default `[autonomy]` tools.
+    fn select(&self, channel: &str, agent: Option<&AgentProfile>) -> Option<&ProfileTools> {
+        let name = agent
+            .and_then(|agent| agent.autonomy.as_deref())
+            .or_else(|| self.channels.get(channel).map(String::as_str))?;
+        self.profiles.get(name.trim())
     }
 }
 
@@ -216,6 +251,15 @@ fn interruption_scope_key(msg: &traits::ChannelMessage) -> String {
     format!("{}_{}_{}", msg.channel, msg.reply_target, msg.sender)
 }
 
+/// Owner of per-person state (`/pin`, auto-saved memories): the sender's
+/// linked identity, or the channel account when it has none.
+fn person_key(ctx: &ChannelRuntimeContext, msg: &traits::ChannelMessage) -> String {
+    ctx.identities.as_ref().map_or_else(
+        || crate::identity::links::account_key(&msg.channel, &msg.sender),
+        |identities| identities.person_key(&msg.channel, &msg.sender),
+    )
+}
+
 /// Strip tool-call XML tags from outgoing messages.
 ///
 /// LLM responses may contain `<function_calls>`, `<function_call>`,
@@ -381,8 +425,8 @@ fn normalize_cached_channel_turns(turns: Vec<ChatMessage>) -> Vec<ChatMessage> {
     normalized
 }
 
-fn supports_runtime_model_switch(_channel_name: &str) -> bool {
-    false
+fn supports_runtime_model_switch(channel_name: &str) -> bool {
+    matches!(channel_name, "telegram" | "discord" | "whatsapp")
 }
 
 fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRuntimeCommand> {
@@ -421,6 +465,16 @@ fn parse_runtime_command(channel_name: &str, content: &str) -> Option<ChannelRun
                 Some(ChannelRuntimeCommand::SetModel(model))
             }
         }
+        "/temperature" => match parts.next() {
+            Some(value) => Some(ChannelRuntimeCommand::SetTemperature(value.to_string())),
+            None => Some(ChannelRuntimeCommand::ShowTemperature),
+        },
+        "/pin" => Some(ChannelRuntimeCommand::Pin),
+        "/unpin" => Some(ChannelRuntimeCommand::Unpin),
+        "/link" => Some(ChannelRuntimeCommand::Link(
+            parts.next().map(|code| code.trim().to_string()),
+        )),
+        "/unlink" => Some(ChannelRuntimeCommand::Unlink),
         _ => None,
     }
 }
@@ -560,10 +614,11 @@ async fn maybe_apply_runtime_config_update(ctx: &ChannelRuntimeContext) -> Resul
     }
 
     let next_defaults = load_runtime_defaults_from_config_file(&config_path).await?;
-    let next_default_provider = providers::create_provider_with_url(
+    let next_default_provider = providers::create_resilient_provider_with_options(
         &next_defaults.default_provider,
         next_defaults.api_key.as_deref(),
         next_defaults.api_url.as_deref(),
+        &ctx.provider_runtime_options,
     )?;
     let next_default_provider: Arc<dyn Provider> = Arc::from(next_default_provider);
 
@@ -612,16 +667,70 @@ fn default_route_selection(ctx: &ChannelRuntimeContext) -> ChannelRouteSelection
     ChannelRouteSelection {
         provider: defaults.default_provider,
         model: defaults.model,
+        temperature: defaults.temperature,
+    }
+}
+
+/// Agent that handles `msg` according to `[[agents.routes]]`.
+async fn resolve_channel_agent(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+) -> Option<Arc<AgentProfile>> {
+    let agents = ctx.agents.as_ref()?;
+    let context = registry::route_context(&msg.channel, &msg.sender, &msg.reply_target);
+    match agents.resolve(&context).await {
+        Ok((agent, _)) => Some(agent),
+        Err(err) => {
+            tracing::warn!("Agent routing failed; using default agent: {err}");
+            None
+        }
     }
 }
 
-fn get_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str) -> ChannelRouteSelection {
-    ctx.route_overrides
+/// Route for `msg`: an explicit `/models`, `/model` or `/temperature`
+/// override wins, then the sender's `/pin` and `[[route_preferences]]`, then
+/// the routed agent's provider/model, then the runtime defaults.
+fn get_agent_route_selection(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    agent: Option<&AgentProfile>,
+) -> ChannelRouteSelection {
+    let sender_key = conversation_history_key(msg);
+    if let Some(route) = ctx
+        .route_overrides
         .lock()
         .unwrap_or_else(|e| e.into_inner())
-        .get(sender_key)
-        .cloned()
-        .unwrap_or_else(|| default_route_selection(ctx))
+        .get(&sender_key)
+    {
+        return route.clone();
+    }
+    let mut route = default_route_selection(ctx);
+    if let Some(agent) = agent {
+        if let Some(provider) = &agent.provider {
+            route.provider.clone_from(provider);
+        }
+        if let Some(model) = &agent.model {
+            route.model.clone_from(model);
+        }
+    }
+    if let Some(preferences) = ctx.route_preferences.as_ref() {
+        let preference = preferences.lookup(
+            &person_key(ctx, msg),
+            &msg.channel,
+            &msg.sender,
+            &msg.reply_target,
+        );
+        if let Some(provider) = preference.provider {
+            route.provider = provider;
+        }
+        if let Some(model) = preference.model {
+            route.model = model;
+        }
+        if let Some(temperature) = preference.temperature {
+            route.temperature = temperature;
+        }
+    }
+    route
 }
 
 fn set_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str, next: ChannelRouteSelection) {
@@ -637,11 +746,23 @@ fn set_route_selection(ctx: &ChannelRuntimeContext, sender_key: &str, next: Chan
     }
 }
 
+/// Start the sender over: drop their history and end their tool session
+/// (which stops their background processes) in every registry.
 fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
     ctx.conversation_histories
         .lock()
         .unwrap_or_else(|e| e.into_inner())
         .remove(sender_key);
+    let profile_tools = ctx
+        .autonomy_profiles
+        .iter()
+        .flat_map(|profiles| profiles.profiles.values())
+        .map(|profile| &profile.tools);
+    for tools in std::iter::once(&ctx.tools_registry).chain(profile_tools) {
+        for tool in tools.iter() {
+            tool.end_session(sender_key);
+        }
+    }
 }
 
 fn compact_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) -> bool {
@@ -731,42 +852,14 @@ fn should_skip_memory_context_entry(key: &str, content: &str) -> bool {
 }
 
 fn is_context_window_overflow_error(err: &anyhow::Error) -> bool {
-    let lower = err.to_string().to_lowercase();
-    [
-        "exceeds the context window",
-        "context window of this model",
-        "maximum context length",
-        "context length exceeded",
-        "too many tokens",
-        "token limit exceeded",
-        "prompt is too long",
-        "input is too long",
-    ]
-    .iter()
-    .any(|hint| lower.contains(hint))
+    providers::traits::is_context_length_error(&err.to_string())
 }
 
 fn load_cached_model_preview(workspace_dir: &Path, provider_name: &str) -> Vec<String> {
-    let cache_path = workspace_dir.join("state").join(MODEL_CACHE_FILE);
-    let Ok(raw) = std::fs::read_to_string(cache_path) else {
-        return Vec::new();
-    };
-    let Ok(state) = serde_json::from_str::<ModelCacheState>(&raw) else {
-        return Vec::new();
-    };
-
-    state
-        .entries
+    providers::catalog::cached_model_ids(workspace_dir, provider_name)
         .into_iter()
-        .find(|entry| entry.provider == provider_name)
-        .map(|entry| {
-            entry
-                .models
-                .into_iter()
-                .take(MODEL_CACHE_PREVIEW_LIMIT)
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default()
+        .take(MODEL_CACHE_PREVIEW_LIMIT)
+        .collect()
 }
 
 async fn get_or_create_provider(
@@ -818,14 +911,15 @@ async fn create_resilient_provider_nonblocking(
     provider_name: &str,
     api_key: Option<String>,
     api_url: Option<String>,
-    _provider_runtime_options: providers::ProviderRuntimeOptions,
+    provider_runtime_options: providers::ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let provider_name = provider_name.to_string();
     tokio::task::spawn_blocking(move || {
-        providers::create_provider_with_url(
+        providers::create_resilient_provider_with_options(
             &provider_name,
             api_key.as_deref(),
             api_url.as_deref(),
+            &provider_runtime_options,
         )
     })
     .await
@@ -887,6 +981,194 @@ fn build_providers_help_response(current: &ChannelRouteSelection) -> String {
     response
 }
 
+/// Channel tools for `config`'s autonomy policy. Reminders are delivered
+/// through the running channels, so the reminder tool only exists here.
+fn build_channel_tools(
+    config: &Config,
+    runtime: Arc<dyn runtime::RuntimeAdapter>,
+    mem: &Arc<dyn Memory>,
+    reminders: Option<&Arc<crate::scheduler::ReminderStore>>,
+) -> Vec<Box<dyn Tool>> {
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_path_grants(PathGrants::for_config(config)),
+    );
+    let mut tools_registry = tools::all_tools_with_runtime(
+        Arc::new(config.clone()),
+        &security,
+        runtime,
+        Arc::clone(mem),
+    );
+    if let Some(store) = reminders {
+        tools_registry.push(Box::new(tools::ReminderTool::new(
+            Arc::clone(store),
+            Arc::clone(&security),
+            crate::util::time::TimeService::from_config(config),
+        )));
+    }
+    tools_registry
+}
+
+/// Tool registries for every autonomy profile an agent or channel selects.
+fn build_autonomy_profiles(
+    config: &Config,
+    runtime: &Arc<dyn runtime::RuntimeAdapter>,
+    mem: &Arc<dyn Memory>,
+    reminders: Option<&Arc<crate::scheduler::ReminderStore>>,
+) -> Result<Option<Arc<AutonomyProfiles>>> {
+    let mut names: Vec<&str> = config
+        .agents
+        .iter()
+        .filter_map(|agent| agent.autonomy.as_deref())
+        .chain(config.channels_config.autonomy.values().map(String::as_str))
+        .map(str::trim)
+        .collect();
+    if names.is_empty() {
+        return Ok(None);
+    }
+    names.sort_unstable();
+    names.dedup();
+
+    let mut profiles = HashMap::new();
+    for name in names {
+        let mut profile_config = config.clone();
+        profile_config.autonomy = config.autonomy.resolve(name)?;
+        let tools = build_channel_tools(&profile_config, Arc::clone(runtime), mem, reminders);
+        profiles.insert(
+            name.to_string(),
+            ProfileTools {
+                tools: Arc::new(tools),
+                non_cli_excluded_tools: Arc::new(
+                    profile_config.autonomy.non_cli_excluded_tools.clone(),
+                ),
+            },
+        );
+    }
+    Ok(Some(Arc::new(AutonomyProfiles {
+        profiles,
+        channels: config.channels_config.autonomy.clone(),
+    })))
+}
+
+fn build_route_preferences(config: &Config) -> Option<Arc<RoutePreferences>> {
+    let rules = config.route_preferences.clone();
+    let db_path = preferences::preferences_db_path(&config.workspace_dir);
+    let preferences = RoutePreferences::open(rules.clone(), &db_path).unwrap_or_else(|error| {
+        tracing::warn!("Route preference storage unavailable, /pin is disabled: {error:#}");
+        RoutePreferences::from_rules(rules)
+    });
+    Some(Arc::new(preferences))
+}
+
+pub(crate) fn build_dm_policy_manager(config: &Config) -> Option<Arc<ChannelDmPolicyManager>> {
+    if config.channels_config.dm_policies.is_empty() {
+        return None;
+    }
+    let zeroclaw_dir = config
+        .config_path
+        .parent()
+        .map_or_else(|| config.workspace_dir.clone(), PathBuf::from);
+    let audit = crate::security::audit::AuditLogger::new(&config.security.audit, &zeroclaw_dir)
+        .unwrap_or_else(|error| {
+            tracing::warn!(
+                "Audit log unavailable, DM policy decisions won't be recorded: {error:#}"
+            );
+            crate::security::audit::AuditLogger::disabled()
+        });
+    let manager =
+        ChannelDmPolicyManager::new(config.channels_config.dm_policies.clone(), Arc::new(audit));
+    // Pairings live next to config.toml: a file in the workspace could be
+    // forged by the agent through file_write or shell.
+    Some(Arc::new(match config.config_path.parent() {
+        Some(config_dir) => manager.with_config_dir(config_dir),
+        None => manager,
+    }))
+}
+
+/// Apply the channel's DM policy. Returns `false` when the message must not
+/// reach the agent (any pairing reply has already been sent).
+async fn admit_dm_sender(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    target_channel: Option<&Arc<dyn Channel>>,
+) -> bool {
+    let Some(policy) = ctx.dm_policy.as_ref() else {
+        return true;
+    };
+    admit_dm_sender_with(policy, msg, target_channel).await
+}
+
+/// [`admit_dm_sender`] for callers outside the channel dispatcher, such as
+/// the gateway's webhook channels.
+pub(crate) async fn admit_dm_sender_with(
+    policy: &ChannelDmPolicyManager,
+    msg: &traits::ChannelMessage,
+    target_channel: Option<&Arc<dyn Channel>>,
+) -> bool {
+    match policy.evaluate(&msg.channel, &msg.sender, &msg.content) {
+        DmDecision::Allow => true,
+        DmDecision::Deny => {
+            tracing::info!(channel = %msg.channel, sender = %msg.sender, "DM denied by policy");
+            false
+        }
+        DmDecision::RateLimited { retry_after_secs } => {
+            tracing::info!(
+                channel = %msg.channel,
+                sender = %msg.sender,
+                retry_after_secs,
+                "DM rate limited by policy"
+            );
+            false
+        }
+        DmDecision::Reply(reply) => {
+            if let Some(channel) = target_channel {
+                if let Err(err) = channel
+                    .send(
+                        &SendMessage::new(reply, &msg.reply_target)
+                            .in_thread(msg.thread_ts.clone()),
+                    )
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to send DM policy reply on {}: {err}",
+                        channel.name()
+                    );
+                }
+            }
+            false
+        }
+    }
+}
+
+fn link_command_response(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    code: Option<&str>,
+) -> String {
+    let Some(identities) = ctx.identities.as_ref() else {
+        return "Identity linking is not available on this instance.".to_string();
+    };
+    match code {
+        // A code posted in a group could be redeemed by anyone there.
+        None if msg.group.is_some() => {
+            "Send `/link` in a direct message to get a link code.".to_string()
+        }
+        None => {
+            let code = identities.issue_code(&msg.channel, &msg.sender);
+            format!(
+                "Your link code is `{code}`. Send `/link {code}` to me from your other account within {} minutes to share preferences and memory between them.",
+                crate::identity::links::LINK_CODE_TTL.as_secs() / 60
+            )
+        }
+        Some(code) => match identities.redeem_code(code, &msg.channel, &msg.sender) {
+            Ok(identity) => format!(
+                "Linked. This account now shares preferences and memory with identity `{identity}`."
+            ),
+            Err(err) => err.to_string(),
+        },
+    }
+}
+
 async fn handle_runtime_command_if_needed(
     ctx: &ChannelRuntimeContext,
     msg: &traits::ChannelMessage,
@@ -901,7 +1183,8 @@ async fn handle_runtime_command_if_needed(
     };
 
     let sender_key = conversation_history_key(msg);
-    let mut current = get_route_selection(ctx, &sender_key);
+    let agent = resolve_channel_agent(ctx, msg).await;
+    let mut current = get_agent_route_selection(ctx, msg, agent.as_deref());
 
     let response = match command {
         ChannelRuntimeCommand::ShowProviders => build_providers_help_response(&current),
@@ -950,6 +1233,67 @@ async fn handle_runtime_command_if_needed(
                 )
             }
         }
+        ChannelRuntimeCommand::ShowTemperature => format!(
+            "Current temperature: `{}`.\nSet it with `/temperature <0.0-2.0>`.",
+            current.temperature
+        ),
+        ChannelRuntimeCommand::SetTemperature(raw) => match raw.parse::<f64>() {
+            Ok(temperature) if (0.0..=2.0).contains(&temperature) => {
+                current.temperature = temperature;
+                set_route_selection(ctx, &sender_key, current.clone());
+                format!("Temperature set to `{temperature}` in this sender session.")
+            }
+            _ => format!("Invalid temperature `{raw}`. Use a value between 0.0 and 2.0."),
+        },
+        ChannelRuntimeCommand::Pin => match ctx.route_preferences.as_ref() {
+            Some(preferences) => {
+                let preference = RoutePreference {
+                    provider: Some(current.provider.clone()),
+                    model: Some(current.model.clone()),
+                    temperature: Some(current.temperature),
+                };
+                match preferences.pin(&person_key(ctx, msg), &preference) {
+                    Ok(()) => format!(
+                        "Pinned provider `{}`, model `{}`, temperature `{}` for you. They persist across restarts; `/unpin` clears them.",
+                        current.provider, current.model, current.temperature
+                    ),
+                    Err(err) => {
+                        tracing::warn!("Failed to pin route preference: {err:#}");
+                        "Failed to save preferences. Route unchanged.".to_string()
+                    }
+                }
+            }
+            None => "Saved preferences are not available on this instance.".to_string(),
+        },
+        ChannelRuntimeCommand::Unpin => {
+            let removed = ctx
+                .route_preferences
+                .as_ref()
+                .map(|preferences| preferences.unpin(&person_key(ctx, msg)));
+            match removed {
+                Some(Ok(true)) => {
+                    set_route_selection(ctx, &sender_key, default_route_selection(ctx));
+                    "Pinned preferences cleared.".to_string()
+                }
+                Some(Ok(false)) | None => "No pinned preferences to clear.".to_string(),
+                Some(Err(err)) => {
+                    tracing::warn!("Failed to clear route preference: {err:#}");
+                    "Failed to clear preferences.".to_string()
+                }
+            }
+        }
+        ChannelRuntimeCommand::Link(code) => link_command_response(ctx, msg, code.as_deref()),
+        ChannelRuntimeCommand::Unlink => match ctx.identities.as_ref() {
+            Some(identities) => match identities.unlink(&msg.channel, &msg.sender) {
+                Ok(true) => "This account is no longer linked to your other accounts.".to_string(),
+                Ok(false) => "This account is not linked to an identity.".to_string(),
+                Err(err) => {
+                    tracing::warn!("Failed to unlink identity: {err:#}");
+                    "Failed to unlink this account.".to_string()
+                }
+            },
+            None => "Identity linking is not available on this instance.".to_string(),
+        },
     };
 
     if let Err(err) = channel
@@ -965,6 +1309,30 @@ async fn handle_runtime_command_if_needed(
     true
 }
 
+/// Send a final reply, split to the channel's message limit. Every chunk
+/// stays in the message's thread; only the first is threaded inline onto
+/// the message being answered.
+async fn send_reply(
+    channel: &dyn Channel,
+    msg: &traits::ChannelMessage,
+    content: &str,
+) -> Result<()> {
+    let chunks = match crate::auto_reply::reply::channel_message_limit(channel.name()) {
+        Some(limit) => crate::auto_reply::reply::split_reply(content, limit),
+        None => vec![content.to_string()],
+    };
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let message = SendMessage::new(chunk, &msg.reply_target).in_thread(msg.thread_ts.clone());
+        let message = if index == 0 {
+            message.quoting(Some(msg.id.clone()))
+        } else {
+            message
+        };
+        channel.send(&message).await?;
+    }
+    Ok(())
+}
+
 async fn build_memory_context(
     mem: &dyn Memory,
     user_msg: &str,
@@ -1116,7 +1484,10 @@ fn sanitize_channel_response(response: &str, tools: &[Box<dyn Tool>]) -> String
         .iter()
         .map(|tool| tool.name().to_ascii_lowercase())
         .collect();
-    strip_isolated_tool_json_artifacts(response, &known_tool_names)
+    redact_outbound(&strip_isolated_tool_json_artifacts(
+        response,
+        &known_tool_names,
+    ))
 }
 
 fn is_tool_call_payload(value: &serde_json::Value, known_tool_names: &HashSet<String>) -> bool {
@@ -1311,15 +1682,28 @@ fn spawn_supervised_listener_with_health_interval(
             let mut health = tokio::time::interval(health_interval);
             health.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
             let result = {
-                let listen_future = ch.listen(tx.clone());
-                tokio::pin!(listen_future);
+                // Each attempt runs as its own task so a panicking listener is
+                // reported and restarted like any other failure. Dropping the
+                // set (when this supervisor is aborted) aborts the attempt.
+                let mut attempt = tokio::task::JoinSet::new();
+                let listener = Arc::clone(&ch);
+                let listener_tx = tx.clone();
+                attempt.spawn(async move { listener.listen(listener_tx).await });
 
                 loop {
                     tokio::select! {
                         _ = health.tick() => {
                             crate::health::mark_component_ok(&component);
                         }
-                        result = &mut listen_future => break result,
+                        joined = attempt.join_next() => break match joined {
+                            Some(Ok(result)) => result,
+                            Some(Err(e)) if e.is_panic() => Err(anyhow::anyhow!(
+                                "listener panicked: {}",
+                                panic_message(e.into_panic().as_ref())
+                            )),
+                            Some(Err(e)) => Err(anyhow::anyhow!("listener task failed: {e}")),
+                            None => Ok(()),
+                        },
                     }
                 }
             };
@@ -1349,6 +1733,14 @@ fn spawn_supervised_listener_with_health_interval(
     })
 }
 
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
 fn compute_max_in_flight_messages(channel_count: usize) -> usize {
     channel_count
         .saturating_mul(CHANNEL_PARALLELISM_PER_CHANNEL)
@@ -1394,9 +1786,54 @@ fn spawn_scoped_typing_task(
     handle
 }
 
+/// Relay the agent's partial output into the draft message. Text arriving
+/// between edits is coalesced so the draft is edited at most once per
+/// `interval`; whatever is still pending when the turn ends is superseded by
+/// `finalize_draft`.
+async fn run_draft_updater(
+    channel: Arc<dyn Channel>,
+    reply_target: String,
+    draft_id: String,
+    mut rx: tokio::sync::mpsc::Receiver<String>,
+    interval: Duration,
+) {
+    let mut accumulated = String::new();
+    let mut dirty = false;
+    let mut next_edit = tokio::time::Instant::now();
+    loop {
+        let delta = if dirty {
+            tokio::select! {
+                delta = rx.recv() => delta,
+                () = tokio::time::sleep_until(next_edit) => {
+                    dirty = false;
+                    next_edit = tokio::time::Instant::now() + interval;
+                    if let Err(e) = channel
+                        .update_draft(&reply_target, &draft_id, &redact_outbound(&accumulated))
+                        .await
+                    {
+                        tracing::debug!("Draft update failed: {e}");
+                    }
+                    continue;
+                }
+            }
+        } else {
+            rx.recv().await
+        };
+        let Some(delta) = delta else {
+            break;
+        };
+        if delta == crate::agent::loop_::DRAFT_CLEAR_SENTINEL {
+            accumulated.clear();
+        } else {
+            accumulated.push_str(&delta);
+        }
+        dirty = !accumulated.trim().is_empty();
+    }
+}
+
 async fn process_channel_message(
     ctx: Arc<ChannelRuntimeContext>,
-    msg: traits::ChannelMessage,
+    mut msg: traits::ChannelMessage,
     cancellation_token: CancellationToken,
 ) {
     if cancellation_token.is_cancelled() {
@@ -1424,8 +1861,44 @@ async fn process_channel_message(
             "content_preview": truncate_with_ellipsis(&msg.content, 160),
         }),
     );
+    ctx.observer.record_event(&ObserverEvent::ChannelMessage {
+        channel: msg.channel.clone(),
+        direction: "inbound".into(),
+    });
+
+    match fire_hook(
+        ctx.hooks.as_deref(),
+        HookEventType::MessageInbound,
+        serde_json::json!({
+            "channel": msg.channel,
+            "sender": msg.sender,
+            "message_id": msg.id,
+            "reply_target": msg.reply_target,
+            "content": msg.content,
+        }),
+    )
+    .await
+    {
+        HookAction::Cancel { reason } => {
+            tracing::info!(
+                channel = %msg.channel,
+                sender = %msg.sender,
+                "Inbound message dropped by plugin hook: {reason}"
+            );
+            return;
+        }
+        HookAction::Modify(data) => {
+            if let Some(content) = data.get("content").and_then(|v| v.as_str()) {
+                msg.content = content.to_string();
+            }
+        }
+        HookAction::Continue => {}
+    }
 
     let target_channel = ctx.channels_by_name.get(&msg.channel).cloned();
+    if !admit_dm_sender(ctx.as_ref(), &msg, target_channel.as_ref()).await {
+        return;
+    }
     if let Err(err) = maybe_apply_runtime_config_update(ctx.as_ref()).await {
         tracing::warn!("Failed to apply runtime config update: {err}");
     }
@@ -1434,15 +1907,19 @@ async fn process_channel_message(
     }
 
     let history_key = conversation_history_key(&msg);
-    let route = get_route_selection(ctx.as_ref(), &history_key);
-    let runtime_defaults = runtime_defaults_snapshot(ctx.as_ref());
+    let agent = resolve_channel_agent(ctx.as_ref(), &msg).await;
+    let route = get_agent_route_selection(ctx.as_ref(), &msg, agent.as_deref());
+    let memory = agent.as_ref().map_or_else(
+        || ctx.memory.clone(),
+        |a| a.scope_memory(ctx.memory.clone()),
+    );
     let active_provider = match get_or_create_provider(ctx.as_ref(), &route.provider).await {
         Ok(provider) => provider,
         Err(err) => {
             let safe_err = providers::sanitize_api_error(&err.to_string());
-            let message = format!(
-                "⚠️ Failed to initialize provider `{}`. Please run `/models` to choose another provider.\nDetails: {safe_err}",
-                route.provider
+            let message = i18n::format(
+                Text::ProviderUnavailable,
+                &[("provider", &route.provider), ("details", &safe_err)],
             );
             if let Some(channel) = target_channel.as_ref() {
                 let _ = channel
@@ -1457,13 +1934,13 @@ async fn process_channel_message(
     };
     if ctx.auto_save_memory && msg.content.chars().count() >= AUTOSAVE_MIN_MESSAGE_CHARS {
         let autosave_key = conversation_memory_key(&msg);
-        let _ = ctx
-            .memory
+        let person = person_key(ctx.as_ref(), &msg);
+        let _ = memory
             .store(
                 &autosave_key,
                 &msg.content,
                 crate::memory::MemoryCategory::Conversation,
-                None,
+                Some(&person),
             )
             .await;
     }
@@ -1495,7 +1972,7 @@ async fn process_channel_message(
     // history. Follow-up turns already include context from previous messages.
     if !had_prior_history {
         let memory_context =
-            build_memory_context(ctx.memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
+            build_memory_context(memory.as_ref(), &msg.content, ctx.min_relevance_score).await;
         if let Some(last_turn) = prior_turns.last_mut() {
             if last_turn.role == "user" && !memory_context.is_empty() {
                 last_turn.content = format!("{memory_context}{}", msg.content);
@@ -1503,12 +1980,48 @@ async fn process_channel_message(
         }
     }
 
-    let system_prompt = build_channel_system_prompt(ctx.system_prompt.as_str(), &msg.channel);
+    if let Some(groups) = ctx.groups.as_deref() {
+        let group_context = groups.context_for(&msg);
+        if let Some(last_turn) = prior_turns.last_mut() {
+            if last_turn.role == "user" && !group_context.is_empty() {
+                last_turn.content = format!("{group_context}{}", last_turn.content);
+            }
+        }
+    }
+
+    let base_prompt = ctx
+        .channel_prompts
+        .get(&msg.channel)
+        .map_or(ctx.system_prompt.as_str(), String::as_str);
+    let mut system_prompt = build_channel_system_prompt(base_prompt, &msg.channel);
+    if let Some(persona) = ctx.channel_personas.get(&msg.channel) {
+        system_prompt.push_str(&prompt::channel_persona_section(
+            persona,
+            &msg.channel,
+            &msg.sender,
+        ));
+    }
+    if let Some(selector) = ctx.skill_selector.as_deref() {
+        let skills = crate::skills::render_selected_skills_prompt(
+            &ctx.workspace_dir,
+            selector,
+            &msg.content,
+        )
+        .await;
+        if !skills.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(skills.trim_end());
+        }
+    }
+    if let Some(agent) = agent.as_deref() {
+        agent.apply_system_prompt(&mut system_prompt);
+    }
     let mut history = vec![ChatMessage::system(system_prompt)];
     history.extend(prior_turns);
-    let use_streaming = target_channel
-        .as_ref()
-        .is_some_and(|ch| ch.supports_draft_updates());
+    let use_streaming = ctx.draft_streaming.enabled
+        && target_channel
+            .as_ref()
+            .is_some_and(|ch| ch.supports_draft_updates());
 
     tracing::debug!(
         channel = %msg.channel,
@@ -1546,30 +2059,18 @@ async fn process_channel_message(
         None
     };
 
-    let draft_updater = if let (Some(mut rx), Some(draft_id_ref), Some(channel_ref)) = (
+    let draft_updater = if let (Some(rx), Some(draft_id_ref), Some(channel_ref)) = (
         delta_rx,
         draft_message_id.as_deref(),
         target_channel.as_ref(),
     ) {
-        let channel = Arc::clone(channel_ref);
-        let reply_target = msg.reply_target.clone();
-        let draft_id = draft_id_ref.to_string();
-        Some(tokio::spawn(async move {
-            let mut accumulated = String::new();
-            while let Some(delta) = rx.recv().await {
-                if delta == crate::agent::loop_::DRAFT_CLEAR_SENTINEL {
-                    accumulated.clear();
-                    continue;
-                }
-                accumulated.push_str(&delta);
-                if let Err(e) = channel
-                    .update_draft(&reply_target, &draft_id, &accumulated)
-                    .await
-                {
-                    tracing::debug!("Draft update failed: {e}");
-                }
-            }
-        }))
+        Some(tokio::spawn(run_draft_updater(
+            Arc::clone(channel_ref),
+            msg.reply_target.clone(),
+            draft_id_ref.to_string(),
+            rx,
+            Duration::from_millis(ctx.draft_streaming.update_interval_ms),
+        )))
     } else {
         None
     };
@@ -1597,6 +2098,23 @@ async fn process_channel_message(
     // Record history length before tool loop so we can extract tool context after.
     let history_len_before_tools = history.len();
 
+    let profile_tools = ctx
+        .autonomy_profiles
+        .as_deref()
+        .and_then(|profiles| profiles.select(&msg.channel, agent.as_deref()));
+    let (tools_registry, non_cli_excluded_tools) = profile_tools.map_or(
+        (&ctx.tools_registry, &ctx.non_cli_excluded_tools),
+        |profile| (&profile.tools, &profile.non_cli_excluded_tools),
+    );
+    let mut excluded_tools: Vec<String> = if msg.channel == "cli" {
+        Vec::new()
+    } else {
+        non_cli_excluded_tools.as_ref().clone()
+    };
+    if let Some(agent) = agent.as_deref() {
+        excluded_tools.extend(agent.disallowed_tools(tools_registry.as_ref()));
+    }
+
     enum LlmExecutionResult {
         Completed(Result<Result<String, anyhow::Error>, tokio::time::error::Elapsed>),
         Cancelled,
@@ -1604,29 +2122,37 @@ async fn process_channel_message(
 
     let timeout_budget_secs =
         channel_message_timeout_budget_secs(ctx.message_timeout_secs, ctx.max_tool_iterations);
+    let reminder_origin = crate::scheduler::ReminderTarget {
+        channel: msg.channel.clone(),
+        recipient: msg.reply_target.clone(),
+        thread_ts: msg.thread_ts.clone(),
+    };
     let llm_result = tokio::select! {
         () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
         result = tokio::time::timeout(
             Duration::from_secs(timeout_budget_secs),
-            run_tool_call_loop(
-                active_provider.as_ref(),
-                &mut history,
-                ctx.tools_registry.as_ref(),
-                ctx.observer.as_ref(),
-                route.provider.as_str(),
-                route.model.as_str(),
-                runtime_defaults.temperature,
-                true,
-                msg.channel.as_str(),
-                ctx.max_tool_iterations,
-                Some(cancellation_token.clone()),
-                delta_tx,
-                if msg.channel == "cli" {
-                    &[]
-                } else {
-                    ctx.non_cli_excluded_tools.as_ref()
-                },
-            ),
+            crate::security::cwd::with_session(history_key.clone(), tools::reminder::with_origin(
+                reminder_origin,
+                run_tool_call_loop(
+                    active_provider.as_ref(),
+                    &mut history,
+                    tools_registry.as_ref(),
+                    ctx.observer.as_ref(),
+                    route.provider.as_str(),
+                    route.model.as_str(),
+                    ctx.fallback_models.as_slice(),
+                    route.temperature,
+                    true,
+                    msg.channel.as_str(),
+                    ctx.max_tool_iterations,
+                    ctx.tool_dispatch,
+                    Some(cancellation_token.clone()),
+                    delta_tx,
+                    &excluded_tools,
+                    ctx.hooks.as_deref(),
+                    &ctx.multimodal,
+                ),
+            )),
         ) => LlmExecutionResult::Completed(result),
     };
 
@@ -1675,10 +2201,11 @@ async fn process_channel_message(
             }
         }
         LlmExecutionResult::Completed(Ok(Ok(response))) => {
+            ctx.observer.record_event(&ObserverEvent::TurnComplete);
             let outbound_response = response;
 
             let sanitized_response =
-                sanitize_channel_response(&outbound_response, ctx.tools_registry.as_ref());
+                sanitize_channel_response(&outbound_response, tools_registry.as_ref());
             let delivered_response = if sanitized_response.is_empty()
                 && !outbound_response.trim().is_empty()
             {
@@ -1686,65 +2213,96 @@ async fn process_channel_message(
             } else {
                 sanitized_response
             };
-            runtime_trace::record_event(
-                "channel_message_outbound",
-                Some(msg.channel.as_str()),
-                Some(route.provider.as_str()),
-                Some(route.model.as_str()),
-                None,
-                Some(true),
-                None,
+            let delivered_response = match fire_hook(
+                ctx.hooks.as_deref(),
+                HookEventType::MessageOutbound,
                 serde_json::json!({
+                    "channel": msg.channel,
                     "sender": msg.sender,
-                    "elapsed_ms": started_at.elapsed().as_millis(),
-                    "response": scrub_credentials(&delivered_response),
+                    "reply_target": msg.reply_target,
+                    "content": delivered_response,
                 }),
-            );
-
-            // Extract condensed tool-use context from the history messages
-            // added during run_tool_call_loop, so the LLM retains awareness
-            // of what it did on subsequent turns.
-            let tool_summary = extract_tool_context_summary(&history, history_len_before_tools);
-            let history_response = if tool_summary.is_empty() || msg.channel == "telegram" {
-                delivered_response.clone()
-            } else {
-                format!("{tool_summary}\n{delivered_response}")
-            };
-
-            append_sender_turn(
-                ctx.as_ref(),
-                &history_key,
-                ChatMessage::assistant(&history_response),
-            );
-            println!(
-                "  🤖 Reply ({}ms): {}",
-                started_at.elapsed().as_millis(),
-                truncate_with_ellipsis(&delivered_response, 80)
-            );
-            if let Some(channel) = target_channel.as_ref() {
-                if let Some(ref draft_id) = draft_message_id {
-                    if let Err(e) = channel
-                        .finalize_draft(&msg.reply_target, draft_id, &delivered_response)
-                        .await
+            )
+            .await
+            {
+                HookAction::Cancel { reason } => {
+                    tracing::info!(
+                        channel = %msg.channel,
+                        sender = %msg.sender,
+                        "Reply suppressed by plugin hook: {reason}"
+                    );
+                    if let (Some(channel), Some(draft_id)) =
+                        (target_channel.as_ref(), draft_message_id.as_deref())
                     {
-                        tracing::warn!("Failed to finalize draft: {e}; sending as new message");
-                        let _ = channel
-                            .send(
-                                &SendMessage::new(&delivered_response, &msg.reply_target)
-                                    .in_thread(msg.thread_ts.clone()),
-                            )
-                            .await;
+                        if let Err(err) = channel.cancel_draft(&msg.reply_target, draft_id).await {
+                            tracing::debug!("Failed to cancel draft on {}: {err}", channel.name());
+                        }
                     }
-                } else if let Err(e) = channel
-                    .send(
-                        &SendMessage::new(delivered_response, &msg.reply_target)
-                            .in_thread(msg.thread_ts.clone()),
-                    )
-                    .await
-                {
-                    eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+                    None
                 }
-            }
+                HookAction::Modify(data) => Some(
+                    data.get("content")
+                        .and_then(|v| v.as_str())
+                        .map_or(delivered_response, ToString::to_string),
+                ),
+                HookAction::Continue => Some(delivered_response),
+            };
+
+            if let Some(delivered_response) = delivered_response {
+                runtime_trace::record_event(
+                    "channel_message_outbound",
+                    Some(msg.channel.as_str()),
+                    Some(route.provider.as_str()),
+                    Some(route.model.as_str()),
+                    None,
+                    Some(true),
+                    None,
+                    serde_json::json!({
+                        "sender": msg.sender,
+                        "elapsed_ms": started_at.elapsed().as_millis(),
+                        "response": scrub_credentials(&delivered_response),
+                    }),
+                );
+
+                // Extract condensed tool-use context from the history messages
+                // added during run_tool_call_loop, so the LLM retains awareness
+                // of what it did on subsequent turns.
+                let tool_summary = extract_tool_context_summary(&history, history_len_before_tools);
+                let history_response = if tool_summary.is_empty() || msg.channel == "telegram" {
+                    delivered_response.clone()
+                } else {
+                    format!("{tool_summary}\n{delivered_response}")
+                };
+
+                append_sender_turn(
+                    ctx.as_ref(),
+                    &history_key,
+                    ChatMessage::assistant(&history_response),
+                );
+                if let Some(groups) = ctx.groups.as_deref() {
+                    groups.record_reply(&msg, &delivered_response);
+                }
+                println!(
+                    "  🤖 Reply ({}ms): {}",
+                    started_at.elapsed().as_millis(),
+                    truncate_with_ellipsis(&delivered_response, 80)
+                );
+                if let Some(channel) = target_channel.as_ref() {
+                    if let Some(ref draft_id) = draft_message_id {
+                        if let Err(e) = channel
+                            .finalize_draft(&msg.reply_target, draft_id, &delivered_response)
+                            .await
+                        {
+                            tracing::warn!("Failed to finalize draft: {e}; sending as new message");
+                            let _ = send_reply(channel.as_ref(), &msg, &delivered_response).await;
+                        }
+                    } else if let Err(e) =
+                        send_reply(channel.as_ref(), &msg, &delivered_response).await
+                    {
+                        eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+                    }
+                }
+            }
         }
         LlmExecutionResult::Completed(Ok(Err(e))) => {
             if crate::agent::loop_::is_tool_loop_cancelled(&e) || cancellation_token.is_cancelled()
@@ -1776,11 +2334,11 @@ async fn process_channel_message(
                 }
             } else if is_context_window_overflow_error(&e) {
                 let compacted = compact_sender_history(ctx.as_ref(), &history_key);
-                let error_text = if compacted {
-                    "⚠️ Context window exceeded for this conversation. I compacted recent history and kept the latest context. Please resend your last message."
+                let error_text = i18n::text(if compacted {
+                    Text::ContextExceededCompacted
                 } else {
-                    "⚠️ Context window exceeded for this conversation. Please resend your last message."
-                };
+                    Text::ContextExceeded
+                });
                 eprintln!(
                     "  ⚠️ Context window exceeded after {}ms; sender history compacted={}",
                     started_at.elapsed().as_millis(),
@@ -1833,6 +2391,10 @@ async fn process_channel_message(
                         "elapsed_ms": started_at.elapsed().as_millis(),
                     }),
                 );
+                ctx.observer.record_event(&ObserverEvent::Error {
+                    component: "channel".into(),
+                    message: safe_error.clone(),
+                });
                 let should_rollback_user_turn = e
                     .downcast_ref::<providers::ProviderCapabilityError>()
                     .is_some_and(|capability| capability.capability.eq_ignore_ascii_case("vision"));
@@ -1849,14 +2411,18 @@ async fn process_channel_message(
                     );
                 }
                 if let Some(channel) = target_channel.as_ref() {
+                    let error_text = redact_outbound(&i18n::format(
+                        Text::ErrorReply,
+                        &[("error", &e.to_string())],
+                    ));
                     if let Some(ref draft_id) = draft_message_id {
                         let _ = channel
-                            .finalize_draft(&msg.reply_target, draft_id, &format!("⚠️ Error: {e}"))
+                            .finalize_draft(&msg.reply_target, draft_id, &error_text)
                             .await;
                     } else {
                         let _ = channel
                             .send(
-                                &SendMessage::new(format!("⚠️ Error: {e}"), &msg.reply_target)
+                                &SendMessage::new(error_text, &msg.reply_target)
                                     .in_thread(msg.thread_ts.clone()),
                             )
                             .await;
@@ -1887,6 +2453,10 @@ async fn process_channel_message(
                 timeout_msg,
                 started_at.elapsed().as_millis()
             );
+            ctx.observer.record_event(&ObserverEvent::Error {
+                component: "channel".into(),
+                message: timeout_msg,
+            });
             // Close the orphan user turn so subsequent messages don't
             // inherit this timed-out request as unfinished context.
             append_sender_turn(
@@ -1895,8 +2465,7 @@ async fn process_channel_message(
                 ChatMessage::assistant("[Task timed out — not continuing this request]"),
             );
             if let Some(channel) = target_channel.as_ref() {
-                let error_text =
-                    "⚠️ Request timed out while waiting for the model. Please try again.";
+                let error_text = i18n::text(Text::Timeout);
                 if let Some(ref draft_id) = draft_message_id {
                     let _ = channel
                         .finalize_draft(&msg.reply_target, draft_id, error_text)
@@ -1924,74 +2493,106 @@ async fn process_channel_message(
     }
 }
 
+fn record_queue_depth(observer: &dyn Observer, queues: &SessionQueues) {
+    observer.record_metric(&observability::traits::ObserverMetric::QueueDepth(
+        queues.depth() as u64,
+    ));
+}
+
 async fn run_message_dispatch_loop(
     mut rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
     ctx: Arc<ChannelRuntimeContext>,
     max_in_flight_messages: usize,
+    shutdown: &crate::infra::shutdown::Shutdown,
 ) {
     let semaphore = Arc::new(tokio::sync::Semaphore::new(max_in_flight_messages));
     let mut workers = tokio::task::JoinSet::new();
-    let in_flight_by_sender = Arc::new(tokio::sync::Mutex::new(HashMap::<
-        String,
-        InFlightSenderTaskState,
-    >::new()));
-    let task_sequence = Arc::new(AtomicU64::new(1));
-
-    while let Some(msg) = rx.recv().await {
-        let permit = match Arc::clone(&semaphore).acquire_owned().await {
-            Ok(permit) => permit,
-            Err(_) => break,
+    let queues = Arc::new(SessionQueues::new(ctx.message_queue.clone()));
+
+    loop {
+        let msg = tokio::select! {
+            biased;
+            () = shutdown.cancelled() => break,
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
         };
 
-        let worker_ctx = Arc::clone(&ctx);
-        let in_flight = Arc::clone(&in_flight_by_sender);
-        let task_sequence = Arc::clone(&task_sequence);
-        workers.spawn(async move {
-            let _permit = permit;
-            let interrupt_enabled =
-                worker_ctx.interrupt_on_new_message && msg.channel == "telegram";
-            let sender_scope_key = interruption_scope_key(&msg);
-            let cancellation_token = CancellationToken::new();
-            let completion = Arc::new(InFlightTaskCompletion::new());
-            let task_id = task_sequence.fetch_add(1, Ordering::Relaxed);
-
-            if interrupt_enabled {
-                let previous = {
-                    let mut active = in_flight.lock().await;
-                    active.insert(
-                        sender_scope_key.clone(),
-                        InFlightSenderTaskState {
-                            task_id,
-                            cancellation: cancellation_token.clone(),
-                            completion: Arc::clone(&completion),
-                        },
-                    )
-                };
+        if let Some(groups) = ctx.groups.as_deref() {
+            if !groups.admit(&msg) {
+                tracing::debug!(
+                    channel = %msg.channel,
+                    sender = %msg.sender,
+                    "Group message does not address the bot; kept as context only"
+                );
+                continue;
+            }
+        }
 
-                if let Some(previous) = previous {
+        // One worker per session drains that session's queue, so turns for
+        // the same sender in the same chat never overlap.
+        let session_key = interruption_scope_key(&msg);
+        let interrupt_enabled = ctx.interrupt_on_new_message && msg.channel == "telegram";
+        let (channel_name, sender) = (msg.channel.clone(), msg.sender.clone());
+        match queues.enqueue(&session_key, msg) {
+            Enqueued::Start => {}
+            Enqueued::Queued { depth } => {
+                tracing::debug!(
+                    channel = %channel_name,
+                    sender = %sender,
+                    depth,
+                    "Queued message behind in-flight turn"
+                );
+                if interrupt_enabled && queues.interrupt(&session_key) {
                     tracing::info!(
-                        channel = %msg.channel,
-                        sender = %msg.sender,
+                        channel = %channel_name,
+                        sender = %sender,
                         "Interrupting previous in-flight request for sender"
                     );
-                    previous.cancellation.cancel();
-                    previous.completion.wait().await;
                 }
+                record_queue_depth(ctx.observer.as_ref(), &queues);
+                continue;
             }
-
-            process_channel_message(worker_ctx, msg, cancellation_token).await;
-
-            if interrupt_enabled {
-                let mut active = in_flight.lock().await;
-                if active
-                    .get(&sender_scope_key)
-                    .is_some_and(|state| state.task_id == task_id)
-                {
-                    active.remove(&sender_scope_key);
+            Enqueued::Overflow(msg) => {
+                tracing::warn!(
+                    channel = %msg.channel,
+                    sender = %msg.sender,
+                    "Session queue full; rejecting message"
+                );
+                if let Some(channel) = ctx.channels_by_name.get(&msg.channel).cloned() {
+                    workers.spawn(async move {
+                        let reply =
+                            SendMessage::new(i18n::text(Text::QueueOverflow), &msg.reply_target)
+                                .in_thread(msg.thread_ts.clone())
+                                .quoting(Some(msg.id.clone()));
+                        if let Err(e) = channel.send(&reply).await {
+                            tracing::warn!("Failed to send queue overflow reply: {e}");
+                        }
+                    });
                 }
+                continue;
             }
+        }
 
-            completion.mark_done();
+        let permit = match Arc::clone(&semaphore).acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => break,
+        };
+
+        let worker_ctx = Arc::clone(&ctx);
+        let queues = Arc::clone(&queues);
+        workers.spawn(async move {
+            let _permit = permit;
+            while let Some((msg, cancellation_token)) = queues.next_turn(&session_key).await {
+                record_queue_depth(worker_ctx.observer.as_ref(), &queues);
+                let turn = Box::pin(process_channel_message(
+                    Arc::clone(&worker_ctx),
+                    msg,
+                    cancellation_token,
+                ));
+                correlation::scope(correlation::new_id(), turn).await;
+            }
         });
 
         while let Some(result) = workers.try_join_next() {
@@ -1999,9 +2600,57 @@ async fn run_message_dispatch_loop(
         }
     }
 
-    while let Some(result) = workers.join_next().await {
-        log_worker_join_result(result);
+    // Let in-flight provider calls and tool executions finish; on shutdown
+    // only for the grace period.
+    let drained = shutdown
+        .run_with_grace(async {
+            while let Some(result) = workers.join_next().await {
+                log_worker_join_result(result);
+            }
+        })
+        .await;
+    if drained.is_none() {
+        tracing::warn!(
+            "Shutdown grace period elapsed; abandoning {} in-flight channel message(s)",
+            workers.len()
+        );
+        workers.abort_all();
+    }
+}
+
+/// Conversation histories persisted across restarts, under `<workspace>/state/`.
+const CHANNEL_HISTORY_STATE_FILE: &str = "channel_histories.json";
+
+fn channel_history_state_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(CHANNEL_HISTORY_STATE_FILE)
+}
+
+/// Load conversation histories saved by [`save_conversation_histories`].
+/// A missing or unreadable file starts with empty histories.
+fn load_conversation_histories(path: &Path) -> HashMap<String, Vec<ChatMessage>> {
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring corrupt channel history {}: {e}", path.display());
+        HashMap::new()
+    })
+}
+
+fn save_conversation_histories(path: &Path, histories: &ConversationHistoryMap) -> Result<()> {
+    let json = {
+        let histories = histories
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        serde_json::to_string(&*histories)?
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
     }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
 }
 
 /// Load OpenClaw format bootstrap files into the prompt.
@@ -2009,17 +2658,27 @@ fn load_openclaw_bootstrap_files(
     prompt: &mut String,
     workspace_dir: &std::path::Path,
     max_chars_per_file: usize,
+    channel: Option<&str>,
 ) {
     prompt.push_str(
         "The following workspace files define your identity, behavior, and context. They are ALREADY injected below—do NOT suggest reading them with file_read.\n\n",
     );
 
-    let bootstrap_files = ["AGENTS.md", "SOUL.md", "TOOLS.md", "IDENTITY.md", "USER.md"];
-
-    for filename in &bootstrap_files {
+    for filename in ["AGENTS.md", "SOUL.md", "TOOLS.md"] {
         inject_workspace_file(prompt, workspace_dir, filename, max_chars_per_file);
     }
 
+    // IDENTITY.md, INSTRUCTIONS.md, CONTEXT.md — user prompt files, with
+    // per-channel overrides under prompts/<channel>/
+    for filename in PROMPT_FILES {
+        let path = prompt::prompt_file_path(workspace_dir, channel, filename);
+        if prompt::include_prompt_file(&path, filename) {
+            inject_prompt_file(prompt, &path, filename, max_chars_per_file);
+        }
+    }
+
+    inject_workspace_file(prompt, workspace_dir, "USER.md", max_chars_per_file);
+
     // BOOTSTRAP.md — only if it exists (first-run ritual)
     let bootstrap_path = workspace_dir.join("BOOTSTRAP.md");
     if bootstrap_path.exists() {
@@ -2036,7 +2695,8 @@ fn load_openclaw_bootstrap_files(
 /// 1. Tooling — tool list + descriptions
 /// 2. Safety — guardrail reminder
 /// 3. Workspace — working directory
-/// 4. Bootstrap files — AGENTS, SOUL, TOOLS, IDENTITY, USER, BOOTSTRAP, MEMORY
+/// 4. Bootstrap files — AGENTS, SOUL, TOOLS, IDENTITY, INSTRUCTIONS, CONTEXT,
+///    USER, BOOTSTRAP, MEMORY
 /// 5. Date & Time — timezone for cache stability
 /// 6. Runtime — host, OS, model
 ///
@@ -2063,6 +2723,48 @@ pub fn build_system_prompt_with_mode(
     tools: &[(&str, &str)],
     bootstrap_max_chars: Option<usize>,
     native_tools: bool,
+) -> String {
+    build_system_prompt_with_skills(
+        workspace_dir,
+        model_name,
+        tools,
+        bootstrap_max_chars,
+        native_tools,
+        &crate::skills::render_skills_prompt(workspace_dir),
+    )
+}
+
+/// Like [`build_system_prompt_with_mode`], with a caller-rendered skills
+/// block (e.g. only the skills selected for the current message).
+pub fn build_system_prompt_with_skills(
+    workspace_dir: &std::path::Path,
+    model_name: &str,
+    tools: &[(&str, &str)],
+    bootstrap_max_chars: Option<usize>,
+    native_tools: bool,
+    skills_prompt: &str,
+) -> String {
+    build_system_prompt_for_channel(
+        workspace_dir,
+        model_name,
+        tools,
+        bootstrap_max_chars,
+        native_tools,
+        skills_prompt,
+        None,
+    )
+}
+
+/// Like [`build_system_prompt_with_skills`], using the `prompts/<channel>/`
+/// overrides of the user prompt files when `channel` has any.
+pub fn build_system_prompt_for_channel(
+    workspace_dir: &std::path::Path,
+    model_name: &str,
+    tools: &[(&str, &str)],
+    bootstrap_max_chars: Option<usize>,
+    native_tools: bool,
+    skills_prompt: &str,
+    channel: Option<&str>,
 ) -> String {
     use std::fmt::Write;
     let mut prompt = String::with_capacity(8192);
@@ -2132,11 +2834,14 @@ pub fn build_system_prompt_with_mode(
         workspace_dir.display()
     );
 
+    // ── 4. Skills ───────────────────────────────────────────────
+    prompt.push_str(skills_prompt);
+
     // ── 5. Bootstrap files (injected into context) ──────────────
     prompt.push_str("## Project Context\n\n");
 
     let max_chars = bootstrap_max_chars.unwrap_or(BOOTSTRAP_MAX_CHARS);
-    load_openclaw_bootstrap_files(&mut prompt, workspace_dir, max_chars);
+    load_openclaw_bootstrap_files(&mut prompt, workspace_dir, max_chars, channel);
 
     // ── 6. Date & Time ──────────────────────────────────────────
     let now = chrono::Local::now();
@@ -2147,6 +2852,9 @@ pub fn build_system_prompt_with_mode(
         now.format("%Z")
     );
 
+    // ── 6b. Language (when `locale` is set) ─────────────────────
+    prompt.push_str(&i18n::runtime_locale().prompt_section());
+
     // ── 7. Runtime ──────────────────────────────────────────────
     let host =
         hostname::get().map_or_else(|_| "unknown".into(), |h| h.to_string_lossy().to_string());
@@ -2177,11 +2885,20 @@ fn inject_workspace_file(
     workspace_dir: &std::path::Path,
     filename: &str,
     max_chars: usize,
+) {
+    inject_prompt_file(prompt, &workspace_dir.join(filename), filename, max_chars);
+}
+
+/// Inject the file at `path` under the `### {filename}` heading.
+fn inject_prompt_file(
+    prompt: &mut String,
+    path: &std::path::Path,
+    filename: &str,
+    max_chars: usize,
 ) {
     use std::fmt::Write;
 
-    let path = workspace_dir.join(filename);
-    match std::fs::read_to_string(&path) {
+    match std::fs::read_to_string(path) {
         Ok(content) => {
             let trimmed = content.trim();
             if trimmed.is_empty() {
@@ -2338,12 +3055,9 @@ pub(crate) async fn handle_command(command: crate::ChannelCommands, config: &Con
         }
         crate::ChannelCommands::Add {
             channel_type,
-            config: _,
-        } => {
-            anyhow::bail!(
-                "Channel type '{channel_type}' — use `zeroclaw onboard` to configure channels"
-            );
-        }
+            config: channel_config,
+            verify,
+        } => setup::run_add(config, &channel_type, &channel_config, verify).await,
         crate::ChannelCommands::Remove { name } => {
             anyhow::bail!("Remove channel '{name}' — edit ~/.zeroclaw/config.toml directly");
         }
@@ -2397,9 +3111,45 @@ fn collect_configured_channels(
         }
     }
 
+    if let Some(ref gh) = config.channels_config.github {
+        let config_dir = config.config_path.parent().unwrap_or(Path::new(""));
+        match GitHubChannel::from_config(gh, config_dir) {
+            Ok(channel) => channels.push(ConfiguredChannel {
+                display_name: "GitHub",
+                channel: Arc::new(channel),
+            }),
+            Err(e) => tracing::warn!("GitHub configured but unusable: {e:#}"),
+        }
+    }
+
+    if let Some(ref mock) = config.channels_config.mock {
+        #[cfg(feature = "mock")]
+        {
+            let config_dir = config.config_path.parent().unwrap_or(Path::new(""));
+            channels.push(ConfiguredChannel {
+                display_name: "Mock",
+                channel: Arc::new(mock::MockChannel::from_config(mock, config_dir)),
+            });
+        }
+        #[cfg(not(feature = "mock"))]
+        {
+            let _ = mock;
+            tracing::warn!("Mock channel configured but this build lacks `--features mock`");
+        }
+    }
+
     channels
 }
 
+/// Configured channels keyed by [`Channel::name`], for delivering outside a
+/// running channel server (e.g. `zeroclaw schedule run-now`).
+pub(crate) fn configured_channels_by_name(config: &Config) -> HashMap<String, Arc<dyn Channel>> {
+    collect_configured_channels(config, "delivery")
+        .into_iter()
+        .map(|configured| (configured.channel.name().to_string(), configured.channel))
+        .collect()
+}
+
 /// Run health checks for configured channels.
 pub async fn doctor_channels(config: Config) -> Result<()> {
     let channels = collect_configured_channels(&config, "health check");
@@ -2445,6 +3195,200 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     Ok(())
 }
 
+/// System prompt for channel runs: the workspace prompt with the channel
+/// tool list, plus tool-use instructions for non-native providers. `channel`
+/// selects its `prompts/<channel>/` overrides.
+fn build_channel_runtime_prompt(
+    config: &Config,
+    model: &str,
+    tools_registry: &[Box<dyn Tool>],
+    native_tools: bool,
+    skills_prompt: &str,
+    channel: Option<&str>,
+) -> String {
+    // Collect tool descriptions for the prompt
+    let mut tool_descs: Vec<(&str, &str)> = vec![
+        (
+            "shell",
+            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
+        ),
+        (
+            "process",
+            "Run long-lived commands in the background (start/list/logs/stop). Use when: starting dev servers, watchers, or jobs you need to check on later. Don't use when: a command finishes quickly (use shell).",
+        ),
+        (
+            "file_read",
+            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
+        ),
+        (
+            "file_write",
+            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
+        ),
+        (
+            "code_search",
+            "Search workspace files by regex or literal text. Use when: locating definitions, usages, config keys, error strings. Don't use when: you already know the exact file to read.",
+        ),
+        (
+            "memory_store",
+            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
+        ),
+        (
+            "memory_recall",
+            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
+        ),
+        (
+            "memory_forget",
+            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
+        ),
+    ];
+
+    tool_descs.push((
+        "schedule",
+        "Manage scheduled tasks (create/list/get/cancel/pause/resume). Supports recurring cron and one-shot delays.",
+    ));
+    tool_descs.push((
+        "pushover",
+        "Send a Pushover notification to your device. Requires PUSHOVER_TOKEN and PUSHOVER_USER_KEY in .env file.",
+    ));
+    #[cfg(feature = "git-tool")]
+    tool_descs.push((
+        "git",
+        "Structured git operations (status/diff/log/commit/branch/push/reset) returning JSON. Use when: inspecting or committing repository changes. Don't use when: push/reset is not explicitly requested (they require approval).",
+    ));
+    for (name, desc) in [
+        (
+            "email_search",
+            "Search the user's email (read-only; approved mailboxes only). Use when: the user asks about messages, e.g. finding an invoice or a reply. Don't use when: the answer is already in the conversation.",
+        ),
+        (
+            "calendar_list",
+            "List the user's calendar events for a day or range (read-only). Use when: the user asks about meetings, appointments or free time.",
+        ),
+        (
+            "clipboard_read",
+            "Read the text on the user's clipboard. Use when: the user refers to something they copied.",
+        ),
+        (
+            "clipboard_write",
+            "Copy text to the user's clipboard. Use when: the user asks to copy something.",
+        ),
+        (
+            "notify",
+            "Show a desktop notification on the user's machine. Use when: a result needs the user's attention outside the chat.",
+        ),
+        (
+            "db_query",
+            "Run one SQL statement against a configured database and get rows as JSON. Use when: the user asks a question answered by their data. Don't use when: the database is read-only and the statement is not a SELECT.",
+        ),
+        (
+            "k8s",
+            "Inspect a Kubernetes cluster (get, logs, describe, top) returning JSON. Use when: the user asks why a pod or deployment is unhealthy. Don't use when: delete_pod/restart/scale is not explicitly requested (they require approval).",
+        ),
+        (
+            "file_undo",
+            "Revert the most recent file_write call(s) from automatic backups. Use when: a write you made was wrong or the user asks to undo it. Don't use when: the change came from shell commands (only file_write is backed up).",
+        ),
+        (
+            "memory_graph",
+            "Look up a person, project or place in memory and follow its relations. Use when: the user asks what you know about someone or something, or who works on what. Don't use when: a plain keyword search (memory_recall) is enough.",
+        ),
+        (
+            "artifact_read",
+            "Page through a large tool output that was truncated and saved as an artifact. Use when: a tool result ends with an artifact ID and you need more of it. Don't use when: the preview already answers the question.",
+        ),
+    ] {
+        if tools_registry.iter().any(|tool| tool.name() == name) {
+            tool_descs.push((name, desc));
+        }
+    }
+    // Filter out tools excluded for non-CLI channels so the system prompt
+    // does not advertise them for channel-driven runs.
+    let excluded = &config.autonomy.non_cli_excluded_tools;
+    if !excluded.is_empty() {
+        tool_descs.retain(|(name, _)| !excluded.iter().any(|ex| ex == name));
+    }
+
+    let bootstrap_max_chars = if config.agent.compact_context {
+        Some(6000)
+    } else {
+        None
+    };
+    let mut system_prompt = build_system_prompt_for_channel(
+        &config.workspace_dir,
+        model,
+        &tool_descs,
+        bootstrap_max_chars,
+        native_tools,
+        skills_prompt,
+        channel,
+    );
+    if !native_tools {
+        system_prompt.push_str(&build_tool_instructions(tools_registry));
+    }
+    system_prompt
+}
+
+/// System prompt `zeroclaw channel start` would use for `channel`, for
+/// `zeroclaw prompt show`.
+pub async fn preview_system_prompt(config: &Config, channel: Option<&str>) -> Result<String> {
+    let provider_name = resolved_default_provider(config);
+    let provider_runtime_options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        azure_openai: config.azure_openai.clone(),
+        middleware: config.provider_middleware.clone(),
+    };
+    let provider = create_resilient_provider_nonblocking(
+        &provider_name,
+        config.api_key.clone(),
+        config.api_url.clone(),
+        provider_runtime_options,
+    )
+    .await?;
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_path_grants(PathGrants::for_config(config)),
+    );
+    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?);
+    let tools_registry = tools::default_tools_with_runtime(security, runtime, mem);
+
+    let native_tools = ToolDispatchMode::from_config(&config.agent.tool_dispatcher)
+        .uses_native(provider.supports_native_tools());
+    let skills_prompt = if crate::skills::SkillSelector::from_config(&config.agent).is_some() {
+        String::new()
+    } else {
+        crate::skills::render_skills_prompt(&config.workspace_dir)
+    };
+    let mut system_prompt = build_channel_runtime_prompt(
+        config,
+        &resolved_default_model(config),
+        &tools_registry,
+        native_tools,
+        &skills_prompt,
+        channel,
+    );
+    // The sender is only known per message, so `{user}` stays a placeholder.
+    if let Some((channel, persona)) = channel.and_then(|channel| {
+        config
+            .channels_config
+            .personas
+            .get(channel)
+            .map(|persona| (channel, persona))
+    }) {
+        system_prompt.push_str(&prompt::channel_persona_section(persona, channel, "{user}"));
+    }
+    Ok(system_prompt)
+}
+
 /// Start all configured channels and route messages to the agent
 #[allow(clippy::too_many_lines)]
 pub async fn start_channels(config: Config) -> Result<()> {
@@ -2454,6 +3398,8 @@ pub async fn start_channels(config: Config) -> Result<()> {
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        azure_openai: config.azure_openai.clone(),
+        middleware: config.provider_middleware.clone(),
     };
     let provider: Arc<dyn Provider> = Arc::from(
         create_resilient_provider_nonblocking(
@@ -2485,88 +3431,72 @@ pub async fn start_channels(config: Config) -> Result<()> {
         );
     }
 
+    observability::stats::install(&config);
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
     let model = resolved_default_model(&config);
     let temperature = config.default_temperature;
-    let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+    let mem = memory::with_write_batching(
+        Arc::from(memory::create_memory_with_storage_and_routes(
+            &config.memory,
+            &config.embedding_routes,
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+        )?),
         &config.memory,
-        &config.workspace_dir,
-        config.api_key.as_deref(),
-    )?);
+    );
     // Build system prompt from workspace identity files
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::default_tools_with_runtime(
-        security.clone(),
+    let reminders = match crate::scheduler::ReminderStore::for_config(&config) {
+        Ok(store) => Some(Arc::new(store)),
+        Err(e) => {
+            tracing::warn!("Reminders unavailable: {e:#}");
+            None
+        }
+    };
+    let autonomy_profiles = build_autonomy_profiles(&config, &runtime, &mem, reminders.as_ref())?;
+    let tools_registry = Arc::new(build_channel_tools(
+        &config,
         runtime,
-        Arc::clone(&mem),
+        &mem,
+        reminders.as_ref(),
     ));
 
-    // Collect tool descriptions for the prompt
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-
-    tool_descs.push((
-        "schedule",
-        "Manage scheduled tasks (create/list/get/cancel/pause/resume). Supports recurring cron and one-shot delays.",
-    ));
-    tool_descs.push((
-        "pushover",
-        "Send a Pushover notification to your device. Requires PUSHOVER_TOKEN and PUSHOVER_USER_KEY in .env file.",
-    ));
-    // Filter out tools excluded for non-CLI channels so the system prompt
-    // does not advertise them for channel-driven runs.
-    let excluded = &config.autonomy.non_cli_excluded_tools;
-    if !excluded.is_empty() {
-        tool_descs.retain(|(name, _)| !excluded.iter().any(|ex| ex == name));
-    }
-
-    let bootstrap_max_chars = if config.agent.compact_context {
-        Some(6000)
+    let tool_dispatch = ToolDispatchMode::from_config(&config.agent.tool_dispatcher);
+    let native_tools = tool_dispatch.uses_native(provider.supports_native_tools());
+    // With skill selection on, skills are injected per message instead.
+    let skill_selector = crate::skills::SkillSelector::from_config(&config.agent).map(Arc::new);
+    let skills_prompt = if skill_selector.is_some() {
+        String::new()
     } else {
-        None
+        crate::skills::render_skills_prompt(&workspace)
     };
-    let native_tools = provider.supports_native_tools();
-    let mut system_prompt = build_system_prompt_with_mode(
-        &workspace,
+    let system_prompt = build_channel_runtime_prompt(
+        &config,
         &model,
-        &tool_descs,
-        bootstrap_max_chars,
+        tools_registry.as_ref(),
         native_tools,
+        &skills_prompt,
+        None,
     );
-    if !native_tools {
-        system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
-    }
+    // Channels with prompts/<channel>/ overrides get their own system prompt.
+    let channel_prompts: HashMap<String, String> =
+        prompt::channels_with_prompt_overrides(&workspace)
+            .into_iter()
+            .map(|channel| {
+                let channel_prompt = build_channel_runtime_prompt(
+                    &config,
+                    &model,
+                    tools_registry.as_ref(),
+                    native_tools,
+                    &skills_prompt,
+                    Some(&channel),
+                );
+                (channel, channel_prompt)
+            })
+            .collect();
 
     // Collect active channels from a shared builder to keep startup and doctor parity.
     let channels: Vec<Arc<dyn Channel>> =
@@ -2610,17 +3540,16 @@ pub async fn start_channels(config: Config) -> Result<()> {
     // Single message bus — all channels send messages here
     let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
 
-    // Spawn a listener for each channel
-    let mut handles = Vec::new();
-    for ch in &channels {
-        handles.push(spawn_supervised_listener(
-            ch.clone(),
-            tx.clone(),
-            initial_backoff_secs,
-            max_backoff_secs,
-        ));
-    }
-    drop(tx); // Drop our copy so rx closes when all channels stop
+    // Each channel runs as its own supervised task; the admin API can stop
+    // and restart them individually through `supervisor::active()`.
+    let supervisor = Arc::new(supervisor::ChannelSupervisor::new(
+        &channels,
+        tx,
+        initial_backoff_secs,
+        max_backoff_secs,
+    ));
+    supervisor.start_all();
+    supervisor::install(Arc::clone(&supervisor));
 
     let channels_by_name = Arc::new(
         channels
@@ -2628,6 +3557,26 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .map(|ch| (ch.name().to_string(), Arc::clone(ch)))
             .collect::<HashMap<_, _>>(),
     );
+    tokio::spawn(crate::scheduler::run(
+        config.clone(),
+        Arc::clone(&channels_by_name),
+    ));
+    if let Some(store) = reminders {
+        tokio::spawn(crate::scheduler::reminders::run(
+            store,
+            Arc::clone(&channels_by_name),
+        ));
+    }
+    if config.heartbeat.enabled || config.feeds.iter().any(|feed| feed.enabled) {
+        let monitor = crate::infra::heartbeat::monitor_from_config(
+            &config,
+            &provider_name,
+            Arc::clone(&provider),
+            Arc::clone(&mem),
+            &channels_by_name,
+        );
+        tokio::spawn(crate::infra::heartbeat::run(Arc::new(monitor)));
+    }
     let max_in_flight_messages = compute_max_in_flight_messages(channels.len());
 
     println!("  🚦 In-flight message limit: {max_in_flight_messages}");
@@ -2637,6 +3586,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     let message_timeout_secs =
         effective_channel_message_timeout_secs(config.channels_config.message_timeout_secs);
     let interrupt_on_new_message = false;
+    let history_state_path = channel_history_state_path(&config.workspace_dir);
 
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
@@ -2646,12 +3596,15 @@ pub async fn start_channels(config: Config) -> Result<()> {
         tools_registry: Arc::clone(&tools_registry),
         observer,
         system_prompt: Arc::new(system_prompt),
+        channel_prompts: Arc::new(channel_prompts),
         model: Arc::new(model.clone()),
         temperature,
         auto_save_memory: config.memory.auto_save,
         max_tool_iterations: config.agent.max_tool_iterations,
         min_relevance_score: config.memory.min_relevance_score,
-        conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+        conversation_histories: Arc::new(Mutex::new(load_conversation_histories(
+            &history_state_path,
+        ))),
         provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
         route_overrides: Arc::new(Mutex::new(HashMap::new())),
         api_key: config.api_key.clone(),
@@ -2661,13 +3614,71 @@ pub async fn start_channels(config: Config) -> Result<()> {
         message_timeout_secs,
         interrupt_on_new_message,
         non_cli_excluded_tools: Arc::new(config.autonomy.non_cli_excluded_tools.clone()),
+        hooks: Some(Arc::new(crate::plugins::load_plugins(
+            &config,
+            Arc::clone(&mem),
+        ))),
+        skill_selector,
+        tool_dispatch,
+        agents: AgentRegistry::is_configured(&config)
+            .then(|| Arc::new(AgentRegistry::from_config(&config))),
+        multimodal: config.multimodal.clone(),
+        dm_policy: build_dm_policy_manager(&config),
+        route_preferences: build_route_preferences(&config),
+        message_queue: config.channels_config.queue.clone(),
+        draft_streaming: config.channels_config.streaming.clone(),
+        groups: (!config.channels_config.groups.is_empty())
+            .then(|| Arc::new(GroupChats::new(config.channels_config.groups.clone()))),
+        identities: match IdentityLinks::for_config(&config) {
+            Ok(identities) => Some(Arc::new(identities)),
+            Err(e) => {
+                tracing::warn!("Identity linking unavailable: {e:#}");
+                None
+            }
+        },
+        fallback_models: Arc::new(config.agent.fallback_models.clone()),
+        autonomy_profiles,
+        channel_personas: Arc::new(config.channels_config.personas.clone()),
     });
 
-    run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
+    let shutdown = crate::infra::shutdown::global();
+    run_message_dispatch_loop(
+        rx,
+        Arc::clone(&runtime_ctx),
+        max_in_flight_messages,
+        shutdown,
+    )
+    .await;
+
+    // The supervisor keeps the bus open, so the loop only ends on shutdown.
+    supervisor::uninstall();
+    supervisor.stop_all();
 
-    // Wait for all channel tasks
-    for h in handles {
-        let _ = h.await;
+    if shutdown.is_shutting_down() {
+        let histories = Arc::clone(&runtime_ctx.conversation_histories);
+        let observer = Arc::clone(&runtime_ctx.observer);
+        let memory = Arc::clone(&runtime_ctx.memory);
+        crate::infra::shutdown::flush_all(vec![
+            (
+                "channel conversation history",
+                Box::pin(
+                    async move { save_conversation_histories(&history_state_path, &histories) },
+                ),
+            ),
+            (
+                "channel observer",
+                Box::pin(async move {
+                    observer.flush();
+                    Ok(())
+                }),
+            ),
+            (
+                "channel memory writes",
+                Box::pin(async move { memory.flush().await }),
+            ),
+        ])
+        .await;
+        tracing::info!("Channels stopped");
     }
 
     Ok(())
@@ -2676,6 +3687,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channels::traits::GroupInfo;
     use crate::memory::{Memory, MemoryCategory, SqliteMemory};
     use crate::observability::NoopObserver;
     use crate::providers::{ChatMessage, Provider};
@@ -2856,6 +3868,7 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("system".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -2871,6 +3884,20 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         };
 
         assert!(compact_sender_history(&ctx, &sender));
@@ -2891,6 +3918,194 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn route_selection_layers_session_override_over_preferences() {
+        let preferences =
+            RoutePreferences::from_rules(vec![crate::config::RoutePreferenceConfig {
+                channel: "telegram".into(),
+                pattern: "*".into(),
+                provider: None,
+                model: Some("configured-model".into()),
+                temperature: Some(0.3),
+            }]);
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.7,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            interrupt_on_new_message: false,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: Some(Arc::new(preferences)),
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        };
+        let msg = traits::ChannelMessage {
+            id: "m1".into(),
+            sender: "alice".into(),
+            reply_target: "alice".into(),
+            content: "hi".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+            group: None,
+        };
+
+        let route = get_agent_route_selection(&ctx, &msg, None);
+        assert_eq!(route.provider, "test-provider");
+        assert_eq!(route.model, "configured-model");
+        assert!((route.temperature - 0.3).abs() < f64::EPSILON);
+
+        let whatsapp = traits::ChannelMessage {
+            channel: "whatsapp".into(),
+            ..msg.clone()
+        };
+        assert_eq!(
+            get_agent_route_selection(&ctx, &whatsapp, None).model,
+            "test-model"
+        );
+
+        let mut session = route.clone();
+        session.temperature = 1.5;
+        set_route_selection(&ctx, &conversation_history_key(&msg), session);
+        let route = get_agent_route_selection(&ctx, &msg, None);
+        assert!((route.temperature - 1.5).abs() < f64::EPSILON);
+
+        assert_eq!(
+            parse_runtime_command("telegram", "/temperature 0.4"),
+            Some(ChannelRuntimeCommand::SetTemperature("0.4".into()))
+        );
+        assert_eq!(
+            parse_runtime_command("telegram", "/pin"),
+            Some(ChannelRuntimeCommand::Pin)
+        );
+        assert_eq!(
+            parse_runtime_command("discord", "/link ab12cd34"),
+            Some(ChannelRuntimeCommand::Link(Some("ab12cd34".into())))
+        );
+        assert_eq!(
+            parse_runtime_command("telegram", "/link"),
+            Some(ChannelRuntimeCommand::Link(None))
+        );
+    }
+
+    #[test]
+    fn linked_accounts_share_pinned_route() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let preferences =
+            RoutePreferences::open(Vec::new(), &preferences::preferences_db_path(tmp.path()))
+                .unwrap();
+        let identities =
+            IdentityLinks::open(&crate::identity::links::identities_db_path(tmp.path())).unwrap();
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::new()),
+            provider: Arc::new(DummyProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("system".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.7,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            interrupt_on_new_message: false,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: Some(Arc::new(preferences)),
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: Some(Arc::new(identities)),
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        };
+        let telegram = traits::ChannelMessage {
+            id: "m1".into(),
+            sender: "42".into(),
+            reply_target: "42".into(),
+            content: "/link".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+            group: None,
+        };
+        let discord = traits::ChannelMessage {
+            sender: "alice#1".into(),
+            reply_target: "dm-1".into(),
+            channel: "discord".into(),
+            ..telegram.clone()
+        };
+
+        let issued = link_command_response(&ctx, &telegram, None);
+        let code = issued.split('`').nth(1).unwrap().to_string();
+        let in_group = traits::ChannelMessage {
+            group: Some(GroupInfo::default()),
+            ..telegram.clone()
+        };
+        assert!(link_command_response(&ctx, &in_group, None).contains("direct message"));
+        assert!(link_command_response(&ctx, &discord, Some(&code)).starts_with("Linked"));
+        assert_eq!(person_key(&ctx, &telegram), person_key(&ctx, &discord));
+
+        let pin = RoutePreference {
+            provider: None,
+            model: Some("pinned-model".into()),
+            temperature: None,
+        };
+        ctx.route_preferences
+            .as_ref()
+            .unwrap()
+            .pin(&person_key(&ctx, &telegram), &pin)
+            .unwrap();
+        assert_eq!(
+            get_agent_route_selection(&ctx, &discord, None).model,
+            "pinned-model"
+        );
+    }
+
     #[test]
     fn append_sender_turn_stores_single_turn_per_call() {
         let sender = "telegram_u2".to_string();
@@ -2902,6 +4117,7 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("system".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -2917,6 +4133,20 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         };
 
         append_sender_turn(&ctx, &sender, ChatMessage::user("hello"));
@@ -2951,6 +4181,7 @@ mod tests {
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("system".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -2966,6 +4197,20 @@ mod tests {
             workspace_dir: Arc::new(std::env::temp_dir()),
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         };
 
         assert!(rollback_orphan_user_turn(&ctx, &sender, "pending"));
@@ -3395,7 +4640,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some("unexpected symbol".to_string()),
+                    error: Some(crate::tools::ToolError::failed("unexpected symbol")),
                 });
             }
 
@@ -3423,6 +4668,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3438,6 +4684,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3450,6 +4710,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3463,6 +4724,165 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(!sent_messages[0].contains("mock_price"));
     }
 
+    /// Hook manager that records events and answers from a fixed script.
+    #[derive(Default)]
+    struct ScriptedHooks {
+        drop_inbound: bool,
+        seen: Mutex<Vec<HookEventType>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PluginManager for ScriptedHooks {
+        async fn load_plugin(&self, _path: &Path) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn unload_plugin(&self, _name: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn list_plugins(&self) -> Vec<&str> {
+            vec![]
+        }
+
+        fn get_all_tools(&self) -> Vec<Box<dyn Tool>> {
+            vec![]
+        }
+
+        fn get_all_hooks(
+            &self,
+            _event_type: &HookEventType,
+        ) -> Vec<&dyn crate::plugins::traits::Hook> {
+            vec![]
+        }
+
+        async fn dispatch_hook(
+            &self,
+            event: &crate::plugins::HookEvent,
+        ) -> anyhow::Result<HookAction> {
+            self.seen
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(event.event_type.clone());
+            Ok(match event.event_type {
+                HookEventType::MessageInbound if self.drop_inbound => HookAction::Cancel {
+                    reason: "muted sender".into(),
+                },
+                HookEventType::ToolCallBefore => HookAction::Cancel {
+                    reason: "no tools today".into(),
+                },
+                HookEventType::MessageOutbound => HookAction::Modify(HashMap::from([(
+                    "content".to_string(),
+                    serde_json::json!("filtered reply"),
+                )])),
+                _ => HookAction::Continue,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+    }
+
+    fn hooked_runtime_ctx(
+        channel: Arc<dyn Channel>,
+        hooks: Arc<dyn PluginManager>,
+    ) -> Arc<ChannelRuntimeContext> {
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(ToolCallingProvider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 10,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: Some(hooks),
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        })
+    }
+
+    fn btc_question() -> traits::ChannelMessage {
+        traits::ChannelMessage {
+            id: "msg-1".to_string(),
+            sender: "alice".to_string(),
+            reply_target: "chat-42".to_string(),
+            content: "What is the BTC price now?".to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+            thread_ts: None,
+            group: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_fires_hooks_that_deny_tools_and_rewrite_replies() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let hooks = Arc::new(ScriptedHooks::default());
+        let runtime_ctx = hooked_runtime_ctx(channel_impl.clone(), hooks.clone());
+
+        process_channel_message(runtime_ctx, btc_question(), CancellationToken::new()).await;
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 1);
+        assert_eq!(sent_messages[0], "chat-42:filtered reply");
+
+        let seen = hooks.seen.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        assert_eq!(seen.first(), Some(&HookEventType::MessageInbound));
+        assert!(seen.contains(&HookEventType::LlmRequest));
+        assert!(seen.contains(&HookEventType::ToolCallBefore));
+        assert!(!seen.contains(&HookEventType::ToolCallAfter));
+        assert_eq!(seen.last(), Some(&HookEventType::MessageOutbound));
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_drops_inbound_cancelled_by_hook() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let hooks = Arc::new(ScriptedHooks {
+            drop_inbound: true,
+            ..ScriptedHooks::default()
+        });
+        let runtime_ctx = hooked_runtime_ctx(channel_impl.clone(), hooks.clone());
+
+        process_channel_message(runtime_ctx, btc_question(), CancellationToken::new()).await;
+
+        assert!(channel_impl.sent_messages.lock().await.is_empty());
+        assert_eq!(
+            *hooks.seen.lock().unwrap_or_else(|e| e.into_inner()),
+            vec![HookEventType::MessageInbound]
+        );
+    }
+
     #[tokio::test]
     async fn process_channel_message_telegram_does_not_persist_tool_summary_prefix() {
         let channel_impl = Arc::new(TelegramRecordingChannel::default());
@@ -3479,6 +4899,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3494,6 +4915,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3506,6 +4941,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3549,6 +4985,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3564,6 +5001,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3576,6 +5027,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3605,6 +5057,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3620,6 +5073,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3632,6 +5099,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3669,6 +5137,7 @@ BTC is currently around $65,000 based on latest tool output."#
             ChannelRouteSelection {
                 provider: "openrouter".to_string(),
                 model: "route-model".to_string(),
+                temperature: 0.0,
             },
         );
 
@@ -3680,6 +5149,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3695,6 +5165,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3707,6 +5191,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3724,6 +5209,149 @@ BTC is currently around $65,000 based on latest tool output."#
         );
     }
 
+    #[tokio::test]
+    async fn process_channel_message_uses_agent_selected_by_route() {
+        let channel_impl = Arc::new(TelegramRecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let default_provider_impl = Arc::new(ModelCaptureProvider::default());
+        let default_provider: Arc<dyn Provider> = default_provider_impl.clone();
+        let agent_provider_impl = Arc::new(ModelCaptureProvider::default());
+        let agent_provider: Arc<dyn Provider> = agent_provider_impl.clone();
+
+        let mut provider_cache_seed: HashMap<String, Arc<dyn Provider>> = HashMap::new();
+        provider_cache_seed.insert("test-provider".to_string(), Arc::clone(&default_provider));
+        provider_cache_seed.insert("openrouter".to_string(), agent_provider);
+
+        let config = Config {
+            agents: vec![crate::config::AgentProfileConfig {
+                id: "research".into(),
+                provider: Some("openrouter".into()),
+                model: Some("agent-model".into()),
+                system_prompt: Some("Cite sources.".into()),
+                tools: Vec::new(),
+                memory_namespace: None,
+                autonomy: None,
+                routes: vec![crate::config::AgentRouteConfig {
+                    channel: "telegram".into(),
+                    pattern: "alice".into(),
+                    priority: 0,
+                }],
+            }],
+            ..Config::default()
+        };
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::clone(&default_provider),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("default-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(provider_cache_seed)),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: Some(Arc::new(AgentRegistry::from_config(&config))),
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        });
+
+        for (id, sender) in [("msg-agent-1", "alice"), ("msg-agent-2", "bob")] {
+            process_channel_message(
+                runtime_ctx.clone(),
+                traits::ChannelMessage {
+                    id: id.to_string(),
+                    sender: sender.to_string(),
+                    reply_target: sender.to_string(),
+                    content: "hello".to_string(),
+                    channel: "telegram".to_string(),
+                    timestamp: 1,
+                    thread_ts: None,
+                    group: None,
+                },
+                CancellationToken::new(),
+            )
+            .await;
+        }
+
+        assert_eq!(agent_provider_impl.call_count.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            agent_provider_impl
+                .models
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .as_slice(),
+            &["agent-model".to_string()]
+        );
+        // Unrouted senders stay on the default agent.
+        assert_eq!(default_provider_impl.call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn autonomy_profiles_prefer_agent_profile_over_channel() {
+        let profile = |excluded: &str| ProfileTools {
+            tools: Arc::new(vec![]),
+            non_cli_excluded_tools: Arc::new(vec![excluded.to_string()]),
+        };
+        let profiles = AutonomyProfiles {
+            profiles: HashMap::from([
+                ("assistant".to_string(), profile("shell")),
+                ("coding".to_string(), profile("browser")),
+            ]),
+            channels: HashMap::from([("telegram".to_string(), "assistant".to_string())]),
+        };
+        let agent = AgentProfile {
+            id: "dev".into(),
+            provider: None,
+            model: None,
+            system_prompt: None,
+            tools: vec![],
+            memory_namespace: None,
+            autonomy: Some("coding".into()),
+        };
+        let excluded = |selected: Option<&ProfileTools>| {
+            selected.map(|profile| profile.non_cli_excluded_tools[0].clone())
+        };
+
+        assert_eq!(
+            excluded(profiles.select("telegram", None)).as_deref(),
+            Some("shell")
+        );
+        assert_eq!(
+            excluded(profiles.select("telegram", Some(&agent))).as_deref(),
+            Some("browser")
+        );
+        assert!(profiles.select("discord", None).is_none());
+    }
+
     #[tokio::test]
     async fn process_channel_message_prefers_cached_default_provider_instance() {
         let channel_impl = Arc::new(TelegramRecordingChannel::default());
@@ -3748,6 +5376,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("default-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3763,6 +5392,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3775,6 +5418,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 3,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3827,6 +5471,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("startup-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3845,6 +5490,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3857,6 +5516,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 4,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3898,6 +5558,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3913,6 +5574,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3925,6 +5600,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -3955,6 +5631,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -3970,6 +5647,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -3982,6 +5673,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -4084,29 +5776,221 @@ BTC is currently around $65,000 based on latest tool output."#
             Ok(None)
         }
 
-        async fn list(
-            &self,
-            _category: Option<&crate::memory::MemoryCategory>,
-            _session_id: Option<&str>,
-        ) -> anyhow::Result<Vec<crate::memory::MemoryEntry>> {
-            Ok(Vec::new())
-        }
+        async fn list(
+            &self,
+            _category: Option<&crate::memory::MemoryCategory>,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<Vec<crate::memory::MemoryEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self) -> anyhow::Result<usize> {
+            Ok(1)
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn message_dispatch_processes_messages_in_parallel() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(SlowProvider {
+                delay: Duration::from_millis(250),
+            }),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 10,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        });
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
+        tx.send(traits::ChannelMessage {
+            id: "1".to_string(),
+            sender: "alice".to_string(),
+            reply_target: "alice".to_string(),
+            content: "hello".to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+            thread_ts: None,
+            group: None,
+        })
+        .await
+        .unwrap();
+        tx.send(traits::ChannelMessage {
+            id: "2".to_string(),
+            sender: "bob".to_string(),
+            reply_target: "bob".to_string(),
+            content: "world".to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 2,
+            thread_ts: None,
+            group: None,
+        })
+        .await
+        .unwrap();
+        drop(tx);
+
+        let started = Instant::now();
+        run_message_dispatch_loop(
+            rx,
+            runtime_ctx,
+            2,
+            &crate::infra::shutdown::Shutdown::default(),
+        )
+        .await;
+        let elapsed = started.elapsed();
+
+        assert!(
+            elapsed < Duration::from_millis(430),
+            "expected parallel dispatch (<430ms), got {:?}",
+            elapsed
+        );
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn message_dispatch_serializes_session_and_rejects_overflow() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::new(SlowProvider {
+                delay: Duration::from_millis(20),
+            }),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 10,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig {
+                max_queued_messages: 1,
+                coalesce: true,
+                coalesce_window_ms: 0,
+            },
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        });
 
-        async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
-            Ok(false)
+        // All four are buffered before the dispatcher runs: the first starts
+        // a turn, the second is merged into it, the rest overflow.
+        let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
+        for (id, content) in [
+            ("1", "first"),
+            ("2", "second"),
+            ("3", "third"),
+            ("4", "fourth"),
+        ] {
+            tx.send(traits::ChannelMessage {
+                id: id.to_string(),
+                sender: "alice".to_string(),
+                reply_target: "alice".to_string(),
+                content: content.to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                thread_ts: None,
+                group: None,
+            })
+            .await
+            .unwrap();
         }
+        drop(tx);
 
-        async fn count(&self) -> anyhow::Result<usize> {
-            Ok(1)
-        }
+        run_message_dispatch_loop(
+            rx,
+            runtime_ctx,
+            2,
+            &crate::infra::shutdown::Shutdown::default(),
+        )
+        .await;
 
-        async fn health_check(&self) -> bool {
-            true
-        }
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        let overflow = format!("alice:{}", i18n::text(Text::QueueOverflow));
+        assert_eq!(sent_messages.iter().filter(|m| **m == overflow).count(), 2);
+        let replies: Vec<_> = sent_messages.iter().filter(|m| **m != overflow).collect();
+        assert_eq!(replies.len(), 1);
+        assert!(replies[0].contains("first") && replies[0].contains("second"));
+        assert!(!replies[0].contains("third"));
     }
 
     #[tokio::test]
-    async fn message_dispatch_processes_messages_in_parallel() {
+    async fn message_dispatch_stops_taking_messages_after_shutdown() {
         let channel_impl = Arc::new(RecordingChannel::default());
         let channel: Arc<dyn Channel> = channel_impl.clone();
 
@@ -4116,13 +6000,14 @@ BTC is currently around $65,000 based on latest tool output."#
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
             channels_by_name: Arc::new(channels_by_name),
             provider: Arc::new(SlowProvider {
-                delay: Duration::from_millis(250),
+                delay: Duration::from_millis(10),
             }),
             default_provider: Arc::new("test-provider".to_string()),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4138,8 +6023,23 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
+        // The sender stays open: only the shutdown trigger ends the loop.
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
         tx.send(traits::ChannelMessage {
             id: "1".to_string(),
@@ -4149,34 +6049,42 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "test-channel".to_string(),
             timestamp: 1,
             thread_ts: None,
+            group: None,
         })
         .await
         .unwrap();
-        tx.send(traits::ChannelMessage {
-            id: "2".to_string(),
-            sender: "bob".to_string(),
-            reply_target: "bob".to_string(),
-            content: "world".to_string(),
-            channel: "test-channel".to_string(),
-            timestamp: 2,
-            thread_ts: None,
-        })
+
+        let shutdown = crate::infra::shutdown::Shutdown::new(Duration::from_secs(5));
+        shutdown.trigger();
+        tokio::time::timeout(
+            Duration::from_secs(2),
+            run_message_dispatch_loop(rx, runtime_ctx, 2, &shutdown),
+        )
         .await
-        .unwrap();
+        .expect("dispatch loop should exit once shutdown is triggered");
+
+        assert!(channel_impl.sent_messages.lock().await.is_empty());
         drop(tx);
+    }
 
-        let started = Instant::now();
-        run_message_dispatch_loop(rx, runtime_ctx, 2).await;
-        let elapsed = started.elapsed();
+    #[test]
+    fn conversation_histories_round_trip_through_state_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = channel_history_state_path(tmp.path());
+        assert!(load_conversation_histories(&path).is_empty());
 
-        assert!(
-            elapsed < Duration::from_millis(430),
-            "expected parallel dispatch (<430ms), got {:?}",
-            elapsed
-        );
+        let histories: ConversationHistoryMap = Arc::new(Mutex::new(HashMap::from([(
+            "telegram_alice".to_string(),
+            vec![ChatMessage::user("hi"), ChatMessage::assistant("hello")],
+        )])));
+        save_conversation_histories(&path, &histories).unwrap();
 
-        let sent_messages = channel_impl.sent_messages.lock().await;
-        assert_eq!(sent_messages.len(), 2);
+        let loaded = load_conversation_histories(&path);
+        assert_eq!(loaded["telegram_alice"].len(), 2);
+        assert_eq!(loaded["telegram_alice"][1].content, "hello");
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_conversation_histories(&path).is_empty());
     }
 
     #[tokio::test]
@@ -4200,6 +6108,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4215,6 +6124,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -4227,6 +6150,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             })
             .await
             .unwrap();
@@ -4239,12 +6163,19 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             })
             .await
             .unwrap();
         });
 
-        run_message_dispatch_loop(rx, runtime_ctx, 4).await;
+        run_message_dispatch_loop(
+            rx,
+            runtime_ctx,
+            4,
+            &crate::infra::shutdown::Shutdown::default(),
+        )
+        .await;
         send_task.await.unwrap();
 
         let sent_messages = channel_impl.sent_messages.lock().await;
@@ -4289,6 +6220,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4304,6 +6236,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: true,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(8);
@@ -4316,6 +6262,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             })
             .await
             .unwrap();
@@ -4328,12 +6275,19 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "telegram".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             })
             .await
             .unwrap();
         });
 
-        run_message_dispatch_loop(rx, runtime_ctx, 4).await;
+        run_message_dispatch_loop(
+            rx,
+            runtime_ctx,
+            4,
+            &crate::infra::shutdown::Shutdown::default(),
+        )
+        .await;
         send_task.await.unwrap();
 
         let sent_messages = channel_impl.sent_messages.lock().await;
@@ -4360,6 +6314,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4375,6 +6330,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -4387,6 +6356,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -4416,6 +6386,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4431,6 +6402,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -4443,6 +6428,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -4568,6 +6554,33 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(prompt.contains("[File not found: IDENTITY.md]"));
     }
 
+    #[test]
+    fn prompt_uses_channel_overrides_for_prompt_files() {
+        let ws = make_workspace();
+        std::fs::write(ws.path().join("INSTRUCTIONS.md"), "Answer in English.").unwrap();
+        let overrides = ws.path().join("prompts").join("telegram");
+        std::fs::create_dir_all(&overrides).unwrap();
+        std::fs::write(overrides.join("IDENTITY.md"), "Name: TeleClaw").unwrap();
+
+        let base = build_system_prompt(ws.path(), "model", &[], None);
+        assert!(base.contains("Name: ZeroClaw"));
+        assert!(base.contains("### INSTRUCTIONS.md"));
+        assert!(!base.contains("### CONTEXT.md"));
+
+        let telegram = build_system_prompt_for_channel(
+            ws.path(),
+            "model",
+            &[],
+            None,
+            false,
+            "",
+            Some("telegram"),
+        );
+        assert!(telegram.contains("Name: TeleClaw"));
+        assert!(!telegram.contains("Name: ZeroClaw"));
+        assert!(telegram.contains("Answer in English."));
+    }
+
     #[test]
     fn prompt_bootstrap_only_if_exists() {
         let ws = make_workspace();
@@ -4709,6 +6722,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            group: None,
         };
 
         assert_eq!(conversation_memory_key(&msg), "slack_U123_msg_abc123");
@@ -4724,6 +6738,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            group: None,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -4733,6 +6748,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            group: None,
         };
 
         assert_ne!(
@@ -4754,6 +6770,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 1,
             thread_ts: None,
+            group: None,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
@@ -4763,6 +6780,7 @@ BTC is currently around $65,000 based on latest tool output."#
             channel: "slack".into(),
             timestamp: 2,
             thread_ts: None,
+            group: None,
         };
 
         mem.store(
@@ -4819,6 +6837,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4834,6 +6853,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -4846,6 +6879,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -4861,6 +6895,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 2,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -4884,6 +6919,92 @@ BTC is currently around $65,000 based on latest tool output."#
         assert!(calls[1][3].1.contains("follow up"));
     }
 
+    #[tokio::test]
+    async fn process_channel_message_adds_channel_persona_after_base_prompt() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let provider_impl = Arc::new(HistoryCaptureProvider::default());
+        let personas = HashMap::from([(
+            "test-channel".to_string(),
+            crate::config::ChannelPersonaConfig {
+                persona: Some("Friendly and warm.".into()),
+                system_prompt: Some("Greet {user} on {channel}.".into()),
+            },
+        )]);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: provider_impl.clone(),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::new(personas),
+        });
+
+        process_channel_message(
+            runtime_ctx,
+            traits::ChannelMessage {
+                id: "msg-persona".to_string(),
+                sender: "alice".to_string(),
+                reply_target: "chat-1".to_string(),
+                content: "hello".to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                thread_ts: None,
+                group: None,
+            },
+            CancellationToken::new(),
+        )
+        .await;
+
+        let calls = provider_impl
+            .calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0][0].0, "system");
+        assert_eq!(
+            calls[0][0].1,
+            "test-system-prompt\n\n## Channel Persona\n\nFriendly and warm.\n\n\
+             ## Channel Instructions\n\nGreet alice on test-channel."
+        );
+    }
+
     #[tokio::test]
     async fn process_channel_message_enriches_current_turn_without_persisting_context() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -4901,6 +7022,7 @@ BTC is currently around $65,000 based on latest tool output."#
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
@@ -4916,6 +7038,20 @@ BTC is currently around $65,000 based on latest tool output."#
             message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
             interrupt_on_new_message: false,
             non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
         });
 
         process_channel_message(
@@ -4928,6 +7064,7 @@ BTC is currently around $65,000 based on latest tool output."#
                 channel: "test-channel".to_string(),
                 timestamp: 1,
                 thread_ts: None,
+                group: None,
             },
             CancellationToken::new(),
         )
@@ -5256,4 +7393,181 @@ This is an example JSON object for profile settings."#;
     }
 
     // ── E2E: photo [IMAGE:] marker rejected by non-vision provider ───
+
+    #[tokio::test]
+    async fn process_channel_message_rejects_image_for_non_vision_provider() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let provider_impl = Arc::new(HistoryCaptureProvider::default());
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: provider_impl.clone(),
+            default_provider: Arc::new("test-provider".to_string()),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            channel_prompts: Arc::new(HashMap::new()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            max_tool_iterations: 5,
+            min_relevance_score: 0.0,
+            conversation_histories: Arc::new(Mutex::new(HashMap::new())),
+            provider_cache: Arc::new(Mutex::new(HashMap::new())),
+            route_overrides: Arc::new(Mutex::new(HashMap::new())),
+            api_key: None,
+            api_url: None,
+            provider_runtime_options: providers::ProviderRuntimeOptions::default(),
+            workspace_dir: Arc::new(std::env::temp_dir()),
+            message_timeout_secs: CHANNEL_MESSAGE_TIMEOUT_SECS,
+            interrupt_on_new_message: false,
+            non_cli_excluded_tools: Arc::new(Vec::new()),
+            hooks: None,
+            skill_selector: None,
+            tool_dispatch: ToolDispatchMode::Auto,
+            agents: None,
+            multimodal: crate::config::MultimodalConfig::default(),
+            dm_policy: None,
+            route_preferences: None,
+            message_queue: crate::config::MessageQueueConfig::default(),
+            draft_streaming: crate::config::DraftStreamingConfig::default(),
+            groups: None,
+            identities: None,
+            fallback_models: Arc::new(Vec::new()),
+            autonomy_profiles: None,
+            channel_personas: Arc::default(),
+        });
+
+        process_channel_message(
+            runtime_ctx.clone(),
+            traits::ChannelMessage {
+                id: "msg-photo".to_string(),
+                sender: "alice".to_string(),
+                reply_target: "chat-1".to_string(),
+                content: "[IMAGE:https://example.com/cat.png] what is this?".to_string(),
+                channel: "test-channel".to_string(),
+                timestamp: 1,
+                thread_ts: None,
+                group: None,
+            },
+            CancellationToken::new(),
+        )
+        .await;
+
+        assert!(provider_impl
+            .calls
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty());
+        let sent = channel_impl.sent_messages.lock().await;
+        assert!(sent.iter().any(|m| m.contains("capability=vision")));
+    }
+
+    #[derive(Default)]
+    struct DraftRecordingChannel {
+        edits: tokio::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for DraftRecordingChannel {
+        fn name(&self) -> &str {
+            "draft-test"
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn supports_draft_updates(&self) -> bool {
+            true
+        }
+
+        async fn update_draft(
+            &self,
+            _recipient: &str,
+            _message_id: &str,
+            text: &str,
+        ) -> anyhow::Result<()> {
+            self.edits.lock().await.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn draft_updates_are_throttled_and_coalesced() {
+        let channel = Arc::new(DraftRecordingChannel::default());
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+        let updater = tokio::spawn(run_draft_updater(
+            Arc::clone(&channel) as Arc<dyn Channel>,
+            "chat-1".into(),
+            "draft-1".into(),
+            rx,
+            Duration::from_millis(300),
+        ));
+
+        tx.send("Thinking...\n".into()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        tx.send(crate::agent::loop_::DRAFT_CLEAR_SENTINEL.into())
+            .await
+            .unwrap();
+        for word in ["The ", "answer ", "is ", "42."] {
+            tx.send(word.into()).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        tx.send(" Trailing text.".into()).await.unwrap();
+        drop(tx);
+        updater.await.unwrap();
+
+        assert_eq!(
+            *channel.edits.lock().await,
+            vec!["Thinking...\n".to_string(), "The answer is 42.".to_string()]
+        );
+    }
+
+    #[test]
+    fn dm_pairings_are_loaded_from_the_config_dir_not_the_workspace() {
+        use crate::security::dm_policy::DM_PAIRINGS_FILE;
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.config_path = tmp.path().join("config.toml");
+        config.workspace_dir = tmp.path().join("workspace");
+        config.channels_config.dm_policies.insert(
+            "whatsapp".into(),
+            crate::config::DmPolicyConfig {
+                mode: crate::security::DmAccessPolicy::PairingRequired,
+                ..Default::default()
+            },
+        );
+        let paired = r#"{"whatsapp":["+1666"]}"#;
+
+        // A pairing the agent could write into its own workspace is ignored.
+        let state_dir = config.workspace_dir.join("state");
+        std::fs::create_dir_all(&state_dir).unwrap();
+        std::fs::write(state_dir.join(DM_PAIRINGS_FILE), paired).unwrap();
+        let manager = build_dm_policy_manager(&config).unwrap();
+        assert_ne!(
+            manager.evaluate("whatsapp", "+1666", "hi"),
+            DmDecision::Allow
+        );
+
+        std::fs::write(tmp.path().join(DM_PAIRINGS_FILE), paired).unwrap();
+        let manager = build_dm_policy_manager(&config).unwrap();
+        assert_eq!(
+            manager.evaluate("whatsapp", "+1666", "hi"),
+            DmDecision::Allow
+        );
+    }
 }
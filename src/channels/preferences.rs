@@ -0,0 +1,249 @@
+//! Per-route provider/model/temperature preferences.
+//!
+//! Two sources feed a route's preference: `[[route_preferences]]` entries in
+//! the config, and pins users set for themselves with `/pin`. Pins live in a
+//! small SQLite table under `<workspace>/state/` so they survive restarts;
+//! they are loaded once at startup and written through on change, so lookups
+//! on the message path never touch the database. Pins belong to a person key
+//! (see [`crate::identity::IdentityLinks::person_key`]), so they follow a
+//! user across linked channel accounts.
+
+use crate::config::RoutePreferenceConfig;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const PREFERENCES_DB_FILE: &str = "preferences.db";
+
+/// Provider/model/temperature override; unset fields fall through to the
+/// next source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RoutePreference {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+}
+
+impl RoutePreference {
+    pub fn is_empty(&self) -> bool {
+        self.provider.is_none() && self.model.is_none() && self.temperature.is_none()
+    }
+
+    /// Fill fields unset in `self` from `fallback`.
+    fn or(self, fallback: Self) -> Self {
+        Self {
+            provider: self.provider.or(fallback.provider),
+            model: self.model.or(fallback.model),
+            temperature: self.temperature.or(fallback.temperature),
+        }
+    }
+}
+
+impl From<&RoutePreferenceConfig> for RoutePreference {
+    fn from(config: &RoutePreferenceConfig) -> Self {
+        Self {
+            provider: config.provider.clone(),
+            model: config.model.clone(),
+            temperature: config.temperature,
+        }
+    }
+}
+
+/// Configured rules plus persisted per-user pins.
+pub struct RoutePreferences {
+    rules: Vec<RoutePreferenceConfig>,
+    conn: Option<Mutex<Connection>>,
+    pins: Mutex<HashMap<String, RoutePreference>>,
+}
+
+pub fn preferences_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(PREFERENCES_DB_FILE)
+}
+
+impl RoutePreferences {
+    /// Config rules only; `/pin` is unavailable.
+    pub fn from_rules(rules: Vec<RoutePreferenceConfig>) -> Self {
+        Self {
+            rules,
+            conn: None,
+            pins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Config rules plus the pin table at `db_path` (created if missing).
+    pub fn open(rules: Vec<RoutePreferenceConfig>, db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS route_preferences (
+                scope       TEXT PRIMARY KEY,
+                provider    TEXT,
+                model       TEXT,
+                temperature REAL,
+                updated_at  TEXT NOT NULL
+            );",
+        )?;
+        let pins = {
+            let mut stmt =
+                conn.prepare("SELECT scope, provider, model, temperature FROM route_preferences")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    RoutePreference {
+                        provider: row.get(1)?,
+                        model: row.get(2)?,
+                        temperature: row.get(3)?,
+                    },
+                ))
+            })?;
+            rows.collect::<rusqlite::Result<HashMap<_, _>>>()?
+        };
+        Ok(Self {
+            rules,
+            conn: Some(Mutex::new(conn)),
+            pins: Mutex::new(pins),
+        })
+    }
+
+    /// Effective preference for a message: the pin of `person` (the sender's
+    /// person key), then the most specific config rule (sender, then group,
+    /// then `"*"`).
+    pub fn lookup(
+        &self,
+        person: &str,
+        channel: &str,
+        sender: &str,
+        reply_target: &str,
+    ) -> RoutePreference {
+        let pinned = self.pinned(person).unwrap_or_default();
+        let rule = self
+            .rules
+            .iter()
+            .filter(|rule| rule.channel.eq_ignore_ascii_case(channel))
+            .filter_map(|rule| {
+                let pattern = rule.pattern.trim();
+                let rank = if pattern == sender {
+                    0
+                } else if pattern == reply_target {
+                    1
+                } else if pattern == "*" {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, rule))
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, rule)| RoutePreference::from(rule))
+            .unwrap_or_default();
+        pinned.or(rule)
+    }
+
+    pub fn pinned(&self, person: &str) -> Option<RoutePreference> {
+        self.pins.lock().get(person).cloned()
+    }
+
+    /// Persist `preference` for `person`.
+    pub fn pin(&self, person: &str, preference: &RoutePreference) -> Result<()> {
+        let conn = self
+            .conn
+            .as_ref()
+            .context("Route preference storage is unavailable")?;
+        let scope = person.to_string();
+        conn.lock().execute(
+            "INSERT INTO route_preferences (scope, provider, model, temperature, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(scope) DO UPDATE SET
+                provider = excluded.provider,
+                model = excluded.model,
+                temperature = excluded.temperature,
+                updated_at = excluded.updated_at",
+            params![
+                scope,
+                preference.provider,
+                preference.model,
+                preference.temperature,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        self.pins.lock().insert(scope, preference.clone());
+        Ok(())
+    }
+
+    /// Remove the pin for `person`. Returns whether one existed.
+    pub fn unpin(&self, person: &str) -> Result<bool> {
+        if let Some(conn) = self.conn.as_ref() {
+            conn.lock().execute(
+                "DELETE FROM route_preferences WHERE scope = ?1",
+                params![person],
+            )?;
+        }
+        Ok(self.pins.lock().remove(person).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, model: &str) -> RoutePreferenceConfig {
+        RoutePreferenceConfig {
+            channel: "telegram".into(),
+            pattern: pattern.into(),
+            provider: None,
+            model: Some(model.into()),
+            temperature: None,
+        }
+    }
+
+    #[test]
+    fn most_specific_rule_wins() {
+        let prefs = RoutePreferences::from_rules(vec![
+            rule("*", "wide"),
+            rule("group-1", "group"),
+            rule("alice", "personal"),
+        ]);
+        let model = |sender, target| {
+            prefs
+                .lookup(&format!("telegram:{sender}"), "telegram", sender, target)
+                .model
+        };
+        assert_eq!(model("alice", "group-1").as_deref(), Some("personal"));
+        assert_eq!(model("bob", "group-1").as_deref(), Some("group"));
+        assert_eq!(model("bob", "bob").as_deref(), Some("wide"));
+        assert!(prefs
+            .lookup("whatsapp:alice", "whatsapp", "alice", "alice")
+            .is_empty());
+    }
+
+    #[test]
+    fn pins_persist_and_override_config_field_by_field() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db_path = preferences_db_path(tmp.path());
+        let mut config_rule = rule("*", "configured");
+        config_rule.temperature = Some(0.9);
+
+        let prefs = RoutePreferences::open(vec![config_rule.clone()], &db_path).unwrap();
+        let pin = RoutePreference {
+            provider: None,
+            model: Some("pinned".into()),
+            temperature: None,
+        };
+        prefs.pin("identity:alice", &pin).unwrap();
+
+        let reopened = RoutePreferences::open(vec![config_rule], &db_path).unwrap();
+        let effective = reopened.lookup("identity:alice", "telegram", "alice", "alice");
+        assert_eq!(effective.model.as_deref(), Some("pinned"));
+        assert_eq!(effective.temperature, Some(0.9));
+
+        assert!(reopened.unpin("identity:alice").unwrap());
+        assert!(!reopened.unpin("identity:alice").unwrap());
+        let reopened = RoutePreferences::open(Vec::new(), &db_path).unwrap();
+        assert!(reopened.pinned("identity:alice").is_none());
+    }
+}
@@ -0,0 +1,227 @@
+//! Per-session message queues for the channel dispatcher.
+//!
+//! Turns for one session (channel + chat + sender) run one at a time. A
+//! message that arrives while its session is mid-turn waits in that
+//! session's queue; when the turn ends, the worker picks up the queued
+//! messages (merged into one turn when `coalesce` is on). Queues are capped
+//! by `[channels_config.queue] max_queued_messages` — the dispatcher answers
+//! overflow with a "still working" reply instead of queueing it.
+
+use super::traits::ChannelMessage;
+use crate::config::MessageQueueConfig;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Outcome of [`SessionQueues::enqueue`].
+#[derive(Debug)]
+pub enum Enqueued {
+    /// The session was idle; the caller must start a worker for it.
+    Start,
+    /// The session is mid-turn; the message waits at position `depth`.
+    Queued { depth: usize },
+    /// The session's queue is full; the message is handed back unqueued.
+    Overflow(ChannelMessage),
+}
+
+/// A session with a running worker. Its entry is removed when the worker
+/// finds the queue empty, so "has an entry" means "has a worker".
+#[derive(Default)]
+struct SessionQueue {
+    pending: VecDeque<ChannelMessage>,
+    /// Cancels the turn currently running for the session.
+    cancellation: Option<CancellationToken>,
+}
+
+impl SessionQueue {
+    /// Messages queued behind a turn. Until the worker takes its first turn,
+    /// the oldest pending message is that turn rather than a queued one.
+    fn waiting(&self) -> usize {
+        self.pending
+            .len()
+            .saturating_sub(usize::from(self.cancellation.is_none()))
+    }
+}
+
+pub struct SessionQueues {
+    config: MessageQueueConfig,
+    sessions: Mutex<HashMap<String, SessionQueue>>,
+}
+
+impl SessionQueues {
+    pub fn new(config: MessageQueueConfig) -> Self {
+        Self {
+            config,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn enqueue(&self, session_key: &str, msg: ChannelMessage) -> Enqueued {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(queue) = sessions.get_mut(session_key) else {
+            let mut queue = SessionQueue::default();
+            queue.pending.push_back(msg);
+            sessions.insert(session_key.to_string(), queue);
+            return Enqueued::Start;
+        };
+        if queue.waiting() >= self.config.max_queued_messages.max(1) {
+            return Enqueued::Overflow(msg);
+        }
+        queue.pending.push_back(msg);
+        Enqueued::Queued {
+            depth: queue.waiting(),
+        }
+    }
+
+    /// Cancel the turn running for `session_key`, if any, so the worker moves
+    /// on to the queued messages.
+    pub fn interrupt(&self, session_key: &str) -> bool {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        match sessions
+            .get(session_key)
+            .and_then(|queue| queue.cancellation.as_ref())
+        {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The next turn for `session_key`, after the configured coalesce window.
+    /// Returns `None` (and retires the session) once its queue is empty.
+    pub async fn next_turn(
+        &self,
+        session_key: &str,
+    ) -> Option<(ChannelMessage, CancellationToken)> {
+        if self.config.coalesce_window_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.config.coalesce_window_ms)).await;
+        }
+        self.take_turn(session_key)
+    }
+
+    fn take_turn(&self, session_key: &str) -> Option<(ChannelMessage, CancellationToken)> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = sessions.get_mut(session_key)?;
+        let msg = if self.config.coalesce {
+            coalesce(queue.pending.drain(..).collect())
+        } else {
+            queue.pending.pop_front()
+        };
+        let Some(msg) = msg else {
+            sessions.remove(session_key);
+            return None;
+        };
+        let cancellation = CancellationToken::new();
+        queue.cancellation = Some(cancellation.clone());
+        Some((msg, cancellation))
+    }
+
+    /// Messages waiting across all sessions (excluding running turns).
+    pub fn depth(&self) -> usize {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(SessionQueue::waiting)
+            .sum()
+    }
+}
+
+/// Merge queued messages into one turn. The result takes its id, timestamp
+/// and thread from the latest message, so the reply answers that one.
+fn coalesce(messages: Vec<ChannelMessage>) -> Option<ChannelMessage> {
+    let mut messages = messages.into_iter();
+    let mut merged = messages.next()?;
+    for msg in messages {
+        merged.content.push_str("\n\n");
+        merged.content.push_str(&msg.content);
+        merged.id = msg.id;
+        merged.timestamp = msg.timestamp;
+        merged.thread_ts = msg.thread_ts;
+    }
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: "alice".into(),
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            thread_ts: None,
+            group: None,
+        }
+    }
+
+    fn queues(max_queued_messages: usize, coalesce: bool) -> SessionQueues {
+        SessionQueues::new(MessageQueueConfig {
+            max_queued_messages,
+            coalesce,
+            coalesce_window_ms: 0,
+        })
+    }
+
+    #[test]
+    fn second_message_waits_and_overflow_is_handed_back() {
+        let queues = queues(1, false);
+        assert!(matches!(
+            queues.enqueue("s", message("1", "a")),
+            Enqueued::Start
+        ));
+        assert!(matches!(
+            queues.enqueue("s", message("0", "early")),
+            Enqueued::Queued { depth: 1 }
+        ));
+        let (first, _) = queues.take_turn("s").unwrap();
+        assert_eq!(first.id, "1");
+        assert_eq!(queues.take_turn("s").unwrap().0.id, "0");
+
+        assert!(matches!(
+            queues.enqueue("s", message("2", "b")),
+            Enqueued::Queued { depth: 1 }
+        ));
+        assert!(matches!(
+            queues.enqueue("s", message("3", "c")),
+            Enqueued::Overflow(msg) if msg.id == "3"
+        ));
+        assert!(matches!(
+            queues.enqueue("other", message("4", "d")),
+            Enqueued::Start
+        ));
+        assert_eq!(queues.depth(), 1);
+
+        assert_eq!(queues.take_turn("s").unwrap().0.id, "2");
+        assert!(queues.take_turn("s").is_none());
+        assert!(matches!(
+            queues.enqueue("s", message("5", "e")),
+            Enqueued::Start
+        ));
+    }
+
+    #[test]
+    fn queued_messages_coalesce_into_one_turn() {
+        let queues = queues(8, true);
+        queues.enqueue("s", message("1", "first"));
+        let (_, running) = queues.take_turn("s").unwrap();
+        queues.enqueue("s", message("2", "second"));
+        queues.enqueue("s", message("3", "third"));
+
+        assert!(queues.interrupt("s"));
+        assert!(running.is_cancelled());
+
+        let (merged, _) = queues.take_turn("s").unwrap();
+        assert_eq!(merged.content, "second\n\nthird");
+        assert_eq!(merged.id, "3");
+        assert!(queues.take_turn("s").is_none());
+        assert!(!queues.interrupt("s"));
+    }
+}
@@ -0,0 +1,403 @@
+//! Typed validation for `zeroclaw channel add <type> '<json>'`.
+//!
+//! The JSON is deserialized into the channel's config struct, then checked
+//! for unknown keys, missing required fields and credential shapes (Meta
+//! access tokens, GitHub token prefixes, E.164 numbers, `owner/name` repos).
+//! Every problem is reported at once, with the key it concerns. `--verify`
+//! additionally runs the channel's health check against the live API before
+//! anything is written to `config.toml`.
+
+use super::{Channel, GitHubChannel, WhatsAppChannel};
+use crate::config::{Config, GatewayTlsConfig, GitHubConfig, WhatsAppConfig};
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::time::Duration;
+
+/// Channel types `channel add` can configure.
+pub const SUPPORTED_CHANNEL_TYPES: &[&str] = &["whatsapp", "github"];
+
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+const GITHUB_TOKEN_PREFIXES: &[&str] = &["ghp_", "github_pat_", "gho_", "ghu_", "ghs_", "ghr_"];
+
+/// A validated channel configuration, ready to be written to config.
+#[derive(Debug)]
+pub enum ChannelSetup {
+    WhatsApp(WhatsAppConfig),
+    GitHub(GitHubConfig),
+}
+
+impl ChannelSetup {
+    /// Parse and validate `json` for `channel_type`. Relative paths resolve
+    /// against `config_dir`.
+    pub fn parse(channel_type: &str, json: &str, config_dir: &Path) -> Result<Self> {
+        let channel_type = channel_type.trim().to_ascii_lowercase();
+        let raw: Value = serde_json::from_str(json)
+            .with_context(|| format!("{channel_type} config must be a JSON object"))?;
+        let (setup, mut issues) = match channel_type.as_str() {
+            "whatsapp" => {
+                let (config, mut issues) = deserialize::<WhatsAppConfig>(&raw)?;
+                issues.extend(whatsapp_issues(&config));
+                (Self::WhatsApp(config), issues)
+            }
+            "github" => {
+                let (config, mut issues) = deserialize::<GitHubConfig>(&raw)?;
+                issues.extend(github_issues(&config, config_dir));
+                (Self::GitHub(config), issues)
+            }
+            other => bail!(
+                "Unsupported channel type '{other}'. Supported: {}",
+                SUPPORTED_CHANNEL_TYPES.join(", ")
+            ),
+        };
+        if issues.is_empty() {
+            return Ok(setup);
+        }
+        issues.sort();
+        bail!(
+            "Invalid {channel_type} config:\n  - {}",
+            issues.join("\n  - ")
+        )
+    }
+
+    pub fn channel_type(&self) -> &'static str {
+        match self {
+            Self::WhatsApp(_) => "whatsapp",
+            Self::GitHub(_) => "github",
+        }
+    }
+
+    /// Store the config in `config`. Fails when the channel is already
+    /// configured.
+    pub fn apply(self, config: &mut Config) -> Result<()> {
+        let channel_type = self.channel_type();
+        let channels = &mut config.channels_config;
+        let already = match self {
+            Self::WhatsApp(_) => channels.whatsapp.is_some(),
+            Self::GitHub(_) => channels.github.is_some(),
+        };
+        if already {
+            bail!(
+                "A {channel_type} channel is already configured; edit [channels_config.{channel_type}] in config.toml instead"
+            );
+        }
+        match self {
+            Self::WhatsApp(whatsapp) => channels.whatsapp = Some(whatsapp),
+            Self::GitHub(github) => channels.github = Some(github),
+        }
+        Ok(())
+    }
+
+    /// Check the credentials against the live API.
+    pub async fn verify(&self, config_dir: &Path) -> Result<()> {
+        let channel: Box<dyn Channel> = match self {
+            Self::WhatsApp(whatsapp) => {
+                if !whatsapp.is_cloud_config() {
+                    bail!("--verify supports WhatsApp Cloud API configs only (Web mode pairs on first start)");
+                }
+                Box::new(WhatsAppChannel::new(
+                    whatsapp.access_token.clone().unwrap_or_default(),
+                    whatsapp.phone_number_id.clone().unwrap_or_default(),
+                    whatsapp.verify_token.clone().unwrap_or_default(),
+                    whatsapp.allowed_numbers.clone(),
+                ))
+            }
+            Self::GitHub(github) => Box::new(GitHubChannel::from_config(github, config_dir)?),
+        };
+        match tokio::time::timeout(VERIFY_TIMEOUT, channel.health_check()).await {
+            Ok(true) => Ok(()),
+            Ok(false) => bail!(
+                "{} rejected the credentials (health check failed)",
+                self.channel_type()
+            ),
+            Err(_) => bail!(
+                "{} health check timed out after {}s",
+                self.channel_type(),
+                VERIFY_TIMEOUT.as_secs()
+            ),
+        }
+    }
+}
+
+/// Deserialize `raw` into `T`, reporting keys `T` does not know.
+fn deserialize<T: DeserializeOwned + Serialize>(raw: &Value) -> Result<(T, Vec<String>)> {
+    let Some(object) = raw.as_object() else {
+        bail!("channel config must be a JSON object");
+    };
+    let config: T = serde_json::from_value(raw.clone()).context("Invalid channel config")?;
+    let known = serde_json::to_value(&config)?;
+    let mut known_keys: Vec<&str> = known
+        .as_object()
+        .map(|known| known.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    known_keys.sort_unstable();
+    let issues = object
+        .keys()
+        .filter(|key| !known_keys.contains(&key.as_str()))
+        .map(|key| {
+            format!(
+                "{key}: unknown key (expected one of {})",
+                known_keys.join(", ")
+            )
+        })
+        .collect();
+    Ok((config, issues))
+}
+
+fn is_blank(value: Option<&String>) -> bool {
+    value.is_none_or(|v| v.trim().is_empty())
+}
+
+/// `+` followed by 7–15 digits, no leading zero.
+fn is_e164(number: &str) -> bool {
+    number.strip_prefix('+').is_some_and(|digits| {
+        (7..=15).contains(&digits.len())
+            && digits.bytes().all(|b| b.is_ascii_digit())
+            && !digits.starts_with('0')
+    })
+}
+
+fn whatsapp_issues(config: &WhatsAppConfig) -> Vec<String> {
+    let mut issues = Vec::new();
+    if config.phone_number_id.is_none() && config.session_path.is_none() {
+        issues.push(
+            "phone_number_id: required for Cloud API mode (or set session_path for Web mode)"
+                .to_string(),
+        );
+    }
+    if let Some(id) = &config.phone_number_id {
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+            issues.push(
+                "phone_number_id: must be the numeric ID from Meta Business (not the phone number)"
+                    .to_string(),
+            );
+        }
+        if is_blank(config.access_token.as_ref()) {
+            issues.push("access_token: required for Cloud API mode".to_string());
+        }
+        if is_blank(config.verify_token.as_ref()) {
+            issues.push(
+                "verify_token: required for Cloud API mode (any secret string you choose)"
+                    .to_string(),
+            );
+        }
+    }
+    if let Some(token) = config.access_token.as_deref().map(str::trim) {
+        if !token.is_empty() && !token.starts_with("EA") {
+            issues.push(
+                "access_token: does not look like a Meta access token (they start with \"EAA\")"
+                    .to_string(),
+            );
+        }
+    }
+    if let Some(phone) = &config.pair_phone {
+        if !(7..=15).contains(&phone.len()) || !phone.bytes().all(|b| b.is_ascii_digit()) {
+            issues.push(
+                "pair_phone: country code and number as digits only (e.g. \"15551234567\")"
+                    .to_string(),
+            );
+        }
+    }
+    for number in &config.allowed_numbers {
+        if number != "*" && !is_e164(number) {
+            issues.push(format!(
+                "allowed_numbers: \"{number}\" is not an E.164 number (e.g. \"+15551234567\") or \"*\""
+            ));
+        }
+    }
+    issues
+}
+
+fn github_issues(config: &GitHubConfig, config_dir: &Path) -> Vec<String> {
+    let mut issues = Vec::new();
+    let app_fields = [
+        config.app_id.is_some(),
+        config.installation_id.is_some(),
+        config.private_key_path.is_some(),
+    ];
+    if app_fields.iter().any(|set| *set) && !config.is_app_config() {
+        issues.push(
+            "app_id, installation_id, private_key_path: App mode needs all three".to_string(),
+        );
+    }
+    if let Some(key_path) = &config.private_key_path {
+        let path = GatewayTlsConfig::resolve_path(key_path, config_dir);
+        if !path.is_file() {
+            issues.push(format!(
+                "private_key_path: {} does not exist",
+                path.display()
+            ));
+        }
+    }
+    let token = config
+        .access_token
+        .as_deref()
+        .map(str::trim)
+        .filter(|token| !token.is_empty());
+    match token {
+        Some(token) if !GITHUB_TOKEN_PREFIXES.iter().any(|p| token.starts_with(p)) => {
+            issues.push(format!(
+                "access_token: does not look like a GitHub token (expected a prefix such as {})",
+                GITHUB_TOKEN_PREFIXES.join(", ")
+            ));
+        }
+        None if !config.is_app_config() => issues.push(
+            "access_token: required unless app_id, installation_id and private_key_path are set"
+                .to_string(),
+        ),
+        _ => {}
+    }
+    if is_blank(config.webhook_secret.as_ref())
+        && std::env::var("ZEROCLAW_GITHUB_WEBHOOK_SECRET").is_err()
+    {
+        issues.push(
+            "webhook_secret: required to accept deliveries (or set ZEROCLAW_GITHUB_WEBHOOK_SECRET)"
+                .to_string(),
+        );
+    }
+    if !config.mention.trim().starts_with('@') {
+        issues.push("mention: must start with '@' (e.g. \"@zeroclaw\")".to_string());
+    }
+    for repo in &config.allowed_repos {
+        let valid = repo == "*"
+            || repo.split_once('/').is_some_and(|(owner, name)| {
+                !owner.is_empty() && !name.is_empty() && !name.contains('/')
+            });
+        if !valid {
+            issues.push(format!(
+                "allowed_repos: \"{repo}\" must be \"owner/name\" or \"*\""
+            ));
+        }
+    }
+    for user in &config.allowed_users {
+        if user.starts_with('@') {
+            issues.push(format!(
+                "allowed_users: \"{user}\" should be the login without '@'"
+            ));
+        }
+    }
+    if let Some(url) = &config.api_url {
+        if !reqwest::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https")) {
+            issues.push(format!("api_url: \"{url}\" is not an http(s) URL"));
+        }
+    }
+    issues
+}
+
+/// `zeroclaw channel add`: validate, optionally verify, then save.
+pub async fn run_add(config: &Config, channel_type: &str, json: &str, verify: bool) -> Result<()> {
+    let config_dir = config.config_path.parent().unwrap_or(Path::new(""));
+    let setup = ChannelSetup::parse(channel_type, json, config_dir)?;
+    let channel_type = setup.channel_type();
+    if verify {
+        setup.verify(config_dir).await?;
+        println!("✅ {channel_type} credentials verified");
+    }
+    let mut updated = config.clone();
+    setup.apply(&mut updated)?;
+    updated.save().await?;
+    println!(
+        "✅ Added {channel_type} channel to {}",
+        updated.config_path.display()
+    );
+    println!("Restart channels to pick it up: zeroclaw channel start");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(channel_type: &str, json: &str) -> Result<ChannelSetup> {
+        ChannelSetup::parse(channel_type, json, Path::new("/nonexistent"))
+    }
+
+    #[test]
+    fn whatsapp_cloud_config_is_accepted() {
+        let setup = parse(
+            "WhatsApp",
+            r#"{"phone_number_id":"123456789","access_token":"EAAGm0PX4ZCps","verify_token":"s3cret","allowed_numbers":["+15551234567","*"]}"#,
+        )
+        .unwrap();
+        assert_eq!(setup.channel_type(), "whatsapp");
+
+        let mut config = Config::default();
+        setup.apply(&mut config).unwrap();
+        assert!(config.channels_config.whatsapp.unwrap().is_cloud_config());
+    }
+
+    #[test]
+    fn every_whatsapp_problem_is_reported() {
+        let err = parse(
+            "whatsapp",
+            r#"{"phone_number_id":"+1 555 123","access_token":"abc","allowed_numbers":["5551234"],"verify":"x"}"#,
+        )
+        .unwrap_err()
+        .to_string();
+        for key in [
+            "phone_number_id:",
+            "access_token: does not look like a Meta access token",
+            "verify_token: required",
+            "allowed_numbers: \"5551234\"",
+            "verify: unknown key",
+        ] {
+            assert!(err.contains(key), "missing {key:?} in {err}");
+        }
+        assert!(parse("whatsapp", "{}")
+            .unwrap_err()
+            .to_string()
+            .contains("phone_number_id: required"));
+    }
+
+    #[test]
+    fn github_token_shape_and_app_mode_are_checked() {
+        let valid =
+            r#"{"access_token":"ghp_abc123","webhook_secret":"hook","allowed_repos":["acme/api"]}"#;
+        assert!(parse("github", valid).is_ok());
+
+        let err = parse(
+            "github",
+            r#"{"access_token":"abc","webhook_secret":"hook","app_id":1,"mention":"zeroclaw","allowed_repos":["acme"]}"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("access_token: does not look like a GitHub token"));
+        assert!(err.contains("App mode needs all three"));
+        assert!(err.contains("mention: must start with '@'"));
+        assert!(err.contains("allowed_repos: \"acme\""));
+
+        assert!(parse("github", r#"{"app_id":"one"}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid channel config"));
+    }
+
+    #[test]
+    fn unsupported_types_and_duplicates_are_rejected() {
+        assert!(parse("telegram", "{}")
+            .unwrap_err()
+            .to_string()
+            .contains("Supported: whatsapp, github"));
+        assert!(parse("github", "[]").is_err());
+
+        let mut config = Config::default();
+        let valid = r#"{"access_token":"github_pat_x","webhook_secret":"hook"}"#;
+        parse("github", valid).unwrap().apply(&mut config).unwrap();
+        assert!(parse("github", valid)
+            .unwrap()
+            .apply(&mut config)
+            .unwrap_err()
+            .to_string()
+            .contains("already configured"));
+    }
+
+    #[test]
+    fn e164_numbers() {
+        assert!(is_e164("+15551234567"));
+        assert!(!is_e164("15551234567"));
+        assert!(!is_e164("+0123456789"));
+        assert!(!is_e164("+1555"));
+    }
+}
@@ -0,0 +1,255 @@
+//! Runtime supervisor for long-running channel listeners.
+//!
+//! Each channel started by `start_channels` runs as its own supervised task
+//! (see `spawn_supervised_listener`): failures and panics are reported to
+//! `health` as `channel:<name>` and the listener is restarted with
+//! exponential backoff. The supervisor of the running process is published
+//! through [`active`] so the gateway admin API
+//! (`POST /api/channels/{name}/start|stop`) can stop and restart individual
+//! channels without touching the others.
+
+use super::traits::{Channel, ChannelMessage};
+use anyhow::{bail, Result};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+static ACTIVE: Mutex<Option<Arc<ChannelSupervisor>>> = Mutex::new(None);
+
+/// Supervisor of the channel runtime in this process, if one is running.
+pub fn active() -> Option<Arc<ChannelSupervisor>> {
+    ACTIVE.lock().clone()
+}
+
+pub(crate) fn install(supervisor: Arc<ChannelSupervisor>) {
+    *ACTIVE.lock() = Some(supervisor);
+}
+
+pub(crate) fn uninstall() {
+    ACTIVE.lock().take();
+}
+
+struct Listener {
+    channel: Arc<dyn Channel>,
+    handle: Option<JoinHandle<()>>,
+}
+
+pub struct ChannelSupervisor {
+    tx: mpsc::Sender<ChannelMessage>,
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+    listeners: Mutex<BTreeMap<String, Listener>>,
+}
+
+impl ChannelSupervisor {
+    /// Supervisor for `channels`, all stopped until [`Self::start_all`].
+    /// Listeners deliver to `tx`, which the supervisor keeps open so stopped
+    /// channels can be started again.
+    pub fn new(
+        channels: &[Arc<dyn Channel>],
+        tx: mpsc::Sender<ChannelMessage>,
+        initial_backoff_secs: u64,
+        max_backoff_secs: u64,
+    ) -> Self {
+        let listeners = channels
+            .iter()
+            .map(|channel| {
+                (
+                    channel.name().to_string(),
+                    Listener {
+                        channel: Arc::clone(channel),
+                        handle: None,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            tx,
+            initial_backoff_secs,
+            max_backoff_secs,
+            listeners: Mutex::new(listeners),
+        }
+    }
+
+    /// Names of the supervised channels.
+    pub fn names(&self) -> Vec<String> {
+        self.listeners.lock().keys().cloned().collect()
+    }
+
+    /// `None` when `name` is not supervised here.
+    pub fn is_running(&self, name: &str) -> Option<bool> {
+        self.listeners.lock().get(name).map(Listener::is_running)
+    }
+
+    pub fn start_all(&self) {
+        for name in self.names() {
+            let _ = self.start(&name);
+        }
+    }
+
+    /// Start a stopped channel. Returns `false` if it was already running.
+    pub fn start(&self, name: &str) -> Result<bool> {
+        let mut listeners = self.listeners.lock();
+        let Some(listener) = listeners.get_mut(name) else {
+            bail!("Channel '{name}' is not configured");
+        };
+        if listener.is_running() {
+            return Ok(false);
+        }
+        listener.handle = Some(super::spawn_supervised_listener(
+            Arc::clone(&listener.channel),
+            self.tx.clone(),
+            self.initial_backoff_secs,
+            self.max_backoff_secs,
+        ));
+        Ok(true)
+    }
+
+    /// Stop a running channel. Returns `false` if it was already stopped.
+    pub fn stop(&self, name: &str) -> Result<bool> {
+        let mut listeners = self.listeners.lock();
+        let Some(listener) = listeners.get_mut(name) else {
+            bail!("Channel '{name}' is not configured");
+        };
+        let Some(handle) = listener.handle.take() else {
+            return Ok(false);
+        };
+        let was_running = !handle.is_finished();
+        handle.abort();
+        crate::health::mark_component_stopped(&format!("channel:{name}"));
+        Ok(was_running)
+    }
+
+    pub fn stop_all(&self) {
+        for listener in self.listeners.lock().values_mut() {
+            if let Some(handle) = listener.handle.take() {
+                handle.abort();
+            }
+        }
+    }
+}
+
+impl Listener {
+    fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|h| !h.is_finished())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::SendMessage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    struct PanickingChannel {
+        name: String,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for PanickingChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            panic!("listener exploded");
+        }
+    }
+
+    struct IdleChannel {
+        name: String,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for IdleChannel {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn send(&self, _message: &SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+            tx.closed().await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_channel_is_restarted_without_affecting_others() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let panicking = format!("test-panic-{}", uuid::Uuid::new_v4());
+        let idle = format!("test-idle-{}", uuid::Uuid::new_v4());
+        let channels: Vec<Arc<dyn Channel>> = vec![
+            Arc::new(PanickingChannel {
+                name: panicking.clone(),
+                calls: Arc::clone(&calls),
+            }),
+            Arc::new(IdleChannel { name: idle.clone() }),
+        ];
+        let (tx, _rx) = mpsc::channel(1);
+        let supervisor = ChannelSupervisor::new(&channels, tx, 1, 1);
+        supervisor.start_all();
+
+        // The restart happens after the 1s backoff; allow for a loaded machine.
+        for _ in 0..100 {
+            if calls.load(Ordering::SeqCst) >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(
+            calls.load(Ordering::SeqCst) >= 2,
+            "panicked listener restarts"
+        );
+        assert_eq!(supervisor.is_running(&panicking), Some(true));
+        assert_eq!(supervisor.is_running(&idle), Some(true));
+
+        let health = crate::health::snapshot();
+        let component = &health.components[&format!("channel:{panicking}")];
+        assert_eq!(component.status, "error");
+        assert!(component
+            .last_error
+            .as_deref()
+            .unwrap_or_default()
+            .contains("listener exploded"));
+        assert_eq!(health.components[&format!("channel:{idle}")].status, "ok");
+        supervisor.stop_all();
+    }
+
+    #[tokio::test]
+    async fn stop_and_start_individual_channels() {
+        let name = format!("test-toggle-{}", uuid::Uuid::new_v4());
+        let channels: Vec<Arc<dyn Channel>> = vec![Arc::new(IdleChannel { name: name.clone() })];
+        let (tx, _rx) = mpsc::channel(1);
+        let supervisor = ChannelSupervisor::new(&channels, tx, 1, 1);
+
+        assert_eq!(supervisor.is_running(&name), Some(false));
+        assert!(supervisor.start(&name).unwrap());
+        assert!(!supervisor.start(&name).unwrap());
+
+        assert!(supervisor.stop(&name).unwrap());
+        assert_eq!(supervisor.is_running(&name), Some(false));
+        assert_eq!(
+            crate::health::snapshot().components[&format!("channel:{name}")].status,
+            "stopped"
+        );
+        assert!(!supervisor.stop(&name).unwrap());
+
+        assert!(supervisor.start(&name).unwrap());
+        assert_eq!(supervisor.is_running(&name), Some(true));
+        assert!(supervisor.start("missing").is_err());
+        assert_eq!(supervisor.is_running("missing"), None);
+        supervisor.stop_all();
+    }
+}
@@ -12,6 +12,18 @@ pub struct ChannelMessage {
     /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
     /// When set, replies should be posted as threaded responses.
     pub thread_ts: Option<String>,
+    /// Set for messages posted in a group chat (Discord guild channel, Slack
+    /// channel, Telegram group); `None` for direct messages.
+    pub group: Option<GroupInfo>,
+}
+
+/// How a group-chat message addresses the bot, as reported by the platform.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupInfo {
+    /// The message mentions the bot (e.g. a Discord/Slack/Telegram mention entity).
+    pub mentions_bot: bool,
+    /// The message is a reply to one of the bot's own messages.
+    pub replies_to_bot: bool,
 }
 
 /// Message to send through a channel
@@ -22,6 +34,10 @@ pub struct SendMessage {
     pub subject: Option<String>,
     /// Platform thread identifier for threaded replies (e.g. Slack `thread_ts`).
     pub thread_ts: Option<String>,
+    /// Platform id of the message this one answers, for channels that show
+    /// inline replies (e.g. WhatsApp message context, Telegram
+    /// `reply_to_message_id`). Channels without reply support ignore it.
+    pub quoted_message_id: Option<String>,
 }
 
 impl SendMessage {
@@ -32,6 +48,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: None,
             thread_ts: None,
+            quoted_message_id: None,
         }
     }
 
@@ -46,6 +63,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: Some(subject.into()),
             thread_ts: None,
+            quoted_message_id: None,
         }
     }
 
@@ -54,6 +72,12 @@ impl SendMessage {
         self.thread_ts = thread_ts;
         self
     }
+
+    /// Set the id of the message this one replies to.
+    pub fn quoting(mut self, message_id: Option<String>) -> Self {
+        self.quoted_message_id = message_id;
+        self
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -73,6 +97,12 @@ pub trait Channel: Send + Sync {
         true
     }
 
+    /// Point the platform's webhook at the gateway's public base URL (e.g. a
+    /// tunnel URL). Webhook-driven channels override this; others ignore it.
+    async fn register_webhook(&self, _public_url: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Signal that the bot is processing a response (e.g. "typing" indicator).
     /// Implementations should repeat the indicator as needed for their platform.
     async fn start_typing(&self, _recipient: &str) -> anyhow::Result<()> {
@@ -84,6 +114,18 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Send an audio clip (e.g. a synthesized voice reply) as a native
+    /// audio message. Channels without audio support return an error so
+    /// callers can fall back to [`Self::send`].
+    async fn send_audio(
+        &self,
+        _recipient: &str,
+        _audio: &[u8],
+        _mime_type: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("{} channel does not support audio messages", self.name())
+    }
+
     /// Whether this channel supports progressive message updates via draft edits.
     fn supports_draft_updates(&self) -> bool {
         false
@@ -172,6 +214,7 @@ mod tests {
                 channel: "dummy".into(),
                 timestamp: 123,
                 thread_ts: None,
+                group: None,
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -188,6 +231,7 @@ mod tests {
             channel: "dummy".into(),
             timestamp: 999,
             thread_ts: None,
+            group: None,
         };
 
         let cloned = message.clone();
@@ -244,6 +288,17 @@ mod tests {
         assert!(channel.cancel_draft("bob", "msg_1").await.is_ok());
     }
 
+    #[tokio::test]
+    async fn default_send_audio_is_unsupported() {
+        let channel = DummyChannel;
+
+        let err = channel
+            .send_audio("bob", b"OggS", "audio/ogg")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("does not support audio"));
+    }
+
     #[tokio::test]
     async fn listen_sends_message_to_channel() {
         let channel = DummyChannel;
@@ -17,6 +17,9 @@ fn ensure_https(url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Placeholder content for an inbound voice note, carrying its media ID.
+const VOICE_NOTE_PREFIX: &str = "[VOICE:";
+
 ///
 /// # Runtime Negotiation
 ///
@@ -27,6 +30,7 @@ pub struct WhatsAppChannel {
     endpoint_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    voice_notes: bool,
 }
 
 impl WhatsAppChannel {
@@ -41,9 +45,17 @@ impl WhatsAppChannel {
             endpoint_id,
             verify_token,
             allowed_numbers,
+            voice_notes: false,
         }
     }
 
+    /// Deliver voice notes as placeholders for transcription instead of
+    /// skipping them like other media.
+    pub fn with_voice_notes(mut self, enabled: bool) -> Self {
+        self.voice_notes = enabled;
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.whatsapp")
     }
@@ -58,6 +70,67 @@ impl WhatsAppChannel {
         &self.verify_token
     }
 
+    /// Media ID of a voice note placeholder produced by
+    /// [`Self::parse_webhook_payload`], e.g. `[VOICE:1234]`.
+    pub fn voice_note_media_id(content: &str) -> Option<&str> {
+        content
+            .strip_prefix(VOICE_NOTE_PREFIX)?
+            .strip_suffix(']')
+            .filter(|id| !id.is_empty())
+    }
+
+    /// Download a media attachment (e.g. a voice note) via the Graph API.
+    /// Returns the bytes and their MIME type.
+    pub async fn download_media(
+        &self,
+        media_id: &str,
+        max_bytes: u64,
+    ) -> anyhow::Result<(Vec<u8>, String)> {
+        let url = format!("https://graph.facebook.com/v18.0/{media_id}");
+        ensure_https(&url)?;
+        let meta: serde_json::Value = self
+            .http_client()
+            .get(&url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let file_size = meta
+            .get("file_size")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        if file_size > max_bytes {
+            anyhow::bail!(
+                "WhatsApp media is {file_size} bytes, exceeding the {max_bytes} byte limit"
+            );
+        }
+        let Some(media_url) = meta.get("url").and_then(|u| u.as_str()) else {
+            anyhow::bail!("WhatsApp media lookup returned no URL");
+        };
+        ensure_https(media_url)?;
+        let mime_type = meta
+            .get("mime_type")
+            .and_then(|m| m.as_str())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let bytes = self
+            .http_client()
+            .get(media_url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        if bytes.len() as u64 > max_bytes {
+            anyhow::bail!("WhatsApp media exceeds the {max_bytes} byte limit");
+        }
+        Ok((bytes.to_vec(), mime_type))
+    }
+
     /// Parse an incoming webhook payload from Meta and extract messages
     pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
         let mut messages = Vec::new();
@@ -105,15 +178,23 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
+                    // Extract text content; voice notes become a placeholder
+                    // the gateway replaces with their transcript.
                     let content = if let Some(text_obj) = msg.get("text") {
                         text_obj
                             .get("body")
                             .and_then(|b| b.as_str())
                             .unwrap_or("")
                             .to_string()
+                    } else if let Some(media_id) = msg
+                        .get("audio")
+                        .and_then(|a| a.get("id"))
+                        .and_then(|i| i.as_str())
+                        .filter(|i| self.voice_notes && !i.is_empty())
+                    {
+                        format!("{VOICE_NOTE_PREFIX}{media_id}]")
                     } else {
-                        // Could be image, audio, etc. — skip for now
+                        // Could be image, document, etc. — skip for now
                         tracing::debug!("WhatsApp: skipping non-text message from {from}");
                         continue;
                     };
@@ -134,14 +215,22 @@ impl WhatsAppChannel {
                                 .as_secs()
                         });
 
+                    // Meta's message ID (wamid) lets retried deliveries be deduplicated
+                    let id = msg
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .filter(|i| !i.is_empty())
+                        .map_or_else(|| Uuid::new_v4().to_string(), str::to_string);
+
                     messages.push(ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                        id,
                         reply_target: normalized_from.clone(),
                         sender: normalized_from,
                         content,
                         channel: "whatsapp".to_string(),
                         timestamp,
                         thread_ts: None,
+                        group: None,
                     });
                 }
             }
@@ -170,7 +259,7 @@ impl Channel for WhatsAppChannel {
             .strip_prefix('+')
             .unwrap_or(&message.recipient);
 
-        let body = serde_json::json!({
+        let mut body = serde_json::json!({
             "messaging_product": "whatsapp",
             "recipient_type": "individual",
             "to": to,
@@ -180,6 +269,9 @@ impl Channel for WhatsAppChannel {
                 "body": message.content
             }
         });
+        if let Some(message_id) = message.quoted_message_id.as_deref() {
+            body["context"] = serde_json::json!({ "message_id": message_id });
+        }
 
         ensure_https(&url)?;
 
@@ -202,6 +294,70 @@ impl Channel for WhatsAppChannel {
         Ok(())
     }
 
+    async fn send_audio(
+        &self,
+        recipient: &str,
+        audio: &[u8],
+        mime_type: &str,
+    ) -> anyhow::Result<()> {
+        // Upload the clip first, then reference the returned media ID.
+        let upload_url = format!(
+            "https://graph.facebook.com/v18.0/{}/media",
+            self.endpoint_id
+        );
+        ensure_https(&upload_url)?;
+        let file = reqwest::multipart::Part::bytes(audio.to_vec())
+            .file_name(format!(
+                "reply.{}",
+                mime_type.rsplit('/').next().unwrap_or("bin")
+            ))
+            .mime_str(mime_type)?;
+        let form = reqwest::multipart::Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime_type.to_string())
+            .part("file", file);
+        let uploaded: serde_json::Value = self
+            .http_client()
+            .post(&upload_url)
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let Some(media_id) = uploaded.get("id").and_then(|id| id.as_str()) else {
+            anyhow::bail!("WhatsApp media upload returned no ID");
+        };
+
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/messages",
+            self.endpoint_id
+        );
+        let to = recipient.strip_prefix('+').unwrap_or(recipient);
+        let body = serde_json::json!({
+            "messaging_product": "whatsapp",
+            "recipient_type": "individual",
+            "to": to,
+            "type": "audio",
+            "audio": { "id": media_id }
+        });
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp audio send failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+        Ok(())
+    }
+
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         // WhatsApp uses webhooks (push-based), not polling.
         // Messages are received via the gateway's /whatsapp endpoint.
@@ -217,6 +373,34 @@ impl Channel for WhatsAppChannel {
         }
     }
 
+    async fn register_webhook(&self, public_url: &str) -> anyhow::Result<()> {
+        // Per-number callback override, so the app-level webhook is untouched.
+        let callback = format!("{}/whatsapp", public_url.trim_end_matches('/'));
+        ensure_https(&callback)?;
+        let url = format!("https://graph.facebook.com/v18.0/{}", self.endpoint_id);
+        let body = serde_json::json!({
+            "webhook_configuration": {
+                "override_callback_uri": callback,
+                "verify_token": self.verify_token,
+            }
+        });
+
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp webhook registration failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+        Ok(())
+    }
+
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
         let url = format!("https://graph.facebook.com/v18.0/{}", self.endpoint_id);
@@ -323,6 +507,7 @@ mod tests {
         assert_eq!(msgs[0].content, "Hello ZeroClaw!");
         assert_eq!(msgs[0].channel, "whatsapp");
         assert_eq!(msgs[0].timestamp, 1_699_999_999);
+        assert_eq!(msgs[0].id, "wamid.xxx");
     }
 
     #[test]
@@ -370,6 +555,35 @@ mod tests {
         assert!(msgs.is_empty(), "Non-text messages should be skipped");
     }
 
+    #[test]
+    fn whatsapp_parse_voice_note_as_placeholder() {
+        let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()])
+            .with_voice_notes(true);
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [{
+                            "from": "1234567890",
+                            "id": "wamid.voice",
+                            "timestamp": "1699999999",
+                            "type": "audio",
+                            "audio": { "id": "media-42", "mime_type": "audio/ogg; codecs=opus" }
+                        }]
+                    }
+                }]
+            }]
+        });
+
+        let msgs = ch.parse_webhook_payload(&payload);
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(
+            WhatsAppChannel::voice_note_media_id(&msgs[0].content),
+            Some("media-42")
+        );
+        assert_eq!(WhatsAppChannel::voice_note_media_id("hello"), None);
+    }
+
     #[test]
     fn whatsapp_parse_multiple_messages() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
@@ -0,0 +1,538 @@
+//! Dot-path accessors for `config.toml` (`zeroclaw config get/set/unset`).
+//!
+//! Edits are applied to a serialized copy of the config and then deserialized
+//! back into [`Config`], so every write goes through the same schema and
+//! [`Config::validate`] checks as a normal load. When the target key already
+//! exists as a single-line `key = value` entry, the file is patched in place so
+//! surrounding comments survive; otherwise the whole file is re-serialized.
+
+use super::schema::Config;
+use anyhow::{bail, Context, Result};
+
+/// Config paths that hold credentials or token hashes. Values are masked on
+/// `get` unless `--reveal` is passed, including when they are printed as part
+/// of a parent table. `[]` stands for any entry of an array.
+pub const SECRET_CONFIG_PATHS: &[&str] = &[
+    "api_key",
+    "embedding_routes[].api_key",
+    "assistant.calendar.password",
+    "assistant.email.password",
+    "channels_config.whatsapp.access_token",
+    "channels_config.whatsapp.app_secret",
+    "channels_config.whatsapp.verify_token",
+    "channels_config.github.access_token",
+    "channels_config.github.webhook_secret",
+    "gateway.paired_tokens",
+    "gateway.webhook_transforms[].signature_secret",
+    "tools.databases[].url",
+    "transcription.api_key",
+    "tts.api_key",
+];
+
+/// Placeholder printed instead of secret values.
+const MASKED_VALUE: &str = "***";
+
+/// Whether `path` refers to a credential field. Array indices (`[0]`) match
+/// the `[]` wildcard in [`SECRET_CONFIG_PATHS`].
+pub fn is_secret_path(path: &str) -> bool {
+    let mut normalized = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(open) = rest.find('[') {
+        normalized.push_str(&rest[..=open]);
+        rest = &rest[open + 1..];
+        let close = rest.find(']').unwrap_or(rest.len());
+        if !rest[..close].bytes().all(|b| b.is_ascii_digit()) {
+            normalized.push_str(&rest[..close]);
+        }
+        rest = &rest[close..];
+    }
+    normalized.push_str(rest);
+    SECRET_CONFIG_PATHS.contains(&normalized.as_str())
+}
+
+/// Replace every secret in `value`, which sits at `path` (empty for the
+/// config root), with `mask`. Arrays of secrets keep their length.
+pub fn mask_secrets(value: &mut toml::Value, path: &str, mask: &str) {
+    if is_secret_path(path) {
+        match value {
+            toml::Value::Array(items) => {
+                for item in items {
+                    *item = toml::Value::String(mask.into());
+                }
+            }
+            other => *other = toml::Value::String(mask.into()),
+        }
+        return;
+    }
+    match value {
+        toml::Value::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                mask_secrets(child, &child_path(path, key), mask);
+            }
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                mask_secrets(item, &format!("{path}[]"), mask);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Dot path of `key` inside the table at `path`.
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn split_path(path: &str) -> Result<Vec<&str>> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        bail!("Config key must not be empty");
+    }
+    let segments: Vec<&str> = trimmed.split('.').collect();
+    if segments.iter().any(|s| s.trim().is_empty()) {
+        bail!("Invalid config key '{trimmed}': empty path segment");
+    }
+    Ok(segments)
+}
+
+fn config_to_value(config: &Config) -> Result<toml::Value> {
+    toml::Value::try_from(config).context("Failed to serialize config")
+}
+
+/// Follow `segments` from `root`. A segment may index an array, as in
+/// `databases[0]`.
+fn lookup<'a>(root: &'a toml::Value, segments: &[&str]) -> Option<&'a toml::Value> {
+    segments.iter().try_fold(root, |node, segment| {
+        let indexed = segment
+            .strip_suffix(']')
+            .and_then(|s| s.split_once('['))
+            .and_then(|(name, index)| Some((name, index.parse::<usize>().ok()?)));
+        match indexed {
+            Some((name, index)) => node.as_table()?.get(name)?.as_array()?.get(index),
+            None => node.as_table()?.get(*segment),
+        }
+    })
+}
+
+/// Parse a CLI value. TOML literals (numbers, booleans, arrays, quoted
+/// strings) are honored unless the existing value is a string, in which case
+/// the raw text is taken verbatim.
+fn parse_cli_value(raw: &str, current: Option<&toml::Value>) -> toml::Value {
+    if matches!(current, Some(toml::Value::String(_))) {
+        let unquoted = raw
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(raw);
+        return toml::Value::String(unquoted.to_string());
+    }
+
+    let parsed = toml::from_str::<toml::Table>(&format!("value = {raw}"))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()));
+
+    match (current, parsed) {
+        (Some(toml::Value::Float(_)), toml::Value::Integer(i)) => toml::Value::Float(i as f64),
+        (_, parsed) => parsed,
+    }
+}
+
+fn rebuild_config(base: &Config, value: toml::Value) -> Result<Config> {
+    let mut updated: Config = value
+        .try_into()
+        .context("Value does not match the config schema")?;
+    updated.workspace_dir = base.workspace_dir.clone();
+    updated.config_path = base.config_path.clone();
+    updated.validate()?;
+    Ok(updated)
+}
+
+/// Read the value at `path`. Secret fields, including those nested in the
+/// returned table or array, are masked unless `reveal` is set.
+pub fn get_value(config: &Config, path: &str, reveal: bool) -> Result<toml::Value> {
+    let segments = split_path(path)?;
+    let root = config_to_value(config)?;
+    let mut value = lookup(&root, &segments)
+        .with_context(|| format!("Unknown or unset config key: {path}"))?
+        .clone();
+
+    if !reveal {
+        mask_secrets(&mut value, &segments.join("."), MASKED_VALUE);
+    }
+    Ok(value)
+}
+
+/// Render a value for terminal output: scalars inline, tables as TOML.
+pub fn render_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Table(table) => toml::to_string_pretty(table).unwrap_or_default(),
+        other => other.to_string(),
+    }
+}
+
+/// Return a copy of `config` with `path` set to `raw`, validated against the
+/// schema and [`Config::validate`].
+pub fn set_value(config: &Config, path: &str, raw: &str) -> Result<Config> {
+    let segments = split_path(path)?;
+    let mut root = config_to_value(config)?;
+    let new_value = parse_cli_value(raw, lookup(&root, &segments));
+
+    let (last, parents) = segments
+        .split_last()
+        .expect("path has at least one segment");
+    let mut node = &mut root;
+    for segment in parents {
+        let table = node
+            .as_table_mut()
+            .with_context(|| format!("Config key '{path}' does not point into a table"))?;
+        node = table
+            .entry((*segment).to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+    node.as_table_mut()
+        .with_context(|| format!("Config key '{path}' does not point into a table"))?
+        .insert((*last).to_string(), new_value.clone());
+
+    let updated = rebuild_config(config, root)?;
+
+    // Unknown keys are silently dropped by serde; catch them by checking the
+    // value survived the round-trip.
+    let roundtrip = config_to_value(&updated)?;
+    if lookup(&roundtrip, &segments) != Some(&new_value) {
+        bail!("Unknown config key or unsupported value: {path}");
+    }
+    Ok(updated)
+}
+
+/// Return a copy of `config` with `path` removed so the schema default applies.
+pub fn unset_value(config: &Config, path: &str) -> Result<Config> {
+    let segments = split_path(path)?;
+    let mut root = config_to_value(config)?;
+    if lookup(&root, &segments).is_none() {
+        bail!("Unknown or unset config key: {path}");
+    }
+
+    let (last, parents) = segments
+        .split_last()
+        .expect("path has at least one segment");
+    let mut node = &mut root;
+    for segment in parents {
+        node = node
+            .as_table_mut()
+            .and_then(|table| table.get_mut(*segment))
+            .with_context(|| format!("Unknown config key: {path}"))?;
+    }
+    if let Some(table) = node.as_table_mut() {
+        table.remove(*last);
+    }
+
+    rebuild_config(config, root).with_context(|| format!("Config key '{path}' cannot be unset"))
+}
+
+/// Persist an edited config. Patches the single `path` line in place when
+/// possible (preserving comments), falling back to [`Config::save`]. Pass
+/// `removed` for `unset` so the line is dropped instead of rewritten.
+pub async fn persist(updated: &Config, path: &str, removed: bool) -> Result<()> {
+    let segments = split_path(path)?;
+    let encrypted = config_to_value(&updated.with_encrypted_secrets()?)?;
+    let expected = lookup(&encrypted, &segments);
+
+    if let Ok(existing) = tokio::fs::read_to_string(&updated.config_path).await {
+        let replacement = if removed { None } else { expected };
+        if let Some(patched) = patch_toml_text(&existing, &segments, replacement) {
+            let patched_matches = toml::from_str::<Config>(&patched)
+                .ok()
+                .and_then(|parsed| config_to_value(&parsed).ok())
+                .is_some_and(|parsed| lookup(&parsed, &segments) == expected);
+            if patched_matches {
+                return updated.write_config_contents(&patched).await;
+            }
+        }
+    }
+
+    updated.save().await
+}
+
+/// Line-level TOML patch. Returns `None` when the edit cannot be expressed
+/// as a single-line replacement/removal in an existing `[table]`.
+fn patch_toml_text(text: &str, segments: &[&str], value: Option<&toml::Value>) -> Option<String> {
+    let (key, table_path) = segments.split_last()?;
+    let target_table = table_path.join(".");
+    if matches!(value, Some(toml::Value::Table(_))) {
+        return None;
+    }
+
+    let mut current_table = String::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut patched = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("[[") {
+            current_table = String::from("\u{0}array");
+        } else if let Some(header) = trimmed.strip_prefix('[') {
+            current_table = header
+                .split(']')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .replace('"', "");
+        } else if !patched && current_table == target_table {
+            if let Some(rest) = trimmed.strip_prefix(*key) {
+                if let Some(raw_value) = rest.trim_start().strip_prefix('=') {
+                    let raw_value = raw_value.trim();
+                    let multiline = raw_value.starts_with("\"\"\"")
+                        || raw_value.starts_with("'''")
+                        || (raw_value.starts_with('[') && !raw_value.ends_with(']'))
+                        || (raw_value.starts_with('{') && !raw_value.ends_with('}'));
+                    if multiline {
+                        return None;
+                    }
+                    patched = true;
+                    if let Some(value) = value {
+                        let indent = &line[..line.len() - line.trim_start().len()];
+                        lines.push(format!("{indent}{key} = {value}"));
+                    }
+                    continue;
+                }
+            }
+        }
+        lines.push(line.to_string());
+    }
+
+    if !patched {
+        return None;
+    }
+    let mut out = lines.join("\n");
+    if text.ends_with('\n') {
+        out.push('\n');
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    #[test]
+    fn get_value_reads_nested_key() {
+        let config = Config::default();
+        let value = get_value(&config, "memory.backend", false).unwrap();
+        assert_eq!(value.as_str(), Some("sqlite"));
+    }
+
+    #[test]
+    fn get_value_masks_secret_fields() {
+        let mut config = Config::default();
+        config.api_key = Some("sk-live-secret".into());
+        let masked = get_value(&config, "api_key", false).unwrap();
+        assert_eq!(masked.as_str(), Some(MASKED_VALUE));
+        let revealed = get_value(&config, "api_key", true).unwrap();
+        assert_eq!(revealed.as_str(), Some("sk-live-secret"));
+    }
+
+    #[test]
+    fn get_value_masks_secrets_inside_parent_tables() {
+        let mut config = Config::default();
+        config.channels_config.whatsapp = Some(
+            toml::from_str("access_token = \"EAAG-secret\"\napp_secret = \"app-secret\"").unwrap(),
+        );
+        let whatsapp = get_value(&config, "channels_config.whatsapp", false).unwrap();
+        let rendered = render_value(&whatsapp);
+        assert!(!rendered.contains("EAAG-secret"));
+        assert!(!rendered.contains("app-secret"));
+        assert_eq!(whatsapp["access_token"].as_str(), Some(MASKED_VALUE));
+
+        let channels = render_value(&get_value(&config, "channels_config", false).unwrap());
+        assert!(!channels.contains("EAAG-secret"));
+        let revealed = get_value(&config, "channels_config.whatsapp", true).unwrap();
+        assert_eq!(revealed["access_token"].as_str(), Some("EAAG-secret"));
+    }
+
+    #[test]
+    fn get_value_masks_secrets_in_array_entries() {
+        let mut config = Config::default();
+        config.tools.databases =
+            vec![
+                toml::from_str("name = \"app\"\nurl = \"postgres://app:hunter2@db/app\"").unwrap(),
+            ];
+
+        let entry = get_value(&config, "tools.databases[0]", false).unwrap();
+        assert_eq!(entry["name"].as_str(), Some("app"));
+        assert_eq!(entry["url"].as_str(), Some(MASKED_VALUE));
+        let url = get_value(&config, "tools.databases[0].url", false).unwrap();
+        assert_eq!(url.as_str(), Some(MASKED_VALUE));
+        let all = get_value(&config, "tools", false).unwrap();
+        assert!(!all.to_string().contains("hunter2"));
+        assert!(get_value(&config, "tools.databases[1]", false).is_err());
+    }
+
+    #[test]
+    fn is_secret_path_matches_array_wildcards() {
+        assert!(is_secret_path("embedding_routes[2].api_key"));
+        assert!(is_secret_path(
+            "gateway.webhook_transforms[0].signature_secret"
+        ));
+        assert!(!is_secret_path("embedding_routes[2].model"));
+        assert!(!is_secret_path("embedding_routes.api_key"));
+    }
+
+    #[test]
+    fn get_value_rejects_unknown_key() {
+        let config = Config::default();
+        assert!(get_value(&config, "memory.nope", false).is_err());
+        assert!(get_value(&config, "", false).is_err());
+        assert!(get_value(&config, "memory..backend", false).is_err());
+    }
+
+    #[test]
+    fn set_value_parses_enum_and_numbers() {
+        let config = Config::default();
+        let updated = set_value(&config, "autonomy.level", "full").unwrap();
+        assert_eq!(updated.autonomy.level, AutonomyLevel::Full);
+
+        let updated = set_value(&updated, "gateway.port", "8080").unwrap();
+        assert_eq!(updated.gateway.port, 8080);
+    }
+
+    #[test]
+    fn set_value_keeps_string_fields_verbatim() {
+        let config = Config::default();
+        let updated = set_value(&config, "default_model", "123").unwrap();
+        assert_eq!(updated.default_model.as_deref(), Some("123"));
+    }
+
+    #[test]
+    fn set_value_rejects_schema_violations() {
+        let config = Config::default();
+        assert!(set_value(&config, "gateway.port", "not-a-port").is_err());
+        assert!(set_value(&config, "autonomy.level", "yolo").is_err());
+        assert!(set_value(&config, "memory.not_a_field", "1").is_err());
+    }
+
+    #[test]
+    fn set_value_runs_config_validate() {
+        let config = Config::default();
+        let err = set_value(&config, "autonomy.max_actions_per_hour", "0").unwrap_err();
+        assert!(err.to_string().contains("max_actions_per_hour"));
+    }
+
+    #[test]
+    fn unset_value_restores_default() {
+        let mut config = Config::default();
+        config.api_url = Some("http://localhost:11434".into());
+        config.gateway.port = 9000;
+
+        let updated = unset_value(&config, "api_url").unwrap();
+        assert!(updated.api_url.is_none());
+
+        let updated = unset_value(&updated, "gateway.port").unwrap();
+        assert_eq!(updated.gateway.port, 42617);
+    }
+
+    #[test]
+    fn unset_value_rejects_required_field() {
+        let config = Config::default();
+        assert!(unset_value(&config, "default_temperature").is_err());
+    }
+
+    #[test]
+    fn patch_preserves_comments_and_other_lines() {
+        let text = "# top comment\ndefault_temperature = 0.7\n\n[gateway]\n# port comment\nport = 42617\nhost = \"127.0.0.1\"\n";
+        let patched = patch_toml_text(
+            text,
+            &["gateway", "port"],
+            Some(&toml::Value::Integer(8080)),
+        )
+        .unwrap();
+        assert!(patched.contains("# top comment"));
+        assert!(patched.contains("# port comment"));
+        assert!(patched.contains("port = 8080"));
+        assert!(patched.contains("host = \"127.0.0.1\""));
+    }
+
+    #[test]
+    fn patch_removes_line_on_unset() {
+        let text = "api_url = \"http://x\"\ndefault_temperature = 0.7\n";
+        let patched = patch_toml_text(text, &["api_url"], None).unwrap();
+        assert_eq!(patched, "default_temperature = 0.7\n");
+    }
+
+    #[test]
+    fn patch_falls_back_when_key_missing_or_multiline() {
+        let text = "[gateway]\nport = 1\n";
+        assert!(patch_toml_text(text, &["gateway", "host"], Some(&"x".into())).is_none());
+
+        let text = "[autonomy]\nallowed_commands = [\n  \"ls\",\n]\n";
+        assert!(patch_toml_text(
+            text,
+            &["autonomy", "allowed_commands"],
+            Some(&toml::Value::Array(vec![]))
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn patch_does_not_match_key_prefix() {
+        let text = "[gateway]\nport_extra = 1\n";
+        assert!(
+            patch_toml_text(text, &["gateway", "port"], Some(&toml::Value::Integer(2))).is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn persist_patches_existing_line_and_keeps_comments() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.config_path = tmp.path().join("config.toml");
+        config.workspace_dir = tmp.path().join("workspace");
+        config.save().await.unwrap();
+
+        let original = tokio::fs::read_to_string(&config.config_path)
+            .await
+            .unwrap();
+        let commented = format!("# managed by hand\n{original}");
+        tokio::fs::write(&config.config_path, &commented)
+            .await
+            .unwrap();
+
+        let updated = set_value(&config, "gateway.port", "8088").unwrap();
+        persist(&updated, "gateway.port", false).await.unwrap();
+
+        let contents = tokio::fs::read_to_string(&config.config_path)
+            .await
+            .unwrap();
+        assert!(contents.starts_with("# managed by hand"));
+        let reloaded: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(reloaded.gateway.port, 8088);
+    }
+
+    #[tokio::test]
+    async fn persist_encrypts_secret_fields() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.config_path = tmp.path().join("config.toml");
+        config.workspace_dir = tmp.path().join("workspace");
+        config.save().await.unwrap();
+
+        let updated = set_value(&config, "api_key", "sk-config-set").unwrap();
+        persist(&updated, "api_key", false).await.unwrap();
+
+        let contents = tokio::fs::read_to_string(&config.config_path)
+            .await
+            .unwrap();
+        assert!(!contents.contains("sk-config-set"));
+        let reloaded: Config = toml::from_str(&contents).unwrap();
+        assert!(reloaded
+            .api_key
+            .as_deref()
+            .is_some_and(crate::security::SecretStore::is_encrypted));
+    }
+}
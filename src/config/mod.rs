@@ -1,3 +1,4 @@
+pub mod edit;
 pub mod schema;
 pub mod traits;
 pub mod workspace;
@@ -5,9 +6,20 @@ pub mod workspace;
 #[allow(unused_imports)]
 pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
-    build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
-    AgentConfig, AuditConfig, AutonomyConfig, ChannelsConfig, Config, GatewayConfig, MemoryConfig,
-    ObservabilityConfig, ProxyConfig, ProxyScope, RuntimeConfig, SecretsConfig, SecurityConfig,
+    build_runtime_proxy_client_with_timeouts, runtime_http_config, runtime_proxy_config,
+    set_runtime_http_config, set_runtime_proxy_config, AgentConfig, AgentProfileConfig,
+    AgentRouteConfig, ArtifactsConfig, AssistantConfig, AuditConfig, AutonomyConfig, AutonomyProfileConfig,
+    AzureOpenAiConfig, CalendarConfig, CalendarScope, ChannelPersonaConfig, ChannelsConfig, Config, DatabaseConfig,
+    DesktopToolsConfig, DmPolicyConfig, DraftStreamingConfig, EmailConfig, EmailScope,
+    EmbeddingRouteConfig, FeedConfig, FileBackupsConfig, GatewayConfig, GatewayCorsConfig,
+    GatewayCsrfConfig, GatewayTlsConfig,
+    GitHubConfig, GroupChatConfig, HeartbeatConfig, HttpConfig, KubernetesToolConfig,
+    MockChannelConfig,
+    LoggingConfig, MemoryConfig, MessageQueueConfig, MultimodalConfig, ObservabilityConfig,
+    PairedToken, PluginHooksConfig, PluginsConfig, ProviderMiddlewareConfig, ProxyConfig,
+    ProxyScope, RouteBindingConfig, RoutePreferenceConfig, RuntimeConfig, ScheduleConfig,
+    ScheduledTaskConfig, SecretsConfig, SecurityConfig, ToolsConfig, TranscriptionConfig,
+    TtsConfig, TunnelConfig, WebhookTransformConfig, WhatsAppConfig,
 };
 pub use traits::{ConfigIssue, ConfigIssueSeverity, ConfigLoader, ConfigValidator, Workspace};
 pub use workspace::FileWorkspace;
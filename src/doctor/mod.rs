@@ -0,0 +1,399 @@
+//! Full-system diagnostics (`zeroclaw doctor`).
+//!
+//! Runs a fixed set of independent checks — config validity, provider
+//! reachability, memory database integrity, free disk space, clock skew and
+//! workspace permissions — and prints a pass/fail report. Unlike
+//! `zeroclaw channel doctor`, which only probes channel connections, this is
+//! meant as the first thing to run when "something is wrong" on a host.
+
+use crate::config::Config;
+use crate::providers;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Timeout for each network-bound check.
+const NETWORK_CHECK_TIMEOUT_SECS: u64 = 10;
+/// Free space below this on the workspace volume is a warning.
+const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+/// Free space below this is a failure: SQLite and session files stop growing.
+const DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+/// Clock skew above this is a warning (webhook replay windows start to bite).
+const CLOCK_WARN_SECS: i64 = 30;
+/// Clock skew above this is a failure: TLS, OTP and signature checks break.
+const CLOCK_FAIL_SECS: i64 = 300;
+const DEFAULT_PROVIDER_URL: &str = "https://api.openai.com/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// The check could not run (e.g. an earlier prerequisite failed).
+    Skip,
+}
+
+impl CheckStatus {
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Pass => "✅",
+            Self::Warn => "⚠️ ",
+            Self::Fail => "❌",
+            Self::Skip => "⏭️ ",
+        }
+    }
+}
+
+/// Outcome of a single diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    /// `false` when any check failed; warnings and skips still pass.
+    pub passed: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<CheckResult>) -> Self {
+        let passed = checks.iter().all(|c| c.status != CheckStatus::Fail);
+        Self { passed, checks }
+    }
+}
+
+/// Run every check. `config` is the result of loading the config so that a
+/// config that fails to load is reported instead of aborting the command.
+pub async fn diagnose(config: Result<Config>) -> DoctorReport {
+    const DEPENDENT: [&str; 5] = ["provider", "memory", "disk", "clock", "workspace"];
+
+    let config = match config.and_then(|c| c.validate().map(|()| c)) {
+        Ok(config) => config,
+        Err(e) => {
+            let mut checks = vec![CheckResult::new(
+                "config",
+                CheckStatus::Fail,
+                format!("{e:#}"),
+            )];
+            for name in DEPENDENT {
+                checks.push(CheckResult::new(
+                    name,
+                    CheckStatus::Skip,
+                    "config did not load",
+                ));
+            }
+            return DoctorReport::new(checks);
+        }
+    };
+
+    let (provider, clock) = tokio::join!(check_provider(&config), check_clock(&config));
+    DoctorReport::new(vec![
+        CheckResult::new(
+            "config",
+            CheckStatus::Pass,
+            config.config_path.display().to_string(),
+        ),
+        provider,
+        check_memory(&config.workspace_dir).await,
+        check_disk(&config.workspace_dir),
+        clock,
+        check_workspace(&config.workspace_dir),
+    ])
+}
+
+/// `zeroclaw doctor [--json]`. Exits non-zero when any check fails.
+pub async fn run(config: Result<Config>, json: bool) -> Result<()> {
+    let report = diagnose(config).await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("🩺 ZeroClaw Doctor");
+        println!();
+        for check in &report.checks {
+            println!(
+                "  {} {:<9} {}",
+                check.status.icon(),
+                check.name,
+                check.detail
+            );
+        }
+        println!();
+    }
+
+    let failed = report
+        .checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        anyhow::bail!("Doctor found {failed} failing check(s)");
+    }
+    if !json {
+        println!("All checks passed.");
+    }
+    Ok(())
+}
+
+/// A cheap authenticated model list call against the default provider.
+async fn check_provider(config: &Config) -> CheckResult {
+    let name = config.default_provider.as_deref().unwrap_or("openrouter");
+    if providers::resolve_provider_credential(name, config.api_key.as_deref()).is_none() {
+        return CheckResult::new(
+            "provider",
+            CheckStatus::Warn,
+            format!("{name}: no API key configured (set api_key or the provider env var)"),
+        );
+    }
+    let provider = match providers::create_provider_with_url(
+        name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+    ) {
+        Ok(provider) => provider,
+        Err(e) => return CheckResult::new("provider", CheckStatus::Fail, format!("{e:#}")),
+    };
+    let timeout = Duration::from_secs(NETWORK_CHECK_TIMEOUT_SECS);
+    match tokio::time::timeout(timeout, provider.warmup()).await {
+        Ok(Ok(())) => CheckResult::new("provider", CheckStatus::Pass, format!("{name} reachable")),
+        Ok(Err(e)) => CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!(
+                "{name}: {}",
+                providers::sanitize_api_error(&format!("{e:#}"))
+            ),
+        ),
+        Err(_) => CheckResult::new(
+            "provider",
+            CheckStatus::Fail,
+            format!("{name}: timed out after {NETWORK_CHECK_TIMEOUT_SECS}s"),
+        ),
+    }
+}
+
+/// `PRAGMA integrity_check` on the SQLite memory database.
+async fn check_memory(workspace_dir: &Path) -> CheckResult {
+    let db_path = workspace_dir.join("memory").join("brain.db");
+    if !db_path.exists() {
+        return CheckResult::new("memory", CheckStatus::Skip, "brain.db not created yet");
+    }
+    let result = tokio::task::spawn_blocking(move || sqlite_integrity_check(&db_path)).await;
+    match result {
+        Ok(Ok(problems)) if problems.is_empty() => {
+            CheckResult::new("memory", CheckStatus::Pass, "brain.db integrity ok")
+        }
+        Ok(Ok(problems)) => CheckResult::new(
+            "memory",
+            CheckStatus::Fail,
+            format!("brain.db is corrupt: {}", problems.join("; ")),
+        ),
+        Ok(Err(e)) => CheckResult::new("memory", CheckStatus::Fail, format!("{e:#}")),
+        Err(e) => CheckResult::new("memory", CheckStatus::Fail, e.to_string()),
+    }
+}
+
+/// Problems reported by `PRAGMA integrity_check` (empty when the database is
+/// healthy). Opens read-only so a running daemon is not disturbed.
+fn sqlite_integrity_check(db_path: &Path) -> Result<Vec<String>> {
+    use rusqlite::{Connection, OpenFlags};
+
+    let conn = Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Failed to open {}", db_path.display()))?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check(10)")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+fn check_disk(workspace_dir: &Path) -> CheckResult {
+    let Some(available) = available_bytes(workspace_dir) else {
+        return CheckResult::new("disk", CheckStatus::Skip, "free space unavailable");
+    };
+    let status = if available < DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if available < DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    let detail = format!(
+        "{:.1} GiB free",
+        available as f64 / (1024.0 * 1024.0 * 1024.0)
+    );
+    CheckResult::new("disk", status, detail)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn available_bytes(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out-pointer.
+    if unsafe { libc::statvfs(path.as_ptr(), &raw mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn available_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Compare the local clock with the `Date` header of the provider endpoint.
+async fn check_clock(config: &Config) -> CheckResult {
+    let url = config.api_url.as_deref().unwrap_or(DEFAULT_PROVIDER_URL);
+    let name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let client = crate::config::build_runtime_proxy_client_with_timeouts(
+        &format!("provider.{name}"),
+        NETWORK_CHECK_TIMEOUT_SECS,
+        NETWORK_CHECK_TIMEOUT_SECS,
+    );
+    let date = match client.head(url).send().await {
+        Ok(response) => response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        Err(e) => {
+            return CheckResult::new(
+                "clock",
+                CheckStatus::Skip,
+                format!("could not reach {url}: {e}"),
+            )
+        }
+    };
+    let Some(skew) = date
+        .as_deref()
+        .and_then(|d| clock_skew_secs(d, chrono::Utc::now()))
+    else {
+        return CheckResult::new("clock", CheckStatus::Skip, "no Date header to compare");
+    };
+    let status = match skew.abs() {
+        s if s > CLOCK_FAIL_SECS => CheckStatus::Fail,
+        s if s > CLOCK_WARN_SECS => CheckStatus::Warn,
+        _ => CheckStatus::Pass,
+    };
+    CheckResult::new("clock", status, format!("skew {skew:+}s vs {url}"))
+}
+
+/// Seconds the local clock is ahead of an HTTP `Date` header (negative when
+/// behind), or `None` if the header does not parse.
+fn clock_skew_secs(date_header: &str, now: chrono::DateTime<chrono::Utc>) -> Option<i64> {
+    let remote = chrono::DateTime::parse_from_rfc2822(date_header).ok()?;
+    Some((now - remote.with_timezone(&chrono::Utc)).num_seconds())
+}
+
+/// The workspace must exist, be writable, and not be writable by others.
+fn check_workspace(workspace_dir: &Path) -> CheckResult {
+    if !workspace_dir.is_dir() {
+        return CheckResult::new(
+            "workspace",
+            CheckStatus::Fail,
+            format!("{} does not exist", workspace_dir.display()),
+        );
+    }
+    let probe = workspace_dir.join(format!(".doctor-{}", uuid::Uuid::new_v4()));
+    if let Err(e) = std::fs::write(&probe, b"") {
+        return CheckResult::new(
+            "workspace",
+            CheckStatus::Fail,
+            format!("{} is not writable: {e}", workspace_dir.display()),
+        );
+    }
+    let _ = std::fs::remove_file(&probe);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(meta) = std::fs::metadata(workspace_dir) {
+            let mode = meta.permissions().mode() & 0o777;
+            if mode & 0o002 != 0 {
+                return CheckResult::new(
+                    "workspace",
+                    CheckStatus::Warn,
+                    format!(
+                        "{} is world-writable (mode {mode:o}); run chmod 700",
+                        workspace_dir.display()
+                    ),
+                );
+            }
+        }
+    }
+    CheckResult::new(
+        "workspace",
+        CheckStatus::Pass,
+        format!("{} writable", workspace_dir.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_skew_is_signed_seconds_against_http_date() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-01T12:00:45Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            clock_skew_secs("Sun, 01 Mar 2026 12:00:00 GMT", now),
+            Some(45)
+        );
+        assert_eq!(
+            clock_skew_secs("Sun, 01 Mar 2026 12:01:00 GMT", now),
+            Some(-15)
+        );
+        assert_eq!(clock_skew_secs("yesterday", now), None);
+    }
+
+    #[test]
+    fn integrity_check_passes_on_healthy_db_and_workspace_is_writable() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let db_path = tmp.path().join("brain.db");
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE t (x TEXT); INSERT INTO t VALUES ('a');")
+            .unwrap();
+        drop(conn);
+
+        assert!(sqlite_integrity_check(&db_path).unwrap().is_empty());
+        assert_ne!(check_workspace(tmp.path()).status, CheckStatus::Fail);
+        assert_eq!(
+            check_workspace(&tmp.path().join("missing")).status,
+            CheckStatus::Fail
+        );
+    }
+
+    #[tokio::test]
+    async fn unloadable_config_fails_and_skips_dependent_checks() {
+        let report = diagnose(Err(anyhow::anyhow!("bad toml"))).await;
+        assert!(!report.passed);
+        assert_eq!(report.checks[0].status, CheckStatus::Fail);
+        assert!(report.checks[1..]
+            .iter()
+            .all(|c| c.status == CheckStatus::Skip));
+    }
+}
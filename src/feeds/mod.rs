@@ -0,0 +1,646 @@
+//! RSS/Atom feed watcher (`[[feeds]]`).
+//!
+//! Feeds are polled from the heartbeat loop: every feed is a
+//! [`HealthCheck`] that starts a background poll once its interval has
+//! elapsed and reports the outcome of the last poll as `feed:<name>`. Each
+//! new entry is rendered into the feed's prompt template and run through the
+//! agent; the reply is stored in memory and/or delivered to a channel.
+//!
+//! Seen entry IDs are kept under `<workspace>/state/feeds/<name>.json`. The
+//! first poll of a feed only records the entries already published, so
+//! adding a feed does not replay its whole history.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+use crate::channels::{Channel, SendMessage};
+use crate::config::{Config, FeedConfig};
+use crate::infra::heartbeat::HealthCheck;
+use crate::memory::{Memory, MemoryCategory};
+use crate::util::truncate_with_ellipsis;
+
+/// Proxy service key for feed downloads (`[proxy] services`).
+pub const FEEDS_SERVICE_KEY: &str = "feeds.fetch";
+
+const FETCH_TIMEOUT_SECS: u64 = 30;
+/// Larger documents are rejected rather than parsed.
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+/// Entry text passed to the agent is cut to this many characters.
+const MAX_CONTENT_CHARS: usize = 4_000;
+/// Seen IDs remembered per feed; older ones are forgotten first.
+const MAX_SEEN_IDS: usize = 1_000;
+/// Memory category for stored replies.
+const MEMORY_CATEGORY: &str = "feeds";
+
+static HTML_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// One item of an RSS or Atom feed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedEntry {
+    /// `guid` / `id`, falling back to the link or title.
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    /// Plain text of the entry body (HTML tags stripped).
+    pub content: String,
+    /// Publication date as written in the feed.
+    pub published: String,
+}
+
+/// Parse an RSS 2.0, RSS 1.0 (RDF) or Atom document. Entries keep document
+/// order, which for most feeds is newest first.
+pub fn parse_feed(xml: &str) -> Result<Vec<FeedEntry>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut stack: Vec<String> = Vec::new();
+    let mut entries = Vec::new();
+    let mut current: Option<FeedEntry> = None;
+    // Full content (`content`, `content:encoded`) wins over summaries.
+    let mut full_content = String::new();
+    let mut saw_root = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .with_context(|| format!("Invalid feed XML at byte {}", reader.buffer_position()))?;
+        match event {
+            Event::Start(e) => {
+                let name = local_name(&e);
+                if !saw_root {
+                    if !matches!(name.as_str(), "rss" | "feed" | "RDF") {
+                        bail!("Not an RSS or Atom feed (root element <{name}>)");
+                    }
+                    saw_root = true;
+                }
+                if matches!(name.as_str(), "item" | "entry") {
+                    current = Some(FeedEntry::default());
+                    full_content.clear();
+                }
+                stack.push(name);
+                if let Some(entry) = current.as_mut() {
+                    if stack.last().is_some_and(|n| n == "link") && is_entry_field(&stack) {
+                        apply_atom_link(entry, &e);
+                    }
+                }
+            }
+            Event::Empty(e) => {
+                let name = local_name(&e);
+                if let Some(entry) = current.as_mut() {
+                    stack.push(name);
+                    if stack.last().is_some_and(|n| n == "link") && is_entry_field(&stack) {
+                        apply_atom_link(entry, &e);
+                    }
+                    stack.pop();
+                }
+            }
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map(|t| t.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&text).into_owned());
+                append_field(&mut current, &mut full_content, &stack, &text);
+            }
+            Event::CData(data) => {
+                let text = String::from_utf8_lossy(&data.into_inner()).into_owned();
+                append_field(&mut current, &mut full_content, &stack, &text);
+            }
+            Event::End(_) => {
+                let name = stack.pop().unwrap_or_default();
+                if matches!(name.as_str(), "item" | "entry") {
+                    if let Some(mut entry) = current.take() {
+                        if !full_content.trim().is_empty() {
+                            entry.content = std::mem::take(&mut full_content);
+                        }
+                        if let Some(entry) = finish_entry(entry) {
+                            entries.push(entry);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !saw_root {
+        bail!("Not an RSS or Atom feed (empty document)");
+    }
+    Ok(entries)
+}
+
+fn local_name(e: &BytesStart<'_>) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).into_owned()
+}
+
+/// Whether the innermost open element is a direct child of the open entry.
+/// `open` is the stack with that element already pushed.
+fn is_entry_field(open: &[String]) -> bool {
+    open.iter()
+        .rposition(|name| name == "item" || name == "entry")
+        .is_some_and(|i| i + 2 == open.len())
+}
+
+/// Atom `<link href="..."/>`: the first `alternate` (or untyped) link wins.
+fn apply_atom_link(entry: &mut FeedEntry, e: &BytesStart<'_>) {
+    if !entry.link.is_empty() {
+        return;
+    }
+    let attr = |key: &str| {
+        e.try_get_attribute(key)
+            .ok()
+            .flatten()
+            .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+    };
+    let rel = attr("rel");
+    if rel.as_deref().is_some_and(|rel| rel != "alternate") {
+        return;
+    }
+    if let Some(href) = attr("href") {
+        entry.link = href;
+    }
+}
+
+fn append_field(
+    current: &mut Option<FeedEntry>,
+    full_content: &mut String,
+    stack: &[String],
+    text: &str,
+) {
+    let Some(entry) = current.as_mut() else {
+        return;
+    };
+    if !is_entry_field(stack) {
+        return;
+    }
+    let field = match stack.last().map(String::as_str) {
+        Some("title") => &mut entry.title,
+        Some("link") => &mut entry.link,
+        Some("guid" | "id") => &mut entry.id,
+        Some("description" | "summary") => &mut entry.content,
+        Some("content" | "encoded") => full_content,
+        Some("pubDate" | "published" | "updated" | "date") => &mut entry.published,
+        _ => return,
+    };
+    field.push_str(text);
+}
+
+fn finish_entry(mut entry: FeedEntry) -> Option<FeedEntry> {
+    entry.title = collapse_whitespace(&entry.title);
+    entry.link = entry.link.trim().to_string();
+    entry.published = entry.published.trim().to_string();
+    entry.content = truncate_with_ellipsis(&html_to_text(&entry.content), MAX_CONTENT_CHARS);
+    entry.id = entry.id.trim().to_string();
+    if entry.id.is_empty() {
+        entry.id = if entry.link.is_empty() {
+            entry.title.clone()
+        } else {
+            entry.link.clone()
+        };
+    }
+    (!entry.id.is_empty()).then_some(entry)
+}
+
+/// Strip tags and the common entities from an HTML fragment.
+fn html_to_text(html: &str) -> String {
+    let text = HTML_TAG_RE.replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    collapse_whitespace(&text)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Fill `{feed}`, `{title}`, `{link}`, `{published}` and `{content}` in
+/// `template`. Unknown placeholders are left as written.
+pub fn render_prompt(template: &str, feed: &str, entry: &FeedEntry) -> String {
+    let mut out = String::with_capacity(template.len() + entry.content.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let value = match &after[1..end] {
+            "feed" => Some(feed),
+            "title" => Some(entry.title.as_str()),
+            "link" => Some(entry.link.as_str()),
+            "published" => Some(entry.published.as_str()),
+            "content" => Some(entry.content.as_str()),
+            _ => None,
+        };
+        match value {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&after[..=end]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Entry IDs already handled for one feed, oldest first.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FeedState {
+    seen: Vec<String>,
+}
+
+impl FeedState {
+    fn remember(&mut self, id: &str) {
+        self.seen.push(id.to_string());
+        if self.seen.len() > MAX_SEEN_IDS {
+            let excess = self.seen.len() - MAX_SEEN_IDS;
+            self.seen.drain(..excess);
+        }
+    }
+}
+
+fn feed_state_path(workspace_dir: &Path, name: &str) -> PathBuf {
+    workspace_dir
+        .join("state")
+        .join("feeds")
+        .join(format!("{name}.json"))
+}
+
+/// `None` before the first successful poll.
+fn load_state(path: &Path) -> Result<Option<FeedState>> {
+    match std::fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .map(Some)
+            .with_context(|| format!("Corrupt feed state {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+fn save_state(path: &Path, state: &FeedState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string(state)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+async fn fetch_feed(url: &str) -> Result<String> {
+    let response = crate::config::build_runtime_proxy_client_with_timeouts(
+        FEEDS_SERVICE_KEY,
+        FETCH_TIMEOUT_SECS,
+        10,
+    )
+    .get(url)
+    .header(
+        "Accept",
+        "application/rss+xml, application/atom+xml, application/xml;q=0.9, */*;q=0.8",
+    )
+    .send()
+    .await
+    .with_context(|| format!("Failed to fetch {url}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Feed request failed ({status})");
+    }
+    let body = response.bytes().await?;
+    if body.len() > MAX_FEED_BYTES {
+        bail!("Feed is larger than {MAX_FEED_BYTES} bytes");
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Polls one feed and runs its new entries through the agent.
+struct FeedPoller {
+    feed: FeedConfig,
+    config: Config,
+    memory: Arc<dyn Memory>,
+    delivery: Option<(Arc<dyn Channel>, String)>,
+    state_path: PathBuf,
+}
+
+impl FeedPoller {
+    /// One poll; returns the number of entries processed.
+    async fn poll(&self) -> Result<usize> {
+        let entries = parse_feed(&fetch_feed(&self.feed.url).await?)?;
+        let Some(mut state) = load_state(&self.state_path)? else {
+            let mut state = FeedState::default();
+            for entry in entries.iter().rev() {
+                state.remember(&entry.id);
+            }
+            save_state(&self.state_path, &state)?;
+            tracing::info!(
+                "Feed '{}': recorded {} existing entries; new entries are processed from now on",
+                self.feed.name,
+                entries.len()
+            );
+            return Ok(0);
+        };
+
+        let seen: HashSet<String> = state.seen.iter().cloned().collect();
+        // Feeds list newest first; handle the oldest pending entries first.
+        let pending: Vec<&FeedEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| !seen.contains(&entry.id))
+            .take(self.feed.max_items_per_poll)
+            .collect();
+        for entry in &pending {
+            Box::pin(self.process(entry))
+                .await
+                .with_context(|| format!("Failed to process entry '{}'", entry.title))?;
+            state.remember(&entry.id);
+            save_state(&self.state_path, &state)?;
+        }
+        Ok(pending.len())
+    }
+
+    async fn process(&self, entry: &FeedEntry) -> Result<()> {
+        tracing::info!("Feed '{}': processing '{}'", self.feed.name, entry.title);
+        let prompt = render_prompt(&self.feed.prompt_template, &self.feed.name, entry);
+        let reply = Box::pin(crate::agent::process_message(self.config.clone(), &prompt)).await?;
+
+        if self.feed.store_in_memory {
+            let key = format!("feed:{}:{}", self.feed.name, entry.id);
+            let content = format!("{}\n{}\n\n{reply}", entry.title, entry.link);
+            self.memory
+                .store(
+                    &key,
+                    &content,
+                    MemoryCategory::Custom(MEMORY_CATEGORY.into()),
+                    None,
+                )
+                .await?;
+        }
+        if let Some((channel, recipient)) = &self.delivery {
+            channel
+                .send(&SendMessage::new(reply, recipient))
+                .await
+                .with_context(|| format!("Failed to deliver to {}:{recipient}", channel.name()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct PollStatus {
+    next_poll: Option<Instant>,
+    running: bool,
+    last_error: Option<String>,
+}
+
+/// Heartbeat check for one `[[feeds]]` entry. Ticks start a background
+/// poll when the feed is due and report how the last poll went, so a slow
+/// agent run never holds up the other health checks.
+pub struct FeedWatcher {
+    poller: Arc<FeedPoller>,
+    status: Arc<Mutex<PollStatus>>,
+}
+
+impl FeedWatcher {
+    pub fn new(
+        feed: FeedConfig,
+        config: Config,
+        memory: Arc<dyn Memory>,
+        delivery: Option<(Arc<dyn Channel>, String)>,
+    ) -> Self {
+        let state_path = feed_state_path(&config.workspace_dir, &feed.name);
+        Self {
+            poller: Arc::new(FeedPoller {
+                feed,
+                config,
+                memory,
+                delivery,
+                state_path,
+            }),
+            status: Arc::new(Mutex::new(PollStatus::default())),
+        }
+    }
+
+    fn start_poll(&self) {
+        let poller = Arc::clone(&self.poller);
+        let status = Arc::clone(&self.status);
+        tokio::spawn(async move {
+            let outcome = Box::pin(poller.poll()).await;
+            let mut status = status.lock();
+            status.running = false;
+            status.next_poll =
+                Some(Instant::now() + Duration::from_secs(poller.feed.interval_secs));
+            match outcome {
+                Ok(processed) => {
+                    if processed > 0 {
+                        tracing::info!(
+                            "Feed '{}': processed {processed} new entries",
+                            poller.feed.name
+                        );
+                    }
+                    status.last_error = None;
+                }
+                Err(e) => {
+                    tracing::warn!("Feed '{}' poll failed: {e:#}", poller.feed.name);
+                    status.last_error = Some(format!("{e:#}"));
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl HealthCheck for FeedWatcher {
+    fn component(&self) -> String {
+        format!("feed:{}", self.poller.feed.name)
+    }
+
+    async fn check(&self) -> Result<()> {
+        let (due, last_error) = {
+            let mut status = self.status.lock();
+            let due = !status.running && status.next_poll.is_none_or(|at| Instant::now() >= at);
+            if due {
+                status.running = true;
+            }
+            (due, status.last_error.clone())
+        };
+        if due {
+            self.start_poll();
+        }
+        match last_error {
+            Some(e) => bail!("{e}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Watchers for the enabled `[[feeds]]`, delivering through `channels`.
+pub(crate) fn watchers_from_config(
+    config: &Config,
+    memory: &Arc<dyn Memory>,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+) -> Vec<FeedWatcher> {
+    config
+        .feeds
+        .iter()
+        .filter(|feed| feed.enabled)
+        .map(|feed| {
+            let delivery = match (&feed.channel, &feed.recipient) {
+                (Some(name), Some(recipient)) => match channels.get(name) {
+                    Some(channel) => Some((Arc::clone(channel), recipient.clone())),
+                    None => {
+                        tracing::warn!(
+                            "Feed '{}': channel '{name}' is not configured; replies are only stored",
+                            feed.name
+                        );
+                        None
+                    }
+                },
+                _ => None,
+            };
+            FeedWatcher::new(feed.clone(), config.clone(), Arc::clone(memory), delivery)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Example Blog</title>
+    <link>https://example.com/</link>
+    <item>
+      <title>Second &amp; newest</title>
+      <link>https://example.com/2</link>
+      <guid isPermaLink="false">post-2</guid>
+      <pubDate>Tue, 02 Jan 2024 10:00:00 GMT</pubDate>
+      <description>Short teaser</description>
+      <content:encoded><![CDATA[<p>Full <b>body</b> of&nbsp;post 2</p>]]></content:encoded>
+    </item>
+    <item>
+      <title>First</title>
+      <link>https://example.com/1</link>
+      <description>&lt;p&gt;Body 1&lt;/p&gt;</description>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Atom Example</title>
+  <link href="https://example.org/"/>
+  <entry>
+    <title type="html">Atom entry</title>
+    <link rel="self" href="https://example.org/feed/1"/>
+    <link rel="alternate" href="https://example.org/1"/>
+    <id>urn:uuid:1</id>
+    <updated>2024-01-03T00:00:00Z</updated>
+    <summary>Summary text</summary>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn parse_feed_reads_rss_items() {
+        let entries = parse_feed(RSS).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "post-2");
+        assert_eq!(entries[0].title, "Second & newest");
+        assert_eq!(entries[0].link, "https://example.com/2");
+        assert_eq!(entries[0].published, "Tue, 02 Jan 2024 10:00:00 GMT");
+        assert_eq!(entries[0].content, "Full body of post 2");
+        // No guid: the link identifies the entry.
+        assert_eq!(entries[1].id, "https://example.com/1");
+        assert_eq!(entries[1].content, "Body 1");
+    }
+
+    #[test]
+    fn parse_feed_reads_atom_entries() {
+        let entries = parse_feed(ATOM).unwrap();
+        assert_eq!(
+            entries,
+            vec![FeedEntry {
+                id: "urn:uuid:1".into(),
+                title: "Atom entry".into(),
+                link: "https://example.org/1".into(),
+                content: "Summary text".into(),
+                published: "2024-01-03T00:00:00Z".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_feed_rejects_other_documents() {
+        assert!(parse_feed("<html><body>hi</body></html>").is_err());
+        assert!(parse_feed("").is_err());
+    }
+
+    #[test]
+    fn render_prompt_fills_placeholders_once() {
+        let entry = FeedEntry {
+            id: "1".into(),
+            title: "Post {content}".into(),
+            link: "https://example.com/1".into(),
+            content: "Body".into(),
+            published: String::new(),
+        };
+        assert_eq!(
+            render_prompt(
+                "[{feed}] {title} <{link}> {unknown}: {content}",
+                "blog",
+                &entry
+            ),
+            "[blog] Post {content} <https://example.com/1> {unknown}: Body"
+        );
+    }
+
+    #[test]
+    fn feed_state_forgets_oldest_ids() {
+        let mut state = FeedState::default();
+        for i in 0..MAX_SEEN_IDS + 5 {
+            state.remember(&i.to_string());
+        }
+        assert_eq!(state.seen.len(), MAX_SEEN_IDS);
+        assert_eq!(state.seen[0], "5");
+    }
+
+    #[tokio::test]
+    async fn first_poll_records_existing_entries_without_running_the_agent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feed.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(RSS))
+            .mount(&server)
+            .await;
+        let workspace = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.workspace_dir = workspace.path().to_path_buf();
+        let feed: FeedConfig = toml::from_str(&format!(
+            "name = \"blog\"\nurl = \"{}/feed.xml\"",
+            server.uri()
+        ))
+        .unwrap();
+        let memory: Arc<dyn Memory> =
+            Arc::new(crate::memory::SqliteMemory::new(workspace.path()).unwrap());
+        let watcher = FeedWatcher::new(feed, config, memory, None);
+
+        assert_eq!(Box::pin(watcher.poller.poll()).await.unwrap(), 0);
+        let state = load_state(&watcher.poller.state_path).unwrap().unwrap();
+        assert_eq!(state.seen, vec!["https://example.com/1", "post-2"]);
+    }
+}
@@ -2,11 +2,11 @@
 //!
 //! All `/api/*` routes require bearer token authentication (PairingGuard).
 
-use super::AppState;
+use super::{rate_limited_response, AppState};
 use axum::{
     extract::{Path, Query, State},
     http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Json},
+    response::{IntoResponse, Json, Response},
 };
 use serde::Deserialize;
 
@@ -20,25 +20,55 @@ fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
         .and_then(|auth| auth.strip_prefix("Bearer "))
 }
 
-/// Verify bearer token against PairingGuard. Returns error response if unauthorized.
-fn require_auth(
-    state: &AppState,
-    headers: &HeaderMap,
-) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+/// Error response from an auth check, boxed to keep `Result`s small.
+pub(super) struct Rejection(Box<Response>);
+
+impl From<Response> for Rejection {
+    fn from(response: Response) -> Self {
+        Self(Box::new(response))
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response {
+        *self.0
+    }
+}
+
+/// Verify bearer token against PairingGuard and charge the token's rate
+/// budget. Returns the error response if unauthorized or rate limited.
+pub(super) fn require_auth(state: &AppState, headers: &HeaderMap) -> Result<(), Rejection> {
+    require_token(state, extract_bearer_token(headers).unwrap_or(""))
+}
+
+/// [`require_auth`] for a token that did not arrive in an Authorization
+/// header (the dashboard's websocket passes it as a query parameter).
+pub(super) fn require_token(state: &AppState, token: &str) -> Result<(), Rejection> {
     if !state.pairing.require_pairing() {
         return Ok(());
     }
 
-    let token = extract_bearer_token(headers).unwrap_or("");
-    if state.pairing.is_authenticated(token) {
-        Ok(())
-    } else {
-        Err((
+    if !state.pairing.is_authenticated(token) {
+        return Err((
             StatusCode::UNAUTHORIZED,
             Json(serde_json::json!({
                 "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
             })),
-        ))
+        )
+            .into_response()
+            .into());
+    }
+
+    let decision = state.rate_limiter.check_token(token);
+    if decision.is_allowed() {
+        Ok(())
+    } else {
+        tracing::warn!("API per-token rate limit exceeded");
+        Err(rate_limited_response(
+            "Too many requests for this token. Please retry later.",
+            decision,
+        )
+        .into())
     }
 }
 
@@ -50,6 +80,42 @@ pub struct MemoryQuery {
     pub category: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct SessionsQuery {
+    pub agent_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionQuery {
+    /// Return only the most recent N transcript entries
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionExportQuery {
+    /// markdown (default) or html
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UsageQuery {
+    /// hour, day, week, month, or all (default: day)
+    pub period: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AgentRunBody {
+    pub message: String,
+    /// Continue this conversation; turns are kept under the session
+    /// `gateway:api:<session_key>`.
+    pub session_key: Option<String>,
+    /// Model instead of the configured default.
+    pub model: Option<String>,
+    /// Offer only these tools.
+    pub tools: Option<Vec<String>>,
+}
+
 #[derive(Deserialize)]
 pub struct MemoryStoreBody {
     pub key: String,
@@ -77,7 +143,7 @@ pub async fn handle_api_status(
         "temperature": state.temperature,
         "uptime_seconds": health.uptime_seconds,
         "gateway_port": config.gateway.port,
-        "locale": "en",
+        "locale": config.locale.as_deref().unwrap_or(crate::util::i18n::DEFAULT_LOCALE),
         "memory_backend": state.mem.name(),
         "paired": state.pairing.is_paired(),
         "health": health,
@@ -86,6 +152,34 @@ pub async fn handle_api_status(
     Json(body).into_response()
 }
 
+/// Leading characters of a token's SHA-256 hash shown to tell devices apart.
+const DEVICE_FINGERPRINT_CHARS: usize = 12;
+
+/// GET /api/devices — paired devices (label, pairing time, token fingerprint)
+pub async fn handle_api_devices(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let devices: Vec<serde_json::Value> = state
+        .pairing
+        .token_records()
+        .into_iter()
+        .map(|record| {
+            serde_json::json!({
+                "label": record.label,
+                "created_at": record.created_at,
+                "fingerprint": record.token.chars().take(DEVICE_FINGERPRINT_CHARS).collect::<String>(),
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({"devices": devices})).into_response()
+}
+
 /// GET /api/config — current config (api_key masked)
 pub async fn handle_api_config_get(
     State(state): State<AppState>,
@@ -97,8 +191,8 @@ pub async fn handle_api_config_get(
 
     let config = state.config.lock().clone();
 
-    // Serialize to TOML, then mask sensitive fields
-    let toml_str = match toml::to_string_pretty(&config) {
+    // Serialize to TOML with secrets masked
+    let masked = match mask_sensitive_fields(&config) {
         Ok(s) => s,
         Err(e) => {
             return (
@@ -109,9 +203,6 @@ pub async fn handle_api_config_get(
         }
     };
 
-    // Mask api_key in the TOML output
-    let masked = mask_sensitive_fields(&toml_str);
-
     Json(serde_json::json!({
         "format": "toml",
         "content": masked,
@@ -129,8 +220,22 @@ pub async fn handle_api_config_put(
         return e.into_response();
     }
 
-    // Parse the incoming TOML
-    let new_config: crate::config::Config = match toml::from_str(&body) {
+    // Parse the incoming TOML, keeping secrets the GET response masked
+    let current = state.config.lock().clone();
+    let mut body: toml::Value = match toml::from_str(&body) {
+        Ok(value) => value,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": format!("Invalid TOML: {e}")})),
+            )
+                .into_response();
+        }
+    };
+    if let Ok(current_value) = toml::Value::try_from(&current) {
+        restore_masked_fields(&mut body, Some(&current_value), "");
+    }
+    let mut new_config: crate::config::Config = match body.try_into() {
         Ok(c) => c,
         Err(e) => {
             return (
@@ -140,6 +245,15 @@ pub async fn handle_api_config_put(
                 .into_response();
         }
     };
+    new_config.config_path = current.config_path;
+    new_config.workspace_dir = current.workspace_dir;
+    if let Err(e) = new_config.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("Invalid config: {e}")})),
+        )
+            .into_response();
+    }
 
     // Save to disk
     if let Err(e) = new_config.save().await {
@@ -304,29 +418,727 @@ pub async fn handle_api_health(
     Json(serde_json::json!({"health": snapshot})).into_response()
 }
 
+/// GET /api/sessions — list recorded sessions
+pub async fn handle_api_sessions_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SessionsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let filter = crate::sessions::SessionFilter {
+        agent_id: params.agent_id,
+        since: None,
+        limit: params.limit,
+    };
+    match state.sessions.list(&filter).await {
+        Ok(sessions) => {
+            let sessions: Vec<serde_json::Value> = sessions
+                .iter()
+                .map(|session| {
+                    serde_json::json!({
+                        "key": format_session_key(&session.key),
+                        "agent_id": session.key.agent_id,
+                        "context": session.key.context,
+                        "created_at": session.created_at,
+                        "last_activity": session.last_activity,
+                        "model": session.model,
+                    })
+                })
+                .collect();
+            Json(serde_json::json!({"sessions": sessions})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Session list failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/:key — inspect a session and its transcript
+pub async fn handle_api_session_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Query(params): Query<SessionQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(session_key) = parse_session_key(&key) else {
+        return invalid_session_key_response();
+    };
+
+    let session = match state.sessions.get(&session_key).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Session not found"})),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Session lookup failed: {e}")})),
+            )
+                .into_response();
+        }
+    };
+
+    match state
+        .sessions
+        .get_transcript(&session_key, params.limit)
+        .await
+    {
+        Ok(transcript) => Json(serde_json::json!({
+            "session": session,
+            "transcript": transcript,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Transcript lookup failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/:key/export — download the transcript as Markdown or HTML
+pub async fn handle_api_session_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+    Query(params): Query<SessionExportQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(session_key) = parse_session_key(&key) else {
+        return invalid_session_key_response();
+    };
+    let format = match params.format.as_deref() {
+        None => crate::sessions::ExportFormat::default(),
+        Some(raw) => match crate::sessions::ExportFormat::parse(raw) {
+            Some(format) => format,
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": "Invalid format. Use markdown or html"
+                    })),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let session = match state.sessions.get(&session_key).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Session not found"})),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Session lookup failed: {e}")})),
+            )
+                .into_response();
+        }
+    };
+
+    match state.sessions.get_transcript(&session_key, None).await {
+        Ok(transcript) => {
+            let body = crate::sessions::render_transcript(&session, &transcript, format);
+            let disposition = format!(
+                "attachment; filename=\"{}\"",
+                crate::sessions::export::export_file_name(&session, format)
+            );
+            (
+                [
+                    (header::CONTENT_TYPE, format.content_type().to_string()),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                body,
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Transcript lookup failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE /api/sessions/:key — delete a session and its transcript
+pub async fn handle_api_session_delete(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(session_key) = parse_session_key(&key) else {
+        return invalid_session_key_response();
+    };
+
+    let existed = matches!(state.sessions.get(&session_key).await, Ok(Some(_)));
+    match state.sessions.delete(&session_key).await {
+        Ok(()) => Json(serde_json::json!({"status": "ok", "deleted": existed})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Session delete failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/channels — configured channels with runtime and health status
+pub async fn handle_api_channels(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let health = crate::health::snapshot();
+    let gateway = gateway_channel_names(&state)
+        .into_iter()
+        .map(|name| (name.to_string(), state.channel_control.is_running(name)));
+    let supervisor = crate::channels::supervisor::active();
+    let supervised = supervisor.iter().flat_map(|supervisor| {
+        supervisor.names().into_iter().map(move |name| {
+            let running = supervisor.is_running(&name).unwrap_or(false);
+            (name, running)
+        })
+    });
+    let channels: Vec<serde_json::Value> = gateway
+        .chain(supervised)
+        .map(|(name, running)| {
+            let component = health.components.get(&format!("channel:{name}"));
+            serde_json::json!({
+                "name": name,
+                "running": running,
+                "health": component,
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({"channels": channels})).into_response()
+}
+
+/// POST /api/channels/:name/start — resume processing for a channel (gateway
+/// channels) or restart its listener (channel runtime)
+pub async fn handle_api_channel_start(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    set_channel_running(&state, &headers, &name, true)
+}
+
+/// POST /api/channels/:name/stop — pause processing for a channel (gateway
+/// channels) or stop its listener (channel runtime)
+pub async fn handle_api_channel_stop(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    set_channel_running(&state, &headers, &name, false)
+}
+
+/// GET /api/usage — token/cost summary and per-model breakdown
+pub async fn handle_api_usage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<UsageQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(period) = parse_usage_period(params.period.as_deref().unwrap_or("day")) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Invalid period. Expected one of: hour, day, week, month, all"
+            })),
+        )
+            .into_response();
+    };
+
+    let summary = state.usage.summary(&period).await;
+    let breakdown = state.usage.breakdown(&period).await;
+    match (summary, breakdown) {
+        (Ok(summary), Ok(breakdown)) => Json(serde_json::json!({
+            "summary": summary,
+            "breakdown": breakdown,
+        }))
+        .into_response(),
+        (Err(e), _) | (_, Err(e)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Usage lookup failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MediaUploadQuery {
+    /// Original file name; only its extension is kept.
+    pub filename: Option<String>,
+}
+
+/// POST /api/media — upload one file as the raw request body, typed by its
+/// `Content-Type`. The body is streamed into `<workspace>/media` and capped at
+/// `[gateway] max_upload_bytes`.
+pub async fn handle_api_media_upload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<MediaUploadQuery>,
+    body: axum::body::Body,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let (media_dir, max_bytes) = {
+        let config = state.config.lock();
+        (
+            config.workspace_dir.join("media"),
+            config.gateway.max_upload_bytes,
+        )
+    };
+    let too_large = || {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(serde_json::json!({
+                "error": format!("Upload exceeds the {max_bytes}-byte limit")
+            })),
+        )
+            .into_response()
+    };
+    let declared_length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_length.is_some_and(|length| length > max_bytes) {
+        return too_large();
+    }
+
+    let mime_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let extension = params
+        .filename
+        .as_deref()
+        .and_then(|name| std::path::Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.len() <= 16 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map_or_else(
+            || crate::media::extension_for_mime(&mime_type).to_string(),
+            str::to_ascii_lowercase,
+        );
+    let metadata = crate::media::MediaMetadata {
+        filename: Some(format!("upload.{extension}")),
+        mime_type: Some(mime_type),
+        size_bytes: None,
+        source_url: None,
+    };
+
+    let store = crate::media::LocalMediaStore::new(&media_dir);
+    match store
+        .store_stream(body.into_data_stream(), metadata, max_bytes)
+        .await
+    {
+        Ok(entry) => Json(serde_json::json!({
+            "id": entry.id.0,
+            "size_bytes": entry.metadata.size_bytes,
+            "mime_type": entry.metadata.mime_type,
+        }))
+        .into_response(),
+        Err(e) if e.downcast_ref::<crate::media::MediaTooLarge>().is_some() => too_large(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Media upload failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 // ── Helpers ─────────────────────────────────────────────────────
 
-fn mask_sensitive_fields(toml_str: &str) -> String {
-    let mut output = String::with_capacity(toml_str.len());
-    for line in toml_str.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("api_key")
-            || trimmed.starts_with("bot_token")
-            || trimmed.starts_with("access_token")
-            || trimmed.starts_with("secret")
-            || trimmed.starts_with("app_secret")
-            || trimmed.starts_with("signing_secret")
+/// Session keys are addressed as `<agent_id>:<context>` in URLs.
+fn format_session_key(key: &crate::sessions::SessionKey) -> String {
+    format!("{}:{}", key.agent_id, key.context)
+}
+
+/// Most earlier turns of a session replayed into `/api/agent/run`.
+const AGENT_RUN_HISTORY_ENTRIES: usize = 40;
+
+/// POST /api/agent/run — run one message through the agent (with tools) and
+/// return the final text, every tool call, token usage and timing
+pub async fn handle_api_agent_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<AgentRunBody>, axum::extract::rejection::JsonRejection>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    let bad_request = |error: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": error })),
+        )
+            .into_response()
+    };
+    let Json(body) = match body {
+        Ok(b) => b,
+        Err(e) => return bad_request(format!("Invalid request body: {e}")),
+    };
+    let message = body.message.trim();
+    if message.is_empty() {
+        return bad_request("message must not be empty".into());
+    }
+    if let Some(tools) = &body.tools {
+        if let Some(unknown) = tools
+            .iter()
+            .find(|name| !state.tools_registry.iter().any(|spec| &spec.name == *name))
+        {
+            return bad_request(format!("Unknown tool: {unknown}"));
+        }
+    }
+    let session_context = match body.session_key.as_deref().map(str::trim) {
+        Some(key) if key.is_empty() || key.contains(':') => {
+            return bad_request("session_key must be non-empty and must not contain ':'".into());
+        }
+        Some(key) => Some(format!("api:{key}")),
+        None => None,
+    };
+
+    let mut history = Vec::new();
+    if let Some(context) = &session_context {
+        let key = crate::sessions::SessionKey {
+            agent_id: super::GATEWAY_SESSION_AGENT_ID.to_string(),
+            context: context.clone(),
+        };
+        if let Ok(entries) = state
+            .sessions
+            .get_transcript(&key, Some(AGENT_RUN_HISTORY_ENTRIES))
+            .await
         {
-            if let Some(eq_pos) = line.find('=') {
-                output.push_str(&line[..=eq_pos]);
-                output.push_str(" \"***MASKED***\"");
-            } else {
-                output.push_str(line);
+            history.extend(
+                entries
+                    .iter()
+                    .filter_map(|entry| match entry.role.as_str() {
+                        "user" => Some(crate::providers::ChatMessage::user(&entry.content)),
+                        "assistant" => {
+                            Some(crate::providers::ChatMessage::assistant(&entry.content))
+                        }
+                        _ => None,
+                    }),
+            );
+        }
+    }
+
+    let config = state.config.lock().clone();
+    let model = body
+        .model
+        .clone()
+        .or_else(|| config.default_model.clone())
+        .unwrap_or_else(|| state.model.clone());
+    let options = crate::agent::RunOptions {
+        model: body.model.clone(),
+        tools: body.tools.clone(),
+        history,
+        ..crate::agent::RunOptions::default()
+    };
+    let started = std::time::Instant::now();
+    // Only runs continuing a session keep a working directory between calls.
+    let run = async {
+        match &session_context {
+            Some(context) => {
+                super::run_agent_turn(&state, context.clone(), config, message, options).await
+            }
+            None => crate::agent::process_message_with(config, message, &options).await,
+        }
+    };
+    let (result, trace) = crate::agent::trace::traced(Box::pin(run)).await;
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    match result {
+        Ok(output) => {
+            if let Some(context) = &session_context {
+                super::record_gateway_turn(&state, context, message, &output).await;
             }
+            let tool_ms: u64 = trace.tool_calls.iter().map(|call| call.duration_ms).sum();
+            Json(serde_json::json!({
+                "output": output,
+                "model": model,
+                "session_key": body.session_key,
+                "tool_calls": trace.tool_calls,
+                "usage": trace.usage,
+                "timing": {
+                    "duration_ms": duration_ms,
+                    "tool_ms": tool_ms,
+                },
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            let error = crate::providers::sanitize_api_error(&format!("{e:#}"));
+            tracing::warn!("Agent run failed: {error}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": error,
+                    "tool_calls": trace.tool_calls,
+                    "usage": trace.usage,
+                    "timing": { "duration_ms": duration_ms },
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn parse_session_key(raw: &str) -> Option<crate::sessions::SessionKey> {
+    let (agent_id, context) = raw.split_once(':')?;
+    if agent_id.is_empty() || context.is_empty() {
+        return None;
+    }
+    Some(crate::sessions::SessionKey {
+        agent_id: agent_id.to_string(),
+        context: context.to_string(),
+    })
+}
+
+fn invalid_session_key_response() -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({
+            "error": "Invalid session key. Expected <agent_id>:<context>"
+        })),
+    )
+        .into_response()
+}
+
+fn parse_usage_period(raw: &str) -> Option<crate::infra::UsagePeriod> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "hour" => Some(crate::infra::UsagePeriod::Hour),
+        "day" => Some(crate::infra::UsagePeriod::Day),
+        "week" => Some(crate::infra::UsagePeriod::Week),
+        "month" => Some(crate::infra::UsagePeriod::Month),
+        "all" => Some(crate::infra::UsagePeriod::All),
+        _ => None,
+    }
+}
+
+/// Channels hosted by this gateway process.
+fn gateway_channel_names(state: &AppState) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    if state.whatsapp.is_some() {
+        names.push("whatsapp");
+    }
+    names
+}
+
+fn set_channel_running(
+    state: &AppState,
+    headers: &HeaderMap,
+    name: &str,
+    running: bool,
+) -> axum::response::Response {
+    if let Err(e) = require_auth(state, headers) {
+        return e.into_response();
+    }
+
+    if gateway_channel_names(state).contains(&name) {
+        if running {
+            state.channel_control.start(name);
         } else {
-            output.push_str(line);
+            state.channel_control.stop(name);
+        }
+    } else {
+        // Long-running channels (Telegram, Discord, ...) are started and
+        // stopped through the channel runtime supervisor.
+        let supervisor = crate::channels::supervisor::active()
+            .filter(|supervisor| supervisor.is_running(name).is_some());
+        let Some(supervisor) = supervisor else {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": format!("Channel '{name}' is not configured")})),
+            )
+                .into_response();
+        };
+        let result = if running {
+            supervisor.start(name)
+        } else {
+            supervisor.stop(name)
+        };
+        if let Err(e) = result {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response();
         }
-        output.push('\n');
     }
-    output
+    tracing::info!(channel = name, running, "Channel state changed via API");
+
+    Json(serde_json::json!({"status": "ok", "name": name, "running": running})).into_response()
+}
+
+/// Value that replaces secrets in GET /api/config.
+const MASKED_VALUE: &str = "***MASKED***";
+
+/// Serialize `config` as TOML with every field in
+/// [`SECRET_CONFIG_PATHS`](crate::config::edit::SECRET_CONFIG_PATHS) masked.
+fn mask_sensitive_fields(config: &crate::config::Config) -> anyhow::Result<String> {
+    let mut value = toml::Value::try_from(config)?;
+    crate::config::edit::mask_secrets(&mut value, "", MASKED_VALUE);
+    Ok(toml::to_string_pretty(&value)?)
+}
+
+/// Put back the secrets [`mask_sensitive_fields`] hid, so a config read from
+/// GET and sent back unchanged keeps its real values. A masked secret in
+/// `body`, at `path`, takes the `current` value at the same path (array
+/// entries by position); a replaced one is kept as sent.
+fn restore_masked_fields(body: &mut toml::Value, current: Option<&toml::Value>, path: &str) {
+    use crate::config::edit::{child_path, is_secret_path};
+
+    let Some(current) = current else {
+        return;
+    };
+    let is_masked = |value: &toml::Value| value.as_str() == Some(MASKED_VALUE);
+    if is_secret_path(path) {
+        match (body, current) {
+            (toml::Value::Array(items), toml::Value::Array(originals)) => {
+                for (item, original) in items.iter_mut().zip(originals) {
+                    if is_masked(item) {
+                        *item = original.clone();
+                    }
+                }
+            }
+            (body, current) => {
+                if is_masked(body) {
+                    *body = current.clone();
+                }
+            }
+        }
+        return;
+    }
+    match body {
+        toml::Value::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                restore_masked_fields(child, current.get(key.as_str()), &child_path(path, key));
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                restore_masked_fields(item, current.get(i), &format!("{path}[]"));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_secrets() -> crate::config::Config {
+        let mut config = crate::config::Config::default();
+        config.api_key = Some("sk-live".into());
+        config.assistant.email = Some(
+            toml::from_str(
+                "imap_host = \"imap.example.com\"\nusername = \"me\"\npassword = \"mail-pass\"",
+            )
+            .unwrap(),
+        );
+        config.channels_config.github =
+            Some(toml::from_str("webhook_secret = \"gh-hook-secret\"").unwrap());
+        config.channels_config.whatsapp =
+            Some(toml::from_str("verify_token = \"wa-verify\"").unwrap());
+        config.gateway.webhook_transforms = vec![toml::from_str(
+            "name = \"slack\"\nscript = \"x.rhai\"\nsignature_secret = \"sig-secret\"",
+        )
+        .unwrap()];
+        config.tools.databases = vec![
+            toml::from_str("name = \"a\"\nurl = \"postgres://a:pw-a@db/a\"").unwrap(),
+            toml::from_str("name = \"b\"\nurl = \"postgres://b:pw-b@db/b\"").unwrap(),
+        ];
+        config
+    }
+
+    #[test]
+    fn config_get_masks_every_secret_path() {
+        let masked = mask_sensitive_fields(&config_with_secrets()).unwrap();
+        for secret in [
+            "sk-live",
+            "mail-pass",
+            "gh-hook-secret",
+            "wa-verify",
+            "sig-secret",
+            "pw-a",
+            "pw-b",
+        ] {
+            assert!(!masked.contains(secret), "{secret} leaked");
+        }
+        assert!(masked.contains("imap.example.com"));
+    }
+
+    #[test]
+    fn masked_secrets_survive_a_config_round_trip() {
+        let config = config_with_secrets();
+        let current = toml::Value::try_from(&config).unwrap();
+        let edited = mask_sensitive_fields(&config)
+            .unwrap()
+            .replace("name = \"b\"", "name = \"c\"");
+
+        let mut body: toml::Value = toml::from_str(&edited).unwrap();
+        restore_masked_fields(&mut body, Some(&current), "");
+        let restored: crate::config::Config = body.try_into().unwrap();
+        assert_eq!(restored.api_key.as_deref(), Some("sk-live"));
+        assert_eq!(
+            restored.assistant.email.unwrap().password.as_deref(),
+            Some("mail-pass")
+        );
+        assert_eq!(
+            restored.gateway.webhook_transforms[0]
+                .signature_secret
+                .as_deref(),
+            Some("sig-secret")
+        );
+        assert_eq!(restored.tools.databases[1].name, "c");
+        assert_eq!(restored.tools.databases[1].url, "postgres://b:pw-b@db/b");
+
+        // A secret replaced in the editor is kept as typed.
+        let replaced = edited.replacen(
+            &format!("api_key = \"{MASKED_VALUE}\""),
+            "api_key = \"sk-new\"",
+            1,
+        );
+        let mut body: toml::Value = toml::from_str(&replaced).unwrap();
+        restore_masked_fields(&mut body, Some(&current), "");
+        assert_eq!(body["api_key"].as_str(), Some("sk-new"));
+    }
 }
@@ -0,0 +1,64 @@
+//! Cross-origin access for browser clients (`[gateway.cors]`).
+//!
+//! The gateway sends no CORS headers unless origins are configured, so by
+//! default a browser can only call the API from the gateway's own origin.
+
+use crate::config::GatewayConfig;
+use anyhow::{Context, Result};
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Build the CORS layer, or `None` when `allowed_origins` is empty.
+pub fn cors_layer(config: &GatewayConfig) -> Result<Option<CorsLayer>> {
+    let cors = &config.cors;
+    if cors.allowed_origins.is_empty() {
+        return Ok(None);
+    }
+
+    let origins = if cors.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = cors
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin.trim_end_matches('/'))
+                    .with_context(|| format!("Invalid gateway.cors origin {origin:?}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        AllowOrigin::list(origins)
+    };
+    let methods = cors
+        .allowed_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes())
+                .with_context(|| format!("Invalid gateway.cors method {method:?}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut headers = cors
+        .allowed_headers
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.trim().as_bytes())
+                .with_context(|| format!("Invalid gateway.cors header {name:?}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if config.csrf.enabled {
+        headers.push(
+            HeaderName::from_bytes(config.csrf.header_name.as_bytes())
+                .context("Invalid gateway.csrf.header_name")?,
+        );
+    }
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .allow_credentials(cors.allow_credentials)
+            .expose_headers([HeaderName::from_static("x-request-id")])
+            .max_age(Duration::from_secs(cors.max_age_secs)),
+    ))
+}
@@ -0,0 +1,104 @@
+//! Double-submit CSRF protection (`[gateway.csrf]`).
+//!
+//! Responses to requests that lack the token cookie set one holding a fresh
+//! random token. A state-changing request that carries cookies is only let
+//! through when it repeats the cookie's token in the CSRF header: a page on
+//! another origin can make the browser send the cookie, but cannot read it.
+//! Requests without cookies (bearer-token clients, signed webhooks) carry no
+//! ambient credentials and pass untouched.
+
+use crate::config::GatewayCsrfConfig;
+use crate::security::pairing::constant_time_eq;
+use anyhow::{Context, Result};
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+
+#[derive(Debug, Clone)]
+pub struct CsrfGuard {
+    cookie_name: String,
+    header_name: HeaderName,
+    /// Mark the cookie `Secure` (the gateway terminates TLS).
+    secure: bool,
+}
+
+impl CsrfGuard {
+    pub fn new(config: &GatewayCsrfConfig, secure: bool) -> Result<Self> {
+        Ok(Self {
+            cookie_name: config.cookie_name.clone(),
+            header_name: HeaderName::from_bytes(config.header_name.as_bytes())
+                .context("Invalid gateway.csrf.header_name")?,
+            secure,
+        })
+    }
+
+    fn cookie_token<'a>(&self, headers: &'a HeaderMap) -> Option<&'a str> {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|cookies| cookies.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.cookie_name)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn set_cookie(&self) -> Option<HeaderValue> {
+        let token = hex::encode(rand::random::<[u8; 32]>());
+        let secure = if self.secure { "; Secure" } else { "" };
+        HeaderValue::from_str(&format!(
+            "{}={token}; Path=/; SameSite=Strict{secure}",
+            self.cookie_name
+        ))
+        .ok()
+    }
+}
+
+fn is_state_changing(method: &Method) -> bool {
+    !matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+/// Middleware enforcing the double-submit check.
+pub async fn enforce(guard: CsrfGuard, request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let cookie_token = guard.cookie_token(headers);
+    if is_state_changing(request.method()) && headers.contains_key(header::COOKIE) {
+        let echoed = headers
+            .get(&guard.header_name)
+            .and_then(|value| value.to_str().ok());
+        let valid = matches!(
+            (cookie_token, echoed),
+            (Some(cookie), Some(echoed)) if constant_time_eq(cookie, echoed)
+        );
+        if !valid {
+            tracing::warn!(
+                "Rejected {} {}: missing or mismatched CSRF token",
+                request.method(),
+                request.uri().path()
+            );
+            let err = serde_json::json!({
+                "error": format!(
+                    "Missing or invalid CSRF token: repeat the {} cookie in the {} header",
+                    guard.cookie_name, guard.header_name
+                )
+            });
+            return (StatusCode::FORBIDDEN, Json(err)).into_response();
+        }
+    }
+
+    let needs_cookie = cookie_token.is_none();
+    let mut response = next.run(request).await;
+    if needs_cookie {
+        if let Some(cookie) = guard.set_cookie() {
+            response.headers_mut().append(header::SET_COOKIE, cookie);
+        }
+    }
+    response
+}
@@ -0,0 +1,128 @@
+//! Single-page web dashboard served at `/`.
+//!
+//! The page is compiled into the binary and talks to the gateway only
+//! through the `/api/*` routes and the `/ws/chat` websocket, so every panel
+//! is gated by the same pairing auth as the API. The page itself holds no
+//! data; a browser pairs with a one-time code (or a pasted token) and keeps
+//! the bearer token in local storage.
+
+use super::{api, AppState};
+use crate::agent::RunOptions;
+use crate::providers::ChatMessage;
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{Query, State},
+    http::header,
+    response::{Html, IntoResponse, Response},
+};
+use serde::Deserialize;
+
+const INDEX_HTML: &str = include_str!("dashboard/index.html");
+
+/// Inline script and style only; API and websocket calls go to this origin.
+const CONTENT_SECURITY_POLICY: &str = "default-src 'none'; script-src 'unsafe-inline'; \
+     style-src 'unsafe-inline'; connect-src 'self'; base-uri 'none'; form-action 'none'; \
+     frame-ancestors 'none'";
+
+/// Earlier messages of a websocket chat replayed into each turn.
+const CHAT_HISTORY_MESSAGES: usize = 40;
+
+/// GET / — the dashboard page
+pub async fn handle_dashboard() -> impl IntoResponse {
+    (
+        [
+            (header::CACHE_CONTROL, "no-cache"),
+            (header::CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        Html(INDEX_HTML),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ChatQuery {
+    /// Bearer token; browsers cannot set headers on a websocket handshake.
+    pub token: Option<String>,
+}
+
+/// Client frame: `{"message": "..."}`.
+#[derive(Deserialize)]
+struct ChatFrame {
+    message: String,
+}
+
+/// GET /ws/chat — chat with the agent over a websocket. Each text frame
+/// `{"message": "..."}` is answered with `{"type": "response", "content"}`
+/// or `{"type": "error", "error"}`; the conversation lasts as long as the
+/// socket.
+pub async fn handle_ws_chat(
+    State(state): State<AppState>,
+    Query(query): Query<ChatQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let token = query.token.unwrap_or_default();
+    if let Err(e) = api::require_token(&state, &token) {
+        return e.into_response();
+    }
+    ws.on_upgrade(move |socket| chat(socket, state, token))
+}
+
+async fn chat(mut socket: WebSocket, state: AppState, token: String) {
+    let mut history = Vec::new();
+    let session = format!("ws:{}", uuid::Uuid::new_v4());
+    while let Some(Ok(frame)) = socket.recv().await {
+        let text = match frame {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let reply = chat_turn(&state, &token, &session, &mut history, text.as_str()).await;
+        if socket
+            .send(Message::Text(reply.to_string().into()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+async fn chat_turn(
+    state: &AppState,
+    token: &str,
+    session: &str,
+    history: &mut Vec<ChatMessage>,
+    frame: &str,
+) -> serde_json::Value {
+    let error = |error: &str| serde_json::json!({ "type": "error", "error": error });
+    let Ok(ChatFrame { message }) = serde_json::from_str(frame) else {
+        return error("Expected {\"message\": \"...\"}");
+    };
+    let message = message.trim();
+    if message.is_empty() {
+        return error("message must not be empty");
+    }
+    if state.pairing.require_pairing() && !state.rate_limiter.check_token(token).is_allowed() {
+        return error("Too many requests for this token. Please retry later.");
+    }
+
+    let config = state.config.lock().clone();
+    let options = RunOptions {
+        history: history.clone(),
+        ..RunOptions::default()
+    };
+    match super::run_agent_turn(state, session.to_string(), config, message, options).await {
+        Ok(output) => {
+            history.push(ChatMessage::user(message));
+            history.push(ChatMessage::assistant(&output));
+            let excess = history.len().saturating_sub(CHAT_HISTORY_MESSAGES);
+            history.drain(..excess);
+            serde_json::json!({ "type": "response", "content": output })
+        }
+        Err(e) => {
+            let safe_error = crate::providers::sanitize_api_error(&format!("{e:#}"));
+            tracing::warn!("Dashboard chat turn failed: {safe_error}");
+            error(&safe_error)
+        }
+    }
+}
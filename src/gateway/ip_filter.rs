@@ -0,0 +1,291 @@
+//! Client address filtering (`[gateway] ip_allowlist` / `ip_denylist`).
+//!
+//! Runs ahead of every route and of pairing auth. The client address is the
+//! TCP peer, or, when `trust_forwarded_headers` is on, the rightmost
+//! `X-Forwarded-For` hop that is not one of `trusted_proxies`: hops to its
+//! left were written by the client and can be spoofed. With `trusted_proxies`
+//! set, forwarded headers from any other peer are ignored. The denylist wins
+//! over the allowlist; an empty allowlist admits every address not denied.
+
+use super::{parse_client_ip, AppState};
+use crate::config::GatewayConfig;
+use anyhow::{Context, Result};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Why a client was turned away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// The address is in `ip_denylist`.
+    Denylisted,
+    /// `ip_allowlist` is set and the address is not in it.
+    NotAllowlisted,
+    /// Filtering is on but the client address is unknown.
+    UnknownAddress,
+}
+
+impl Rejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Denylisted => "denylisted",
+            Self::NotAllowlisted => "not_allowlisted",
+            Self::UnknownAddress => "unknown_address",
+        }
+    }
+}
+
+/// Parse a CIDR block (`10.0.0.0/8`, `fd00::/8`) or a single address.
+pub fn parse_network(raw: &str) -> Result<IpNet> {
+    let raw = raw.trim();
+    raw.parse::<IpNet>()
+        .or_else(|_| raw.parse::<IpAddr>().map(IpNet::from))
+        .with_context(|| format!("{raw:?} is not an IP address or CIDR block"))
+}
+
+#[derive(Debug, Default)]
+pub struct IpFilter {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+    trust_forwarded_headers: bool,
+    trusted_proxies: Vec<IpNet>,
+    denylisted: AtomicU64,
+    not_allowlisted: AtomicU64,
+    unknown_address: AtomicU64,
+}
+
+impl IpFilter {
+    pub fn new(config: &GatewayConfig) -> Result<Self> {
+        let parse = |list: &[String], key: &str| {
+            list.iter()
+                .map(|raw| parse_network(raw).with_context(|| format!("Invalid gateway.{key}")))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            allow: parse(&config.ip_allowlist, "ip_allowlist")?,
+            deny: parse(&config.ip_denylist, "ip_denylist")?,
+            trust_forwarded_headers: config.trust_forwarded_headers,
+            trusted_proxies: parse(&config.trusted_proxies, "trusted_proxies")?,
+            ..Self::default()
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        !self.allow.is_empty() || !self.deny.is_empty()
+    }
+
+    /// Decide on a client address, counting rejections.
+    pub fn check(&self, ip: Option<IpAddr>) -> Result<(), Rejection> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let rejection = match ip.map(|ip| ip.to_canonical()) {
+            None => Some(Rejection::UnknownAddress),
+            Some(ip) if self.deny.iter().any(|net| net.contains(&ip)) => {
+                Some(Rejection::Denylisted)
+            }
+            Some(ip)
+                if !self.allow.is_empty() && !self.allow.iter().any(|net| net.contains(&ip)) =>
+            {
+                Some(Rejection::NotAllowlisted)
+            }
+            Some(_) => None,
+        };
+        match rejection {
+            None => Ok(()),
+            Some(rejection) => {
+                let counter = match rejection {
+                    Rejection::Denylisted => &self.denylisted,
+                    Rejection::NotAllowlisted => &self.not_allowlisted,
+                    Rejection::UnknownAddress => &self.unknown_address,
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+                Err(rejection)
+            }
+        }
+    }
+
+    /// Rejections so far, by reason.
+    pub fn rejections(&self) -> [(Rejection, u64); 3] {
+        [
+            (
+                Rejection::Denylisted,
+                self.denylisted.load(Ordering::Relaxed),
+            ),
+            (
+                Rejection::NotAllowlisted,
+                self.not_allowlisted.load(Ordering::Relaxed),
+            ),
+            (
+                Rejection::UnknownAddress,
+                self.unknown_address.load(Ordering::Relaxed),
+            ),
+        ]
+    }
+
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        if !self.trust_forwarded_headers
+            || (!self.trusted_proxies.is_empty()
+                && !peer.is_some_and(|ip| self.is_trusted_proxy(ip)))
+        {
+            return peer;
+        }
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        if let Some(xff) = header("X-Forwarded-For") {
+            // Walk from the proxy nearest to us; the first hop it did not
+            // forward for itself is the client. A malformed hop leaves the
+            // address unknown rather than trusting what lies beyond it.
+            let mut client = None;
+            for hop in xff.rsplit(',') {
+                let ip = parse_client_ip(hop)?;
+                client = Some(ip);
+                if !self.is_trusted_proxy(ip) {
+                    break;
+                }
+            }
+            if client.is_some() {
+                return client;
+            }
+        }
+        header("X-Real-IP").and_then(parse_client_ip).or(peer)
+    }
+}
+
+/// Middleware refusing filtered clients with `403` before any handler runs.
+pub async fn enforce(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let ip = state.ip_filter.client_ip(&request);
+    if let Err(rejection) = state.ip_filter.check(ip) {
+        tracing::warn!(
+            client_ip = ip.map(|ip| ip.to_string()).as_deref().unwrap_or("unknown"),
+            reason = rejection.as_str(),
+            "Rejected {} {} by the gateway IP filter",
+            request.method(),
+            request.uri().path()
+        );
+        let err = serde_json::json!({
+            "error": "Forbidden: this client address may not use the gateway",
+            "reason": rejection.as_str(),
+        });
+        return (StatusCode::FORBIDDEN, Json(err)).into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(allow: &[&str], deny: &[&str]) -> IpFilter {
+        let config = GatewayConfig {
+            ip_allowlist: allow.iter().map(|s| (*s).to_string()).collect(),
+            ip_denylist: deny.iter().map(|s| (*s).to_string()).collect(),
+            ..GatewayConfig::default()
+        };
+        IpFilter::new(&config).unwrap()
+    }
+
+    fn proxied(trusted_proxies: &[&str], allow: &[&str]) -> IpFilter {
+        let config = GatewayConfig {
+            trust_forwarded_headers: true,
+            trusted_proxies: trusted_proxies.iter().map(|s| (*s).to_string()).collect(),
+            ip_allowlist: allow.iter().map(|s| (*s).to_string()).collect(),
+            ..GatewayConfig::default()
+        };
+        IpFilter::new(&config).unwrap()
+    }
+
+    fn request(peer: &str, forwarded_for: &str) -> Request {
+        let mut request = Request::builder()
+            .header("X-Forwarded-For", forwarded_for)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let peer: SocketAddr = format!("{peer}:50000").parse().unwrap();
+        request.extensions_mut().insert(ConnectInfo(peer));
+        request
+    }
+
+    fn ip(raw: &str) -> Option<IpAddr> {
+        Some(raw.parse().unwrap())
+    }
+
+    #[test]
+    fn parses_cidr_blocks_and_single_addresses() {
+        assert_eq!(parse_network("10.0.0.0/8").unwrap().prefix_len(), 8);
+        assert_eq!(parse_network(" 192.168.1.7 ").unwrap().prefix_len(), 32);
+        assert_eq!(parse_network("::1").unwrap().prefix_len(), 128);
+        assert!(parse_network("10.0.0.0/33").is_err());
+        assert!(parse_network("lan").is_err());
+    }
+
+    #[test]
+    fn denylist_wins_and_allowlist_restricts() {
+        let open = filter(&[], &[]);
+        assert!(open.check(None).is_ok());
+
+        let lan = filter(&["192.168.0.0/16", "::1"], &["192.168.66.0/24"]);
+        assert!(lan.check(ip("192.168.1.20")).is_ok());
+        assert!(lan.check(ip("::1")).is_ok());
+        // IPv4-mapped IPv6 peers are matched as IPv4.
+        assert!(lan.check(ip("::ffff:192.168.1.20")).is_ok());
+        assert_eq!(lan.check(ip("192.168.66.5")), Err(Rejection::Denylisted));
+        assert_eq!(lan.check(ip("8.8.8.8")), Err(Rejection::NotAllowlisted));
+        assert_eq!(lan.check(None), Err(Rejection::UnknownAddress));
+
+        let deny_only = filter(&[], &["203.0.113.0/24"]);
+        assert!(deny_only.check(ip("8.8.8.8")).is_ok());
+        assert_eq!(
+            deny_only.check(ip("203.0.113.9")),
+            Err(Rejection::Denylisted)
+        );
+
+        let counts = lan.rejections();
+        assert!(counts.iter().all(|(_, count)| *count == 1));
+    }
+
+    #[test]
+    fn spoofed_forwarded_for_entries_are_ignored() {
+        // The client claims a LAN address; the proxy appends the real one.
+        let lan_only = proxied(&[], &["10.0.0.0/8"]);
+        let spoofed = request("127.0.0.1", "10.0.0.5, 203.0.113.9");
+        let client = lan_only.client_ip(&spoofed);
+        assert_eq!(client, ip("203.0.113.9"));
+        assert_eq!(lan_only.check(client), Err(Rejection::NotAllowlisted));
+
+        // Hops added by trusted proxies are skipped, spoofed ones are not reached.
+        let chained = proxied(&["10.1.0.0/16"], &[]);
+        let via_proxies = request("10.1.0.2", "10.0.0.5, 198.51.100.7, 10.1.0.3");
+        assert_eq!(chained.client_ip(&via_proxies), ip("198.51.100.7"));
+        assert_eq!(
+            chained.client_ip(&request("10.1.0.2", "10.0.0.5, bogus, 10.1.0.3")),
+            None
+        );
+
+        // Peers outside trusted_proxies cannot forward at all.
+        let direct = request("198.51.100.7", "10.0.0.5");
+        assert_eq!(chained.client_ip(&direct), ip("198.51.100.7"));
+
+        // Without trust_forwarded_headers only the peer counts.
+        let untrusting = filter(&["10.0.0.0/8"], &[]);
+        assert_eq!(untrusting.client_ip(&spoofed), ip("127.0.0.1"));
+    }
+}
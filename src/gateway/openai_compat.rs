@@ -0,0 +1,516 @@
+//! OpenAI-compatible API surface (`/v1/chat/completions`, `/v1/models`).
+//!
+//! Lets chat UIs and IDE plugins that speak the OpenAI wire format use the
+//! local ZeroClaw gateway as a backend. Authentication reuses the pairing
+//! bearer token: clients put it where they would normally put an OpenAI key
+//! (`Authorization: Bearer <token>`).
+
+use super::api::require_auth;
+use super::{record_gateway_turn, AppState};
+use crate::providers::structured::{self, StructuredOutputError};
+use crate::providers::{self, ChatMessage, ResponseFormat, StreamOptions};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use futures_util::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+
+/// Owner string reported for every model in `/v1/models`.
+const MODEL_OWNER: &str = "zeroclaw";
+
+/// Session context used when recording `/v1/chat/completions` turns.
+const OPENAI_SESSION_CONTEXT: &str = "openai";
+
+// ── Wire types ──────────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub stream: bool,
+    /// `{"type": "json_object"}` or `{"type": "json_schema", "json_schema": {...}}`.
+    #[serde(default)]
+    pub response_format: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    /// Either a plain string or an array of content parts
+    /// (`[{"type": "text", "text": "..."}]`).
+    #[serde(default)]
+    pub content: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionResponse {
+    id: String,
+    object: &'static str,
+    created: i64,
+    model: String,
+    choices: Vec<ChatCompletionChoice>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionChoice {
+    index: u32,
+    message: ChatCompletionChoiceMessage,
+    finish_reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionChoiceMessage {
+    role: &'static str,
+    content: String,
+}
+
+// ── Conversion helpers ──────────────────────────────────────────
+
+/// Flatten OpenAI message content (string or content-part array) into text.
+fn content_to_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| {
+                if part.get("type").and_then(|t| t.as_str()) == Some("text") {
+                    part.get("text").and_then(|t| t.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Convert OpenAI request messages into provider chat history. Unknown roles
+/// are rejected so clients get a clear 400 instead of silently dropped turns.
+fn convert_messages(messages: &[ChatCompletionMessage]) -> Result<Vec<ChatMessage>, String> {
+    messages
+        .iter()
+        .map(|msg| {
+            let text = content_to_text(&msg.content);
+            match msg.role.as_str() {
+                "system" | "developer" => Ok(ChatMessage::system(text)),
+                "user" => Ok(ChatMessage::user(text)),
+                "assistant" => Ok(ChatMessage::assistant(text)),
+                other => Err(format!("Unsupported message role: {other}")),
+            }
+        })
+        .collect()
+}
+
+/// Parse an OpenAI `response_format` object; `{"type": "text"}` means none.
+fn parse_response_format(value: &serde_json::Value) -> Result<Option<ResponseFormat>, String> {
+    match value.get("type").and_then(serde_json::Value::as_str) {
+        Some("text") => Ok(None),
+        Some("json_object") => Ok(Some(ResponseFormat::JsonObject)),
+        Some("json_schema") => {
+            let spec = value
+                .get("json_schema")
+                .ok_or("response_format.json_schema is required for type json_schema")?;
+            let schema = spec
+                .get("schema")
+                .cloned()
+                .ok_or("response_format.json_schema.schema is required")?;
+            crate::tools::SchemaCleanr::validate(&schema)
+                .map_err(|e| format!("Invalid response_format schema: {e}"))?;
+            Ok(Some(ResponseFormat::JsonSchema {
+                name: spec
+                    .get("name")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("response")
+                    .to_string(),
+                schema,
+                strict: spec
+                    .get("strict")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false),
+            }))
+        }
+        other => Err(format!(
+            "Unsupported response_format type: {}",
+            other.unwrap_or("<missing>")
+        )),
+    }
+}
+
+fn completion_id() -> String {
+    format!("chatcmpl-{}", uuid::Uuid::new_v4().simple())
+}
+
+fn chunk_payload(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: &serde_json::Value,
+    finish: Option<&str>,
+) -> String {
+    serde_json::json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish,
+        }],
+    })
+    .to_string()
+}
+
+fn openai_error(status: StatusCode, message: &str, kind: &str) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "error": {
+                "message": message,
+                "type": kind,
+            }
+        })),
+    )
+        .into_response()
+}
+
+// ── Handlers ────────────────────────────────────────────────────
+
+/// GET /v1/models — list the configured model
+pub async fn handle_models(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    Json(serde_json::json!({
+        "object": "list",
+        "data": [{
+            "id": state.model,
+            "object": "model",
+            "created": 0,
+            "owned_by": MODEL_OWNER,
+        }],
+    }))
+    .into_response()
+}
+
+/// POST /v1/chat/completions — OpenAI-compatible chat (streaming and non-streaming)
+pub async fn handle_chat_completions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Json(request) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            return openai_error(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid request body: {e}"),
+                "invalid_request_error",
+            );
+        }
+    };
+
+    let mut messages = match convert_messages(&request.messages) {
+        Ok(m) => m,
+        Err(msg) => return openai_error(StatusCode::BAD_REQUEST, &msg, "invalid_request_error"),
+    };
+    if !messages.iter().any(|m| m.role == "user") {
+        return openai_error(
+            StatusCode::BAD_REQUEST,
+            "At least one user message is required",
+            "invalid_request_error",
+        );
+    }
+    let response_format = match request
+        .response_format
+        .as_ref()
+        .map(parse_response_format)
+        .transpose()
+    {
+        Ok(format) => format.flatten(),
+        Err(msg) => return openai_error(StatusCode::BAD_REQUEST, &msg, "invalid_request_error"),
+    };
+    if response_format.is_some() && request.stream {
+        return openai_error(
+            StatusCode::BAD_REQUEST,
+            "response_format is not supported with stream=true",
+            "invalid_request_error",
+        );
+    }
+
+    // Mirror webhook behavior: inject the workspace system prompt unless the
+    // client already supplied one.
+    if !messages.iter().any(|m| m.role == "system") {
+        let system_prompt = {
+            let config_guard = state.config.lock();
+            crate::channels::build_system_prompt(
+                &config_guard.workspace_dir,
+                &state.model,
+                &[],
+                None,
+            )
+        };
+        messages.insert(0, ChatMessage::system(system_prompt));
+    }
+
+    // Only the configured model is served; requested names are echoed back.
+    let model = request.model.clone().unwrap_or_else(|| state.model.clone());
+    let temperature = request
+        .temperature
+        .filter(|t| (0.0..=2.0).contains(t))
+        .unwrap_or(state.temperature);
+    let id = completion_id();
+    let created = chrono::Utc::now().timestamp();
+
+    if request.stream {
+        return stream_completion(&state, &messages, id, created, model, temperature).await;
+    }
+
+    let reply = match &response_format {
+        Some(format) => structured::chat_json(
+            state.provider.as_ref(),
+            &messages,
+            format,
+            &state.model,
+            temperature,
+            structured::DEFAULT_STRUCTURED_OUTPUT_ATTEMPTS,
+        )
+        .await
+        .map(|value| value.to_string()),
+        None => {
+            state
+                .provider
+                .chat_with_history(&messages, &state.model, temperature)
+                .await
+        }
+    };
+
+    match reply {
+        Ok(text) => {
+            let last_user = messages
+                .iter()
+                .rfind(|m| m.role == "user")
+                .map_or("", |m| m.content.as_str());
+            record_gateway_turn(&state, OPENAI_SESSION_CONTEXT, last_user, &text).await;
+
+            Json(ChatCompletionResponse {
+                id,
+                object: "chat.completion",
+                created,
+                model,
+                choices: vec![ChatCompletionChoice {
+                    index: 0,
+                    message: ChatCompletionChoiceMessage {
+                        role: "assistant",
+                        content: text,
+                    },
+                    finish_reason: "stop",
+                }],
+            })
+            .into_response()
+        }
+        Err(e) => {
+            if let Some(invalid) = e.downcast_ref::<StructuredOutputError>() {
+                return openai_error(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    &providers::sanitize_api_error(&invalid.to_string()),
+                    "invalid_response_format",
+                );
+            }
+            let sanitized = providers::sanitize_api_error(&e.to_string());
+            tracing::error!("/v1/chat/completions provider error: {sanitized}");
+            openai_error(StatusCode::BAD_GATEWAY, "LLM request failed", "api_error")
+        }
+    }
+}
+
+/// Stream a completion as OpenAI `chat.completion.chunk` SSE events. Providers
+/// without native streaming are called once and relayed as a single delta.
+async fn stream_completion(
+    state: &AppState,
+    messages: &[ChatMessage],
+    id: String,
+    created: i64,
+    model: String,
+    temperature: f64,
+) -> Response {
+    let role_chunk = chunk_payload(
+        &id,
+        created,
+        &model,
+        &serde_json::json!({"role": "assistant"}),
+        None,
+    );
+
+    let deltas: stream::BoxStream<'static, String> = if state.provider.supports_streaming() {
+        state
+            .provider
+            .stream_chat_with_history(
+                messages,
+                &state.model,
+                temperature,
+                StreamOptions::new(true),
+            )
+            .filter_map(|chunk| async move {
+                match chunk {
+                    Ok(chunk) if !chunk.delta.is_empty() => Some(chunk.delta),
+                    Ok(_) => None,
+                    Err(e) => {
+                        tracing::warn!("/v1/chat/completions stream error: {e}");
+                        None
+                    }
+                }
+            })
+            .boxed()
+    } else {
+        match state
+            .provider
+            .chat_with_history(messages, &state.model, temperature)
+            .await
+        {
+            Ok(text) => stream::once(async move { text }).boxed(),
+            Err(e) => {
+                let sanitized = providers::sanitize_api_error(&e.to_string());
+                tracing::error!("/v1/chat/completions provider error: {sanitized}");
+                return openai_error(StatusCode::BAD_GATEWAY, "LLM request failed", "api_error");
+            }
+        }
+    };
+
+    let content_id = id.clone();
+    let content_model = model.clone();
+    let content_events = deltas.map(move |delta| {
+        chunk_payload(
+            &content_id,
+            created,
+            &content_model,
+            &serde_json::json!({"content": delta}),
+            None,
+        )
+    });
+    let stop_chunk = chunk_payload(&id, created, &model, &serde_json::json!({}), Some("stop"));
+
+    let events = stream::once(async move { role_chunk })
+        .chain(content_events)
+        .chain(stream::iter([stop_chunk, "[DONE]".to_string()]))
+        .map(|data| Ok::<Event, Infallible>(Event::default().data(data)));
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_to_text_handles_string_and_parts() {
+        assert_eq!(content_to_text(&serde_json::json!("hi")), "hi");
+        let parts = serde_json::json!([
+            {"type": "text", "text": "first"},
+            {"type": "image_url", "image_url": {"url": "http://x"}},
+            {"type": "text", "text": "second"},
+        ]);
+        assert_eq!(content_to_text(&parts), "first\nsecond");
+        assert_eq!(content_to_text(&serde_json::Value::Null), "");
+    }
+
+    #[test]
+    fn convert_messages_maps_roles() {
+        let request: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "gpt-4o",
+            "messages": [
+                {"role": "developer", "content": "be brief"},
+                {"role": "user", "content": "hello"},
+                {"role": "assistant", "content": "hi"},
+            ],
+        }))
+        .unwrap();
+        assert!(!request.stream);
+
+        let converted = convert_messages(&request.messages).unwrap();
+        let roles: Vec<&str> = converted.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant"]);
+        assert_eq!(converted[1].content, "hello");
+    }
+
+    #[test]
+    fn convert_messages_rejects_unknown_role() {
+        let messages = vec![ChatCompletionMessage {
+            role: "function".into(),
+            content: serde_json::json!("x"),
+        }];
+        assert!(convert_messages(&messages).is_err());
+    }
+
+    #[test]
+    fn chunk_payload_matches_openai_shape() {
+        let raw = chunk_payload(
+            "chatcmpl-1",
+            42,
+            "m",
+            &serde_json::json!({"content": "hey"}),
+            None,
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(parsed["object"], "chat.completion.chunk");
+        assert_eq!(parsed["choices"][0]["delta"]["content"], "hey");
+        assert!(parsed["choices"][0]["finish_reason"].is_null());
+    }
+
+    #[test]
+    fn parse_response_format_accepts_openai_shapes() {
+        assert_eq!(
+            parse_response_format(&serde_json::json!({"type": "text"})).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_response_format(&serde_json::json!({"type": "json_object"})).unwrap(),
+            Some(ResponseFormat::JsonObject)
+        );
+
+        let format = parse_response_format(&serde_json::json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": "weather",
+                "strict": true,
+                "schema": {"type": "object", "properties": {"temp": {"type": "number"}}}
+            }
+        }))
+        .unwrap()
+        .unwrap();
+        let ResponseFormat::JsonSchema { name, strict, .. } = format else {
+            panic!("expected json_schema format");
+        };
+        assert_eq!(name, "weather");
+        assert!(strict);
+
+        assert!(parse_response_format(&serde_json::json!({"type": "json_schema"})).is_err());
+        assert!(parse_response_format(&serde_json::json!({"type": "xml"})).is_err());
+    }
+
+    #[test]
+    fn completion_ids_are_prefixed_and_unique() {
+        let a = completion_id();
+        let b = completion_id();
+        assert!(a.starts_with("chatcmpl-"));
+        assert_ne!(a, b);
+    }
+}
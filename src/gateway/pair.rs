@@ -0,0 +1,167 @@
+//! `zeroclaw pair` — pair a new device with a running gateway.
+//!
+//! Opens a short-lived pairing window through the gateway's loopback-only
+//! `/pair/window` endpoint, shows the one-time code and a QR code carrying
+//! the gateway URL plus code, then waits until a device pairs or the window
+//! expires. The gateway itself persists the issued token (hashed, with label
+//! and creation time) into `gateway.paired_tokens`.
+
+use super::qr::QrCode;
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+
+/// How often the window status is polled while waiting for a device.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Base URL for reaching the local gateway. Wildcard binds are reached via
+/// loopback, since window endpoints only answer loopback peers.
+pub(crate) fn local_gateway_base(host: &str, port: u16) -> String {
+    let host = match host {
+        "0.0.0.0" | "" => "127.0.0.1",
+        "::" | "[::]" => "[::1]",
+        other => other,
+    };
+    if host.contains(':') && !host.starts_with('[') {
+        format!("http://[{host}]:{port}")
+    } else {
+        format!("http://{host}:{port}")
+    }
+}
+
+/// URI encoded in the QR code: where to pair and with which code.
+fn pairing_uri(gateway_url: &str, code: &str) -> Result<String> {
+    let mut uri = reqwest::Url::parse("zeroclaw://pair").context("invalid pairing URI base")?;
+    uri.query_pairs_mut()
+        .append_pair("gateway", gateway_url.trim_end_matches('/'))
+        .append_pair("code", code);
+    Ok(uri.to_string())
+}
+
+fn error_message(body: &serde_json::Value) -> String {
+    body.get("error")
+        .and_then(|e| e.as_str())
+        .unwrap_or("unknown error")
+        .to_string()
+}
+
+/// Run the interactive pairing flow against the configured gateway.
+pub async fn run_pair(
+    config: &Config,
+    label: Option<String>,
+    ttl_secs: u64,
+    port: Option<u16>,
+    public_url: Option<String>,
+) -> Result<()> {
+    let port = port.unwrap_or(config.gateway.port);
+    let base = local_gateway_base(&config.gateway.host, port);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let response = client
+        .post(format!("{base}/pair/window"))
+        .json(&serde_json::json!({ "label": label, "ttl_secs": ttl_secs }))
+        .send()
+        .await
+        .with_context(|| {
+            format!("Gateway is not reachable at {base}. Start it first with `zeroclaw gateway`.")
+        })?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    if !status.is_success() {
+        bail!(
+            "Gateway refused to open a pairing window: {}",
+            error_message(&body)
+        );
+    }
+
+    let code = body
+        .get("code")
+        .and_then(|c| c.as_str())
+        .context("Gateway response is missing the pairing code")?
+        .to_string();
+    let expires_in = body
+        .get("expires_in_secs")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(ttl_secs);
+
+    let gateway_url = public_url.unwrap_or_else(|| base.clone());
+    let uri = pairing_uri(&gateway_url, &code)?;
+
+    println!("🔐 Pairing window open for {expires_in}s");
+    println!();
+    match QrCode::encode(uri.as_bytes()) {
+        Ok(qr) => print!("{}", qr.render_terminal()),
+        Err(e) => println!("  (QR code unavailable: {e})"),
+    }
+    println!();
+    println!("  One-time code: {code}");
+    println!("  Pairing URI:   {uri}");
+    println!("  Or send: POST {gateway_url}/pair with header X-Pairing-Code: {code}");
+    println!();
+    println!("  Waiting for a device to pair (Ctrl+C to cancel)...");
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(expires_in + 5);
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!("Pairing cancelled; the code expires on its own.");
+                return Ok(());
+            }
+            () = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+
+        let body: serde_json::Value = match client.get(format!("{base}/pair/window")).send().await {
+            Ok(resp) => resp.json().await.unwrap_or_default(),
+            Err(e) => {
+                tracing::debug!("pairing window poll failed: {e}");
+                serde_json::Value::Null
+            }
+        };
+
+        match body.get("status").and_then(|s| s.as_str()) {
+            Some("paired") => {
+                let who = body
+                    .get("label")
+                    .and_then(|l| l.as_str())
+                    .map_or_else(|| "device".to_string(), |l| format!("'{l}'"));
+                println!("✅ Paired {who}. Token saved to gateway.paired_tokens.");
+                return Ok(());
+            }
+            Some("expired") => bail!("Pairing window expired before a device paired"),
+            Some("closed") => bail!("Gateway restarted; pairing window was lost"),
+            _ => {}
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            bail!("Timed out waiting for the gateway to report a paired device");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_gateway_base_maps_wildcard_binds_to_loopback() {
+        assert_eq!(
+            local_gateway_base("0.0.0.0", 42617),
+            "http://127.0.0.1:42617"
+        );
+        assert_eq!(local_gateway_base("::", 8080), "http://[::1]:8080");
+        assert_eq!(local_gateway_base("::1", 8080), "http://[::1]:8080");
+        assert_eq!(local_gateway_base("localhost", 1), "http://localhost:1");
+    }
+
+    #[test]
+    fn pairing_uri_encodes_gateway_and_code() {
+        let uri = pairing_uri("https://claw.example.com/", "123456").unwrap();
+        assert_eq!(
+            uri,
+            "zeroclaw://pair?gateway=https%3A%2F%2Fclaw.example.com&code=123456"
+        );
+        assert!(QrCode::encode(uri.as_bytes()).is_ok());
+    }
+}
@@ -0,0 +1,557 @@
+//! Minimal QR code encoder for terminal display.
+//!
+//! Supports byte mode at error-correction level M for versions 1–10 (up to
+//! 213 bytes), which is plenty for pairing URIs. Rendering uses Unicode
+//! half blocks so each text row carries two module rows.
+
+use anyhow::{bail, Result};
+
+/// Largest supported symbol version.
+const MAX_VERSION: usize = 10;
+/// Light border around the symbol, in modules.
+const QUIET_ZONE: usize = 2;
+
+/// Error-correction block layout for one version at level M. Group 2
+/// blocks (if any) hold one more data codeword than group 1 blocks.
+struct BlockLayout {
+    ec_per_block: usize,
+    group1_blocks: usize,
+    group1_data: usize,
+    group2_blocks: usize,
+}
+
+impl BlockLayout {
+    const fn new(
+        ec_per_block: usize,
+        group1_blocks: usize,
+        group1_data: usize,
+        group2_blocks: usize,
+    ) -> Self {
+        Self {
+            ec_per_block,
+            group1_blocks,
+            group1_data,
+            group2_blocks,
+        }
+    }
+
+    fn data_codewords(&self) -> usize {
+        self.group1_blocks * self.group1_data + self.group2_blocks * (self.group1_data + 1)
+    }
+}
+
+const LAYOUT_M: [BlockLayout; MAX_VERSION] = [
+    BlockLayout::new(10, 1, 16, 0),
+    BlockLayout::new(16, 1, 28, 0),
+    BlockLayout::new(26, 1, 44, 0),
+    BlockLayout::new(18, 2, 32, 0),
+    BlockLayout::new(24, 2, 43, 0),
+    BlockLayout::new(16, 4, 27, 0),
+    BlockLayout::new(18, 4, 31, 0),
+    BlockLayout::new(22, 2, 38, 2),
+    BlockLayout::new(22, 3, 36, 2),
+    BlockLayout::new(26, 4, 43, 1),
+];
+
+const ALIGNMENT_POSITIONS: [&[usize]; MAX_VERSION] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+/// An encoded QR symbol.
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encode `data` in byte mode, picking the smallest version that fits.
+    pub fn encode(data: &[u8]) -> Result<Self> {
+        let Some(version) = (1..=MAX_VERSION)
+            .find(|&v| LAYOUT_M[v - 1].data_codewords() * 8 >= 4 + count_bits(v) + data.len() * 8)
+        else {
+            bail!(
+                "QR payload too long ({} bytes, max {})",
+                data.len(),
+                (LAYOUT_M[MAX_VERSION - 1].data_codewords() * 8 - 4 - count_bits(MAX_VERSION)) / 8
+            );
+        };
+
+        let layout = &LAYOUT_M[version - 1];
+        let codewords = interleave_with_ec(&data_codewords(data, version, layout), layout);
+
+        let mut grid = Grid::new(version);
+        grid.draw_function_patterns();
+        grid.draw_codewords(&codewords);
+
+        let mut best: Option<(u32, Grid)> = None;
+        for mask in 0..8 {
+            let mut candidate = grid.clone();
+            candidate.apply_mask(mask);
+            candidate.draw_format_bits(mask);
+            let score = candidate.penalty();
+            if best.as_ref().is_none_or(|(s, _)| score < *s) {
+                best = Some((score, candidate));
+            }
+        }
+        let (_, grid) = best.expect("eight masks evaluated");
+
+        Ok(Self {
+            size: grid.size,
+            modules: grid.modules,
+        })
+    }
+
+    /// Width (and height) of the symbol in modules, excluding the quiet zone.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x`, row `y` is dark.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// Render for a terminal. Light modules are drawn as blocks so the code
+    /// scans on the usual dark-background terminal.
+    pub fn render_terminal(&self) -> String {
+        let total = self.size + QUIET_ZONE * 2;
+        let ink = |x: usize, y: usize| -> bool {
+            if y >= total {
+                return false;
+            }
+            let inside = (QUIET_ZONE..QUIET_ZONE + self.size).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + self.size).contains(&y);
+            !inside || !self.is_dark(x - QUIET_ZONE, y - QUIET_ZONE)
+        };
+
+        let mut out = String::new();
+        for y in (0..total).step_by(2) {
+            for x in 0..total {
+                out.push(match (ink(x, y), ink(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Width of the byte-mode character count field.
+fn count_bits(version: usize) -> usize {
+    if version <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: usize, len: usize) {
+    for i in (0..len).rev() {
+        bits.push((value >> i) & 1 == 1);
+    }
+}
+
+/// Build the padded data codeword sequence for byte mode.
+fn data_codewords(data: &[u8], version: usize, layout: &BlockLayout) -> Vec<u8> {
+    let capacity = layout.data_codewords() * 8;
+    let mut bits: Vec<bool> = Vec::with_capacity(capacity);
+
+    push_bits(&mut bits, 0b0100, 4);
+    push_bits(&mut bits, data.len(), count_bits(version));
+    for &byte in data {
+        push_bits(&mut bits, usize::from(byte), 8);
+    }
+    let terminator = (capacity - bits.len()).min(4);
+    push_bits(&mut bits, 0, terminator);
+    let padding = (8 - bits.len() % 8) % 8;
+    push_bits(&mut bits, 0, padding);
+
+    let mut bytes: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |acc, &b| (acc << 1) | u8::from(b)))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if bytes.len() >= layout.data_codewords() {
+            break;
+        }
+        bytes.push(pad);
+    }
+    bytes
+}
+
+/// Split data into blocks, append Reed-Solomon codewords, and interleave.
+fn interleave_with_ec(data: &[u8], layout: &BlockLayout) -> Vec<u8> {
+    let divisor = rs_divisor(layout.ec_per_block);
+    let mut blocks: Vec<(&[u8], Vec<u8>)> = Vec::new();
+    let mut offset = 0;
+    for i in 0..layout.group1_blocks + layout.group2_blocks {
+        let len = if i < layout.group1_blocks {
+            layout.group1_data
+        } else {
+            layout.group1_data + 1
+        };
+        let block = &data[offset..offset + len];
+        offset += len;
+        blocks.push((block, rs_remainder(block, &divisor)));
+    }
+
+    let mut result = Vec::with_capacity(data.len() + blocks.len() * layout.ec_per_block);
+    for i in 0..=layout.group1_data {
+        for (block, _) in &blocks {
+            if let Some(&b) = block.get(i) {
+                result.push(b);
+            }
+        }
+    }
+    for i in 0..layout.ec_per_block {
+        for (_, ec) in &blocks {
+            result.push(ec[i]);
+        }
+    }
+    result
+}
+
+/// Multiply in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u16 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= u16::from((y >> i) & 1) * u16::from(x);
+    }
+    // Reduction above keeps z within 8 bits
+    z.to_le_bytes()[0]
+}
+
+/// Reed-Solomon generator polynomial coefficients (leading 1 omitted).
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+/// 15-bit format information for level M and the given mask.
+fn format_bits(mask: u8) -> u32 {
+    // Level M is encoded as 0b00
+    let data = u32::from(mask);
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    ((data << 10) | rem) ^ 0x5412
+}
+
+/// 18-bit version information (versions 7 and up).
+fn version_bits(version: usize) -> u32 {
+    let version = u32::try_from(version).unwrap_or(0);
+    let mut rem = version;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+    (version << 12) | rem
+}
+
+fn mask_applies(mask: u8, x: usize, y: usize) -> bool {
+    match mask {
+        0 => (x + y).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (x + y).is_multiple_of(3),
+        4 => (x / 3 + y / 2).is_multiple_of(2),
+        5 => (x * y) % 2 + (x * y) % 3 == 0,
+        6 => ((x * y) % 2 + (x * y) % 3).is_multiple_of(2),
+        _ => ((x + y) % 2 + (x * y) % 3).is_multiple_of(2),
+    }
+}
+
+#[derive(Clone)]
+struct Grid {
+    version: usize,
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl Grid {
+    fn new(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            version,
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self) {
+        for i in 0..self.size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        let far = self.size - 4;
+        for (cx, cy) in [(3, 3), (far, 3), (3, far)] {
+            self.draw_finder(cx, cy);
+        }
+
+        let positions = ALIGNMENT_POSITIONS[self.version - 1];
+        let last = positions.len().saturating_sub(1);
+        for (i, &cy) in positions.iter().enumerate() {
+            for (j, &cx) in positions.iter().enumerate() {
+                // Skip the three corners occupied by finder patterns
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in 0..5_usize {
+                    for dx in 0..5_usize {
+                        let ring = dx.abs_diff(2).max(dy.abs_diff(2));
+                        self.set_function(cx + dx - 2, cy + dy - 2, ring != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserve format areas; real bits are drawn once the mask is chosen
+        self.draw_format_bits(0);
+
+        if self.version >= 7 {
+            let bits = version_bits(self.version);
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let a = self.size - 11 + i % 3;
+                let b = i / 3;
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    /// Finder pattern plus its light separator, clipped to the symbol.
+    fn draw_finder(&mut self, cx: usize, cy: usize) {
+        for dy in 0..9 {
+            for dx in 0..9 {
+                let (Some(x), Some(y)) = ((cx + dx).checked_sub(4), (cy + dy).checked_sub(4))
+                else {
+                    continue;
+                };
+                if x >= self.size || y >= self.size {
+                    continue;
+                }
+                let ring = dx.abs_diff(4).max(dy.abs_diff(4));
+                self.set_function(x, y, ring != 2 && ring != 4);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u8) {
+        let bits = format_bits(mask);
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..6 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        // Always-dark module
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Place codewords along the standard two-column zigzag.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let total_bits = codewords.len() * 8;
+        let mut i = 0;
+        let mut right = self.size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..self.size {
+                let y = if upward { self.size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    let idx = y * self.size + x;
+                    if !self.function[idx] && i < total_bits {
+                        self.modules[idx] = (codewords[i / 8] >> (7 - i % 8)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let idx = y * self.size + x;
+                if !self.function[idx] && mask_applies(mask, x, y) {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    /// Standard mask penalty score (lower is better).
+    fn penalty(&self) -> u32 {
+        const FINDER_LIKE: [bool; 11] = [
+            true, false, true, true, true, false, true, false, false, false, false,
+        ];
+        let n = self.size;
+        let rows = (0..n).map(|y| (0..n).map(|x| self.get(x, y)).collect::<Vec<_>>());
+        let cols = (0..n).map(|x| (0..n).map(|y| self.get(x, y)).collect::<Vec<_>>());
+
+        let mut score = 0u32;
+        for line in rows.chain(cols) {
+            let mut run = 1u32;
+            for pair in line.windows(2) {
+                if pair[0] == pair[1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        score += run - 2;
+                    }
+                    run = 1;
+                }
+            }
+            if run >= 5 {
+                score += run - 2;
+            }
+
+            for window in line.windows(FINDER_LIKE.len()) {
+                if window == FINDER_LIKE || window.iter().rev().eq(FINDER_LIKE.iter()) {
+                    score += 40;
+                }
+            }
+        }
+
+        for y in 0..n - 1 {
+            for x in 0..n - 1 {
+                let c = self.get(x, y);
+                if c == self.get(x + 1, y) && c == self.get(x, y + 1) && c == self.get(x + 1, y + 1)
+                {
+                    score += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let percent = dark * 100 / (n * n);
+        let deviation = u32::try_from(percent.abs_diff(50) / 5).unwrap_or(u32::MAX);
+        score + deviation * 10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reed_solomon_matches_reference_vector() {
+        // "HELLO WORLD" at 1-M from the QR specification walkthrough
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let ec = rs_remainder(&data, &rs_divisor(10));
+        assert_eq!(ec, vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+
+    #[test]
+    fn format_and_version_bits_match_reference_values() {
+        assert_eq!(format_bits(0), 0b101_0100_0001_0010);
+        assert_eq!(version_bits(7), 0x07C94);
+    }
+
+    #[test]
+    fn encode_picks_smallest_fitting_version() {
+        assert_eq!(QrCode::encode(&[b'a'; 14]).unwrap().size(), 21);
+        assert_eq!(QrCode::encode(&[b'a'; 15]).unwrap().size(), 25);
+        assert_eq!(QrCode::encode(&[b'a'; 213]).unwrap().size(), 57);
+        assert!(QrCode::encode(&[b'a'; 214]).is_err());
+    }
+
+    #[test]
+    fn encode_draws_finder_patterns_and_timing() {
+        let qr = QrCode::encode(b"zeroclaw://pair?code=123456").unwrap();
+        let n = qr.size();
+        for (cx, cy) in [(3, 3), (n - 4, 3), (3, n - 4)] {
+            assert!(qr.is_dark(cx, cy));
+            assert!(!qr.is_dark(cx + 2, cy));
+            assert!(qr.is_dark(cx + 3, cy));
+        }
+        for i in 8..n - 8 {
+            assert_eq!(qr.is_dark(i, 6), i % 2 == 0);
+        }
+        assert!(qr.is_dark(8, n - 8));
+    }
+
+    #[test]
+    fn render_terminal_uses_half_block_rows() {
+        let qr = QrCode::encode(b"hi").unwrap();
+        let rendered = qr.render_terminal();
+        let total = qr.size() + QUIET_ZONE * 2;
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), total.div_ceil(2));
+        assert!(lines.iter().all(|l| l.chars().count() == total));
+        // Quiet zone renders as solid light blocks
+        assert!(lines[0].chars().all(|c| c == '█'));
+    }
+}
@@ -0,0 +1,173 @@
+//! Rhai runtime for webhook transformation scripts.
+//!
+//! Scripts run in a sandboxed engine: no module imports, bounded operations,
+//! call depth and data sizes. `print`/`debug` go to the gateway log.
+
+use super::transforms::{Inbound, WebhookTransform};
+use anyhow::{anyhow, bail, Result};
+use rhai::{Dynamic, Engine, Scope, AST};
+
+/// Operation budget per script call.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_BYTES: usize = 1024 * 1024;
+const MAX_COLLECTION_ITEMS: usize = 10_000;
+
+const INBOUND_FN: &str = "inbound";
+const OUTBOUND_FN: &str = "outbound";
+
+pub struct RhaiTransform {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    has_outbound: bool,
+}
+
+impl RhaiTransform {
+    pub fn compile(name: &str, source: &str) -> Result<Self> {
+        let engine = sandboxed_engine(name);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| anyhow!("webhook transform '{name}' failed to compile: {e}"))?;
+        let has_fn = |fn_name: &str, arity: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == fn_name && f.params.len() == arity)
+        };
+        if !has_fn(INBOUND_FN, 2) {
+            bail!("webhook transform '{name}' must define fn {INBOUND_FN}(payload, headers)");
+        }
+        let has_outbound = has_fn(OUTBOUND_FN, 2);
+        Ok(Self {
+            name: name.to_string(),
+            engine,
+            ast,
+            has_outbound,
+        })
+    }
+
+    fn call(&self, function: &str, args: (Dynamic, Dynamic)) -> Result<Dynamic> {
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, function, args)
+            .map_err(|e| anyhow!("webhook transform '{}' {function}() failed: {e}", self.name))
+    }
+}
+
+fn sandboxed_engine(name: &str) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_BYTES)
+        .set_max_array_size(MAX_COLLECTION_ITEMS)
+        .set_max_map_size(MAX_COLLECTION_ITEMS);
+    let print_name = name.to_string();
+    engine.on_print(move |text| tracing::info!(transform = %print_name, "{text}"));
+    let debug_name = name.to_string();
+    engine.on_debug(move |text, _source, _pos| {
+        tracing::debug!(transform = %debug_name, "{text}");
+    });
+    engine
+}
+
+fn to_dynamic(value: &serde_json::Value) -> Result<Dynamic> {
+    rhai::serde::to_dynamic(value).map_err(|e| anyhow!("{e}"))
+}
+
+impl WebhookTransform for RhaiTransform {
+    fn inbound(&self, payload: &serde_json::Value, headers: &serde_json::Value) -> Result<Inbound> {
+        let result = self.call(INBOUND_FN, (to_dynamic(payload)?, to_dynamic(headers)?))?;
+        if result.is_unit() {
+            return Ok(Inbound::Ignore);
+        }
+        match result.into_string() {
+            Ok(message) if message.trim().is_empty() => Ok(Inbound::Ignore),
+            Ok(message) => Ok(Inbound::Message(message)),
+            Err(type_name) => bail!(
+                "webhook transform '{}' {INBOUND_FN}() must return a string or (), got {type_name}",
+                self.name
+            ),
+        }
+    }
+
+    fn outbound(
+        &self,
+        reply: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Option<serde_json::Value>> {
+        if !self.has_outbound {
+            return Ok(None);
+        }
+        let result = self.call(
+            OUTBOUND_FN,
+            (Dynamic::from(reply.to_string()), to_dynamic(payload)?),
+        )?;
+        if result.is_unit() {
+            return Ok(None);
+        }
+        rhai::serde::from_dynamic(&result).map(Some).map_err(|e| {
+            anyhow!(
+                "webhook transform '{}' {OUTBOUND_FN}() returned a value that is not JSON: {e}",
+                self.name
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITHUB_SCRIPT: &str = r#"
+        fn inbound(payload, headers) {
+            if headers["x-github-event"] != "issues" { return (); }
+            `New issue #${payload.issue.number}: ${payload.issue.title}`
+        }
+
+        fn outbound(reply, payload) {
+            #{ body: reply, issue: payload.issue.number }
+        }
+    "#;
+
+    #[test]
+    fn maps_payload_to_message_and_reply_to_body() {
+        let transform = RhaiTransform::compile("github", GITHUB_SCRIPT).unwrap();
+        let payload = serde_json::json!({"issue": {"number": 7, "title": "Crash on start"}});
+
+        let inbound = transform
+            .inbound(&payload, &serde_json::json!({"x-github-event": "issues"}))
+            .unwrap();
+        assert_eq!(
+            inbound,
+            Inbound::Message("New issue #7: Crash on start".into())
+        );
+
+        let ignored = transform
+            .inbound(&payload, &serde_json::json!({"x-github-event": "push"}))
+            .unwrap();
+        assert_eq!(ignored, Inbound::Ignore);
+
+        let body = transform.outbound("On it.", &payload).unwrap();
+        assert_eq!(
+            body,
+            Some(serde_json::json!({"body": "On it.", "issue": 7}))
+        );
+    }
+
+    #[test]
+    fn compile_requires_inbound_fn() {
+        let err = RhaiTransform::compile("bad", "fn other(x) { x }")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("fn inbound(payload, headers)"));
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let transform = RhaiTransform::compile("loop", "fn inbound(p, h) { loop {} }").unwrap();
+        let err = transform
+            .inbound(&serde_json::json!({}), &serde_json::json!({}))
+            .unwrap_err();
+        assert!(err.to_string().contains("inbound() failed"));
+    }
+}
@@ -0,0 +1,295 @@
+//! Webhook signature verification for channel webhook routes.
+//!
+//! Each [`WebhookVerifier`] checks one sender's scheme in constant time:
+//! HMAC-SHA256 of the body (`X-Hub-Signature-256`, Meta/WhatsApp and
+//! GitHub), Slack's `v0` signature, Ed25519 (Discord interactions), or a
+//! shared token header (Telegram `secret_token`). Slack and Discord sign a
+//! timestamp with the body, so those deliveries are also rejected outside
+//! `gateway.webhook_replay_window_secs`.
+//!
+//! `/whatsapp` and `/github` use the HMAC verifier with their channel
+//! secrets; `[[gateway.webhook_transforms]]` routes pick a scheme with
+//! `signature`.
+
+use crate::security::pairing::constant_time_eq;
+use anyhow::{bail, Context, Result};
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+
+/// Values accepted for `[[gateway.webhook_transforms]] signature`.
+pub const SIGNATURE_SCHEMES: [&str; 4] = ["hmac_sha256", "slack", "ed25519", "token"];
+
+/// Default header for `signature = "token"`.
+pub const TELEGRAM_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// Why a delivery failed verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    /// A signature, timestamp or token header is absent.
+    Missing,
+    /// The signature or token does not match.
+    Invalid,
+    /// The signed timestamp is outside the replay window.
+    Stale,
+}
+
+impl Rejection {
+    /// Reason published with `gateway.rejected` events.
+    pub fn reason(self) -> &'static str {
+        match self {
+            Self::Missing => "missing_signature",
+            Self::Invalid => "invalid_signature",
+            Self::Stale => "stale_signature",
+        }
+    }
+}
+
+/// A sender signature scheme and its key material.
+#[derive(Clone)]
+pub enum WebhookVerifier {
+    /// `X-Hub-Signature-256: sha256=<hex>`, HMAC-SHA256 of the body.
+    HmacSha256 { secret: Arc<str> },
+    /// `X-Slack-Signature: v0=<hex>`, HMAC-SHA256 of
+    /// `v0:<X-Slack-Request-Timestamp>:<body>`.
+    Slack { signing_secret: Arc<str> },
+    /// `X-Signature-Ed25519: <hex>` over `<X-Signature-Timestamp><body>`.
+    Ed25519 { public_key: [u8; 32] },
+    /// The configured token, sent verbatim in `header`.
+    Token { header: String, token: Arc<str> },
+}
+
+impl WebhookVerifier {
+    /// Verifier for a configured scheme name (see [`SIGNATURE_SCHEMES`]).
+    /// `secret` is the HMAC secret, the hex Ed25519 public key, or the token.
+    pub fn from_scheme(scheme: &str, secret: &str, header: Option<&str>) -> Result<Self> {
+        let secret = secret.trim();
+        if secret.is_empty() {
+            bail!("signature secret must not be empty");
+        }
+        Ok(match scheme {
+            "hmac_sha256" => Self::HmacSha256 {
+                secret: Arc::from(secret),
+            },
+            "slack" => Self::Slack {
+                signing_secret: Arc::from(secret),
+            },
+            "ed25519" => {
+                let bytes = hex::decode(secret).context("Ed25519 public key must be hex")?;
+                let public_key = <[u8; 32]>::try_from(bytes.as_slice())
+                    .ok()
+                    .context("Ed25519 public key must be 32 bytes (64 hex characters)")?;
+                Self::Ed25519 { public_key }
+            }
+            "token" => Self::Token {
+                header: header
+                    .map(str::trim)
+                    .filter(|h| !h.is_empty())
+                    .unwrap_or(TELEGRAM_SECRET_HEADER)
+                    .to_string(),
+                token: Arc::from(secret),
+            },
+            other => bail!(
+                "unknown signature scheme '{other}' (expected one of {})",
+                SIGNATURE_SCHEMES.join(", ")
+            ),
+        })
+    }
+
+    /// Check a delivery against the current time.
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        replay_window_secs: u64,
+    ) -> std::result::Result<(), Rejection> {
+        self.verify_at(
+            headers,
+            body,
+            replay_window_secs,
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    fn verify_at(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        replay_window_secs: u64,
+        now: i64,
+    ) -> std::result::Result<(), Rejection> {
+        match self {
+            Self::HmacSha256 { secret } => {
+                let signature = header(headers, "X-Hub-Signature-256")?;
+                let hex_sig = signature
+                    .strip_prefix("sha256=")
+                    .ok_or(Rejection::Invalid)?;
+                hmac_matches(secret, &[body], hex_sig)
+            }
+            Self::Slack { signing_secret } => {
+                let timestamp = header(headers, "X-Slack-Request-Timestamp")?;
+                let signature = header(headers, "X-Slack-Signature")?;
+                let hex_sig = signature.strip_prefix("v0=").ok_or(Rejection::Invalid)?;
+                let prefix = format!("v0:{timestamp}:");
+                hmac_matches(signing_secret, &[prefix.as_bytes(), body], hex_sig)?;
+                check_fresh(timestamp, replay_window_secs, now)
+            }
+            Self::Ed25519 { public_key } => {
+                let timestamp = header(headers, "X-Signature-Timestamp")?;
+                let signature = header(headers, "X-Signature-Ed25519")?;
+                let signature = hex::decode(signature).map_err(|_| Rejection::Invalid)?;
+                let mut message = timestamp.as_bytes().to_vec();
+                message.extend_from_slice(body);
+                ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+                    .verify(&message, &signature)
+                    .map_err(|_| Rejection::Invalid)?;
+                check_fresh(timestamp, replay_window_secs, now)
+            }
+            Self::Token {
+                header: name,
+                token,
+            } => {
+                let sent = header(headers, name)?;
+                if constant_time_eq(sent, token) {
+                    Ok(())
+                } else {
+                    Err(Rejection::Invalid)
+                }
+            }
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> std::result::Result<&'a str, Rejection> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or(Rejection::Missing)
+}
+
+/// HMAC-SHA256 of the concatenated `parts` against a hex signature;
+/// `verify_slice` compares in constant time.
+fn hmac_matches(
+    secret: &str,
+    parts: &[&[u8]],
+    hex_sig: &str,
+) -> std::result::Result<(), Rejection> {
+    let expected = hex::decode(hex_sig).map_err(|_| Rejection::Invalid)?;
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|_| Rejection::Invalid)?;
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(&expected).map_err(|_| Rejection::Invalid)
+}
+
+fn check_fresh(timestamp: &str, window_secs: u64, now: i64) -> std::result::Result<(), Rejection> {
+    let signed_at: i64 = timestamp.parse().map_err(|_| Rejection::Invalid)?;
+    if window_secs > 0 && now.abs_diff(signed_at) > window_secs {
+        return Err(Rejection::Stale);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::KeyPair;
+
+    fn hmac_hex(secret: &str, message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn hmac_and_token_schemes() {
+        let body = br#"{"entry":[]}"#;
+        let hub = WebhookVerifier::from_scheme("hmac_sha256", "app-secret", None).unwrap();
+        let signed = headers(&[(
+            "X-Hub-Signature-256",
+            format!("sha256={}", hmac_hex("app-secret", body)),
+        )]);
+        assert_eq!(hub.verify(&signed, body, 300), Ok(()));
+        assert_eq!(
+            hub.verify(&signed, b"tampered", 300),
+            Err(Rejection::Invalid)
+        );
+        assert_eq!(
+            hub.verify(&HeaderMap::new(), body, 300),
+            Err(Rejection::Missing)
+        );
+
+        let token = WebhookVerifier::from_scheme("token", "tg-secret", None).unwrap();
+        let sent = headers(&[(TELEGRAM_SECRET_HEADER, "tg-secret".into())]);
+        assert_eq!(token.verify(&sent, body, 300), Ok(()));
+        let wrong = headers(&[(TELEGRAM_SECRET_HEADER, "tg-secreT".into())]);
+        assert_eq!(token.verify(&wrong, body, 300), Err(Rejection::Invalid));
+
+        assert!(WebhookVerifier::from_scheme("md5", "x", None).is_err());
+        assert!(WebhookVerifier::from_scheme("ed25519", "abcd", None).is_err());
+    }
+
+    #[test]
+    fn timestamped_schemes_enforce_the_replay_window() {
+        let body = b"token=x&text=hi";
+        let now = 1_700_000_000;
+        let slack = WebhookVerifier::from_scheme("slack", "slack-secret", None).unwrap();
+        let signed_at = (now - 30).to_string();
+        let mut base = format!("v0:{signed_at}:").into_bytes();
+        base.extend_from_slice(body);
+        let slack_headers = headers(&[
+            ("X-Slack-Request-Timestamp", signed_at),
+            (
+                "X-Slack-Signature",
+                format!("v0={}", hmac_hex("slack-secret", &base)),
+            ),
+        ]);
+        assert_eq!(slack.verify_at(&slack_headers, body, 300, now), Ok(()));
+        assert_eq!(
+            slack.verify_at(&slack_headers, body, 10, now),
+            Err(Rejection::Stale)
+        );
+        assert_eq!(
+            slack.verify_at(&slack_headers, body, 0, now + 86_400),
+            Ok(())
+        );
+
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = ring::signature::Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let pair = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let discord =
+            WebhookVerifier::from_scheme("ed25519", &hex::encode(pair.public_key().as_ref()), None)
+                .unwrap();
+        let signed_at = now.to_string();
+        let mut message = signed_at.clone().into_bytes();
+        message.extend_from_slice(body);
+        let discord_headers = headers(&[
+            ("X-Signature-Timestamp", signed_at),
+            (
+                "X-Signature-Ed25519",
+                hex::encode(pair.sign(&message).as_ref()),
+            ),
+        ]);
+        assert_eq!(discord.verify_at(&discord_headers, body, 300, now), Ok(()));
+        assert_eq!(
+            discord.verify_at(&discord_headers, b"other", 300, now),
+            Err(Rejection::Invalid)
+        );
+        assert_eq!(
+            discord.verify_at(&discord_headers, body, 300, now + 301),
+            Err(Rejection::Stale)
+        );
+    }
+}
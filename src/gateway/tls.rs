@@ -0,0 +1,216 @@
+//! TLS termination for the gateway (`[gateway.tls]`).
+//!
+//! [`TlsListener`] wraps a [`TcpListener`] and performs the rustls handshake
+//! off the accept path, so a slow or hostile client cannot stall other
+//! connections. The certificate is held by a [`ReloadableAcceptor`]; on Unix,
+//! `SIGHUP` re-reads the PEM files and swaps the acceptor in place. A reload
+//! that fails keeps serving the previous certificate.
+
+use crate::config::GatewayTlsConfig;
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Upper bound on a single TLS handshake.
+const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+/// Completed handshakes buffered ahead of the HTTP server.
+const ACCEPT_QUEUE: usize = 64;
+
+/// Load a PEM certificate chain and private key into a rustls server config.
+pub fn load_server_config(cert_path: &Path, key_path: &Path) -> Result<rustls::ServerConfig> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("Failed to open TLS certificate {}", cert_path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid TLS certificate {}", cert_path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificates found in {}", cert_path.display());
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("Failed to read TLS private key {}", key_path.display()))?;
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .context("TLS certificate and private key do not match")?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// A TLS acceptor whose certificate can be swapped while the gateway runs.
+pub struct ReloadableAcceptor {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    acceptor: RwLock<TlsAcceptor>,
+}
+
+impl ReloadableAcceptor {
+    /// Resolve `[gateway.tls]` paths against `config_dir` and load them.
+    pub fn from_config(tls: &GatewayTlsConfig, config_dir: &Path) -> Result<Self> {
+        Self::new(
+            GatewayTlsConfig::resolve_path(&tls.cert_path, config_dir),
+            GatewayTlsConfig::resolve_path(&tls.key_path, config_dir),
+        )
+    }
+
+    pub fn new(cert_path: PathBuf, key_path: PathBuf) -> Result<Self> {
+        let config = load_server_config(&cert_path, &key_path)?;
+        Ok(Self {
+            cert_path,
+            key_path,
+            acceptor: RwLock::new(TlsAcceptor::from(Arc::new(config))),
+        })
+    }
+
+    /// Re-read the certificate and key. On error the current acceptor stays.
+    pub fn reload(&self) -> Result<()> {
+        let config = load_server_config(&self.cert_path, &self.key_path)?;
+        *self.acceptor.write() = TlsAcceptor::from(Arc::new(config));
+        Ok(())
+    }
+
+    pub fn current(&self) -> TlsAcceptor {
+        self.acceptor.read().clone()
+    }
+}
+
+/// An [`axum::serve::Listener`] yielding TLS streams.
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    /// Start accepting on `listener`, handshaking with `acceptor`.
+    pub fn new(listener: TcpListener, acceptor: Arc<ReloadableAcceptor>) -> Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (tx, incoming) = mpsc::channel(ACCEPT_QUEUE);
+        tokio::spawn(accept_loop(listener, acceptor, tx));
+        Ok(Self {
+            local_addr,
+            incoming,
+        })
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(conn) => conn,
+            // The accept loop only exits once this listener is dropped.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+async fn accept_loop(
+    listener: TcpListener,
+    acceptor: Arc<ReloadableAcceptor>,
+    tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!("Gateway accept failed: {e}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        if tx.is_closed() {
+            return;
+        }
+        let tls = acceptor.current();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let handshake = tokio::time::timeout(
+                Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+                tls.accept(stream),
+            );
+            match handshake.await {
+                Ok(Ok(stream)) => {
+                    let _ = tx.send((stream, peer)).await;
+                }
+                Ok(Err(e)) => tracing::debug!("TLS handshake with {peer} failed: {e}"),
+                Err(_) => tracing::debug!("TLS handshake with {peer} timed out"),
+            }
+        });
+    }
+}
+
+/// Reload the certificate whenever the process receives `SIGHUP`.
+#[cfg(unix)]
+pub fn spawn_reload_on_sighup(acceptor: Arc<ReloadableAcceptor>) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).context("Failed to install SIGHUP handler")?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            match acceptor.reload() {
+                Ok(()) => tracing::info!("Reloaded gateway TLS certificate"),
+                Err(e) => {
+                    tracing::warn!("Gateway TLS reload failed, keeping current certificate: {e:#}");
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_reload_on_sighup(_acceptor: Arc<ReloadableAcceptor>) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_certificate_is_reported_with_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cert = tmp.path().join("missing.pem");
+        let err = load_server_config(&cert, &tmp.path().join("missing.key")).unwrap_err();
+        assert!(format!("{err:#}").contains("missing.pem"));
+    }
+
+    #[test]
+    fn empty_certificate_file_is_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cert = tmp.path().join("empty.pem");
+        std::fs::write(&cert, "not a certificate\n").unwrap();
+        let err = load_server_config(&cert, &tmp.path().join("key.pem")).unwrap_err();
+        assert!(err.to_string().contains("No certificates found"));
+    }
+
+    #[test]
+    fn tls_paths_resolve_against_config_dir() {
+        let dir = Path::new("/etc/zeroclaw");
+        assert_eq!(
+            GatewayTlsConfig::resolve_path("certs/gw.pem", dir),
+            PathBuf::from("/etc/zeroclaw/certs/gw.pem")
+        );
+        assert_eq!(
+            GatewayTlsConfig::resolve_path("/srv/gw.pem", dir),
+            PathBuf::from("/srv/gw.pem")
+        );
+    }
+}
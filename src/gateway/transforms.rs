@@ -0,0 +1,202 @@
+//! Webhook transformation scripts (`[[gateway.webhook_transforms]]`).
+//!
+//! Each entry serves `POST /webhook/<name>`. Its script maps an arbitrary
+//! inbound JSON payload (GitHub, Jira, Grafana alerts, ...) to an agent
+//! message and, optionally, maps the agent reply back to the JSON response
+//! the sender expects, so new integrations need no rebuild. A route with
+//! `signature` set authenticates deliveries by the sender's signature
+//! (see [`super::signatures`]) instead of gateway pairing.
+//!
+//! Scripts are Rhai (`.rhai`) and need the `webhook-transforms` build
+//! feature:
+//!
+//! ```rhai
+//! fn inbound(payload, headers) {
+//!     if headers["x-github-event"] != "issues" { return (); }
+//!     `New issue #${payload.issue.number}: ${payload.issue.title}`
+//! }
+//!
+//! fn outbound(reply, payload) {
+//!     #{ body: reply }
+//! }
+//! ```
+
+use super::signatures::WebhookVerifier;
+use crate::config::{GatewayTlsConfig, WebhookTransformConfig};
+use anyhow::{bail, Context, Result};
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Headers never exposed to scripts.
+const HIDDEN_HEADERS: &[&str] = &[
+    "authorization",
+    "cookie",
+    "x-webhook-secret",
+    "x-telegram-bot-api-secret-token",
+];
+
+/// What a transform made of an inbound payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inbound {
+    /// Run the agent on this message.
+    Message(String),
+    /// Acknowledge the delivery without running the agent.
+    Ignore,
+}
+
+/// A loaded webhook transformation.
+pub trait WebhookTransform: Send + Sync {
+    /// Map an inbound payload (and its lower-cased headers) to an agent message.
+    fn inbound(&self, payload: &serde_json::Value, headers: &serde_json::Value) -> Result<Inbound>;
+
+    /// Map the agent reply to a response body; `None` keeps the default
+    /// `{"response": ..., "model": ...}` body.
+    fn outbound(
+        &self,
+        reply: &str,
+        payload: &serde_json::Value,
+    ) -> Result<Option<serde_json::Value>>;
+}
+
+/// Transforms by route name.
+#[derive(Default, Clone)]
+pub struct WebhookTransforms {
+    routes: HashMap<String, Arc<dyn WebhookTransform>>,
+    /// Sender signature checks for routes with `signature` set.
+    verifiers: HashMap<String, WebhookVerifier>,
+}
+
+impl WebhookTransforms {
+    /// Compile every configured script; script paths resolve against `config_dir`.
+    pub fn load(configs: &[WebhookTransformConfig], config_dir: &Path) -> Result<Self> {
+        let mut routes = HashMap::new();
+        let mut verifiers = HashMap::new();
+        for config in configs {
+            let name = config.name.trim();
+            let path = GatewayTlsConfig::resolve_path(&config.script, config_dir);
+            let source = std::fs::read_to_string(&path).with_context(|| {
+                format!(
+                    "failed to read webhook transform '{name}' script {}",
+                    path.display()
+                )
+            })?;
+            let transform = compile(name, &path, &source)?;
+            routes.insert(name.to_string(), transform);
+            if let Some(scheme) = config.signature.as_deref() {
+                let verifier = WebhookVerifier::from_scheme(
+                    scheme,
+                    config.signature_secret.as_deref().unwrap_or_default(),
+                    config.signature_header.as_deref(),
+                )
+                .with_context(|| format!("webhook transform '{name}' signature"))?;
+                verifiers.insert(name.to_string(), verifier);
+            }
+        }
+        Ok(Self { routes, verifiers })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn WebhookTransform>> {
+        self.routes.get(name).cloned()
+    }
+
+    /// The sender signature check for `name`; such routes skip pairing auth.
+    pub fn verifier(&self, name: &str) -> Option<&WebhookVerifier> {
+        self.verifiers.get(name)
+    }
+
+    /// Route names, sorted.
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.routes.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+fn compile(name: &str, path: &Path, source: &str) -> Result<Arc<dyn WebhookTransform>> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        #[cfg(feature = "webhook-transforms")]
+        Some("rhai") => Ok(Arc::new(super::rhai_transform::RhaiTransform::compile(
+            name, source,
+        )?)),
+        #[cfg(not(feature = "webhook-transforms"))]
+        Some("rhai") => {
+            let _ = source;
+            bail!(
+                "webhook transform '{name}' is a Rhai script, but this build lacks script \
+                 support (rebuild with `--features webhook-transforms`)"
+            )
+        }
+        _ => bail!(
+            "webhook transform '{name}': unsupported script type {} (expected a .rhai file)",
+            path.display()
+        ),
+    }
+}
+
+/// Request headers as a JSON object with lower-cased names, minus credentials.
+pub fn headers_to_json(headers: &HeaderMap) -> serde_json::Value {
+    let map = headers
+        .iter()
+        .filter(|(name, _)| !HIDDEN_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.as_str().to_string(), serde_json::Value::from(v)))
+        })
+        .collect();
+    serde_json::Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_to_json_hides_credentials() {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-GitHub-Event", "issues".parse().unwrap());
+        headers.insert("Authorization", "Bearer secret".parse().unwrap());
+        headers.insert("X-Webhook-Secret", "secret".parse().unwrap());
+
+        let json = headers_to_json(&headers);
+        assert_eq!(json, serde_json::json!({"x-github-event": "issues"}));
+    }
+
+    #[test]
+    fn load_rejects_unknown_script_types() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("hook.js"), "function inbound() {}").unwrap();
+        let configs = [WebhookTransformConfig {
+            name: "js".into(),
+            script: "hook.js".into(),
+            signature: None,
+            signature_secret: None,
+            signature_header: None,
+        }];
+        let err = WebhookTransforms::load(&configs, dir.path()).err().unwrap();
+        assert!(err.to_string().contains("unsupported script type"));
+    }
+
+    #[cfg(not(feature = "webhook-transforms"))]
+    #[test]
+    fn rhai_transforms_need_the_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("hook.rhai"), "fn inbound(p, h) { \"x\" }").unwrap();
+        let configs = [WebhookTransformConfig {
+            name: "hook".into(),
+            script: "hook.rhai".into(),
+            signature: None,
+            signature_secret: None,
+            signature_header: None,
+        }];
+        let err = WebhookTransforms::load(&configs, dir.path()).err().unwrap();
+        assert!(err.to_string().contains("webhook-transforms"));
+    }
+}
@@ -76,6 +76,14 @@ pub fn mark_component_error(component: &str, error: impl ToString) {
     });
 }
 
+/// Mark a component as deliberately stopped (e.g. a channel stopped through
+/// the admin API), as opposed to failing.
+pub fn mark_component_stopped(component: &str) {
+    upsert_component(component, |entry| {
+        entry.status = "stopped".into();
+    });
+}
+
 pub fn bump_component_restart(component: &str) {
     upsert_component(component, |entry| {
         entry.restart_count = entry.restart_count.saturating_add(1);
@@ -102,6 +110,78 @@ pub fn snapshot_json() -> serde_json::Value {
     })
 }
 
+/// Readiness of one component group reported by the gateway's `/readyz`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentReadiness {
+    /// `"ok"`, `"error"`, `"starting"` or `"not_configured"`.
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+impl ComponentReadiness {
+    pub fn ok() -> Self {
+        Self {
+            status: "ok",
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            status: "error",
+            errors: vec![message.into()],
+        }
+    }
+
+    /// Whether this group allows the deployment to take traffic.
+    pub fn is_ready(&self) -> bool {
+        matches!(self.status, "ok" | "not_configured")
+    }
+}
+
+/// Roll up registry components named `name` or `name:<...>` for each of
+/// `names` (e.g. `"channel"` covers `channel:whatsapp`). Deliberately
+/// stopped components are ignored; a group nothing has reported on is
+/// `not_configured`.
+pub fn component_readiness(snapshot: &HealthSnapshot, names: &[&str]) -> ComponentReadiness {
+    let matching = snapshot.components.iter().filter(|(key, entry)| {
+        entry.status != "stopped"
+            && names.iter().any(|name| {
+                key.as_str() == *name
+                    || key
+                        .strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with(':'))
+            })
+    });
+
+    let mut seen = false;
+    let mut starting = false;
+    let mut errors = Vec::new();
+    for (key, entry) in matching {
+        seen = true;
+        match entry.status.as_str() {
+            "error" => errors.push(format!(
+                "{key}: {}",
+                entry.last_error.as_deref().unwrap_or("unhealthy")
+            )),
+            "starting" => starting = true,
+            _ => {}
+        }
+    }
+
+    let status = if !errors.is_empty() {
+        "error"
+    } else if starting {
+        "starting"
+    } else if seen {
+        "ok"
+    } else {
+        "not_configured"
+    };
+    ComponentReadiness { status, errors }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +261,29 @@ mod tests {
         assert!(component_json["last_ok"].as_str().is_some());
         assert!(json["uptime_seconds"].as_u64().is_some());
     }
+
+    #[test]
+    fn component_readiness_rolls_up_prefixed_components() {
+        let ok = unique_component("ready-ok");
+        let failing = unique_component("ready-fail");
+        let stopped = unique_component("ready-stopped");
+        mark_component_ok(&format!("{ok}:a"));
+        mark_component_error(&format!("{failing}:b"), "listener crashed");
+        mark_component_error(&stopped, "gone");
+        mark_component_stopped(&stopped);
+        let snapshot = snapshot();
+
+        assert_eq!(
+            component_readiness(&snapshot, &[ok.as_str()]),
+            ComponentReadiness::ok()
+        );
+        let failed = component_readiness(&snapshot, &[ok.as_str(), failing.as_str()]);
+        assert_eq!(failed.status, "error");
+        assert_eq!(failed.errors, [format!("{failing}:b: listener crashed")]);
+        assert!(!failed.is_ready());
+
+        let idle = component_readiness(&snapshot, &[stopped.as_str()]);
+        assert_eq!(idle.status, "not_configured");
+        assert!(idle.is_ready());
+    }
 }
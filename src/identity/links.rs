@@ -0,0 +1,384 @@
+//! Cross-channel identities: one person, several channel accounts.
+//!
+//! An account is a `<channel>:<sender>` pair. Linking accounts to the same
+//! identity makes per-person state — `/pin` preferences and auto-saved
+//! memories — follow the person instead of the platform account. Links are
+//! made with `zeroclaw identity link`, or from chat: `/link` in a direct
+//! message issues a short-lived code, and `/link <code>` sent from another
+//! account joins that account to the first one's identity.
+//!
+//! Links live in `identities.db` next to `config.toml`, outside the
+//! workspace the agent can write to, since a forged link would hand one
+//! person's memories to another. They are read on every lookup, so CLI
+//! changes apply to a running daemon immediately. Pending codes are kept in
+//! memory only.
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const IDENTITIES_DB_FILE: &str = "identities.db";
+/// How long a `/link` code stays valid.
+pub const LINK_CODE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Unambiguous characters; 32 of them, so a random byte maps without bias.
+const CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 8;
+/// Wrong codes an account may send within one code lifetime.
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+/// Prefix of [`IdentityLinks::person_key`] for linked accounts.
+const PERSON_KEY_PREFIX: &str = "identity:";
+
+/// Link database in `config_dir`.
+pub fn identities_db_path(config_dir: &Path) -> PathBuf {
+    config_dir.join(IDENTITIES_DB_FILE)
+}
+
+/// `<channel>:<sender>`, the key of an unlinked account.
+pub fn account_key(channel: &str, account: &str) -> String {
+    format!("{channel}:{account}")
+}
+
+/// Split `telegram:alice` into channel and account.
+fn parse_account(raw: &str) -> Result<(String, String)> {
+    match raw.trim().split_once(':') {
+        Some((channel, account)) if !channel.trim().is_empty() && !account.trim().is_empty() => {
+            Ok((
+                channel.trim().to_ascii_lowercase(),
+                account.trim().to_string(),
+            ))
+        }
+        _ => bail!("Expected <channel>:<account> (e.g. telegram:123456789), got '{raw}'"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityLink {
+    pub identity: String,
+    pub channel: String,
+    pub account: String,
+}
+
+struct PendingCode {
+    identity: String,
+    issuer: (String, String),
+    expires_at: Instant,
+}
+
+struct Failures {
+    count: u32,
+    since: Instant,
+}
+
+pub struct IdentityLinks {
+    conn: Mutex<Connection>,
+    codes: Mutex<HashMap<String, PendingCode>>,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+impl IdentityLinks {
+    /// Open the link table at `db_path` (created if missing).
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS identity_links (
+                channel   TEXT NOT NULL,
+                account   TEXT NOT NULL,
+                identity  TEXT NOT NULL,
+                linked_at TEXT NOT NULL,
+                PRIMARY KEY (channel, account)
+            );
+            CREATE INDEX IF NOT EXISTS idx_identity_links_identity
+                ON identity_links(identity);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            codes: Mutex::new(HashMap::new()),
+            failures: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Open the links stored next to `config.toml`.
+    pub fn for_config(config: &Config) -> Result<Self> {
+        let config_dir = config
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        Self::open(&identities_db_path(config_dir))
+    }
+
+    /// The identity `account` on `channel` is linked to, if any.
+    pub fn identity_of(&self, channel: &str, account: &str) -> Option<String> {
+        self.conn
+            .lock()
+            .query_row(
+                "SELECT identity FROM identity_links WHERE channel = ?1 AND account = ?2",
+                params![channel, account],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or_else(|e| {
+                tracing::warn!("Identity lookup failed: {e}");
+                None
+            })
+    }
+
+    /// Key that owns per-person state: `identity:<name>` for linked accounts,
+    /// `<channel>:<sender>` otherwise.
+    pub fn person_key(&self, channel: &str, account: &str) -> String {
+        self.identity_of(channel, account).map_or_else(
+            || account_key(channel, account),
+            |identity| format!("{PERSON_KEY_PREFIX}{identity}"),
+        )
+    }
+
+    /// Link `account` on `channel` to `identity`, replacing any earlier link.
+    pub fn link(&self, identity: &str, channel: &str, account: &str) -> Result<()> {
+        let identity = identity.trim();
+        if identity.is_empty() {
+            bail!("Identity name must not be empty");
+        }
+        self.conn.lock().execute(
+            "INSERT INTO identity_links (channel, account, identity, linked_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(channel, account) DO UPDATE SET
+                identity = excluded.identity,
+                linked_at = excluded.linked_at",
+            params![channel, account, identity, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the link for `account` on `channel`. Returns whether one existed.
+    pub fn unlink(&self, channel: &str, account: &str) -> Result<bool> {
+        let removed = self.conn.lock().execute(
+            "DELETE FROM identity_links WHERE channel = ?1 AND account = ?2",
+            params![channel, account],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Every link, grouped by identity.
+    pub fn list(&self) -> Result<Vec<IdentityLink>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT identity, channel, account FROM identity_links
+             ORDER BY identity, channel, account",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(IdentityLink {
+                identity: row.get(0)?,
+                channel: row.get(1)?,
+                account: row.get(2)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Issue a one-time code that links another account to this account's
+    /// identity. An unlinked account starts a new identity named after it.
+    pub fn issue_code(&self, channel: &str, account: &str) -> String {
+        let identity = self
+            .identity_of(channel, account)
+            .unwrap_or_else(|| account_key(channel, account));
+        let bytes: [u8; CODE_LENGTH] = rand::random();
+        let code: String = bytes
+            .iter()
+            .map(|b| char::from(CODE_ALPHABET[usize::from(*b) % CODE_ALPHABET.len()]))
+            .collect();
+
+        let now = Instant::now();
+        let issuer = (channel.to_string(), account.to_string());
+        let mut codes = self.codes.lock();
+        // Drop expired codes and this account's previous one.
+        codes.retain(|_, pending| pending.expires_at > now && pending.issuer != issuer);
+        codes.insert(
+            code.clone(),
+            PendingCode {
+                identity,
+                issuer,
+                expires_at: now + LINK_CODE_TTL,
+            },
+        );
+        code
+    }
+
+    /// Link `account` on `channel` to the identity that issued `code`.
+    /// Returns the identity.
+    pub fn redeem_code(&self, code: &str, channel: &str, account: &str) -> Result<String> {
+        let redeemer = account_key(channel, account);
+        let now = Instant::now();
+        {
+            let mut failures = self.failures.lock();
+            failures.retain(|_, f| now.duration_since(f.since) < LINK_CODE_TTL);
+            if failures
+                .get(&redeemer)
+                .is_some_and(|f| f.count >= MAX_FAILED_ATTEMPTS)
+            {
+                bail!("Too many wrong codes. Request a new code and try again later.");
+            }
+        }
+
+        let code = code.trim().to_ascii_uppercase();
+        let pending = {
+            let mut codes = self.codes.lock();
+            let own_code = codes.get(&code).is_some_and(|pending| {
+                pending.issuer == (channel.to_string(), account.to_string())
+            });
+            if own_code {
+                bail!("Send this code from your other account, not the one that requested it.");
+            }
+            codes
+                .remove(&code)
+                .filter(|pending| pending.expires_at > now)
+        };
+        let Some(pending) = pending else {
+            self.failures
+                .lock()
+                .entry(redeemer)
+                .or_insert(Failures {
+                    count: 0,
+                    since: now,
+                })
+                .count += 1;
+            bail!("That code is invalid or has expired.");
+        };
+
+        let (issuer_channel, issuer_account) = &pending.issuer;
+        if self.identity_of(issuer_channel, issuer_account).is_none() {
+            self.link(&pending.identity, issuer_channel, issuer_account)?;
+        }
+        self.link(&pending.identity, channel, account)?;
+        self.failures.lock().remove(&redeemer);
+        Ok(pending.identity)
+    }
+}
+
+/// `zeroclaw identity list`.
+pub fn run_list(config: &Config) -> Result<()> {
+    let links = IdentityLinks::for_config(config)?.list()?;
+    if links.is_empty() {
+        println!("No linked identities.");
+        println!();
+        println!("Link accounts with: zeroclaw identity link alice telegram:123456789");
+        println!("or send /link to the bot from one account and /link <code> from another.");
+        return Ok(());
+    }
+    let mut current: Option<&str> = None;
+    for link in &links {
+        if current != Some(link.identity.as_str()) {
+            println!("{}", link.identity);
+            current = Some(link.identity.as_str());
+        }
+        println!("  {}", account_key(&link.channel, &link.account));
+    }
+    Ok(())
+}
+
+/// `zeroclaw identity link <identity> <channel>:<account>`.
+pub fn run_link(config: &Config, identity: &str, account: &str) -> Result<()> {
+    let (channel, account) = parse_account(account)?;
+    IdentityLinks::for_config(config)?.link(identity, &channel, &account)?;
+    println!(
+        "✅ Linked {} to identity '{}'",
+        account_key(&channel, &account),
+        identity.trim()
+    );
+    Ok(())
+}
+
+/// `zeroclaw identity unlink <channel>:<account>`.
+pub fn run_unlink(config: &Config, account: &str) -> Result<()> {
+    let (channel, account) = parse_account(account)?;
+    let key = account_key(&channel, &account);
+    if IdentityLinks::for_config(config)?.unlink(&channel, &account)? {
+        println!("✅ Unlinked {key}");
+    } else {
+        println!("{key} is not linked to an identity.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(tmp: &tempfile::TempDir) -> IdentityLinks {
+        IdentityLinks::open(&identities_db_path(tmp.path())).unwrap()
+    }
+
+    #[test]
+    fn links_are_stored_next_to_config_not_in_workspace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.config_path = tmp.path().join("config.toml");
+        config.workspace_dir = tmp.path().join("workspace");
+
+        IdentityLinks::for_config(&config)
+            .unwrap()
+            .link("alice", "telegram", "42")
+            .unwrap();
+        assert!(tmp.path().join(IDENTITIES_DB_FILE).exists());
+        assert!(!config.workspace_dir.join("state").exists());
+    }
+
+    #[test]
+    fn manual_links_map_accounts_to_one_person_key() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let links = links(&tmp);
+        assert_eq!(links.person_key("telegram", "42"), "telegram:42");
+
+        links.link("alice", "telegram", "42").unwrap();
+        links.link("alice", "discord", "alice#1").unwrap();
+        assert_eq!(links.person_key("telegram", "42"), "identity:alice");
+        assert_eq!(links.person_key("discord", "alice#1"), "identity:alice");
+        assert!(links.link("  ", "slack", "U1").is_err());
+
+        assert!(links.unlink("discord", "alice#1").unwrap());
+        assert!(!links.unlink("discord", "alice#1").unwrap());
+        assert_eq!(links.person_key("discord", "alice#1"), "discord:alice#1");
+        assert_eq!(links.list().unwrap().len(), 1);
+
+        assert_eq!(
+            parse_account("Telegram:42").unwrap(),
+            ("telegram".to_string(), "42".to_string())
+        );
+        assert!(parse_account("telegram").is_err());
+        assert!(parse_account(":42").is_err());
+    }
+
+    #[test]
+    fn link_codes_join_accounts_once_and_limit_guessing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let links = links(&tmp);
+
+        let code = links.issue_code("telegram", "42");
+        assert_eq!(code.len(), CODE_LENGTH);
+        assert!(links.redeem_code(&code, "telegram", "42").is_err());
+        let identity = links
+            .redeem_code(&code.to_lowercase(), "discord", "alice#1")
+            .unwrap();
+        assert_eq!(identity, "telegram:42");
+        assert_eq!(
+            links.person_key("telegram", "42"),
+            links.person_key("discord", "alice#1")
+        );
+        // Codes are single-use.
+        assert!(links.redeem_code(&code, "slack", "U1").is_err());
+
+        for _ in 1..MAX_FAILED_ATTEMPTS {
+            assert!(links.redeem_code("WRONG", "slack", "U1").is_err());
+        }
+        let fresh = links.issue_code("discord", "alice#1");
+        let err = links.redeem_code(&fresh, "slack", "U1").unwrap_err();
+        assert!(err.to_string().contains("Too many"));
+        assert!(links.identity_of("slack", "U1").is_none());
+    }
+}
@@ -3,6 +3,13 @@
 //! AIEOS (AI Entity Object Specification) is a standardization framework for
 //! portable AI identity. This module handles loading and converting AIEOS v1.1
 //! JSON to ZeroClaw's system prompt format.
+//!
+//! The identities of the people the agent talks to — one person, several
+//! channel accounts — live in [`links`].
+
+pub mod links;
+
+pub use links::IdentityLinks;
 
 /// Identity configuration (defined locally after config strip).
 #[derive(Debug, Clone, Default)]
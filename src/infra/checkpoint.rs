@@ -0,0 +1,651 @@
+//! Workspace checkpoints (`zeroclaw checkpoint create|list|restore`).
+//!
+//! When the workspace is the root of a git repository, a checkpoint is a
+//! commit of the working tree (tracked and untracked files, honoring
+//! `.gitignore`) kept under `refs/zeroclaw/checkpoints/<id>`. It is built with
+//! a temporary index, so HEAD, the current branch and the staging area are
+//! never touched. Other workspaces are archived into
+//! `.zeroclaw/checkpoints/<id>.tar.gz`.
+//!
+//! Runtime data ([`EXCLUDED_DIRS`]: memory, media, state and `.zeroclaw`
+//! itself) is left out of checkpoints and never changed by a restore.
+//! Restoring brings every other file back to its checkpointed contents and
+//! removes files created since; a checkpoint of the current state is taken
+//! first so the restore itself can be undone.
+//!
+//! The manifest sits inside the workspace, so ids and references read from
+//! it are checked against the formats `create` produces before they are used.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+pub const CHECKPOINT_DIR: &str = ".zeroclaw/checkpoints";
+const MANIFEST_FILE: &str = "checkpoints.json";
+const GIT_REF_PREFIX: &str = "refs/zeroclaw/checkpoints/";
+const ARCHIVE_EXTENSION: &str = "tar.gz";
+/// Top-level workspace directories holding runtime data rather than work.
+pub const EXCLUDED_DIRS: [&str; 4] = [".zeroclaw", "memory", "media", "state"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointKind {
+    Git,
+    Archive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: String,
+    pub created_at: String,
+    pub label: String,
+    pub kind: CheckpointKind,
+    /// Taken automatically before an agent run (subject to pruning).
+    #[serde(default)]
+    pub auto: bool,
+    /// Commit id for git checkpoints, archive file name otherwise.
+    pub reference: String,
+}
+
+impl Checkpoint {
+    pub fn short_id(&self) -> &str {
+        self.id.get(..8).unwrap_or(&self.id)
+    }
+
+    /// Reject manifest entries `create` could not have written: the id must
+    /// be a simple uuid, a git reference a commit hash and an archive
+    /// reference the bare `<id>.tar.gz` file name.
+    fn validate(&self) -> Result<()> {
+        let is_hex = |s: &str| s.bytes().all(|b| b.is_ascii_hexdigit());
+        if self.id.len() != 32 || !is_hex(&self.id) {
+            bail!("Checkpoint id '{}' is malformed", self.id);
+        }
+        let valid = match self.kind {
+            CheckpointKind::Git => {
+                matches!(self.reference.len(), 40 | 64) && is_hex(&self.reference)
+            }
+            CheckpointKind::Archive => self.reference == archive_file_name(&self.id),
+        };
+        if !valid {
+            bail!(
+                "Checkpoint {} has an invalid reference '{}'",
+                self.short_id(),
+                self.reference
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Checkpoints of one workspace.
+pub struct Checkpoints {
+    workspace_dir: PathBuf,
+    dir: PathBuf,
+}
+
+impl Checkpoints {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+            dir: workspace_dir.join(CHECKPOINT_DIR),
+        }
+    }
+
+    /// Snapshot the workspace.
+    pub fn create(&self, label: &str, auto: bool) -> Result<Checkpoint> {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let (kind, reference) = if self.is_git_root() {
+            (CheckpointKind::Git, self.git_snapshot(&id, label)?)
+        } else {
+            (CheckpointKind::Archive, self.archive_snapshot(&id)?)
+        };
+        let checkpoint = Checkpoint {
+            id,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            label: label.to_string(),
+            kind,
+            auto,
+            reference,
+        };
+        let mut manifest = self.list()?;
+        manifest.push(checkpoint.clone());
+        self.write_manifest(&manifest)?;
+        Ok(checkpoint)
+    }
+
+    /// Checkpoints, oldest first.
+    pub fn list(&self) -> Result<Vec<Checkpoint>> {
+        let path = self.dir.join(MANIFEST_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Roll the workspace back to the checkpoint whose id starts with
+    /// `id_prefix`. Returns the restored checkpoint and the safety checkpoint
+    /// taken just before.
+    pub fn restore(&self, id_prefix: &str) -> Result<(Checkpoint, Checkpoint)> {
+        let target = self.find(id_prefix)?;
+        target.validate()?;
+        let safety = self.create(&format!("before restoring {}", target.short_id()), false)?;
+        match target.kind {
+            CheckpointKind::Git => self.git_restore(&target.reference)?,
+            CheckpointKind::Archive => self.archive_restore(&target.reference)?,
+        }
+        Ok((target, safety))
+    }
+
+    /// Drop all but the newest `keep` automatic checkpoints.
+    pub fn prune_auto(&self, keep: usize) -> Result<usize> {
+        let manifest = self.list()?;
+        let auto_count = manifest.iter().filter(|c| c.auto).count();
+        let mut excess = auto_count.saturating_sub(keep);
+        if excess == 0 {
+            return Ok(0);
+        }
+        let pruned = excess;
+        let mut kept = Vec::with_capacity(manifest.len());
+        for checkpoint in manifest {
+            if checkpoint.auto && excess > 0 {
+                excess -= 1;
+                self.discard(&checkpoint);
+            } else {
+                kept.push(checkpoint);
+            }
+        }
+        self.write_manifest(&kept)?;
+        Ok(pruned)
+    }
+
+    fn find(&self, id_prefix: &str) -> Result<Checkpoint> {
+        let matches: Vec<Checkpoint> = self
+            .list()?
+            .into_iter()
+            .filter(|c| c.id.starts_with(id_prefix))
+            .collect();
+        match matches.len() {
+            0 => bail!("No checkpoint matches '{id_prefix}'"),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            n => bail!("'{id_prefix}' matches {n} checkpoints; use a longer id"),
+        }
+    }
+
+    fn discard(&self, checkpoint: &Checkpoint) {
+        let result = checkpoint.validate().and_then(|()| match checkpoint.kind {
+            CheckpointKind::Git => self
+                .git(
+                    &[
+                        "update-ref",
+                        "-d",
+                        &format!("{GIT_REF_PREFIX}{}", checkpoint.id),
+                    ],
+                    None,
+                )
+                .map(|_| ()),
+            CheckpointKind::Archive => {
+                std::fs::remove_file(self.dir.join(&checkpoint.reference)).map_err(Into::into)
+            }
+        });
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to delete checkpoint {}: {e:#}",
+                checkpoint.short_id()
+            );
+        }
+    }
+
+    fn write_manifest(&self, manifest: &[Checkpoint]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(MANIFEST_FILE);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(manifest)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    // ── git ─────────────────────────────────────────────────────────
+
+    fn is_git_root(&self) -> bool {
+        let Ok(toplevel) = self.git(&["rev-parse", "--show-toplevel"], None) else {
+            return false;
+        };
+        let workspace = self
+            .workspace_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace_dir.clone());
+        Path::new(toplevel.trim()).canonicalize().ok() == Some(workspace)
+    }
+
+    fn git(&self, args: &[&str], index: Option<&Path>) -> Result<String> {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(&self.workspace_dir)
+            .args(args)
+            // commit-tree needs an identity even when the user has none set.
+            .env("GIT_AUTHOR_NAME", "ZeroClaw")
+            .env("GIT_AUTHOR_EMAIL", "zeroclaw@localhost")
+            .env("GIT_COMMITTER_NAME", "ZeroClaw")
+            .env("GIT_COMMITTER_EMAIL", "zeroclaw@localhost");
+        if let Some(index) = index {
+            command.env("GIT_INDEX_FILE", index);
+        }
+        let output = command
+            .output()
+            .context("failed to run git (is it installed?)")?;
+        if !output.status.success() {
+            bail!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Stage the working tree into a temporary index (seeded from the real
+    /// one so unchanged files are not rehashed). Returns the index path.
+    fn stage_worktree(&self, name: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let index = self.dir.join(format!("index-{name}"));
+        let real_index = self.git(&["rev-parse", "--git-path", "index"], None)?;
+        let real_index = self.workspace_dir.join(real_index.trim());
+        if real_index.is_file() {
+            std::fs::copy(&real_index, &index)?;
+        }
+        let mut args = vec!["add", "-A", "--", "."];
+        let excludes: Vec<String> = EXCLUDED_DIRS
+            .iter()
+            .map(|dir| format!(":(exclude){dir}"))
+            .collect();
+        args.extend(excludes.iter().map(String::as_str));
+        if let Err(e) = self.git(&args, Some(&index)) {
+            let _ = std::fs::remove_file(&index);
+            return Err(e);
+        }
+        Ok(index)
+    }
+
+    fn git_snapshot(&self, id: &str, label: &str) -> Result<String> {
+        let index = self.stage_worktree(id)?;
+        let tree = self.git(&["write-tree"], Some(&index));
+        let _ = std::fs::remove_file(&index);
+        let tree = tree?;
+        let message = format!("zeroclaw checkpoint: {label}");
+        let mut args = vec!["commit-tree", tree.trim(), "-m", message.as_str()];
+        let head = self
+            .git(&["rev-parse", "--verify", "-q", "HEAD"], None)
+            .ok();
+        if let Some(head) = head.as_deref() {
+            args.extend(["-p", head.trim()]);
+        }
+        let commit = self.git(&args, None)?.trim().to_string();
+        self.git(
+            &["update-ref", &format!("{GIT_REF_PREFIX}{id}"), &commit],
+            None,
+        )?;
+        Ok(commit)
+    }
+
+    fn git_restore(&self, commit: &str) -> Result<()> {
+        let wanted: HashSet<String> = self
+            .git(&["ls-tree", "-r", "-z", "--name-only", commit], None)?
+            .split('\0')
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let current_index = self.stage_worktree("restore-current")?;
+        let current = self.git(&["ls-files", "-z"], Some(&current_index));
+        let _ = std::fs::remove_file(&current_index);
+        for name in current?.split('\0').filter(|name| !name.is_empty()) {
+            if !wanted.contains(name) && !is_excluded(Path::new(name)) {
+                remove_if_exists(&self.workspace_dir.join(name))?;
+            }
+        }
+
+        let index = self.dir.join("index-restore");
+        let result = self
+            .git(&["read-tree", commit], Some(&index))
+            .and_then(|_| self.git(&["checkout-index", "-a", "-f"], Some(&index)));
+        let _ = std::fs::remove_file(&index);
+        result.map(|_| ())
+    }
+
+    // ── archive ─────────────────────────────────────────────────────
+
+    /// Workspace files outside [`EXCLUDED_DIRS`], relative to the workspace.
+    fn worktree_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let walker = ignore::WalkBuilder::new(&self.workspace_dir)
+            .standard_filters(false)
+            .filter_entry(|entry| entry.depth() != 1 || !is_excluded(Path::new(entry.file_name())))
+            .build();
+        for entry in walker {
+            let entry = entry?;
+            if entry.file_type().is_some_and(|t| t.is_file()) {
+                if let Ok(relative) = entry.path().strip_prefix(&self.workspace_dir) {
+                    files.push(relative.to_path_buf());
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    fn archive_snapshot(&self, id: &str) -> Result<String> {
+        std::fs::create_dir_all(&self.dir)?;
+        let name = archive_file_name(id);
+        let file = std::fs::File::create(self.dir.join(&name))?;
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for relative in self.worktree_files()? {
+            builder.append_path_with_name(
+                self.workspace_dir.join(&relative),
+                archive_name(&relative),
+            )?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(name)
+    }
+
+    fn open_archive(
+        &self,
+        name: &str,
+    ) -> Result<tar::Archive<flate2::read::GzDecoder<std::fs::File>>> {
+        let file = std::fs::File::open(self.dir.join(name))
+            .with_context(|| format!("Checkpoint archive {name} is missing"))?;
+        Ok(tar::Archive::new(flate2::read::GzDecoder::new(file)))
+    }
+
+    fn archive_restore(&self, name: &str) -> Result<()> {
+        let mut wanted = HashSet::new();
+        for entry in self.open_archive(name)?.entries()? {
+            wanted.insert(archive_name(&enclosed_path(&entry?)?));
+        }
+
+        for relative in self.worktree_files()? {
+            if !wanted.contains(&archive_name(&relative)) {
+                remove_if_exists(&self.workspace_dir.join(&relative))?;
+            }
+        }
+
+        for entry in self.open_archive(name)?.entries()? {
+            let mut entry = entry?;
+            let relative = enclosed_path(&entry)?;
+            if is_excluded(&relative) || !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let target = self.workspace_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // Replace rather than write through a symlink created since.
+            remove_if_exists(&target)?;
+            let mut out = std::fs::File::create(&target)?;
+            std::io::copy(&mut entry, &mut out)?;
+        }
+        Ok(())
+    }
+}
+
+fn is_excluded(relative: &Path) -> bool {
+    relative
+        .components()
+        .next()
+        .and_then(|first| first.as_os_str().to_str())
+        .is_some_and(|first| EXCLUDED_DIRS.contains(&first))
+}
+
+fn archive_file_name(id: &str) -> String {
+    format!("{id}.{ARCHIVE_EXTENSION}")
+}
+
+/// Path of an archive entry, refusing absolute paths and `..`.
+fn enclosed_path<R: std::io::Read>(entry: &tar::Entry<'_, R>) -> Result<PathBuf> {
+    let path = entry.path()?;
+    if !path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!(
+            "Checkpoint archive entry '{}' escapes the workspace",
+            path.display()
+        );
+    }
+    Ok(path.into_owned())
+}
+
+/// Archive entry name: `/`-separated on every platform.
+fn archive_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checkpoint the workspace before an autonomous run when
+/// `[agent] auto_checkpoint` is on. Failures are logged, never fatal.
+pub async fn auto_checkpoint(config: &crate::config::Config, label: &str) {
+    if !config.agent.auto_checkpoint {
+        return;
+    }
+    let workspace_dir = config.workspace_dir.clone();
+    let keep = config.agent.max_auto_checkpoints;
+    let task_label = label.to_string();
+    let result = tokio::task::spawn_blocking(move || {
+        let checkpoints = Checkpoints::new(&workspace_dir);
+        let checkpoint = checkpoints.create(&task_label, true)?;
+        checkpoints.prune_auto(keep)?;
+        anyhow::Ok(checkpoint)
+    })
+    .await;
+    match result {
+        Ok(Ok(checkpoint)) => tracing::info!(
+            "Created checkpoint {} before {label}",
+            checkpoint.short_id()
+        ),
+        Ok(Err(e)) => tracing::warn!("Failed to checkpoint the workspace: {e:#}"),
+        Err(e) => tracing::warn!("Checkpoint task failed: {e}"),
+    }
+}
+
+/// `zeroclaw checkpoint create [label]`.
+pub fn run_create(config: &crate::config::Config, label: Option<&str>) -> Result<()> {
+    let checkpoint =
+        Checkpoints::new(&config.workspace_dir).create(label.unwrap_or("manual"), false)?;
+    println!(
+        "✅ Created {} checkpoint {}",
+        kind_name(checkpoint.kind),
+        checkpoint.short_id()
+    );
+    Ok(())
+}
+
+/// `zeroclaw checkpoint list`.
+pub fn run_list(config: &crate::config::Config) -> Result<()> {
+    let checkpoints = Checkpoints::new(&config.workspace_dir).list()?;
+    if checkpoints.is_empty() {
+        println!("No checkpoints. Create one with `zeroclaw checkpoint create`.");
+        return Ok(());
+    }
+    println!("{} checkpoint(s), newest first:", checkpoints.len());
+    for checkpoint in checkpoints.iter().rev() {
+        println!(
+            "  {}  {}  {:<7} {}{}",
+            checkpoint.short_id(),
+            checkpoint.created_at,
+            kind_name(checkpoint.kind),
+            checkpoint.label,
+            if checkpoint.auto { " (auto)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// `zeroclaw checkpoint restore <id>`.
+pub fn run_restore(config: &crate::config::Config, id: &str) -> Result<()> {
+    let (restored, safety) = Checkpoints::new(&config.workspace_dir).restore(id)?;
+    println!(
+        "↩️  Restored checkpoint {} ({})",
+        restored.short_id(),
+        restored.label
+    );
+    println!(
+        "The previous state was saved as checkpoint {}; restore it to undo.",
+        safety.short_id()
+    );
+    Ok(())
+}
+
+fn kind_name(kind: CheckpointKind) -> &'static str {
+    match kind {
+        CheckpointKind::Git => "git",
+        CheckpointKind::Archive => "archive",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_available() -> bool {
+        Command::new("git").arg("--version").output().is_ok()
+    }
+
+    fn exercise_restore(workspace: &Path, expected: CheckpointKind) {
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+        std::fs::create_dir_all(workspace.join("state")).unwrap();
+        std::fs::write(workspace.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(workspace.join("notes.md"), "v1").unwrap();
+
+        let checkpoints = Checkpoints::new(workspace);
+        let checkpoint = checkpoints.create("before run", false).unwrap();
+        assert_eq!(checkpoint.kind, expected);
+
+        std::fs::write(workspace.join("notes.md"), "broken").unwrap();
+        std::fs::remove_file(workspace.join("src/main.rs")).unwrap();
+        std::fs::write(workspace.join("junk.txt"), "junk").unwrap();
+        std::fs::write(workspace.join("state/runtime.json"), "{}").unwrap();
+
+        let (restored, safety) = checkpoints.restore(checkpoint.short_id()).unwrap();
+        assert_eq!(restored.id, checkpoint.id);
+        assert_ne!(safety.id, checkpoint.id);
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("notes.md")).unwrap(),
+            "v1"
+        );
+        assert!(workspace.join("src/main.rs").exists());
+        assert!(!workspace.join("junk.txt").exists());
+        // Runtime data is left alone.
+        assert!(workspace.join("state/runtime.json").exists());
+
+        // The safety checkpoint undoes the restore.
+        checkpoints.restore(&safety.id).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(workspace.join("notes.md")).unwrap(),
+            "broken"
+        );
+        assert!(workspace.join("junk.txt").exists());
+    }
+
+    #[test]
+    fn archive_checkpoint_restores_files_and_removes_new_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        exercise_restore(tmp.path(), CheckpointKind::Archive);
+    }
+
+    #[test]
+    fn git_checkpoint_restores_without_touching_head() {
+        if !git_available() {
+            return;
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(workspace)
+            .status()
+            .unwrap();
+        let checkpoints = Checkpoints::new(workspace);
+        let head_before = checkpoints.git(&["rev-parse", "--verify", "-q", "HEAD"], None);
+
+        exercise_restore(workspace, CheckpointKind::Git);
+
+        let head_after = checkpoints.git(&["rev-parse", "--verify", "-q", "HEAD"], None);
+        assert_eq!(head_before.ok(), head_after.ok());
+        let refs = checkpoints
+            .git(
+                &["for-each-ref", "--format=%(refname)", GIT_REF_PREFIX],
+                None,
+            )
+            .unwrap();
+        assert_eq!(refs.lines().count(), 3);
+    }
+
+    #[test]
+    fn tampered_manifest_references_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        std::fs::write(workspace.join("a.txt"), "a").unwrap();
+        let victim = tmp.path().join("victim.txt");
+        std::fs::write(&victim, "victim").unwrap();
+
+        let checkpoints = Checkpoints::new(&workspace);
+        let mut checkpoint = checkpoints.create("auto", true).unwrap();
+        checkpoint.reference = "../../../victim.txt".into();
+        checkpoints.write_manifest(&[checkpoint.clone()]).unwrap();
+
+        let err = checkpoints.restore(checkpoint.short_id()).unwrap_err();
+        assert!(err.to_string().contains("invalid reference"));
+        checkpoints.prune_auto(0).unwrap();
+        assert!(victim.exists());
+
+        checkpoint.kind = CheckpointKind::Git;
+        checkpoint.reference = "--output=/tmp/x".into();
+        assert!(checkpoint.validate().is_err());
+    }
+
+    #[test]
+    fn prune_keeps_newest_auto_and_all_manual_checkpoints() {
+        let tmp = tempfile::tempdir().unwrap();
+        let checkpoints = Checkpoints::new(tmp.path());
+        std::fs::write(tmp.path().join("a.txt"), "a").unwrap();
+        let manual = checkpoints.create("manual", false).unwrap();
+        for _ in 0..3 {
+            checkpoints.create("auto", true).unwrap();
+        }
+        assert_eq!(checkpoints.prune_auto(1).unwrap(), 2);
+
+        let remaining = checkpoints.list().unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[0].id, manual.id);
+        assert!(remaining[1].auto);
+        let archives = std::fs::read_dir(tmp.path().join(CHECKPOINT_DIR))
+            .unwrap()
+            .filter(|e| {
+                e.as_ref()
+                    .unwrap()
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext == "gz")
+            })
+            .count();
+        assert_eq!(archives, 2);
+    }
+}
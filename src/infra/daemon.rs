@@ -1,6 +1,18 @@
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Output;
 
 use super::traits::{Daemon, DaemonPlatform, DaemonStatus};
+use crate::config::Config;
+
+/// systemd user unit name.
+pub const SYSTEMD_UNIT: &str = "zeroclaw.service";
+/// launchd job label.
+pub const LAUNCHD_LABEL: &str = "com.zeroclaw.daemon";
+/// Seconds the service manager waits before restarting a crashed daemon.
+pub(super) const RESTART_DELAY_SECS: u32 = 5;
 
 /// Stub daemon for manual process management. All operations are no-ops
 /// and status always reports `Stopped`.
@@ -8,6 +20,14 @@ pub struct ManualDaemon;
 
 #[async_trait]
 impl Daemon for ManualDaemon {
+    async fn install(&self) -> anyhow::Result<()> {
+        bail!("No supported service manager on this platform; supervise `zeroclaw daemon run` manually")
+    }
+
+    async fn uninstall(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn start(&self) -> anyhow::Result<()> {
         Ok(())
     }
@@ -29,10 +49,420 @@ impl Daemon for ManualDaemon {
     }
 }
 
+/// What the installed service runs: `zeroclaw --config-dir <dir> daemon run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceSpec {
+    pub executable: PathBuf,
+    pub config_dir: PathBuf,
+    pub log_dir: PathBuf,
+}
+
+impl ServiceSpec {
+    /// Service for the running binary and the loaded config's directory.
+    /// Logs go to `<config dir>/logs`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let executable =
+            std::env::current_exe().context("Could not determine the zeroclaw executable path")?;
+        let config_dir = config
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?
+            .to_path_buf();
+        Ok(Self {
+            executable,
+            log_dir: config_dir.join("logs"),
+            config_dir,
+        })
+    }
+
+    pub(super) fn args(&self) -> Vec<String> {
+        vec![
+            self.executable.display().to_string(),
+            "--config-dir".into(),
+            self.config_dir.display().to_string(),
+            "daemon".into(),
+            "run".into(),
+        ]
+    }
+
+    fn stdout_log(&self) -> PathBuf {
+        self.log_dir.join("daemon.log")
+    }
+
+    fn stderr_log(&self) -> PathBuf {
+        self.log_dir.join("daemon.err.log")
+    }
+}
+
+/// Quote one `ExecStart=` argument per systemd's unit file syntax.
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!("\"{escaped}\"")
+}
+
+/// Render the systemd user unit. Restarts on failure; output is appended to
+/// the spec's log files.
+pub fn render_systemd_unit(spec: &ServiceSpec) -> String {
+    let exec = spec
+        .args()
+        .iter()
+        .map(|arg| systemd_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Unit]\n\
+         Description=ZeroClaw daemon (gateway + channels)\n\
+         After=network-online.target\n\
+         Wants=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={exec}\n\
+         Restart=on-failure\n\
+         RestartSec={RESTART_DELAY_SECS}\n\
+         Environment=RUST_LOG=info\n\
+         StandardOutput=append:{}\n\
+         StandardError=append:{}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        spec.stdout_log().display(),
+        spec.stderr_log().display(),
+    )
+}
+
+fn xml_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render the launchd agent plist. Runs at load and is relaunched unless it
+/// exits cleanly.
+pub fn render_launchd_plist(spec: &ServiceSpec) -> String {
+    let args = spec.args().iter().fold(String::new(), |mut out, arg| {
+        let _ = writeln!(out, "        <string>{}</string>", xml_escape(arg));
+        out
+    });
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n\
+         \x20   <key>Label</key>\n\
+         \x20   <string>{LAUNCHD_LABEL}</string>\n\
+         \x20   <key>ProgramArguments</key>\n\
+         \x20   <array>\n\
+         {args}\
+         \x20   </array>\n\
+         \x20   <key>EnvironmentVariables</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>RUST_LOG</key>\n\
+         \x20       <string>info</string>\n\
+         \x20   </dict>\n\
+         \x20   <key>RunAtLoad</key>\n\
+         \x20   <true/>\n\
+         \x20   <key>KeepAlive</key>\n\
+         \x20   <dict>\n\
+         \x20       <key>SuccessfulExit</key>\n\
+         \x20       <false/>\n\
+         \x20   </dict>\n\
+         \x20   <key>ThrottleInterval</key>\n\
+         \x20   <integer>{RESTART_DELAY_SECS}</integer>\n\
+         \x20   <key>StandardOutPath</key>\n\
+         \x20   <string>{}</string>\n\
+         \x20   <key>StandardErrorPath</key>\n\
+         \x20   <string>{}</string>\n\
+         </dict>\n\
+         </plist>\n",
+        xml_escape(&spec.stdout_log().display().to_string()),
+        xml_escape(&spec.stderr_log().display().to_string()),
+    )
+}
+
+fn home_dir() -> Result<PathBuf> {
+    directories::UserDirs::new()
+        .map(|u| u.home_dir().to_path_buf())
+        .context("Could not find home directory")
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<Output> {
+    tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {program}"))
+}
+
+async fn run_checked(program: &str, args: &[&str]) -> Result<()> {
+    let output = run(program, args).await?;
+    if !output.status.success() {
+        bail!(
+            "{program} {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+async fn write_service_file(path: &Path, contents: &str, spec: &ServiceSpec) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    tokio::fs::create_dir_all(&spec.log_dir)
+        .await
+        .with_context(|| format!("Failed to create {}", spec.log_dir.display()))?;
+    tokio::fs::write(path, contents)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// systemd user service (`~/.config/systemd/user/zeroclaw.service`).
+pub struct SystemdDaemon {
+    spec: ServiceSpec,
+    unit_path: PathBuf,
+}
+
+impl SystemdDaemon {
+    pub fn new(spec: ServiceSpec) -> Result<Self> {
+        let unit_path = home_dir()?
+            .join(".config")
+            .join("systemd")
+            .join("user")
+            .join(SYSTEMD_UNIT);
+        Ok(Self { spec, unit_path })
+    }
+}
+
+/// Map `systemctl is-active` output to a status.
+fn systemd_status(state: &str) -> DaemonStatus {
+    match state.trim() {
+        "active" | "reloading" | "activating" => DaemonStatus::Running,
+        "inactive" | "failed" | "deactivating" => DaemonStatus::Stopped,
+        _ => DaemonStatus::Unknown,
+    }
+}
+
+#[async_trait]
+impl Daemon for SystemdDaemon {
+    async fn install(&self) -> anyhow::Result<()> {
+        write_service_file(
+            &self.unit_path,
+            &render_systemd_unit(&self.spec),
+            &self.spec,
+        )
+        .await?;
+        run_checked("systemctl", &["--user", "daemon-reload"]).await?;
+        run_checked("systemctl", &["--user", "enable", SYSTEMD_UNIT]).await
+    }
+
+    async fn uninstall(&self) -> anyhow::Result<()> {
+        if !self.unit_path.exists() {
+            return Ok(());
+        }
+        // Best effort: the unit may already be stopped or disabled.
+        let _ = run("systemctl", &["--user", "disable", "--now", SYSTEMD_UNIT]).await;
+        tokio::fs::remove_file(&self.unit_path)
+            .await
+            .with_context(|| format!("Failed to remove {}", self.unit_path.display()))?;
+        run_checked("systemctl", &["--user", "daemon-reload"]).await
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        run_checked("systemctl", &["--user", "start", SYSTEMD_UNIT]).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        run_checked("systemctl", &["--user", "stop", SYSTEMD_UNIT]).await
+    }
+
+    async fn status(&self) -> anyhow::Result<DaemonStatus> {
+        if !self.unit_path.exists() {
+            return Ok(DaemonStatus::NotInstalled);
+        }
+        let output = run("systemctl", &["--user", "is-active", SYSTEMD_UNIT]).await?;
+        Ok(systemd_status(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn platform(&self) -> DaemonPlatform {
+        DaemonPlatform::Systemd
+    }
+
+    fn name(&self) -> &str {
+        "systemd"
+    }
+}
+
+/// launchd user agent (`~/Library/LaunchAgents/com.zeroclaw.daemon.plist`).
+pub struct LaunchdDaemon {
+    spec: ServiceSpec,
+    plist_path: PathBuf,
+}
+
+impl LaunchdDaemon {
+    pub fn new(spec: ServiceSpec) -> Result<Self> {
+        let plist_path = home_dir()?
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{LAUNCHD_LABEL}.plist"));
+        Ok(Self { spec, plist_path })
+    }
+
+    fn plist(&self) -> Result<&str> {
+        self.plist_path
+            .to_str()
+            .context("launchd plist path is not valid UTF-8")
+    }
+}
+
+#[async_trait]
+impl Daemon for LaunchdDaemon {
+    async fn install(&self) -> anyhow::Result<()> {
+        write_service_file(
+            &self.plist_path,
+            &render_launchd_plist(&self.spec),
+            &self.spec,
+        )
+        .await?;
+        // Reload so an existing job picks up the new definition.
+        let _ = run("launchctl", &["unload", self.plist()?]).await;
+        run_checked("launchctl", &["load", "-w", self.plist()?]).await
+    }
+
+    async fn uninstall(&self) -> anyhow::Result<()> {
+        if !self.plist_path.exists() {
+            return Ok(());
+        }
+        let _ = run("launchctl", &["unload", "-w", self.plist()?]).await;
+        tokio::fs::remove_file(&self.plist_path)
+            .await
+            .with_context(|| format!("Failed to remove {}", self.plist_path.display()))
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        if !self.plist_path.exists() {
+            bail!("Service is not installed; run `zeroclaw daemon install` first");
+        }
+        // `load` starts the job via RunAtLoad; `start` covers an already-loaded one.
+        let _ = run("launchctl", &["load", self.plist()?]).await;
+        run_checked("launchctl", &["start", LAUNCHD_LABEL]).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        // Unload rather than `launchctl stop`: KeepAlive would relaunch a
+        // job that was merely signalled.
+        run_checked("launchctl", &["unload", self.plist()?]).await
+    }
+
+    async fn status(&self) -> anyhow::Result<DaemonStatus> {
+        if !self.plist_path.exists() {
+            return Ok(DaemonStatus::NotInstalled);
+        }
+        let output = run("launchctl", &["list", LAUNCHD_LABEL]).await?;
+        if !output.status.success() {
+            return Ok(DaemonStatus::Stopped);
+        }
+        Ok(launchd_status(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn platform(&self) -> DaemonPlatform {
+        DaemonPlatform::Launchd
+    }
+
+    fn name(&self) -> &str {
+        "launchd"
+    }
+}
+
+/// A loaded launchd job is running when `launchctl list <label>` shows a PID.
+fn launchd_status(listing: &str) -> DaemonStatus {
+    if listing.contains("\"PID\"") {
+        DaemonStatus::Running
+    } else {
+        DaemonStatus::Stopped
+    }
+}
+
+/// Run the gateway and channels together in the foreground. This is what the
+/// installed service executes; an error exits non-zero so the service
+/// manager restarts it.
+pub async fn run_foreground(config: Config) -> Result<()> {
+    let host = config.gateway.host.clone();
+    let port = config.gateway.port;
+    tracing::info!("Starting ZeroClaw daemon (gateway on {host}:{port} + channels)");
+    tokio::try_join!(
+        crate::gateway::run_gateway(&host, port, config.clone()),
+        crate::channels::start_channels(config),
+    )?;
+    Ok(())
+}
+
+/// Handle `zeroclaw daemon <subcommand>` CLI commands.
+pub async fn handle_command(command: crate::DaemonCommands, config: &Config) -> Result<()> {
+    let daemon = super::create_daemon(config)?;
+    match command {
+        crate::DaemonCommands::Run => {
+            bail!("Run must be handled in main.rs (runs the foreground runtime)")
+        }
+        crate::DaemonCommands::Install => {
+            daemon.install().await?;
+            let spec = ServiceSpec::from_config(config)?;
+            println!("✅ Installed {} service", daemon.name());
+            println!("   Logs: {}", spec.log_dir.display());
+            if daemon.platform() == DaemonPlatform::Systemd {
+                println!("   Start now with: zeroclaw daemon start");
+                println!("   To start at boot without logging in: loginctl enable-linger $USER");
+            }
+            if daemon.platform() == DaemonPlatform::WindowsService {
+                println!("   Start now with: zeroclaw daemon start (from an Administrator shell)");
+                println!("   Events are written to the Application event log (source: ZeroClaw)");
+            }
+        }
+        crate::DaemonCommands::Uninstall => {
+            daemon.uninstall().await?;
+            println!("✅ Uninstalled {} service", daemon.name());
+        }
+        crate::DaemonCommands::Start => {
+            daemon.start().await?;
+            println!("✅ Started {} service", daemon.name());
+        }
+        crate::DaemonCommands::Stop => {
+            daemon.stop().await?;
+            println!("✅ Stopped {} service", daemon.name());
+        }
+        crate::DaemonCommands::Status => {
+            let status = daemon.status().await?;
+            let label = match status {
+                DaemonStatus::Running => "🟢 running",
+                DaemonStatus::Stopped => "🔴 stopped",
+                DaemonStatus::NotInstalled => "⚪ not installed (run `zeroclaw daemon install`)",
+                DaemonStatus::Unknown => "❔ unknown",
+            };
+            println!("Daemon ({}): {label}", daemon.name());
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn spec() -> ServiceSpec {
+        ServiceSpec {
+            executable: PathBuf::from("/opt/zero claw/bin/zeroclaw"),
+            config_dir: PathBuf::from("/home/u/.zeroclaw"),
+            log_dir: PathBuf::from("/home/u/.zeroclaw/logs"),
+        }
+    }
+
     #[tokio::test]
     async fn manual_daemon_reports_stopped() {
         let d = ManualDaemon;
@@ -45,5 +475,49 @@ mod tests {
         let d = ManualDaemon;
         d.start().await.unwrap();
         d.stop().await.unwrap();
+        assert!(d.install().await.is_err());
+    }
+
+    #[test]
+    fn systemd_unit_runs_daemon_with_restart_and_log_redirection() {
+        let unit = render_systemd_unit(&spec());
+        assert!(unit.contains(
+            "ExecStart=\"/opt/zero claw/bin/zeroclaw\" \"--config-dir\" \"/home/u/.zeroclaw\" \"daemon\" \"run\"\n"
+        ));
+        assert!(unit.contains("Restart=on-failure\nRestartSec=5\n"));
+        assert!(unit.contains("StandardOutput=append:/home/u/.zeroclaw/logs/daemon.log\n"));
+        assert!(unit.contains("StandardError=append:/home/u/.zeroclaw/logs/daemon.err.log\n"));
+        assert!(unit.ends_with("[Install]\nWantedBy=default.target\n"));
+        assert_eq!(systemd_quote("50%\"x\""), "\"50%%\\\"x\\\"\"");
+    }
+
+    #[test]
+    fn launchd_plist_keeps_job_alive_and_escapes_paths() {
+        let mut spec = spec();
+        spec.config_dir = PathBuf::from("/Users/a&b/.zeroclaw");
+        let plist = render_launchd_plist(&spec);
+        assert!(plist.contains("<string>com.zeroclaw.daemon</string>"));
+        assert!(plist.contains(
+            "        <string>/opt/zero claw/bin/zeroclaw</string>\n        <string>--config-dir</string>\n        <string>/Users/a&amp;b/.zeroclaw</string>\n"
+        ));
+        assert!(plist.contains("<key>SuccessfulExit</key>\n        <false/>"));
+        assert!(plist.contains(
+            "<key>StandardErrorPath</key>\n    <string>/home/u/.zeroclaw/logs/daemon.err.log</string>"
+        ));
+    }
+
+    #[test]
+    fn status_parsers_map_service_manager_output() {
+        assert_eq!(systemd_status("active\n"), DaemonStatus::Running);
+        assert_eq!(systemd_status("failed\n"), DaemonStatus::Stopped);
+        assert_eq!(systemd_status(""), DaemonStatus::Unknown);
+        assert_eq!(
+            launchd_status("{\n\t\"PID\" = 4242;\n\t\"Label\" = \"com.zeroclaw.daemon\";\n};"),
+            DaemonStatus::Running
+        );
+        assert_eq!(
+            launchd_status("{\n\t\"LastExitStatus\" = 0;\n};"),
+            DaemonStatus::Stopped
+        );
     }
 }
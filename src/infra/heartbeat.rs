@@ -1,26 +1,250 @@
+use anyhow::{bail, Context};
 use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 use super::traits::{Heartbeat, HeartbeatResult};
+use crate::channels::{Channel, SendMessage};
+use crate::memory::Memory;
+use crate::providers::Provider;
 
-/// Simple heartbeat that always reports healthy with a configurable interval.
+/// One probe run on every heartbeat tick. Results are recorded in the
+/// health registry under [`HealthCheck::component`].
+#[async_trait]
+pub trait HealthCheck: Send + Sync {
+    /// Health registry key, e.g. `provider:openrouter`.
+    fn component(&self) -> String;
+
+    async fn check(&self) -> anyhow::Result<()>;
+}
+
+/// Provider reachability via [`Provider::warmup`].
+pub struct ProviderCheck {
+    name: String,
+    provider: Arc<dyn Provider>,
+}
+
+impl ProviderCheck {
+    pub fn new(name: impl Into<String>, provider: Arc<dyn Provider>) -> Self {
+        Self {
+            name: name.into(),
+            provider,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for ProviderCheck {
+    fn component(&self) -> String {
+        format!("provider:{}", self.name)
+    }
+
+    async fn check(&self) -> anyhow::Result<()> {
+        self.provider.warmup().await
+    }
+}
+
+/// Channel connectivity via [`Channel::health_check`]. Registered as
+/// `channel:<name>:probe` so it does not overwrite the listener's own entry.
+pub struct ChannelCheck {
+    channel: Arc<dyn Channel>,
+}
+
+impl ChannelCheck {
+    pub fn new(channel: Arc<dyn Channel>) -> Self {
+        Self { channel }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for ChannelCheck {
+    fn component(&self) -> String {
+        format!("channel:{}:probe", self.channel.name())
+    }
+
+    async fn check(&self) -> anyhow::Result<()> {
+        if !self.channel.health_check().await {
+            bail!("health check failed");
+        }
+        Ok(())
+    }
+}
+
+/// Memory backend health via [`Memory::health_check`].
+pub struct MemoryCheck {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemoryCheck {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for MemoryCheck {
+    fn component(&self) -> String {
+        format!("memory:{}", self.memory.name())
+    }
+
+    async fn check(&self) -> anyhow::Result<()> {
+        if !self.memory.health_check().await {
+            bail!("health check failed");
+        }
+        Ok(())
+    }
+}
+
+/// Free disk space on the filesystem holding `path`.
+pub struct DiskSpaceCheck {
+    path: PathBuf,
+    min_free_bytes: u64,
+}
+
+impl DiskSpaceCheck {
+    pub fn new(path: impl Into<PathBuf>, min_free_mb: u64) -> Self {
+        Self {
+            path: path.into(),
+            min_free_bytes: min_free_mb.saturating_mul(1024 * 1024),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for DiskSpaceCheck {
+    fn component(&self) -> String {
+        "disk".into()
+    }
+
+    async fn check(&self) -> anyhow::Result<()> {
+        let free = free_disk_bytes(&self.path).await?;
+        if free < self.min_free_bytes {
+            bail!(
+                "only {} MB free on {} (minimum {} MB)",
+                free / (1024 * 1024),
+                self.path.display(),
+                self.min_free_bytes / (1024 * 1024)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Available bytes on the filesystem holding `path`, from POSIX `df -Pk`.
+async fn free_disk_bytes(path: &std::path::Path) -> anyhow::Result<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .await
+        .context("Failed to run df")?;
+    if !output.status.success() {
+        bail!(
+            "df failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_df_available_kb(&String::from_utf8_lossy(&output.stdout))
+        .map(|kb| kb.saturating_mul(1024))
+        .context("Unexpected df output")
+}
+
+/// `Available` column (1K blocks) of the last line of `df -Pk` output.
+fn parse_df_available_kb(output: &str) -> Option<u64> {
+    output
+        .lines()
+        .skip(1)
+        .last()?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()
+}
+
+/// Heartbeat that runs its [`HealthCheck`]s on every tick, records each
+/// result in [`crate::health`], and optionally alerts a channel when a
+/// component turns unhealthy or recovers. With no checks it always reports
+/// healthy.
 pub struct DefaultHeartbeat {
     interval: Duration,
+    checks: Vec<Box<dyn HealthCheck>>,
+    alert: Option<(Arc<dyn Channel>, String)>,
+    /// Last known health per component, for transition detection.
+    last_healthy: Mutex<HashMap<String, bool>>,
 }
 
 impl DefaultHeartbeat {
     pub fn new(interval: Duration) -> Self {
-        Self { interval }
+        Self {
+            interval,
+            checks: Vec::new(),
+            alert: None,
+            last_healthy: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn with_check(mut self, check: Box<dyn HealthCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Send state-change alerts to `recipient` on `channel`.
+    #[must_use]
+    pub fn with_alerts(mut self, channel: Arc<dyn Channel>, recipient: impl Into<String>) -> Self {
+        self.alert = Some((channel, recipient.into()));
+        self
+    }
+
+    async fn notify(&self, text: String) {
+        let Some((channel, recipient)) = &self.alert else {
+            return;
+        };
+        if let Err(e) = channel.send(&SendMessage::new(text, recipient)).await {
+            tracing::warn!("Heartbeat alert via {} failed: {e}", channel.name());
+        }
     }
 }
 
 #[async_trait]
 impl Heartbeat for DefaultHeartbeat {
     async fn tick(&self) -> anyhow::Result<HeartbeatResult> {
+        let mut failures = Vec::new();
+        for check in &self.checks {
+            let component = check.component();
+            let outcome = check.check().await;
+            let healthy = outcome.is_ok();
+            match &outcome {
+                Ok(()) => crate::health::mark_component_ok(&component),
+                Err(e) => crate::health::mark_component_error(&component, e),
+            }
+
+            let previous = self.last_healthy.lock().insert(component.clone(), healthy);
+            match (previous, outcome) {
+                (Some(false), Ok(())) => {
+                    tracing::info!("Health check recovered: {component}");
+                    self.notify(format!("✅ ZeroClaw: {component} recovered"))
+                        .await;
+                }
+                (prev, Err(e)) => {
+                    if prev != Some(false) {
+                        tracing::warn!("Health check failed: {component}: {e}");
+                        self.notify(format!("⚠️ ZeroClaw: {component} is unhealthy: {e}"))
+                            .await;
+                    }
+                    failures.push(format!("{component}: {e}"));
+                }
+                _ => {}
+            }
+        }
+
         Ok(HeartbeatResult {
-            healthy: true,
+            healthy: failures.is_empty(),
             checked_at: chrono::Utc::now(),
-            details: None,
+            details: (!failures.is_empty()).then(|| failures.join("; ")),
         })
     }
 
@@ -37,9 +261,119 @@ impl Heartbeat for DefaultHeartbeat {
     }
 }
 
+/// Build the monitor described by `[heartbeat]`: probes for the provider,
+/// every channel, the memory backend and workspace disk space, alerting via
+/// `alert_channel` when it is one of `channels`. Enabled `[[feeds]]` are
+/// polled from the same loop, even when the probes are disabled.
+pub(crate) fn monitor_from_config(
+    config: &crate::config::Config,
+    provider_name: &str,
+    provider: Arc<dyn Provider>,
+    memory: Arc<dyn Memory>,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+) -> DefaultHeartbeat {
+    let hb = &config.heartbeat;
+    let mut monitor = DefaultHeartbeat::new(Duration::from_secs(hb.interval_secs.max(1)));
+    for watcher in crate::feeds::watchers_from_config(config, &memory, channels) {
+        monitor = monitor.with_check(Box::new(watcher));
+    }
+    if !hb.enabled {
+        return monitor;
+    }
+
+    monitor = monitor
+        .with_check(Box::new(ProviderCheck::new(provider_name, provider)))
+        .with_check(Box::new(MemoryCheck::new(memory)))
+        .with_check(Box::new(DiskSpaceCheck::new(
+            config.workspace_dir.clone(),
+            hb.min_free_disk_mb,
+        )));
+    let mut names: Vec<&String> = channels.keys().collect();
+    names.sort();
+    for name in names {
+        monitor = monitor.with_check(Box::new(ChannelCheck::new(Arc::clone(&channels[name]))));
+    }
+
+    if let (Some(name), Some(recipient)) = (&hb.alert_channel, &hb.alert_recipient) {
+        match channels.get(name) {
+            Some(channel) => monitor = monitor.with_alerts(Arc::clone(channel), recipient.clone()),
+            None => tracing::warn!(
+                "heartbeat.alert_channel '{name}' is not configured; alerts disabled"
+            ),
+        }
+    }
+    monitor
+}
+
+/// Tick `heartbeat` forever at its interval, reporting the loop itself as
+/// the `heartbeat` health component.
+pub async fn run(heartbeat: Arc<dyn Heartbeat>) {
+    if let Err(e) = heartbeat.on_wake().await {
+        tracing::warn!("Heartbeat wake hook failed: {e}");
+    }
+    let mut ticker = tokio::time::interval(heartbeat.interval());
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match heartbeat.tick().await {
+            Ok(result) if result.healthy => crate::health::mark_component_ok("heartbeat"),
+            Ok(result) => crate::health::mark_component_error(
+                "heartbeat",
+                result.details.unwrap_or_else(|| "unhealthy".into()),
+            ),
+            Err(e) => crate::health::mark_component_error("heartbeat", e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct ToggleCheck {
+        component: String,
+        healthy: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl HealthCheck for ToggleCheck {
+        fn component(&self) -> String {
+            self.component.clone()
+        }
+
+        async fn check(&self) -> anyhow::Result<()> {
+            if self.healthy.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                bail!("unreachable")
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.sent.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<crate::channels::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
 
     #[tokio::test]
     async fn tick_reports_healthy() {
@@ -53,4 +387,52 @@ mod tests {
         let hb = DefaultHeartbeat::new(Duration::from_secs(60));
         assert_eq!(hb.interval(), Duration::from_secs(60));
     }
+
+    #[tokio::test]
+    async fn tick_records_health_and_alerts_on_transitions_only() {
+        let component = format!("test-probe-{}", uuid::Uuid::new_v4());
+        let healthy = Arc::new(AtomicBool::new(true));
+        let channel = Arc::new(RecordingChannel::default());
+        let hb = DefaultHeartbeat::new(Duration::from_secs(1))
+            .with_check(Box::new(ToggleCheck {
+                component: component.clone(),
+                healthy: Arc::clone(&healthy),
+            }))
+            .with_alerts(channel.clone(), "ops");
+
+        assert!(hb.tick().await.unwrap().healthy);
+        assert_eq!(
+            crate::health::snapshot().components[&component].status,
+            "ok"
+        );
+
+        healthy.store(false, Ordering::SeqCst);
+        let result = hb.tick().await.unwrap();
+        assert!(!result.healthy);
+        assert_eq!(
+            result.details.as_deref(),
+            Some(format!("{component}: unreachable").as_str())
+        );
+        hb.tick().await.unwrap();
+        assert_eq!(
+            crate::health::snapshot().components[&component].status,
+            "error"
+        );
+
+        healthy.store(true, Ordering::SeqCst);
+        hb.tick().await.unwrap();
+
+        let sent = channel.sent.lock().clone();
+        assert_eq!(sent.len(), 2, "one alert per transition: {sent:?}");
+        assert!(sent[0].contains("is unhealthy: unreachable"));
+        assert!(sent[1].contains("recovered"));
+    }
+
+    #[test]
+    fn parse_df_available_kb_reads_posix_output() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/sda1 102400 40960 61440 40% /\n";
+        assert_eq!(parse_df_available_kb(output), Some(61440));
+        assert_eq!(parse_df_available_kb("Filesystem\n"), None);
+    }
 }
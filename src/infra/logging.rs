@@ -0,0 +1,675 @@
+//! Process logging (`[logging]`).
+//!
+//! `main` logs to stdout at `RUST_LOG` (default `info`) while config loads,
+//! then [`init`] installs the configured subscriber: a level plus per-module
+//! overrides, human-readable or JSON lines, and optionally a file rotated by
+//! size and/or time. JSON lines carry the enclosing spans' fields, so the
+//! `request_id` of a turn reaches log aggregators as a field. `zeroclaw logs`
+//! ([`run_logs`]) reads the file and its rotations back.
+
+use crate::config::{Config, LoggingConfig};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use parking_lot::Mutex;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::field::RecordFields;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Subscriber used until config is loaded: stdout, `RUST_LOG` or `info`.
+pub fn bootstrap_subscriber() -> impl Subscriber + Send + Sync {
+    tracing_subscriber::fmt::Subscriber::builder()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .finish()
+}
+
+/// Install the process-wide subscriber for `config` (defaults when `None`,
+/// e.g. `doctor` with a config that failed to load).
+pub fn init(config: Option<&Config>) -> Result<()> {
+    let default = LoggingConfig::default();
+    let logging = config.map_or(&default, |config| &config.logging);
+    let filter = build_filter(logging)?;
+
+    let (writer, ansi) = match config.and_then(log_file_path) {
+        Some(path) => {
+            let rotating = RotatingFile::open(
+                &path,
+                u64::from(logging.max_size_mb) * 1024 * 1024,
+                Rotation::parse(&logging.rotation),
+                logging.max_files as usize,
+            )
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            (BoxMakeWriter::new(rotating), false)
+        }
+        None => (BoxMakeWriter::new(io::stdout), true),
+    };
+
+    let registry = tracing_subscriber::registry().with(filter);
+    let installed = if logging.format == "json" {
+        tracing::subscriber::set_global_default(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .event_format(JsonFormat)
+                    .fmt_fields(JsonFields)
+                    .with_writer(writer),
+            ),
+        )
+    } else {
+        tracing::subscriber::set_global_default(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(ansi)
+                    .with_writer(writer),
+            ),
+        )
+    };
+    installed.context("Failed to install the logging subscriber")
+}
+
+/// `RUST_LOG` when set, otherwise `level` followed by the module overrides
+/// (sorted, so the directive string is stable).
+fn build_filter(logging: &LoggingConfig) -> Result<EnvFilter> {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return Ok(filter);
+    }
+    EnvFilter::try_new(filter_directives(logging)).context("Invalid [logging] level directives")
+}
+
+fn filter_directives(logging: &LoggingConfig) -> String {
+    let mut modules: Vec<_> = logging.modules.iter().collect();
+    modules.sort();
+    let mut directives = vec![logging.level.trim().to_ascii_lowercase()];
+    directives.extend(
+        modules
+            .into_iter()
+            .map(|(module, level)| format!("{module}={}", level.trim().to_ascii_lowercase())),
+    );
+    directives.join(",")
+}
+
+/// The configured log file (`logging.file` under the config directory).
+pub fn log_file_path(config: &Config) -> Option<PathBuf> {
+    let file = config.logging.file.as_deref()?;
+    Some(resolve_log_path(file, config.config_path.parent()))
+}
+
+fn resolve_log_path(file: &str, base: Option<&Path>) -> PathBuf {
+    let path = PathBuf::from(file);
+    match base {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    }
+}
+
+// ── File rotation ────────────────────────────────────────────────
+
+/// Time-based rotation period (`logging.rotation`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rotation {
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl Rotation {
+    fn parse(raw: &str) -> Self {
+        match raw {
+            "hourly" => Self::Hourly,
+            "daily" => Self::Daily,
+            _ => Self::Never,
+        }
+    }
+
+    /// Label of the period containing `at`; a change means "rotate".
+    fn period(self, at: DateTime<Local>) -> String {
+        match self {
+            Self::Never => String::new(),
+            Self::Hourly => at.format("%Y-%m-%d %H").to_string(),
+            Self::Daily => at.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+struct RotatingState {
+    file: File,
+    size: u64,
+    period: String,
+}
+
+/// Append-only log file rotated to `<path>.1` … `<path>.<max_files>` when a
+/// write would push it past `max_bytes` or the rotation period changes.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    rotation: Rotation,
+    max_files: usize,
+    state: Mutex<RotatingState>,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_bytes: u64, rotation: Rotation, max_files: usize) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = open_append(path)?;
+        let meta = file.metadata()?;
+        // A file left over from an earlier period rotates on the first write.
+        let period = match meta.modified() {
+            Ok(modified) if meta.len() > 0 => rotation.period(modified.into()),
+            _ => rotation.period(Local::now()),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            rotation,
+            max_files,
+            state: Mutex::new(RotatingState {
+                file,
+                size: meta.len(),
+                period,
+            }),
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{n}"));
+        PathBuf::from(rotated)
+    }
+
+    fn rotate(&self, state: &mut RotatingState) -> io::Result<()> {
+        state.file.flush()?;
+        if self.max_files == 0 {
+            state.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated_path(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            state.file = open_append(&self.path)?;
+        }
+        state.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for &RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self.state.lock();
+        let period = self.rotation.period(Local::now());
+        let len = buf.len() as u64;
+        let oversize = self.max_bytes > 0 && state.size > 0 && state.size + len > self.max_bytes;
+        if oversize || period != state.period {
+            self.rotate(&mut state)?;
+            state.period = period;
+        }
+        state.file.write_all(buf)?;
+        state.size += len;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.state.lock().file.flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingFile {
+    type Writer = &'a RotatingFile;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self
+    }
+}
+
+// ── JSON lines ───────────────────────────────────────────────────
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.0.insert(field.name().into(), value.to_string().into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().into(), format!("{value:?}").into());
+    }
+}
+
+/// Stores span fields as a JSON object so [`JsonFormat`] can nest them.
+pub struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut map = Map::new();
+        fields.record(&mut JsonVisitor(&mut map));
+        write!(writer, "{}", Value::Object(map))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        let mut map: Map<String, Value> = serde_json::from_str(&current.fields).unwrap_or_default();
+        fields.record(&mut JsonVisitor(&mut map));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+/// One JSON object per event: `timestamp`, `level`, `target`, `fields`, and
+/// the innermost `span` plus all enclosing `spans` (root first).
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut spans = Vec::new();
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let mut entry: Map<String, Value> = span
+                    .extensions()
+                    .get::<FormattedFields<N>>()
+                    .and_then(|formatted| serde_json::from_str(&formatted.fields).ok())
+                    .unwrap_or_default();
+                entry.insert("name".into(), span.name().into());
+                spans.push(Value::Object(entry));
+            }
+        }
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".into(),
+            Utc::now()
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+                .into(),
+        );
+        line.insert("level".into(), meta.level().to_string().into());
+        line.insert("target".into(), meta.target().into());
+        line.insert("fields".into(), Value::Object(fields));
+        if let Some(innermost) = spans.last() {
+            line.insert("span".into(), innermost.clone());
+            line.insert("spans".into(), Value::Array(spans));
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+// ── `zeroclaw logs` ──────────────────────────────────────────────
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Line filters for `zeroclaw logs`.
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    /// Least severe level shown.
+    pub min_level: Option<Level>,
+    /// Oldest timestamp shown.
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl LogQuery {
+    /// Lines of `text` that pass the filters. A line without a timestamp and
+    /// level (a wrapped message) follows the verdict of the line before it,
+    /// carried across calls in `keep`.
+    fn select<'a>(&self, text: &'a str, keep: &mut bool) -> Vec<&'a str> {
+        let mut selected = Vec::new();
+        for line in text.lines() {
+            if let Some((timestamp, level)) = parse_log_header(line) {
+                // `Level` orders more verbose levels as greater.
+                *keep = self.min_level.is_none_or(|min| level <= min)
+                    && self.since.is_none_or(|since| timestamp >= since);
+            }
+            if *keep {
+                selected.push(line);
+            }
+        }
+        selected
+    }
+}
+
+/// Timestamp and level of a pretty (`<rfc3339>  LEVEL ...`) or JSON log line.
+fn parse_log_header(line: &str) -> Option<(DateTime<Utc>, Level)> {
+    let (timestamp, level) = if line.starts_with('{') {
+        let value: Value = serde_json::from_str(line).ok()?;
+        (
+            value["timestamp"].as_str()?.to_string(),
+            value["level"].as_str()?.to_string(),
+        )
+    } else {
+        let mut parts = line.split_whitespace();
+        (parts.next()?.to_string(), parts.next()?.to_string())
+    };
+    let timestamp = DateTime::parse_from_rfc3339(&timestamp).ok()?;
+    Some((timestamp.with_timezone(&Utc), level.parse().ok()?))
+}
+
+/// `--since` values: a number followed by `s`, `m`, `h`, `d` or `w`.
+pub fn parse_since(raw: &str) -> Result<chrono::Duration> {
+    let raw = raw.trim();
+    let split = raw.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = raw.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .ok()
+        .filter(|n| *n >= 0)
+        .with_context(|| format!("Invalid --since '{raw}' (expected e.g. 30m, 1h, 2d)"))?;
+    let duration = match unit {
+        "s" => chrono::Duration::try_seconds(amount),
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        "w" => chrono::Duration::try_weeks(amount),
+        _ => None,
+    };
+    duration.with_context(|| format!("Invalid --since '{raw}' (expected e.g. 30m, 1h, 2d)"))
+}
+
+/// `path` and its rotated siblings, oldest first.
+fn log_files(path: &Path) -> Vec<PathBuf> {
+    let mut rotated = Vec::new();
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string());
+    if let (Some(name), Some(parent)) = (name, path.parent()) {
+        let prefix = format!("{name}.");
+        if let Ok(entries) = fs::read_dir(parent) {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if let Some(n) = file_name
+                    .strip_prefix(&prefix)
+                    .and_then(|n| n.parse::<usize>().ok())
+                {
+                    rotated.push((n, entry.path()));
+                }
+            }
+        }
+    }
+    rotated.sort_by_key(|(n, _)| std::cmp::Reverse(*n));
+    let mut files: Vec<PathBuf> = rotated.into_iter().map(|(_, p)| p).collect();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+    files
+}
+
+/// `zeroclaw logs`: print the last `lines` matching lines across the log
+/// file and its rotations, then optionally follow the live file.
+pub async fn run_logs(config: &Config, query: &LogQuery, lines: usize, follow: bool) -> Result<()> {
+    let Some(path) = log_file_path(config) else {
+        anyhow::bail!(
+            "File logging is not enabled; set [logging] file (e.g. \"logs/zeroclaw.log\") \
+             and restart the daemon"
+        );
+    };
+
+    let mut keep = false;
+    let mut recent = std::collections::VecDeque::with_capacity(lines.min(10_000));
+    let mut offset = 0u64;
+    for file in log_files(&path) {
+        let bytes =
+            fs::read(&file).with_context(|| format!("Failed to read {}", file.display()))?;
+        if file == path {
+            offset = bytes.len() as u64;
+        }
+        for line in query.select(&String::from_utf8_lossy(&bytes), &mut keep) {
+            if lines == 0 {
+                break;
+            }
+            if recent.len() == lines {
+                recent.pop_front();
+            }
+            recent.push_back(line.to_string());
+        }
+    }
+    if recent.is_empty() && !follow {
+        println!("No matching log lines in {}", path.display());
+    }
+    for line in recent {
+        println!("{line}");
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    // Poll the live file; a shrink means it was rotated, so start over.
+    let mut partial = String::new();
+    loop {
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        if meta.len() < offset {
+            offset = 0;
+            partial.clear();
+        }
+        if meta.len() == offset {
+            continue;
+        }
+        let mut file = File::open(&path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut chunk = Vec::new();
+        file.read_to_end(&mut chunk)?;
+        offset += chunk.len() as u64;
+        partial.push_str(&String::from_utf8_lossy(&chunk));
+        let Some(end) = partial.rfind('\n') else {
+            continue;
+        };
+        let complete: String = partial.drain(..=end).collect();
+        for line in query.select(&complete, &mut keep) {
+            println!("{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn directives_put_level_first_then_sorted_modules() {
+        let mut logging = LoggingConfig {
+            level: "WARN".into(),
+            ..LoggingConfig::default()
+        };
+        logging
+            .modules
+            .insert("zeroclaw::gateway".into(), "trace".into());
+        logging
+            .modules
+            .insert("zeroclaw::channels".into(), "debug".into());
+        assert_eq!(
+            filter_directives(&logging),
+            "warn,zeroclaw::channels=debug,zeroclaw::gateway=trace"
+        );
+        assert_eq!(
+            resolve_log_path("logs/zc.log", Some(Path::new("/etc/zc"))),
+            PathBuf::from("/etc/zc/logs/zc.log")
+        );
+    }
+
+    #[test]
+    fn json_lines_carry_event_and_span_fields() {
+        let captured = Captured::default();
+        let sink = captured.clone();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .event_format(JsonFormat)
+                .fmt_fields(JsonFields)
+                .with_writer(move || sink.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request", request_id = "req-7");
+            let _entered = span.enter();
+            tracing::warn!(attempt = 2, "provider retry");
+        });
+
+        let output = String::from_utf8(captured.0.lock().clone()).unwrap();
+        let line: Value = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["fields"]["message"], "provider retry");
+        assert_eq!(line["fields"]["attempt"], 2);
+        assert_eq!(line["span"]["name"], "request");
+        assert_eq!(line["span"]["request_id"], "req-7");
+        assert_eq!(line["spans"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("logs/zeroclaw.log");
+        let file = RotatingFile::open(&path, 10, Rotation::Never, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            (&file).write_all(line.as_bytes()).unwrap();
+        }
+        (&file).flush().unwrap();
+
+        let read = |p: PathBuf| fs::read_to_string(p).unwrap();
+        assert_eq!(read(path.clone()), "fourth\n");
+        assert_eq!(read(file.rotated_path(1)), "third\n");
+        assert_eq!(read(file.rotated_path(2)), "second\n");
+        assert!(!file.rotated_path(3).exists());
+    }
+
+    #[test]
+    fn rotates_when_the_period_changes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("zeroclaw.log");
+        let file = RotatingFile::open(&path, 0, Rotation::Daily, 3).unwrap();
+        (&file).write_all(b"yesterday\n").unwrap();
+        file.state.lock().period = "2000-01-01".into();
+        (&file).write_all(b"today\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "today\n");
+        assert_eq!(
+            fs::read_to_string(file.rotated_path(1)).unwrap(),
+            "yesterday\n"
+        );
+    }
+
+    #[test]
+    fn query_filters_both_formats_and_keeps_continuation_lines() {
+        let text = "\
+2026-10-16T09:00:00.000000Z  INFO zeroclaw::gateway: listening
+2026-10-16T10:00:00.000000Z  WARN zeroclaw::channels: telegram poll failed
+  caused by: timeout
+{\"timestamp\":\"2026-10-16T10:05:00.000Z\",\"level\":\"ERROR\",\"fields\":{}}
+{\"timestamp\":\"2026-10-16T10:06:00.000Z\",\"level\":\"DEBUG\",\"fields\":{}}
+";
+        let query = LogQuery {
+            min_level: Some(Level::WARN),
+            since: Some("2026-10-16T09:30:00Z".parse().unwrap()),
+        };
+        let mut keep = false;
+        let selected = query.select(text, &mut keep);
+        assert_eq!(selected.len(), 3);
+        assert!(selected[0].contains("telegram poll failed"));
+        assert_eq!(selected[1], "  caused by: timeout");
+        assert!(selected[2].contains("ERROR"));
+
+        assert_eq!(parse_since("90m").unwrap(), chrono::Duration::minutes(90));
+        assert_eq!(parse_since("2d").unwrap(), chrono::Duration::days(2));
+        assert!(parse_since("1y").is_err());
+        assert!(parse_since("h").is_err());
+    }
+
+    #[test]
+    fn log_files_lists_rotations_oldest_first() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("zeroclaw.log");
+        for name in [
+            "zeroclaw.log",
+            "zeroclaw.log.1",
+            "zeroclaw.log.10",
+            "zeroclaw.log.2",
+        ] {
+            fs::write(tmp.path().join(name), "").unwrap();
+        }
+        fs::write(tmp.path().join("zeroclaw.log.bak"), "").unwrap();
+        let names: Vec<String> = log_files(&path)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "zeroclaw.log.10",
+                "zeroclaw.log.2",
+                "zeroclaw.log.1",
+                "zeroclaw.log"
+            ]
+        );
+    }
+}
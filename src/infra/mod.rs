@@ -1,20 +1,47 @@
+pub mod checkpoint;
 pub mod daemon;
 pub mod heartbeat;
+pub mod logging;
+pub mod shutdown;
 pub mod traits;
 pub mod usage;
+#[cfg(windows)]
+pub mod windows_service;
 
-pub use daemon::ManualDaemon;
+pub use daemon::{LaunchdDaemon, ManualDaemon, ServiceSpec, SystemdDaemon};
 pub use heartbeat::DefaultHeartbeat;
-pub use traits::{
-    Daemon, DaemonPlatform, DaemonStatus, Heartbeat, HeartbeatResult, UsageBreakdown, UsageEvent,
-    UsagePeriod, UsageSummary, UsageTracker,
-};
+pub use traits::{Daemon, Heartbeat, UsageEvent, UsagePeriod, UsageTracker};
 pub use usage::InMemoryUsageTracker;
 
 use std::time::Duration;
 
-pub fn create_daemon() -> Box<dyn Daemon> {
-    Box::new(ManualDaemon)
+/// Pick the service manager for this OS: launchd on macOS, systemd on
+/// Linux, the Service Control Manager on Windows, manual management
+/// elsewhere.
+pub fn create_daemon(config: &crate::config::Config) -> anyhow::Result<Box<dyn Daemon>> {
+    if cfg!(target_os = "macos") {
+        Ok(Box::new(LaunchdDaemon::new(ServiceSpec::from_config(
+            config,
+        )?)?))
+    } else if cfg!(target_os = "linux") {
+        Ok(Box::new(SystemdDaemon::new(ServiceSpec::from_config(
+            config,
+        )?)?))
+    } else {
+        fallback_daemon(config)
+    }
+}
+
+#[cfg(windows)]
+fn fallback_daemon(config: &crate::config::Config) -> anyhow::Result<Box<dyn Daemon>> {
+    Ok(Box::new(windows_service::WindowsServiceDaemon::new(
+        ServiceSpec::from_config(config)?,
+    )))
+}
+
+#[cfg(not(windows))]
+fn fallback_daemon(_config: &crate::config::Config) -> anyhow::Result<Box<dyn Daemon>> {
+    Ok(Box::new(ManualDaemon))
 }
 
 pub fn create_heartbeat(interval: Duration) -> Box<dyn Heartbeat> {
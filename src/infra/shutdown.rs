@@ -0,0 +1,248 @@
+//! Coordinated graceful shutdown.
+//!
+//! The first SIGINT/SIGTERM triggers the process-wide [`Shutdown`]: the
+//! gateway stops accepting connections, the channel dispatcher stops taking
+//! new messages, and both wait up to `[runtime] shutdown_grace_secs` for
+//! in-flight provider calls and tool executions to finish before flushing
+//! their state. A second signal exits immediately.
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+/// Default time in-flight work gets to finish once shutdown starts.
+pub const DEFAULT_GRACE_SECS: u64 = 30;
+/// Exit status used when a second signal forces an immediate exit.
+const FORCED_EXIT_CODE: i32 = 130;
+
+static SHUTDOWN: OnceLock<Shutdown> = OnceLock::new();
+
+/// Shutdown state shared by the gateway, channels and agent workers.
+pub struct Shutdown {
+    token: CancellationToken,
+    in_flight: AtomicUsize,
+    idle: Notify,
+    grace_secs: AtomicU64,
+}
+
+/// Marks one unit of in-flight work; dropping it lets [`Shutdown::drain`]
+/// finish once nothing else is running.
+pub struct WorkGuard<'a> {
+    shutdown: &'a Shutdown,
+}
+
+impl Drop for WorkGuard<'_> {
+    fn drop(&mut self) {
+        if self.shutdown.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shutdown.idle.notify_waiters();
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_GRACE_SECS))
+    }
+}
+
+impl Shutdown {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            in_flight: AtomicUsize::new(0),
+            idle: Notify::new(),
+            grace_secs: AtomicU64::new(grace.as_secs()),
+        }
+    }
+
+    pub fn set_grace_period(&self, grace: Duration) {
+        self.grace_secs.store(grace.as_secs(), Ordering::Relaxed);
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_secs.load(Ordering::Relaxed))
+    }
+
+    /// Start shutting down. Idempotent.
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown has been triggered.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// Owned variant of [`Self::cancelled`], e.g. for `with_graceful_shutdown`.
+    pub fn cancelled_owned(&self) -> WaitForCancellationFutureOwned {
+        self.token.clone().cancelled_owned()
+    }
+
+    /// Register in-flight work, or `None` once shutdown has started so the
+    /// caller can refuse it.
+    pub fn begin_work(&self) -> Option<WorkGuard<'_>> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let guard = WorkGuard { shutdown: self };
+        (!self.is_shutting_down()).then_some(guard)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Wait for in-flight work to finish, at most the grace period.
+    /// Returns `false` if work was still running when the grace period ended.
+    pub async fn drain(&self) -> bool {
+        let deadline = tokio::time::sleep(self.grace_period());
+        tokio::pin!(deadline);
+        loop {
+            let idle = self.idle.notified();
+            if self.in_flight() == 0 {
+                return true;
+            }
+            tokio::select! {
+                () = idle => {}
+                () = &mut deadline => return self.in_flight() == 0,
+            }
+        }
+    }
+
+    /// Run `work` to completion, or until the grace period after shutdown
+    /// starts. Returns `None` when the grace period cut it short.
+    pub async fn run_with_grace<F: IntoFuture>(&self, work: F) -> Option<F::Output> {
+        let grace = self.grace_period();
+        let cutoff = async {
+            self.cancelled().await;
+            tokio::time::sleep(grace).await;
+        };
+        tokio::select! {
+            biased;
+            output = work.into_future() => Some(output),
+            () = cutoff => None,
+        }
+    }
+}
+
+/// The process-wide shutdown coordinator.
+pub fn global() -> &'static Shutdown {
+    SHUTDOWN.get_or_init(Shutdown::default)
+}
+
+/// A flush step run after draining, named for log messages.
+pub type FlushStep<'a> = (
+    &'static str,
+    Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'a>>,
+);
+
+/// Run flush steps in order, logging (not propagating) failures so one
+/// broken sink does not prevent the others from being persisted.
+pub async fn flush_all(steps: Vec<FlushStep<'_>>) {
+    for (name, step) in steps {
+        match step.await {
+            Ok(()) => tracing::debug!("Flushed {name}"),
+            Err(e) => tracing::warn!("Failed to flush {name} during shutdown: {e:#}"),
+        }
+    }
+}
+
+/// Trap SIGINT/SIGTERM: the first triggers [`global`] shutdown with a grace
+/// period of `grace_secs`, a second exits immediately. Only the first call
+/// installs handlers.
+pub fn install_signal_handlers(grace_secs: u64) {
+    static INSTALLED: AtomicBool = AtomicBool::new(false);
+    if INSTALLED.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    global().set_grace_period(Duration::from_secs(grace_secs));
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        tracing::info!(
+            "Shutdown requested; draining in-flight work for up to {grace_secs}s (signal again to force)"
+        );
+        global().trigger();
+        wait_for_signal().await;
+        tracing::warn!("Second shutdown signal; exiting immediately");
+        std::process::exit(FORCED_EXIT_CODE);
+    });
+}
+
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to install SIGTERM handler: {e}");
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn begin_work_is_refused_after_trigger() {
+        let shutdown = Shutdown::new(Duration::from_secs(1));
+        let guard = shutdown.begin_work();
+        assert!(guard.is_some());
+        assert_eq!(shutdown.in_flight(), 1);
+
+        shutdown.trigger();
+        assert!(shutdown.begin_work().is_none());
+        assert_eq!(shutdown.in_flight(), 1);
+        drop(guard);
+        assert_eq!(shutdown.in_flight(), 0);
+        assert!(shutdown.drain().await);
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_in_flight_work() {
+        let shutdown = Arc::new(Shutdown::new(Duration::from_secs(5)));
+        let worker = {
+            let shutdown = Arc::clone(&shutdown);
+            tokio::spawn(async move {
+                let _guard = shutdown.begin_work();
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        shutdown.trigger();
+        assert!(shutdown.drain().await);
+        worker.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn grace_period_bounds_drain_and_run_with_grace() {
+        let shutdown = Shutdown::new(Duration::ZERO);
+        let _stuck = shutdown.begin_work();
+        shutdown.trigger();
+        assert!(!shutdown.drain().await);
+
+        let finished = shutdown.run_with_grace(std::future::pending::<()>()).await;
+        assert!(finished.is_none());
+        assert_eq!(shutdown.run_with_grace(async { 7 }).await, Some(7));
+    }
+}
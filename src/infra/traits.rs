@@ -7,6 +7,7 @@ use std::time::Duration;
 pub enum DaemonStatus {
     Running,
     Stopped,
+    NotInstalled,
     Unknown,
 }
 
@@ -20,6 +21,10 @@ pub enum DaemonPlatform {
 
 #[async_trait]
 pub trait Daemon: Send + Sync {
+    /// Register the service with the OS so it starts on boot.
+    async fn install(&self) -> anyhow::Result<()>;
+    /// Stop the service and remove its registration.
+    async fn uninstall(&self) -> anyhow::Result<()>;
     async fn start(&self) -> anyhow::Result<()>;
     async fn stop(&self) -> anyhow::Result<()>;
     async fn status(&self) -> anyhow::Result<DaemonStatus>;
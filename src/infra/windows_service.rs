@@ -0,0 +1,349 @@
+//! Windows Service support for `zeroclaw daemon`.
+//!
+//! `zeroclaw daemon install` registers an auto-start service (run as
+//! LocalSystem) that executes `zeroclaw --config-dir <dir> daemon run`. When
+//! the Service Control Manager launches that command, [`run`] hands the
+//! process to the service dispatcher: the runtime runs on the caller's Tokio
+//! runtime, a stop or shutdown request triggers the graceful
+//! [`shutdown`](super::shutdown) path, and start, stop and failures are
+//! written to the Application event log under the `ZeroClaw` source. Run
+//! from a console instead, `daemon run` stays in the foreground.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::ffi::OsString;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use windows_service::service::{
+    ServiceAccess, ServiceAction, ServiceActionType, ServiceControl, ServiceControlAccept,
+    ServiceErrorControl, ServiceExitCode, ServiceFailureActions, ServiceFailureResetPeriod,
+    ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+};
+use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+use windows_service::{define_windows_service, service_dispatcher};
+use windows_sys::Win32::Foundation::{
+    ERROR_FAILED_SERVICE_CONTROLLER_CONNECT, ERROR_SERVICE_DOES_NOT_EXIST,
+};
+use windows_sys::Win32::System::EventLog::{
+    DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+    EVENTLOG_INFORMATION_TYPE, REPORT_EVENT_TYPE,
+};
+
+use super::daemon::{ServiceSpec, RESTART_DELAY_SECS};
+use super::traits::{Daemon, DaemonPlatform, DaemonStatus};
+use crate::config::Config;
+
+/// Service name, also the event log source.
+pub const SERVICE_NAME: &str = "ZeroClaw";
+const SERVICE_DISPLAY_NAME: &str = "ZeroClaw daemon";
+const SERVICE_DESCRIPTION: &str = "ZeroClaw gateway, channels and scheduled tasks";
+const SERVICE_TYPE: ServiceType = ServiceType::OWN_PROCESS;
+/// Failure count resets after a day without crashes.
+const FAILURE_RESET_SECS: u64 = 24 * 60 * 60;
+
+/// Registered Windows Service (`sc.exe query ZeroClaw`).
+pub struct WindowsServiceDaemon {
+    spec: ServiceSpec,
+}
+
+impl WindowsServiceDaemon {
+    pub fn new(spec: ServiceSpec) -> Self {
+        Self { spec }
+    }
+}
+
+fn manager(access: ServiceManagerAccess) -> Result<ServiceManager> {
+    ServiceManager::local_computer(None::<&str>, access)
+        .context("Failed to connect to the Service Control Manager (run as Administrator)")
+}
+
+fn is_missing(error: &windows_service::Error) -> bool {
+    matches!(error, windows_service::Error::Winapi(e)
+        if e.raw_os_error() == Some(ERROR_SERVICE_DOES_NOT_EXIST as i32))
+}
+
+/// Map the SCM's reported state to a status.
+fn daemon_status(state: ServiceState) -> DaemonStatus {
+    match state {
+        ServiceState::Running | ServiceState::StartPending | ServiceState::ContinuePending => {
+            DaemonStatus::Running
+        }
+        ServiceState::Stopped | ServiceState::StopPending => DaemonStatus::Stopped,
+        ServiceState::Paused | ServiceState::PausePending => DaemonStatus::Unknown,
+    }
+}
+
+#[async_trait]
+impl Daemon for WindowsServiceDaemon {
+    async fn install(&self) -> anyhow::Result<()> {
+        std::fs::create_dir_all(&self.spec.log_dir)
+            .with_context(|| format!("Failed to create {}", self.spec.log_dir.display()))?;
+        let manager =
+            manager(ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+        let info = ServiceInfo {
+            name: SERVICE_NAME.into(),
+            display_name: SERVICE_DISPLAY_NAME.into(),
+            service_type: SERVICE_TYPE,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: self.spec.executable.clone(),
+            launch_arguments: self
+                .spec
+                .args()
+                .into_iter()
+                .skip(1)
+                .map(OsString::from)
+                .collect(),
+            dependencies: Vec::new(),
+            account_name: None,
+            account_password: None,
+        };
+        let access = ServiceAccess::CHANGE_CONFIG | ServiceAccess::START;
+        // Reinstalling updates the existing registration in place.
+        let service = match manager.create_service(&info, access) {
+            Ok(service) => service,
+            Err(_) => {
+                let service = manager
+                    .open_service(SERVICE_NAME, access)
+                    .context("Failed to create the ZeroClaw service")?;
+                service
+                    .change_config(&info)
+                    .context("Failed to update the ZeroClaw service")?;
+                service
+            }
+        };
+        service.set_description(SERVICE_DESCRIPTION)?;
+        let restart = ServiceAction {
+            action_type: ServiceActionType::Restart,
+            delay: Duration::from_secs(u64::from(RESTART_DELAY_SECS)),
+        };
+        service.update_failure_actions(ServiceFailureActions {
+            reset_period: ServiceFailureResetPeriod::After(Duration::from_secs(FAILURE_RESET_SECS)),
+            reboot_msg: None,
+            command: None,
+            actions: Some(vec![restart.clone(), restart.clone(), restart]),
+        })?;
+        // Also restart when `daemon run` exits with an error, not only on crashes.
+        service.set_failure_actions_on_non_crash_failures(true)?;
+        Ok(())
+    }
+
+    async fn uninstall(&self) -> anyhow::Result<()> {
+        let manager = manager(ServiceManagerAccess::CONNECT)?;
+        let access = ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE;
+        let service = match manager.open_service(SERVICE_NAME, access) {
+            Ok(service) => service,
+            Err(e) if is_missing(&e) => return Ok(()),
+            Err(e) => return Err(e).context("Failed to open the ZeroClaw service"),
+        };
+        // Deletion completes once the service has stopped.
+        service
+            .delete()
+            .context("Failed to delete the ZeroClaw service")?;
+        if service.query_status()?.current_state != ServiceState::Stopped {
+            let _ = service.stop();
+        }
+        Ok(())
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        let service = manager(ServiceManagerAccess::CONNECT)?
+            .open_service(SERVICE_NAME, ServiceAccess::START)
+            .context("Service is not installed; run `zeroclaw daemon install` first")?;
+        service
+            .start::<&str>(&[])
+            .context("Failed to start the ZeroClaw service")
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        let service = manager(ServiceManagerAccess::CONNECT)?
+            .open_service(SERVICE_NAME, ServiceAccess::STOP)
+            .context("Failed to open the ZeroClaw service")?;
+        service
+            .stop()
+            .context("Failed to stop the ZeroClaw service")?;
+        Ok(())
+    }
+
+    async fn status(&self) -> anyhow::Result<DaemonStatus> {
+        let manager = manager(ServiceManagerAccess::CONNECT)?;
+        let service = match manager.open_service(SERVICE_NAME, ServiceAccess::QUERY_STATUS) {
+            Ok(service) => service,
+            Err(e) if is_missing(&e) => return Ok(DaemonStatus::NotInstalled),
+            Err(e) => return Err(e).context("Failed to open the ZeroClaw service"),
+        };
+        Ok(daemon_status(service.query_status()?.current_state))
+    }
+
+    fn platform(&self) -> DaemonPlatform {
+        DaemonPlatform::WindowsService
+    }
+
+    fn name(&self) -> &str {
+        "windows-service"
+    }
+}
+
+// ── Service process ─────────────────────────────────────────────
+
+/// What the SCM's service thread needs from `daemon run`.
+struct ServiceContext {
+    runtime: tokio::runtime::Handle,
+    config: Config,
+    outcome: Mutex<Option<Result<()>>>,
+}
+
+static SERVICE_CONTEXT: OnceLock<ServiceContext> = OnceLock::new();
+
+define_windows_service!(ffi_service_main, service_main);
+
+/// `zeroclaw daemon run`: run as the service when the SCM started the
+/// process, otherwise in the foreground.
+pub async fn run(config: Config) -> Result<()> {
+    let context = ServiceContext {
+        runtime: tokio::runtime::Handle::current(),
+        config: config.clone(),
+        outcome: Mutex::new(None),
+    };
+    if SERVICE_CONTEXT.set(context).is_err() {
+        anyhow::bail!("daemon run was already started in this process");
+    }
+    let dispatched =
+        tokio::task::spawn_blocking(|| service_dispatcher::start(SERVICE_NAME, ffi_service_main))
+            .await?;
+    match dispatched {
+        Ok(()) => SERVICE_CONTEXT
+            .get()
+            .and_then(|context| context.outcome.lock().ok()?.take())
+            .unwrap_or(Ok(())),
+        Err(windows_service::Error::Winapi(e))
+            if e.raw_os_error() == Some(ERROR_FAILED_SERVICE_CONTROLLER_CONNECT as i32) =>
+        {
+            Box::pin(super::daemon::run_foreground(config)).await
+        }
+        Err(e) => Err(e).context("Failed to start the Windows service dispatcher"),
+    }
+}
+
+fn service_main(_arguments: Vec<OsString>) {
+    let Some(context) = SERVICE_CONTEXT.get() else {
+        return;
+    };
+    let outcome = run_service(context);
+    if let Err(e) = &outcome {
+        report_event(
+            EVENTLOG_ERROR_TYPE,
+            &format!("ZeroClaw service failed: {e:#}"),
+        );
+    }
+    if let Ok(mut slot) = context.outcome.lock() {
+        *slot = Some(outcome);
+    }
+}
+
+/// Status reported to the SCM; only a running service accepts controls.
+fn reported_status(state: ServiceState, exit_code: ServiceExitCode) -> ServiceStatus {
+    ServiceStatus {
+        service_type: SERVICE_TYPE,
+        current_state: state,
+        controls_accepted: if state == ServiceState::Running {
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+        } else {
+            ServiceControlAccept::empty()
+        },
+        exit_code,
+        checkpoint: 0,
+        wait_hint: Duration::ZERO,
+        process_id: None,
+    }
+}
+
+fn run_service(context: &ServiceContext) -> Result<()> {
+    let handler = |control| match control {
+        ServiceControl::Stop | ServiceControl::Shutdown => {
+            super::shutdown::global().trigger();
+            ServiceControlHandlerResult::NoError
+        }
+        ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+        _ => ServiceControlHandlerResult::NotImplemented,
+    };
+    let handle = service_control_handler::register(SERVICE_NAME, handler)
+        .context("Failed to register the service control handler")?;
+    handle.set_service_status(reported_status(
+        ServiceState::Running,
+        ServiceExitCode::Win32(0),
+    ))?;
+    report_event(EVENTLOG_INFORMATION_TYPE, "ZeroClaw service started");
+
+    let outcome = context
+        .runtime
+        .block_on(Box::pin(super::daemon::run_foreground(
+            context.config.clone(),
+        )));
+
+    // A non-zero exit code makes the SCM apply the restart failure actions.
+    let exit_code = match outcome {
+        Ok(()) => ServiceExitCode::Win32(0),
+        Err(_) => ServiceExitCode::ServiceSpecific(1),
+    };
+    handle.set_service_status(reported_status(ServiceState::Stopped, exit_code))?;
+    if outcome.is_ok() {
+        report_event(EVENTLOG_INFORMATION_TYPE, "ZeroClaw service stopped");
+    }
+    outcome
+}
+
+/// Write `message` to the Application event log. Failures are ignored: the
+/// event log is a convenience, not a requirement for running.
+fn report_event(kind: REPORT_EVENT_TYPE, message: &str) {
+    let source: Vec<u16> = SERVICE_NAME.encode_utf16().chain([0]).collect();
+    let text: Vec<u16> = message.encode_utf16().chain([0]).collect();
+    let strings = [text.as_ptr()];
+    // SAFETY: both strings are NUL-terminated UTF-16 buffers that outlive the
+    // calls, and the event source handle is released before returning.
+    unsafe {
+        let handle = RegisterEventSourceW(std::ptr::null(), source.as_ptr());
+        if handle.is_null() {
+            return;
+        }
+        ReportEventW(
+            handle,
+            kind,
+            0,
+            0,
+            std::ptr::null_mut(),
+            1,
+            0,
+            strings.as_ptr(),
+            std::ptr::null(),
+        );
+        DeregisterEventSource(handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scm_states_map_to_daemon_status() {
+        assert_eq!(daemon_status(ServiceState::Running), DaemonStatus::Running);
+        assert_eq!(
+            daemon_status(ServiceState::StartPending),
+            DaemonStatus::Running
+        );
+        assert_eq!(daemon_status(ServiceState::Stopped), DaemonStatus::Stopped);
+        assert_eq!(daemon_status(ServiceState::Paused), DaemonStatus::Unknown);
+    }
+
+    #[test]
+    fn only_a_running_service_accepts_stop() {
+        let running = reported_status(ServiceState::Running, ServiceExitCode::Win32(0));
+        assert!(running
+            .controls_accepted
+            .contains(ServiceControlAccept::STOP));
+        let stopped = reported_status(ServiceState::Stopped, ServiceExitCode::ServiceSpecific(1));
+        assert!(stopped.controls_accepted.is_empty());
+    }
+}
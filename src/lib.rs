@@ -42,6 +42,8 @@ pub mod agent;
 pub mod auto_reply;
 pub mod channels;
 pub mod config;
+pub mod doctor;
+pub mod feeds;
 pub mod gateway;
 pub(crate) mod health;
 pub(crate) mod identity;
@@ -53,10 +55,12 @@ pub mod plugins;
 pub mod providers;
 pub mod routing;
 pub mod runtime;
+pub mod scheduler;
 pub(crate) mod security;
 pub mod sessions;
 pub mod skills;
 pub mod tools;
+pub mod tunnel;
 pub(crate) mod util;
 
 pub use config::Config;
@@ -74,19 +78,25 @@ pub enum ChannelCommands {
     #[command(long_about = "\
 Add a new channel configuration.
 
-Provide the channel type and a JSON object with the required \
-configuration keys for that channel type.
+Provide the channel type and a JSON object with the configuration keys \
+for that channel type (the fields of [channels_config.<type>]). Unknown \
+keys, missing required fields and malformed credentials (token shapes, \
+E.164 numbers, owner/name repositories) are all reported before anything \
+is saved. --verify also checks the credentials against the live API.
 
-Supported types: telegram, discord, slack, whatsapp, matrix, imessage, email.
+Supported types: whatsapp, github.
 
 Examples:
-  zeroclaw channel add telegram '{\"bot_token\":\"...\",\"name\":\"my-bot\"}'
-  zeroclaw channel add discord '{\"bot_token\":\"...\",\"name\":\"my-discord\"}'")]
+  zeroclaw channel add whatsapp '{\"phone_number_id\":\"1234567890\",\"access_token\":\"EAA...\",\"verify_token\":\"...\",\"allowed_numbers\":[\"+15551234567\"]}'
+  zeroclaw channel add github '{\"access_token\":\"ghp_...\",\"webhook_secret\":\"...\",\"allowed_repos\":[\"acme/api\"]}' --verify")]
     Add {
-        /// Channel type (telegram, discord, slack, whatsapp, matrix, imessage, email)
+        /// Channel type (whatsapp, github)
         channel_type: String,
-        /// Optional configuration as JSON
+        /// Channel configuration as JSON
         config: String,
+        /// Check the credentials against the live API before saving
+        #[arg(long)]
+        verify: bool,
     },
     /// Remove a channel configuration
     Remove {
@@ -147,5 +157,153 @@ pub enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Encrypt stored memory content and enable `[memory] encrypt`
+    #[command(long_about = "\
+Encrypt stored memory content and enable `[memory] encrypt`.
+
+Seals the content of every entry in memory/brain.db with the secret \
+store key (.secret_key next to config.toml), rebuilds the search index \
+and vacuums the database. New entries are written encrypted from then \
+on. Keys, categories, timestamps and embeddings are not encrypted.
+
+Back up .secret_key: without it encrypted memories cannot be read.
+
+Examples:
+  zeroclaw memory encrypt
+  zeroclaw memory decrypt")]
+    Encrypt,
+    /// Decrypt stored memory content and disable `[memory] encrypt`
+    Decrypt,
+}
+
+
+/// Skill pack management subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SkillCommands {
+    /// List skills in the workspace skills/ directory
+    List,
+    /// Install a skill pack from a git URL or local path
+    #[command(long_about = "\
+Install a skill pack from a git URL or local path.
+
+A pack is a Markdown file, or a directory of Markdown files, whose \
+frontmatter declares a `name` and `description`. Skills are copied \
+into the workspace skills/ directory and picked up on the next prompt build.
+
+Examples:
+  zeroclaw skill install https://github.com/acme/ops-skills.git
+  zeroclaw skill install ./my-skills
+  zeroclaw skill install ./triage.md")]
+    Install {
+        /// Git URL, pack directory, or single skill file
+        source: String,
+    },
+    /// Re-fetch installed packs from their original source
+    Update {
+        /// Pack to update (all packs when omitted)
+        name: Option<String>,
+    },
+    /// Remove an installed pack, or a standalone skill by name
+    Remove {
+        /// Pack or skill name
+        name: String,
+    },
+}
+
+/// Scheduled task subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScheduleCommands {
+    /// List scheduled tasks with their next run time
+    List,
+    /// Add a recurring agent task to config.toml
+    #[command(long_about = "\
+Add a recurring agent task to config.toml.
+
+The cron expression has five fields (minute hour day month weekday) \
+evaluated in local time; @hourly, @daily, @weekly and @monthly also work. \
+Tasks run while `zeroclaw channel start` is running.
+
+Examples:
+  zeroclaw schedule add \"0 9 * * *\" \"Summarize my inbox\"
+  zeroclaw schedule add \"30 8 * * mon-fri\" \"Today's agenda\" --name agenda --channel whatsapp --to +15551234567")]
+    Add {
+        /// Cron expression, e.g. \"0 9 * * *\"
+        cron: String,
+        /// Prompt sent to the agent on each run
+        prompt: String,
+        /// Task name (defaults to task-<n>)
+        #[arg(long)]
+        name: Option<String>,
+        /// Channel to deliver results to (e.g. whatsapp)
+        #[arg(long, requires = "to")]
+        channel: Option<String>,
+        /// Recipient on the delivery channel
+        #[arg(long, requires = "channel")]
+        to: Option<String>,
+    },
+    /// Remove a scheduled task
+    Remove {
+        /// Task name
+        name: String,
+    },
+    /// Run a task immediately and deliver its result
+    RunNow {
+        /// Task name
+        name: String,
+    },
+}
+
+/// Route binding subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RouteCommands {
+    /// List route bindings in the order they are matched
+    List,
+    /// Bind a channel conversation pattern to an agent in config.toml
+    #[command(long_about = "\
+Bind a channel conversation pattern to an agent in config.toml.
+
+The pattern is matched against the sender, account or guild ID of \
+incoming messages on the channel; \"*\" matches every conversation. \
+Bindings with lower priority values are tried first.
+
+Examples:
+  zeroclaw route add telegram research
+  zeroclaw route add telegram support --pattern alice --priority 5 --id alice-support")]
+    Add {
+        /// Channel name (e.g. telegram)
+        channel: String,
+        /// Agent ID ("default" or an [[agents]] id)
+        agent: String,
+        /// Sender, account or guild ID to match
+        #[arg(long, default_value = "*")]
+        pattern: String,
+        /// Lower values are matched first
+        #[arg(long, default_value_t = 0)]
+        priority: u32,
+        /// Binding ID (defaults to route-<n>)
+        #[arg(long)]
+        id: Option<String>,
+    },
+    /// Remove a route binding
+    Remove {
+        /// Binding ID
+        id: String,
+    },
 }
 
+/// Background service subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DaemonCommands {
+    /// Install the OS service (systemd user unit or launchd agent) and enable it at boot
+    Install,
+    /// Stop the service and remove it
+    Uninstall,
+    /// Start the installed service
+    Start,
+    /// Stop the running service
+    Stop,
+    /// Show whether the service is installed and running
+    Status,
+    /// Run gateway and channels in the foreground (what the service executes)
+    Run,
+}
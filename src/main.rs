@@ -36,7 +36,6 @@ use anyhow::{bail, Result};
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use std::io::Write;
 use tracing::info;
-use tracing_subscriber::{fmt, EnvFilter};
 
 fn parse_temperature(s: &str) -> std::result::Result<f64, String> {
     let t: f64 = s.parse().map_err(|e| format!("{e}"))?;
@@ -46,24 +45,46 @@ fn parse_temperature(s: &str) -> std::result::Result<f64, String> {
     Ok(t)
 }
 
+fn parse_export_format(s: &str) -> std::result::Result<sessions::ExportFormat, String> {
+    sessions::ExportFormat::parse(s).ok_or_else(|| "format must be markdown or html".to_string())
+}
+
+fn parse_audit_severity(s: &str) -> std::result::Result<security::AuditSeverity, String> {
+    security::AuditSeverity::parse(s)
+        .ok_or_else(|| "severity must be info, warning, or critical".to_string())
+}
+
 mod agent;
+mod auto_reply;
 mod channels;
 mod config;
+mod doctor;
+mod feeds;
 mod gateway;
 mod health;
 mod identity;
+mod infra;
+mod media;
 mod memory;
 mod observability;
+mod plugins;
 mod providers;
+mod routing;
 mod runtime;
+mod scheduler;
 mod security;
+mod sessions;
+mod skills;
 mod tools;
+mod tunnel;
 mod util;
 
 use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
-pub use zeroclaw::ChannelCommands;
+pub use zeroclaw::{
+    ChannelCommands, DaemonCommands, RouteCommands, ScheduleCommands, SkillCommands,
+};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 enum CompletionShell {
@@ -100,12 +121,21 @@ enum Commands {
 Start the AI agent loop.
 
 Launches an interactive chat session with the configured AI provider. \
-Use --message for single-shot queries without entering interactive mode.
+Use --message for single-shot queries without entering interactive mode. \
+Use --agent to run as one of the [[agents]] defined in config, and \
+--autonomy to pick a named autonomy profile ([autonomy.profiles.<name>] \
+or a built-in preset: read_only, coding, assistant). \
+Conversations are saved to <workspace>/state/sessions.db; use --resume \
+or --session to continue one.
 
 Examples:
   zeroclaw agent                              # interactive session
   zeroclaw agent -m \"Summarize today's logs\"  # single message
-  zeroclaw agent -p anthropic --model claude-sonnet-4-20250514")]
+  zeroclaw agent -p anthropic --model claude-sonnet-4-20250514
+  zeroclaw agent --agent research -m \"Compare tokio and async-std\"
+  zeroclaw agent --autonomy coding            # coding profile for this run
+  zeroclaw agent --resume                     # continue the last session
+  zeroclaw agent --session research           # continue or start a named session")]
     Agent {
         /// Single message mode (don't enter interactive mode)
         #[arg(short, long)]
@@ -122,6 +152,28 @@ Examples:
         /// Temperature (0.0 - 2.0)
         #[arg(short, long, default_value = "0.7", value_parser = parse_temperature)]
         temperature: f64,
+
+        /// Agent ID from [[agents]] in config (provider, model, prompt, tools, memory)
+        #[arg(long = "agent", value_name = "ID")]
+        agent_id: Option<String>,
+
+        /// Autonomy profile from [autonomy.profiles] or a built-in preset
+        /// (read_only, coding, assistant); overrides the agent's profile
+        #[arg(long, value_name = "PROFILE")]
+        autonomy: Option<String>,
+
+        /// Plan mode: list the tool calls the agent would make without running
+        /// state-changing tools, then ask before executing the plan
+        #[arg(long)]
+        plan: bool,
+
+        /// Continue the most recent conversation
+        #[arg(long, conflicts_with = "session")]
+        resume: bool,
+
+        /// Continue (or start) the named conversation
+        #[arg(long, value_name = "NAME")]
+        session: Option<String>,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -147,11 +199,98 @@ Examples:
         host: Option<String>,
     },
 
+    /// Pair a new device with the running gateway
+    #[command(long_about = "\
+Pair a new device with the running gateway.
+
+Opens a short-lived pairing window on the local gateway and prints a \
+one-time code plus a QR code holding the gateway URL and code. The \
+device exchanges the code for a bearer token via POST /pair; the token \
+is saved (hashed, with label and creation time) to gateway.paired_tokens.
+
+Examples:
+  zeroclaw pair
+  zeroclaw pair --label phone
+  zeroclaw pair --label laptop --ttl 300
+  zeroclaw pair --url https://claw.example.com   # URL shown in the QR code")]
+    Pair {
+        /// Label stored with the issued token (e.g. phone, laptop)
+        #[arg(long)]
+        label: Option<String>,
+
+        /// How long the pairing window stays open, in seconds
+        #[arg(long, default_value_t = gateway::PAIR_WINDOW_DEFAULT_TTL_SECS)]
+        ttl: u64,
+
+        /// Gateway port; defaults to config gateway.port
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Public gateway URL to encode in the QR code (e.g. a tunnel URL)
+        #[arg(long)]
+        url: Option<String>,
+    },
+
     /// Show system status (full details)
     Status,
 
-    /// List supported AI providers
-    Providers,
+    /// Diagnose config, provider, memory, disk, clock and workspace health
+    #[command(long_about = "\
+Diagnose config, provider, memory, disk, clock and workspace health.
+
+Checks that the config loads and validates, the default provider answers a \
+cheap authenticated model list call, the memory database passes \
+PRAGMA integrity_check, the workspace volume has free space, the local \
+clock agrees with the provider's Date header, and the workspace is \
+writable. Exits non-zero when any check fails.
+
+Examples:
+  zeroclaw doctor
+  zeroclaw doctor --json")]
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List supported AI providers, or test the configured ones
+    #[command(long_about = "\
+List supported AI providers, or test the configured ones.
+
+`providers test` sends a minimal prompt to each configured provider/model \
+(the default and every [[agents]] override) and reports latency, time to \
+first streamed token, whether the model answers a native tool call, and \
+the estimated cost where the provider publishes pricing. Exits non-zero \
+when any request fails.
+
+Examples:
+  zeroclaw providers
+  zeroclaw providers test
+  zeroclaw providers test --provider azure-openai --model gpt-4o-mini
+  zeroclaw providers test --json")]
+    Providers {
+        #[command(subcommand)]
+        providers_command: Option<ProviderCommands>,
+    },
+
+    /// List the models a provider serves
+    #[command(long_about = "\
+List the models a provider serves.
+
+Queries the provider's model listing endpoint (OpenAI-compatible /v1/models, \
+the OpenRouter catalog, or Ollama's local tags) and prints each model's \
+context window, price per million input/output tokens, and whether it \
+supports tool calling, where the provider reports them. `refresh` also \
+caches the model ids for the in-chat /models command.
+
+Examples:
+  zeroclaw models list
+  zeroclaw models list --provider openrouter --json
+  zeroclaw models refresh --provider ollama")]
+    Models {
+        #[command(subcommand)]
+        models_command: ModelCommands,
+    },
 
     /// Manage channels (telegram, discord, slack)
     #[command(long_about = "\
@@ -191,22 +330,361 @@ Examples:
         memory_command: MemoryCommands,
     },
 
+    /// Ingest a document into memory for grounded answers
+    #[command(long_about = "\
+Ingest a document into memory for grounded answers.
+
+Parses a local file or https:// URL (text, Markdown, DOCX, XLSX; PDF with \
+the rag-pdf build feature), splits it into [memory].chunk_max_tokens \
+chunks and stores them in the 'documents' memory category with their \
+source. Ingesting the same source again replaces its previous chunks.
+
+Examples:
+  zeroclaw ingest ./handbook.md
+  zeroclaw ingest https://example.com/pricing.xlsx")]
+    Ingest {
+        /// File path or https:// URL of the document
+        source: String,
+    },
+
+    /// Manage skill packs (install, list, update, remove)
+    #[command(long_about = "\
+Manage skill packs.
+
+Skills are Markdown files with `name`/`description` frontmatter in the \
+workspace skills/ directory. Install packs from a git URL or local path, \
+update them from their recorded source, or remove them as a unit.
+
+Examples:
+  zeroclaw skill list
+  zeroclaw skill install https://github.com/acme/ops-skills.git
+  zeroclaw skill update
+  zeroclaw skill remove ops-skills")]
+    #[command(alias = "skills")]
+    Skill {
+        #[command(subcommand)]
+        skill_command: SkillCommands,
+    },
+
+    /// Manage scheduled agent tasks (list, add, remove, run-now)
+    #[command(long_about = "\
+Manage scheduled agent tasks.
+
+Tasks live in config.toml under [[schedule.tasks]]. Each fires on a \
+cron expression while `zeroclaw channel start` is running, sends its \
+prompt through the agent, and delivers the reply to the configured \
+channel and recipient.
+
+Examples:
+  zeroclaw schedule list
+  zeroclaw schedule add \"0 9 * * *\" \"Summarize my inbox\" --name inbox
+  zeroclaw schedule run-now inbox
+  zeroclaw schedule remove inbox")]
+    Schedule {
+        #[command(subcommand)]
+        schedule_command: ScheduleCommands,
+    },
+
+    /// Manage route bindings from channel conversations to agents (list, add, remove)
+    #[command(long_about = "\
+Manage route bindings from channel conversations to agents.
+
+Bindings live in config.toml under [[routes]] and are loaded together with \
+[[agents.routes]] when channels and the gateway start. Run with \
+RUST_LOG=zeroclaw=debug to log which binding matched each message.
+
+Examples:
+  zeroclaw route list
+  zeroclaw route add telegram research --pattern alice --priority 5
+  zeroclaw route remove route-1")]
+    Route {
+        #[command(subcommand)]
+        route_command: RouteCommands,
+    },
+
+    /// Manage the background service (install, uninstall, start, stop, status)
+    #[command(long_about = "\
+Manage the ZeroClaw background service.
+
+'install' writes a systemd user unit on Linux \
+(~/.config/systemd/user/zeroclaw.service) or a launchd agent on macOS \
+(~/Library/LaunchAgents/com.zeroclaw.daemon.plist) that runs the gateway \
+and all configured channels at boot, restarts them on failure, and appends \
+output to daemon.log and daemon.err.log in the config directory's logs/ folder.
+
+'run' starts the same runtime in the foreground.
+
+Examples:
+  zeroclaw daemon install
+  zeroclaw daemon start
+  zeroclaw daemon status
+  zeroclaw daemon uninstall
+  zeroclaw daemon run")]
+    Daemon {
+        #[command(subcommand)]
+        daemon_command: DaemonCommands,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
 
 Inspect and export configuration settings. Use 'schema' to dump \
 the full JSON Schema for the config file, which documents every \
-available key, type, and default value.
+available key, type, and default value. Use 'get', 'set', and 'unset' \
+with dot-separated keys to read or edit individual values; edits are \
+validated before config.toml is written and secrets are encrypted.
 
 Examples:
   zeroclaw config schema              # print JSON Schema to stdout
-  zeroclaw config schema > schema.json")]
+  zeroclaw config schema > schema.json
+  zeroclaw config get memory.backend
+  zeroclaw config set autonomy.level full
+  zeroclaw config unset api_url")]
     Config {
         #[command(subcommand)]
         config_command: ConfigCommands,
     },
 
+    /// Export gateway session transcripts
+    #[command(long_about = "\
+Export gateway session transcripts.
+
+Downloads a session recorded by the running gateway (see GET /api/sessions) \
+as Markdown or a standalone HTML page, including messages, tool calls and \
+timestamps. Pass --token when the gateway requires pairing.
+
+Examples:
+  zeroclaw session export gateway:webhook:127.0.0.1
+  zeroclaw session export gateway:webhook:127.0.0.1 --format html -o run.html")]
+    Session {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Inspect the assembled system prompt
+    #[command(long_about = "\
+Inspect the assembled system prompt.
+
+The prompt merges workspace files in a fixed order: AGENTS.md, SOUL.md, \
+TOOLS.md, IDENTITY.md, INSTRUCTIONS.md, CONTEXT.md, USER.md, BOOTSTRAP.md \
+and MEMORY.md. Files under prompts/<channel>/ replace IDENTITY.md, \
+INSTRUCTIONS.md or CONTEXT.md for that channel.
+
+Examples:
+  zeroclaw prompt show
+  zeroclaw prompt show --channel telegram")]
+    Prompt {
+        #[command(subcommand)]
+        prompt_command: PromptCommands,
+    },
+
+    /// Review or roll back tool actions interrupted by a crash
+    #[command(long_about = "\
+Review or roll back tool actions interrupted by a crash.
+
+Side-effecting tool calls (shell, process, file_write, memory_store, git) are \
+journaled to state/journal.jsonl before they run. An entry without a \
+completion record means the process stopped mid-action. File writes keep a \
+backup of the previous contents and can be rolled back; other actions can \
+only be reviewed and cleared.
+
+Examples:
+  zeroclaw journal status
+  zeroclaw journal rollback
+  zeroclaw journal clear 3f2a9c1e")]
+    Journal {
+        #[command(subcommand)]
+        journal_command: JournalCommands,
+    },
+
+    /// Undo recent file writes made by the agent
+    #[command(long_about = "\
+Undo recent file writes made by the agent.
+
+Before file_write replaces a file, the previous contents are saved to \
+.zeroclaw/backups/ in the workspace (see [tools.file_backups]). \
+`undo-last` reverts the newest writes first: backed-up files are \
+restored and files the write created are removed.
+
+Examples:
+  zeroclaw workspace backups
+  zeroclaw workspace undo-last
+  zeroclaw workspace undo-last --count 3")]
+    Workspace {
+        #[command(subcommand)]
+        workspace_command: WorkspaceCommands,
+    },
+
+    /// Snapshot the whole workspace and roll it back
+    #[command(long_about = "\
+Snapshot the whole workspace and roll it back.
+
+In a git repository a checkpoint is a commit of the working tree stored \
+under refs/zeroclaw/checkpoints/ (HEAD, branches and the index are not \
+touched); other workspaces are zipped into .zeroclaw/checkpoints/. Runtime \
+data (memory/, media/, state/, .zeroclaw/) is not included.
+
+`restore` brings files back to their checkpointed contents and removes \
+files created since. The current state is checkpointed first, so a restore \
+can itself be undone. Set [agent] auto_checkpoint = true to checkpoint \
+before every `zeroclaw agent -m` run and scheduled task.
+
+Examples:
+  zeroclaw checkpoint create \"before refactor\"
+  zeroclaw checkpoint list
+  zeroclaw checkpoint restore 3f2a9c1e")]
+    Checkpoint {
+        #[command(subcommand)]
+        checkpoint_command: CheckpointCommands,
+    },
+
+    /// List or cancel reminders set through the reminder tool
+    #[command(long_about = "\
+List or cancel reminders set through the reminder tool.
+
+Reminders are stored in state/reminders.db and delivered to the chat they \
+were set from while `zeroclaw daemon` or `zeroclaw channel start` is \
+running. Ids are shown by `list` and may be abbreviated to a unique prefix.
+
+Examples:
+  zeroclaw reminders list
+  zeroclaw reminders list --all
+  zeroclaw reminders cancel 3f2a9c1e")]
+    Reminders {
+        #[command(subcommand)]
+        reminders_command: RemindersCommands,
+    },
+
+    /// Link one person's accounts across channels
+    #[command(long_about = "\
+Link one person's accounts across channels.
+
+Accounts are <channel>:<sender id> pairs. Accounts linked to the same \
+identity share /pin preferences and auto-saved memories. Users can also \
+link their own accounts from chat: /link in a direct message returns a \
+code, and /link <code> from the other account completes the link.
+
+Examples:
+  zeroclaw identity list
+  zeroclaw identity link alice telegram:123456789
+  zeroclaw identity link alice discord:alice#1234
+  zeroclaw identity unlink discord:alice#1234")]
+    Identity {
+        #[command(subcommand)]
+        identity_command: IdentityCommands,
+    },
+
+    /// Show recent lines from the [logging] log file
+    #[command(long_about = "\
+Show recent lines from the [logging] log file.
+
+Reads the configured log file and its rotations (<file>.N … <file>.1) \
+oldest first and prints the most recent matching lines. Works with both \
+the pretty and json formats. Requires [logging] file to be set.
+
+Examples:
+  zeroclaw logs
+  zeroclaw logs --level warn --since 1h
+  zeroclaw logs --follow
+  zeroclaw logs -n 50 --level error")]
+    Logs {
+        /// Keep printing new lines as they are written
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Only show lines at this level or more severe (trace, debug, info, warn, error)
+        #[arg(long)]
+        level: Option<String>,
+
+        /// Only show lines newer than this (e.g. 30m, 1h, 2d)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// How many recent lines to print before following
+        #[arg(short = 'n', long, default_value_t = 200)]
+        lines: usize,
+    },
+
+    /// Show local usage statistics (turns, tool calls, errors, channels)
+    #[command(long_about = "\
+Show local usage statistics.
+
+Charts turns per day and per week, with tool calls, errors and inbound \
+channel messages alongside, then messages per channel. Counts are \
+recorded by the running agent, channels and gateway into state/stats.db \
+in the workspace; nothing is sent over the network. Turn recording off \
+with [observability] local_stats = false.
+
+Examples:
+  zeroclaw stats
+  zeroclaw stats --days 30 --weeks 12")]
+    Stats {
+        /// Days shown in the daily chart
+        #[arg(long, default_value_t = 14)]
+        days: u32,
+
+        /// Weeks shown in the weekly chart
+        #[arg(long, default_value_t = 8)]
+        weeks: u32,
+    },
+
+    /// Audit configuration and files for security issues
+    #[command(long_about = "\
+Audit configuration and files for security issues.
+
+Checks config.toml permissions, secrets stored without encryption, \
+public gateway binds without pairing, over-broad autonomy.allowed_roots \
+and missing channel allowlists. Findings are ranked by severity; the \
+command exits non-zero when any finding reaches --fail-on (default: \
+warning), so it can gate CI.
+
+Examples:
+  zeroclaw security audit
+  zeroclaw security audit --json --fail-on critical")]
+    Security {
+        #[command(subcommand)]
+        security_command: SecurityCommands,
+    },
+
+    /// Check what the security policy would do with hypothetical tool calls
+    #[command(long_about = "\
+Check what the security policy would do with hypothetical tool calls.
+
+`policy simulate` runs each --command through the same checks as the shell \
+tool and each --path through the file_read and file_write checks, using \
+the [autonomy] config (or --autonomy <profile>), and reports allow, deny or \
+approval_required with the rule that decided it. Nothing is executed.
+
+Examples:
+  zeroclaw policy simulate --command \"rm -rf /tmp/x\" --path /etc/passwd
+  zeroclaw policy simulate --command \"git push\" --approved
+  zeroclaw policy simulate --autonomy read_only --path notes.md --json")]
+    Policy {
+        #[command(subcommand)]
+        policy_command: PolicyCommands,
+    },
+
+    /// Grant the agent temporary access to paths outside the workspace
+    #[command(long_about = "\
+Grant the agent temporary access to paths outside the workspace.
+
+A grant lets the file and shell tools reach a directory (and everything \
+below it) until it expires, without adding it to [autonomy].allowed_roots \
+for good. Grants are stored in path_grants.json next to config.toml, are \
+read when an agent starts, and are recorded in the audit log.
+
+Examples:
+  zeroclaw grants add ~/projects/foo --for 1h
+  zeroclaw grants add /srv/data --for 30m --reason \"log triage\"
+  zeroclaw grants list
+  zeroclaw grants revoke 3f2a
+  zeroclaw grants revoke all")]
+    Grants {
+        #[command(subcommand)]
+        grants_command: GrantsCommands,
+    },
+
     /// Generate shell completion script to stdout
     #[command(long_about = "\
 Generate shell completion scripts for `zeroclaw`.
@@ -228,6 +706,190 @@ Examples:
 enum ConfigCommands {
     /// Dump the full configuration JSON Schema to stdout
     Schema,
+    /// Print the value at a dot-separated key (e.g. memory.backend)
+    Get {
+        key: String,
+        /// Print secret values instead of masking them
+        #[arg(long)]
+        reveal: bool,
+    },
+    /// Set the value at a dot-separated key and save config.toml
+    Set { key: String, value: String },
+    /// Remove a key from config.toml so its default applies
+    Unset { key: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum SessionCommands {
+    /// Write a session transcript to a Markdown or HTML file
+    Export {
+        /// Session key (<agent_id>:<context>)
+        key: String,
+        /// Output format: markdown or html
+        #[arg(long, default_value = "markdown", value_parser = parse_export_format)]
+        format: sessions::ExportFormat,
+        /// Output file (defaults to session-<key>.<ext>)
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Gateway bearer token
+        #[arg(long)]
+        token: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SecurityCommands {
+    /// Report security findings for the active configuration
+    Audit {
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+        /// Exit non-zero when a finding is at or above this severity
+        #[arg(long, default_value = "warning", value_parser = parse_audit_severity)]
+        fail_on: security::AuditSeverity,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PolicyCommands {
+    /// Report allow/deny/approval decisions for hypothetical tool calls
+    Simulate {
+        /// Shell command to evaluate (repeatable)
+        #[arg(long = "command", value_name = "COMMAND")]
+        commands: Vec<String>,
+        /// File path to evaluate for reads and writes (repeatable)
+        #[arg(long = "path", value_name = "PATH")]
+        paths: Vec<String>,
+        /// Autonomy profile from [autonomy.profiles] or a built-in preset
+        #[arg(long, value_name = "PROFILE")]
+        autonomy: Option<String>,
+        /// Treat commands as approved (approved=true)
+        #[arg(long)]
+        approved: bool,
+        /// Print decisions as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum GrantsCommands {
+    /// Allow a directory until the grant expires
+    Add {
+        path: String,
+        /// How long the grant lasts (e.g. 30m, 1h, 2d)
+        #[arg(long = "for", value_name = "DURATION", default_value = "1h")]
+        duration: String,
+        /// Note stored with the grant
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// List active grants, soonest expiry first
+    List,
+    /// Revoke a grant by id (or unique id prefix), or `all`
+    Revoke { id: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum JournalCommands {
+    /// List tool actions that never completed
+    Status,
+    /// Undo incomplete file writes (all, or one entry by id prefix)
+    Rollback { id: Option<String> },
+    /// Dismiss incomplete entries without changing the workspace
+    Clear { id: Option<String> },
+}
+
+#[derive(Subcommand, Debug)]
+enum WorkspaceCommands {
+    /// List file writes that can be undone, newest first
+    Backups,
+    /// Revert the most recent file writes
+    UndoLast {
+        /// Number of writes to revert
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CheckpointCommands {
+    /// Snapshot the workspace now
+    Create {
+        /// Short description shown by `list`
+        label: Option<String>,
+    },
+    /// List checkpoints, newest first
+    List,
+    /// Roll the workspace back to a checkpoint (id or unique id prefix)
+    Restore { id: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum RemindersCommands {
+    /// List pending reminders
+    List {
+        /// Include delivered, failed and cancelled reminders
+        #[arg(long)]
+        all: bool,
+    },
+    /// Cancel a pending reminder by id (or unique id prefix)
+    Cancel { id: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum IdentityCommands {
+    /// List identities and their linked accounts
+    List,
+    /// Link an account (<channel>:<sender id>) to an identity
+    Link { identity: String, account: String },
+    /// Detach an account (<channel>:<sender id>) from its identity
+    Unlink { account: String },
+}
+
+#[derive(Subcommand, Debug)]
+enum ProviderCommands {
+    /// Send a test prompt to each configured provider/model and report results
+    Test {
+        /// Only test this provider
+        #[arg(long)]
+        provider: Option<String>,
+        /// Test this model instead of the configured ones
+        #[arg(long)]
+        model: Option<String>,
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ModelCommands {
+    /// Print the provider's model catalog
+    List {
+        /// Provider to query (defaults to the configured default_provider)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Print the catalog as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the catalog and cache its model ids for /models in chat
+    Refresh {
+        /// Provider to query (defaults to the configured default_provider)
+        #[arg(long)]
+        provider: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PromptCommands {
+    /// Print the system prompt channel conversations receive
+    Show {
+        /// Apply the prompts/<channel>/ overrides and persona for this channel
+        #[arg(long)]
+        channel: Option<String>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -258,6 +920,10 @@ enum MemoryCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Encrypt stored memory content and enable `[memory] encrypt`
+    Encrypt,
+    /// Decrypt stored memory content and disable `[memory] encrypt`
+    Decrypt,
 }
 
 #[tokio::main]
@@ -287,34 +953,56 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Initialize logging - respects RUST_LOG env var, defaults to INFO
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .finish();
+    // Log to stdout (RUST_LOG, default INFO) while config loads; `[logging]`
+    // takes over once it is available.
+    let bootstrap_logging =
+        tracing::subscriber::set_default(infra::logging::bootstrap_subscriber());
 
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    // Doctor reports a config that fails to load instead of aborting on it.
+    if let Commands::Doctor { json } = cli.command {
+        let config = Config::load_or_init().await.map(|mut config| {
+            config.apply_env_overrides();
+            config
+        });
+        drop(bootstrap_logging);
+        if let Err(e) = infra::logging::init(config.as_ref().ok()) {
+            infra::logging::init(None)?;
+            tracing::warn!("{e:#}; logging to stdout");
+        }
+        return doctor::run(config, json).await;
+    }
 
     // All other commands need config loaded first
     let mut config = Config::load_or_init().await?;
     config.apply_env_overrides();
+    drop(bootstrap_logging);
+    infra::logging::init(Some(&config))?;
 
     match cli.command {
-        Commands::Completions { .. } => unreachable!(),
+        Commands::Completions { .. } | Commands::Doctor { .. } => unreachable!(),
 
         Commands::Agent {
             message,
             provider,
             model,
             temperature,
-        } => agent::run(
+            agent_id,
+            autonomy,
+            plan,
+            resume,
+            session,
+        } => Box::pin(agent::run(
             config,
             message,
             provider,
             model,
             temperature,
-        )
+            agent_id,
+            autonomy,
+            plan,
+            session,
+            resume,
+        ))
         .await
         .map(|_| ()),
 
@@ -326,9 +1014,17 @@ async fn main() -> Result<()> {
             } else {
                 info!("🚀 Starting ZeroClaw Gateway on {host}:{port}");
             }
+            infra::shutdown::install_signal_handlers(config.runtime.shutdown_grace_secs);
             gateway::run_gateway(&host, port, config).await
         }
 
+        Commands::Pair {
+            label,
+            ttl,
+            port,
+            url,
+        } => gateway::pair::run_pair(&config, label, ttl, port, url).await,
+
         Commands::Status => {
             println!("🦀 ZeroClaw Status");
             println!();
@@ -390,7 +1086,18 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
-        Commands::Providers => {
+        Commands::Providers {
+            providers_command:
+                Some(ProviderCommands::Test {
+                    provider,
+                    model,
+                    json,
+                }),
+        } => providers::probe::run(&config, provider.as_deref(), model.as_deref(), json).await,
+
+        Commands::Providers {
+            providers_command: None,
+        } => {
             let providers = providers::list_providers();
             let current = config
                 .default_provider
@@ -424,7 +1131,10 @@ async fn main() -> Result<()> {
         }
 
         Commands::Channel { channel_command } => match channel_command {
-            ChannelCommands::Start => channels::start_channels(config).await,
+            ChannelCommands::Start => {
+                infra::shutdown::install_signal_handlers(config.runtime.shutdown_grace_secs);
+                channels::start_channels(config).await
+            }
             ChannelCommands::Doctor => channels::doctor_channels(config).await,
             other => channels::handle_command(other, &config).await,
         },
@@ -433,6 +1143,150 @@ async fn main() -> Result<()> {
             memory::handle_memory_command(memory_command, &config).await
         }
 
+        Commands::Ingest { source } => memory::ingest::run_ingest(&config, &source).await,
+
+        Commands::Skill { skill_command } => skills::handle_command(skill_command, &config).await,
+
+        Commands::Schedule { schedule_command } => {
+            Box::pin(scheduler::handle_command(schedule_command, &config)).await
+        }
+
+        Commands::Route { route_command } => routing::handle_command(route_command, &config).await,
+
+        Commands::Daemon { daemon_command } => match daemon_command {
+            DaemonCommands::Run => {
+                infra::shutdown::install_signal_handlers(config.runtime.shutdown_grace_secs);
+                #[cfg(windows)]
+                {
+                    Box::pin(infra::windows_service::run(config)).await
+                }
+                #[cfg(not(windows))]
+                {
+                    Box::pin(infra::daemon::run_foreground(config)).await
+                }
+            }
+            other => infra::daemon::handle_command(other, &config).await,
+        },
+
+        Commands::Session { session_command } => match session_command {
+            SessionCommands::Export {
+                key,
+                format,
+                output,
+                token,
+            } => sessions::export::run_export(&config, &key, format, output, token).await,
+        },
+
+        Commands::Journal { journal_command } => match journal_command {
+            JournalCommands::Status => tools::journal::run_status(&config),
+            JournalCommands::Rollback { id } => {
+                tools::journal::run_rollback(&config, id.as_deref())
+            }
+            JournalCommands::Clear { id } => tools::journal::run_clear(&config, id.as_deref()),
+        },
+
+        Commands::Workspace { workspace_command } => match workspace_command {
+            WorkspaceCommands::Backups => tools::file_undo::run_list(&config),
+            WorkspaceCommands::UndoLast { count } => {
+                tools::file_undo::run_undo_last(&config, count)
+            }
+        },
+
+        Commands::Checkpoint { checkpoint_command } => match checkpoint_command {
+            CheckpointCommands::Create { label } => {
+                infra::checkpoint::run_create(&config, label.as_deref())
+            }
+            CheckpointCommands::List => infra::checkpoint::run_list(&config),
+            CheckpointCommands::Restore { id } => infra::checkpoint::run_restore(&config, &id),
+        },
+
+        Commands::Reminders { reminders_command } => match reminders_command {
+            RemindersCommands::List { all } => scheduler::reminders::run_list(&config, all),
+            RemindersCommands::Cancel { id } => scheduler::reminders::run_cancel(&config, &id),
+        },
+
+        Commands::Models { models_command } => match models_command {
+            ModelCommands::List { provider, json } => {
+                providers::catalog::run(&config, provider.as_deref(), json, false).await
+            }
+            ModelCommands::Refresh { provider } => {
+                providers::catalog::run(&config, provider.as_deref(), false, true).await
+            }
+        },
+
+        Commands::Identity { identity_command } => match identity_command {
+            IdentityCommands::List => identity::links::run_list(&config),
+            IdentityCommands::Link { identity, account } => {
+                identity::links::run_link(&config, &identity, &account)
+            }
+            IdentityCommands::Unlink { account } => identity::links::run_unlink(&config, &account),
+        },
+
+        Commands::Stats { days, weeks } => observability::stats::run(&config, days, weeks),
+
+        Commands::Logs {
+            follow,
+            level,
+            since,
+            lines,
+        } => {
+            let query = infra::logging::LogQuery {
+                min_level: level
+                    .map(|level| {
+                        level
+                            .parse()
+                            .map_err(|_| anyhow::anyhow!("Unknown --level '{level}'"))
+                    })
+                    .transpose()?,
+                since: since
+                    .map(|since| infra::logging::parse_since(&since))
+                    .transpose()?
+                    .map(|age| chrono::Utc::now() - age),
+            };
+            infra::logging::run_logs(&config, &query, lines, follow).await
+        }
+
+        Commands::Security { security_command } => match security_command {
+            SecurityCommands::Audit { json, fail_on } => {
+                security::auditor::run_audit(&config, json, fail_on).await
+            }
+        },
+
+        Commands::Policy { policy_command } => match policy_command {
+            PolicyCommands::Simulate {
+                commands,
+                paths,
+                autonomy,
+                approved,
+                json,
+            } => security::simulate::run(
+                &config,
+                autonomy.as_deref(),
+                &commands,
+                &paths,
+                approved,
+                json,
+            ),
+        },
+
+        Commands::Grants { grants_command } => match grants_command {
+            GrantsCommands::Add {
+                path,
+                duration,
+                reason,
+            } => security::grants::run_add(&config, &path, &duration, reason),
+            GrantsCommands::List => security::grants::run_list(&config),
+            GrantsCommands::Revoke { id } => security::grants::run_revoke(&config, &id),
+        },
+
+        Commands::Prompt { prompt_command } => match prompt_command {
+            PromptCommands::Show { channel } => {
+                let prompt = channels::preview_system_prompt(&config, channel.as_deref()).await?;
+                println!("{prompt}");
+                Ok(())
+            }
+        },
+
         Commands::Config { config_command } => match config_command {
             ConfigCommands::Schema => {
                 let schema = schemars::schema_for!(config::Config);
@@ -442,6 +1296,23 @@ async fn main() -> Result<()> {
                 );
                 Ok(())
             }
+            ConfigCommands::Get { key, reveal } => {
+                let value = config::edit::get_value(&config, &key, reveal)?;
+                println!("{}", config::edit::render_value(&value));
+                Ok(())
+            }
+            ConfigCommands::Set { key, value } => {
+                let updated = config::edit::set_value(&config, &key, &value)?;
+                config::edit::persist(&updated, &key, false).await?;
+                println!("✅ Set {key}");
+                Ok(())
+            }
+            ConfigCommands::Unset { key } => {
+                let updated = config::edit::unset_value(&config, &key)?;
+                config::edit::persist(&updated, &key, true).await?;
+                println!("✅ Unset {key} (default applies)");
+                Ok(())
+            }
         },
     }
 }
@@ -501,4 +1372,467 @@ mod tests {
         );
     }
 
+    #[test]
+    fn config_cli_parses_get_set_unset() {
+        let cli = Cli::try_parse_from(["zeroclaw", "config", "set", "autonomy.level", "full"])
+            .expect("config set should parse");
+        match cli.command {
+            Commands::Config {
+                config_command: ConfigCommands::Set { key, value },
+            } => {
+                assert_eq!(key, "autonomy.level");
+                assert_eq!(value, "full");
+            }
+            other => panic!("expected config set, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["zeroclaw", "config", "get", "api_key", "--reveal"])
+            .expect("config get should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                config_command: ConfigCommands::Get { reveal: true, .. }
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["zeroclaw", "config", "unset", "api_url"])
+            .expect("config unset should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Config {
+                config_command: ConfigCommands::Unset { .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn agent_cli_parses_resume_and_session() {
+        let cli = Cli::try_parse_from(["zeroclaw", "agent", "--session", "research"])
+            .expect("agent --session should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Agent { session: Some(ref name), resume: false, .. } if name == "research"
+        ));
+
+        let cli = Cli::try_parse_from(["zeroclaw", "agent", "--resume", "-m", "and then?"])
+            .expect("agent --resume should parse");
+        assert!(matches!(cli.command, Commands::Agent { resume: true, .. }));
+
+        assert!(Cli::try_parse_from(["zeroclaw", "agent", "--resume", "--session", "x"]).is_err());
+    }
+
+    #[test]
+    fn schedule_cli_parses_add_with_delivery() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "schedule",
+            "add",
+            "0 9 * * *",
+            "Summarize my inbox",
+            "--channel",
+            "whatsapp",
+            "--to",
+            "+15551234567",
+        ])
+        .expect("schedule add should parse");
+        match cli.command {
+            Commands::Schedule {
+                schedule_command: ScheduleCommands::Add { cron, channel, .. },
+            } => {
+                assert_eq!(cron, "0 9 * * *");
+                assert_eq!(channel.as_deref(), Some("whatsapp"));
+            }
+            other => panic!("expected schedule add, got {other:?}"),
+        }
+
+        let missing_recipient = ["zeroclaw", "schedule", "add", "@daily", "x", "--channel", "a"];
+        assert!(Cli::try_parse_from(missing_recipient).is_err());
+        assert!(Cli::try_parse_from(["zeroclaw", "schedule", "run-now", "inbox"]).is_ok());
+    }
+
+    #[test]
+    fn daemon_cli_parses_lifecycle_subcommands() {
+        let cli = Cli::try_parse_from(["zeroclaw", "daemon", "install"])
+            .expect("daemon install should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Daemon {
+                daemon_command: DaemonCommands::Install
+            }
+        ));
+        for sub in ["uninstall", "start", "stop", "status", "run"] {
+            assert!(Cli::try_parse_from(["zeroclaw", "daemon", sub]).is_ok());
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "daemon"]).is_err());
+    }
+
+    #[test]
+    fn agent_cli_accepts_agent_id() {
+        let cli = Cli::try_parse_from(["zeroclaw", "agent", "--agent", "research", "-m", "hi"])
+            .expect("agent --agent should parse");
+        match cli.command {
+            Commands::Agent {
+                agent_id, message, ..
+            } => {
+                assert_eq!(agent_id.as_deref(), Some("research"));
+                assert_eq!(message.as_deref(), Some("hi"));
+            }
+            other => panic!("expected agent command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn agent_cli_accepts_autonomy_profile() {
+        let cli = Cli::try_parse_from(["zeroclaw", "agent", "--autonomy", "coding"])
+            .expect("agent --autonomy should parse");
+        match cli.command {
+            Commands::Agent { autonomy, .. } => {
+                assert_eq!(autonomy.as_deref(), Some("coding"));
+            }
+            other => panic!("expected agent command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn route_cli_parses_add_with_defaults() {
+        let cli = Cli::try_parse_from(["zeroclaw", "route", "add", "telegram", "research"])
+            .expect("route add should parse");
+        match cli.command {
+            Commands::Route {
+                route_command:
+                    RouteCommands::Add {
+                        channel,
+                        agent,
+                        pattern,
+                        priority,
+                        id,
+                    },
+            } => {
+                assert_eq!(channel, "telegram");
+                assert_eq!(agent, "research");
+                assert_eq!(pattern, "*");
+                assert_eq!(priority, 0);
+                assert!(id.is_none());
+            }
+            other => panic!("expected route add, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "route", "remove", "route-1"]).is_ok());
+    }
+
+    #[test]
+    fn session_export_parses_format() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "session",
+            "export",
+            "gateway:a",
+            "--format=html",
+        ])
+        .expect("session export should parse");
+        match cli.command {
+            Commands::Session {
+                session_command: SessionCommands::Export { key, format, .. },
+            } => {
+                assert_eq!(key, "gateway:a");
+                assert_eq!(format, sessions::ExportFormat::Html);
+            }
+            other => panic!("expected session export, got {other:?}"),
+        }
+        let bad_format = ["zeroclaw", "session", "export", "k", "--format", "pdf"];
+        assert!(Cli::try_parse_from(bad_format).is_err());
+    }
+
+    #[test]
+    fn ingest_requires_source() {
+        let cli = Cli::try_parse_from(["zeroclaw", "ingest", "docs/handbook.md"])
+            .expect("ingest should parse");
+        match cli.command {
+            Commands::Ingest { source } => assert_eq!(source, "docs/handbook.md"),
+            other => panic!("expected ingest, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "ingest"]).is_err());
+    }
+
+    #[test]
+    fn prompt_show_accepts_channel() {
+        let cli = Cli::try_parse_from(["zeroclaw", "prompt", "show", "--channel", "telegram"])
+            .expect("prompt show should parse");
+        match cli.command {
+            Commands::Prompt {
+                prompt_command: PromptCommands::Show { channel },
+            } => assert_eq!(channel.as_deref(), Some("telegram")),
+            other => panic!("expected prompt show, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn security_audit_parses_fail_on() {
+        let cli = Cli::try_parse_from(["zeroclaw", "security", "audit", "--fail-on", "critical"])
+            .expect("security audit should parse");
+        match cli.command {
+            Commands::Security {
+                security_command: SecurityCommands::Audit { json, fail_on },
+            } => {
+                assert!(!json);
+                assert_eq!(fail_on, security::AuditSeverity::Critical);
+            }
+            other => panic!("expected security audit, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "security", "audit", "--fail-on", "x"]).is_err());
+    }
+
+    #[test]
+    fn policy_simulate_cli_collects_commands_and_paths() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "policy",
+            "simulate",
+            "--command",
+            "rm -rf /tmp/x",
+            "--command",
+            "ls",
+            "--path",
+            "/etc/passwd",
+            "--autonomy",
+            "coding",
+        ])
+        .expect("policy simulate should parse");
+        match cli.command {
+            Commands::Policy {
+                policy_command:
+                    PolicyCommands::Simulate {
+                        commands,
+                        paths,
+                        autonomy,
+                        approved,
+                        json,
+                    },
+            } => {
+                assert_eq!(commands, ["rm -rf /tmp/x", "ls"]);
+                assert_eq!(paths, ["/etc/passwd"]);
+                assert_eq!(autonomy.as_deref(), Some("coding"));
+                assert!(!approved && !json);
+            }
+            other => panic!("expected policy simulate, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn grants_cli_parses_add_list_and_revoke() {
+        let cli = Cli::try_parse_from(["zeroclaw", "grants", "add", "~/projects/foo"])
+            .expect("grants add should parse");
+        match cli.command {
+            Commands::Grants {
+                grants_command:
+                    GrantsCommands::Add {
+                        path,
+                        duration,
+                        reason,
+                    },
+            } => {
+                assert_eq!(path, "~/projects/foo");
+                assert_eq!(duration, "1h");
+                assert!(reason.is_none());
+            }
+            other => panic!("expected grants add, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(["zeroclaw", "grants", "add", "/srv", "--for", "30m"])
+            .expect("grants add --for should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Grants {
+                grants_command: GrantsCommands::Add { ref duration, .. },
+            } if duration == "30m"
+        ));
+        assert!(Cli::try_parse_from(["zeroclaw", "grants", "list"]).is_ok());
+        assert!(Cli::try_parse_from(["zeroclaw", "grants", "revoke", "3f2a"]).is_ok());
+        assert!(Cli::try_parse_from(["zeroclaw", "grants", "revoke"]).is_err());
+    }
+
+    #[test]
+    fn doctor_accepts_json_flag() {
+        let cli = Cli::try_parse_from(["zeroclaw", "doctor", "--json"])
+            .expect("doctor --json should parse");
+        assert!(matches!(cli.command, Commands::Doctor { json: true }));
+    }
+
+    #[test]
+    fn checkpoint_create_label_is_optional() {
+        let cli = Cli::try_parse_from(["zeroclaw", "checkpoint", "create"])
+            .expect("checkpoint create should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Checkpoint {
+                checkpoint_command: CheckpointCommands::Create { label: None }
+            }
+        ));
+        let cli = Cli::try_parse_from(["zeroclaw", "checkpoint", "restore", "3f2a"])
+            .expect("checkpoint restore should parse");
+        match cli.command {
+            Commands::Checkpoint {
+                checkpoint_command: CheckpointCommands::Restore { id },
+            } => assert_eq!(id, "3f2a"),
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn workspace_undo_last_defaults_to_one_write() {
+        let cli = Cli::try_parse_from(["zeroclaw", "workspace", "undo-last"])
+            .expect("workspace undo-last should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Workspace {
+                workspace_command: WorkspaceCommands::UndoLast { count: 1 }
+            }
+        ));
+        let cli = Cli::try_parse_from(["zeroclaw", "workspace", "undo-last", "-n", "3"])
+            .expect("workspace undo-last -n should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Workspace {
+                workspace_command: WorkspaceCommands::UndoLast { count: 3 }
+            }
+        ));
+    }
+
+    #[test]
+    fn journal_rollback_accepts_optional_id() {
+        let cli = Cli::try_parse_from(["zeroclaw", "journal", "rollback", "3f2a"])
+            .expect("journal rollback should parse");
+        match cli.command {
+            Commands::Journal {
+                journal_command: JournalCommands::Rollback { id },
+            } => assert_eq!(id.as_deref(), Some("3f2a")),
+            other => panic!("expected journal rollback, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "journal", "status"]).is_ok());
+    }
+
+    #[test]
+    fn logs_cli_parses_filters() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw", "logs", "--follow", "--level", "warn", "--since", "1h", "-n", "20",
+        ])
+        .expect("logs should parse");
+        match cli.command {
+            Commands::Logs {
+                follow,
+                level,
+                since,
+                lines,
+            } => {
+                assert!(follow);
+                assert_eq!(level.as_deref(), Some("warn"));
+                assert_eq!(since.as_deref(), Some("1h"));
+                assert_eq!(lines, 20);
+            }
+            other => panic!("expected logs, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stats_cli_parses_day_and_week_counts() {
+        let cli = Cli::try_parse_from(["zeroclaw", "stats"]).expect("stats should parse");
+        match cli.command {
+            Commands::Stats { days, weeks } => assert_eq!((days, weeks), (14, 8)),
+            other => panic!("expected stats, got {other:?}"),
+        }
+        let cli = Cli::try_parse_from(["zeroclaw", "stats", "--days", "30"])
+            .expect("stats --days should parse");
+        assert!(matches!(cli.command, Commands::Stats { days: 30, weeks: 8 }));
+    }
+
+    #[test]
+    fn reminders_cli_parses_list_and_cancel() {
+        let cli = Cli::try_parse_from(["zeroclaw", "reminders", "list", "--all"])
+            .expect("reminders list should parse");
+        match cli.command {
+            Commands::Reminders {
+                reminders_command: RemindersCommands::List { all },
+            } => assert!(all),
+            other => panic!("expected reminders list, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "reminders", "cancel", "3f2a"]).is_ok());
+        assert!(Cli::try_parse_from(["zeroclaw", "reminders", "cancel"]).is_err());
+    }
+
+    #[test]
+    fn models_list_accepts_provider_and_json() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "models",
+            "list",
+            "--provider",
+            "openrouter",
+            "--json",
+        ])
+        .expect("models list should parse");
+        match cli.command {
+            Commands::Models {
+                models_command: ModelCommands::List { provider, json },
+            } => {
+                assert_eq!(provider.as_deref(), Some("openrouter"));
+                assert!(json);
+            }
+            other => panic!("expected models list, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "models", "refresh"]).is_ok());
+    }
+
+    #[test]
+    fn providers_cli_lists_or_tests() {
+        let cli = Cli::try_parse_from(["zeroclaw", "providers"]).expect("providers should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Providers {
+                providers_command: None
+            }
+        ));
+
+        let cli = Cli::try_parse_from(["zeroclaw", "providers", "test", "--provider", "openai"])
+            .expect("providers test should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Providers {
+                providers_command: Some(ProviderCommands::Test { provider: Some(ref p), json: false, .. }),
+            } if p == "openai"
+        ));
+    }
+
+    #[test]
+    fn identity_link_requires_identity_and_account() {
+        let cli = Cli::try_parse_from(["zeroclaw", "identity", "link", "alice", "telegram:42"])
+            .expect("identity link should parse");
+        match cli.command {
+            Commands::Identity {
+                identity_command: IdentityCommands::Link { identity, account },
+            } => {
+                assert_eq!(identity, "alice");
+                assert_eq!(account, "telegram:42");
+            }
+            other => panic!("expected identity link, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["zeroclaw", "identity", "link", "alice"]).is_err());
+    }
+
+    #[test]
+    fn pair_cli_defaults_and_flags() {
+        let cli = Cli::try_parse_from(["zeroclaw", "pair"]).expect("pair should parse");
+        match cli.command {
+            Commands::Pair { label, ttl, .. } => {
+                assert!(label.is_none());
+                assert_eq!(ttl, gateway::PAIR_WINDOW_DEFAULT_TTL_SECS);
+            }
+            other => panic!("expected pair command, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["zeroclaw", "pair", "--label", "phone", "--ttl", "30"])
+            .expect("pair with flags should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Pair { ttl: 30, label: Some(ref l), .. } if l == "phone"
+        ));
+    }
+
 }
@@ -0,0 +1,508 @@
+//! Text extraction for uploaded documents.
+//!
+//! Plain text and Markdown pass through unchanged; PDF text is extracted with
+//! `pdf-extract` (feature `rag-pdf`); DOCX and XLSX are read straight from
+//! their Office Open XML parts. Extracted text is capped and split into
+//! memory-sized chunks (`[memory].chunk_max_tokens`).
+
+use std::fmt::Write;
+use std::io::{Cursor, Read};
+
+use anyhow::{bail, Context};
+
+use super::traits::{DocumentFormat, DocumentOptions, ParsedDocument};
+use crate::memory::chunker::chunk_text;
+
+const DEFAULT_MAX_INPUT_BYTES: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_TEXT_CHARS: usize = 200_000;
+const DEFAULT_CHUNK_MAX_TOKENS: usize = 512;
+/// Decompressed size cap for a single part inside an Office archive.
+const MAX_ARCHIVE_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+
+const DOCX_MIME: &str = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+const XLSX_MIME: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet";
+
+impl Default for DocumentOptions {
+    fn default() -> Self {
+        Self {
+            max_input_bytes: DEFAULT_MAX_INPUT_BYTES,
+            max_text_chars: DEFAULT_MAX_TEXT_CHARS,
+            chunk_max_tokens: DEFAULT_CHUNK_MAX_TOKENS,
+        }
+    }
+}
+
+impl DocumentFormat {
+    /// Detect the format from the MIME type, then the file extension, then
+    /// the content itself (PDF magic bytes, or UTF-8 text).
+    pub fn detect(data: &[u8], mime_type: Option<&str>, file_name: Option<&str>) -> Option<Self> {
+        let from_mime = mime_type.and_then(|mime| {
+            let mime = mime.split(';').next().unwrap_or(mime).trim();
+            match mime.to_ascii_lowercase().as_str() {
+                "text/markdown" | "text/x-markdown" => Some(Self::Markdown),
+                "application/pdf" => Some(Self::Pdf),
+                DOCX_MIME => Some(Self::Docx),
+                XLSX_MIME => Some(Self::Xlsx),
+                m if m.starts_with("text/") => Some(Self::PlainText),
+                _ => None,
+            }
+        });
+        let from_extension = || {
+            let extension = file_name?.rsplit_once('.')?.1.to_ascii_lowercase();
+            match extension.as_str() {
+                "txt" | "text" | "log" | "csv" => Some(Self::PlainText),
+                "md" | "markdown" => Some(Self::Markdown),
+                "pdf" => Some(Self::Pdf),
+                "docx" => Some(Self::Docx),
+                "xlsx" => Some(Self::Xlsx),
+                _ => None,
+            }
+        };
+        let from_content = || {
+            if data.starts_with(b"%PDF-") {
+                return Some(Self::Pdf);
+            }
+            let head = &data[..data.len().min(8192)];
+            let is_text = !head.contains(&0)
+                && match std::str::from_utf8(head) {
+                    Ok(_) => true,
+                    // A multi-byte character may straddle the sample boundary.
+                    Err(e) => e.error_len().is_none(),
+                };
+            is_text.then_some(Self::PlainText)
+        };
+        from_mime.or_else(from_extension).or_else(from_content)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PlainText => "text",
+            Self::Markdown => "markdown",
+            Self::Pdf => "pdf",
+            Self::Docx => "docx",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Extract and chunk the text of a document.
+pub fn parse_document(
+    data: &[u8],
+    mime_type: Option<&str>,
+    file_name: Option<&str>,
+    options: &DocumentOptions,
+) -> anyhow::Result<ParsedDocument> {
+    if data.len() as u64 > options.max_input_bytes {
+        bail!(
+            "Document is {} bytes, exceeding the {} byte limit",
+            data.len(),
+            options.max_input_bytes
+        );
+    }
+    let format = DocumentFormat::detect(data, mime_type, file_name).with_context(|| {
+        format!(
+            "Unsupported document type{}",
+            mime_type.map(|m| format!(" '{m}'")).unwrap_or_default()
+        )
+    })?;
+    let text = match format {
+        DocumentFormat::PlainText | DocumentFormat::Markdown => String::from_utf8_lossy(data)
+            .trim_start_matches('\u{feff}')
+            .to_string(),
+        DocumentFormat::Pdf => extract_pdf(data)?,
+        DocumentFormat::Docx => extract_docx(data)?,
+        DocumentFormat::Xlsx => extract_xlsx(data)?,
+    };
+
+    let text = text.trim();
+    let truncated = text.chars().count() > options.max_text_chars;
+    let text: String = if truncated {
+        text.chars().take(options.max_text_chars).collect()
+    } else {
+        text.to_string()
+    };
+    let chunks = chunk_text(&text, options.chunk_max_tokens);
+    Ok(ParsedDocument {
+        format,
+        text,
+        chunks,
+        truncated,
+    })
+}
+
+#[cfg(feature = "rag-pdf")]
+fn extract_pdf(data: &[u8]) -> anyhow::Result<String> {
+    pdf_extract::extract_text_from_mem(data).context("Failed to extract text from PDF")
+}
+
+#[cfg(not(feature = "rag-pdf"))]
+fn extract_pdf(_data: &[u8]) -> anyhow::Result<String> {
+    bail!("PDF extraction is not available; rebuild with `--features rag-pdf`")
+}
+
+fn open_archive(data: &[u8]) -> anyhow::Result<zip::ZipArchive<Cursor<&[u8]>>> {
+    zip::ZipArchive::new(Cursor::new(data)).context("Document is not a valid Office archive")
+}
+
+fn read_archive_entry(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+) -> anyhow::Result<Option<String>> {
+    let file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {name}")),
+    };
+    if file.size() > MAX_ARCHIVE_ENTRY_BYTES {
+        bail!("{name} expands beyond the {MAX_ARCHIVE_ENTRY_BYTES} byte limit");
+    }
+    let mut xml = String::new();
+    file.take(MAX_ARCHIVE_ENTRY_BYTES + 1)
+        .read_to_string(&mut xml)
+        .with_context(|| format!("Failed to read {name}"))?;
+    if xml.len() as u64 > MAX_ARCHIVE_ENTRY_BYTES {
+        bail!("{name} expands beyond the {MAX_ARCHIVE_ENTRY_BYTES} byte limit");
+    }
+    Ok(Some(xml))
+}
+
+fn extract_docx(data: &[u8]) -> anyhow::Result<String> {
+    let mut archive = open_archive(data)?;
+    let xml = read_archive_entry(&mut archive, "word/document.xml")?
+        .context("DOCX has no word/document.xml")?;
+    Ok(docx_text(&xml))
+}
+
+fn extract_xlsx(data: &[u8]) -> anyhow::Result<String> {
+    let mut archive = open_archive(data)?;
+    let shared = read_archive_entry(&mut archive, "xl/sharedStrings.xml")?
+        .map(|xml| xlsx_shared_strings(&xml))
+        .unwrap_or_default();
+    let names = read_archive_entry(&mut archive, "xl/workbook.xml")?
+        .map(|xml| xlsx_sheet_names(&xml))
+        .unwrap_or_default();
+
+    let mut sheets: Vec<(u32, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let number = name
+                .strip_prefix("xl/worksheets/sheet")?
+                .strip_suffix(".xml")?
+                .parse()
+                .ok()?;
+            Some((number, name.to_string()))
+        })
+        .collect();
+    sheets.sort();
+
+    let mut out = String::new();
+    for (index, (number, path)) in sheets.iter().enumerate() {
+        let Some(xml) = read_archive_entry(&mut archive, path)? else {
+            continue;
+        };
+        let title = names
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| format!("Sheet{number}"));
+        let _ = write!(out, "## {title}\n\n");
+        out.push_str(&xlsx_sheet_text(&xml, &shared));
+        out.push_str("\n\n");
+    }
+    Ok(out)
+}
+
+enum XmlEvent<'a> {
+    Open { name: &'a str, attrs: &'a str },
+    Empty { name: &'a str, attrs: &'a str },
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Minimal tag scanner for the well-formed XML inside Office archives.
+fn scan_xml<'a>(xml: &'a str, mut on_event: impl FnMut(XmlEvent<'a>)) {
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            on_event(XmlEvent::Text(rest));
+            break;
+        };
+        if start > 0 {
+            on_event(XmlEvent::Text(&rest[..start]));
+        }
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            on_event(XmlEvent::Close(name.trim()));
+            continue;
+        }
+        let (body, empty) = match tag.strip_suffix('/') {
+            Some(body) => (body, true),
+            None => (tag, false),
+        };
+        let (name, attrs) = body
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((body.trim(), ""));
+        on_event(if empty {
+            XmlEvent::Empty { name, attrs }
+        } else {
+            XmlEvent::Open { name, attrs }
+        });
+    }
+}
+
+/// Value of attribute `name` in a raw attribute list.
+fn xml_attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!("{name}=\"");
+    let mut offset = 0;
+    while let Some(found) = attrs[offset..].find(&needle) {
+        let start = offset + found;
+        let at_boundary = attrs[..start]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        let value_start = start + needle.len();
+        if at_boundary {
+            let len = attrs[value_start..].find('"')?;
+            return Some(&attrs[value_start..value_start + len]);
+        }
+        offset = value_start;
+    }
+    None
+}
+
+fn unescape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let Some(semi) = after.find(';').filter(|&i| i <= 10) else {
+            out.push('&');
+            rest = after;
+            continue;
+        };
+        let entity = &after[..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &after[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn docx_text(xml: &str) -> String {
+    let mut out = String::new();
+    let mut in_text = false;
+    scan_xml(xml, |event| match event {
+        XmlEvent::Open { name: "w:t", .. } => in_text = true,
+        XmlEvent::Close("w:t") => in_text = false,
+        XmlEvent::Text(text) if in_text => out.push_str(&unescape_xml(text)),
+        XmlEvent::Empty { name: "w:tab", .. } => out.push('\t'),
+        XmlEvent::Empty {
+            name: "w:br" | "w:cr",
+            ..
+        } => out.push('\n'),
+        XmlEvent::Close("w:p") => out.push_str("\n\n"),
+        _ => {}
+    });
+    out
+}
+
+fn xlsx_shared_strings(xml: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    let mut in_phonetic = false;
+    scan_xml(xml, |event| match event {
+        XmlEvent::Open { name: "si", .. } => current.clear(),
+        XmlEvent::Close("si") => strings.push(std::mem::take(&mut current)),
+        XmlEvent::Open { name: "rPh", .. } => in_phonetic = true,
+        XmlEvent::Close("rPh") => in_phonetic = false,
+        XmlEvent::Open { name: "t", .. } => in_text = !in_phonetic,
+        XmlEvent::Close("t") => in_text = false,
+        XmlEvent::Text(text) if in_text => current.push_str(&unescape_xml(text)),
+        _ => {}
+    });
+    strings
+}
+
+fn xlsx_sheet_names(xml: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    scan_xml(xml, |event| {
+        if let XmlEvent::Open {
+            name: "sheet",
+            attrs,
+        }
+        | XmlEvent::Empty {
+            name: "sheet",
+            attrs,
+        } = event
+        {
+            if let Some(name) = xml_attr(attrs, "name") {
+                names.push(unescape_xml(name));
+            }
+        }
+    });
+    names
+}
+
+fn xlsx_sheet_text(xml: &str, shared: &[String]) -> String {
+    let mut lines = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell_type = String::new();
+    let mut value = String::new();
+    let mut in_value = false;
+    scan_xml(xml, |event| match event {
+        XmlEvent::Open { name: "c", attrs } => {
+            cell_type = xml_attr(attrs, "t").unwrap_or_default().to_string();
+            value.clear();
+        }
+        XmlEvent::Open {
+            name: "v" | "t", ..
+        } => in_value = true,
+        XmlEvent::Close("v" | "t") => in_value = false,
+        XmlEvent::Text(text) if in_value => value.push_str(&unescape_xml(text)),
+        XmlEvent::Close("c") => {
+            let cell = if cell_type == "s" {
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| shared.get(i).cloned())
+                    .unwrap_or_default()
+            } else {
+                std::mem::take(&mut value)
+            };
+            row.push(cell);
+        }
+        XmlEvent::Close("row") => {
+            let line = std::mem::take(&mut row).join("\t");
+            if !line.trim().is_empty() {
+                lines.push(line.trim_end().to_string());
+            }
+        }
+        _ => {}
+    });
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn office_archive(parts: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in parts {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn detects_formats_by_mime_extension_and_content() {
+        assert_eq!(
+            DocumentFormat::detect(b"", Some("text/markdown; charset=utf-8"), None),
+            Some(DocumentFormat::Markdown)
+        );
+        assert_eq!(
+            DocumentFormat::detect(b"PK", None, Some("Report.DOCX")),
+            Some(DocumentFormat::Docx)
+        );
+        assert_eq!(
+            DocumentFormat::detect(b"%PDF-1.7", Some("application/octet-stream"), None),
+            Some(DocumentFormat::Pdf)
+        );
+        assert_eq!(DocumentFormat::detect(b"\x00\x01\x02", None, None), None);
+    }
+
+    #[test]
+    fn text_passthrough_is_capped_and_chunked() {
+        let options = DocumentOptions {
+            max_text_chars: 30,
+            chunk_max_tokens: 4,
+            ..DocumentOptions::default()
+        };
+        let doc = parse_document(
+            "\u{feff}First paragraph.\n\nSecond paragraph that is cut".as_bytes(),
+            None,
+            Some("notes.md"),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(doc.format, DocumentFormat::Markdown);
+        assert!(doc.truncated);
+        assert_eq!(doc.text.chars().count(), 30);
+        assert_eq!(doc.chunks[0], "First paragraph.");
+        assert!(doc.chunks.len() > 1);
+
+        let tiny = DocumentOptions {
+            max_input_bytes: 4,
+            ..DocumentOptions::default()
+        };
+        let err = parse_document(b"hello", None, None, &tiny).unwrap_err();
+        assert!(err.to_string().contains("byte limit"));
+    }
+
+    #[test]
+    fn extracts_docx_paragraphs() {
+        let document = r#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:r><w:t>Quarterly</w:t></w:r><w:r><w:t xml:space="preserve"> report &amp; plan</w:t></w:r></w:p>
+            <w:p><w:r><w:t>Revenue</w:t><w:tab/><w:t>42</w:t></w:r></w:p>
+            </w:body></w:document>"#;
+        let data = office_archive(&[("word/document.xml", document)]);
+        let doc =
+            parse_document(&data, Some(DOCX_MIME), None, &DocumentOptions::default()).unwrap();
+        assert_eq!(doc.text, "Quarterly report & plan\n\nRevenue\t42");
+    }
+
+    #[test]
+    fn extracts_xlsx_rows_with_shared_strings() {
+        let workbook = r#"<workbook><sheets><sheet name="Q1 &amp; Q2" sheetId="1" r:id="rId1"/></sheets></workbook>"#;
+        let shared =
+            r#"<sst><si><t>Region</t></si><si><r><t>No</t></r><r><t>rth</t></r></si></sst>"#;
+        let sheet = r#"<worksheet><sheetData>
+            <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="inlineStr"><is><t>Sales</t></is></c></row>
+            <row r="2"><c r="A2" t="s"><v>1</v></c><c r="B2"><v>1200</v></c></row>
+            </sheetData></worksheet>"#;
+        let data = office_archive(&[
+            ("xl/workbook.xml", workbook),
+            ("xl/sharedStrings.xml", shared),
+            ("xl/worksheets/sheet1.xml", sheet),
+        ]);
+        let doc =
+            parse_document(&data, None, Some("sales.xlsx"), &DocumentOptions::default()).unwrap();
+        assert_eq!(doc.format, DocumentFormat::Xlsx);
+        assert_eq!(doc.text, "## Q1 & Q2\n\nRegion\tSales\nNorth\t1200");
+    }
+}
@@ -0,0 +1,306 @@
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+
+use super::traits::{
+    FetchOptions, FetchedMedia, MediaEntry, MediaFetcher, MediaMetadata, MediaStore,
+};
+
+/// Proxy service key for attachment downloads (`[proxy] services`).
+pub const MEDIA_FETCH_SERVICE_KEY: &str = "media.fetch";
+
+const DEFAULT_MAX_SIZE_BYTES: u64 = 20 * 1024 * 1024;
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            max_size_bytes: DEFAULT_MAX_SIZE_BYTES,
+            timeout: std::time::Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            allowed_mime_types: None,
+        }
+    }
+}
+
+/// Downloads remote attachments through the runtime proxy client.
+///
+/// Only URLs with an allowlisted scheme are fetched (including after
+/// redirects). Bodies are streamed and abandoned as soon as they exceed
+/// `max_size_bytes`. The MIME type is sniffed from the payload, falling back
+/// to the `Content-Type` header, and checked against `allowed_mime_types`.
+pub struct HttpMediaFetcher {
+    allowed_schemes: Vec<String>,
+}
+
+impl HttpMediaFetcher {
+    pub fn new() -> Self {
+        Self {
+            allowed_schemes: vec!["https".into()],
+        }
+    }
+
+    /// Replace the scheme allowlist (default: `https` only).
+    pub fn with_allowed_schemes(mut self, schemes: &[&str]) -> Self {
+        self.allowed_schemes = schemes.iter().map(|s| s.to_ascii_lowercase()).collect();
+        self
+    }
+
+    fn check_scheme(&self, url: &reqwest::Url) -> anyhow::Result<()> {
+        if self.allowed_schemes.iter().any(|s| s == url.scheme()) {
+            Ok(())
+        } else {
+            bail!(
+                "URL scheme '{}' is not allowed (allowed: {})",
+                url.scheme(),
+                self.allowed_schemes.join(", ")
+            )
+        }
+    }
+}
+
+impl Default for HttpMediaFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MediaFetcher for HttpMediaFetcher {
+    async fn fetch(&self, url: &str, options: &FetchOptions) -> anyhow::Result<FetchedMedia> {
+        let parsed =
+            reqwest::Url::parse(url).with_context(|| format!("Invalid media URL: {url}"))?;
+        self.check_scheme(&parsed)?;
+
+        let client = crate::config::build_runtime_proxy_client(MEDIA_FETCH_SERVICE_KEY);
+        let mut response = client
+            .get(parsed)
+            .timeout(options.timeout)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {url}"))?;
+        self.check_scheme(response.url())?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Fetching {url} failed with HTTP {status}");
+        }
+        if let Some(length) = response.content_length() {
+            if length > options.max_size_bytes {
+                bail!(
+                    "Media is {length} bytes, exceeding the {} byte limit",
+                    options.max_size_bytes
+                );
+            }
+        }
+        let header_mime = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| !v.is_empty());
+
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (data.len() + chunk.len()) as u64 > options.max_size_bytes {
+                bail!("Media exceeds the {} byte limit", options.max_size_bytes);
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let mime_type = sniff_mime_type(&data)
+            .map(str::to_string)
+            .or(header_mime)
+            .unwrap_or_else(|| "application/octet-stream".into());
+        if let Some(allowed) = &options.allowed_mime_types {
+            if !mime_allowed(&mime_type, allowed) {
+                bail!("Media type '{mime_type}' is not allowed");
+            }
+        }
+
+        Ok(FetchedMedia {
+            size_bytes: data.len() as u64,
+            data,
+            mime_type,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "http"
+    }
+}
+
+/// Detect common image, audio and document types from their magic bytes.
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"OggS", "audio/ogg"),
+        (b"ID3", "audio/mpeg"),
+        (b"fLaC", "audio/flac"),
+    ];
+    if let Some(&(_, mime)) = SIGNATURES.iter().find(|(magic, _)| data.starts_with(magic)) {
+        return Some(mime);
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" {
+        match &data[8..12] {
+            b"WEBP" => return Some("image/webp"),
+            b"WAVE" => return Some("audio/wav"),
+            _ => {}
+        }
+    }
+    if data.len() >= 12 && &data[4..8] == b"ftyp" {
+        return Some(if data[8..12].starts_with(b"M4A") {
+            "audio/mp4"
+        } else {
+            "video/mp4"
+        });
+    }
+    None
+}
+
+/// Whether `mime` matches one of `allowed` (`type/subtype` or `type/*`).
+fn mime_allowed(mime: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|pattern| {
+        let pattern = pattern.trim().to_ascii_lowercase();
+        match pattern.strip_suffix("/*") {
+            Some(prefix) => mime.split('/').next() == Some(prefix),
+            None => pattern == mime,
+        }
+    })
+}
+
+/// File name for fetched media: the last URL path segment, or `download`
+/// with an extension derived from the MIME type.
+fn media_file_name(url: &str, mime_type: &str) -> String {
+    let from_url = reqwest::Url::parse(url).ok().and_then(|u| {
+        u.path_segments()
+            .and_then(|mut segments| segments.next_back().map(str::to_string))
+            .filter(|name| name.contains('.'))
+    });
+    from_url.unwrap_or_else(|| format!("download.{}", extension_for_mime(mime_type)))
+}
+
+/// File extension for a MIME type, `bin` when it is not a known one.
+pub fn extension_for_mime(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "application/zip" => "zip",
+        "audio/ogg" => "ogg",
+        "audio/mpeg" => "mp3",
+        "audio/wav" => "wav",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+/// Fetch `url` and save it to `store`, recording the source URL and sniffed
+/// MIME type in the entry metadata.
+pub async fn fetch_and_store(
+    fetcher: &dyn MediaFetcher,
+    store: &dyn MediaStore,
+    url: &str,
+    options: &FetchOptions,
+) -> anyhow::Result<MediaEntry> {
+    let fetched = fetcher.fetch(url, options).await?;
+    let metadata = MediaMetadata {
+        filename: Some(media_file_name(url, &fetched.mime_type)),
+        mime_type: Some(fetched.mime_type.clone()),
+        size_bytes: Some(fetched.size_bytes),
+        source_url: Some(url.to_string()),
+    };
+    store.store(&fetched.data, metadata).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::LocalMediaStore;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one canned HTTP response on a loopback port.
+    async fn serve_once(content_type: &str, body: Vec<u8>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        );
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(&body).await;
+        });
+        format!("http://{addr}/files/photo.png")
+    }
+
+    #[test]
+    fn sniffs_common_signatures() {
+        assert_eq!(sniff_mime_type(b"\x89PNG\r\n\x1a\nrest"), Some("image/png"));
+        assert_eq!(sniff_mime_type(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime_type(b"plain text"), None);
+
+        let allowed = vec!["image/*".to_string(), "application/pdf".to_string()];
+        assert!(mime_allowed("image/png", &allowed));
+        assert!(mime_allowed("application/pdf", &allowed));
+        assert!(!mime_allowed("application/zip", &allowed));
+    }
+
+    #[tokio::test]
+    async fn rejects_disallowed_schemes() {
+        let fetcher = HttpMediaFetcher::new();
+        let err = fetcher
+            .fetch("http://example.com/a.png", &FetchOptions::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not allowed"));
+        assert!(fetcher
+            .fetch("file:///etc/passwd", &FetchOptions::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn fetches_sniffs_and_stores_media() {
+        let png = b"\x89PNG\r\n\x1a\nfake-image".to_vec();
+        let url = serve_once("application/octet-stream", png.clone()).await;
+        let fetcher = HttpMediaFetcher::new().with_allowed_schemes(&["http"]);
+        let tmp = TempDir::new().unwrap();
+        let store = LocalMediaStore::new(tmp.path());
+        let options = FetchOptions {
+            allowed_mime_types: Some(vec!["image/*".into()]),
+            ..FetchOptions::default()
+        };
+
+        let entry = fetch_and_store(&fetcher, &store, &url, &options)
+            .await
+            .unwrap();
+        assert_eq!(entry.metadata.mime_type.as_deref(), Some("image/png"));
+        assert_eq!(entry.metadata.source_url.as_deref(), Some(url.as_str()));
+        assert_eq!(std::fs::read(&entry.path).unwrap(), png);
+        assert_eq!(entry.path.extension().unwrap(), "png");
+    }
+
+    #[tokio::test]
+    async fn enforces_size_limit() {
+        let url = serve_once("image/png", vec![0u8; 2048]).await;
+        let fetcher = HttpMediaFetcher::new().with_allowed_schemes(&["http"]);
+        let options = FetchOptions {
+            max_size_bytes: 1024,
+            ..FetchOptions::default()
+        };
+        let err = fetcher.fetch(&url, &options).await.unwrap_err();
+        assert!(err.to_string().contains("byte limit"));
+    }
+}
@@ -1,5 +1,7 @@
 use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
 use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
 
 use super::traits::{MediaEntry, MediaId, MediaMetadata, MediaStore};
 
@@ -9,12 +11,84 @@ pub struct LocalMediaStore {
     base_dir: PathBuf,
 }
 
+/// A streamed upload went past its size limit; nothing was kept.
+#[derive(Debug, thiserror::Error)]
+#[error("media exceeds the {limit}-byte limit")]
+pub struct MediaTooLarge {
+    pub limit: u64,
+}
+
 impl LocalMediaStore {
     pub fn new(base_dir: &Path) -> Self {
         Self {
             base_dir: base_dir.to_path_buf(),
         }
     }
+
+    /// Write `chunks` straight to disk instead of buffering them, failing
+    /// with [`MediaTooLarge`] once more than `max_bytes` arrive. The file
+    /// only gets its final name when the stream completed, so a rejected or
+    /// broken upload leaves nothing behind.
+    pub async fn store_stream<S, B, E>(
+        &self,
+        mut chunks: S,
+        mut metadata: MediaMetadata,
+        max_bytes: u64,
+    ) -> anyhow::Result<MediaEntry>
+    where
+        S: Stream<Item = Result<B, E>> + Unpin,
+        B: AsRef<[u8]>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        tokio::fs::create_dir_all(&self.base_dir).await?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let partial = self.base_dir.join(format!("{id}.part"));
+        let written = async {
+            let mut file = tokio::fs::File::create(&partial).await?;
+            let mut size: u64 = 0;
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk?;
+                let chunk = chunk.as_ref();
+                size += chunk.len() as u64;
+                if size > max_bytes {
+                    return Err(MediaTooLarge { limit: max_bytes }.into());
+                }
+                file.write_all(chunk).await?;
+            }
+            file.flush().await?;
+            anyhow::Ok(size)
+        }
+        .await;
+        let size = match written {
+            Ok(size) => size,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(e);
+            }
+        };
+
+        let filename = format!("{id}.{}", file_extension(&metadata));
+        let path = self.base_dir.join(&filename);
+        tokio::fs::rename(&partial, &path).await?;
+        metadata.size_bytes = Some(size);
+
+        Ok(MediaEntry {
+            id: MediaId(id),
+            path,
+            metadata,
+            created_at: chrono::Utc::now(),
+        })
+    }
+}
+
+fn file_extension(metadata: &MediaMetadata) -> &str {
+    metadata
+        .filename
+        .as_deref()
+        .and_then(|f| Path::new(f).extension())
+        .and_then(|e| e.to_str())
+        .unwrap_or("bin")
 }
 
 #[async_trait]
@@ -23,13 +97,7 @@ impl MediaStore for LocalMediaStore {
         tokio::fs::create_dir_all(&self.base_dir).await?;
 
         let id = uuid::Uuid::new_v4().to_string();
-        let extension = metadata
-            .filename
-            .as_deref()
-            .and_then(|f| Path::new(f).extension())
-            .and_then(|e| e.to_str())
-            .unwrap_or("bin");
-        let filename = format!("{id}.{extension}");
+        let filename = format!("{id}.{}", file_extension(&metadata));
         let path = self.base_dir.join(&filename);
 
         tokio::fs::write(&path, data).await?;
@@ -168,4 +236,40 @@ mod tests {
         let entries = store.list().await.unwrap();
         assert_eq!(entries.len(), 2);
     }
+
+    #[tokio::test]
+    async fn store_stream_writes_chunks_and_enforces_limit() {
+        let tmp = TempDir::new().unwrap();
+        let store = LocalMediaStore::new(tmp.path());
+        let meta = MediaMetadata {
+            filename: Some("clip.ogg".into()),
+            mime_type: Some("audio/ogg".into()),
+            size_bytes: None,
+            source_url: None,
+        };
+        let chunks = |parts: &[&'static [u8]]| {
+            futures_util::stream::iter(
+                parts
+                    .iter()
+                    .map(|part| Ok::<_, std::io::Error>(*part))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let entry = store
+            .store_stream(chunks(&[b"ab", b"cd"]), meta.clone(), 4)
+            .await
+            .unwrap();
+        assert_eq!(entry.metadata.size_bytes, Some(4));
+        assert!(entry.path.to_string_lossy().ends_with(".ogg"));
+        assert_eq!(std::fs::read(&entry.path).unwrap(), b"abcd");
+
+        let err = store
+            .store_stream(chunks(&[b"ab", b"cd", b"e"]), meta, 4)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<MediaTooLarge>().is_some());
+        // Only the first upload is left; the rejected one was removed.
+        assert_eq!(store.list().await.unwrap().len(), 1);
+    }
 }
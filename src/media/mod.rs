@@ -1,13 +1,20 @@
+pub mod document;
+pub mod http;
 pub mod local;
 pub mod parser;
 pub mod traits;
+pub mod transcription;
+pub mod tts;
 
-pub use local::LocalMediaStore;
+pub use http::{extension_for_mime, HttpMediaFetcher};
+pub use local::{LocalMediaStore, MediaTooLarge};
 pub use parser::DefaultMediaParser;
 pub use traits::{
-    FetchOptions, FetchedMedia, MediaEntry, MediaFetcher, MediaId, MediaMetadata, MediaParser,
-    MediaStore, MediaToken,
+    DocumentFormat, DocumentOptions, FetchOptions, MediaFetcher, MediaId, MediaMetadata,
+    MediaParser, MediaStore,
 };
+pub use transcription::{create_transcriber, transcribe_voice_note};
+pub use tts::{create_speech_synthesizer, SpeechSynthesizer};
 
 use std::path::Path;
 
@@ -18,3 +25,7 @@ pub fn create_media_store(base_dir: &Path) -> Box<dyn MediaStore> {
 pub fn create_media_parser() -> Box<dyn MediaParser> {
     Box::new(DefaultMediaParser)
 }
+
+pub fn create_media_fetcher() -> Box<dyn MediaFetcher> {
+    Box::new(HttpMediaFetcher::new())
+}
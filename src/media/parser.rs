@@ -1,6 +1,7 @@
-use super::traits::{MediaParser, MediaToken};
+use super::traits::{DocumentOptions, MediaParser, MediaToken, ParsedDocument};
 
-/// Default parser that extracts `MEDIA: <path_or_url>` tokens from text.
+/// Default parser that extracts `MEDIA: <path_or_url>` tokens from text and
+/// text from documents (see [`super::document`]).
 pub struct DefaultMediaParser;
 
 impl MediaParser for DefaultMediaParser {
@@ -20,6 +21,16 @@ impl MediaParser for DefaultMediaParser {
         tokens
     }
 
+    fn parse_document(
+        &self,
+        data: &[u8],
+        mime_type: Option<&str>,
+        file_name: Option<&str>,
+        options: &DocumentOptions,
+    ) -> anyhow::Result<ParsedDocument> {
+        super::document::parse_document(data, mime_type, file_name, options)
+    }
+
     fn name(&self) -> &str {
         "default"
     }
@@ -53,6 +64,20 @@ mod tests {
         assert_eq!(tokens.len(), 1);
     }
 
+    #[test]
+    fn parses_plain_text_documents() {
+        let parser = DefaultMediaParser;
+        let doc = parser
+            .parse_document(
+                b"hello\n\nworld",
+                Some("text/plain"),
+                None,
+                &DocumentOptions::default(),
+            )
+            .unwrap();
+        assert_eq!(doc.chunks, vec!["hello\n\nworld"]);
+    }
+
     #[test]
     fn skips_empty_media_value() {
         let parser = DefaultMediaParser;
@@ -56,7 +56,49 @@ pub trait MediaFetcher: Send + Sync {
     fn name(&self) -> &str;
 }
 
+/// Document formats with text extraction support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    PlainText,
+    Markdown,
+    Pdf,
+    Docx,
+    Xlsx,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentOptions {
+    /// Reject inputs larger than this many bytes.
+    pub max_input_bytes: u64,
+    /// Truncate extracted text beyond this many characters.
+    pub max_text_chars: usize,
+    /// Chunk size for memory storage (`[memory].chunk_max_tokens`).
+    pub chunk_max_tokens: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedDocument {
+    pub format: DocumentFormat,
+    pub text: String,
+    pub chunks: Vec<String>,
+    /// `true` when `text` was cut at `max_text_chars`.
+    pub truncated: bool,
+}
+
 pub trait MediaParser: Send + Sync {
     fn parse_tokens(&self, text: &str) -> Vec<MediaToken>;
+
+    /// Extract chunked text from a document (PDF, DOCX, XLSX, text, Markdown).
+    fn parse_document(
+        &self,
+        _data: &[u8],
+        _mime_type: Option<&str>,
+        _file_name: Option<&str>,
+        _options: &DocumentOptions,
+    ) -> anyhow::Result<ParsedDocument> {
+        anyhow::bail!("{} media parser does not extract documents", self.name())
+    }
+
     fn name(&self) -> &str;
 }
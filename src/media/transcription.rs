@@ -0,0 +1,336 @@
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::traits::{MediaMetadata, MediaStore};
+use crate::config::{Config, TranscriptionConfig};
+
+/// Proxy service key for transcription API calls (`[proxy] services`).
+pub const TRANSCRIPTION_SERVICE_KEY: &str = "media.transcription";
+
+const DEFAULT_OPENAI_API_URL: &str = "https://api.openai.com/v1";
+const TRANSCRIPTION_TIMEOUT_SECS: u64 = 120;
+
+/// Speech-to-text backend for voice notes.
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    async fn transcribe(&self, audio: &[u8], mime_type: &str) -> anyhow::Result<String>;
+    fn name(&self) -> &str;
+}
+
+/// OpenAI-compatible `/audio/transcriptions` endpoint.
+pub struct OpenAiTranscriber {
+    api_url: String,
+    api_key: String,
+    model: String,
+    language: Option<String>,
+}
+
+impl OpenAiTranscriber {
+    pub fn new(api_url: Option<&str>, api_key: &str, model: &str, language: Option<&str>) -> Self {
+        Self {
+            api_url: api_url
+                .unwrap_or(DEFAULT_OPENAI_API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+            language: language.map(str::to_string),
+        }
+    }
+}
+
+#[async_trait]
+impl Transcriber for OpenAiTranscriber {
+    async fn transcribe(&self, audio: &[u8], mime_type: &str) -> anyhow::Result<String> {
+        let file = reqwest::multipart::Part::bytes(audio.to_vec())
+            .file_name(format!("voice.{}", audio_extension(mime_type)))
+            .mime_str(base_mime_type(mime_type))?;
+        let mut form = reqwest::multipart::Form::new()
+            .text("model", self.model.clone())
+            .text("response_format", "json")
+            .part("file", file);
+        if let Some(language) = &self.language {
+            form = form.text("language", language.clone());
+        }
+
+        let response = crate::config::build_runtime_proxy_client_with_timeouts(
+            TRANSCRIPTION_SERVICE_KEY,
+            TRANSCRIPTION_TIMEOUT_SECS,
+            10,
+        )
+        .post(format!("{}/audio/transcriptions", self.api_url))
+        .bearer_auth(&self.api_key)
+        .multipart(form)
+        .send()
+        .await
+        .context("Transcription request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Transcription API error ({status}): {}",
+                crate::providers::sanitize_api_error(&body)
+            );
+        }
+        let body: serde_json::Value = response.json().await?;
+        let text = body
+            .get("text")
+            .and_then(serde_json::Value::as_str)
+            .context("Transcription response has no text")?;
+        Ok(text.trim().to_string())
+    }
+
+    fn name(&self) -> &str {
+        "openai"
+    }
+}
+
+/// Local whisper.cpp binary (`whisper-cli -m <model> -f <file>`).
+pub struct WhisperCppTranscriber {
+    binary: String,
+    model: PathBuf,
+    language: Option<String>,
+}
+
+impl WhisperCppTranscriber {
+    pub fn new(binary: &str, model: PathBuf, language: Option<&str>) -> Self {
+        Self {
+            binary: binary.to_string(),
+            model,
+            language: language.map(str::to_string),
+        }
+    }
+}
+
+#[async_trait]
+impl Transcriber for WhisperCppTranscriber {
+    async fn transcribe(&self, audio: &[u8], mime_type: &str) -> anyhow::Result<String> {
+        let input = std::env::temp_dir().join(format!(
+            "zeroclaw-voice-{}.{}",
+            uuid::Uuid::new_v4(),
+            audio_extension(mime_type)
+        ));
+        tokio::fs::write(&input, audio).await?;
+
+        let mut command = tokio::process::Command::new(&self.binary);
+        command
+            .arg("-m")
+            .arg(&self.model)
+            .arg("-f")
+            .arg(&input)
+            .args(["--no-timestamps", "--no-prints"])
+            .kill_on_drop(true);
+        if let Some(language) = &self.language {
+            command.args(["-l", language]);
+        }
+        let output = tokio::time::timeout(
+            Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS),
+            command.output(),
+        )
+        .await;
+        let _ = tokio::fs::remove_file(&input).await;
+
+        let output = output
+            .context("whisper.cpp timed out")?
+            .with_context(|| format!("Failed to run {}", self.binary))?;
+        if !output.status.success() {
+            bail!(
+                "whisper.cpp exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let text = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(text)
+    }
+
+    fn name(&self) -> &str {
+        "whisper_cpp"
+    }
+}
+
+/// Build the configured transcriber, or `None` when transcription is disabled.
+pub fn create_transcriber(config: &Config) -> anyhow::Result<Option<Box<dyn Transcriber>>> {
+    let settings: &TranscriptionConfig = &config.transcription;
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let transcriber: Box<dyn Transcriber> = match settings.backend.as_str() {
+        "openai" => {
+            let api_key = settings
+                .api_key
+                .as_deref()
+                .or(config.api_key.as_deref())
+                .filter(|key| !key.is_empty())
+                .context("transcription.api_key (or api_key) is required for backend \"openai\"")?;
+            Box::new(OpenAiTranscriber::new(
+                settings.api_url.as_deref(),
+                api_key,
+                &settings.model,
+                settings.language.as_deref(),
+            ))
+        }
+        "whisper_cpp" => {
+            let model = settings
+                .whisper_model
+                .as_deref()
+                .context("transcription.whisper_model is required for backend \"whisper_cpp\"")?;
+            Box::new(WhisperCppTranscriber::new(
+                &settings.whisper_binary,
+                PathBuf::from(model),
+                settings.language.as_deref(),
+            ))
+        }
+        other => bail!("Unknown transcription backend: {other}"),
+    };
+    Ok(Some(transcriber))
+}
+
+fn base_mime_type(mime_type: &str) -> &str {
+    mime_type.split(';').next().unwrap_or(mime_type).trim()
+}
+
+fn audio_extension(mime_type: &str) -> &'static str {
+    match base_mime_type(mime_type) {
+        "audio/ogg" | "audio/opus" => "ogg",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/aac" => "aac",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/webm" => "webm",
+        "audio/flac" => "flac",
+        "audio/amr" => "amr",
+        _ => "bin",
+    }
+}
+
+/// Transcribe a voice note and keep the original audio in `store`.
+///
+/// Returns the message content handed to the agent: the transcript followed
+/// by a `MEDIA:` reference to the stored audio, so the session transcript
+/// keeps a pointer to the recording (see [`super::DefaultMediaParser`]).
+pub async fn transcribe_voice_note(
+    transcriber: &dyn Transcriber,
+    store: &dyn MediaStore,
+    audio: &[u8],
+    mime_type: &str,
+    source_url: Option<&str>,
+) -> anyhow::Result<String> {
+    let entry = store
+        .store(
+            audio,
+            MediaMetadata {
+                filename: Some(format!("voice.{}", audio_extension(mime_type))),
+                mime_type: Some(base_mime_type(mime_type).to_string()),
+                size_bytes: Some(audio.len() as u64),
+                source_url: source_url.map(str::to_string),
+            },
+        )
+        .await?;
+    let text = transcriber.transcribe(audio, mime_type).await?;
+    if text.is_empty() {
+        bail!("Transcription returned no text");
+    }
+    tracing::debug!(
+        backend = transcriber.name(),
+        chars = text.len(),
+        "Transcribed voice note"
+    );
+    Ok(format!(
+        "[Voice message] {text}\n\nMEDIA: {}",
+        entry.path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::{DefaultMediaParser, LocalMediaStore, MediaParser};
+    use tempfile::TempDir;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    struct FixedTranscriber(&'static str);
+
+    #[async_trait]
+    impl Transcriber for FixedTranscriber {
+        async fn transcribe(&self, _audio: &[u8], _mime_type: &str) -> anyhow::Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn voice_note_content_has_transcript_and_audio_reference() {
+        let tmp = TempDir::new().unwrap();
+        let store = LocalMediaStore::new(tmp.path());
+        let content = transcribe_voice_note(
+            &FixedTranscriber("remind me to call mom"),
+            &store,
+            b"OggS-audio",
+            "audio/ogg; codecs=opus",
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert!(content.starts_with("[Voice message] remind me to call mom"));
+        let tokens = DefaultMediaParser.parse_tokens(&content);
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].source.ends_with(".ogg"));
+        assert_eq!(std::fs::read(&tokens[0].source).unwrap(), b"OggS-audio");
+    }
+
+    #[tokio::test]
+    async fn openai_transcriber_posts_audio_and_reads_text() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/transcriptions"))
+            .and(header("authorization", "Bearer sk-test"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"text": " hi there "})),
+            )
+            .mount(&server)
+            .await;
+
+        let transcriber = OpenAiTranscriber::new(
+            Some(&format!("{}/v1", server.uri())),
+            "sk-test",
+            "whisper-1",
+            Some("en"),
+        );
+        let text = transcriber.transcribe(b"audio", "audio/ogg").await.unwrap();
+        assert_eq!(text, "hi there");
+    }
+
+    #[test]
+    fn factory_respects_enabled_flag_and_backend_requirements() {
+        let mut config = Config::default();
+        assert!(create_transcriber(&config).unwrap().is_none());
+
+        config.transcription.enabled = true;
+        config.api_key = Some("sk-test".into());
+        assert_eq!(
+            create_transcriber(&config).unwrap().unwrap().name(),
+            "openai"
+        );
+
+        config.transcription.backend = "whisper_cpp".into();
+        assert!(create_transcriber(&config).is_err());
+        config.transcription.whisper_model = Some("/models/ggml-base.bin".into());
+        assert_eq!(
+            create_transcriber(&config).unwrap().unwrap().name(),
+            "whisper_cpp"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+
+use crate::config::{Config, TtsConfig};
+
+/// Proxy service key for text-to-speech API calls (`[proxy] services`).
+pub const TTS_SERVICE_KEY: &str = "media.tts";
+
+const DEFAULT_OPENAI_API_URL: &str = "https://api.openai.com/v1";
+const TTS_TIMEOUT_SECS: u64 = 60;
+
+/// Audio produced by a [`SpeechSynthesizer`].
+#[derive(Debug, Clone)]
+pub struct SynthesizedSpeech {
+    pub audio: Vec<u8>,
+    pub mime_type: String,
+}
+
+/// Text-to-speech backend for voice replies.
+#[async_trait]
+pub trait SpeechSynthesizer: Send + Sync {
+    async fn synthesize(&self, text: &str) -> anyhow::Result<SynthesizedSpeech>;
+    fn name(&self) -> &str;
+}
+
+/// OpenAI-compatible `/audio/speech` endpoint.
+pub struct OpenAiSpeechSynthesizer {
+    api_url: String,
+    api_key: String,
+    model: String,
+    voice: String,
+    format: String,
+    max_chars: usize,
+}
+
+impl OpenAiSpeechSynthesizer {
+    pub fn new(settings: &TtsConfig, api_key: &str) -> Self {
+        Self {
+            api_url: settings
+                .api_url
+                .as_deref()
+                .unwrap_or(DEFAULT_OPENAI_API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: api_key.to_string(),
+            model: settings.model.clone(),
+            voice: settings.voice.clone(),
+            format: settings.format.clone(),
+            max_chars: settings.max_chars,
+        }
+    }
+}
+
+#[async_trait]
+impl SpeechSynthesizer for OpenAiSpeechSynthesizer {
+    async fn synthesize(&self, text: &str) -> anyhow::Result<SynthesizedSpeech> {
+        let text = text.trim();
+        if text.is_empty() {
+            bail!("Nothing to synthesize");
+        }
+        let chars = text.chars().count();
+        if chars > self.max_chars {
+            bail!(
+                "Reply is {chars} characters, exceeding tts.max_chars ({})",
+                self.max_chars
+            );
+        }
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "voice": self.voice,
+            "input": text,
+            "response_format": self.format,
+        });
+        let response = crate::config::build_runtime_proxy_client_with_timeouts(
+            TTS_SERVICE_KEY,
+            TTS_TIMEOUT_SECS,
+            10,
+        )
+        .post(format!("{}/audio/speech", self.api_url))
+        .bearer_auth(&self.api_key)
+        .json(&body)
+        .send()
+        .await
+        .context("Speech synthesis request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Speech API error ({status}): {}",
+                crate::providers::sanitize_api_error(&body)
+            );
+        }
+        let audio = response.bytes().await?.to_vec();
+        if audio.is_empty() {
+            bail!("Speech API returned no audio");
+        }
+        Ok(SynthesizedSpeech {
+            audio,
+            mime_type: speech_mime_type(&self.format).to_string(),
+        })
+    }
+
+    fn name(&self) -> &str {
+        "openai"
+    }
+}
+
+/// MIME type of audio in a `[tts].format`.
+pub fn speech_mime_type(format: &str) -> &'static str {
+    match format {
+        "opus" => "audio/ogg",
+        "mp3" => "audio/mpeg",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Build the configured speech synthesizer from `[tts]`.
+pub fn create_speech_synthesizer(config: &Config) -> anyhow::Result<Box<dyn SpeechSynthesizer>> {
+    let settings = &config.tts;
+    match settings.provider.as_str() {
+        "openai" => {
+            let api_key = settings
+                .api_key
+                .as_deref()
+                .or(config.api_key.as_deref())
+                .filter(|key| !key.is_empty())
+                .context("tts.api_key (or api_key) is required for provider \"openai\"")?;
+            Ok(Box::new(OpenAiSpeechSynthesizer::new(settings, api_key)))
+        }
+        other => bail!("Unknown TTS provider: {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn openai_synthesizer_posts_text_and_returns_audio() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/audio/speech"))
+            .and(header("authorization", "Bearer sk-test"))
+            .and(body_partial_json(
+                serde_json::json!({"voice": "nova", "input": "hello"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"OggS-speech".to_vec()))
+            .mount(&server)
+            .await;
+
+        let settings = TtsConfig {
+            api_url: Some(format!("{}/v1", server.uri())),
+            voice: "nova".into(),
+            ..TtsConfig::default()
+        };
+        let synthesizer = OpenAiSpeechSynthesizer::new(&settings, "sk-test");
+        let speech = synthesizer.synthesize(" hello ").await.unwrap();
+        assert_eq!(speech.audio, b"OggS-speech");
+        assert_eq!(speech.mime_type, "audio/ogg");
+
+        let limited = OpenAiSpeechSynthesizer::new(
+            &TtsConfig {
+                max_chars: 3,
+                ..settings
+            },
+            "sk-test",
+        );
+        let err = limited.synthesize("hello").await.unwrap_err();
+        assert!(err.to_string().contains("max_chars"));
+    }
+
+    #[test]
+    fn factory_requires_api_key() {
+        let mut config = Config::default();
+        assert!(create_speech_synthesizer(&config).is_err());
+
+        config.tts.api_key = Some("sk-tts".into());
+        assert_eq!(create_speech_synthesizer(&config).unwrap().name(), "openai");
+    }
+}
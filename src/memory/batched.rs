@@ -0,0 +1,240 @@
+//! Write batching for high-frequency memory stores (`[memory] write_batch_window_ms`).
+//!
+//! With `auto_save` on, busy channels store a memory per message. This
+//! wrapper buffers `store` calls and writes them in one `store_batch` (one
+//! SQLite transaction) once the window elapses or the buffer fills. Every
+//! read first flushes the buffer, so a recall right after a store still sees
+//! it; runtimes call [`Memory::flush`] on shutdown.
+
+use super::entities::EntityNode;
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryWrite};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct BatchedMemory {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    inner: Arc<dyn Memory>,
+    window: Duration,
+    max_entries: usize,
+    pending: Mutex<Vec<MemoryWrite>>,
+    /// A delayed flush is already scheduled for the current buffer.
+    scheduled: AtomicBool,
+    /// Serializes flushes so a read waits for writes already in flight.
+    flushing: tokio::sync::Mutex<()>,
+}
+
+impl BatchedMemory {
+    pub fn new(inner: Arc<dyn Memory>, window: Duration, max_entries: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                inner,
+                window,
+                max_entries: max_entries.max(1),
+                pending: Mutex::new(Vec::new()),
+                scheduled: AtomicBool::new(false),
+                flushing: tokio::sync::Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Buffered writes not yet handed to the backend.
+    pub fn pending(&self) -> usize {
+        self.shared.pending.lock().len()
+    }
+}
+
+impl Shared {
+    async fn flush(&self) -> anyhow::Result<()> {
+        let _guard = self.flushing.lock().await;
+        self.scheduled.store(false, Ordering::SeqCst);
+        let writes = std::mem::take(&mut *self.pending.lock());
+        if writes.is_empty() {
+            return Ok(());
+        }
+        let count = writes.len();
+        self.inner.store_batch(writes).await.inspect_err(|e| {
+            tracing::warn!("Failed to write {count} buffered memory entries: {e:#}");
+        })
+    }
+
+    fn schedule_flush(self: &Arc<Self>) {
+        if self.scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // Without a runtime the buffer is written by the next read or
+            // explicit flush.
+            self.scheduled.store(false, Ordering::SeqCst);
+            return;
+        };
+        let shared = Arc::clone(self);
+        runtime.spawn(async move {
+            tokio::time::sleep(shared.window).await;
+            let _ = shared.flush().await;
+        });
+    }
+}
+
+#[async_trait]
+impl Memory for BatchedMemory {
+    fn name(&self) -> &str {
+        self.shared.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let full = {
+            let mut pending = self.shared.pending.lock();
+            pending.push(MemoryWrite {
+                key: key.to_string(),
+                content: content.to_string(),
+                category,
+                session_id: session_id.map(String::from),
+            });
+            pending.len() >= self.shared.max_entries
+        };
+        if full {
+            self.shared.flush().await
+        } else {
+            self.shared.schedule_flush();
+            Ok(())
+        }
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.shared.flush().await?;
+        self.shared.inner.recall(query, limit, session_id).await
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.shared.flush().await?;
+        self.shared.inner.get(key).await
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.shared.flush().await?;
+        self.shared.inner.list(category, session_id).await
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        // A buffered store of the same key must not land after the delete.
+        self.shared.flush().await?;
+        self.shared.inner.forget(key).await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        self.shared.flush().await?;
+        self.shared.inner.count().await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.shared.inner.health_check().await
+    }
+
+    async fn store_batch(&self, writes: Vec<MemoryWrite>) -> anyhow::Result<()> {
+        self.shared.flush().await?;
+        self.shared.inner.store_batch(writes).await
+    }
+
+    async fn flush(&self) -> anyhow::Result<()> {
+        self.shared.flush().await?;
+        self.shared.inner.flush().await
+    }
+
+    async fn entity(&self, name: &str) -> anyhow::Result<Option<EntityNode>> {
+        self.shared.flush().await?;
+        self.shared.inner.entity(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    fn batched(
+        tmp: &TempDir,
+        window: Duration,
+        max_entries: usize,
+    ) -> (Arc<dyn Memory>, BatchedMemory) {
+        let backend: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let batched = BatchedMemory::new(Arc::clone(&backend), window, max_entries);
+        (backend, batched)
+    }
+
+    #[tokio::test]
+    async fn reads_see_buffered_writes() {
+        let tmp = TempDir::new().unwrap();
+        let (backend, mem) = batched(&tmp, Duration::from_secs(60), 100);
+
+        mem.store("lang", "Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("editor", "helix", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(mem.pending(), 2);
+        assert_eq!(backend.count().await.unwrap(), 0);
+
+        let entry = mem.get("lang").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Rust");
+        assert_eq!(mem.pending(), 0);
+        assert_eq!(backend.count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn window_and_size_limit_trigger_flushes() {
+        let tmp = TempDir::new().unwrap();
+        let (backend, mem) = batched(&tmp, Duration::from_millis(20), 3);
+
+        mem.store("a", "1", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(mem.pending(), 0);
+        assert_eq!(backend.count().await.unwrap(), 1);
+
+        let (backend, mem) = batched(&tmp, Duration::from_secs(60), 3);
+        for key in ["b", "c", "d"] {
+            mem.store(key, "x", MemoryCategory::Daily, None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(mem.pending(), 0);
+        assert_eq!(backend.count().await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn forget_is_not_undone_by_a_buffered_store() {
+        let tmp = TempDir::new().unwrap();
+        let (backend, mem) = batched(&tmp, Duration::from_secs(60), 100);
+
+        mem.store("temp", "draft", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+        assert!(mem.forget("temp").await.unwrap());
+        mem.flush().await.unwrap();
+        assert!(backend.get("temp").await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,100 @@
+//! Split long text into memory-sized chunks (`[memory].chunk_max_tokens`).
+
+/// Rough characters-per-token ratio used to size chunks without a tokenizer.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Approximate token count of `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Split `text` into chunks of at most `max_tokens` (estimated).
+///
+/// Paragraphs (blank-line separated) are packed together while they fit;
+/// oversized paragraphs are split by line, then at whitespace, and only as a
+/// last resort mid-word. Empty input yields no chunks.
+pub fn chunk_text(text: &str, max_tokens: usize) -> Vec<String> {
+    let max_chars = max_tokens.max(1) * CHARS_PER_TOKEN;
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for piece in split_oversized(paragraph, max_chars) {
+            let needed = piece.chars().count() + if current.is_empty() { 0 } else { 2 };
+            if !current.is_empty() && current.chars().count() + needed > max_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn split_oversized(paragraph: &str, max_chars: usize) -> Vec<String> {
+    if paragraph.chars().count() <= max_chars {
+        return vec![paragraph.to_string()];
+    }
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for line in paragraph.lines() {
+        for word in line.split_inclusive(char::is_whitespace) {
+            if current.chars().count() + word.chars().count() > max_chars && !current.is_empty() {
+                pieces.push(current.trim_end().to_string());
+                current.clear();
+            }
+            if word.chars().count() > max_chars {
+                let chars: Vec<char> = word.chars().collect();
+                for part in chars.chunks(max_chars) {
+                    pieces.push(part.iter().collect());
+                }
+                continue;
+            }
+            current.push_str(word);
+        }
+        if !current.ends_with(char::is_whitespace) && !current.is_empty() {
+            current.push('\n');
+        }
+    }
+    if !current.trim().is_empty() {
+        pieces.push(current.trim_end().to_string());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_paragraphs_up_to_the_limit() {
+        let text = "alpha beta\n\ngamma delta\n\n\nepsilon";
+        assert_eq!(
+            chunk_text(text, 100),
+            vec!["alpha beta\n\ngamma delta\n\nepsilon"]
+        );
+
+        let chunks = chunk_text(text, 3);
+        assert_eq!(chunks, vec!["alpha beta", "gamma delta", "epsilon"]);
+        assert!(chunk_text("  \n\n ", 10).is_empty());
+    }
+
+    #[test]
+    fn splits_oversized_paragraphs_within_budget() {
+        let paragraph = "word ".repeat(200);
+        let chunks = chunk_text(&paragraph, 10);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 40));
+        assert_eq!(chunks.concat().matches("word").count(), 200);
+
+        let long_word = "x".repeat(100);
+        let chunks = chunk_text(&long_word, 5);
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(estimate_tokens(&chunks[0]), 5);
+    }
+}
@@ -0,0 +1,278 @@
+//! Embedding providers for vector memory search.
+//!
+//! Embeddings are configured separately from chat: `memory.embedding_provider`
+//! and `memory.embedding_model` pick the backend directly, or
+//! `memory.embedding_model = "hint:<name>"` selects an `[[embedding_routes]]`
+//! entry with its own provider, base URL and API key (e.g. chat via
+//! Anthropic, embeddings via OpenAI or a local server).
+
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use super::sqlite::{EmbeddingProvider, NoopEmbedding};
+use crate::config::{EmbeddingRouteConfig, MemoryConfig};
+
+/// Proxy service key for embedding API calls (`[proxy] services`).
+pub const EMBEDDINGS_SERVICE_KEY: &str = "memory.embeddings";
+
+/// Prefix on `memory.embedding_model` that selects a named embedding route.
+pub const EMBEDDING_ROUTE_HINT_PREFIX: &str = "hint:";
+
+const DEFAULT_OPENAI_API_URL: &str = "https://api.openai.com/v1";
+const EMBEDDINGS_TIMEOUT_SECS: u64 = 60;
+
+/// OpenAI-compatible `/embeddings` endpoint (OpenAI, Ollama, LM Studio, vLLM, ...).
+pub struct OpenAiEmbedding {
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+    dimensions: usize,
+}
+
+impl OpenAiEmbedding {
+    pub fn new(
+        api_url: Option<&str>,
+        api_key: Option<&str>,
+        model: &str,
+        dimensions: usize,
+    ) -> Self {
+        Self {
+            api_url: api_url
+                .unwrap_or(DEFAULT_OPENAI_API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            api_key: api_key.map(str::to_string),
+            model: model.to_string(),
+            dimensions,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbedding {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut request = crate::config::build_runtime_proxy_client_with_timeouts(
+            EMBEDDINGS_SERVICE_KEY,
+            EMBEDDINGS_TIMEOUT_SECS,
+            10,
+        )
+        .post(format!("{}/embeddings", self.api_url))
+        .json(&serde_json::json!({ "model": self.model, "input": texts }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.context("Embedding request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Embedding API error ({status}): {}",
+                crate::providers::sanitize_api_error(&body)
+            );
+        }
+        let mut body: EmbeddingsResponse = response.json().await?;
+        if body.data.len() != texts.len() {
+            bail!(
+                "Embedding API returned {} vectors for {} inputs",
+                body.data.len(),
+                texts.len()
+            );
+        }
+        body.data.sort_by_key(|item| item.index);
+        Ok(body.data.into_iter().map(|item| item.embedding).collect())
+    }
+}
+
+/// Effective embedding settings once an `[[embedding_routes]]` hint is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingSettings {
+    /// `"none"`, `"openai"` or `"custom:<base URL>"`.
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    /// Base URL override; wins over the provider's default.
+    pub api_url: Option<String>,
+    pub api_key: Option<String>,
+}
+
+/// Resolve `memory.embedding_*` against `routes`.
+///
+/// A route only uses its own `api_key` (or the provider's environment key), so
+/// the chat credential is never sent to a different embedding provider; without
+/// a route the chat `api_key` is reused as before.
+pub fn resolve_embedding_settings(
+    memory: &MemoryConfig,
+    routes: &[EmbeddingRouteConfig],
+    chat_api_key: Option<&str>,
+) -> anyhow::Result<EmbeddingSettings> {
+    let Some(hint) = memory
+        .embedding_model
+        .trim()
+        .strip_prefix(EMBEDDING_ROUTE_HINT_PREFIX)
+    else {
+        return Ok(EmbeddingSettings {
+            provider: memory.embedding_provider.trim().to_string(),
+            model: memory.embedding_model.trim().to_string(),
+            dimensions: memory.embedding_dimensions,
+            api_url: None,
+            api_key: chat_api_key.map(str::to_string),
+        });
+    };
+    let hint = hint.trim();
+    let route = routes
+        .iter()
+        .find(|route| route.hint.trim() == hint)
+        .with_context(|| {
+            format!("memory.embedding_model references unknown embedding route '{hint}'")
+        })?;
+    Ok(EmbeddingSettings {
+        provider: route.provider.trim().to_string(),
+        model: route.model.trim().to_string(),
+        dimensions: route.dimensions.unwrap_or(memory.embedding_dimensions),
+        api_url: route.api_url.clone(),
+        api_key: route.api_key.clone(),
+    })
+}
+
+/// Check that `provider` is one [`create_embedding_provider`] accepts.
+pub fn validate_embedding_provider(provider: &str) -> anyhow::Result<()> {
+    match provider.trim() {
+        "none" | "openai" => Ok(()),
+        other => match other.strip_prefix("custom:") {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => Ok(()),
+            _ => bail!(
+                "unsupported embedding provider '{other}' (expected none, openai or custom:<http(s) URL>)"
+            ),
+        },
+    }
+}
+
+/// Build the embedder described by `settings`.
+pub fn create_embedding_provider(
+    settings: &EmbeddingSettings,
+) -> anyhow::Result<Arc<dyn EmbeddingProvider>> {
+    validate_embedding_provider(&settings.provider)?;
+    let (default_url, api_key) = match settings.provider.as_str() {
+        "none" => return Ok(Arc::new(NoopEmbedding)),
+        "openai" => (
+            None,
+            crate::providers::resolve_provider_credential("openai", settings.api_key.as_deref()),
+        ),
+        custom => (custom.strip_prefix("custom:"), settings.api_key.clone()),
+    };
+    if settings.model.is_empty() {
+        bail!("embedding model must not be empty");
+    }
+    if settings.dimensions == 0 {
+        bail!("embedding dimensions must be greater than 0");
+    }
+    Ok(Arc::new(OpenAiEmbedding::new(
+        settings.api_url.as_deref().or(default_url),
+        api_key.as_deref().filter(|key| !key.is_empty()),
+        &settings.model,
+        settings.dimensions,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn route(hint: &str, provider: &str) -> EmbeddingRouteConfig {
+        EmbeddingRouteConfig {
+            hint: hint.into(),
+            provider: provider.into(),
+            model: "nomic-embed-text".into(),
+            dimensions: Some(768),
+            api_url: None,
+            api_key: Some("route-key".into()),
+        }
+    }
+
+    #[test]
+    fn hint_selects_route_with_its_own_key() {
+        let mut memory = MemoryConfig {
+            embedding_provider: "openai".into(),
+            ..MemoryConfig::default()
+        };
+        let routes = [route("local", "custom:http://127.0.0.1:11434/v1")];
+
+        let direct = resolve_embedding_settings(&memory, &routes, Some("chat-key")).unwrap();
+        assert_eq!(direct.provider, "openai");
+        assert_eq!(direct.api_key.as_deref(), Some("chat-key"));
+
+        memory.embedding_model = "hint:local".into();
+        let routed = resolve_embedding_settings(&memory, &routes, Some("chat-key")).unwrap();
+        assert_eq!(routed.provider, "custom:http://127.0.0.1:11434/v1");
+        assert_eq!(routed.model, "nomic-embed-text");
+        assert_eq!(routed.dimensions, 768);
+        assert_eq!(routed.api_key.as_deref(), Some("route-key"));
+
+        memory.embedding_model = "hint:missing".into();
+        assert!(resolve_embedding_settings(&memory, &routes, None).is_err());
+    }
+
+    #[test]
+    fn validate_embedding_provider_accepts_known_names() {
+        assert!(validate_embedding_provider("none").is_ok());
+        assert!(validate_embedding_provider("openai").is_ok());
+        assert!(validate_embedding_provider("custom:https://embed.example.com/v1").is_ok());
+        assert!(validate_embedding_provider("custom:embed.example.com").is_err());
+        assert!(validate_embedding_provider("anthropic").is_err());
+    }
+
+    #[tokio::test]
+    async fn openai_embedding_posts_inputs_and_orders_vectors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/embeddings"))
+            .and(header("authorization", "Bearer route-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {"index": 1, "embedding": [0.0, 1.0]},
+                    {"index": 0, "embedding": [1.0, 0.0]}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let embedder = create_embedding_provider(&EmbeddingSettings {
+            provider: format!("custom:{}/v1", server.uri()),
+            model: "nomic-embed-text".into(),
+            dimensions: 2,
+            api_url: None,
+            api_key: Some("route-key".into()),
+        })
+        .unwrap();
+        let vectors = embedder.embed(&["first", "second"]).await.unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    }
+}
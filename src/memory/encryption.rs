@@ -0,0 +1,118 @@
+//! At-rest encryption of memory content (`[memory] encrypt = true`).
+//!
+//! Memory content is sealed with the secret store key (`.secret_key` next to
+//! `config.toml`, ChaCha20-Poly1305, the same `enc2:` format as encrypted
+//! config secrets). Keys, categories, timestamps and embedding vectors stay
+//! in the clear so upserts, filters and vector search keep working; keyword
+//! recall decrypts and scans in process instead of using the FTS index.
+//! `zeroclaw memory encrypt` / `decrypt` migrate existing entries.
+
+use crate::security::SecretStore;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+static RUNTIME_KEY_DIR: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+
+fn runtime_key_dir_state() -> &'static RwLock<Option<PathBuf>> {
+    RUNTIME_KEY_DIR.get_or_init(|| RwLock::new(None))
+}
+
+/// Record the directory holding the secret store key (the config directory),
+/// so memory factories that only see `[memory]` can build a cipher.
+pub fn set_runtime_key_dir(dir: Option<&Path>) {
+    match runtime_key_dir_state().write() {
+        Ok(mut guard) => *guard = dir.map(Path::to_path_buf),
+        Err(poisoned) => *poisoned.into_inner() = dir.map(Path::to_path_buf),
+    }
+}
+
+fn runtime_key_dir() -> Option<PathBuf> {
+    match runtime_key_dir_state().read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Seals memory content on write (when enabled) and opens sealed content on
+/// read. Plaintext rows pass through, so a half-migrated database still reads.
+#[derive(Debug, Clone)]
+pub struct ContentCipher {
+    store: SecretStore,
+    seal_writes: bool,
+}
+
+impl ContentCipher {
+    /// Cipher keyed from `key_dir/.secret_key` (created on first use).
+    pub fn new(key_dir: &Path, seal_writes: bool) -> Self {
+        Self {
+            store: SecretStore::new(key_dir, true),
+            seal_writes,
+        }
+    }
+
+    /// Cipher for the runtime key directory, if one was recorded.
+    pub fn from_runtime(seal_writes: bool) -> Option<Self> {
+        runtime_key_dir().map(|dir| Self::new(&dir, seal_writes))
+    }
+
+    /// Whether new content is written encrypted.
+    pub fn seals_writes(&self) -> bool {
+        self.seal_writes
+    }
+
+    pub fn is_sealed(stored: &str) -> bool {
+        SecretStore::is_secure_encrypted(stored)
+    }
+
+    /// Content as it should be stored.
+    pub fn seal(&self, content: &str) -> Result<String> {
+        if !self.seal_writes || content.is_empty() {
+            return Ok(content.to_string());
+        }
+        self.store.encrypt(content)
+    }
+
+    /// Encrypt regardless of the write setting (migration).
+    pub fn seal_always(&self, content: &str) -> Result<String> {
+        if content.is_empty() || Self::is_sealed(content) {
+            return Ok(content.to_string());
+        }
+        self.store.encrypt(content)
+    }
+
+    /// Plaintext of stored content.
+    pub fn open(&self, stored: &str) -> Result<String> {
+        if !Self::is_sealed(stored) {
+            return Ok(stored.to_string());
+        }
+        self.store.decrypt(stored).context(
+            "Failed to decrypt memory content (is .secret_key the one it was encrypted with?)",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn seals_only_when_enabled_and_opens_both_forms() {
+        let tmp = TempDir::new().unwrap();
+        let sealing = ContentCipher::new(tmp.path(), true);
+        let sealed = sealing.seal("likes green tea").unwrap();
+        assert!(ContentCipher::is_sealed(&sealed));
+        assert!(!sealed.contains("green"));
+
+        let reading = ContentCipher::new(tmp.path(), false);
+        assert_eq!(reading.seal("plain").unwrap(), "plain");
+        assert_eq!(reading.open(&sealed).unwrap(), "likes green tea");
+        assert_eq!(reading.open("enc: not ours").unwrap(), "enc: not ours");
+
+        let other = TempDir::new().unwrap();
+        assert!(ContentCipher::new(other.path(), false)
+            .open(&sealed)
+            .is_err());
+    }
+}
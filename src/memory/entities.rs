@@ -0,0 +1,464 @@
+//! Entity graph over memory entries (`[memory] entity_graph`).
+//!
+//! On each write, [`extract`] picks out capitalised names and classifies them
+//! as people, projects or places from the words around them ("works on X",
+//! "in X", "the X project"). The SQLite backend keeps them in an `entities`
+//! table with `entity_relations` edges:
+//!
+//! - `mentioned_in`: entity → memory key that names it
+//! - `works_on`: person → project, from "<person> works on <project>"
+//!
+//! Every edge records the memory key it came from, so rewriting or
+//! forgetting an entry replaces or drops its edges. Extraction is a cheap
+//! heuristic, not NER: it favours precision on notes like "Alice works on
+//! Zeroclaw from Berlin" over recall on free-form prose.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Longest run of capitalised words taken as one name ("New York City").
+const MAX_NAME_WORDS: usize = 4;
+
+/// Capitalised words that start sentences or name times, never entities.
+const STOPWORDS: &[&str] = &[
+    "a",
+    "about",
+    "after",
+    "also",
+    "an",
+    "and",
+    "as",
+    "at",
+    "but",
+    "do",
+    "for",
+    "from",
+    "he",
+    "her",
+    "his",
+    "i",
+    "i'm",
+    "if",
+    "in",
+    "it",
+    "its",
+    "let",
+    "maybe",
+    "my",
+    "no",
+    "not",
+    "note",
+    "ok",
+    "on",
+    "our",
+    "please",
+    "remember",
+    "she",
+    "so",
+    "thanks",
+    "that",
+    "the",
+    "their",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "to",
+    "today",
+    "tomorrow",
+    "user",
+    "we",
+    "what",
+    "when",
+    "where",
+    "who",
+    "why",
+    "yes",
+    "yesterday",
+    "you",
+    "your",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Words before a name that make it a place.
+const PLACE_CUES: &[&str] = &["in", "at", "from", "to", "near", "visiting", "visit"];
+
+/// Verbs that, followed by `on`, make the next name a project.
+const WORK_VERBS: &[&str] = &[
+    "works", "work", "working", "worked", "leads", "lead", "leading",
+];
+
+/// Words next to a name that make it a project.
+const PROJECT_NOUNS: &[&str] = &[
+    "project",
+    "repo",
+    "repository",
+    "app",
+    "service",
+    "codebase",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Person,
+    Project,
+    Place,
+}
+
+impl EntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Person => "person",
+            Self::Project => "project",
+            Self::Place => "place",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "project" => Self::Project,
+            "place" => Self::Place,
+            _ => Self::Person,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// Entity → memory key.
+    MentionedIn,
+    /// Person → project.
+    WorksOn,
+}
+
+impl Relation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MentionedIn => "mentioned_in",
+            Self::WorksOn => "works_on",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mentioned_in" => Some(Self::MentionedIn),
+            "works_on" => Some(Self::WorksOn),
+            _ => None,
+        }
+    }
+}
+
+/// Entities and relations found in one memory entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extraction {
+    pub entities: Vec<(String, EntityKind)>,
+    /// `(person, project)` pairs.
+    pub works_on: Vec<(String, String)>,
+}
+
+/// One edge of an entity, seen from that entity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityEdge {
+    pub relation: Relation,
+    /// The other entity, or the memory key for `mentioned_in`.
+    pub other: String,
+    /// `true` when the edge points at this entity (a project's workers).
+    pub incoming: bool,
+    /// Memory entry the edge was extracted from.
+    pub memory_key: String,
+}
+
+/// An entity and all its edges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityNode {
+    pub name: String,
+    pub kind: EntityKind,
+    pub edges: Vec<EntityEdge>,
+}
+
+struct Word<'a> {
+    text: &'a str,
+    /// Punctuation after the word ends a name run.
+    breaks: bool,
+}
+
+fn words(sentence: &str) -> Vec<Word<'_>> {
+    sentence
+        .split_whitespace()
+        .filter_map(|raw| {
+            let text = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-');
+            let text = text
+                .strip_suffix("'s")
+                .unwrap_or(text)
+                .trim_matches(|c: char| c == '\'' || c == '-');
+            (!text.is_empty()).then(|| Word {
+                text,
+                breaks: raw.ends_with([',', ';', ':', ')', '"']),
+            })
+        })
+        .collect()
+}
+
+fn is_name_word(word: &str) -> bool {
+    word.chars().next().is_some_and(char::is_uppercase)
+        && !STOPWORDS.contains(&word.to_lowercase().as_str())
+}
+
+/// Find people, projects and places in `content`.
+pub fn extract(content: &str) -> Extraction {
+    let mut out = Extraction::default();
+    for sentence in content.split(['.', '!', '?', '\n']) {
+        let words = words(sentence);
+        let lower: Vec<String> = words.iter().map(|w| w.text.to_lowercase()).collect();
+        let mut last_person: Option<String> = None;
+        let mut i = 0;
+        while i < words.len() {
+            if !is_name_word(words[i].text) {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < words.len()
+                && i - start < MAX_NAME_WORDS
+                && is_name_word(words[i].text)
+                && (i == start || !words[i - 1].breaks)
+            {
+                i += 1;
+            }
+            let name = words[start..i]
+                .iter()
+                .map(|w| w.text)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let before = start.checked_sub(1).map(|j| lower[j].as_str());
+            let before2 = start.checked_sub(2).map(|j| lower[j].as_str());
+            let after = lower.get(i).map(String::as_str);
+            let works_on = before == Some("on") && before2.is_some_and(|w| WORK_VERBS.contains(&w));
+            let kind = if works_on
+                || before.is_some_and(|w| PROJECT_NOUNS.contains(&w))
+                || after.is_some_and(|w| PROJECT_NOUNS.contains(&w))
+            {
+                EntityKind::Project
+            } else if before.is_some_and(|w| PLACE_CUES.contains(&w)) {
+                EntityKind::Place
+            } else {
+                EntityKind::Person
+            };
+            if works_on {
+                if let Some(person) = &last_person {
+                    let pair = (person.clone(), name.clone());
+                    if !out.works_on.contains(&pair) {
+                        out.works_on.push(pair);
+                    }
+                }
+            }
+            if kind == EntityKind::Person {
+                last_person = Some(name.clone());
+            }
+            if !out
+                .entities
+                .iter()
+                .any(|(seen, _)| seen.eq_ignore_ascii_case(&name))
+            {
+                out.entities.push((name, kind));
+            }
+        }
+    }
+    out
+}
+
+/// Create the entity tables.
+pub(super) fn init_schema(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS entities (
+            name        TEXT PRIMARY KEY COLLATE NOCASE,
+            kind        TEXT NOT NULL,
+            created_at  TEXT NOT NULL,
+            updated_at  TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS entity_relations (
+            source      TEXT NOT NULL COLLATE NOCASE,
+            relation    TEXT NOT NULL,
+            target      TEXT NOT NULL COLLATE NOCASE,
+            memory_key  TEXT NOT NULL,
+            created_at  TEXT NOT NULL,
+            UNIQUE(source, relation, target, memory_key)
+        );
+        CREATE INDEX IF NOT EXISTS idx_entity_relations_source ON entity_relations(source);
+        CREATE INDEX IF NOT EXISTS idx_entity_relations_target ON entity_relations(target);
+        CREATE INDEX IF NOT EXISTS idx_entity_relations_key ON entity_relations(memory_key);",
+    )?;
+    Ok(())
+}
+
+/// Replace the edges extracted from `memory_key` with `extraction`'s.
+pub(super) fn record(
+    conn: &Connection,
+    memory_key: &str,
+    extraction: &Extraction,
+    now: &str,
+) -> anyhow::Result<()> {
+    forget(conn, memory_key)?;
+    for (name, kind) in &extraction.entities {
+        conn.execute(
+            "INSERT INTO entities (name, kind, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)
+             ON CONFLICT(name) DO UPDATE SET updated_at = excluded.updated_at",
+            params![name, kind.as_str(), now],
+        )?;
+        conn.execute(
+            "INSERT OR IGNORE INTO entity_relations (source, relation, target, memory_key, created_at)
+             VALUES (?1, ?2, ?3, ?3, ?4)",
+            params![name, Relation::MentionedIn.as_str(), memory_key, now],
+        )?;
+    }
+    for (person, project) in &extraction.works_on {
+        conn.execute(
+            "INSERT OR IGNORE INTO entity_relations (source, relation, target, memory_key, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![person, Relation::WorksOn.as_str(), project, memory_key, now],
+        )?;
+    }
+    Ok(())
+}
+
+/// Drop the edges extracted from `memory_key`, and entities left without any.
+pub(super) fn forget(conn: &Connection, memory_key: &str) -> anyhow::Result<()> {
+    conn.execute(
+        "DELETE FROM entity_relations WHERE memory_key = ?1",
+        params![memory_key],
+    )?;
+    conn.execute(
+        "DELETE FROM entities WHERE name NOT IN (SELECT source FROM entity_relations)
+         AND name NOT IN (SELECT target FROM entity_relations)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// `name` (case-insensitive) and its edges.
+pub(super) fn lookup(conn: &Connection, name: &str) -> anyhow::Result<Option<EntityNode>> {
+    let Some((name, kind)) = conn
+        .query_row(
+            "SELECT name, kind FROM entities WHERE name = ?1",
+            params![name.trim()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let mut stmt = conn.prepare(
+        "SELECT relation, target, 0, memory_key FROM entity_relations WHERE source = ?1
+         UNION ALL
+         SELECT relation, source, 1, memory_key FROM entity_relations
+         WHERE target = ?1 AND relation != 'mentioned_in'
+         ORDER BY 1, 2",
+    )?;
+    let edges = stmt
+        .query_map(params![name], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .filter_map(std::result::Result::ok)
+        .filter_map(|(relation, other, incoming, memory_key)| {
+            Some(EntityEdge {
+                relation: Relation::parse(&relation)?,
+                other,
+                incoming,
+                memory_key,
+            })
+        })
+        .collect();
+    Ok(Some(EntityNode {
+        name,
+        kind: EntityKind::parse(&kind),
+        edges,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_classifies_people_projects_and_places() {
+        let found = extract(
+            "Alice Chen works on Zeroclaw from Berlin. Today Bob joined the Atlas project, \
+             and Alice's sister Carol lives in Lisbon.",
+        );
+        assert_eq!(
+            found.entities,
+            vec![
+                ("Alice Chen".into(), EntityKind::Person),
+                ("Zeroclaw".into(), EntityKind::Project),
+                ("Berlin".into(), EntityKind::Place),
+                ("Bob".into(), EntityKind::Person),
+                ("Atlas".into(), EntityKind::Project),
+                ("Alice".into(), EntityKind::Person),
+                ("Carol".into(), EntityKind::Person),
+                ("Lisbon".into(), EntityKind::Place),
+            ]
+        );
+        assert_eq!(
+            found.works_on,
+            vec![("Alice Chen".into(), "Zeroclaw".into())]
+        );
+        assert_eq!(
+            extract("the user prefers tea on Monday"),
+            Extraction::default()
+        );
+    }
+
+    #[test]
+    fn edges_follow_their_memory_entry() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let now = "2026-01-01T00:00:00Z";
+        record(&conn, "team", &extract("Alice works on Zeroclaw"), now).unwrap();
+        record(&conn, "trip", &extract("She flew to Lisbon"), now).unwrap();
+
+        let alice = lookup(&conn, "alice").unwrap().unwrap();
+        assert_eq!(alice.name, "Alice");
+        assert_eq!(alice.kind, EntityKind::Person);
+        assert_eq!(alice.edges.len(), 2);
+        let project = lookup(&conn, "Zeroclaw").unwrap().unwrap();
+        assert!(project.edges.contains(&EntityEdge {
+            relation: Relation::WorksOn,
+            other: "Alice".into(),
+            incoming: true,
+            memory_key: "team".into(),
+        }));
+
+        record(&conn, "team", &extract("Alice moved teams"), now).unwrap();
+        assert!(lookup(&conn, "Zeroclaw").unwrap().is_none());
+        forget(&conn, "team").unwrap();
+        assert!(lookup(&conn, "Alice").unwrap().is_none());
+        assert!(lookup(&conn, "Lisbon").unwrap().is_some());
+    }
+}
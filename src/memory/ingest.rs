@@ -0,0 +1,280 @@
+//! `zeroclaw ingest` — load documents into memory for grounded answers.
+//!
+//! Documents are parsed by the media parser, split into
+//! `[memory].chunk_max_tokens` chunks and stored under the `documents`
+//! category. Each chunk key and header records its source, so recalled
+//! passages can be cited. Embeddings are computed by the memory backend's
+//! embedder when one is configured.
+
+use anyhow::Context;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use super::traits::{Memory, MemoryCategory};
+use crate::config::Config;
+use crate::media::{
+    DocumentFormat, DocumentOptions, FetchOptions, HttpMediaFetcher, MediaFetcher, MediaParser,
+};
+
+/// Memory category holding ingested document chunks.
+pub const DOCUMENT_CATEGORY: &str = "documents";
+
+/// Outcome of ingesting one document.
+#[derive(Debug, Clone)]
+pub struct IngestReport {
+    pub source: String,
+    pub format: DocumentFormat,
+    pub chunks: usize,
+    /// Chunks from an earlier ingest of the same source that were replaced.
+    pub replaced: usize,
+    pub truncated: bool,
+}
+
+/// Raw document bytes with whatever type hints the source provided.
+pub struct DocumentSource {
+    pub data: Vec<u8>,
+    pub mime_type: Option<String>,
+    pub file_name: Option<String>,
+}
+
+fn document_category() -> MemoryCategory {
+    MemoryCategory::Custom(DOCUMENT_CATEGORY.to_string())
+}
+
+/// Stable key prefix for all chunks of `source`.
+fn source_key_prefix(source: &str) -> String {
+    let name = source
+        .trim_end_matches('/')
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(source);
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(48)
+        .collect();
+    let digest = hex::encode(Sha256::digest(source.as_bytes()));
+    format!("doc:{name}:{}:", &digest[..8])
+}
+
+/// Read a local file or download an `https://` URL.
+pub async fn load_source(source: &str, max_bytes: u64) -> anyhow::Result<DocumentSource> {
+    if source.starts_with("https://") || source.starts_with("http://") {
+        let options = FetchOptions {
+            max_size_bytes: max_bytes,
+            ..FetchOptions::default()
+        };
+        let fetched = HttpMediaFetcher::new().fetch(source, &options).await?;
+        let file_name = reqwest::Url::parse(source).ok().and_then(|url| {
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back().map(str::to_string))
+                .filter(|name| !name.is_empty())
+        });
+        return Ok(DocumentSource {
+            data: fetched.data,
+            mime_type: Some(fetched.mime_type),
+            file_name,
+        });
+    }
+
+    let path = Path::new(source);
+    let size = tokio::fs::metadata(path)
+        .await
+        .with_context(|| format!("Document not found: {source}"))?
+        .len();
+    if size > max_bytes {
+        anyhow::bail!("{source} is {size} bytes, exceeding the {max_bytes} byte limit");
+    }
+    Ok(DocumentSource {
+        data: tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {source}"))?,
+        mime_type: None,
+        file_name: path.file_name().map(|n| n.to_string_lossy().into_owned()),
+    })
+}
+
+/// Parse `document`, then replace any chunks previously stored for `source`.
+pub async fn ingest_document(
+    mem: &dyn Memory,
+    parser: &dyn MediaParser,
+    source: &str,
+    document: &DocumentSource,
+    options: &DocumentOptions,
+) -> anyhow::Result<IngestReport> {
+    let parsed = parser.parse_document(
+        &document.data,
+        document.mime_type.as_deref(),
+        document.file_name.as_deref(),
+        options,
+    )?;
+    if parsed.chunks.is_empty() {
+        anyhow::bail!("No text could be extracted from {source}");
+    }
+
+    let prefix = source_key_prefix(source);
+    let category = document_category();
+    let mut replaced = 0;
+    for entry in mem.list(Some(&category), None).await? {
+        if entry.key.starts_with(&prefix) && mem.forget(&entry.key).await? {
+            replaced += 1;
+        }
+    }
+
+    let total = parsed.chunks.len();
+    for (index, chunk) in parsed.chunks.iter().enumerate() {
+        let content = format!("[Document: {source} | part {}/{total}]\n{chunk}", index + 1);
+        mem.store(
+            &format!("{prefix}{:04}", index + 1),
+            &content,
+            category.clone(),
+            None,
+        )
+        .await?;
+    }
+
+    Ok(IngestReport {
+        source: source.to_string(),
+        format: parsed.format,
+        chunks: total,
+        replaced,
+        truncated: parsed.truncated,
+    })
+}
+
+/// Handle `zeroclaw ingest <path|url>`.
+pub async fn run_ingest(config: &Config, source: &str) -> anyhow::Result<()> {
+    let options = DocumentOptions {
+        chunk_max_tokens: config.memory.chunk_max_tokens,
+        ..DocumentOptions::default()
+    };
+    let document = load_source(source, options.max_input_bytes).await?;
+    let mem = super::create_memory_with_storage_and_routes(
+        &config.memory,
+        &config.embedding_routes,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?;
+    let parser = crate::media::create_media_parser();
+    let report =
+        ingest_document(mem.as_ref(), parser.as_ref(), source, &document, &options).await?;
+
+    println!(
+        "✅ Ingested {} ({}) into memory category '{DOCUMENT_CATEGORY}': {} chunk(s)",
+        report.source,
+        report.format.as_str(),
+        report.chunks
+    );
+    if report.replaced > 0 {
+        println!(
+            "   Replaced {} chunk(s) from a previous ingest.",
+            report.replaced
+        );
+    }
+    if report.truncated {
+        println!(
+            "   ⚠️  Text was truncated at {} characters.",
+            options.max_text_chars
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::media::DefaultMediaParser;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    fn markdown(text: &str) -> DocumentSource {
+        DocumentSource {
+            data: text.as_bytes().to_vec(),
+            mime_type: None,
+            file_name: Some("handbook.md".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_chunks_with_source_and_replaces_on_reingest() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let options = DocumentOptions {
+            chunk_max_tokens: 8,
+            ..DocumentOptions::default()
+        };
+        let source = "/docs/handbook.md";
+
+        let report = ingest_document(
+            &mem,
+            &DefaultMediaParser,
+            source,
+            &markdown("Vacation policy is 25 days.\n\nExpenses need receipts."),
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.format, DocumentFormat::Markdown);
+        assert_eq!(report.chunks, 2);
+        assert_eq!(report.replaced, 0);
+
+        let entries = mem.list(Some(&document_category()), None).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        let first = entries
+            .iter()
+            .find(|e| e.key.ends_with("0001"))
+            .expect("first chunk stored");
+        assert!(first.key.starts_with("doc:handbook.md:"));
+        assert!(first
+            .content
+            .starts_with("[Document: /docs/handbook.md | part 1/2]\nVacation policy"));
+
+        let report = ingest_document(
+            &mem,
+            &DefaultMediaParser,
+            source,
+            &markdown("Vacation policy is 30 days."),
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.replaced, 2);
+        assert_eq!(
+            mem.list(Some(&document_category()), None)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn loads_local_files_and_rejects_empty_documents() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.txt");
+        std::fs::write(&path, "  \n ").unwrap();
+        let source = path.to_str().unwrap();
+
+        let document = load_source(source, 1024).await.unwrap();
+        assert_eq!(document.file_name.as_deref(), Some("notes.txt"));
+        assert!(load_source(source, 1).await.is_err());
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let err = ingest_document(
+            &mem,
+            &DefaultMediaParser,
+            source,
+            &document,
+            &DocumentOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("No text"));
+    }
+}
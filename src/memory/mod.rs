@@ -1,14 +1,29 @@
+pub mod batched;
+pub mod chunker;
+pub mod embeddings;
+pub mod encryption;
+pub mod entities;
+pub mod ingest;
+pub mod namespaced;
+pub mod pii;
+pub mod pool;
 pub mod sqlite;
 pub mod traits;
 
+pub use batched::BatchedMemory;
+#[allow(unused_imports)]
+pub use entities::{EntityEdge, EntityKind, EntityNode, Relation};
+pub use namespaced::NamespacedMemory;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
 pub use traits::{MemoryCategory, MemoryEntry};
 
-use crate::config::MemoryConfig;
-use anyhow::Result;
+use crate::config::{EmbeddingRouteConfig, MemoryConfig};
+use anyhow::{Context, Result};
+use encryption::ContentCipher;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Return the effective memory backend name (always sqlite after the strip).
 pub fn effective_memory_backend_name(memory_backend: &str) -> String {
@@ -24,33 +39,69 @@ pub fn is_assistant_autosave_key(key: &str) -> bool {
 
 /// Factory: create the right memory backend from config
 pub fn create_memory(
-    _config: &MemoryConfig,
+    config: &MemoryConfig,
     workspace_dir: &Path,
-    _api_key: Option<&str>,
+    api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    Ok(Box::new(SqliteMemory::new(workspace_dir)?))
+    create_memory_with_storage_and_routes(config, &[], workspace_dir, api_key)
 }
 
 /// Factory: create memory with optional storage-provider override.
 ///
 /// Simplified after removing `StorageProviderConfig`.
 pub fn create_memory_with_storage(
-    _config: &MemoryConfig,
+    config: &MemoryConfig,
     workspace_dir: &Path,
-    _api_key: Option<&str>,
+    api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    Ok(Box::new(SqliteMemory::new(workspace_dir)?))
+    create_memory_with_storage_and_routes(config, &[], workspace_dir, api_key)
 }
 
-/// Factory: create memory with storage and embedding routes.
+/// Factory: create memory whose embedder may come from an
+/// `[[embedding_routes]]` entry (`memory.embedding_model = "hint:<name>"`).
 ///
-/// Simplified after removing `EmbeddingRouteConfig` and `StorageProviderConfig`.
+/// `api_key` is the chat provider's key, used for embeddings only when no
+/// route is selected.
+#[allow(clippy::cast_possible_truncation)]
 pub fn create_memory_with_storage_and_routes(
-    _config: &MemoryConfig,
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
     workspace_dir: &Path,
-    _api_key: Option<&str>,
+    api_key: Option<&str>,
 ) -> anyhow::Result<Box<dyn Memory>> {
-    Ok(Box::new(SqliteMemory::new(workspace_dir)?))
+    let settings = embeddings::resolve_embedding_settings(config, embedding_routes, api_key)?;
+    let embedder = embeddings::create_embedding_provider(&settings)?;
+    let memory = SqliteMemory::with_embedder(
+        workspace_dir,
+        embedder,
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+        config.embedding_cache_size,
+        config.sqlite_open_timeout_secs,
+    )?;
+    let cipher = ContentCipher::from_runtime(config.encrypt);
+    if config.encrypt && cipher.is_none() {
+        anyhow::bail!("[memory] encrypt is set but the secret key directory is unknown");
+    }
+    Ok(Box::new(
+        memory
+            .with_cipher(cipher)
+            .with_pii_scrubber(pii::PiiScrubber::from_config(config))
+            .with_entity_graph(config.entity_graph),
+    ))
+}
+
+/// Wrap `memory` in a [`BatchedMemory`] when `[memory] write_batch_window_ms`
+/// is set. Used by long-running runtimes, which flush it on shutdown.
+pub fn with_write_batching(memory: Arc<dyn Memory>, config: &MemoryConfig) -> Arc<dyn Memory> {
+    if config.write_batch_window_ms == 0 {
+        return memory;
+    }
+    Arc::new(BatchedMemory::new(
+        memory,
+        std::time::Duration::from_millis(config.write_batch_window_ms),
+        config.write_batch_max_entries,
+    ))
 }
 
 pub fn create_memory_for_migration(
@@ -67,7 +118,12 @@ pub async fn handle_memory_command(
     command: crate::MemoryCommands,
     config: &crate::config::Config,
 ) -> Result<()> {
-    let mem = SqliteMemory::new(&config.workspace_dir)?;
+    let key_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let cipher = ContentCipher::new(key_dir, config.memory.encrypt);
+    let mem = SqliteMemory::new(&config.workspace_dir)?.with_cipher(Some(cipher.clone()));
     match command {
         crate::MemoryCommands::List {
             category,
@@ -131,11 +187,7 @@ pub async fn handle_memory_command(
             );
             println!("  Total:    {total}");
         }
-        crate::MemoryCommands::Clear {
-            key,
-            category,
-            yes,
-        } => {
+        crate::MemoryCommands::Clear { key, category, yes } => {
             if let Some(key) = key {
                 if !yes {
                     eprintln!("Use --yes to confirm deletion of key '{key}'.");
@@ -171,10 +223,33 @@ pub async fn handle_memory_command(
                 println!("✓ Cleared {deleted}/{} entries.", entries.len());
             }
         }
+        crate::MemoryCommands::Encrypt => {
+            let changed = mem.migrate_encryption(&cipher, true).await?;
+            set_memory_encryption(config, true).await?;
+            println!("✓ Encrypted {changed} memory entries; [memory] encrypt = true.");
+            println!(
+                "  Keep {} backed up: encrypted memories cannot be read without it.",
+                key_dir.join(".secret_key").display()
+            );
+        }
+        crate::MemoryCommands::Decrypt => {
+            let changed = mem.migrate_encryption(&cipher, false).await?;
+            set_memory_encryption(config, false).await?;
+            println!("✓ Decrypted {changed} memory entries; [memory] encrypt = false.");
+        }
     }
     Ok(())
 }
 
+async fn set_memory_encryption(config: &crate::config::Config, encrypt: bool) -> Result<()> {
+    if config.memory.encrypt == encrypt {
+        return Ok(());
+    }
+    let mut updated = config.clone();
+    updated.memory.encrypt = encrypt;
+    updated.save().await
+}
+
 fn parse_category(s: &str) -> MemoryCategory {
     match s.trim().to_ascii_lowercase().as_str() {
         "core" => MemoryCategory::Core,
@@ -212,10 +287,7 @@ mod tests {
 
     #[test]
     fn effective_backend_always_returns_sqlite() {
-        assert_eq!(
-            effective_memory_backend_name("sqlite"),
-            "sqlite"
-        );
+        assert_eq!(effective_memory_backend_name("sqlite"), "sqlite");
     }
 
     #[test]
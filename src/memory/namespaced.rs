@@ -0,0 +1,196 @@
+//! Memory view scoped to one namespace.
+//!
+//! Keys are stored as `<namespace>:<key>` in the shared backend; reads only
+//! see entries with the namespace prefix and return them with the prefix
+//! stripped, so agents with different namespaces cannot read each other's
+//! memories.
+
+use super::entities::{EntityNode, Relation};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryWrite};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Extra candidates fetched per requested result, since entries from other
+/// namespaces are filtered out after recall.
+const RECALL_OVERFETCH: usize = 4;
+
+pub struct NamespacedMemory {
+    inner: Arc<dyn Memory>,
+    prefix: String,
+}
+
+impl NamespacedMemory {
+    pub fn new(inner: Arc<dyn Memory>, namespace: &str) -> Self {
+        Self {
+            inner,
+            prefix: format!("{}:", namespace.trim()),
+        }
+    }
+
+    fn scoped_key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+
+    fn unscope(&self, entries: Vec<MemoryEntry>) -> Vec<MemoryEntry> {
+        entries
+            .into_iter()
+            .filter_map(|mut entry| {
+                let key = entry.key.strip_prefix(&self.prefix)?.to_string();
+                entry.key = key;
+                Some(entry)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Memory for NamespacedMemory {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .store(&self.scoped_key(key), content, category, session_id)
+            .await
+    }
+
+    async fn recall(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let candidates = self
+            .inner
+            .recall(query, limit.saturating_mul(RECALL_OVERFETCH), session_id)
+            .await?;
+        let mut entries = self.unscope(candidates);
+        entries.truncate(limit);
+        Ok(entries)
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let entry = self.inner.get(&self.scoped_key(key)).await?;
+        Ok(entry.and_then(|entry| self.unscope(vec![entry]).pop()))
+    }
+
+    async fn list(
+        &self,
+        category: Option<&MemoryCategory>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let entries = self.inner.list(category, session_id).await?;
+        Ok(self.unscope(entries))
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        self.inner.forget(&self.scoped_key(key)).await
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        Ok(self.list(None, None).await?.len())
+    }
+
+    async fn health_check(&self) -> bool {
+        self.inner.health_check().await
+    }
+
+    async fn store_batch(&self, writes: Vec<MemoryWrite>) -> anyhow::Result<()> {
+        let writes = writes
+            .into_iter()
+            .map(|write| MemoryWrite {
+                key: self.scoped_key(&write.key),
+                ..write
+            })
+            .collect();
+        self.inner.store_batch(writes).await
+    }
+
+    async fn flush(&self) -> anyhow::Result<()> {
+        self.inner.flush().await
+    }
+
+    /// Only edges extracted from this namespace's entries are visible.
+    async fn entity(&self, name: &str) -> anyhow::Result<Option<EntityNode>> {
+        let Some(mut node) = self.inner.entity(name).await? else {
+            return Ok(None);
+        };
+        node.edges.retain_mut(|edge| {
+            let Some(key) = edge.memory_key.strip_prefix(&self.prefix) else {
+                return false;
+            };
+            if edge.relation == Relation::MentionedIn {
+                edge.other = key.to_string();
+            }
+            edge.memory_key = key.to_string();
+            true
+        });
+        Ok((!node.edges.is_empty()).then_some(node))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn namespaces_isolate_entries_in_shared_backend() {
+        let tmp = TempDir::new().unwrap();
+        let shared: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let research = NamespacedMemory::new(shared.clone(), "research");
+        let support = NamespacedMemory::new(shared.clone(), "support");
+
+        research
+            .store("topic", "rust async runtimes", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        support
+            .store("topic", "refund policy", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let entry = research.get("topic").await.unwrap().unwrap();
+        assert_eq!(entry.key, "topic");
+        assert_eq!(entry.content, "rust async runtimes");
+
+        let recalled = support.recall("rust", 5, None).await.unwrap();
+        assert!(recalled.is_empty());
+        assert_eq!(research.count().await.unwrap(), 1);
+        assert_eq!(shared.count().await.unwrap(), 2);
+
+        assert!(support.forget("topic").await.unwrap());
+        assert!(research.get("topic").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn entity_edges_are_scoped_to_the_namespace() {
+        let tmp = TempDir::new().unwrap();
+        let shared: Arc<dyn Memory> = Arc::new(
+            SqliteMemory::new(tmp.path())
+                .unwrap()
+                .with_entity_graph(true),
+        );
+        let research = NamespacedMemory::new(shared.clone(), "research");
+        let support = NamespacedMemory::new(shared.clone(), "support");
+        research
+            .store("team", "Alice works on Atlas", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let alice = research.entity("Alice").await.unwrap().unwrap();
+        assert!(alice
+            .edges
+            .iter()
+            .any(|edge| edge.relation == Relation::MentionedIn && edge.other == "team"));
+        assert!(support.entity("Alice").await.unwrap().is_none());
+    }
+}
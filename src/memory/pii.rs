@@ -0,0 +1,205 @@
+//! PII scrubbing for memory writes (`[memory] redact_pii = true`).
+//!
+//! Content is scrubbed before it is embedded or stored, so neither
+//! `brain.db` nor the embedding provider sees the original values. Matches
+//! are replaced with typed placeholders such as `[REDACTED:email]`.
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// A category of personal data the scrubber recognises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiKind {
+    Email,
+    CreditCard,
+    NationalId,
+    Phone,
+}
+
+/// Names accepted in `memory.pii_kinds`, in the order patterns are applied.
+pub const PII_KIND_NAMES: [&str; 4] = ["email", "credit_card", "national_id", "phone"];
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b").unwrap());
+
+/// 13–19 digits, optionally grouped by spaces or dashes; Luhn-checked.
+static CREDIT_CARD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+
+/// US SSN (`123-45-6789`) and UK National Insurance numbers (`AB 12 34 56 C`).
+static NATIONAL_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b\d{3}-\d{2}-\d{4}\b|\b[A-CEGHJ-PR-TW-Z]{2} ?\d{2} ?\d{2} ?\d{2} ?[A-D]\b")
+        .unwrap()
+});
+
+/// E.164 (`+14155550123`) and grouped numbers (`(415) 555-0123`,
+/// `+44 20 7946 0958`).
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\+\d{8,15}\b|(?:\+\d{1,3}[ .-]?)?(?:\(\d{2,4}\)|\b\d{2,4})[ .-]?\d{3,4}[ .-]?\d{4}\b",
+    )
+    .unwrap()
+});
+
+impl PiiKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "email" => Some(Self::Email),
+            "credit_card" => Some(Self::CreditCard),
+            "national_id" => Some(Self::NationalId),
+            "phone" => Some(Self::Phone),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::CreditCard => "credit_card",
+            Self::NationalId => "national_id",
+            Self::Phone => "phone",
+        }
+    }
+
+    fn pattern(self) -> &'static Regex {
+        match self {
+            Self::Email => &EMAIL,
+            Self::CreditCard => &CREDIT_CARD,
+            Self::NationalId => &NATIONAL_ID,
+            Self::Phone => &PHONE,
+        }
+    }
+
+    fn placeholder(self) -> String {
+        format!("[REDACTED:{}]", self.name())
+    }
+}
+
+fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Replaces PII in memory content with typed placeholders.
+#[derive(Debug, Clone)]
+pub struct PiiScrubber {
+    kinds: Vec<PiiKind>,
+}
+
+impl PiiScrubber {
+    /// Scrubber for `kinds`, applied in the fixed [`PII_KIND_NAMES`] order
+    /// so e.g. digits inside an email are not mistaken for a phone number.
+    pub fn new(kinds: &[PiiKind]) -> Self {
+        let kinds = PII_KIND_NAMES
+            .iter()
+            .filter_map(|name| PiiKind::parse(name))
+            .filter(|kind| kinds.contains(kind))
+            .collect();
+        Self { kinds }
+    }
+
+    /// Scrubber for `[memory]`, or `None` when `redact_pii` is off.
+    /// Unknown kind names are ignored here; `Config::validate` rejects them.
+    pub fn from_config(config: &crate::config::MemoryConfig) -> Option<Self> {
+        if !config.redact_pii {
+            return None;
+        }
+        let kinds: Vec<PiiKind> = config
+            .pii_kinds
+            .iter()
+            .filter_map(|name| PiiKind::parse(name))
+            .collect();
+        Some(Self::new(&kinds))
+    }
+
+    /// Scrubbed text plus the number of replacements per kind (non-zero
+    /// counts only).
+    pub fn scrub(&self, text: &str) -> (String, Vec<(PiiKind, usize)>) {
+        let mut scrubbed = text.to_string();
+        let mut counts = Vec::new();
+        for &kind in &self.kinds {
+            let mut count = 0usize;
+            let replaced = kind
+                .pattern()
+                .replace_all(&scrubbed, |caps: &Captures<'_>| {
+                    let matched = &caps[0];
+                    if kind == PiiKind::CreditCard && !luhn_valid(matched) {
+                        return matched.to_string();
+                    }
+                    count += 1;
+                    kind.placeholder()
+                });
+            if count > 0 {
+                scrubbed = replaced.into_owned();
+                counts.push((kind, count));
+            }
+        }
+        (scrubbed, counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> PiiScrubber {
+        PiiScrubber::new(&[
+            PiiKind::Email,
+            PiiKind::CreditCard,
+            PiiKind::NationalId,
+            PiiKind::Phone,
+        ])
+    }
+
+    #[test]
+    fn replaces_each_kind_with_typed_placeholder() {
+        let (text, counts) = all().scrub(
+            "Mail jane.doe+work@example.co.uk, card 4111 1111 1111 1111, \
+             SSN 123-45-6789, NI AB 12 34 56 C, call (415) 555-0123 or +442079460958.",
+        );
+        assert_eq!(
+            text,
+            "Mail [REDACTED:email], card [REDACTED:credit_card], \
+             SSN [REDACTED:national_id], NI [REDACTED:national_id], \
+             call [REDACTED:phone] or [REDACTED:phone]."
+        );
+        assert_eq!(
+            counts,
+            vec![
+                (PiiKind::Email, 1),
+                (PiiKind::CreditCard, 1),
+                (PiiKind::NationalId, 2),
+                (PiiKind::Phone, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_ordinary_numbers_and_disabled_kinds_alone() {
+        let text = "Order 1234567812345678 shipped on 2024-01-15 at 10:30, version 1.2.3";
+        let (scrubbed, counts) = all().scrub(text);
+        assert_eq!(scrubbed, text);
+        assert!(counts.is_empty());
+
+        let emails_only = PiiScrubber::new(&[PiiKind::Email]);
+        let (scrubbed, _) = emails_only.scrub("a@b.io 415-555-0123");
+        assert_eq!(scrubbed, "[REDACTED:email] 415-555-0123");
+    }
+}
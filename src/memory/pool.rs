@@ -0,0 +1,281 @@
+//! Connection pool and async access layer for the SQLite memory database.
+//!
+//! Each pooled connection runs in WAL mode with a busy timeout, so readers
+//! proceed while a write is in flight and concurrent writers (several
+//! channels, or the daemon and a CLI command) wait for the lock instead of
+//! failing with "database is locked". Queries run on the blocking thread
+//! pool through [`SqlitePool::run`] and never block the async runtime.
+
+use anyhow::Context;
+use parking_lot::{Condvar, Mutex};
+use rusqlite::Connection;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// Maximum allowed open timeout (seconds) to avoid unreasonable waits.
+const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
+/// How long a statement waits for another connection's write lock.
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a caller waits for a free connection when all are checked out.
+const SQLITE_CHECKOUT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of connections kept per database.
+pub const DEFAULT_POOL_SIZE: usize = 4;
+
+/// Cloneable handle to a pool of connections to one database file.
+#[derive(Clone)]
+pub struct SqlitePool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    db_path: PathBuf,
+    open_timeout_secs: Option<u64>,
+    max_size: usize,
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+struct PoolState {
+    idle: Vec<Connection>,
+    open: usize,
+}
+
+/// A connection checked out of the pool; returned to it on drop.
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    pool: Arc<PoolInner>,
+}
+
+impl SqlitePool {
+    /// Open the pool and its first connection (so open errors surface here).
+    ///
+    /// If `open_timeout_secs` is `Some(n)`, opening each connection is limited
+    /// to `n` seconds (capped at 300). `None` = wait indefinitely.
+    pub fn open(
+        db_path: &Path,
+        open_timeout_secs: Option<u64>,
+        max_size: usize,
+    ) -> anyhow::Result<Self> {
+        let first = open_connection(db_path, open_timeout_secs)?;
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                db_path: db_path.to_path_buf(),
+                open_timeout_secs,
+                max_size: max_size.max(1),
+                state: Mutex::new(PoolState {
+                    idle: vec![first],
+                    open: 1,
+                }),
+                available: Condvar::new(),
+            }),
+        })
+    }
+
+    /// Check out a connection, opening a new one while below the pool size
+    /// and waiting for one to be returned otherwise. Blocking.
+    pub fn get(&self) -> anyhow::Result<PooledConnection> {
+        let inner = &self.inner;
+        let mut state = inner.state.lock();
+        loop {
+            if let Some(conn) = state.idle.pop() {
+                return Ok(self.wrap(conn));
+            }
+            if state.open < inner.max_size {
+                state.open += 1;
+                drop(state);
+                return match open_connection(&inner.db_path, inner.open_timeout_secs) {
+                    Ok(conn) => Ok(self.wrap(conn)),
+                    Err(e) => {
+                        inner.state.lock().open -= 1;
+                        inner.available.notify_one();
+                        Err(e)
+                    }
+                };
+            }
+            if inner
+                .available
+                .wait_for(&mut state, SQLITE_CHECKOUT_TIMEOUT)
+                .timed_out()
+                && state.idle.is_empty()
+            {
+                anyhow::bail!(
+                    "Timed out after {}s waiting for a SQLite connection",
+                    SQLITE_CHECKOUT_TIMEOUT.as_secs()
+                );
+            }
+        }
+    }
+
+    /// Run `f` with a pooled connection on the blocking thread pool.
+    pub async fn run<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> anyhow::Result<T> + Send + 'static,
+    {
+        let pool = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            f(&conn)
+        })
+        .await?
+    }
+
+    fn wrap(&self, conn: Connection) -> PooledConnection {
+        PooledConnection {
+            conn: Some(conn),
+            pool: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("connection is present until drop")
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.state.lock().idle.push(conn);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+/// Open and configure one connection, optionally with a timeout (for
+/// locked/slow storage).
+fn open_connection(db_path: &Path, open_timeout_secs: Option<u64>) -> anyhow::Result<Connection> {
+    let path_buf = db_path.to_path_buf();
+
+    let conn = if let Some(secs) = open_timeout_secs {
+        let capped = secs.min(SQLITE_OPEN_TIMEOUT_CAP_SECS);
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let result = Connection::open(&path_buf);
+            let _ = tx.send(result);
+        });
+        match rx.recv_timeout(Duration::from_secs(capped)) {
+            Ok(Ok(c)) => c,
+            Ok(Err(e)) => return Err(e).context("SQLite failed to open database"),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                anyhow::bail!("SQLite connection open timed out after {} seconds", capped);
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                anyhow::bail!("SQLite open thread exited unexpectedly");
+            }
+        }
+    } else {
+        Connection::open(&path_buf).context("SQLite failed to open database")?
+    };
+
+    conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
+
+    // ── Production-grade PRAGMA tuning ──────────────────────
+    // WAL mode: concurrent reads during writes, crash-safe
+    // normal sync: 2× write speed, still durable on WAL
+    // mmap 8 MB: let the OS page-cache serve hot reads
+    // cache 2 MB: keep ~500 hot pages in-process
+    // temp_store memory: temp tables never hit disk
+    conn.execute_batch(
+        "PRAGMA journal_mode = WAL;
+         PRAGMA synchronous  = NORMAL;
+         PRAGMA mmap_size    = 8388608;
+         PRAGMA cache_size   = -2000;
+         PRAGMA temp_store   = MEMORY;",
+    )?;
+
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn connections_use_wal_and_busy_timeout() {
+        let tmp = TempDir::new().unwrap();
+        let pool = SqlitePool::open(&tmp.path().join("test.db"), None, 2).unwrap();
+        let conn = pool.get().unwrap();
+        let mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+        let timeout: i64 = conn
+            .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(timeout, 5000);
+    }
+
+    #[test]
+    fn pool_reuses_returned_connections_up_to_max_size() {
+        let tmp = TempDir::new().unwrap();
+        let pool = SqlitePool::open(&tmp.path().join("test.db"), Some(5), 2).unwrap();
+        let a = pool.get().unwrap();
+        let b = pool.get().unwrap();
+        assert_eq!(pool.inner.state.lock().open, 2);
+
+        let waiter = {
+            let pool = pool.clone();
+            thread::spawn(move || pool.get().map(|_| ()))
+        };
+        thread::sleep(Duration::from_millis(50));
+        drop(a);
+        waiter.join().unwrap().unwrap();
+        drop(b);
+
+        let state = pool.inner.state.lock();
+        assert_eq!(state.open, 2);
+        assert_eq!(state.idle.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_writers_do_not_hit_database_locked() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("test.db");
+        // Two pools on one file stand in for two processes.
+        let first = SqlitePool::open(&path, None, 4).unwrap();
+        let second = SqlitePool::open(&path, None, 4).unwrap();
+        first
+            .run(|conn| {
+                conn.execute_batch("CREATE TABLE t (n INTEGER)")?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let mut writes = tokio::task::JoinSet::new();
+        for i in 0..40 {
+            let pool = if i % 2 == 0 {
+                first.clone()
+            } else {
+                second.clone()
+            };
+            writes.spawn(async move {
+                pool.run(move |conn| {
+                    conn.execute("INSERT INTO t (n) VALUES (?1)", [i])?;
+                    Ok(())
+                })
+                .await
+            });
+        }
+        while let Some(result) = writes.join_next().await {
+            result.unwrap().unwrap();
+        }
+
+        let count: i64 = first
+            .run(|conn| Ok(conn.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))?))
+            .await
+            .unwrap();
+        assert_eq!(count, 40);
+    }
+}
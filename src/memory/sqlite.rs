@@ -1,15 +1,14 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
-use anyhow::Context;
+use super::encryption::ContentCipher;
+use super::entities::{self, EntityNode};
+use super::pii::PiiScrubber;
+use super::pool::{SqlitePool, DEFAULT_POOL_SIZE};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryWrite};
 use async_trait::async_trait;
 use chrono::Local;
-use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, Transaction, TransactionBehavior};
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
 use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
 use uuid::Uuid;
 
 // ── Embedding provider trait + noop (inlined from deleted embeddings.rs) ──
@@ -45,7 +44,7 @@ impl EmbeddingProvider for NoopEmbedding {
 
 // ── Vector utilities (inlined from deleted vector.rs) ──
 
-fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
     }
@@ -150,8 +149,16 @@ fn hybrid_merge(
     results
 }
 
-/// Maximum allowed open timeout (seconds) to avoid unreasonable waits.
-const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
+/// Insert a memory, or replace the content of an existing key.
+const UPSERT_MEMORY_SQL: &str =
+    "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+     ON CONFLICT(key) DO UPDATE SET
+        content = excluded.content,
+        category = excluded.category,
+        embedding = excluded.embedding,
+        updated_at = excluded.updated_at,
+        session_id = excluded.session_id";
 
 /// SQLite-backed persistent memory — the brain
 ///
@@ -161,13 +168,19 @@ const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
+/// - **At-rest encryption**: optional sealed content (`[memory] encrypt`)
+/// - **PII redaction**: optional scrubbing of writes (`[memory] redact_pii`)
+/// - **Entity graph**: optional people/project/place links (`[memory] entity_graph`)
 pub struct SqliteMemory {
-    conn: Arc<Mutex<Connection>>,
+    pool: SqlitePool,
     db_path: PathBuf,
     embedder: Arc<dyn EmbeddingProvider>,
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    cipher: Option<ContentCipher>,
+    pii: Option<PiiScrubber>,
+    entity_graph: bool,
 }
 
 impl SqliteMemory {
@@ -201,63 +214,86 @@ impl SqliteMemory {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Self::open_connection(&db_path, open_timeout_secs)?;
-
-        // ── Production-grade PRAGMA tuning ──────────────────────
-        // WAL mode: concurrent reads during writes, crash-safe
-        // normal sync: 2× write speed, still durable on WAL
-        // mmap 8 MB: let the OS page-cache serve hot reads
-        // cache 2 MB: keep ~500 hot pages in-process
-        // temp_store memory: temp tables never hit disk
-        conn.execute_batch(
-            "PRAGMA journal_mode = WAL;
-             PRAGMA synchronous  = NORMAL;
-             PRAGMA mmap_size    = 8388608;
-             PRAGMA cache_size   = -2000;
-             PRAGMA temp_store   = MEMORY;",
-        )?;
-
+        let pool = SqlitePool::open(&db_path, open_timeout_secs, DEFAULT_POOL_SIZE)?;
+        let conn = pool.get()?;
         Self::init_schema(&conn)?;
+        drop(conn);
 
         Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
+            pool,
             db_path,
             embedder,
             vector_weight,
             keyword_weight,
             cache_max,
+            cipher: None,
+            pii: None,
+            entity_graph: false,
         })
     }
 
-    /// Open SQLite connection, optionally with a timeout (for locked/slow storage).
-    fn open_connection(
-        db_path: &Path,
-        open_timeout_secs: Option<u64>,
-    ) -> anyhow::Result<Connection> {
-        let path_buf = db_path.to_path_buf();
-
-        let conn = if let Some(secs) = open_timeout_secs {
-            let capped = secs.min(SQLITE_OPEN_TIMEOUT_CAP_SECS);
-            let (tx, rx) = mpsc::channel();
-            thread::spawn(move || {
-                let result = Connection::open(&path_buf);
-                let _ = tx.send(result);
-            });
-            match rx.recv_timeout(Duration::from_secs(capped)) {
-                Ok(Ok(c)) => c,
-                Ok(Err(e)) => return Err(e).context("SQLite failed to open database"),
-                Err(mpsc::RecvTimeoutError::Timeout) => {
-                    anyhow::bail!("SQLite connection open timed out after {} seconds", capped);
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    anyhow::bail!("SQLite open thread exited unexpectedly");
-                }
-            }
-        } else {
-            Connection::open(&path_buf).context("SQLite failed to open database")?
+    /// Open sealed content on read and, if the cipher seals writes, encrypt
+    /// new content. Without a cipher, content is stored as-is.
+    #[must_use]
+    pub fn with_cipher(mut self, cipher: Option<ContentCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Scrub PII from content before it is embedded or stored.
+    #[must_use]
+    pub fn with_pii_scrubber(mut self, pii: Option<PiiScrubber>) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// Extract entities from writes into the entity graph.
+    #[must_use]
+    pub fn with_entity_graph(mut self, enabled: bool) -> Self {
+        self.entity_graph = enabled;
+        self
+    }
+
+    /// Entities in `content` when the entity graph is on. Runs on scrubbed,
+    /// unsealed content.
+    fn extract_entities(&self, content: &str) -> Option<entities::Extraction> {
+        self.entity_graph.then(|| entities::extract(content))
+    }
+
+    fn scrub_content(&self, key: &str, content: &str) -> String {
+        let Some(pii) = &self.pii else {
+            return content.to_string();
         };
+        let (scrubbed, counts) = pii.scrub(content);
+        if !counts.is_empty() {
+            let summary = counts
+                .iter()
+                .map(|(kind, n)| format!("{}={n}", kind.name()))
+                .collect::<Vec<_>>()
+                .join(",");
+            tracing::info!(key, redactions = %summary, "Redacted PII from memory write");
+        }
+        scrubbed
+    }
 
-        Ok(conn)
+    fn seal_content(&self, content: &str) -> anyhow::Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.seal(content),
+            None => Ok(content.to_string()),
+        }
+    }
+
+    fn open_entries(&self, entries: Vec<MemoryEntry>) -> anyhow::Result<Vec<MemoryEntry>> {
+        let Some(cipher) = &self.cipher else {
+            return Ok(entries);
+        };
+        entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.content = cipher.open(&entry.content)?;
+                Ok(entry)
+            })
+            .collect()
     }
 
     /// Initialize all tables: memories, FTS5, `embedding_cache`
@@ -306,6 +342,7 @@ impl SqliteMemory {
             );
             CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
         )?;
+        entities::init_schema(conn)?;
 
         // Migration: add session_id column if not present (safe to run repeatedly)
         let has_session_id: bool = conn
@@ -367,24 +404,24 @@ impl SqliteMemory {
         let now = Local::now().to_rfc3339();
 
         // Check cache (offloaded to blocking thread)
-        let conn = self.conn.clone();
         let hash_c = hash.clone();
         let now_c = now.clone();
-        let cached = tokio::task::spawn_blocking(move || -> anyhow::Result<Option<Vec<f32>>> {
-            let conn = conn.lock();
-            let mut stmt =
-                conn.prepare("SELECT embedding FROM embedding_cache WHERE content_hash = ?1")?;
-            let blob: Option<Vec<u8>> = stmt.query_row(params![hash_c], |row| row.get(0)).ok();
-            if let Some(bytes) = blob {
-                conn.execute(
-                    "UPDATE embedding_cache SET accessed_at = ?1 WHERE content_hash = ?2",
-                    params![now_c, hash_c],
-                )?;
-                return Ok(Some(bytes_to_vec(&bytes)));
-            }
-            Ok(None)
-        })
-        .await??;
+        let cached = self
+            .pool
+            .run(move |conn| -> anyhow::Result<Option<Vec<f32>>> {
+                let mut stmt =
+                    conn.prepare("SELECT embedding FROM embedding_cache WHERE content_hash = ?1")?;
+                let blob: Option<Vec<u8>> = stmt.query_row(params![hash_c], |row| row.get(0)).ok();
+                if let Some(bytes) = blob {
+                    conn.execute(
+                        "UPDATE embedding_cache SET accessed_at = ?1 WHERE content_hash = ?2",
+                        params![now_c, hash_c],
+                    )?;
+                    return Ok(Some(bytes_to_vec(&bytes)));
+                }
+                Ok(None)
+            })
+            .await?;
 
         if cached.is_some() {
             return Ok(cached);
@@ -395,11 +432,9 @@ impl SqliteMemory {
         let bytes = vec_to_bytes(&embedding);
 
         // Store in cache + LRU eviction (offloaded to blocking thread)
-        let conn = self.conn.clone();
         #[allow(clippy::cast_possible_wrap)]
         let cache_max = self.cache_max as i64;
-        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let conn = conn.lock();
+        self.pool.run(move |conn| -> anyhow::Result<()> {
             conn.execute(
                 "INSERT OR REPLACE INTO embedding_cache (content_hash, embedding, created_at, accessed_at)
                  VALUES (?1, ?2, ?3, ?4)",
@@ -415,7 +450,7 @@ impl SqliteMemory {
             )?;
             Ok(())
         })
-        .await??;
+        .await?;
 
         Ok(Some(embedding))
     }
@@ -517,13 +552,14 @@ impl SqliteMemory {
     pub async fn reindex(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
-            let conn = self.conn.clone();
-            tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                let conn = conn.lock();
-                conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
-                Ok(())
-            })
-            .await??;
+            self.pool
+                .run(move |conn| -> anyhow::Result<()> {
+                    conn.execute_batch(
+                        "INSERT INTO memories_fts(memories_fts) VALUES('rebuild');",
+                    )?;
+                    Ok(())
+                })
+                .await?;
         }
 
         // Step 2: Re-embed all memories that lack embeddings
@@ -531,39 +567,172 @@ impl SqliteMemory {
             return Ok(0);
         }
 
-        let conn = self.conn.clone();
-        let entries: Vec<(String, String)> = tokio::task::spawn_blocking(move || {
-            let conn = conn.lock();
-            let mut stmt =
-                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
-            let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?;
-            Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
-        })
-        .await??;
+        let entries: Vec<(String, String)> = self
+            .pool
+            .run(move |conn| {
+                let mut stmt =
+                    conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?;
+                Ok::<_, anyhow::Error>(rows.filter_map(std::result::Result::ok).collect())
+            })
+            .await?;
 
         let mut count = 0;
         for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
+            let content = match &self.cipher {
+                Some(cipher) => cipher.open(content)?,
+                None => content.clone(),
+            };
+            if let Ok(Some(emb)) = self.get_or_compute_embedding(&content).await {
                 let bytes = vec_to_bytes(&emb);
-                let conn = self.conn.clone();
                 let id = id.clone();
-                tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-                    let conn = conn.lock();
-                    conn.execute(
-                        "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-                        params![bytes, id],
-                    )?;
-                    Ok(())
-                })
-                .await??;
+                self.pool
+                    .run(move |conn| -> anyhow::Result<()> {
+                        conn.execute(
+                            "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                            params![bytes, id],
+                        )?;
+                        Ok(())
+                    })
+                    .await?;
                 count += 1;
             }
         }
 
         Ok(count)
     }
+
+    /// Re-write every entry's content sealed (`encrypt`) or in plaintext,
+    /// then rebuild the FTS index and `VACUUM` so no stale copies of the
+    /// old form stay in free pages. Returns the number of entries changed.
+    pub async fn migrate_encryption(
+        &self,
+        cipher: &ContentCipher,
+        encrypt: bool,
+    ) -> anyhow::Result<usize> {
+        let rows: Vec<(String, String)> = self
+            .pool
+            .run(move |conn| {
+                let mut stmt = conn.prepare("SELECT id, content FROM memories")?;
+                let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+            })
+            .await?;
+
+        let mut updates = Vec::new();
+        for (id, content) in rows {
+            let migrated = if encrypt {
+                cipher.seal_always(&content)?
+            } else {
+                cipher.open(&content)?
+            };
+            if migrated != content {
+                updates.push((id, migrated));
+            }
+        }
+
+        let changed = updates.len();
+        self.pool
+            .run(move |conn| -> anyhow::Result<()> {
+                let tx = conn.unchecked_transaction()?;
+                {
+                    let mut stmt = tx.prepare("UPDATE memories SET content = ?1 WHERE id = ?2")?;
+                    for (id, content) in &updates {
+                        stmt.execute(params![content, id])?;
+                    }
+                }
+                tx.commit()?;
+                conn.execute_batch(
+                    "INSERT INTO memories_fts(memories_fts) VALUES('rebuild');
+                     VACUUM;",
+                )?;
+                Ok(())
+            })
+            .await?;
+        Ok(changed)
+    }
+
+    /// Recall for encrypted stores: the FTS index only holds ciphertext, so
+    /// keyword matching runs over decrypted content in process.
+    async fn recall_decrypted(
+        &self,
+        cipher: &ContentCipher,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let query_embedding = self.get_or_compute_embedding(query).await?;
+        let sid = session_id.map(String::from);
+
+        let (rows, vector_results) = self
+            .pool
+            .run(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT id, key, content, category, created_at, session_id FROM memories
+                     WHERE ?1 IS NULL OR session_id = ?1",
+                )?;
+                let rows = stmt
+                    .query_map(params![sid], |row| {
+                        Ok(MemoryEntry {
+                            id: row.get(0)?,
+                            key: row.get(1)?,
+                            content: row.get(2)?,
+                            category: Self::str_to_category(&row.get::<_, String>(3)?),
+                            timestamp: row.get(4)?,
+                            session_id: row.get(5)?,
+                            score: None,
+                        })
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                let vector_results = match query_embedding {
+                    Some(ref qe) => Self::vector_search(conn, qe, limit * 2, None, sid.as_deref())
+                        .unwrap_or_default(),
+                    None => Vec::new(),
+                };
+                Ok((rows, vector_results))
+            })
+            .await?;
+
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut entries = std::collections::HashMap::new();
+        let mut keyword_results = Vec::new();
+        for mut entry in rows {
+            entry.content = cipher.open(&entry.content)?;
+            let haystack = format!("{} {}", entry.key, entry.content).to_lowercase();
+            let matched = terms
+                .iter()
+                .filter(|t| haystack.contains(t.as_str()))
+                .count();
+            if matched > 0 {
+                #[allow(clippy::cast_precision_loss)]
+                keyword_results.push((entry.id.clone(), matched as f32));
+            }
+            entries.insert(entry.id.clone(), entry);
+        }
+
+        let merged = if vector_results.is_empty() {
+            hybrid_merge(&[], &keyword_results, 0.0, 1.0, limit)
+        } else {
+            hybrid_merge(
+                &vector_results,
+                &keyword_results,
+                self.vector_weight,
+                self.keyword_weight,
+                limit,
+            )
+        };
+        Ok(merged
+            .into_iter()
+            .filter_map(|scored| {
+                entries.remove(&scored.id).map(|mut entry| {
+                    entry.score = Some(f64::from(scored.final_score));
+                    entry
+                })
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -579,37 +748,39 @@ impl Memory for SqliteMemory {
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> anyhow::Result<()> {
+        let content = self.scrub_content(key, content);
+
         // Compute embedding (async, before blocking work)
         let embedding_bytes = self
-            .get_or_compute_embedding(content)
+            .get_or_compute_embedding(&content)
             .await?
             .map(|emb| vec_to_bytes(&emb));
 
-        let conn = self.conn.clone();
         let key = key.to_string();
-        let content = content.to_string();
+        let extraction = self.extract_entities(&content);
+        let content = self.seal_content(&content)?;
         let sid = session_id.map(String::from);
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
-            let conn = conn.lock();
-            let now = Local::now().to_rfc3339();
-            let cat = Self::category_to_str(&category);
-            let id = Uuid::new_v4().to_string();
-
-            conn.execute(
-                "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-                 ON CONFLICT(key) DO UPDATE SET
-                    content = excluded.content,
-                    category = excluded.category,
-                    embedding = excluded.embedding,
-                    updated_at = excluded.updated_at,
-                    session_id = excluded.session_id",
-                params![id, key, content, cat, embedding_bytes, now, now, sid],
-            )?;
-            Ok(())
-        })
-        .await?
+        self.pool
+            .run(move |conn| -> anyhow::Result<()> {
+                let now = Local::now().to_rfc3339();
+                let cat = Self::category_to_str(&category);
+                let id = Uuid::new_v4().to_string();
+
+                let row = params![id, key, content, cat, embedding_bytes, now, now, sid];
+                let Some(extraction) = &extraction else {
+                    conn.execute(UPSERT_MEMORY_SQL, row)?;
+                    return Ok(());
+                };
+                // Take the write lock up front so the entry and its edges
+                // commit together without a lock upgrade.
+                let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+                tx.execute(UPSERT_MEMORY_SQL, row)?;
+                entities::record(&tx, &key, extraction, &now)?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await
     }
 
     async fn recall(
@@ -621,179 +792,184 @@ impl Memory for SqliteMemory {
         if query.trim().is_empty() {
             return Ok(Vec::new());
         }
+        if let Some(cipher) = self.cipher.as_ref().filter(|c| c.seals_writes()) {
+            return self
+                .recall_decrypted(cipher, query, limit, session_id)
+                .await;
+        }
 
         // Compute query embedding (async, before blocking work)
         let query_embedding = self.get_or_compute_embedding(query).await?;
 
-        let conn = self.conn.clone();
         let query = query.to_string();
         let sid = session_id.map(String::from);
         let vector_weight = self.vector_weight;
         let keyword_weight = self.keyword_weight;
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
-            let conn = conn.lock();
-            let session_ref = sid.as_deref();
-
-            // FTS5 BM25 keyword search
-            let keyword_results = Self::fts5_search(&conn, &query, limit * 2).unwrap_or_default();
-
-            // Vector similarity search (if embeddings available)
-            let vector_results = if let Some(ref qe) = query_embedding {
-                Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
-            } else {
-                Vec::new()
-            };
-
-            // Hybrid merge
-            let merged = if vector_results.is_empty() {
-                keyword_results
-                    .iter()
-                    .map(|(id, score)| ScoredResult {
-                        id: id.clone(),
-                        vector_score: None,
-                        keyword_score: Some(*score),
-                        final_score: *score,
-                    })
-                    .collect::<Vec<_>>()
-            } else {
-                hybrid_merge(
-                    &vector_results,
-                    &keyword_results,
-                    vector_weight,
-                    keyword_weight,
-                    limit,
-                )
-            };
-
-            // Fetch full entries for merged results in a single query
-            // instead of N round-trips (N+1 pattern).
-            let mut results = Vec::new();
-            if !merged.is_empty() {
-                let placeholders: String = (1..=merged.len())
-                    .map(|i| format!("?{i}"))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                let sql = format!(
-                    "SELECT id, key, content, category, created_at, session_id \
+        let results = self
+            .pool
+            .run(move |conn| -> anyhow::Result<Vec<MemoryEntry>> {
+                let session_ref = sid.as_deref();
+
+                // FTS5 BM25 keyword search
+                let keyword_results =
+                    Self::fts5_search(conn, &query, limit * 2).unwrap_or_default();
+
+                // Vector similarity search (if embeddings available)
+                let vector_results = if let Some(ref qe) = query_embedding {
+                    Self::vector_search(conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                } else {
+                    Vec::new()
+                };
+
+                // Hybrid merge
+                let merged = if vector_results.is_empty() {
+                    keyword_results
+                        .iter()
+                        .map(|(id, score)| ScoredResult {
+                            id: id.clone(),
+                            vector_score: None,
+                            keyword_score: Some(*score),
+                            final_score: *score,
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    hybrid_merge(
+                        &vector_results,
+                        &keyword_results,
+                        vector_weight,
+                        keyword_weight,
+                        limit,
+                    )
+                };
+
+                // Fetch full entries for merged results in a single query
+                // instead of N round-trips (N+1 pattern).
+                let mut results = Vec::new();
+                if !merged.is_empty() {
+                    let placeholders: String = (1..=merged.len())
+                        .map(|i| format!("?{i}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let sql = format!(
+                        "SELECT id, key, content, category, created_at, session_id \
                      FROM memories WHERE id IN ({placeholders})"
-                );
-                let mut stmt = conn.prepare(&sql)?;
-                let id_params: Vec<Box<dyn rusqlite::types::ToSql>> = merged
-                    .iter()
-                    .map(|s| Box::new(s.id.clone()) as Box<dyn rusqlite::types::ToSql>)
-                    .collect();
-                let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                    id_params.iter().map(AsRef::as_ref).collect();
-                let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok((
-                        row.get::<_, String>(0)?,
-                        row.get::<_, String>(1)?,
-                        row.get::<_, String>(2)?,
-                        row.get::<_, String>(3)?,
-                        row.get::<_, String>(4)?,
-                        row.get::<_, Option<String>>(5)?,
-                    ))
-                })?;
+                    );
+                    let mut stmt = conn.prepare(&sql)?;
+                    let id_params: Vec<Box<dyn rusqlite::types::ToSql>> = merged
+                        .iter()
+                        .map(|s| Box::new(s.id.clone()) as Box<dyn rusqlite::types::ToSql>)
+                        .collect();
+                    let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+                        id_params.iter().map(AsRef::as_ref).collect();
+                    let rows = stmt.query_map(params_ref.as_slice(), |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, String>(2)?,
+                            row.get::<_, String>(3)?,
+                            row.get::<_, String>(4)?,
+                            row.get::<_, Option<String>>(5)?,
+                        ))
+                    })?;
 
-                let mut entry_map = std::collections::HashMap::new();
-                for row in rows {
-                    let (id, key, content, cat, ts, sid) = row?;
-                    entry_map.insert(id, (key, content, cat, ts, sid));
-                }
+                    let mut entry_map = std::collections::HashMap::new();
+                    for row in rows {
+                        let (id, key, content, cat, ts, sid) = row?;
+                        entry_map.insert(id, (key, content, cat, ts, sid));
+                    }
 
-                for scored in &merged {
-                    if let Some((key, content, cat, ts, sid)) = entry_map.remove(&scored.id) {
-                        let entry = MemoryEntry {
-                            id: scored.id.clone(),
-                            key,
-                            content,
-                            category: Self::str_to_category(&cat),
-                            timestamp: ts,
-                            session_id: sid,
-                            score: Some(f64::from(scored.final_score)),
-                        };
-                        if let Some(filter_sid) = session_ref {
-                            if entry.session_id.as_deref() != Some(filter_sid) {
-                                continue;
+                    for scored in &merged {
+                        if let Some((key, content, cat, ts, sid)) = entry_map.remove(&scored.id) {
+                            let entry = MemoryEntry {
+                                id: scored.id.clone(),
+                                key,
+                                content,
+                                category: Self::str_to_category(&cat),
+                                timestamp: ts,
+                                session_id: sid,
+                                score: Some(f64::from(scored.final_score)),
+                            };
+                            if let Some(filter_sid) = session_ref {
+                                if entry.session_id.as_deref() != Some(filter_sid) {
+                                    continue;
+                                }
                             }
+                            results.push(entry);
                         }
-                        results.push(entry);
                     }
                 }
-            }
 
-            // If hybrid returned nothing, fall back to LIKE search.
-            // Cap keyword count so we don't create too many SQL shapes,
-            // which helps prepared-statement cache efficiency.
-            if results.is_empty() {
-                const MAX_LIKE_KEYWORDS: usize = 8;
-                let keywords: Vec<String> = query
-                    .split_whitespace()
-                    .take(MAX_LIKE_KEYWORDS)
-                    .map(|w| format!("%{w}%"))
-                    .collect();
-                if !keywords.is_empty() {
-                    let conditions: Vec<String> = keywords
-                        .iter()
-                        .enumerate()
-                        .map(|(i, _)| {
-                            format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
-                        })
+                // If hybrid returned nothing, fall back to LIKE search.
+                // Cap keyword count so we don't create too many SQL shapes,
+                // which helps prepared-statement cache efficiency.
+                if results.is_empty() {
+                    const MAX_LIKE_KEYWORDS: usize = 8;
+                    let keywords: Vec<String> = query
+                        .split_whitespace()
+                        .take(MAX_LIKE_KEYWORDS)
+                        .map(|w| format!("%{w}%"))
                         .collect();
-                    let where_clause = conditions.join(" OR ");
-                    let sql = format!(
+                    if !keywords.is_empty() {
+                        let conditions: Vec<String> = keywords
+                            .iter()
+                            .enumerate()
+                            .map(|(i, _)| {
+                                format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
+                            })
+                            .collect();
+                        let where_clause = conditions.join(" OR ");
+                        let sql = format!(
                         "SELECT id, key, content, category, created_at, session_id FROM memories
                          WHERE {where_clause}
                          ORDER BY updated_at DESC
                          LIMIT ?{}",
                         keywords.len() * 2 + 1
                     );
-                    let mut stmt = conn.prepare(&sql)?;
-                    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-                    for kw in &keywords {
-                        param_values.push(Box::new(kw.clone()));
-                        param_values.push(Box::new(kw.clone()));
-                    }
-                    #[allow(clippy::cast_possible_wrap)]
-                    param_values.push(Box::new(limit as i64));
-                    let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                        param_values.iter().map(AsRef::as_ref).collect();
-                    let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                        Ok(MemoryEntry {
-                            id: row.get(0)?,
-                            key: row.get(1)?,
-                            content: row.get(2)?,
-                            category: Self::str_to_category(&row.get::<_, String>(3)?),
-                            timestamp: row.get(4)?,
-                            session_id: row.get(5)?,
-                            score: Some(1.0),
-                        })
-                    })?;
-                    for row in rows {
-                        let entry = row?;
-                        if let Some(sid) = session_ref {
-                            if entry.session_id.as_deref() != Some(sid) {
-                                continue;
+                        let mut stmt = conn.prepare(&sql)?;
+                        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+                        for kw in &keywords {
+                            param_values.push(Box::new(kw.clone()));
+                            param_values.push(Box::new(kw.clone()));
+                        }
+                        #[allow(clippy::cast_possible_wrap)]
+                        param_values.push(Box::new(limit as i64));
+                        let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+                            param_values.iter().map(AsRef::as_ref).collect();
+                        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+                            Ok(MemoryEntry {
+                                id: row.get(0)?,
+                                key: row.get(1)?,
+                                content: row.get(2)?,
+                                category: Self::str_to_category(&row.get::<_, String>(3)?),
+                                timestamp: row.get(4)?,
+                                session_id: row.get(5)?,
+                                score: Some(1.0),
+                            })
+                        })?;
+                        for row in rows {
+                            let entry = row?;
+                            if let Some(sid) = session_ref {
+                                if entry.session_id.as_deref() != Some(sid) {
+                                    continue;
+                                }
                             }
+                            results.push(entry);
                         }
-                        results.push(entry);
                     }
                 }
-            }
 
-            results.truncate(limit);
-            Ok(results)
-        })
-        .await?
+                results.truncate(limit);
+                Ok(results)
+            })
+            .await?;
+        self.open_entries(results)
     }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
-        let conn = self.conn.clone();
         let key = key.to_string();
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<Option<MemoryEntry>> {
-            let conn = conn.lock();
+        let entry = self.pool.run(move |conn| -> anyhow::Result<Option<MemoryEntry>> {
             let mut stmt = conn.prepare(
                 "SELECT id, key, content, category, created_at, session_id FROM memories WHERE key = ?1",
             )?;
@@ -815,7 +991,8 @@ impl Memory for SqliteMemory {
                 _ => Ok(None),
             }
         })
-        .await?
+        .await?;
+        Ok(self.open_entries(entry.into_iter().collect())?.pop())
     }
 
     async fn list(
@@ -825,95 +1002,147 @@ impl Memory for SqliteMemory {
     ) -> anyhow::Result<Vec<MemoryEntry>> {
         const DEFAULT_LIST_LIMIT: i64 = 1000;
 
-        let conn = self.conn.clone();
         let category = category.cloned();
         let sid = session_id.map(String::from);
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
-            let conn = conn.lock();
-            let session_ref = sid.as_deref();
-            let mut results = Vec::new();
-
-            let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: None,
-                })
-            };
+        let results = self
+            .pool
+            .run(move |conn| -> anyhow::Result<Vec<MemoryEntry>> {
+                let session_ref = sid.as_deref();
+                let mut results = Vec::new();
+
+                let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
+                    Ok(MemoryEntry {
+                        id: row.get(0)?,
+                        key: row.get(1)?,
+                        content: row.get(2)?,
+                        category: Self::str_to_category(&row.get::<_, String>(3)?),
+                        timestamp: row.get(4)?,
+                        session_id: row.get(5)?,
+                        score: None,
+                    })
+                };
 
-            if let Some(ref cat) = category {
-                let cat_str = Self::category_to_str(cat);
-                let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
+                if let Some(ref cat) = category {
+                    let cat_str = Self::category_to_str(cat);
+                    let mut stmt = conn.prepare(
+                        "SELECT id, key, content, category, created_at, session_id FROM memories
                      WHERE category = ?1 ORDER BY updated_at DESC LIMIT ?2",
-                )?;
-                let rows = stmt.query_map(params![cat_str, DEFAULT_LIST_LIMIT], row_mapper)?;
-                for row in rows {
-                    let entry = row?;
-                    if let Some(sid) = session_ref {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
+                    )?;
+                    let rows = stmt.query_map(params![cat_str, DEFAULT_LIST_LIMIT], row_mapper)?;
+                    for row in rows {
+                        let entry = row?;
+                        if let Some(sid) = session_ref {
+                            if entry.session_id.as_deref() != Some(sid) {
+                                continue;
+                            }
                         }
+                        results.push(entry);
                     }
-                    results.push(entry);
-                }
-            } else {
-                let mut stmt = conn.prepare(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
+                } else {
+                    let mut stmt = conn.prepare(
+                        "SELECT id, key, content, category, created_at, session_id FROM memories
                      ORDER BY updated_at DESC LIMIT ?1",
-                )?;
-                let rows = stmt.query_map(params![DEFAULT_LIST_LIMIT], row_mapper)?;
-                for row in rows {
-                    let entry = row?;
-                    if let Some(sid) = session_ref {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
+                    )?;
+                    let rows = stmt.query_map(params![DEFAULT_LIST_LIMIT], row_mapper)?;
+                    for row in rows {
+                        let entry = row?;
+                        if let Some(sid) = session_ref {
+                            if entry.session_id.as_deref() != Some(sid) {
+                                continue;
+                            }
                         }
+                        results.push(entry);
                     }
-                    results.push(entry);
                 }
-            }
 
-            Ok(results)
-        })
-        .await?
+                Ok(results)
+            })
+            .await?;
+        self.open_entries(results)
     }
 
     async fn forget(&self, key: &str) -> anyhow::Result<bool> {
-        let conn = self.conn.clone();
         let key = key.to_string();
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
-            let conn = conn.lock();
-            let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
-            Ok(affected > 0)
-        })
-        .await?
+        self.pool
+            .run(move |conn| -> anyhow::Result<bool> {
+                let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
+                entities::forget(conn, &key)?;
+                Ok(affected > 0)
+            })
+            .await
     }
 
     async fn count(&self) -> anyhow::Result<usize> {
-        let conn = self.conn.clone();
-
-        tokio::task::spawn_blocking(move || -> anyhow::Result<usize> {
-            let conn = conn.lock();
-            let count: i64 =
-                conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
-            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-            Ok(count as usize)
-        })
-        .await?
+        self.pool
+            .run(move |conn| -> anyhow::Result<usize> {
+                let count: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                Ok(count as usize)
+            })
+            .await
+    }
+
+    async fn store_batch(&self, writes: Vec<MemoryWrite>) -> anyhow::Result<()> {
+        if writes.is_empty() {
+            return Ok(());
+        }
+        let mut rows = Vec::with_capacity(writes.len());
+        for mut write in writes {
+            write.content = self.scrub_content(&write.key, &write.content);
+            let embedding_bytes = self
+                .get_or_compute_embedding(&write.content)
+                .await?
+                .map(|emb| vec_to_bytes(&emb));
+            let extraction = self.extract_entities(&write.content);
+            write.content = self.seal_content(&write.content)?;
+            rows.push((write, embedding_bytes, extraction));
+        }
+
+        self.pool
+            .run(move |conn| -> anyhow::Result<()> {
+                let tx = conn.unchecked_transaction()?;
+                let now = Local::now().to_rfc3339();
+                {
+                    let mut stmt = tx.prepare(UPSERT_MEMORY_SQL)?;
+                    for (write, embedding_bytes, _) in &rows {
+                        stmt.execute(params![
+                            Uuid::new_v4().to_string(),
+                            write.key,
+                            write.content,
+                            Self::category_to_str(&write.category),
+                            embedding_bytes,
+                            now,
+                            now,
+                            write.session_id
+                        ])?;
+                    }
+                }
+                for (write, _, extraction) in &rows {
+                    if let Some(extraction) = extraction {
+                        entities::record(&tx, &write.key, extraction, &now)?;
+                    }
+                }
+                tx.commit()?;
+                Ok(())
+            })
+            .await
     }
 
     async fn health_check(&self) -> bool {
-        let conn = self.conn.clone();
-        tokio::task::spawn_blocking(move || conn.lock().execute_batch("SELECT 1").is_ok())
+        self.pool
+            .run(|conn| Ok(conn.execute_batch("SELECT 1")?))
+            .await
+            .is_ok()
+    }
+
+    async fn entity(&self, name: &str) -> anyhow::Result<Option<EntityNode>> {
+        let name = name.to_string();
+        self.pool
+            .run(move |conn| entities::lookup(conn, &name))
             .await
-            .unwrap_or(false)
     }
 }
 
@@ -1232,7 +1461,7 @@ mod tests {
     #[tokio::test]
     async fn schema_has_fts5_table() {
         let (_tmp, mem) = temp_sqlite();
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         // FTS5 table should exist
         let count: i64 = conn
             .query_row(
@@ -1247,7 +1476,7 @@ mod tests {
     #[tokio::test]
     async fn schema_has_embedding_cache() {
         let (_tmp, mem) = temp_sqlite();
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         let count: i64 = conn
             .query_row(
                 "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='embedding_cache'",
@@ -1261,7 +1490,7 @@ mod tests {
     #[tokio::test]
     async fn schema_memories_has_embedding_column() {
         let (_tmp, mem) = temp_sqlite();
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         // Check that embedding column exists by querying it
         let result = conn.execute_batch("SELECT embedding FROM memories LIMIT 0");
         assert!(result.is_ok());
@@ -1281,7 +1510,7 @@ mod tests {
         .await
         .unwrap();
 
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         let count: i64 = conn
             .query_row(
                 "SELECT COUNT(*) FROM memories_fts WHERE memories_fts MATCH '\"unique_searchterm_xyz\"'",
@@ -1305,7 +1534,7 @@ mod tests {
         .unwrap();
         mem.forget("del_key").await.unwrap();
 
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         let count: i64 = conn
             .query_row(
                 "SELECT COUNT(*) FROM memories_fts WHERE memories_fts MATCH '\"deletable_content_abc\"'",
@@ -1331,7 +1560,7 @@ mod tests {
             .await
             .unwrap();
 
-        let conn = mem.conn.lock();
+        let conn = mem.pool.get().unwrap();
         // Old content should not be findable
         let old: i64 = conn
             .query_row(
@@ -1391,6 +1620,30 @@ mod tests {
         assert_eq!(entry.content, "value with timeout");
     }
 
+    #[tokio::test]
+    async fn concurrent_stores_from_two_instances_succeed() {
+        let tmp = TempDir::new().unwrap();
+        let first = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let second = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+
+        let mut writes = tokio::task::JoinSet::new();
+        for i in 0..20 {
+            let mem = if i % 2 == 0 {
+                Arc::clone(&first)
+            } else {
+                Arc::clone(&second)
+            };
+            writes.spawn(async move {
+                mem.store(&format!("k{i}"), "value", MemoryCategory::Core, None)
+                    .await
+            });
+        }
+        while let Some(result) = writes.join_next().await {
+            result.unwrap().unwrap();
+        }
+        assert_eq!(first.count().await.unwrap(), 20);
+    }
+
     // ── With-embedder constructor test ───────────────────────────
 
     #[test]
@@ -2033,4 +2286,93 @@ mod tests {
 
         assert_eq!(mem.count().await.unwrap(), 1);
     }
+
+    fn raw_content(mem: &SqliteMemory, key: &str) -> String {
+        mem.pool
+            .get()
+            .unwrap()
+            .query_row(
+                "SELECT content FROM memories WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn encrypted_content_is_sealed_on_disk_and_still_recalled() {
+        let (tmp, mem) = temp_sqlite();
+        let mem = mem.with_cipher(Some(ContentCipher::new(tmp.path(), true)));
+        mem.store(
+            "diet",
+            "Prefers oolong tea",
+            MemoryCategory::Core,
+            Some("s1"),
+        )
+        .await
+        .unwrap();
+        mem.store("pet", "Has a cat named Miso", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        assert!(ContentCipher::is_sealed(&raw_content(&mem, "diet")));
+        assert_eq!(
+            mem.get("diet").await.unwrap().unwrap().content,
+            "Prefers oolong tea"
+        );
+
+        let hits = mem.recall("OOLONG", 5, None).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].content, "Prefers oolong tea");
+        assert!(mem
+            .recall("oolong", 5, Some("s2"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(mem.recall("pet", 5, None).await.unwrap()[0].key, "pet");
+    }
+
+    #[tokio::test]
+    async fn migrate_encryption_round_trips_existing_entries() {
+        let (tmp, mem) = temp_sqlite();
+        mem.store("a", "plain note", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let cipher = ContentCipher::new(tmp.path(), true);
+
+        assert_eq!(mem.migrate_encryption(&cipher, true).await.unwrap(), 1);
+        assert_eq!(mem.migrate_encryption(&cipher, true).await.unwrap(), 0);
+        assert!(ContentCipher::is_sealed(&raw_content(&mem, "a")));
+
+        assert_eq!(mem.migrate_encryption(&cipher, false).await.unwrap(), 1);
+        assert_eq!(raw_content(&mem, "a"), "plain note");
+        assert_eq!(mem.recall("note", 5, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pii_is_scrubbed_before_store() {
+        let (_tmp, mem) = temp_sqlite();
+        let mem = mem.with_pii_scrubber(Some(PiiScrubber::new(&[
+            crate::memory::pii::PiiKind::Email,
+        ])));
+        mem.store(
+            "contact",
+            "Reach me at sam@example.com",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store_batch(vec![MemoryWrite {
+            key: "batched".into(),
+            content: "cc sam@example.com".into(),
+            category: MemoryCategory::Core,
+            session_id: None,
+        }])
+        .await
+        .unwrap();
+
+        assert_eq!(raw_content(&mem, "contact"), "Reach me at [REDACTED:email]");
+        assert_eq!(raw_content(&mem, "batched"), "cc [REDACTED:email]");
+    }
 }
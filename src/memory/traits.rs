@@ -1,3 +1,4 @@
+use super::entities::EntityNode;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -52,6 +53,15 @@ impl std::fmt::Display for MemoryCategory {
 }
 
 /// Core memory trait — implement for any persistence backend
+/// One pending `store` call, as handed to [`Memory::store_batch`].
+#[derive(Debug, Clone)]
+pub struct MemoryWrite {
+    pub key: String,
+    pub content: String,
+    pub category: MemoryCategory,
+    pub session_id: Option<String>,
+}
+
 #[async_trait]
 pub trait Memory: Send + Sync {
     /// Backend name
@@ -92,6 +102,32 @@ pub trait Memory: Send + Sync {
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Store several entries in order. Backends that can write them in one
+    /// transaction override this; the default stores them one at a time.
+    async fn store_batch(&self, writes: Vec<MemoryWrite>) -> anyhow::Result<()> {
+        for write in writes {
+            self.store(
+                &write.key,
+                &write.content,
+                write.category,
+                write.session_id.as_deref(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Persist buffered writes. A no-op for backends that do not buffer.
+    async fn flush(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Look up an entity and its relations in the entity graph
+    /// (`[memory] entity_graph`). Backends without one return `None`.
+    async fn entity(&self, _name: &str) -> anyhow::Result<Option<EntityNode>> {
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
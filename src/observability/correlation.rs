@@ -0,0 +1,82 @@
+//! Per-request correlation IDs.
+//!
+//! Every inbound channel message and gateway request runs inside
+//! [`scope`], which stores an ID in a task-local and enters a `request`
+//! tracing span carrying `request_id`. Log lines from providers, tools and
+//! channels inside the turn therefore carry the ID, and code that needs it
+//! explicitly (audit entries, gateway replies) reads [`current`].
+
+use std::future::Future;
+use tracing::Instrument;
+
+/// Header accepted on, and echoed by, gateway routes.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_CHARS: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// A fresh correlation ID.
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Caller-supplied ID, if it is safe to log and echo back: 1–128 characters
+/// of ASCII letters, digits, `-`, `_`, `.` or `:`.
+pub fn sanitize(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let valid = !raw.is_empty()
+        && raw.len() <= MAX_REQUEST_ID_CHARS
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    valid.then(|| raw.to_string())
+}
+
+/// The correlation ID of the current task, if it runs inside [`scope`].
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` with `id` as its correlation ID.
+pub async fn scope<F: Future>(id: String, fut: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %id);
+    REQUEST_ID.scope(id, fut.instrument(span)).await
+}
+
+/// Wrap `fut` so a spawned task keeps the caller's correlation ID (or gets
+/// a new one). Task-locals and spans do not cross `tokio::spawn` otherwise.
+pub fn propagate<F: Future>(fut: F) -> impl Future<Output = F::Output> {
+    scope(current().unwrap_or_else(new_id), fut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_accepts_safe_ids_only() {
+        assert_eq!(
+            sanitize(" abc-123:x.y_z ").as_deref(),
+            Some("abc-123:x.y_z")
+        );
+        assert_eq!(sanitize(""), None);
+        assert_eq!(sanitize("a b"), None);
+        assert_eq!(sanitize("id\r\nX-Evil: 1"), None);
+        assert_eq!(sanitize(&"a".repeat(129)), None);
+    }
+
+    #[tokio::test]
+    async fn scope_sets_current_and_propagates_into_spawned_tasks() {
+        assert_eq!(current(), None);
+        let seen = scope("req-1".into(), async {
+            let inner = tokio::spawn(propagate(async { current() })).await.unwrap();
+            (current(), inner)
+        })
+        .await;
+        assert_eq!(seen, (Some("req-1".into()), Some("req-1".into())));
+        assert_eq!(current(), None);
+    }
+}
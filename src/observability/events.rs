@@ -0,0 +1,212 @@
+//! Process-wide observability event bus.
+//!
+//! Subsystems publish [`ObserverEvent`]s and [`ObserverMetric`]s to the bus
+//! instead of talking to a backend; backends subscribe. The backend selected
+//! by `[observability] backend` is subscribed by
+//! [`create_observer`](super::create_observer), which hands callers a
+//! [`BusObserver`]. Code without an observer handle (gateway auth, provider
+//! middleware) calls [`publish`] directly. A new sink is one
+//! [`EventBus::subscribe`] call.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use parking_lot::{Mutex, RwLock};
+use std::any::Any;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock};
+
+/// Handle returned by [`EventBus::subscribe`].
+pub type SubscriberId = u64;
+
+static GLOBAL: LazyLock<EventBus> = LazyLock::new(EventBus::new);
+
+/// Fans published events out to every subscriber.
+pub struct EventBus {
+    subscribers: RwLock<Vec<(SubscriberId, Arc<dyn Observer>)>>,
+    next_id: AtomicU64,
+    /// The config-selected backend: its name and subscription.
+    backend: Mutex<Option<(String, SubscriberId)>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            subscribers: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            backend: Mutex::new(None),
+        }
+    }
+
+    pub fn subscribe(&self, observer: Arc<dyn Observer>) -> SubscriberId {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.subscribers.write().push((id, observer));
+        id
+    }
+
+    /// Returns `false` when `id` was not subscribed.
+    pub fn unsubscribe(&self, id: SubscriberId) -> bool {
+        let mut subscribers = self.subscribers.write();
+        let before = subscribers.len();
+        subscribers.retain(|(sid, _)| *sid != id);
+        subscribers.len() != before
+    }
+
+    /// Subscribe the backend named `name`, replacing a previously installed
+    /// backend. A no-op when `name` is already installed, so runtimes that
+    /// each call `create_observer` share one backend instance.
+    pub fn set_backend(&self, name: &str, make: impl FnOnce() -> Arc<dyn Observer>) {
+        let mut backend = self.backend.lock();
+        if backend.as_ref().is_some_and(|(current, _)| current == name) {
+            return;
+        }
+        if let Some((_, old)) = backend.take() {
+            self.unsubscribe(old);
+        }
+        *backend = Some((name.to_string(), self.subscribe(make())));
+    }
+
+    /// Names of current subscribers, in subscription order.
+    pub fn subscriber_names(&self) -> Vec<String> {
+        self.subscribers
+            .read()
+            .iter()
+            .map(|(_, observer)| observer.name().to_string())
+            .collect()
+    }
+
+    /// Subscribers are cloned out of the lock so one may publish or
+    /// subscribe from inside `record_event` without deadlocking.
+    fn snapshot(&self) -> Vec<Arc<dyn Observer>> {
+        self.subscribers
+            .read()
+            .iter()
+            .map(|(_, observer)| Arc::clone(observer))
+            .collect()
+    }
+
+    pub fn publish(&self, event: &ObserverEvent) {
+        for observer in self.snapshot() {
+            observer.record_event(event);
+        }
+    }
+
+    pub fn publish_metric(&self, metric: &ObserverMetric) {
+        for observer in self.snapshot() {
+            observer.record_metric(metric);
+        }
+    }
+
+    pub fn flush(&self) {
+        for observer in self.snapshot() {
+            observer.flush();
+        }
+    }
+}
+
+/// The process-wide bus.
+pub fn global() -> &'static EventBus {
+    &GLOBAL
+}
+
+/// Publish `event` on the process-wide bus.
+pub fn publish(event: &ObserverEvent) {
+    global().publish(event);
+}
+
+/// Publish `metric` on the process-wide bus.
+pub fn publish_metric(metric: &ObserverMetric) {
+    global().publish_metric(metric);
+}
+
+/// Observer handle that forwards everything to the process-wide bus.
+pub struct BusObserver;
+
+impl Observer for BusObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        publish(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        publish_metric(metric);
+    }
+
+    fn flush(&self) {
+        global().flush();
+    }
+
+    fn name(&self) -> &str {
+        "bus"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+        metrics: Mutex<u64>,
+    }
+
+    impl Observer for Recorder {
+        fn record_event(&self, event: &ObserverEvent) {
+            self.events.lock().push(format!("{event:?}"));
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {
+            *self.metrics.lock() += 1;
+        }
+
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    #[test]
+    fn publishes_to_every_subscriber_until_unsubscribed() {
+        let bus = EventBus::new();
+        let first = Arc::new(Recorder::default());
+        let second = Arc::new(Recorder::default());
+        let first_id = bus.subscribe(first.clone());
+        bus.subscribe(second.clone());
+
+        bus.publish(&ObserverEvent::HeartbeatTick);
+        bus.publish_metric(&ObserverMetric::QueueDepth(3));
+        assert!(bus.unsubscribe(first_id));
+        assert!(!bus.unsubscribe(first_id));
+        bus.publish(&ObserverEvent::TurnComplete);
+
+        assert_eq!(first.events.lock().len(), 1);
+        assert_eq!(second.events.lock().len(), 2);
+        assert_eq!(*second.metrics.lock(), 1);
+    }
+
+    #[test]
+    fn set_backend_replaces_only_when_the_name_changes() {
+        let bus = EventBus::new();
+        let extra = Arc::new(Recorder::default());
+        bus.subscribe(extra);
+
+        bus.set_backend("noop", || Arc::new(NoopObserver));
+        bus.set_backend("noop", || panic!("same backend must not be rebuilt"));
+        assert_eq!(bus.subscriber_names(), ["recorder", "noop"]);
+
+        bus.set_backend("recorder", || Arc::new(Recorder::default()));
+        assert_eq!(bus.subscriber_names(), ["recorder", "recorder"]);
+    }
+}
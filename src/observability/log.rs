@@ -34,9 +34,10 @@ impl Observer for LogObserver {
                 tool,
                 duration,
                 success,
+                error_kind,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
-                info!(tool = %tool, duration_ms = ms, success = success, "tool.call");
+                info!(tool = %tool, duration_ms = ms, success = success, error_kind = ?error_kind, "tool.call");
             }
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
@@ -47,6 +48,9 @@ impl Observer for LogObserver {
             ObserverEvent::HeartbeatTick => {
                 info!("heartbeat.tick");
             }
+            ObserverEvent::GatewayRequestRejected { route, reason } => {
+                info!(route = %route, reason = %reason, "gateway.rejected");
+            }
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
@@ -166,12 +170,17 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: false,
+            error_kind: Some("timeout".into()),
         });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "outbound".into(),
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
+        obs.record_event(&ObserverEvent::GatewayRequestRejected {
+            route: "/webhook".into(),
+            reason: "unauthorized".into(),
+        });
         obs.record_event(&ObserverEvent::Error {
             component: "provider".into(),
             message: "timeout".into(),
@@ -1,6 +1,9 @@
+pub mod correlation;
+pub mod events;
 pub mod log;
 pub mod noop;
 pub mod runtime_trace;
+pub mod stats;
 pub mod traits;
 
 #[allow(unused_imports)]
@@ -9,9 +12,17 @@ pub use noop::NoopObserver;
 pub use traits::{Observer, ObserverEvent};
 
 use crate::config::ObservabilityConfig;
+use std::sync::Arc;
 
-/// Factory: create the right observer from config
+/// Factory: subscribe the configured backend to the event bus and return a
+/// handle that publishes to it.
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
+    events::global().set_backend(&config.backend, || Arc::from(create_backend(config)));
+    Box::new(events::BusObserver)
+}
+
+/// Factory: create the backend observer named by `[observability] backend`
+pub fn create_backend(config: &ObservabilityConfig) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
         "none" | "noop" => Box::new(NoopObserver),
@@ -35,7 +46,7 @@ mod tests {
             backend: "none".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_backend(&cfg).name(), "noop");
     }
 
     #[test]
@@ -44,7 +55,7 @@ mod tests {
             backend: "noop".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_backend(&cfg).name(), "noop");
     }
 
     #[test]
@@ -53,7 +64,7 @@ mod tests {
             backend: "log".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "log");
+        assert_eq!(create_backend(&cfg).name(), "log");
     }
 
     #[test]
@@ -62,7 +73,7 @@ mod tests {
             backend: "xyzzy_unknown".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_backend(&cfg).name(), "noop");
     }
 
     #[test]
@@ -71,7 +82,7 @@ mod tests {
             backend: String::new(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_backend(&cfg).name(), "noop");
     }
 
     #[test]
@@ -80,6 +91,15 @@ mod tests {
             backend: "xyzzy_garbage_123".into(),
             ..ObservabilityConfig::default()
         };
-        assert_eq!(create_observer(&cfg).name(), "noop");
+        assert_eq!(create_backend(&cfg).name(), "noop");
+    }
+
+    #[test]
+    fn create_observer_returns_bus_handle() {
+        let cfg = ObservabilityConfig {
+            backend: "log".into(),
+            ..ObservabilityConfig::default()
+        };
+        assert_eq!(create_observer(&cfg).name(), "bus");
     }
 }
@@ -56,6 +56,7 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_secs(1),
             success: true,
+            error_kind: None,
         });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "cli".into(),
@@ -0,0 +1,500 @@
+//! Local usage statistics shown by `zeroclaw stats`.
+//!
+//! [`StatsObserver`] is an event-bus subscriber that counts turns, tool
+//! calls, errors and inbound channel messages per day (in the configured
+//! `timezone`) and stores the counts in `<workspace>/state/stats.db`. Only
+//! counts are kept, never message content, and nothing is sent anywhere.
+//! Disable with `[observability] local_stats = false`.
+
+use anyhow::{Context, Result};
+use chrono::{Days, NaiveDate, Utc, Weekday};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::config::Config;
+use crate::util::time::TimeService;
+
+const STATS_DB_FILE: &str = "stats.db";
+/// Counts are buffered in memory and written at most this often (and on
+/// flush), keeping SQLite off the event hot path.
+const WRITE_INTERVAL: Duration = Duration::from_secs(30);
+/// Width of the longest bar in `zeroclaw stats` charts.
+const BAR_WIDTH: usize = 30;
+
+pub fn stats_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(STATS_DB_FILE)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Counter {
+    Turns,
+    ToolCalls,
+    Errors,
+    Messages,
+}
+
+impl Counter {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Turns => "turns",
+            Self::ToolCalls => "tool_calls",
+            Self::Errors => "errors",
+            Self::Messages => "messages",
+        }
+    }
+}
+
+/// Counts for one day, or for the week starting on `day`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DayCounts {
+    pub day: NaiveDate,
+    pub turns: u64,
+    pub tool_calls: u64,
+    pub errors: u64,
+    pub messages: u64,
+}
+
+impl DayCounts {
+    fn add(&mut self, counter: &str, count: u64) {
+        match counter {
+            "turns" => self.turns += count,
+            "tool_calls" => self.tool_calls += count,
+            "errors" => self.errors += count,
+            "messages" => self.messages += count,
+            _ => {}
+        }
+    }
+}
+
+/// Pending increments keyed by day, counter and channel (empty when the
+/// event names none).
+type Increments = HashMap<(NaiveDate, Counter, String), u64>;
+
+pub struct StatsStore {
+    conn: Mutex<Connection>,
+}
+
+impl StatsStore {
+    /// Open (creating if missing) the stats table at `db_path`.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS daily_counts (
+                day      TEXT NOT NULL,
+                counter  TEXT NOT NULL,
+                channel  TEXT NOT NULL DEFAULT '',
+                count    INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, counter, channel)
+            );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn add(&self, increments: &Increments) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for ((day, counter, channel), count) in increments {
+            tx.execute(
+                "INSERT INTO daily_counts (day, counter, channel, count) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(day, counter, channel) DO UPDATE SET count = count + excluded.count",
+                params![day.to_string(), counter.as_str(), channel, *count],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// One entry per day from `from` to `to` inclusive, zero-filled.
+    pub fn daily(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<DayCounts>> {
+        let mut days: BTreeMap<NaiveDate, DayCounts> = from
+            .iter_days()
+            .take_while(|day| *day <= to)
+            .map(|day| {
+                (
+                    day,
+                    DayCounts {
+                        day,
+                        ..DayCounts::default()
+                    },
+                )
+            })
+            .collect();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT day, counter, SUM(count) FROM daily_counts
+             WHERE day >= ?1 AND day <= ?2 GROUP BY day, counter",
+        )?;
+        let rows = stmt.query_map(params![from.to_string(), to.to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (day, counter, count) = row?;
+            let Ok(day) = day.parse::<NaiveDate>() else {
+                continue;
+            };
+            if let Some(counts) = days.get_mut(&day) {
+                counts.add(&counter, count);
+            }
+        }
+        Ok(days.into_values().collect())
+    }
+
+    /// Inbound messages per channel since `from`, busiest first.
+    pub fn channels(&self, from: NaiveDate) -> Result<Vec<(String, u64)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT channel, SUM(count) AS total FROM daily_counts
+             WHERE day >= ?1 AND counter = 'messages' AND channel != ''
+             GROUP BY channel ORDER BY total DESC, channel",
+        )?;
+        let rows = stmt.query_map(params![from.to_string()], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        rows.collect::<rusqlite::Result<_>>().map_err(Into::into)
+    }
+}
+
+/// Event-bus subscriber that buffers counts and writes them to a
+/// [`StatsStore`].
+pub struct StatsObserver {
+    store: Arc<StatsStore>,
+    time: TimeService,
+    pending: Mutex<Increments>,
+    last_write: Mutex<Instant>,
+}
+
+impl StatsObserver {
+    pub fn new(store: StatsStore, time: TimeService) -> Self {
+        Self {
+            store: Arc::new(store),
+            time,
+            pending: Mutex::new(HashMap::new()),
+            last_write: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn count(&self, counter: Counter, channel: &str) {
+        let today = self.time.localize(Utc::now()).date_naive();
+        *self
+            .pending
+            .lock()
+            .entry((today, counter, channel.to_string()))
+            .or_default() += 1;
+    }
+
+    fn write(store: &StatsStore, increments: &Increments) {
+        if let Err(e) = store.add(increments) {
+            tracing::debug!("Failed to record usage statistics: {e:#}");
+        }
+    }
+}
+
+impl Observer for StatsObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        match event {
+            ObserverEvent::TurnComplete => self.count(Counter::Turns, ""),
+            ObserverEvent::ToolCall { success, .. } => {
+                self.count(Counter::ToolCalls, "");
+                if !success {
+                    self.count(Counter::Errors, "");
+                }
+            }
+            ObserverEvent::Error { .. } => self.count(Counter::Errors, ""),
+            ObserverEvent::ChannelMessage { channel, direction } if direction == "inbound" => {
+                self.count(Counter::Messages, channel);
+            }
+            _ => return,
+        }
+
+        {
+            let mut last_write = self.last_write.lock();
+            if last_write.elapsed() < WRITE_INTERVAL {
+                return;
+            }
+            *last_write = Instant::now();
+        }
+        let increments = std::mem::take(&mut *self.pending.lock());
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let store = Arc::clone(&self.store);
+                handle.spawn_blocking(move || Self::write(&store, &increments));
+            }
+            Err(_) => Self::write(&self.store, &increments),
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn flush(&self) {
+        let increments = std::mem::take(&mut *self.pending.lock());
+        if !increments.is_empty() {
+            Self::write(&self.store, &increments);
+        }
+        *self.last_write.lock() = Instant::now();
+    }
+
+    fn name(&self) -> &str {
+        "local-stats"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Subscribe a [`StatsObserver`] for `config` to the event bus, once per
+/// process. A no-op when `[observability] local_stats` is off.
+pub fn install(config: &Config) {
+    static INSTALLED: OnceLock<()> = OnceLock::new();
+    if !config.observability.local_stats {
+        return;
+    }
+    INSTALLED.get_or_init(|| {
+        let path = stats_db_path(&config.workspace_dir);
+        let first_run = !path.exists();
+        match StatsStore::open(&path) {
+            Ok(store) => {
+                if first_run {
+                    tracing::info!(
+                        "Recording local usage statistics in {} (view with `zeroclaw stats`; \
+                         disable with [observability] local_stats = false)",
+                        path.display()
+                    );
+                }
+                let observer = StatsObserver::new(store, TimeService::from_config(config));
+                super::events::global().subscribe(Arc::new(observer));
+            }
+            Err(e) => tracing::warn!("Local usage statistics disabled: {e:#}"),
+        }
+    });
+}
+
+/// Sum `days` into weeks starting on Monday.
+fn weekly(days: &[DayCounts]) -> Vec<DayCounts> {
+    let mut weeks: Vec<DayCounts> = Vec::new();
+    for day in days {
+        let start = day.day.week(Weekday::Mon).first_day();
+        if weeks.last().is_none_or(|week| week.day != start) {
+            weeks.push(DayCounts {
+                day: start,
+                ..DayCounts::default()
+            });
+        }
+        if let Some(week) = weeks.last_mut() {
+            week.turns += day.turns;
+            week.tool_calls += day.tool_calls;
+            week.errors += day.errors;
+            week.messages += day.messages;
+        }
+    }
+    weeks
+}
+
+fn bar(value: u64, max: u64) -> String {
+    if value == 0 || max == 0 {
+        return String::new();
+    }
+    let len = value.saturating_mul(BAR_WIDTH as u64).div_ceil(max);
+    "█".repeat(usize::try_from(len).unwrap_or(BAR_WIDTH).min(BAR_WIDTH))
+}
+
+/// Chart of turns per row, with tool call, error and message columns.
+fn render_chart(title: &str, rows: &[DayCounts], label: impl Fn(NaiveDate) -> String) -> String {
+    let max = rows.iter().map(|row| row.turns).max().unwrap_or(0);
+    let mut out = format!(
+        "{title}\n  {:<14} {:<width$} {:>6} {:>6} {:>6} {:>8}\n",
+        "",
+        "",
+        "turns",
+        "tools",
+        "errors",
+        "messages",
+        width = BAR_WIDTH
+    );
+    for row in rows {
+        let _ = writeln!(
+            out,
+            "  {:<14} {:<width$} {:>6} {:>6} {:>6} {:>8}",
+            label(row.day),
+            bar(row.turns, max),
+            row.turns,
+            row.tool_calls,
+            row.errors,
+            row.messages,
+            width = BAR_WIDTH
+        );
+    }
+    out
+}
+
+/// Render the report for the `days` and `weeks` ending on `today`.
+fn render(store: &StatsStore, today: NaiveDate, days: u32, weeks: u32) -> Result<String> {
+    let day_from = today - Days::new(u64::from(days.max(1)) - 1);
+    let week_from =
+        today.week(Weekday::Mon).first_day() - Days::new(7 * (u64::from(weeks.max(1)) - 1));
+    let daily = store.daily(day_from.min(week_from), today)?;
+
+    let recent: Vec<DayCounts> = daily
+        .iter()
+        .copied()
+        .filter(|d| d.day >= day_from)
+        .collect();
+    let older: Vec<DayCounts> = daily
+        .iter()
+        .copied()
+        .filter(|d| d.day >= week_from)
+        .collect();
+    let mut out = render_chart(&format!("Last {} days", recent.len()), &recent, |day| {
+        day.format("%a %Y-%m-%d").to_string()
+    });
+    out.push('\n');
+    let weeks = weekly(&older);
+    out.push_str(&render_chart(
+        &format!("Last {} weeks", weeks.len()),
+        &weeks,
+        |day| format!("wk {}", day.format("%Y-%m-%d")),
+    ));
+
+    let channels = store.channels(day_from)?;
+    let _ = write!(out, "\nChannel messages (last {} days)\n", recent.len());
+    if channels.is_empty() {
+        out.push_str("  none\n");
+    }
+    for (channel, count) in channels {
+        let _ = writeln!(out, "  {channel:<14} {count:>8}");
+    }
+    Ok(out)
+}
+
+/// `zeroclaw stats`: charts of the last `days` days and `weeks` weeks.
+pub fn run(config: &Config, days: u32, weeks: u32) -> Result<()> {
+    let path = stats_db_path(&config.workspace_dir);
+    if !path.exists() {
+        println!("No usage recorded yet.");
+        if !config.observability.local_stats {
+            println!("Enable with [observability] local_stats = true.");
+        }
+        return Ok(());
+    }
+    let store = StatsStore::open(&path)?;
+    let today = TimeService::from_config(config)
+        .localize(Utc::now())
+        .date_naive();
+    print!("{}", render(&store, today, days, weeks)?);
+    if !config.observability.local_stats {
+        println!("\nRecording is off ([observability] local_stats = false).");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn observer_counts_events_and_writes_on_flush() {
+        let tmp = TempDir::new().unwrap();
+        let path = stats_db_path(tmp.path());
+        let observer = StatsObserver::new(StatsStore::open(&path).unwrap(), TimeService::local());
+
+        observer.record_event(&ObserverEvent::TurnComplete);
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(5),
+            success: false,
+            error_kind: Some("failed".into()),
+        });
+        for direction in ["inbound", "inbound", "outbound"] {
+            observer.record_event(&ObserverEvent::ChannelMessage {
+                channel: "telegram".into(),
+                direction: direction.into(),
+            });
+        }
+        observer.record_event(&ObserverEvent::HeartbeatTick);
+        let store = StatsStore::open(&path).unwrap();
+        let today = TimeService::local().localize(Utc::now()).date_naive();
+        assert_eq!(store.daily(today, today).unwrap()[0].turns, 0);
+
+        observer.flush();
+        let counts = store.daily(today, today).unwrap();
+        assert_eq!(
+            counts,
+            vec![DayCounts {
+                day: today,
+                turns: 1,
+                tool_calls: 1,
+                errors: 1,
+                messages: 2,
+            }]
+        );
+        assert_eq!(
+            store.channels(today).unwrap(),
+            vec![("telegram".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn report_charts_days_weeks_and_channels() {
+        let tmp = TempDir::new().unwrap();
+        let store = StatsStore::open(&stats_db_path(tmp.path())).unwrap();
+        let mut increments = Increments::new();
+        increments.insert((date("2026-10-14"), Counter::Turns, String::new()), 4);
+        increments.insert((date("2026-10-15"), Counter::Turns, String::new()), 2);
+        increments.insert((date("2026-10-05"), Counter::Turns, String::new()), 8);
+        increments.insert((date("2026-10-15"), Counter::Messages, "discord".into()), 3);
+        store.add(&increments).unwrap();
+        store.add(&increments).unwrap();
+
+        let report = render(&store, date("2026-10-16"), 3, 2).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "Last 3 days");
+        assert!(lines[2].starts_with(&format!("  Wed 2026-10-14 {} ", "█".repeat(30))));
+        assert!(lines[2].ends_with("     8      0      0        0"));
+        assert!(lines[3].contains(&format!(" {} ", "█".repeat(15))));
+        assert!(lines[4].ends_with("     0      0      0        0"));
+        assert_eq!(lines[6], "Last 2 weeks");
+        assert!(lines[8].starts_with("  wk 2026-10-05 "));
+        assert!(lines[8].contains("    16 "));
+        assert!(lines[9].contains("    12 "));
+        assert!(report.ends_with("Channel messages (last 3 days)\n  discord               6\n"));
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        let days: Vec<DayCounts> = date("2026-10-04")
+            .iter_days()
+            .take(3)
+            .map(|day| DayCounts {
+                day,
+                turns: 1,
+                ..DayCounts::default()
+            })
+            .collect();
+        let weeks = weekly(&days);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!((weeks[0].day, weeks[0].turns), (date("2026-09-28"), 1));
+        assert_eq!((weeks[1].day, weeks[1].turns), (date("2026-10-05"), 2));
+    }
+}
@@ -46,6 +46,8 @@ pub enum ObserverEvent {
         tool: String,
         duration: Duration,
         success: bool,
+        /// Failure class (`ToolErrorKind`, snake_case) when the call failed.
+        error_kind: Option<String>,
     },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
@@ -58,6 +60,13 @@ pub enum ObserverEvent {
     },
     /// Periodic heartbeat tick from the runtime keep-alive loop.
     HeartbeatTick,
+    /// The gateway refused a webhook request before running the agent.
+    GatewayRequestRejected {
+        /// Route path (e.g., `"/webhook"`).
+        route: String,
+        /// `"rate_limited"`, `"unauthorized"` or `"invalid_secret"`.
+        reason: String,
+    },
     /// An error occurred in a named component.
     Error {
         /// Subsystem where the error originated (e.g., `"provider"`, `"gateway"`).
@@ -188,6 +197,7 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_kind: None,
         };
         let metric = ObserverMetric::RequestLatency(Duration::from_millis(8));
 
@@ -0,0 +1,426 @@
+//! Plugins declared by `plugin.toml` and run outside the agent: either as a
+//! separate process or as a sandboxed WASM module. Both runtimes speak the
+//! same JSON contract, one request per invocation.
+//!
+//! Requests are tagged by `type`:
+//! - `{"type":"command","command":"ticket","args":"ABC-1"}` → `{"output":"..."}`
+//! - `{"type":"hook","event":{...}}` → `{"action":"continue"}`,
+//!   `{"action":"modify","data":{...}}` or `{"action":"cancel","reason":"..."}`
+//! - `{"type":"prompt_section","section":"...","model":"...","workspace_dir":"...","tools":[...]}`
+//!   → `{"content":"..."}`
+//!
+//! Any reply may instead be `{"error":"..."}`. An empty reply from a hook
+//! means `continue`.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::manifest::{HookManifest, PluginManifest, PluginRuntime};
+use super::process::ProcessRunner;
+use super::traits::{Hook, HookAction, HookEvent, HookEventType, Plugin, PluginCommand};
+use crate::agent::prompt::{PromptContext, PromptSection};
+use crate::memory::Memory;
+
+/// JSON request handed to the plugin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginRequest {
+    Command {
+        command: String,
+        args: String,
+    },
+    Hook {
+        event: HookEvent,
+    },
+    PromptSection {
+        section: String,
+        model: String,
+        workspace_dir: PathBuf,
+        tools: Vec<String>,
+    },
+}
+
+/// Hook reply, mapped onto [`HookAction`].
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum HookReply {
+    Continue,
+    Modify {
+        #[serde(default)]
+        data: HashMap<String, serde_json::Value>,
+    },
+    Cancel {
+        #[serde(default)]
+        reason: String,
+    },
+}
+
+impl From<HookReply> for HookAction {
+    fn from(reply: HookReply) -> Self {
+        match reply {
+            HookReply::Continue => Self::Continue,
+            HookReply::Modify { data } => Self::Modify(data),
+            HookReply::Cancel { reason } => Self::Cancel { reason },
+        }
+    }
+}
+
+/// Executes one plugin request to completion. Returns `Null` for an empty
+/// reply.
+pub trait PluginRunner: Send + Sync {
+    fn invoke_blocking(&self, request: &PluginRequest) -> Result<serde_json::Value>;
+}
+
+/// Decode a raw plugin reply, surfacing `{"error": ...}` as an error.
+pub(super) fn parse_reply(plugin: &str, raw: &[u8]) -> Result<serde_json::Value> {
+    let text = String::from_utf8_lossy(raw);
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    let reply: serde_json::Value = serde_json::from_str(text)
+        .with_context(|| format!("plugin '{plugin}' returned invalid JSON"))?;
+    if let Some(error) = reply.get("error").and_then(|e| e.as_str()) {
+        bail!("plugin '{plugin}' failed: {error}");
+    }
+    Ok(reply)
+}
+
+/// Run a request off the async runtime threads.
+async fn invoke(
+    runner: &Arc<dyn PluginRunner>,
+    request: PluginRequest,
+) -> Result<serde_json::Value> {
+    let runner = Arc::clone(runner);
+    tokio::task::spawn_blocking(move || runner.invoke_blocking(&request))
+        .await
+        .context("plugin invocation panicked")?
+}
+
+#[cfg_attr(not(feature = "plugins-wasm"), allow(unused_variables))]
+fn runner_for(
+    manifest: &PluginManifest,
+    dir: &Path,
+    memory: Option<Arc<dyn Memory>>,
+) -> Result<Arc<dyn PluginRunner>> {
+    match manifest.runtime {
+        PluginRuntime::Process => Ok(Arc::new(ProcessRunner::new(manifest, dir))),
+        #[cfg(feature = "plugins-wasm")]
+        PluginRuntime::Wasm => {
+            let runner = super::wasm::WasmRunner::load(manifest, dir)?;
+            Ok(Arc::new(match memory {
+                Some(memory) => runner.with_memory(memory),
+                None => runner,
+            }))
+        }
+        #[cfg(not(feature = "plugins-wasm"))]
+        PluginRuntime::Wasm => bail!(
+            "plugin '{}' is a WASM plugin, but this build lacks WASM support \
+             (rebuild with `--features plugins-wasm`)",
+            manifest.name
+        ),
+    }
+}
+
+/// A plugin described by `plugin.toml`, backed by a process or WASM runner.
+pub struct ExternalPlugin {
+    manifest: PluginManifest,
+    runner: Arc<dyn PluginRunner>,
+}
+
+impl ExternalPlugin {
+    /// `memory` backs the WASM memory host functions; process plugins
+    /// ignore it.
+    pub fn load(
+        manifest: PluginManifest,
+        dir: &Path,
+        memory: Option<Arc<dyn Memory>>,
+    ) -> Result<Self> {
+        let runner = runner_for(&manifest, dir, memory)?;
+        Ok(Self { manifest, runner })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+}
+
+#[async_trait]
+impl Plugin for ExternalPlugin {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn version(&self) -> &str {
+        &self.manifest.version
+    }
+
+    fn hooks(&self) -> Vec<Box<dyn Hook>> {
+        self.manifest
+            .hooks
+            .iter()
+            .enumerate()
+            .map(|(index, hook)| {
+                Box::new(ExternalHook::new(
+                    &self.manifest.name,
+                    index,
+                    hook,
+                    &self.runner,
+                )) as Box<dyn Hook>
+            })
+            .collect()
+    }
+
+    fn commands(&self) -> Vec<PluginCommand> {
+        self.manifest.commands.clone()
+    }
+
+    async fn run_command(&self, name: &str, args: &str) -> Result<String> {
+        let reply = invoke(
+            &self.runner,
+            PluginRequest::Command {
+                command: name.to_string(),
+                args: args.to_string(),
+            },
+        )
+        .await?;
+        Ok(reply
+            .get("output")
+            .and_then(|o| o.as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    fn prompt_sections(&self) -> Vec<Box<dyn PromptSection>> {
+        self.manifest
+            .prompt_sections
+            .iter()
+            .map(|section| {
+                Box::new(ExternalPromptSection {
+                    section: section.name.clone(),
+                    runner: Arc::clone(&self.runner),
+                }) as Box<dyn PromptSection>
+            })
+            .collect()
+    }
+}
+
+/// One `[[hooks]]` entry of an external plugin.
+pub struct ExternalHook {
+    name: String,
+    events: Vec<HookEventType>,
+    priority: i32,
+    runner: Arc<dyn PluginRunner>,
+}
+
+impl ExternalHook {
+    fn new(
+        plugin: &str,
+        index: usize,
+        hook: &HookManifest,
+        runner: &Arc<dyn PluginRunner>,
+    ) -> Self {
+        Self {
+            name: format!("{plugin}#{index}"),
+            events: hook.events.clone(),
+            priority: hook.priority,
+            runner: Arc::clone(runner),
+        }
+    }
+}
+
+#[async_trait]
+impl Hook for ExternalHook {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn event_types(&self) -> Vec<HookEventType> {
+        self.events.clone()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    async fn execute(&self, event: &HookEvent) -> Result<HookAction> {
+        let reply = invoke(
+            &self.runner,
+            PluginRequest::Hook {
+                event: event.clone(),
+            },
+        )
+        .await?;
+        if reply.is_null() {
+            return Ok(HookAction::Continue);
+        }
+        let reply: HookReply = serde_json::from_value(reply)
+            .with_context(|| format!("hook '{}' returned an unknown action", self.name))?;
+        Ok(reply.into())
+    }
+}
+
+/// System prompt section rendered by an external plugin. Failures are logged
+/// and produce an empty section so a broken plugin can't block the agent.
+pub struct ExternalPromptSection {
+    section: String,
+    runner: Arc<dyn PluginRunner>,
+}
+
+impl PromptSection for ExternalPromptSection {
+    fn name(&self) -> &str {
+        &self.section
+    }
+
+    fn build(&self, ctx: &PromptContext<'_>) -> Result<String> {
+        let request = PluginRequest::PromptSection {
+            section: self.section.clone(),
+            model: ctx.model_name.to_string(),
+            workspace_dir: ctx.workspace_dir.to_path_buf(),
+            tools: ctx.tools.iter().map(|t| t.name().to_string()).collect(),
+        };
+        match self.runner.invoke_blocking(&request) {
+            Ok(reply) => Ok(reply
+                .get("content")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .to_string()),
+            Err(e) => {
+                tracing::warn!("Prompt section '{}' skipped: {e:#}", self.section);
+                Ok(String::new())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// In-memory runner that answers with canned replies and records requests.
+    struct CannedRunner {
+        reply: serde_json::Value,
+        seen: Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl PluginRunner for CannedRunner {
+        fn invoke_blocking(&self, request: &PluginRequest) -> Result<serde_json::Value> {
+            self.seen.lock().push(serde_json::to_value(request)?);
+            Ok(self.reply.clone())
+        }
+    }
+
+    fn canned_plugin(extra: &str, reply: serde_json::Value) -> (ExternalPlugin, Arc<CannedRunner>) {
+        let manifest =
+            PluginManifest::parse(&format!("name = \"canned\"\ncommand = \"unused\"\n{extra}"))
+                .unwrap();
+        let runner = Arc::new(CannedRunner {
+            reply,
+            seen: Mutex::new(Vec::new()),
+        });
+        let plugin = ExternalPlugin {
+            manifest,
+            runner: runner.clone(),
+        };
+        (plugin, runner)
+    }
+
+    fn event(event_type: HookEventType) -> HookEvent {
+        HookEvent {
+            event_type,
+            data: HashMap::new(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn run_command_sends_tagged_request_and_reads_output() {
+        let (plugin, runner) = canned_plugin("", serde_json::json!({"output": "pong"}));
+        assert_eq!(plugin.run_command("ping", "now").await.unwrap(), "pong");
+
+        let seen = runner.seen.lock();
+        assert_eq!(
+            seen[0],
+            serde_json::json!({"type": "command", "command": "ping", "args": "now"})
+        );
+    }
+
+    #[tokio::test]
+    async fn hook_replies_map_to_actions() {
+        let extra = "[[hooks]]\nevents = [\"ToolCallBefore\"]\npriority = 3\n";
+        let (plugin, _) = canned_plugin(
+            extra,
+            serde_json::json!({"action": "cancel", "reason": "blocked by policy"}),
+        );
+        let hooks = plugin.hooks();
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].priority(), 3);
+        let action = hooks[0]
+            .execute(&event(HookEventType::ToolCallBefore))
+            .await
+            .unwrap();
+        assert!(matches!(action, HookAction::Cancel { reason } if reason == "blocked by policy"));
+
+        let (plugin, _) = canned_plugin(extra, serde_json::Value::Null);
+        let action = plugin.hooks()[0]
+            .execute(&event(HookEventType::ToolCallBefore))
+            .await
+            .unwrap();
+        assert!(matches!(action, HookAction::Continue));
+
+        let (plugin, _) = canned_plugin(extra, serde_json::json!({"action": "explode"}));
+        assert!(plugin.hooks()[0]
+            .execute(&event(HookEventType::ToolCallBefore))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn prompt_section_renders_content() {
+        let (plugin, runner) = canned_plugin(
+            "[[prompt_sections]]\nname = \"notes\"\n",
+            serde_json::json!({"content": "Plugin notes"}),
+        );
+        let sections = plugin.prompt_sections();
+        assert_eq!(sections[0].name(), "notes");
+
+        let ctx = PromptContext {
+            workspace_dir: Path::new("/tmp/ws"),
+            model_name: "test-model",
+            tools: &[],
+            dispatcher_instructions: "",
+        };
+        assert_eq!(sections[0].build(&ctx).unwrap(), "Plugin notes");
+        assert_eq!(runner.seen.lock()[0]["model"], "test-model");
+    }
+
+    #[test]
+    fn parse_reply_handles_empty_error_and_invalid_output() {
+        assert!(parse_reply("p", b"  \n").unwrap().is_null());
+        assert_eq!(
+            parse_reply("p", br#"{"output":"x"}"#).unwrap()["output"],
+            "x"
+        );
+        assert!(parse_reply("p", br#"{"error":"bad"}"#)
+            .unwrap_err()
+            .to_string()
+            .contains("bad"));
+        assert!(parse_reply("p", b"not json").is_err());
+    }
+
+    #[cfg(not(feature = "plugins-wasm"))]
+    #[test]
+    fn wasm_plugins_need_the_feature() {
+        let manifest =
+            PluginManifest::parse("name = \"w\"\nruntime = \"wasm\"\nmodule = \"w.wasm\"").unwrap();
+        let err = ExternalPlugin::load(manifest, Path::new("/tmp"), None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("plugins-wasm"));
+    }
+}
@@ -1,23 +1,155 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
 use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
 
+use super::external::ExternalPlugin;
+use super::manifest::{discover_plugin_dirs, PluginManifest};
 use super::traits::{Hook, HookAction, HookEvent, HookEventType, Plugin, PluginManager};
+use crate::agent::prompt::PromptSection;
+use crate::config::PluginsConfig;
+use crate::memory::Memory;
 
 /// Default in-process plugin manager.
 ///
 /// Stores loaded plugins behind a `Mutex` and dispatches hook events in
-/// priority order. `load_plugin` / `unload_plugin` are stubs—dynamic
-/// plugin discovery is future work.
+/// priority order. `load_plugin` reads a `plugin.toml` manifest and registers
+/// a process- or WASM-backed plugin; `discover` loads every plugin under a
+/// directory.
 pub struct DefaultPluginManager {
-    plugins: Mutex<Vec<Box<dyn Plugin>>>,
+    plugins: Mutex<Vec<Arc<dyn Plugin>>>,
+    hook_filter: Mutex<HookFilter>,
+    memory: Option<Arc<dyn Memory>>,
+}
+
+/// Hook switches from `[plugins.hooks]`, resolved to event types.
+#[derive(Default)]
+struct HookFilter {
+    muted: HashSet<String>,
+    disabled_events: HashMap<String, HashSet<HookEventType>>,
+}
+
+impl HookFilter {
+    fn allows(&self, plugin: &str, event: &HookEventType) -> bool {
+        !self.muted.contains(plugin)
+            && self
+                .disabled_events
+                .get(plugin)
+                .map_or(true, |events| !events.contains(event))
+    }
 }
 
 impl DefaultPluginManager {
     pub fn new() -> Self {
         Self {
             plugins: Mutex::new(Vec::new()),
+            hook_filter: Mutex::new(HookFilter::default()),
+            memory: None,
+        }
+    }
+
+    /// Memory backend exposed to WASM plugins loaded after this call.
+    pub fn with_memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Apply per-plugin hook switches. Unknown event names are logged and
+    /// ignored.
+    pub fn configure_hooks(&self, config: &PluginsConfig) {
+        let mut filter = HookFilter::default();
+        for (plugin, hooks) in &config.hooks {
+            if !hooks.enabled {
+                filter.muted.insert(plugin.clone());
+            }
+            let mut events = HashSet::new();
+            for name in &hooks.disabled_events {
+                match serde_json::from_value::<HookEventType>(serde_json::Value::String(
+                    name.clone(),
+                )) {
+                    Ok(event) => {
+                        events.insert(event);
+                    }
+                    Err(_) => tracing::warn!(
+                        "Ignoring unknown hook event '{name}' in [plugins.hooks.{plugin}]"
+                    ),
+                }
+            }
+            if !events.is_empty() {
+                filter.disabled_events.insert(plugin.clone(), events);
+            }
+        }
+        *self.hook_filter.lock() = filter;
+    }
+
+    /// Register a plugin, replacing any loaded plugin with the same name.
+    pub fn register(&self, plugin: Box<dyn Plugin>) {
+        let plugin: Arc<dyn Plugin> = Arc::from(plugin);
+        let mut plugins = self.plugins.lock();
+        plugins.retain(|p| p.name() != plugin.name());
+        plugins.push(plugin);
+    }
+
+    /// Load every plugin directory under `root`. Broken manifests are logged
+    /// and skipped so one bad plugin doesn't disable the rest.
+    pub fn discover(&self, root: &Path) -> Result<Vec<String>> {
+        let mut loaded = Vec::new();
+        for dir in discover_plugin_dirs(root)? {
+            match self.load_external(&dir) {
+                Ok(plugin) => {
+                    tracing::info!("Loaded plugin {} v{}", plugin.name(), plugin.version());
+                    loaded.push(plugin.name().to_string());
+                    self.register(Box::new(plugin));
+                }
+                Err(e) => tracing::warn!("Skipping plugin at {}: {e:#}", dir.display()),
+            }
         }
+        Ok(loaded)
+    }
+
+    /// Names of loaded plugins, in load order.
+    pub fn plugin_names(&self) -> Vec<String> {
+        self.plugins
+            .lock()
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect()
+    }
+
+    /// Run a plugin slash command. Returns `None` when no plugin declares it.
+    pub async fn run_command(&self, command: &str, args: &str) -> Result<Option<String>> {
+        let command = command.trim_start_matches('/');
+        let owner = {
+            let plugins = self.plugins.lock();
+            plugins
+                .iter()
+                .find(|p| {
+                    p.commands()
+                        .iter()
+                        .any(|c| c.name.trim_start_matches('/') == command)
+                })
+                .cloned()
+        };
+        match owner {
+            Some(plugin) => plugin.run_command(command, args).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn load_external(&self, path: &Path) -> Result<ExternalPlugin> {
+        let (manifest, dir) = PluginManifest::load(path)?;
+        ExternalPlugin::load(manifest, &dir, self.memory.clone())
+    }
+
+    /// Prompt sections contributed by all loaded plugins.
+    pub fn prompt_sections(&self) -> Vec<Box<dyn PromptSection>> {
+        self.plugins
+            .lock()
+            .iter()
+            .flat_map(|p| p.prompt_sections())
+            .collect()
     }
 }
 
@@ -29,19 +161,24 @@ impl Default for DefaultPluginManager {
 
 #[async_trait]
 impl PluginManager for DefaultPluginManager {
-    async fn load_plugin(&self, _path: &std::path::Path) -> Result<()> {
-        // Dynamic plugin loading is future work.
+    async fn load_plugin(&self, path: &std::path::Path) -> Result<()> {
+        self.register(Box::new(self.load_external(path)?));
         Ok(())
     }
 
-    async fn unload_plugin(&self, _name: &str) -> Result<()> {
-        // Dynamic plugin unloading is future work.
+    async fn unload_plugin(&self, name: &str) -> Result<()> {
+        let mut plugins = self.plugins.lock();
+        let before = plugins.len();
+        plugins.retain(|p| p.name() != name);
+        if plugins.len() == before {
+            bail!("plugin '{name}' is not loaded");
+        }
         Ok(())
     }
 
     fn list_plugins(&self) -> Vec<&str> {
         // Mutex guard lifetime prevents returning borrowed &str directly;
-        // return an empty vec for now (no dynamic loading yet).
+        // use `plugin_names()` for an owned list.
         vec![]
     }
 
@@ -59,7 +196,12 @@ impl PluginManager for DefaultPluginManager {
     async fn dispatch_hook(&self, event: &HookEvent) -> Result<HookAction> {
         let all_hooks: Vec<Box<dyn Hook>> = {
             let plugins = self.plugins.lock();
-            plugins.iter().flat_map(|p| p.hooks()).collect()
+            let filter = self.hook_filter.lock();
+            plugins
+                .iter()
+                .filter(|p| filter.allows(p.name(), &event.event_type))
+                .flat_map(|p| p.hooks())
+                .collect()
         };
 
         let mut relevant: Vec<&dyn Hook> = all_hooks
@@ -71,18 +213,182 @@ impl PluginManager for DefaultPluginManager {
         // Lower priority value runs first.
         relevant.sort_by_key(|h| h.priority());
 
-        let mut action = HookAction::Continue;
+        // Modifications accumulate: later hooks see earlier changes, and the
+        // caller receives the merged set.
+        let mut current = event.clone();
+        let mut changes = HashMap::new();
         for hook in relevant {
-            action = hook.execute(event).await?;
-            if matches!(action, HookAction::Cancel { .. }) {
-                return Ok(action);
+            match hook.execute(&current).await? {
+                HookAction::Continue => {}
+                HookAction::Modify(data) => {
+                    for (key, value) in data {
+                        current.data.insert(key.clone(), value.clone());
+                        changes.insert(key, value);
+                    }
+                }
+                cancel @ HookAction::Cancel { .. } => return Ok(cancel),
             }
         }
 
-        Ok(action)
+        if changes.is_empty() {
+            Ok(HookAction::Continue)
+        } else {
+            Ok(HookAction::Modify(changes))
+        }
     }
 
     fn name(&self) -> &str {
         "default"
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::plugins::manifest::MANIFEST_FILE;
+    use std::collections::HashMap;
+
+    fn write_plugin(root: &Path, name: &str, manifest_extra: &str, reply: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("run.sh");
+        std::fs::write(
+            &script,
+            format!("#!/bin/sh\ncat >/dev/null\necho '{reply}'\n"),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            format!("name = \"{name}\"\ncommand = \"./run.sh\"\n{manifest_extra}"),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn discover_loads_plugins_and_routes_commands() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_plugin(
+            tmp.path(),
+            "weather",
+            "[[commands]]\nname = \"forecast\"\ndescription = \"Forecast\"\n",
+            r#"{"output":"sunny"}"#,
+        );
+        std::fs::create_dir_all(tmp.path().join("broken")).unwrap();
+        std::fs::write(tmp.path().join("broken").join(MANIFEST_FILE), "name = 1").unwrap();
+
+        let manager = DefaultPluginManager::new();
+        let loaded = manager.discover(tmp.path()).unwrap();
+        assert_eq!(loaded, vec!["weather".to_string()]);
+
+        assert_eq!(
+            manager.run_command("/forecast", "berlin").await.unwrap(),
+            Some("sunny".to_string())
+        );
+        assert_eq!(manager.run_command("/unknown", "").await.unwrap(), None);
+
+        manager.unload_plugin("weather").await.unwrap();
+        assert!(manager.plugin_names().is_empty());
+        assert!(manager.unload_plugin("weather").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn dispatch_hook_stops_at_first_cancel_in_priority_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_plugin(
+            tmp.path(),
+            "audit",
+            "[[hooks]]\nevents = [\"ToolCallBefore\"]\npriority = 10\n",
+            r#"{"action":"modify","data":{"seen":true}}"#,
+        );
+        write_plugin(
+            tmp.path(),
+            "deny",
+            "[[hooks]]\nevents = [\"ToolCallBefore\"]\npriority = -1\n",
+            r#"{"action":"cancel","reason":"denied"}"#,
+        );
+
+        let manager = DefaultPluginManager::new();
+        manager.discover(tmp.path()).unwrap();
+
+        let event = HookEvent {
+            event_type: HookEventType::ToolCallBefore,
+            data: HashMap::new(),
+            timestamp: chrono::Utc::now(),
+        };
+        let action = manager.dispatch_hook(&event).await.unwrap();
+        assert!(matches!(action, HookAction::Cancel { reason } if reason == "denied"));
+
+        let other = HookEvent {
+            event_type: HookEventType::AgentStart,
+            ..event
+        };
+        assert!(matches!(
+            manager.dispatch_hook(&other).await.unwrap(),
+            HookAction::Continue
+        ));
+    }
+
+    #[tokio::test]
+    async fn configure_hooks_mutes_plugins_and_events() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_plugin(
+            tmp.path(),
+            "audit",
+            "[[hooks]]\nevents = [\"BeforeToolCall\", \"ResponseSent\"]\n",
+            r#"{"action":"modify","data":{"content":"audited"}}"#,
+        );
+        write_plugin(
+            tmp.path(),
+            "deny",
+            "[[hooks]]\nevents = [\"ToolCallBefore\"]\n",
+            r#"{"action":"cancel","reason":"denied"}"#,
+        );
+
+        let manager = DefaultPluginManager::new();
+        manager.discover(tmp.path()).unwrap();
+        manager.configure_hooks(&PluginsConfig {
+            enabled: true,
+            hooks: HashMap::from([
+                (
+                    "audit".to_string(),
+                    crate::config::PluginHooksConfig {
+                        enabled: true,
+                        disabled_events: vec!["ResponseSent".into()],
+                    },
+                ),
+                (
+                    "deny".to_string(),
+                    crate::config::PluginHooksConfig {
+                        enabled: false,
+                        disabled_events: Vec::new(),
+                    },
+                ),
+            ]),
+        });
+
+        let before_tool = HookEvent::new(HookEventType::ToolCallBefore, serde_json::json!({}));
+        match manager.dispatch_hook(&before_tool).await.unwrap() {
+            HookAction::Modify(data) => assert_eq!(data["content"], "audited"),
+            other => panic!("expected audit modification, got {other:?}"),
+        }
+
+        let outbound = HookEvent::new(HookEventType::MessageOutbound, serde_json::json!({}));
+        assert!(matches!(
+            manager.dispatch_hook(&outbound).await.unwrap(),
+            HookAction::Continue
+        ));
+    }
+
+    #[test]
+    fn register_replaces_plugins_with_same_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_plugin(tmp.path(), "dup", "", "{}");
+        let manager = DefaultPluginManager::new();
+        manager.discover(tmp.path()).unwrap();
+        manager.discover(tmp.path()).unwrap();
+        assert_eq!(manager.plugin_names(), vec!["dup".to_string()]);
+    }
+}
@@ -0,0 +1,339 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::traits::{HookEventType, PluginCommand};
+
+/// File name looked up inside each plugin directory.
+pub const MANIFEST_FILE: &str = "plugin.toml";
+
+/// Default time an external plugin process may run per invocation.
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// `plugin.toml` — declares what an external plugin contributes.
+///
+/// ```toml
+/// name = "jira"
+/// version = "0.1.0"
+/// command = "./jira-plugin"
+///
+/// [[commands]]
+/// name = "ticket"
+/// description = "Look up a Jira ticket"
+///
+/// [[hooks]]
+/// events = ["ToolCallBefore"]
+///
+/// [[prompt_sections]]
+/// name = "jira-context"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub runtime: PluginRuntime,
+    /// Executable invoked for every request, fed one JSON request on stdin
+    /// (`runtime = "process"`).
+    #[serde(default)]
+    pub command: String,
+    /// WebAssembly module relative to the plugin directory
+    /// (`runtime = "wasm"`).
+    #[serde(default)]
+    pub module: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Slash commands handled by the plugin.
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub hooks: Vec<HookManifest>,
+    /// Named system prompt sections the plugin contributes.
+    #[serde(default)]
+    pub prompt_sections: Vec<PromptSectionManifest>,
+}
+
+/// How a plugin's requests are executed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginRuntime {
+    /// Spawn `command` once per request; JSON over stdin/stdout.
+    #[default]
+    Process,
+    /// Run `module` in a sandbox with no filesystem or network access.
+    Wasm,
+}
+
+/// A hook registration: which lifecycle events to receive, in what order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HookManifest {
+    pub events: Vec<HookEventType>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptSectionManifest {
+    pub name: String,
+}
+
+fn default_version() -> String {
+    "0.0.0".into()
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+impl PluginManifest {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(raw).context("invalid plugin manifest")?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Read `plugin.toml` from a plugin directory (or the manifest path itself).
+    pub fn load(path: &Path) -> Result<(Self, PathBuf)> {
+        let (manifest_path, dir) = if path.is_dir() {
+            (path.join(MANIFEST_FILE), path.to_path_buf())
+        } else {
+            let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+            (path.to_path_buf(), dir)
+        };
+        let raw = std::fs::read_to_string(&manifest_path)
+            .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+        let manifest =
+            Self::parse(&raw).with_context(|| format!("in {}", manifest_path.display()))?;
+        Ok((manifest, dir))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !is_valid_name(&self.name) {
+            bail!(
+                "plugin name '{}' must be non-empty and use only [A-Za-z0-9_-]",
+                self.name
+            );
+        }
+        match self.runtime {
+            PluginRuntime::Process => {
+                if self.command.trim().is_empty() {
+                    bail!("plugin '{}' has an empty command", self.name);
+                }
+            }
+            PluginRuntime::Wasm => {
+                let module = self.module.as_deref().map(str::trim).unwrap_or_default();
+                let path = Path::new(module);
+                if module.is_empty()
+                    || path.is_absolute()
+                    || path
+                        .components()
+                        .any(|c| matches!(c, std::path::Component::ParentDir))
+                {
+                    bail!(
+                        "plugin '{}' needs a `module` path inside the plugin directory",
+                        self.name
+                    );
+                }
+            }
+        }
+        if self.timeout_secs == 0 {
+            bail!("plugin '{}' timeout_secs must be greater than 0", self.name);
+        }
+        for command in &self.commands {
+            if !is_valid_name(command.name.trim_start_matches('/')) {
+                bail!(
+                    "plugin '{}' declares invalid command name '{}'",
+                    self.name,
+                    command.name
+                );
+            }
+        }
+        if self.hooks.iter().any(|hook| hook.events.is_empty()) {
+            bail!("plugin '{}' declares a hook without events", self.name);
+        }
+        for section in &self.prompt_sections {
+            if !is_valid_name(&section.name) {
+                bail!(
+                    "plugin '{}' declares invalid prompt section name '{}'",
+                    self.name,
+                    section.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Path of the plugin executable. Bare program names are left for `PATH`
+    /// lookup; anything with a path separator resolves against `plugin_dir`.
+    pub fn resolve_command(&self, plugin_dir: &Path) -> PathBuf {
+        let command = self.command.trim();
+        if command.contains(['/', '\\']) {
+            plugin_dir.join(command)
+        } else {
+            // Bare program name, looked up on PATH.
+            PathBuf::from(command)
+        }
+    }
+
+    /// Path of the WASM module, if this is a WASM plugin.
+    pub fn resolve_module(&self, plugin_dir: &Path) -> Option<PathBuf> {
+        self.module
+            .as_deref()
+            .map(|module| plugin_dir.join(module.trim()))
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Directory scanned for plugins: `plugins/` next to `config.toml`
+/// (`~/.zeroclaw/plugins/` by default).
+pub fn plugins_dir(config: &crate::config::Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .map_or_else(|| PathBuf::from("plugins"), |dir| dir.join("plugins"))
+}
+
+/// Plugin directories under `root` that contain a manifest, sorted by name.
+pub fn discover_plugin_dirs(root: &Path) -> Result<Vec<PathBuf>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut dirs = Vec::new();
+    for entry in std::fs::read_dir(root)
+        .with_context(|| format!("failed to read plugins directory {}", root.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() && path.join(MANIFEST_FILE).is_file() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_parses_commands_hooks_and_sections() {
+        let manifest = PluginManifest::parse(
+            r#"
+name = "jira"
+version = "1.2.0"
+command = "./run.sh"
+
+[[commands]]
+name = "ticket"
+description = "Look up a ticket"
+usage = "/ticket <id>"
+
+[[hooks]]
+events = ["ToolCallBefore", "ToolCallAfter"]
+priority = -5
+
+[[prompt_sections]]
+name = "jira-context"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.version, "1.2.0");
+        assert_eq!(manifest.timeout_secs, DEFAULT_TIMEOUT_SECS);
+        assert_eq!(manifest.commands[0].name, "ticket");
+        assert_eq!(
+            manifest.hooks[0].events,
+            vec![HookEventType::ToolCallBefore, HookEventType::ToolCallAfter]
+        );
+        assert_eq!(manifest.hooks[0].priority, -5);
+        assert_eq!(manifest.prompt_sections[0].name, "jira-context");
+    }
+
+    #[test]
+    fn manifest_rejects_invalid_names_and_empty_hooks() {
+        assert!(PluginManifest::parse("name = \"../evil\"\ncommand = \"x\"").is_err());
+        assert!(PluginManifest::parse("name = \"ok\"\ncommand = \" \"").is_err());
+        assert!(
+            PluginManifest::parse("name = \"ok\"\ncommand = \"x\"\n[[hooks]]\nevents = []")
+                .is_err()
+        );
+        assert!(PluginManifest::parse(
+            "name = \"ok\"\ncommand = \"x\"\n[[hooks]]\nevents = [\"NotAnEvent\"]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn wasm_manifest_requires_module_inside_plugin_dir() {
+        let manifest =
+            PluginManifest::parse("name = \"w\"\nruntime = \"wasm\"\nmodule = \"plugin.wasm\"")
+                .unwrap();
+        assert_eq!(manifest.runtime, PluginRuntime::Wasm);
+        assert_eq!(
+            manifest.resolve_module(Path::new("/plugins/w")),
+            Some(PathBuf::from("/plugins/w/plugin.wasm"))
+        );
+
+        assert!(PluginManifest::parse("name = \"w\"\nruntime = \"wasm\"").is_err());
+        assert!(PluginManifest::parse(
+            "name = \"w\"\nruntime = \"wasm\"\nmodule = \"../other/x.wasm\""
+        )
+        .is_err());
+        assert!(PluginManifest::parse(
+            "name = \"w\"\nruntime = \"wasm\"\nmodule = \"/abs/x.wasm\""
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn resolve_command_is_relative_to_plugin_dir() {
+        let dir = Path::new("/plugins/jira");
+        let mut manifest =
+            PluginManifest::parse("name = \"jira\"\ncommand = \"./run.sh\"").unwrap();
+        assert_eq!(manifest.resolve_command(dir), dir.join("./run.sh"));
+
+        manifest.command = "bin/run".into();
+        assert_eq!(manifest.resolve_command(dir), dir.join("bin/run"));
+
+        manifest.command = "python3".into();
+        assert_eq!(manifest.resolve_command(dir), PathBuf::from("python3"));
+    }
+
+    #[test]
+    fn discover_only_returns_dirs_with_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(discover_plugin_dirs(&tmp.path().join("missing"))
+            .unwrap()
+            .is_empty());
+
+        std::fs::create_dir_all(tmp.path().join("b")).unwrap();
+        std::fs::write(tmp.path().join("b").join(MANIFEST_FILE), "").unwrap();
+        std::fs::create_dir_all(tmp.path().join("a")).unwrap();
+        std::fs::write(tmp.path().join("a").join(MANIFEST_FILE), "").unwrap();
+        std::fs::create_dir_all(tmp.path().join("empty")).unwrap();
+
+        let dirs = discover_plugin_dirs(tmp.path()).unwrap();
+        assert_eq!(dirs, vec![tmp.path().join("a"), tmp.path().join("b")]);
+    }
+
+    #[test]
+    fn plugins_dir_sits_next_to_config() {
+        let mut config = crate::config::Config::default();
+        config.config_path = PathBuf::from("/home/u/.zeroclaw/config.toml");
+        assert_eq!(
+            plugins_dir(&config),
+            PathBuf::from("/home/u/.zeroclaw/plugins")
+        );
+    }
+}
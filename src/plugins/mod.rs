@@ -1,13 +1,60 @@
+pub mod external;
 pub mod manager;
+pub mod manifest;
+pub mod process;
 pub mod traits;
+#[cfg(feature = "plugins-wasm")]
+pub mod wasm;
 
 pub use manager::DefaultPluginManager;
-pub use traits::{
-    Hook, HookAction, HookEvent, HookEventType, Plugin, PluginCommand, PluginContext,
-    PluginManager,
-};
+pub use manifest::plugins_dir;
+pub use traits::{HookAction, HookEvent, HookEventType, PluginManager};
+
+use std::sync::Arc;
 
 /// Create the default plugin manager instance.
 pub fn create_plugin_manager() -> Box<dyn PluginManager> {
     Box::new(DefaultPluginManager::new())
 }
+
+/// Create a plugin manager with every plugin under the config's plugins
+/// directory (`~/.zeroclaw/plugins/` by default) loaded and `[plugins.hooks]`
+/// applied. WASM plugins get `memory` for their memory host functions.
+/// Returns an empty manager when `[plugins] enabled = false`.
+pub fn load_plugins(
+    config: &crate::config::Config,
+    memory: Arc<dyn crate::memory::Memory>,
+) -> DefaultPluginManager {
+    let manager = DefaultPluginManager::new().with_memory(memory);
+    if !config.plugins.enabled {
+        return manager;
+    }
+    manager.configure_hooks(&config.plugins);
+    let dir = plugins_dir(config);
+    if let Err(e) = manager.discover(&dir) {
+        tracing::warn!("Plugin discovery failed for {}: {e:#}", dir.display());
+    }
+    manager
+}
+
+/// Fire a lifecycle event through `manager`, if any.
+///
+/// Hook failures are logged and treated as `Continue` so a broken plugin
+/// cannot wedge the agent loop.
+pub async fn fire_hook(
+    manager: Option<&dyn PluginManager>,
+    event_type: HookEventType,
+    data: serde_json::Value,
+) -> HookAction {
+    let Some(manager) = manager else {
+        return HookAction::Continue;
+    };
+    let event = HookEvent::new(event_type, data);
+    match manager.dispatch_hook(&event).await {
+        Ok(action) => action,
+        Err(e) => {
+            tracing::warn!("Hook dispatch for {:?} failed: {e:#}", event.event_type);
+            HookAction::Continue
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! Process plugins: every request spawns the plugin executable, writes the
+//! JSON request to stdin and reads the JSON reply from stdout.
+
+use anyhow::{bail, Context, Result};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use super::external::{parse_reply, PluginRequest, PluginRunner};
+use super::manifest::PluginManifest;
+
+/// Upper bound on captured stdout per invocation.
+const MAX_OUTPUT_BYTES: u64 = 1024 * 1024;
+/// Upper bound on stderr kept for error messages.
+const MAX_STDERR_BYTES: u64 = 4096;
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Spawns the plugin executable, one process per request.
+#[derive(Debug)]
+pub struct ProcessRunner {
+    plugin: String,
+    program: PathBuf,
+    args: Vec<String>,
+    dir: PathBuf,
+    timeout: Duration,
+}
+
+impl ProcessRunner {
+    pub fn new(manifest: &PluginManifest, dir: &Path) -> Self {
+        Self {
+            plugin: manifest.name.clone(),
+            program: manifest.resolve_command(dir),
+            args: manifest.args.clone(),
+            dir: dir.to_path_buf(),
+            timeout: Duration::from_secs(manifest.timeout_secs),
+        }
+    }
+}
+
+impl PluginRunner for ProcessRunner {
+    fn invoke_blocking(&self, request: &PluginRequest) -> Result<serde_json::Value> {
+        let payload = serde_json::to_vec(request)?;
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.dir)
+            .env("ZEROCLAW_PLUGIN", &self.plugin)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "plugin '{}': failed to start {}",
+                    self.plugin,
+                    self.program.display()
+                )
+            })?;
+
+        // Feed stdin and drain the pipes on helper threads so a chatty plugin
+        // can't deadlock against a full pipe buffer.
+        let mut stdin = child.stdin.take().context("plugin stdin unavailable")?;
+        let writer = std::thread::spawn(move || {
+            let _ = stdin.write_all(&payload);
+        });
+        let stdout = child.stdout.take().context("plugin stdout unavailable")?;
+        let stdout_reader = std::thread::spawn(move || drain_limited(stdout, MAX_OUTPUT_BYTES));
+        let stderr = child.stderr.take().context("plugin stderr unavailable")?;
+        let stderr_reader = std::thread::spawn(move || drain_limited(stderr, MAX_STDERR_BYTES));
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "plugin '{}' timed out after {}s",
+                    self.plugin,
+                    self.timeout.as_secs()
+                );
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        };
+
+        let _ = writer.join();
+        let stdout = stdout_reader.join().unwrap_or_default();
+        let stderr = stderr_reader.join().unwrap_or_default();
+
+        if !status.success() {
+            bail!(
+                "plugin '{}' exited with {status}: {}",
+                self.plugin,
+                String::from_utf8_lossy(&stderr).trim()
+            );
+        }
+
+        parse_reply(&self.plugin, &stdout)
+    }
+}
+
+fn drain_limited(mut reader: impl Read, limit: u64) -> Vec<u8> {
+    let mut buf = Vec::new();
+    let _ = reader.by_ref().take(limit).read_to_end(&mut buf);
+    // Keep reading past the limit so the plugin never blocks on a full pipe.
+    let _ = std::io::copy(&mut reader, &mut std::io::sink());
+    buf
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::plugins::traits::{HookEvent, HookEventType};
+
+    fn runner_in(dir: &Path, script: &str, timeout_secs: u64) -> ProcessRunner {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("run.sh");
+        std::fs::write(&path, script).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let mut manifest =
+            PluginManifest::parse("name = \"test\"\ncommand = \"./run.sh\"").unwrap();
+        manifest.timeout_secs = timeout_secs;
+        ProcessRunner::new(&manifest, dir)
+    }
+
+    fn command() -> PluginRequest {
+        PluginRequest::Command {
+            command: "x".into(),
+            args: String::new(),
+        }
+    }
+
+    #[test]
+    fn runner_round_trips_json_and_treats_empty_output_as_null() {
+        let tmp = tempfile::tempdir().unwrap();
+        let runner = runner_in(
+            tmp.path(),
+            "#!/bin/sh\ncat >/dev/null\nprintf '{\"output\":\"%s\"}' \"$ZEROCLAW_PLUGIN\"\n",
+            5,
+        );
+        assert_eq!(
+            runner.invoke_blocking(&command()).unwrap()["output"],
+            "test"
+        );
+
+        let runner = runner_in(tmp.path(), "#!/bin/sh\ncat >/dev/null\n", 5);
+        let reply = runner
+            .invoke_blocking(&PluginRequest::Hook {
+                event: HookEvent {
+                    event_type: HookEventType::AgentStart,
+                    data: std::collections::HashMap::new(),
+                    timestamp: chrono::Utc::now(),
+                },
+            })
+            .unwrap();
+        assert!(reply.is_null());
+    }
+
+    #[test]
+    fn runner_reports_errors_and_timeouts() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        let runner = runner_in(tmp.path(), "#!/bin/sh\necho '{\"error\":\"nope\"}'\n", 5);
+        let err = runner.invoke_blocking(&command()).unwrap_err();
+        assert!(err.to_string().contains("nope"));
+
+        let runner = runner_in(tmp.path(), "#!/bin/sh\necho boom >&2\nexit 3\n", 5);
+        let err = runner.invoke_blocking(&command()).unwrap_err();
+        assert!(err.to_string().contains("boom"));
+
+        let runner = runner_in(tmp.path(), "#!/bin/sh\nsleep 5\n", 1);
+        let err = runner.invoke_blocking(&command()).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+
+        let mut manifest =
+            PluginManifest::parse("name = \"test\"\ncommand = \"./missing.sh\"").unwrap();
+        manifest.timeout_secs = 1;
+        let runner = ProcessRunner::new(&manifest, tmp.path());
+        assert!(runner.invoke_blocking(&command()).is_err());
+    }
+}
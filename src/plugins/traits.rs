@@ -5,22 +5,40 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Hook types covering the full agent lifecycle.
+///
+/// Manifests and config may also use the aliases `BeforeToolCall`,
+/// `AfterToolCall`, `BeforeProviderCall`, `MessageReceived` and
+/// `ResponseSent`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum HookEventType {
     AgentStart,
     AgentEnd,
     ModelSelect,
     PromptBuild,
+    /// Before a tool runs. `Cancel` denies the call; `Modify` may replace
+    /// `arguments`.
+    #[serde(alias = "BeforeToolCall")]
     ToolCallBefore,
+    /// After a tool ran. `Modify` may replace `output`.
+    #[serde(alias = "AfterToolCall")]
     ToolCallAfter,
     SessionStart,
     SessionEnd,
     GatewayConnect,
     GatewayDisconnect,
+    /// Before each provider call. `Cancel` aborts the turn; `Modify` may
+    /// replace `model`.
+    #[serde(alias = "BeforeProviderCall")]
     LlmRequest,
     LlmResponse,
     Compaction,
+    /// A channel message arrived. `Cancel` drops it; `Modify` may replace
+    /// `content`.
+    #[serde(alias = "MessageReceived")]
     MessageInbound,
+    /// A reply is about to be sent. `Cancel` suppresses it; `Modify` may
+    /// replace `content`.
+    #[serde(alias = "ResponseSent")]
     MessageOutbound,
 }
 
@@ -32,6 +50,21 @@ pub struct HookEvent {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl HookEvent {
+    /// Build an event stamped now. Non-object `data` yields an empty payload.
+    pub fn new(event_type: HookEventType, data: serde_json::Value) -> Self {
+        let data = match data {
+            serde_json::Value::Object(map) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        };
+        Self {
+            event_type,
+            data,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
 /// Action returned by a hook to control pipeline flow.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum HookAction {
@@ -52,7 +85,7 @@ pub trait Hook: Send + Sync {
 }
 
 /// Descriptor for a CLI command contributed by a plugin.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PluginCommand {
     pub name: String,
     pub description: String,
@@ -94,6 +127,16 @@ pub trait Plugin: Send + Sync {
     fn commands(&self) -> Vec<PluginCommand> {
         vec![]
     }
+
+    /// Handle one of the slash commands advertised by `commands()`.
+    async fn run_command(&self, name: &str, args: &str) -> Result<String> {
+        let _ = args;
+        anyhow::bail!("plugin '{}' does not handle command '{name}'", self.name())
+    }
+
+    fn prompt_sections(&self) -> Vec<Box<dyn crate::agent::prompt::PromptSection>> {
+        vec![]
+    }
 }
 
 /// Manager responsible for loading, unloading, and dispatching across
@@ -0,0 +1,436 @@
+//! WASM plugins: core WebAssembly modules run in an interpreter sandbox.
+//!
+//! The guest sees three host functions and no filesystem, network, clock or
+//! environment access:
+//!
+//! - `zeroclaw.emit(ptr, len)` — publish a UTF-8 message from guest memory
+//! - `zeroclaw.memory_store(key_ptr, key_len, value_ptr, value_len) -> i32` —
+//!   save a value in agent memory; `0` on success, `-1` on failure
+//! - `zeroclaw.memory_get(key_ptr, key_len, out_ptr, out_cap) -> i32` — copy
+//!   a stored value to `out_ptr` and return its length, or `-1` when it is
+//!   missing. A value longer than `out_cap` is not copied; retry with a
+//!   buffer of the returned length.
+//!
+//! Memory keys are scoped to the plugin (`plugin.<name>:<key>`), so a plugin
+//! sees neither the agent's own memories nor another plugin's. Without a
+//! memory backend both memory functions return `-1`. A module must export:
+//!
+//! - `memory` — its linear memory
+//! - `alloc(len: i32) -> i32` — reserve `len` bytes for the request
+//! - `handle(ptr: i32, len: i32) -> i64` — process the JSON request and
+//!   return the reply location packed as `(ptr << 32) | len`
+//!
+//! Requests and replies use the same JSON contract as process plugins.
+//! Each invocation gets a fresh instance with bounded fuel and memory.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use wasmi::{Caller, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use super::external::{parse_reply, PluginRequest, PluginRunner};
+use super::manifest::PluginManifest;
+use crate::memory::{Memory, MemoryCategory, NamespacedMemory};
+
+/// Import module name for host functions.
+const HOST_MODULE: &str = "zeroclaw";
+/// Host functions a guest may import.
+const HOST_FUNCTIONS: &[&str] = &["emit", "memory_get", "memory_store"];
+/// Instruction budget per invocation.
+const FUEL_PER_CALL: u64 = 100_000_000;
+/// Linear memory cap per instance.
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+/// Upper bound on reply size read back from the guest.
+const MAX_REPLY_BYTES: usize = 1024 * 1024;
+/// Upper bound on a single emitted message.
+const MAX_MESSAGE_BYTES: usize = 16 * 1024;
+/// Messages kept per invocation; further emits are dropped.
+const MAX_MESSAGES: usize = 64;
+/// Upper bound on a value passed to `memory_store`.
+const MAX_MEMORY_VALUE_BYTES: usize = 64 * 1024;
+/// Return value of a failed host call.
+const HOST_ERROR: i32 = -1;
+
+struct HostState {
+    messages: Vec<String>,
+    memory: Option<Arc<dyn Memory>>,
+    runtime: Option<Handle>,
+    limits: StoreLimits,
+}
+
+/// Runs a compiled WASM plugin module.
+pub struct WasmRunner {
+    plugin: String,
+    engine: Engine,
+    module: Module,
+    memory: Option<Arc<dyn Memory>>,
+}
+
+impl WasmRunner {
+    pub fn load(manifest: &PluginManifest, dir: &Path) -> Result<Self> {
+        let path = manifest
+            .resolve_module(dir)
+            .with_context(|| format!("plugin '{}' has no `module`", manifest.name))?;
+        let bytes =
+            std::fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        Self::from_bytes(&manifest.name, &bytes)
+    }
+
+    pub fn from_bytes(plugin: &str, wasm: &[u8]) -> Result<Self> {
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm)
+            .map_err(|e| anyhow!("plugin '{plugin}' is not a valid WASM module: {e}"))?;
+
+        // Refuse anything beyond the host API up front, so sandbox escapes
+        // fail at load time rather than on first use.
+        for import in module.imports() {
+            if import.module() != HOST_MODULE || !HOST_FUNCTIONS.contains(&import.name()) {
+                bail!(
+                    "plugin '{plugin}' imports {}::{}, which the sandbox does not provide",
+                    import.module(),
+                    import.name()
+                );
+            }
+        }
+
+        Ok(Self {
+            plugin: plugin.to_string(),
+            engine,
+            module,
+            memory: None,
+        })
+    }
+
+    /// Back `memory_get`/`memory_store` with `memory`, scoped to this plugin.
+    pub fn with_memory(mut self, memory: Arc<dyn Memory>) -> Self {
+        let namespace = format!("plugin.{}", self.plugin);
+        self.memory = Some(Arc::new(NamespacedMemory::new(memory, &namespace)));
+        self
+    }
+
+    fn trap(&self, what: &str, e: impl std::fmt::Display) -> anyhow::Error {
+        anyhow!("plugin '{}' {what}: {e}", self.plugin)
+    }
+}
+
+/// Copy `len` (at most `max`) bytes at `ptr` out of the calling instance's
+/// memory.
+fn read_guest(caller: &Caller<'_, HostState>, ptr: i32, len: i32, max: usize) -> Option<Vec<u8>> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let start = usize::try_from(ptr).ok()?;
+    let len = usize::try_from(len).ok()?;
+    if len > max {
+        return None;
+    }
+    let mut buf = vec![0; len];
+    memory.read(caller, start, &mut buf).ok()?;
+    Some(buf)
+}
+
+fn read_guest_str(
+    caller: &Caller<'_, HostState>,
+    ptr: i32,
+    len: i32,
+    max: usize,
+) -> Option<String> {
+    String::from_utf8(read_guest(caller, ptr, len, max)?).ok()
+}
+
+/// Memory backend and runtime for a memory host call, if both are available.
+fn memory_host(caller: &Caller<'_, HostState>) -> Option<(Arc<dyn Memory>, Handle)> {
+    let state = caller.data();
+    Some((state.memory.clone()?, state.runtime.clone()?))
+}
+
+/// Drive an async memory call from a synchronous host function. The call
+/// runs on a scoped thread, so this works even when the plugin itself was
+/// invoked from a runtime worker (e.g. while building prompt sections).
+fn block_on_memory<T: Send>(
+    runtime: &Handle,
+    call: impl Future<Output = Result<T>> + Send,
+) -> Option<T> {
+    let result = std::thread::scope(|scope| scope.spawn(|| runtime.block_on(call)).join());
+    match result {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            tracing::warn!("WASM plugin memory call failed: {e:#}");
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+fn host_memory_store(
+    caller: Caller<'_, HostState>,
+    key_ptr: i32,
+    key_len: i32,
+    value_ptr: i32,
+    value_len: i32,
+) -> i32 {
+    let Some(key) = read_guest_str(&caller, key_ptr, key_len, MAX_MESSAGE_BYTES) else {
+        return HOST_ERROR;
+    };
+    let Some(value) = read_guest_str(&caller, value_ptr, value_len, MAX_MEMORY_VALUE_BYTES) else {
+        return HOST_ERROR;
+    };
+    let Some((memory, runtime)) = memory_host(&caller) else {
+        return HOST_ERROR;
+    };
+    let stored = block_on_memory(&runtime, async move {
+        memory
+            .store(&key, &value, MemoryCategory::Custom("plugin".into()), None)
+            .await
+    });
+    if stored.is_some() {
+        0
+    } else {
+        HOST_ERROR
+    }
+}
+
+fn host_memory_get(
+    mut caller: Caller<'_, HostState>,
+    key_ptr: i32,
+    key_len: i32,
+    out_ptr: i32,
+    out_cap: i32,
+) -> i32 {
+    let Some(key) = read_guest_str(&caller, key_ptr, key_len, MAX_MESSAGE_BYTES) else {
+        return HOST_ERROR;
+    };
+    let Some((memory, runtime)) = memory_host(&caller) else {
+        return HOST_ERROR;
+    };
+    let Some(Some(entry)) = block_on_memory(&runtime, async move { memory.get(&key).await }) else {
+        return HOST_ERROR;
+    };
+    let content = entry.content.into_bytes();
+    let Ok(len) = i32::try_from(content.len()) else {
+        return HOST_ERROR;
+    };
+    if len > out_cap {
+        return len;
+    }
+    let Some(guest_memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+        return HOST_ERROR;
+    };
+    let Ok(start) = usize::try_from(out_ptr) else {
+        return HOST_ERROR;
+    };
+    match guest_memory.write(&mut caller, start, &content) {
+        Ok(()) => len,
+        Err(_) => HOST_ERROR,
+    }
+}
+
+impl PluginRunner for WasmRunner {
+    fn invoke_blocking(&self, request: &PluginRequest) -> Result<serde_json::Value> {
+        let state = HostState {
+            messages: Vec::new(),
+            memory: self.memory.clone(),
+            runtime: Handle::try_current().ok(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_BYTES)
+                .build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(FUEL_PER_CALL)
+            .map_err(|e| self.trap("could not be fueled", e))?;
+
+        let mut linker = Linker::<HostState>::new(&self.engine);
+        linker
+            .func_wrap(
+                HOST_MODULE,
+                "emit",
+                |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| {
+                    if let Some(bytes) = read_guest(&caller, ptr, len, MAX_MESSAGE_BYTES) {
+                        let messages = &mut caller.data_mut().messages;
+                        if messages.len() < MAX_MESSAGES {
+                            messages.push(String::from_utf8_lossy(&bytes).into_owned());
+                        }
+                    }
+                },
+            )
+            .and_then(|linker| linker.func_wrap(HOST_MODULE, "memory_store", host_memory_store))
+            .and_then(|linker| linker.func_wrap(HOST_MODULE, "memory_get", host_memory_get))
+            .map_err(|e| self.trap("host setup failed", e))?;
+
+        let instance = linker
+            .instantiate(&mut store, &self.module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| self.trap("failed to instantiate", e))?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .with_context(|| format!("plugin '{}' does not export `memory`", self.plugin))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| self.trap("has no usable `alloc` export", e))?;
+        let handle = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "handle")
+            .map_err(|e| self.trap("has no usable `handle` export", e))?;
+
+        let payload = serde_json::to_vec(request)?;
+        let len = i32::try_from(payload.len()).context("plugin request too large")?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|e| self.trap("trapped in `alloc`", e))?;
+        memory
+            .write(
+                &mut store,
+                usize::try_from(ptr).context("negative request pointer")?,
+                &payload,
+            )
+            .map_err(|e| self.trap("returned an invalid request buffer", e))?;
+
+        let packed = handle
+            .call(&mut store, (ptr, len))
+            .map_err(|e| self.trap("trapped in `handle`", e))?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let (reply_ptr, reply_len) = (
+            ((packed as u64) >> 32) as usize,
+            ((packed as u64) & 0xffff_ffff) as usize,
+        );
+        if reply_len > MAX_REPLY_BYTES {
+            bail!(
+                "plugin '{}' reply exceeds {MAX_REPLY_BYTES} bytes",
+                self.plugin
+            );
+        }
+        let mut reply = vec![0; reply_len];
+        memory
+            .read(&store, reply_ptr, &mut reply)
+            .map_err(|e| self.trap("returned an invalid reply buffer", e))?;
+
+        let messages = std::mem::take(&mut store.data_mut().messages);
+        for message in &messages {
+            tracing::info!("plugin {}: {message}", self.plugin);
+        }
+
+        let mut reply = parse_reply(&self.plugin, &reply)?;
+        // Commands may answer purely through emitted messages.
+        if matches!(request, PluginRequest::Command { .. })
+            && reply.get("output").is_none()
+            && !messages.is_empty()
+        {
+            reply = serde_json::json!({ "output": messages.join("\n") });
+        }
+        Ok(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-assembled module: imports `zeroclaw.emit`, exports `memory`,
+    /// `alloc` (always 1024) and `handle`, which emits "hi" and replies with
+    /// the `{"output":"hi"}` data segment at offset 16.
+    const ECHO_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x11, 0x03, 0x60, 0x01, 0x7f, 0x01,
+        0x7f, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7e, 0x60, 0x02, 0x7f, 0x7f, 0x00, 0x02, 0x11, 0x01,
+        0x08, 0x7a, 0x65, 0x72, 0x6f, 0x63, 0x6c, 0x61, 0x77, 0x04, 0x65, 0x6d, 0x69, 0x74, 0x00,
+        0x02, 0x03, 0x03, 0x02, 0x00, 0x01, 0x05, 0x03, 0x01, 0x00, 0x01, 0x07, 0x1b, 0x03, 0x06,
+        0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00, 0x05, 0x61, 0x6c, 0x6c, 0x6f, 0x63, 0x00,
+        0x01, 0x06, 0x68, 0x61, 0x6e, 0x64, 0x6c, 0x65, 0x00, 0x02, 0x0a, 0x17, 0x02, 0x05, 0x00,
+        0x41, 0x80, 0x08, 0x0b, 0x0f, 0x00, 0x41, 0x30, 0x41, 0x02, 0x10, 0x00, 0x42, 0x8f, 0x80,
+        0x80, 0x80, 0x80, 0x02, 0x0b, 0x0b, 0x1c, 0x02, 0x00, 0x41, 0x10, 0x0b, 0x0f, 0x7b, 0x22,
+        0x6f, 0x75, 0x74, 0x70, 0x75, 0x74, 0x22, 0x3a, 0x22, 0x68, 0x69, 0x22, 0x7d, 0x00, 0x41,
+        0x30, 0x0b, 0x02, 0x68, 0x69,
+    ];
+
+    /// Module whose `handle` calls `memory_store("note", "hello")`, then
+    /// `memory_get("note")` into its reply buffer and answers
+    /// `{"output":"<value>"}`.
+    const MEMORY_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x14, 0x03, 0x60, 0x04, 0x7f, 0x7f,
+        0x7f, 0x7f, 0x01, 0x7f, 0x60, 0x01, 0x7f, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f, 0x01, 0x7e,
+        0x02, 0x2f, 0x02, 0x08, 0x7a, 0x65, 0x72, 0x6f, 0x63, 0x6c, 0x61, 0x77, 0x0c, 0x6d, 0x65,
+        0x6d, 0x6f, 0x72, 0x79, 0x5f, 0x73, 0x74, 0x6f, 0x72, 0x65, 0x00, 0x00, 0x08, 0x7a, 0x65,
+        0x72, 0x6f, 0x63, 0x6c, 0x61, 0x77, 0x0a, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x5f, 0x67,
+        0x65, 0x74, 0x00, 0x00, 0x03, 0x03, 0x02, 0x01, 0x02, 0x05, 0x03, 0x01, 0x00, 0x01, 0x07,
+        0x1b, 0x03, 0x06, 0x6d, 0x65, 0x6d, 0x6f, 0x72, 0x79, 0x02, 0x00, 0x05, 0x61, 0x6c, 0x6c,
+        0x6f, 0x63, 0x00, 0x02, 0x06, 0x68, 0x61, 0x6e, 0x64, 0x6c, 0x65, 0x00, 0x03, 0x0a, 0x3e,
+        0x02, 0x05, 0x00, 0x41, 0x80, 0x08, 0x0b, 0x36, 0x01, 0x01, 0x7f, 0x41, 0x10, 0x41, 0x04,
+        0x41, 0x20, 0x41, 0x05, 0x10, 0x00, 0x1a, 0x41, 0x10, 0x41, 0x04, 0x41, 0x33, 0x41, 0xc0,
+        0x00, 0x10, 0x01, 0x21, 0x02, 0x20, 0x02, 0x41, 0x33, 0x6a, 0x41, 0xa2, 0xfa, 0x01, 0x3b,
+        0x00, 0x00, 0x42, 0x80, 0x80, 0x80, 0x80, 0x80, 0x05, 0x20, 0x02, 0x41, 0x0d, 0x6a, 0xad,
+        0x84, 0x0b, 0x0b, 0x24, 0x03, 0x00, 0x41, 0x10, 0x0b, 0x04, 0x6e, 0x6f, 0x74, 0x65, 0x00,
+        0x41, 0x20, 0x0b, 0x05, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x41, 0x28, 0x0b, 0x0b, 0x7b,
+        0x22, 0x6f, 0x75, 0x74, 0x70, 0x75, 0x74, 0x22, 0x3a, 0x22,
+    ];
+
+    /// Module importing `env.nope`, a function the sandbox does not offer.
+    const FOREIGN_IMPORT_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x02,
+        0x0c, 0x01, 0x03, 0x65, 0x6e, 0x76, 0x04, 0x6e, 0x6f, 0x70, 0x65, 0x00, 0x00,
+    ];
+
+    #[tokio::test]
+    async fn wasm_runner_round_trips_plugin_scoped_memory() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let backend: Arc<dyn Memory> =
+            Arc::new(crate::memory::SqliteMemory::new(tmp.path()).unwrap());
+        let runner = WasmRunner::from_bytes("notes", MEMORY_MODULE)
+            .unwrap()
+            .with_memory(Arc::clone(&backend));
+
+        let reply = tokio::task::spawn_blocking(move || {
+            runner.invoke_blocking(&PluginRequest::Command {
+                command: "remember".into(),
+                args: String::new(),
+            })
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(reply["output"], "hello");
+
+        let stored = backend.get("plugin.notes:note").await.unwrap().unwrap();
+        assert_eq!(stored.content, "hello");
+        assert!(backend.get("note").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn wasm_runner_memory_calls_fail_without_a_backend() {
+        // `memory_get` returns -1, so the reply is truncated JSON.
+        let runner = WasmRunner::from_bytes("notes", MEMORY_MODULE).unwrap();
+        let err = runner
+            .invoke_blocking(&PluginRequest::Command {
+                command: "remember".into(),
+                args: String::new(),
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid JSON"));
+    }
+
+    #[test]
+    fn wasm_runner_exchanges_json_through_linear_memory() {
+        let runner = WasmRunner::from_bytes("echo", ECHO_MODULE).unwrap();
+        let reply = runner
+            .invoke_blocking(&PluginRequest::Command {
+                command: "ping".into(),
+                args: String::new(),
+            })
+            .unwrap();
+        assert_eq!(reply["output"], "hi");
+    }
+
+    #[test]
+    fn wasm_runner_rejects_imports_outside_host_api() {
+        let err = WasmRunner::from_bytes("evil", FOREIGN_IMPORT_MODULE)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("env::nope"));
+    }
+
+    #[test]
+    fn wasm_runner_rejects_invalid_modules() {
+        assert!(WasmRunner::from_bytes("junk", b"not wasm").is_err());
+    }
+}
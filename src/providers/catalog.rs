@@ -0,0 +1,423 @@
+//! Model catalogs: what a provider endpoint can serve.
+//!
+//! Backs `zeroclaw models`. Three listing APIs are understood, picked from
+//! the provider name or base URL:
+//!
+//! - OpenRouter `/api/v1/models` — context window, pricing, tool support
+//! - Ollama `/api/tags`, plus `/api/show` per model for context and tools
+//! - OpenAI-compatible `/v1/models` — ids only, unless the server adds
+//!   `context_length`-style fields (vLLM, LM Studio and others do)
+//!
+//! `zeroclaw models refresh` also saves the ids to
+//! `<workspace>/state/models_cache.json`, which the channel `/models`
+//! command previews.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+const MODEL_CACHE_FILE: &str = "models_cache.json";
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+/// Which listing API an endpoint speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogKind {
+    OpenAi,
+    OpenRouter,
+    Ollama,
+}
+
+impl CatalogKind {
+    pub fn detect(provider: &str, base_url: Option<&str>) -> Self {
+        let provider = provider.trim().to_ascii_lowercase();
+        let url = base_url.unwrap_or_default().to_ascii_lowercase();
+        if provider == "openrouter" || url.contains("openrouter.ai") {
+            Self::OpenRouter
+        } else if provider == "ollama" || url.contains(":11434") {
+            Self::Ollama
+        } else {
+            Self::OpenAi
+        }
+    }
+
+    fn default_base_url(self) -> &'static str {
+        match self {
+            Self::OpenAi => OPENAI_BASE_URL,
+            Self::OpenRouter => OPENROUTER_BASE_URL,
+            Self::Ollama => OLLAMA_BASE_URL,
+        }
+    }
+}
+
+/// USD per million tokens.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelPricing {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
+/// One model as reported by the provider. Unknown fields stay `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub context_window: Option<u64>,
+    pub pricing: Option<ModelPricing>,
+    pub supports_tools: Option<bool>,
+}
+
+impl ModelInfo {
+    fn id_only(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            context_window: None,
+            pricing: None,
+            supports_tools: None,
+        }
+    }
+}
+
+fn data_array(body: &Value, field: &str) -> Result<Vec<Value>> {
+    body.get(field)
+        .and_then(Value::as_array)
+        .cloned()
+        .with_context(|| format!("Unexpected model listing: missing '{field}' array"))
+}
+
+fn first_u64(value: &Value, fields: &[&str]) -> Option<u64> {
+    fields
+        .iter()
+        .find_map(|field| value.get(*field).and_then(Value::as_u64))
+}
+
+/// Parse an OpenAI-compatible `/models` response.
+pub fn parse_openai_models(body: &Value) -> Result<Vec<ModelInfo>> {
+    Ok(data_array(body, "data")?
+        .iter()
+        .filter_map(|model| {
+            let id = model.get("id")?.as_str()?;
+            Some(ModelInfo {
+                context_window: first_u64(
+                    model,
+                    &["context_length", "context_window", "max_model_len"],
+                ),
+                ..ModelInfo::id_only(id)
+            })
+        })
+        .collect())
+}
+
+/// Parse OpenRouter's `/models` catalog. Prices arrive as USD-per-token
+/// strings.
+pub fn parse_openrouter_models(body: &Value) -> Result<Vec<ModelInfo>> {
+    let per_million = |pricing: &Value, field: &str| {
+        pricing
+            .get(field)
+            .and_then(Value::as_str)
+            .and_then(|raw| raw.parse::<f64>().ok())
+            .map(|per_token| per_token * TOKENS_PER_PRICE_UNIT)
+    };
+    Ok(data_array(body, "data")?
+        .iter()
+        .filter_map(|model| {
+            let id = model.get("id")?.as_str()?;
+            let pricing = model.get("pricing").and_then(|pricing| {
+                Some(ModelPricing {
+                    prompt: per_million(pricing, "prompt")?,
+                    completion: per_million(pricing, "completion")?,
+                })
+            });
+            let supports_tools = model
+                .get("supported_parameters")
+                .and_then(Value::as_array)
+                .map(|params| params.iter().any(|p| p.as_str() == Some("tools")));
+            Some(ModelInfo {
+                id: id.to_string(),
+                context_window: first_u64(model, &["context_length"]),
+                pricing,
+                supports_tools,
+            })
+        })
+        .collect())
+}
+
+/// Parse Ollama's `/api/tags` listing (names only).
+pub fn parse_ollama_tags(body: &Value) -> Result<Vec<ModelInfo>> {
+    Ok(data_array(body, "models")?
+        .iter()
+        .filter_map(|model| Some(ModelInfo::id_only(model.get("name")?.as_str()?)))
+        .collect())
+}
+
+/// Fill context window and tool support from an Ollama `/api/show` response.
+fn apply_ollama_details(model: &mut ModelInfo, show: &Value) {
+    model.context_window = show
+        .get("model_info")
+        .and_then(Value::as_object)
+        .and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+        });
+    model.supports_tools = show
+        .get("capabilities")
+        .and_then(Value::as_array)
+        .map(|caps| caps.iter().any(|cap| cap.as_str() == Some("tools")));
+}
+
+/// Fetch the model catalog for `provider`, sorted by id.
+pub async fn fetch_models(
+    provider: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+) -> Result<Vec<ModelInfo>> {
+    let kind = CatalogKind::detect(provider, api_url);
+    let base_url = api_url
+        .unwrap_or(kind.default_base_url())
+        .trim_end_matches('/');
+    let client = crate::config::build_runtime_proxy_client_with_timeouts(
+        &format!("provider.{provider}"),
+        30,
+        10,
+    );
+    let get = |url: String| {
+        let mut request = client.get(url);
+        if let Some(key) = api_key {
+            request = request.bearer_auth(key);
+        }
+        request
+    };
+
+    let mut models = match kind {
+        CatalogKind::OpenAi | CatalogKind::OpenRouter => {
+            let response = get(format!("{base_url}/models")).send().await?;
+            if !response.status().is_success() {
+                return Err(super::api_error(provider, response).await);
+            }
+            let body: Value = response.json().await?;
+            if kind == CatalogKind::OpenRouter {
+                parse_openrouter_models(&body)?
+            } else {
+                parse_openai_models(&body)?
+            }
+        }
+        CatalogKind::Ollama => {
+            // Ollama's native API lives at the root, not under /v1.
+            let root = base_url.trim_end_matches("/v1");
+            let response = get(format!("{root}/api/tags")).send().await?;
+            if !response.status().is_success() {
+                return Err(super::api_error(provider, response).await);
+            }
+            let mut models = parse_ollama_tags(&response.json().await?)?;
+            for model in &mut models {
+                let show = client
+                    .post(format!("{root}/api/show"))
+                    .json(&serde_json::json!({ "model": model.id }))
+                    .send()
+                    .await;
+                match show {
+                    Ok(response) if response.status().is_success() => {
+                        if let Ok(details) = response.json::<Value>().await {
+                            apply_ollama_details(model, &details);
+                        }
+                    }
+                    _ => tracing::debug!("No details for Ollama model {}", model.id),
+                }
+            }
+            models
+        }
+    };
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(models)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ModelCacheState {
+    entries: Vec<ModelCacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModelCacheEntry {
+    provider: String,
+    models: Vec<String>,
+}
+
+pub fn models_cache_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(MODEL_CACHE_FILE)
+}
+
+fn load_cache(workspace_dir: &Path) -> ModelCacheState {
+    std::fs::read_to_string(models_cache_path(workspace_dir))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Cached model ids for `provider` (empty when never refreshed).
+pub fn cached_model_ids(workspace_dir: &Path, provider: &str) -> Vec<String> {
+    load_cache(workspace_dir)
+        .entries
+        .into_iter()
+        .find(|entry| entry.provider == provider)
+        .map(|entry| entry.models)
+        .unwrap_or_default()
+}
+
+/// Replace the cached ids for `provider`.
+pub fn save_cached_model_ids(workspace_dir: &Path, provider: &str, ids: Vec<String>) -> Result<()> {
+    let mut state = load_cache(workspace_dir);
+    state.entries.retain(|entry| entry.provider != provider);
+    state.entries.push(ModelCacheEntry {
+        provider: provider.to_string(),
+        models: ids,
+    });
+    let path = models_cache_path(workspace_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(&state)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn format_price(pricing: Option<&ModelPricing>) -> String {
+    pricing.map_or_else(
+        || "-".to_string(),
+        |p| format!("{:.2} / {:.2}", p.prompt, p.completion),
+    )
+}
+
+/// `zeroclaw models list|refresh`. Lists the catalog of `provider` (the
+/// configured default when `None`); `refresh` also updates the cache.
+pub async fn run(config: &Config, provider: Option<&str>, json: bool, refresh: bool) -> Result<()> {
+    let default_provider = config.default_provider.as_deref().unwrap_or("openrouter");
+    let provider = provider.unwrap_or(default_provider).trim().to_string();
+    // `api_url` belongs to the default provider; other providers use their
+    // public endpoint.
+    let api_url = if provider == default_provider {
+        config.api_url.as_deref()
+    } else {
+        None
+    };
+    let api_key = super::resolve_provider_credential(&provider, config.api_key.as_deref());
+    let models = fetch_models(&provider, api_key.as_deref(), api_url)
+        .await
+        .with_context(|| format!("Failed to list models for '{provider}'"))?;
+
+    if refresh {
+        let ids = models.iter().map(|model| model.id.clone()).collect();
+        save_cached_model_ids(&config.workspace_dir, &provider, ids)?;
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&models)?);
+        return Ok(());
+    }
+
+    println!("Models from {provider} ({}):\n", models.len());
+    println!(
+        "  {:<48} {:>9}  {:<19} TOOLS",
+        "ID", "CONTEXT", "$/1M IN / OUT"
+    );
+    for model in &models {
+        println!(
+            "  {:<48} {:>9}  {:<19} {}",
+            model.id,
+            model
+                .context_window
+                .map_or_else(|| "-".to_string(), |tokens| tokens.to_string()),
+            format_price(model.pricing.as_ref()),
+            match model.supports_tools {
+                Some(true) => "yes",
+                Some(false) => "no",
+                None => "?",
+            }
+        );
+    }
+    if refresh {
+        println!(
+            "\n✅ Cached {} model ids for /models previews",
+            models.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn catalog_kind_follows_provider_then_url() {
+        assert_eq!(
+            CatalogKind::detect("openrouter", None),
+            CatalogKind::OpenRouter
+        );
+        assert_eq!(
+            CatalogKind::detect("openai", Some("https://openrouter.ai/api/v1")),
+            CatalogKind::OpenRouter
+        );
+        assert_eq!(
+            CatalogKind::detect("openai", Some("http://10.0.0.1:11434/v1")),
+            CatalogKind::Ollama
+        );
+        assert_eq!(CatalogKind::detect("openai", None), CatalogKind::OpenAi);
+    }
+
+    #[test]
+    fn parses_each_listing_format() {
+        let openrouter = parse_openrouter_models(&json!({"data": [{
+            "id": "anthropic/claude-sonnet-4.6",
+            "context_length": 200_000,
+            "pricing": {"prompt": "0.000003", "completion": "0.000015"},
+            "supported_parameters": ["temperature", "tools"]
+        }, {
+            "id": "some/base-model",
+            "context_length": 8192,
+            "supported_parameters": ["temperature"]
+        }]}))
+        .unwrap();
+        assert_eq!(openrouter[0].context_window, Some(200_000));
+        let pricing = openrouter[0].pricing.as_ref().unwrap();
+        assert!((pricing.prompt - 3.0).abs() < 1e-9);
+        assert!((pricing.completion - 15.0).abs() < 1e-9);
+        assert_eq!(openrouter[0].supports_tools, Some(true));
+        assert_eq!(openrouter[1].supports_tools, Some(false));
+        assert!(openrouter[1].pricing.is_none());
+
+        let openai = parse_openai_models(&json!({"data": [
+            {"id": "gpt-4o", "owned_by": "openai"},
+            {"id": "local-llama", "max_model_len": 32768}
+        ]}))
+        .unwrap();
+        assert_eq!(openai[0], ModelInfo::id_only("gpt-4o"));
+        assert_eq!(openai[1].context_window, Some(32768));
+
+        let mut ollama = parse_ollama_tags(&json!({"models": [{"name": "qwen3:8b"}]})).unwrap();
+        apply_ollama_details(
+            &mut ollama[0],
+            &json!({
+                "capabilities": ["completion", "tools"],
+                "model_info": {"qwen3.context_length": 40960}
+            }),
+        );
+        assert_eq!(ollama[0].context_window, Some(40960));
+        assert_eq!(ollama[0].supports_tools, Some(true));
+
+        assert!(parse_openai_models(&json!({"error": "nope"})).is_err());
+    }
+
+    #[test]
+    fn cache_round_trips_per_provider() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        save_cached_model_ids(tmp.path(), "openai", vec!["gpt-4o".into()]).unwrap();
+        save_cached_model_ids(tmp.path(), "ollama", vec!["qwen3:8b".into()]).unwrap();
+        save_cached_model_ids(tmp.path(), "openai", vec!["gpt-5".into()]).unwrap();
+        assert_eq!(cached_model_ids(tmp.path(), "openai"), vec!["gpt-5"]);
+        assert_eq!(cached_model_ids(tmp.path(), "ollama"), vec!["qwen3:8b"]);
+        assert!(cached_model_ids(tmp.path(), "openrouter").is_empty());
+    }
+}
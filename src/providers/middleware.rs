@@ -0,0 +1,576 @@
+//! Request/response middleware around [`Provider`].
+//!
+//! Cross-cutting behaviour (logging, budgets, redaction, retries, caching)
+//! lives in [`ProviderMiddleware`] layers instead of individual providers.
+//! A [`MiddlewareProvider`] runs every structured chat call through the
+//! configured pipeline, outermost layer first; each layer receives the call
+//! and a [`Next`] handle that forwards it to the rest of the stack and,
+//! finally, to the wrapped provider.
+//!
+//! ```toml
+//! [provider_middleware]
+//! pipeline = ["logging", "budget", "redact", "retry", "cache"]
+//! ```
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilities, ProviderErrorKind,
+    ResponseFormat, StreamChunk, StreamOptions, StreamResult, ToolsPayload,
+};
+use crate::config::ProviderMiddlewareConfig;
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::stream;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Middleware names accepted in `[provider_middleware] pipeline`.
+pub const MIDDLEWARE_NAMES: &[&str] = &["logging", "budget", "redact", "retry", "cache"];
+
+/// An owned chat call as seen by middleware; layers may rewrite any field.
+#[derive(Debug, Clone)]
+pub struct ProviderCall {
+    pub messages: Vec<ChatMessage>,
+    pub tools: Option<Vec<ToolSpec>>,
+    pub model: String,
+    pub temperature: f64,
+}
+
+/// The remainder of the pipeline. Cheap to copy, so a layer can call it
+/// more than once (e.g. to retry).
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    provider: &'a dyn Provider,
+    layers: &'a [Arc<dyn ProviderMiddleware>],
+}
+
+impl Next<'_> {
+    pub async fn run(self, call: ProviderCall) -> anyhow::Result<ChatResponse> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => {
+                layer
+                    .handle(
+                        call,
+                        Next {
+                            provider: self.provider,
+                            layers,
+                        },
+                    )
+                    .await
+            }
+            None => {
+                self.provider
+                    .chat(
+                        ChatRequest {
+                            messages: &call.messages,
+                            tools: call.tools.as_deref(),
+                        },
+                        &call.model,
+                        call.temperature,
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// One layer of the provider pipeline.
+#[async_trait]
+pub trait ProviderMiddleware: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Handle `call`, usually by forwarding it with `next.run(call)`.
+    async fn handle(&self, call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse>;
+}
+
+/// A provider whose chat calls pass through a middleware pipeline.
+///
+/// `chat`, `chat_with_history` and `chat_with_system` go through the
+/// pipeline; raw-JSON `chat_with_tools`, structured output, streaming and
+/// capability queries are forwarded to the inner provider unchanged.
+pub struct MiddlewareProvider {
+    inner: Box<dyn Provider>,
+    layers: Vec<Arc<dyn ProviderMiddleware>>,
+}
+
+impl MiddlewareProvider {
+    pub fn new(inner: Box<dyn Provider>, layers: Vec<Arc<dyn ProviderMiddleware>>) -> Self {
+        Self { inner, layers }
+    }
+
+    async fn call(&self, call: ProviderCall) -> anyhow::Result<ChatResponse> {
+        Next {
+            provider: self.inner.as_ref(),
+            layers: &self.layers,
+        }
+        .run(call)
+        .await
+    }
+}
+
+#[async_trait]
+impl Provider for MiddlewareProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system_prompt) = system_prompt {
+            messages.push(ChatMessage::system(system_prompt));
+        }
+        messages.push(ChatMessage::user(message));
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let response = self
+            .call(ProviderCall {
+                messages: messages.to_vec(),
+                tools: None,
+                model: model.to_string(),
+                temperature,
+            })
+            .await?;
+        Ok(response.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.call(ProviderCall {
+            messages: request.messages.to_vec(),
+            tools: request.tools.map(<[ToolSpec]>::to_vec),
+            model: model.to_string(),
+            temperature,
+        })
+        .await
+    }
+
+    async fn chat_with_response_format(
+        &self,
+        messages: &[ChatMessage],
+        format: &ResponseFormat,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.inner
+            .chat_with_response_format(messages, format, model, temperature)
+            .await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+/// Wrap `provider` in the pipeline from `config`; returns it unchanged when
+/// the pipeline is empty.
+pub fn wrap(
+    provider: Box<dyn Provider>,
+    config: &ProviderMiddlewareConfig,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let layers = build_pipeline(config)?;
+    if layers.is_empty() {
+        return Ok(provider);
+    }
+    Ok(Box::new(MiddlewareProvider::new(provider, layers)))
+}
+
+/// Instantiate the configured layers, outermost first.
+pub fn build_pipeline(
+    config: &ProviderMiddlewareConfig,
+) -> anyhow::Result<Vec<Arc<dyn ProviderMiddleware>>> {
+    config
+        .pipeline
+        .iter()
+        .map(|name| -> anyhow::Result<Arc<dyn ProviderMiddleware>> {
+            Ok(match name.trim() {
+                "logging" => Arc::new(LoggingMiddleware),
+                "budget" => Arc::new(BudgetMiddleware::new(config.budget_requests_per_hour)),
+                "redact" => Arc::new(RedactionMiddleware),
+                "retry" => Arc::new(RetryMiddleware::new(
+                    config.retry_attempts,
+                    Duration::from_millis(config.retry_backoff_ms),
+                )),
+                "cache" => Arc::new(CacheMiddleware::new(
+                    Duration::from_secs(config.cache_ttl_secs),
+                    config.cache_max_entries,
+                )),
+                other => anyhow::bail!(
+                    "unknown provider middleware '{other}' (expected one of: {})",
+                    MIDDLEWARE_NAMES.join(", ")
+                ),
+            })
+        })
+        .collect()
+}
+
+// ── Built-in layers ─────────────────────────────────────────────
+
+/// Logs model, message count, latency and outcome of every call.
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl ProviderMiddleware for LoggingMiddleware {
+    fn name(&self) -> &str {
+        "logging"
+    }
+
+    async fn handle(&self, call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse> {
+        let model = call.model.clone();
+        let messages = call.messages.len();
+        let started = Instant::now();
+        let result = next.run(call).await;
+        let elapsed_ms = started.elapsed().as_millis();
+        match &result {
+            Ok(response) => tracing::info!(
+                model = model.as_str(),
+                messages,
+                elapsed_ms,
+                tool_calls = response.tool_calls.len(),
+                "Provider call succeeded"
+            ),
+            Err(e) => tracing::warn!(
+                model = model.as_str(),
+                messages,
+                elapsed_ms,
+                error = %super::sanitize_api_error(&e.to_string()),
+                "Provider call failed"
+            ),
+        }
+        result
+    }
+}
+
+/// Rejects calls once `max_per_hour` requests were made in the last hour.
+pub struct BudgetMiddleware {
+    max_per_hour: u32,
+    recent: Mutex<VecDeque<Instant>>,
+}
+
+impl BudgetMiddleware {
+    pub fn new(max_per_hour: u32) -> Self {
+        Self {
+            max_per_hour,
+            recent: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for BudgetMiddleware {
+    fn name(&self) -> &str {
+        "budget"
+    }
+
+    async fn handle(&self, call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse> {
+        if self.max_per_hour > 0 {
+            let now = Instant::now();
+            let mut recent = self.recent.lock();
+            while recent
+                .front()
+                .is_some_and(|at| now.duration_since(*at) >= Duration::from_secs(3600))
+            {
+                recent.pop_front();
+            }
+            if recent.len() >= self.max_per_hour as usize {
+                anyhow::bail!(
+                    "Provider request budget exhausted: {} requests in the last hour (limit {})",
+                    recent.len(),
+                    self.max_per_hour
+                );
+            }
+            recent.push_back(now);
+        }
+        next.run(call).await
+    }
+}
+
+/// Scrubs API-key-like tokens from outgoing messages before they leave the host.
+pub struct RedactionMiddleware;
+
+#[async_trait]
+impl ProviderMiddleware for RedactionMiddleware {
+    fn name(&self) -> &str {
+        "redact"
+    }
+
+    async fn handle(&self, mut call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse> {
+        for message in &mut call.messages {
+            message.content = super::scrub_secret_patterns(&message.content);
+        }
+        next.run(call).await
+    }
+}
+
+/// Retries rate-limit, capacity and network failures with exponential backoff.
+pub struct RetryMiddleware {
+    attempts: u32,
+    backoff: Duration,
+}
+
+impl RetryMiddleware {
+    pub fn new(attempts: u32, backoff: Duration) -> Self {
+        Self { attempts, backoff }
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for RetryMiddleware {
+    fn name(&self) -> &str {
+        "retry"
+    }
+
+    async fn handle(&self, call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse> {
+        let mut attempt = 0;
+        loop {
+            match next.run(call.clone()).await {
+                Err(e) if attempt < self.attempts && is_retryable(&e) => {
+                    let delay = self.backoff.saturating_mul(2_u32.saturating_pow(attempt));
+                    attempt += 1;
+                    tracing::warn!(
+                        model = call.model.as_str(),
+                        attempt,
+                        delay_ms = delay.as_millis(),
+                        "Retrying provider call: {}",
+                        super::sanitize_api_error(&e.to_string())
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn is_retryable(err: &anyhow::Error) -> bool {
+    matches!(
+        super::traits::classify_provider_error(&format!("{err:#}")),
+        ProviderErrorKind::RateLimit | ProviderErrorKind::Capacity | ProviderErrorKind::Network
+    )
+}
+
+/// Serves identical calls (same model, temperature, messages and tools) from
+/// memory for `ttl`.
+pub struct CacheMiddleware {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, ChatResponse)>>,
+}
+
+impl CacheMiddleware {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(call: &ProviderCall) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(call.model.as_bytes());
+        hasher.update(call.temperature.to_bits().to_le_bytes());
+        hasher.update(serde_json::to_vec(&call.messages).unwrap_or_default());
+        hasher.update(serde_json::to_vec(&call.tools).unwrap_or_default());
+        hex::encode(hasher.finalize())
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for CacheMiddleware {
+    fn name(&self) -> &str {
+        "cache"
+    }
+
+    async fn handle(&self, call: ProviderCall, next: Next<'_>) -> anyhow::Result<ChatResponse> {
+        if self.max_entries == 0 {
+            return next.run(call).await;
+        }
+        let key = Self::key(&call);
+        let cached = self
+            .entries
+            .lock()
+            .get(&key)
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, response)| response.clone());
+        if let Some(response) = cached {
+            return Ok(response);
+        }
+
+        let response = next.run(call).await?;
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        if entries.len() >= self.max_entries {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(key, _)| key.clone())
+            {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), response.clone()));
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fails with a 429 for the first `failures` calls, then echoes the last
+    /// user message.
+    struct FlakyProvider {
+        failures: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for FlakyProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                anyhow::bail!("API error (429 Too Many Requests)");
+            }
+            Ok(format!("echo: {message}"))
+        }
+    }
+
+    fn wrapped(failures: usize, pipeline: &[&str]) -> (Box<dyn Provider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = ProviderMiddlewareConfig {
+            pipeline: pipeline.iter().map(ToString::to_string).collect(),
+            retry_backoff_ms: 1,
+            ..ProviderMiddlewareConfig::default()
+        };
+        let provider = wrap(
+            Box::new(FlakyProvider {
+                failures,
+                calls: Arc::clone(&calls),
+            }),
+            &config,
+        )
+        .unwrap();
+        (provider, calls)
+    }
+
+    #[tokio::test]
+    async fn layers_compose_in_pipeline_order() {
+        let (provider, calls) = wrapped(1, &["redact", "retry", "cache"]);
+
+        let first = provider
+            .chat_with_system(None, "token sk-abcdefghijklmnop", "m", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(first, "echo: token [REDACTED]");
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "one failure, one retry");
+
+        let cached = provider
+            .chat_with_system(None, "token sk-abcdefghijklmnop", "m", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(cached, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "served from cache");
+    }
+
+    #[tokio::test]
+    async fn budget_rejects_calls_over_the_hourly_limit() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = MiddlewareProvider::new(
+            Box::new(FlakyProvider {
+                failures: 0,
+                calls: Arc::clone(&calls),
+            }),
+            vec![Arc::new(BudgetMiddleware::new(1))],
+        );
+        assert!(provider.simple_chat("hi", "m", 0.0).await.is_ok());
+        let err = provider.simple_chat("hi", "m", 0.0).await.unwrap_err();
+        assert!(err.to_string().contains("budget exhausted"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn unknown_middleware_name_is_rejected() {
+        let config = ProviderMiddlewareConfig {
+            pipeline: vec!["logging".into(), "telemetry".into()],
+            ..ProviderMiddlewareConfig::default()
+        };
+        let err = build_pipeline(&config).err().unwrap();
+        assert!(err.to_string().contains("telemetry"));
+    }
+}
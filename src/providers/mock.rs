@@ -0,0 +1,317 @@
+//! Deterministic `mock` provider for end-to-end tests (`--features mock`).
+//!
+//! With `default_provider = "mock"`, `api_url` names a JSONL script (relative
+//! paths resolve against the config directory). Each line is one model turn,
+//! consumed in order:
+//!
+//! ```text
+//! {"text": "Listing files."}
+//! {"tool_calls": [{"name": "shell", "arguments": {"command": "ls"}}]}
+//! {"expect": "Cargo.toml", "text": "Found the manifest."}
+//! {"error": "simulated outage"}
+//! ```
+//!
+//! `expect` fails the turn unless the latest message of the request (usually
+//! the tool result) contains the given text, which lets a script assert on
+//! tool output and autonomy decisions. Running past the end of the script is
+//! an error. Without a script, every turn echoes the latest user message.
+
+use super::traits::{ChatMessage, ChatRequest, ChatResponse, Provider, ToolCall};
+use crate::config::GatewayTlsConfig;
+use crate::providers::traits::ProviderCapabilities;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::path::Path;
+
+/// One scripted model turn.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockTurn {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Vec<MockToolCall>,
+    /// Text the request's latest message must contain.
+    #[serde(default)]
+    pub expect: Option<String>,
+    /// Fail the turn with this provider error.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    turns: VecDeque<MockTurn>,
+    served: usize,
+    /// Tool call ids handed out so far, for `mock_call_<n>` ids.
+    calls: usize,
+}
+
+pub struct MockProvider {
+    /// `None` echoes instead of following a script.
+    script: Option<Mutex<Script>>,
+}
+
+impl MockProvider {
+    /// Echo provider without a script.
+    pub fn echo() -> Self {
+        Self { script: None }
+    }
+
+    pub fn with_turns(turns: impl IntoIterator<Item = MockTurn>) -> Self {
+        Self {
+            script: Some(Mutex::new(Script {
+                turns: turns.into_iter().collect(),
+                ..Script::default()
+            })),
+        }
+    }
+
+    /// Parse a JSONL script; blank lines and `#` comments are skipped.
+    pub fn parse_script(raw: &str) -> Result<Vec<MockTurn>> {
+        raw.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid mock script line {}", index + 1))
+            })
+            .collect()
+    }
+
+    /// Build from `api_url`: a script path, or echo mode when unset.
+    pub fn from_api_url(api_url: Option<&str>, config_dir: Option<&Path>) -> Result<Self> {
+        let Some(raw_path) = api_url.map(str::trim).filter(|path| !path.is_empty()) else {
+            return Ok(Self::echo());
+        };
+        let raw_path = raw_path.strip_prefix("file://").unwrap_or(raw_path);
+        let path = GatewayTlsConfig::resolve_path(raw_path, config_dir.unwrap_or(Path::new("")));
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read mock script {}", path.display()))?;
+        Ok(Self::with_turns(Self::parse_script(&raw)?))
+    }
+
+    fn next_response(&self, messages: &[ChatMessage]) -> Result<ChatResponse> {
+        let Some(script) = &self.script else {
+            let last_user = messages
+                .iter()
+                .rfind(|m| m.role == "user")
+                .map_or("", |m| m.content.as_str());
+            return Ok(text_response(format!("echo: {last_user}")));
+        };
+
+        let mut script = script.lock();
+        let Some(turn) = script.turns.pop_front() else {
+            anyhow::bail!("mock script exhausted after {} responses", script.served);
+        };
+        script.served += 1;
+        let turn_number = script.served;
+
+        if let Some(expected) = &turn.expect {
+            let latest = messages.last().map_or("", |m| m.content.as_str());
+            if !latest.contains(expected.as_str()) {
+                anyhow::bail!(
+                    "mock script turn {turn_number} expected the latest message to contain \
+                     {expected:?}, got {latest:?}"
+                );
+            }
+        }
+        if let Some(error) = turn.error {
+            anyhow::bail!("{error}");
+        }
+
+        let tool_calls = turn
+            .tool_calls
+            .into_iter()
+            .map(|call| {
+                script.calls += 1;
+                let arguments = match call.arguments {
+                    serde_json::Value::Null => "{}".to_string(),
+                    arguments => arguments.to_string(),
+                };
+                ToolCall {
+                    id: format!("mock_call_{}", script.calls),
+                    name: call.name,
+                    arguments,
+                }
+            })
+            .collect();
+        Ok(ChatResponse {
+            text: turn.text,
+            tool_calls,
+            usage: None,
+            reasoning_content: None,
+            reasoning: None,
+        })
+    }
+}
+
+fn text_response(text: String) -> ChatResponse {
+    ChatResponse {
+        text: Some(text),
+        tool_calls: Vec::new(),
+        usage: None,
+        reasoning_content: None,
+        reasoning: None,
+    }
+}
+
+/// Text-only rendering of a turn, with tool calls in the prompt-guided
+/// `<tool_call>` format.
+fn render_text(response: ChatResponse) -> String {
+    let mut text = response.text.unwrap_or_default();
+    for call in response.tool_calls {
+        let arguments: serde_json::Value =
+            serde_json::from_str(&call.arguments).unwrap_or_default();
+        let payload = serde_json::json!({ "name": call.name, "arguments": arguments });
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        let _ = write!(text, "<tool_call>\n{payload}\n</tool_call>");
+    }
+    text
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            ..ProviderCapabilities::default()
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system_prompt) = system_prompt {
+            messages.push(ChatMessage::system(system_prompt));
+        }
+        messages.push(ChatMessage::user(message));
+        self.next_response(&messages).map(render_text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<String> {
+        self.next_response(messages).map(render_text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.next_response(request.messages)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[serde_json::Value],
+        _model: &str,
+        _temperature: f64,
+    ) -> Result<ChatResponse> {
+        self.next_response(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn follows_the_script_in_order() {
+        let turns = MockProvider::parse_script(
+            r#"
+# list, then answer from the tool output
+{"tool_calls": [{"name": "shell", "arguments": {"command": "ls"}}]}
+{"expect": "Cargo.toml", "text": "Found the manifest."}
+{"error": "simulated outage"}
+"#,
+        )
+        .unwrap();
+        let provider = MockProvider::with_turns(turns);
+        let request = |messages| ChatRequest {
+            messages,
+            tools: None,
+        };
+
+        let question = [ChatMessage::user("what is here?")];
+        let first = provider.chat(request(&question), "m", 0.0).await.unwrap();
+        assert_eq!(first.tool_calls.len(), 1);
+        assert_eq!(first.tool_calls[0].id, "mock_call_1");
+        assert_eq!(first.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+
+        let mismatch = [ChatMessage::tool_result("mock_call_1", "README.md")];
+        let err = provider
+            .chat(request(&mismatch), "m", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("expected the latest message"));
+
+        let outage = provider
+            .chat_with_history(&[ChatMessage::user("again")], "m", 0.0)
+            .await
+            .unwrap_err();
+        assert_eq!(outage.to_string(), "simulated outage");
+        let exhausted = provider.simple_chat("more", "m", 0.0).await.unwrap_err();
+        assert!(exhausted.to_string().contains("exhausted after 3"));
+    }
+
+    #[tokio::test]
+    async fn echoes_without_a_script_and_renders_prompt_guided_calls() {
+        let echo = MockProvider::from_api_url(None, None).unwrap();
+        assert_eq!(
+            echo.simple_chat("ping", "m", 0.0).await.unwrap(),
+            "echo: ping"
+        );
+
+        let provider = MockProvider::with_turns([MockTurn {
+            text: Some("Checking.".into()),
+            tool_calls: vec![MockToolCall {
+                name: "file_read".into(),
+                arguments: serde_json::json!({"path": "a.txt"}),
+            }],
+            ..MockTurn::default()
+        }]);
+        let text = provider.simple_chat("read a.txt", "m", 0.0).await.unwrap();
+        assert!(text.starts_with("Checking.\n<tool_call>"));
+        assert!(text.contains(r#""name":"file_read""#));
+    }
+
+    #[test]
+    fn script_paths_resolve_against_the_config_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("script.jsonl"), "{\"text\": \"hi\"}\n").unwrap();
+        assert!(MockProvider::from_api_url(Some("script.jsonl"), Some(dir.path())).is_ok());
+        assert!(MockProvider::from_api_url(Some("missing.jsonl"), Some(dir.path())).is_err());
+
+        std::fs::write(dir.path().join("bad.jsonl"), "{\"txt\": \"typo\"}\n").unwrap();
+        let err = MockProvider::from_api_url(Some("bad.jsonl"), Some(dir.path()))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
@@ -9,15 +9,22 @@
 //! To add a new provider, implement [`Provider`] in a new submodule and register it
 //! in [`create_provider_with_url`]. See `AGENTS.md` §7.1 for the full change playbook.
 
+pub mod catalog;
+pub mod middleware;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod multimodal;
 pub mod openai;
+pub mod probe;
 pub mod registry;
+pub mod structured;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, EmbeddingProvider,
+    ChatMessage, ChatRequest, ChatResponse, ContentPart, ConversationMessage, EmbeddingProvider,
     NoopEmbeddingProvider, Provider, ProviderCapabilityError, ProviderErrorKind,
-    ProviderRegistry, ToolCall, ToolResultMessage,
+    ProviderRegistry, ResponseFormat, StreamOptions, ToolCall, ToolResultMessage,
 };
 pub use registry::DefaultProviderRegistry;
 
@@ -25,12 +32,20 @@ use std::path::PathBuf;
 
 const MAX_API_ERROR_CHARS: usize = 200;
 
+/// Shortest token body (after the prefix) treated as a secret; shorter
+/// matches are usually prose or an already-redacted `sk-1*[REDACTED]` stub.
+const MIN_SECRET_TOKEN_CHARS: usize = 4;
+
 #[derive(Debug, Clone)]
 pub struct ProviderRuntimeOptions {
     pub auth_profile_override: Option<String>,
     pub zeroclaw_dir: Option<PathBuf>,
     pub secrets_encrypt: bool,
     pub reasoning_enabled: Option<bool>,
+    /// `[azure_openai]` settings for the `azure-openai` provider.
+    pub azure_openai: crate::config::AzureOpenAiConfig,
+    /// `[provider_middleware]` pipeline wrapped around resilient/routed providers.
+    pub middleware: crate::config::ProviderMiddlewareConfig,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -40,6 +55,8 @@ impl Default for ProviderRuntimeOptions {
             zeroclaw_dir: None,
             secrets_encrypt: true,
             reasoning_enabled: None,
+            azure_openai: crate::config::AzureOpenAiConfig::default(),
+            middleware: crate::config::ProviderMiddlewareConfig::default(),
         }
     }
 }
@@ -63,7 +80,7 @@ fn token_end(input: &str, from: usize) -> usize {
 /// Scrub known secret-like token prefixes from provider error strings.
 ///
 /// Redacts tokens with prefixes like `sk-`, `xoxb-`, `xoxp-`, `ghp_`, `gho_`,
-/// `ghu_`, and `github_pat_`.
+/// `ghu_`, and `github_pat_` when they start a token.
 pub fn scrub_secret_patterns(input: &str) -> String {
     const PREFIXES: [&str; 7] = [
         "sk-",
@@ -88,7 +105,12 @@ pub fn scrub_secret_patterns(input: &str) -> String {
             let content_start = start + prefix.len();
             let end = token_end(&scrubbed, content_start);
 
-            if end == content_start {
+            // Only match at a token boundary so words like `task-list` survive.
+            let inside_word = scrubbed[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_alphanumeric());
+            if end - content_start < MIN_SECRET_TOKEN_CHARS || inside_word {
                 search_from = content_start;
                 continue;
             }
@@ -129,7 +151,10 @@ pub async fn api_error(provider: &str, response: reqwest::Response) -> anyhow::E
 }
 
 /// Resolve API key for a provider from config and environment variables.
-fn resolve_provider_credential(name: &str, credential_override: Option<&str>) -> Option<String> {
+pub(crate) fn resolve_provider_credential(
+    name: &str,
+    credential_override: Option<&str>,
+) -> Option<String> {
     if let Some(raw_override) = credential_override {
         let trimmed_override = raw_override.trim();
         if !trimmed_override.is_empty() {
@@ -139,6 +164,7 @@ fn resolve_provider_credential(name: &str, credential_override: Option<&str>) ->
 
     let provider_env_candidates: Vec<&str> = match name {
         "openai" => vec!["OPENAI_API_KEY"],
+        "azure-openai" => vec!["AZURE_OPENAI_API_KEY"],
         _ => vec![],
     };
 
@@ -192,7 +218,7 @@ fn create_provider_with_url_and_options(
     name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
-    _options: &ProviderRuntimeOptions,
+    options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let resolved_credential = resolve_provider_credential(name, api_key)
         .map(|v| String::from_utf8(v.into_bytes()).unwrap_or_default());
@@ -200,9 +226,30 @@ fn create_provider_with_url_and_options(
     let key = resolved_credential.as_ref().map(String::as_str);
 
     match name {
-        "openai" => Ok(Box::new(openai::OpenAiProvider::with_base_url(api_url, key))),
+        "openai" => Ok(Box::new(
+            openai::OpenAiProvider::with_base_url(api_url, key)
+                .with_reasoning(options.reasoning_enabled),
+        )),
+        "azure-openai" | "azure" => {
+            let endpoint = api_url
+                .map(str::to_string)
+                .or_else(|| std::env::var("AZURE_OPENAI_ENDPOINT").ok());
+            Ok(Box::new(
+                openai::OpenAiProvider::azure(endpoint.as_deref(), key, &options.azure_openai)
+                    .with_reasoning(options.reasoning_enabled),
+            ))
+        }
+        #[cfg(feature = "mock")]
+        "mock" => Ok(Box::new(mock::MockProvider::from_api_url(
+            api_url,
+            options.zeroclaw_dir.as_deref(),
+        )?)),
+        #[cfg(not(feature = "mock"))]
+        "mock" => anyhow::bail!(
+            "The mock provider is for tests and needs a build with `--features mock`."
+        ),
         _ => anyhow::bail!(
-            "Unknown provider: {name}. Only \"openai\" is currently supported."
+            "Unknown provider: {name}. Supported providers: \"openai\", \"azure-openai\"."
         ),
     }
 }
@@ -220,13 +267,17 @@ pub fn create_resilient_provider(
 }
 
 /// Create provider chain with retry/fallback behavior and auth runtime options.
+///
+/// The provider is wrapped in the `[provider_middleware]` pipeline from
+/// `options.middleware` (see [`middleware`]).
 pub fn create_resilient_provider_with_options(
     primary_name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
     options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
-    create_provider_with_url_and_options(primary_name, api_key, api_url, options)
+    let provider = create_provider_with_url_and_options(primary_name, api_key, api_url, options)?;
+    middleware::wrap(provider, &options.middleware)
 }
 
 /// Create a routed or standard provider. Without routing support, this falls
@@ -270,6 +321,19 @@ pub fn list_providers() -> Vec<ProviderInfo> {
             aliases: &[],
             local: false,
         },
+        ProviderInfo {
+            name: "azure-openai",
+            display_name: "Azure OpenAI",
+            aliases: &["azure"],
+            local: false,
+        },
+        #[cfg(feature = "mock")]
+        ProviderInfo {
+            name: "mock",
+            display_name: "Mock (scripted, for tests)",
+            aliases: &[],
+            local: true,
+        },
     ]
 }
 
@@ -392,6 +456,13 @@ mod tests {
         assert_eq!(result, "failed: [REDACTED]");
     }
 
+    #[test]
+    fn scrub_ignores_prefixes_inside_words() {
+        let input = "run task-list, then disk-usage; key sk-live123";
+        let result = scrub_secret_patterns(input);
+        assert_eq!(result, "run task-list, then disk-usage; key [REDACTED]");
+    }
+
     #[test]
     fn resolve_provider_credential_prefers_explicit_argument() {
         let resolved = resolve_provider_credential("openai", Some("  explicit-key  "));
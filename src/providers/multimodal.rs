@@ -0,0 +1,283 @@
+//! Image attachments for vision-capable providers.
+//!
+//! Channels reference attachments inline as `[IMAGE:<source>]`, where the
+//! source is a local file path (typically an entry in the media store), a
+//! `data:` URI, or an `http(s)` URL when `[multimodal].allow_remote_fetch` is
+//! enabled. Before a request is sent, markers in user messages are lifted into
+//! base64 [`ContentPart`]s so the provider receives the image itself instead
+//! of its reference.
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use base64::Engine;
+
+use super::traits::{ChatMessage, ContentPart, ProviderCapabilityError};
+use crate::config::MultimodalConfig;
+use crate::media::{FetchOptions, HttpMediaFetcher, MediaFetcher};
+
+const IMAGE_MARKER_PREFIX: &str = "[IMAGE:";
+const REMOTE_FETCH_TIMEOUT_SECS: u64 = 30;
+
+/// MIME types accepted for image input.
+pub const ALLOWED_IMAGE_MIME_TYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/gif",
+    "image/bmp",
+];
+
+/// Inline marker for an image attachment, e.g. `[IMAGE:/path/to/photo.jpg]`.
+pub fn image_marker(source: &str) -> String {
+    format!("{IMAGE_MARKER_PREFIX}{source}]")
+}
+
+/// Split `[IMAGE:...]` markers out of `content`, returning the remaining text
+/// and the marker sources in order.
+pub fn extract_image_markers(content: &str) -> (String, Vec<String>) {
+    let mut text = String::with_capacity(content.len());
+    let mut sources = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(IMAGE_MARKER_PREFIX) {
+        let after = &rest[start + IMAGE_MARKER_PREFIX.len()..];
+        let Some(end) = after.find(']') else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let source = after[..end].trim();
+        if !source.is_empty() {
+            sources.push(source.to_string());
+        }
+        rest = &after[end + 1..];
+    }
+    text.push_str(rest);
+    (text.trim().to_string(), sources)
+}
+
+fn image_mime_for_path(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "bmp" => Some("image/bmp"),
+        _ => None,
+    }
+}
+
+fn ensure_allowed_mime(mime_type: &str, source: &str) -> anyhow::Result<()> {
+    if ALLOWED_IMAGE_MIME_TYPES.contains(&mime_type) {
+        Ok(())
+    } else {
+        bail!("Unsupported image type '{mime_type}' for attachment {source}")
+    }
+}
+
+fn ensure_within_limit(size: u64, max_bytes: u64, source: &str) -> anyhow::Result<()> {
+    if size > max_bytes {
+        bail!("Image attachment {source} is {size} bytes, exceeding the {max_bytes} byte limit");
+    }
+    Ok(())
+}
+
+/// Resolve a marker source into an inline base64 image part.
+pub async fn load_image(source: &str, config: &MultimodalConfig) -> anyhow::Result<ContentPart> {
+    let max_bytes = config.max_image_size_mb as u64 * 1024 * 1024;
+    let lower = source.to_ascii_lowercase();
+
+    if lower.starts_with("data:") {
+        let (header, data) = source[5..]
+            .split_once(',')
+            .context("Malformed data URI image attachment")?;
+        let Some(mime_type) = header.strip_suffix(";base64") else {
+            bail!("Data URI image attachments must be base64-encoded");
+        };
+        let mime_type = mime_type.to_ascii_lowercase();
+        ensure_allowed_mime(&mime_type, "data URI")?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(data.trim())
+            .context("Invalid base64 in data URI image attachment")?;
+        ensure_within_limit(bytes.len() as u64, max_bytes, "data URI")?;
+        return Ok(ContentPart::image_bytes(mime_type, &bytes));
+    }
+
+    if lower.starts_with("https://") || lower.starts_with("http://") {
+        if !config.allow_remote_fetch {
+            bail!("Remote image URLs are disabled; set [multimodal].allow_remote_fetch = true");
+        }
+        let options = FetchOptions {
+            max_size_bytes: max_bytes,
+            timeout: Duration::from_secs(REMOTE_FETCH_TIMEOUT_SECS),
+            allowed_mime_types: Some(
+                ALLOWED_IMAGE_MIME_TYPES
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            ),
+        };
+        let fetched = HttpMediaFetcher::new()
+            .with_allowed_schemes(&["https", "http"])
+            .fetch(source, &options)
+            .await?;
+        return Ok(ContentPart::image_bytes(fetched.mime_type, &fetched.data));
+    }
+
+    let path = Path::new(source);
+    let size = tokio::fs::metadata(path)
+        .await
+        .with_context(|| format!("Image attachment not found: {source}"))?
+        .len();
+    ensure_within_limit(size, max_bytes, source)?;
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read image attachment {source}"))?;
+    let mime_type = crate::media::http::sniff_mime_type(&bytes)
+        .filter(|mime| mime.starts_with("image/"))
+        .or_else(|| image_mime_for_path(path))
+        .unwrap_or("application/octet-stream");
+    ensure_allowed_mime(mime_type, source)?;
+    Ok(ContentPart::image_bytes(mime_type, &bytes))
+}
+
+/// Lift image markers in user messages into content parts.
+///
+/// Returns the messages unchanged (borrowed) when no images are present.
+/// Fails with a `vision` [`ProviderCapabilityError`] when images are present
+/// but the provider cannot accept them.
+pub async fn prepare_messages<'a>(
+    messages: &'a [ChatMessage],
+    provider_name: &str,
+    supports_vision: bool,
+    config: &MultimodalConfig,
+) -> anyhow::Result<Cow<'a, [ChatMessage]>> {
+    let has_markers = |m: &ChatMessage| m.role == "user" && m.content.contains(IMAGE_MARKER_PREFIX);
+    if !messages.iter().any(|m| has_markers(m) || m.has_images()) {
+        return Ok(Cow::Borrowed(messages));
+    }
+    if !supports_vision {
+        return Err(ProviderCapabilityError {
+            provider: provider_name.to_string(),
+            capability: "vision".to_string(),
+            message: "this provider does not accept image input; use a vision-capable model"
+                .to_string(),
+        }
+        .into());
+    }
+
+    let mut prepared = messages.to_vec();
+    let mut image_count = prepared.iter().map(|m| m.parts.len()).sum::<usize>();
+    for message in prepared.iter_mut().filter(|m| has_markers(m)) {
+        let (text, sources) = extract_image_markers(&message.content);
+        image_count += sources.len();
+        if image_count > config.max_images {
+            bail!(
+                "Too many images in this conversation (limit {}, see [multimodal].max_images)",
+                config.max_images
+            );
+        }
+        for source in sources {
+            message.parts.push(load_image(&source, config).await?);
+        }
+        message.content = text;
+    }
+    Ok(Cow::Owned(prepared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn extracts_markers_and_keeps_text() {
+        let (text, sources) = extract_image_markers(
+            "What is this? [IMAGE:https://example.com/cat.png] and [IMAGE:/tmp/a.jpg]",
+        );
+        assert_eq!(text, "What is this?  and");
+        assert_eq!(sources, vec!["https://example.com/cat.png", "/tmp/a.jpg"]);
+
+        let (text, sources) = extract_image_markers("no images [IMAGE: unterminated");
+        assert_eq!(text, "no images [IMAGE: unterminated");
+        assert!(sources.is_empty());
+    }
+
+    #[tokio::test]
+    async fn local_and_data_uri_images_are_inlined() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("photo.png");
+        std::fs::write(&path, b"\x89PNG").unwrap();
+        let config = MultimodalConfig::default();
+
+        let part = load_image(path.to_str().unwrap(), &config).await.unwrap();
+        assert_eq!(
+            part,
+            ContentPart::ImageData {
+                mime_type: "image/png".into(),
+                data: "iVBORw==".into(),
+            }
+        );
+        assert_eq!(part.image_url(), "data:image/png;base64,iVBORw==");
+
+        let part = load_image("data:image/png;base64,iVBORw==", &config)
+            .await
+            .unwrap();
+        assert_eq!(part.image_url(), "data:image/png;base64,iVBORw==");
+
+        let notes = tmp.path().join("notes.txt");
+        std::fs::write(&notes, "hello").unwrap();
+        assert!(load_image(notes.to_str().unwrap(), &config).await.is_err());
+        let err = load_image("https://example.com/cat.png", &config)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("allow_remote_fetch"));
+    }
+
+    #[tokio::test]
+    async fn prepare_lifts_markers_only_for_vision_providers() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("cat.gif");
+        std::fs::write(&path, b"GIF89a").unwrap();
+        let marker = image_marker(path.to_str().unwrap());
+        let messages = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user(format!("Describe {marker}")),
+        ];
+        let config = MultimodalConfig::default();
+
+        let prepared = prepare_messages(&messages, "openai", true, &config)
+            .await
+            .unwrap();
+        assert_eq!(prepared[1].content, "Describe");
+        assert_eq!(prepared[1].parts.len(), 1);
+        assert!(prepared[1].parts[0]
+            .image_url()
+            .starts_with("data:image/gif;base64,"));
+
+        let err = prepare_messages(&messages, "ollama", false, &config)
+            .await
+            .unwrap_err();
+        let capability = err.downcast_ref::<ProviderCapabilityError>().unwrap();
+        assert_eq!(capability.capability, "vision");
+
+        let limited = MultimodalConfig {
+            max_images: 1,
+            ..MultimodalConfig::default()
+        };
+        let two = vec![ChatMessage::user(format!("{marker} {marker}"))];
+        assert!(prepare_messages(&two, "openai", true, &limited)
+            .await
+            .is_err());
+
+        let plain = vec![ChatMessage::user("hello")];
+        assert!(matches!(
+            prepare_messages(&plain, "ollama", false, &config)
+                .await
+                .unwrap(),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -1,15 +1,33 @@
+use crate::config::AzureOpenAiConfig;
 use crate::providers::traits::{
-    ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    split_think_blocks, with_system_instructions, ChatMessage, ChatRequest as ProviderChatRequest,
+    ChatResponse as ProviderChatResponse, Provider, ResponseFormat, TokenUsage,
+    ToolCall as ProviderToolCall,
 };
-use crate::tools::ToolSpec;
+use crate::tools::{CleaningStrategy, SchemaCleanr, ToolSpec};
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
 
 pub struct OpenAiProvider {
     base_url: String,
     credential: Option<String>,
+    azure: Option<AzureRouting>,
+    /// `runtime.reasoning_enabled`: `None` keeps provider defaults.
+    reasoning_enabled: Option<bool>,
+}
+
+/// Azure OpenAI addressing: requests go to
+/// `{endpoint}/openai/deployments/{deployment}/...?api-version=...` and
+/// authenticate with an `api-key` header instead of a bearer token.
+#[derive(Debug, Clone)]
+struct AzureRouting {
+    api_version: String,
+    /// Model name -> deployment name; unmapped models use the model name.
+    deployments: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,6 +35,8 @@ struct ChatRequest {
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,13 +82,17 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
 struct NativeMessage {
     role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<NativeContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -79,6 +103,38 @@ struct NativeMessage {
     reasoning_content: Option<String>,
 }
 
+/// Message content: plain text, or text plus image parts for vision models.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum NativeContent {
+    Text(String),
+    Parts(Vec<NativeContentPart>),
+}
+
+impl From<String> for NativeContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for NativeContent {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeContentPart {
+    Text { text: String },
+    ImageUrl { image_url: NativeImageUrl },
+}
+
+#[derive(Debug, Serialize)]
+struct NativeImageUrl {
+    url: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct NativeToolSpec {
     #[serde(rename = "type")]
@@ -173,12 +229,140 @@ impl OpenAiProvider {
         Self {
             base_url: base_url
                 .map(|u| u.trim_end_matches('/').to_string())
-                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            credential: credential.map(ToString::to_string),
+            azure: None,
+            reasoning_enabled: None,
+        }
+    }
+
+    /// Create an Azure OpenAI provider for a resource endpoint such as
+    /// `https://my-resource.openai.azure.com`.
+    pub fn azure(
+        endpoint: Option<&str>,
+        credential: Option<&str>,
+        config: &AzureOpenAiConfig,
+    ) -> Self {
+        Self {
+            base_url: endpoint
+                .unwrap_or_default()
+                .trim()
+                .trim_end_matches('/')
+                .to_string(),
             credential: credential.map(ToString::to_string),
+            azure: Some(AzureRouting {
+                api_version: config.api_version.clone(),
+                deployments: config.deployments.clone(),
+            }),
+            reasoning_enabled: None,
+        }
+    }
+
+    /// Apply `runtime.reasoning_enabled`: request high/low `reasoning_effort`
+    /// from reasoning models and, when `false`, drop reasoning traces from
+    /// responses.
+    pub fn with_reasoning(mut self, reasoning_enabled: Option<bool>) -> Self {
+        self.reasoning_enabled = reasoning_enabled;
+        self
+    }
+
+    /// `reasoning_effort` request value; only o-series and GPT-5 models accept it.
+    fn reasoning_effort(&self, model: &str) -> Option<&'static str> {
+        let enabled = self.reasoning_enabled?;
+        let model = model.rsplit('/').next().unwrap_or(model);
+        let is_reasoning_model = model.starts_with("gpt-5")
+            || (model.starts_with('o') && model[1..].starts_with(|c: char| c.is_ascii_digit()));
+        is_reasoning_model.then_some(if enabled { "high" } else { "low" })
+    }
+
+    /// Split `<think>` blocks out of the reply and honor `reasoning_enabled = false`.
+    fn finish_response(&self, mut response: ProviderChatResponse) -> ProviderChatResponse {
+        if let Some(text) = response.text.take() {
+            let (visible, thought) = split_think_blocks(&text);
+            response.text = Some(visible);
+            response.reasoning = response.reasoning.or(thought);
+        }
+        if self.reasoning_enabled == Some(false) {
+            response.reasoning = None;
+        }
+        response
+    }
+
+    fn display_name(&self) -> &'static str {
+        if self.azure.is_some() {
+            "Azure OpenAI"
+        } else {
+            "OpenAI"
+        }
+    }
+
+    fn require_credential(&self) -> anyhow::Result<&str> {
+        if self.azure.is_some() && self.base_url.is_empty() {
+            anyhow::bail!(
+                "Azure OpenAI endpoint not set. Set api_url (https://<resource>.openai.azure.com) or AZURE_OPENAI_ENDPOINT."
+            );
+        }
+        self.credential.as_deref().ok_or_else(|| {
+            if self.azure.is_some() {
+                anyhow::anyhow!(
+                    "Azure OpenAI API key not set. Set AZURE_OPENAI_API_KEY or edit config.toml."
+                )
+            } else {
+                anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+            }
+        })
+    }
+
+    fn chat_completions_url(&self, model: &str) -> String {
+        match &self.azure {
+            Some(azure) => {
+                let deployment = azure.deployments.get(model).map_or(model, String::as_str);
+                format!(
+                    "{}/openai/deployments/{deployment}/chat/completions",
+                    self.base_url
+                )
+            }
+            None => format!("{}/chat/completions", self.base_url),
+        }
+    }
+
+    fn models_url(&self) -> String {
+        if self.azure.is_some() {
+            format!("{}/openai/models", self.base_url)
+        } else {
+            format!("{}/models", self.base_url)
+        }
+    }
+
+    /// Attach auth (and, for Azure, the `api-version` query parameter).
+    fn authorize(&self, request: RequestBuilder, credential: &str) -> RequestBuilder {
+        match &self.azure {
+            Some(azure) => request
+                .query(&[("api-version", azure.api_version.as_str())])
+                .header("api-key", credential),
+            None => request.header("Authorization", format!("Bearer {credential}")),
+        }
+    }
+
+    /// OpenAI and Azure accept full JSON Schema; other OpenAI-compatible
+    /// servers (Ollama, llama.cpp, vLLM, ...) get strict-mode schemas so
+    /// plugin tools are not rejected.
+    fn schema_strategy(&self) -> CleaningStrategy {
+        if self.azure.is_some() || self.base_url == DEFAULT_BASE_URL {
+            CleaningStrategy::OpenAI
+        } else {
+            CleaningStrategy::Strict
+        }
+    }
+
+    fn clean_parameters(&self, parameters: serde_json::Value) -> serde_json::Value {
+        match self.schema_strategy() {
+            CleaningStrategy::OpenAI => parameters,
+            strategy => SchemaCleanr::clean(parameters, strategy),
         }
     }
 
-    fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
+    fn convert_tools(&self, tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
         tools.map(|items| {
             items
                 .iter()
@@ -187,13 +371,34 @@ impl OpenAiProvider {
                     function: NativeToolFunctionSpec {
                         name: tool.name.clone(),
                         description: tool.description.clone(),
-                        parameters: tool.parameters.clone(),
+                        parameters: self.clean_parameters(tool.parameters.clone()),
                     },
                 })
                 .collect()
         })
     }
 
+    /// Vision payload: a text part followed by `image_url` parts.
+    fn convert_content(message: &ChatMessage) -> NativeContent {
+        if message.parts.is_empty() {
+            return NativeContent::Text(message.content.clone());
+        }
+        let mut parts = Vec::with_capacity(message.parts.len() + 1);
+        if !message.content.is_empty() {
+            parts.push(NativeContentPart::Text {
+                text: message.content.clone(),
+            });
+        }
+        for part in &message.parts {
+            parts.push(NativeContentPart::ImageUrl {
+                image_url: NativeImageUrl {
+                    url: part.image_url(),
+                },
+            });
+        }
+        NativeContent::Parts(parts)
+    }
+
     fn convert_messages(messages: &[ChatMessage]) -> Vec<NativeMessage> {
         messages
             .iter()
@@ -220,7 +425,7 @@ impl OpenAiProvider {
                                 let content = value
                                     .get("content")
                                     .and_then(serde_json::Value::as_str)
-                                    .map(ToString::to_string);
+                                    .map(NativeContent::from);
                                 let reasoning_content = value
                                     .get("reasoning_content")
                                     .and_then(serde_json::Value::as_str)
@@ -243,10 +448,10 @@ impl OpenAiProvider {
                             .get("tool_call_id")
                             .and_then(serde_json::Value::as_str)
                             .map(ToString::to_string);
-                        let content = value
-                            .get("content")
-                            .and_then(serde_json::Value::as_str)
-                            .map(ToString::to_string);
+                        let content = value.get("content").map(|content| match content {
+                            serde_json::Value::String(text) => NativeContent::from(text.as_str()),
+                            other => NativeContent::from(other.to_string()),
+                        });
                         return NativeMessage {
                             role: "tool".to_string(),
                             content,
@@ -259,7 +464,7 @@ impl OpenAiProvider {
 
                 NativeMessage {
                     role: m.role.clone(),
-                    content: Some(m.content.clone()),
+                    content: Some(Self::convert_content(m)),
                     tool_call_id: None,
                     tool_calls: None,
                     reasoning_content: None,
@@ -268,9 +473,72 @@ impl OpenAiProvider {
             .collect()
     }
 
+    /// Wire messages for a request: [`Self::convert_messages`] followed by
+    /// [`Self::repair_tool_sequence`].
+    fn native_messages(messages: &[ChatMessage]) -> Vec<NativeMessage> {
+        Self::repair_tool_sequence(Self::convert_messages(messages))
+    }
+
+    /// Enforce the Chat Completions tool-call invariants, which the API
+    /// rejects requests for violating: a `tool` message must answer a call of
+    /// the preceding assistant `tool_calls` message, and every call must be
+    /// answered before the conversation moves on. Orphaned results (e.g.
+    /// after history trimming) become user messages; calls left unanswered
+    /// (e.g. by a cancelled turn) get a placeholder result.
+    fn repair_tool_sequence(messages: Vec<NativeMessage>) -> Vec<NativeMessage> {
+        let mut repaired = Vec::with_capacity(messages.len());
+        let mut pending: Vec<String> = Vec::new();
+        for message in messages {
+            if message.role == "tool" {
+                let answered = message
+                    .tool_call_id
+                    .as_ref()
+                    .and_then(|id| pending.iter().position(|p| p == id));
+                if let Some(index) = answered {
+                    pending.remove(index);
+                    repaired.push(message);
+                } else {
+                    let text = match message.content {
+                        Some(NativeContent::Text(text)) => text,
+                        _ => String::new(),
+                    };
+                    repaired.push(NativeMessage {
+                        role: "user".to_string(),
+                        content: Some(NativeContent::Text(format!("[Tool result]\n{text}"))),
+                        tool_call_id: None,
+                        tool_calls: None,
+                        reasoning_content: None,
+                    });
+                }
+                continue;
+            }
+
+            for id in pending.drain(..) {
+                repaired.push(NativeMessage {
+                    role: "tool".to_string(),
+                    content: Some(NativeContent::from(
+                        "(no result: the tool call was not completed)",
+                    )),
+                    tool_call_id: Some(id),
+                    tool_calls: None,
+                    reasoning_content: None,
+                });
+            }
+            if let Some(calls) = &message.tool_calls {
+                pending = calls.iter().filter_map(|call| call.id.clone()).collect();
+            }
+            repaired.push(message);
+        }
+        repaired
+    }
+
     fn parse_native_response(message: NativeResponseMessage) -> ProviderChatResponse {
         let text = message.effective_content();
         let reasoning_content = message.reasoning_content.clone();
+        let reasoning = message
+            .reasoning_content
+            .clone()
+            .filter(|r| !r.trim().is_empty());
         let tool_calls = message
             .tool_calls
             .unwrap_or_default()
@@ -287,6 +555,57 @@ impl OpenAiProvider {
             tool_calls,
             usage: None,
             reasoning_content,
+            reasoning,
+        }
+    }
+
+    async fn send_native(
+        &self,
+        credential: &str,
+        model: &str,
+        native_request: &NativeChatRequest,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let response = self
+            .authorize(
+                self.http_client().post(self.chat_completions_url(model)),
+                credential,
+            )
+            .json(native_request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error(self.display_name(), response).await);
+        }
+
+        let native_response: NativeChatResponse = response.json().await?;
+        let usage = native_response.usage.map(|u| TokenUsage {
+            input_tokens: u.prompt_tokens,
+            output_tokens: u.completion_tokens,
+        });
+        let message = native_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .ok_or_else(|| anyhow::anyhow!("No response from {}", self.display_name()))?;
+        let mut result = Self::parse_native_response(message);
+        result.usage = usage;
+        Ok(self.finish_response(result))
+    }
+
+    /// `response_format` request body for [`ResponseFormat`].
+    fn native_response_format(format: &ResponseFormat) -> serde_json::Value {
+        match format {
+            ResponseFormat::JsonObject => serde_json::json!({"type": "json_object"}),
+            ResponseFormat::JsonSchema {
+                name,
+                schema,
+                strict,
+            } => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {"name": name, "schema": schema, "strict": strict},
+            }),
         }
     }
 
@@ -304,9 +623,7 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
+        let credential = self.require_credential()?;
 
         let mut messages = Vec::new();
 
@@ -326,18 +643,20 @@ impl Provider for OpenAiProvider {
             model: model.to_string(),
             messages,
             temperature,
+            reasoning_effort: self.reasoning_effort(model),
         };
 
         let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
+            .authorize(
+                self.http_client().post(self.chat_completions_url(model)),
+                credential,
+            )
             .json(&request)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
+            return Err(super::api_error(self.display_name(), response).await);
         }
 
         let chat_response: ChatResponse = response.json().await?;
@@ -346,8 +665,8 @@ impl Provider for OpenAiProvider {
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message.effective_content())
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+            .map(|c| split_think_blocks(&c.message.effective_content()).0)
+            .ok_or_else(|| anyhow::anyhow!("No response from {}", self.display_name()))
     }
 
     async fn chat(
@@ -356,51 +675,55 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
+        let credential = self.require_credential()?;
 
-        let tools = Self::convert_tools(request.tools);
+        let tools = self.convert_tools(request.tools);
         let native_request = NativeChatRequest {
             model: model.to_string(),
-            messages: Self::convert_messages(request.messages),
+            messages: Self::native_messages(request.messages),
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            reasoning_effort: self.reasoning_effort(model),
+            response_format: None,
         };
 
-        let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
-            .json(&native_request)
-            .send()
-            .await?;
+        self.send_native(credential, model, &native_request).await
+    }
 
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
-        }
+    async fn chat_with_response_format(
+        &self,
+        messages: &[ChatMessage],
+        format: &ResponseFormat,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let credential = self.require_credential()?;
 
-        let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
-        let message = native_response
-            .choices
-            .into_iter()
-            .next()
-            .map(|c| c.message)
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        let mut result = Self::parse_native_response(message);
-        result.usage = usage;
-        Ok(result)
+        // JSON mode requires the word "JSON" somewhere in the prompt.
+        let messages = with_system_instructions(messages, &format.instructions());
+        let native_request = NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::native_messages(&messages),
+            temperature,
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: self.reasoning_effort(model),
+            response_format: Some(Self::native_response_format(format)),
+        };
+
+        let response = self.send_native(credential, model, &native_request).await?;
+        Ok(response.text.unwrap_or_default())
     }
 
     fn supports_native_tools(&self) -> bool {
         true
     }
 
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -408,9 +731,7 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
+        let credential = self.require_credential()?;
 
         let native_tools: Option<Vec<NativeToolSpec>> = if tools.is_empty() {
             None
@@ -419,52 +740,31 @@ impl Provider for OpenAiProvider {
                 tools
                     .iter()
                     .cloned()
-                    .map(parse_native_tool_spec)
+                    .map(|tool| {
+                        let mut spec = parse_native_tool_spec(tool)?;
+                        spec.function.parameters = self.clean_parameters(spec.function.parameters);
+                        Ok::<_, anyhow::Error>(spec)
+                    })
                     .collect::<Result<Vec<_>, _>>()?,
             )
         };
 
         let native_request = NativeChatRequest {
             model: model.to_string(),
-            messages: Self::convert_messages(messages),
+            messages: Self::native_messages(messages),
             temperature,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            reasoning_effort: self.reasoning_effort(model),
+            response_format: None,
         };
 
-        let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
-            .json(&native_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
-        }
-
-        let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
-        let message = native_response
-            .choices
-            .into_iter()
-            .next()
-            .map(|c| c.message)
-            .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        let mut result = Self::parse_native_response(message);
-        result.usage = usage;
-        Ok(result)
+        self.send_native(credential, model, &native_request).await
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
-        if let Some(credential) = self.credential.as_ref() {
-            self.http_client()
-                .get(format!("{}/models", self.base_url))
-                .header("Authorization", format!("Bearer {credential}"))
+        if let Some(credential) = self.credential.as_deref() {
+            self.authorize(self.http_client().get(self.models_url()), credential)
                 .send()
                 .await?
                 .error_for_status()?;
@@ -527,6 +827,7 @@ mod tests {
                 },
             ],
             temperature: 0.7,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("\"role\":\"system\""));
@@ -543,6 +844,7 @@ mod tests {
                 content: "hello".to_string(),
             }],
             temperature: 0.0,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(!json.contains("system"));
@@ -751,6 +1053,38 @@ mod tests {
         assert_eq!(parsed.tool_calls.len(), 1);
     }
 
+    #[test]
+    fn finish_response_splits_think_blocks_and_honors_disabled_reasoning() {
+        let json = r#"{"content":"<think>check units</think>42 meters"}"#;
+        let message: NativeResponseMessage = serde_json::from_str(json).unwrap();
+
+        let provider = OpenAiProvider::new(Some("key"));
+        let parsed = provider.finish_response(OpenAiProvider::parse_native_response(message));
+        assert_eq!(parsed.text.as_deref(), Some("42 meters"));
+        assert_eq!(parsed.reasoning.as_deref(), Some("check units"));
+
+        let message: NativeResponseMessage = serde_json::from_str(json).unwrap();
+        let provider = OpenAiProvider::new(Some("key")).with_reasoning(Some(false));
+        let parsed = provider.finish_response(OpenAiProvider::parse_native_response(message));
+        assert_eq!(parsed.text.as_deref(), Some("42 meters"));
+        assert!(parsed.reasoning.is_none());
+    }
+
+    #[test]
+    fn reasoning_effort_only_sent_to_reasoning_models_when_configured() {
+        let default = OpenAiProvider::new(Some("key"));
+        assert_eq!(default.reasoning_effort("o3-mini"), None);
+
+        let enabled = OpenAiProvider::new(Some("key")).with_reasoning(Some(true));
+        assert_eq!(enabled.reasoning_effort("o3-mini"), Some("high"));
+        assert_eq!(enabled.reasoning_effort("openai/gpt-5"), Some("high"));
+        assert_eq!(enabled.reasoning_effort("gpt-4o"), None);
+        assert_eq!(enabled.reasoning_effort("ollama"), None);
+
+        let disabled = OpenAiProvider::new(Some("key")).with_reasoning(Some(false));
+        assert_eq!(disabled.reasoning_effort("o1"), Some("low"));
+    }
+
     #[test]
     fn parse_native_response_none_reasoning_content_for_normal_model() {
         let json = r#"{"choices":[{"message":{"content":"hello"}}]}"#;
@@ -802,11 +1136,76 @@ mod tests {
         assert!(native[0].reasoning_content.is_none());
     }
 
+    #[test]
+    fn convert_messages_sends_tool_calls_and_tool_results() {
+        let call = ProviderToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: r#"{"command":"ls"}"#.into(),
+        };
+        let messages = vec![
+            ChatMessage::user("list files"),
+            ChatMessage::assistant_tool_calls("", &[call], None),
+            ChatMessage::tool_result("call_1", "Cargo.toml\nsrc"),
+            ChatMessage::assistant("Two entries."),
+        ];
+        let json = serde_json::to_value(OpenAiProvider::native_messages(&messages)).unwrap();
+
+        assert_eq!(
+            json[1],
+            serde_json::json!({
+                "role": "assistant",
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": {"name": "shell", "arguments": "{\"command\":\"ls\"}"},
+                }],
+            })
+        );
+        assert_eq!(
+            json[2],
+            serde_json::json!({
+                "role": "tool",
+                "content": "Cargo.toml\nsrc",
+                "tool_call_id": "call_1",
+            })
+        );
+        assert_eq!(json.as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn convert_messages_repairs_orphaned_and_unanswered_tool_messages() {
+        let call = |id: &str| ProviderToolCall {
+            id: id.into(),
+            name: "shell".into(),
+            arguments: "{}".into(),
+        };
+        let messages = vec![
+            // Result whose assistant turn was trimmed away.
+            ChatMessage::tool_result("call_0", "stale output"),
+            ChatMessage::user("try again"),
+            ChatMessage::assistant_tool_calls("Checking", &[call("call_1"), call("call_2")], None),
+            ChatMessage::tool_result("call_2", "done"),
+            // call_1 was never answered (cancelled turn).
+            ChatMessage::user("never mind"),
+        ];
+        let native = OpenAiProvider::native_messages(&messages);
+        let roles: Vec<&str> = native.iter().map(|m| m.role.as_str()).collect();
+
+        assert_eq!(roles, ["user", "user", "assistant", "tool", "tool", "user"]);
+        assert!(matches!(
+            &native[0].content,
+            Some(NativeContent::Text(text)) if text == "[Tool result]\nstale output"
+        ));
+        assert_eq!(native[3].tool_call_id.as_deref(), Some("call_2"));
+        assert_eq!(native[4].tool_call_id.as_deref(), Some("call_1"));
+    }
+
     #[test]
     fn native_message_omits_reasoning_content_when_none() {
         let msg = NativeMessage {
             role: "assistant".to_string(),
-            content: Some("hi".to_string()),
+            content: Some("hi".into()),
             tool_call_id: None,
             tool_calls: None,
             reasoning_content: None,
@@ -819,7 +1218,7 @@ mod tests {
     fn native_message_includes_reasoning_content_when_some() {
         let msg = NativeMessage {
             role: "assistant".to_string(),
-            content: Some("hi".to_string()),
+            content: Some("hi".into()),
             tool_call_id: None,
             tool_calls: None,
             reasoning_content: Some("thinking...".to_string()),
@@ -828,4 +1227,133 @@ mod tests {
         assert!(json.contains("reasoning_content"));
         assert!(json.contains("thinking..."));
     }
+
+    #[test]
+    fn convert_messages_formats_image_parts_for_vision() {
+        use crate::providers::ContentPart;
+
+        let messages = vec![
+            ChatMessage::user("What is in this photo?").with_parts(vec![
+                ContentPart::ImageUrl {
+                    url: "https://example.com/cat.png".into(),
+                },
+                ContentPart::image_bytes("image/png", b"\x89PNG"),
+            ]),
+            ChatMessage::assistant("A cat."),
+        ];
+        let native = OpenAiProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&native).unwrap();
+
+        assert_eq!(
+            json[0]["content"],
+            serde_json::json!([
+                {"type": "text", "text": "What is in this photo?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw=="}},
+            ])
+        );
+        assert_eq!(json[1]["content"], "A cat.");
+    }
+
+    #[tokio::test]
+    async fn azure_posts_to_mapped_deployment_with_api_version_and_api_key() {
+        use wiremock::matchers::{header, method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/openai/deployments/prod-gpt4o/chat/completions"))
+            .and(query_param("api-version", "2024-10-21"))
+            .and(header("api-key", "azure-test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "hi from azure"}}]
+            })))
+            .mount(&server)
+            .await;
+
+        let config = AzureOpenAiConfig {
+            deployments: HashMap::from([("gpt-4o".to_string(), "prod-gpt4o".to_string())]),
+            ..AzureOpenAiConfig::default()
+        };
+        let provider = OpenAiProvider::azure(Some(&server.uri()), Some("azure-test-key"), &config);
+        let reply = provider
+            .chat_with_system(None, "hello", "gpt-4o", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "hi from azure");
+    }
+
+    #[test]
+    fn convert_tools_uses_strict_schemas_for_custom_endpoints() {
+        let tool = ToolSpec {
+            name: "fetch".into(),
+            description: "Fetch a target".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "target": {"anyOf": [{"type": "string"}, {"type": "integer"}]}
+                },
+                "required": ["target", "ghost"]
+            }),
+        };
+
+        let openai = OpenAiProvider::new(Some("key"));
+        let native = openai
+            .convert_tools(Some(std::slice::from_ref(&tool)))
+            .unwrap();
+        assert_eq!(native[0].function.parameters, tool.parameters);
+
+        let local = OpenAiProvider::with_base_url(Some("http://127.0.0.1:11434/v1"), None);
+        let native = local
+            .convert_tools(Some(std::slice::from_ref(&tool)))
+            .unwrap();
+        let parameters = &native[0].function.parameters;
+        assert_eq!(parameters["properties"]["target"]["type"], "string");
+        assert!(parameters["properties"]["target"].get("anyOf").is_none());
+        assert_eq!(parameters["required"], serde_json::json!(["target"]));
+    }
+
+    #[tokio::test]
+    async fn chat_with_response_format_sends_json_schema() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "response_format": {
+                    "type": "json_schema",
+                    "json_schema": {"name": "answer", "strict": true}
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "{\"answer\": 4}"}}]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiProvider::with_base_url(Some(&server.uri()), Some("key"));
+        let format = ResponseFormat::JsonSchema {
+            name: "answer".into(),
+            schema: serde_json::json!({"type": "object"}),
+            strict: true,
+        };
+        let reply = provider
+            .chat_with_response_format(&[ChatMessage::user("2+2?")], &format, "gpt-4o", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "{\"answer\": 4}");
+    }
+
+    #[tokio::test]
+    async fn azure_without_endpoint_fails_before_sending() {
+        let provider =
+            OpenAiProvider::azure(None, Some("azure-test-key"), &AzureOpenAiConfig::default());
+        let err = provider
+            .chat_with_system(None, "hello", "gpt-4o", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("AZURE_OPENAI_ENDPOINT"));
+    }
 }
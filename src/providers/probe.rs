@@ -0,0 +1,500 @@
+//! Provider smoke tests: `zeroclaw providers test`.
+//!
+//! Sends a minimal prompt to every configured provider/model pair (the
+//! default, plus each `[[agents]]` override) and reports round-trip latency,
+//! time to first streamed token, whether the model answered a native tool
+//! call, and an estimated cost from the catalog pricing where the provider
+//! publishes it. Meant for checking credentials and comparing backends
+//! before committing to a default.
+
+use super::catalog::{self, ModelPricing};
+use super::traits::{ChatMessage, ChatRequest, Provider, StreamOptions, TokenUsage};
+use crate::config::Config;
+use crate::tools::ToolSpec;
+use anyhow::Result;
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+const PROBE_PROMPT: &str = "Reply with the single word: pong";
+const TOOL_PROBE_PROMPT: &str = "Call the ping tool now.";
+const TOOL_PROBE_NAME: &str = "ping";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+const DEFAULT_PROVIDER: &str = "openai";
+const DEFAULT_MODEL: &str = "gpt-4o";
+
+/// One provider/model pair to test and where it comes from in config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeTarget {
+    pub provider: String,
+    pub model: String,
+    /// `default` or `agent:<id>`.
+    pub source: String,
+}
+
+/// Result of testing one target. `latency_ms` is `None` when the request
+/// failed; `error` then carries the reason.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeReport {
+    pub provider: String,
+    pub model: String,
+    pub source: String,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+    pub streaming: bool,
+    pub first_token_ms: Option<u64>,
+    pub tool_calling: bool,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub cost_usd: Option<f64>,
+}
+
+impl ProbeReport {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The provider/model pairs `config` uses, deduplicated. `provider` keeps
+/// only targets of that provider (or tests it with `model`, the default
+/// model otherwise, when nothing in config uses it); `model` overrides the
+/// model of every target.
+pub fn configured_targets(
+    config: &Config,
+    provider: Option<&str>,
+    model: Option<&str>,
+) -> Vec<ProbeTarget> {
+    let default_provider = config
+        .default_provider
+        .as_deref()
+        .unwrap_or(DEFAULT_PROVIDER);
+    let default_model = config.default_model.as_deref().unwrap_or(DEFAULT_MODEL);
+
+    let mut candidates = vec![ProbeTarget {
+        provider: default_provider.to_string(),
+        model: default_model.to_string(),
+        source: "default".into(),
+    }];
+    for agent in &config.agents {
+        candidates.push(ProbeTarget {
+            provider: agent
+                .provider
+                .clone()
+                .unwrap_or_else(|| default_provider.to_string()),
+            model: agent
+                .model
+                .clone()
+                .unwrap_or_else(|| default_model.to_string()),
+            source: format!("agent:{}", agent.id.trim()),
+        });
+    }
+
+    let mut targets: Vec<ProbeTarget> = Vec::new();
+    for mut candidate in candidates {
+        if let Some(model) = model {
+            candidate.model = model.to_string();
+        }
+        if provider.is_some_and(|p| !p.trim().eq_ignore_ascii_case(&candidate.provider)) {
+            continue;
+        }
+        if !targets
+            .iter()
+            .any(|t| t.provider == candidate.provider && t.model == candidate.model)
+        {
+            targets.push(candidate);
+        }
+    }
+    if targets.is_empty() {
+        if let Some(provider) = provider {
+            targets.push(ProbeTarget {
+                provider: provider.trim().to_string(),
+                model: model.unwrap_or(default_model).to_string(),
+                source: "--provider".into(),
+            });
+        }
+    }
+    targets
+}
+
+/// USD cost of `usage` at `pricing`, when both token counts are known.
+pub fn estimate_cost(usage: &TokenUsage, pricing: &ModelPricing) -> Option<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    let cost = usage.input_tokens? as f64 * pricing.prompt / TOKENS_PER_PRICE_UNIT
+        + usage.output_tokens? as f64 * pricing.completion / TOKENS_PER_PRICE_UNIT;
+    Some(cost)
+}
+
+fn elapsed_ms(start: Instant) -> u64 {
+    u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+fn ping_tool() -> ToolSpec {
+    ToolSpec {
+        name: TOOL_PROBE_NAME.into(),
+        description: "Connectivity check. Takes no arguments.".into(),
+        parameters: serde_json::json!({"type": "object", "properties": {}}),
+    }
+}
+
+/// Test one target with an already-built provider.
+pub async fn probe(
+    provider: &dyn Provider,
+    target: &ProbeTarget,
+    pricing: Option<&ModelPricing>,
+) -> ProbeReport {
+    let mut report = ProbeReport {
+        provider: target.provider.clone(),
+        model: target.model.clone(),
+        source: target.source.clone(),
+        latency_ms: None,
+        error: None,
+        streaming: provider.supports_streaming(),
+        first_token_ms: None,
+        tool_calling: false,
+        input_tokens: None,
+        output_tokens: None,
+        cost_usd: None,
+    };
+
+    let messages = [ChatMessage::user(PROBE_PROMPT)];
+    let start = Instant::now();
+    let response = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        provider.chat(
+            ChatRequest {
+                messages: &messages,
+                tools: None,
+            },
+            &target.model,
+            0.0,
+        ),
+    )
+    .await;
+    let response = match response {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            report.error = Some(format!("{e:#}"));
+            return report;
+        }
+        Err(_) => {
+            report.error = Some(format!("timed out after {}s", PROBE_TIMEOUT.as_secs()));
+            return report;
+        }
+    };
+    report.latency_ms = Some(elapsed_ms(start));
+    if let Some(usage) = &response.usage {
+        report.input_tokens = usage.input_tokens;
+        report.output_tokens = usage.output_tokens;
+        report.cost_usd = pricing.and_then(|pricing| estimate_cost(usage, pricing));
+    }
+
+    if report.streaming {
+        let start = Instant::now();
+        let mut stream = provider.stream_chat_with_system(
+            None,
+            PROBE_PROMPT,
+            &target.model,
+            0.0,
+            StreamOptions::new(true),
+        );
+        let first = tokio::time::timeout(PROBE_TIMEOUT, async {
+            while let Some(chunk) = stream.next().await {
+                match chunk {
+                    Ok(chunk) if !chunk.is_final && !chunk.delta.is_empty() => return true,
+                    Ok(chunk) if chunk.is_final => return false,
+                    Ok(_) => {}
+                    Err(_) => return false,
+                }
+            }
+            false
+        })
+        .await;
+        if matches!(first, Ok(true)) {
+            report.first_token_ms = Some(elapsed_ms(start));
+        }
+    }
+
+    if provider.supports_native_tools() {
+        let messages = [ChatMessage::user(TOOL_PROBE_PROMPT)];
+        let tools = [ping_tool()];
+        let response = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            provider.chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: Some(&tools),
+                },
+                &target.model,
+                0.0,
+            ),
+        )
+        .await;
+        report.tool_calling = matches!(
+            response,
+            Ok(Ok(response)) if response.tool_calls.iter().any(|call| call.name == TOOL_PROBE_NAME)
+        );
+    }
+
+    report
+}
+
+fn build_provider(config: &Config, name: &str) -> Result<Box<dyn Provider>> {
+    let options = super::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        azure_openai: config.azure_openai.clone(),
+        middleware: config.provider_middleware.clone(),
+    };
+    super::create_routed_provider_with_options(
+        name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &options,
+    )
+}
+
+/// Catalog pricing for `target`, when its provider publishes prices.
+async fn lookup_pricing(config: &Config, target: &ProbeTarget) -> Option<ModelPricing> {
+    let api_key = super::resolve_provider_credential(&target.provider, config.api_key.as_deref());
+    // As in `zeroclaw models`, `api_url` belongs to the default provider.
+    let default_provider = config
+        .default_provider
+        .as_deref()
+        .unwrap_or(DEFAULT_PROVIDER);
+    let api_url = if target.provider == default_provider {
+        config.api_url.as_deref()
+    } else {
+        None
+    };
+    let models = catalog::fetch_models(&target.provider, api_key.as_deref(), api_url)
+        .await
+        .inspect_err(|e| tracing::debug!("No pricing for {}: {e:#}", target.provider))
+        .ok()?;
+    models
+        .into_iter()
+        .find(|model| model.id == target.model)
+        .and_then(|model| model.pricing)
+}
+
+fn format_ms(ms: Option<u64>) -> String {
+    ms.map_or_else(|| "-".to_string(), |ms| format!("{ms} ms"))
+}
+
+/// `zeroclaw providers test`. Fails when any target fails.
+pub async fn run(
+    config: &Config,
+    provider: Option<&str>,
+    model: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let targets = configured_targets(config, provider, model);
+    if targets.is_empty() {
+        anyhow::bail!(
+            "No configured model uses provider '{}'",
+            provider.unwrap_or_default()
+        );
+    }
+
+    let mut reports = Vec::with_capacity(targets.len());
+    for target in &targets {
+        let report = match build_provider(config, &target.provider) {
+            Ok(built) => {
+                let pricing = lookup_pricing(config, target).await;
+                probe(built.as_ref(), target, pricing.as_ref()).await
+            }
+            Err(e) => ProbeReport {
+                provider: target.provider.clone(),
+                model: target.model.clone(),
+                source: target.source.clone(),
+                latency_ms: None,
+                error: Some(format!("{e:#}")),
+                streaming: false,
+                first_token_ms: None,
+                tool_calling: false,
+                input_tokens: None,
+                output_tokens: None,
+                cost_usd: None,
+            },
+        };
+        reports.push(report);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+    } else {
+        println!(
+            "  {:<14} {:<32} {:>9}  {:<14} {:<5} {:>10}  SOURCE",
+            "PROVIDER", "MODEL", "LATENCY", "STREAM (1ST)", "TOOLS", "COST"
+        );
+        for report in &reports {
+            let stream = if report.streaming {
+                format!("yes ({})", format_ms(report.first_token_ms))
+            } else {
+                "no".to_string()
+            };
+            println!(
+                "{} {:<14} {:<32} {:>9}  {:<14} {:<5} {:>10}  {}",
+                if report.ok() { "✅" } else { "❌" },
+                report.provider,
+                report.model,
+                format_ms(report.latency_ms),
+                stream,
+                if report.tool_calling { "yes" } else { "no" },
+                report
+                    .cost_usd
+                    .map_or_else(|| "-".to_string(), |cost| format!("${cost:.6}")),
+                report.source
+            );
+            if let Some(error) = &report.error {
+                println!("     {error}");
+            }
+        }
+    }
+
+    let failed = reports.iter().filter(|report| !report.ok()).count();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} provider test(s) failed", reports.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentProfileConfig;
+    use crate::providers::{ChatResponse, ToolCall};
+    use async_trait::async_trait;
+
+    fn agent(id: &str, provider: Option<&str>, model: Option<&str>) -> AgentProfileConfig {
+        AgentProfileConfig {
+            id: id.into(),
+            provider: provider.map(String::from),
+            model: model.map(String::from),
+            system_prompt: None,
+            tools: Vec::new(),
+            memory_namespace: None,
+            autonomy: None,
+            routes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn targets_cover_default_and_agents_without_duplicates() {
+        let mut config = Config::default();
+        config.default_provider = Some("openai".into());
+        config.default_model = Some("gpt-4o".into());
+        config.agents = vec![
+            agent("same", None, None),
+            agent("mini", None, Some("gpt-4o-mini")),
+            agent("azure", Some("azure"), None),
+        ];
+
+        let targets = configured_targets(&config, None, None);
+        let pairs: Vec<(&str, &str, &str)> = targets
+            .iter()
+            .map(|t| (t.provider.as_str(), t.model.as_str(), t.source.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("openai", "gpt-4o", "default"),
+                ("openai", "gpt-4o-mini", "agent:mini"),
+                ("azure", "gpt-4o", "agent:azure"),
+            ]
+        );
+
+        let azure = configured_targets(&config, Some("Azure"), None);
+        assert_eq!(azure.len(), 1);
+        assert_eq!(azure[0].source, "agent:azure");
+
+        let unlisted = configured_targets(&config, Some("azure-openai"), Some("o3"));
+        assert_eq!(unlisted[0].model, "o3");
+        assert_eq!(unlisted[0].source, "--provider");
+    }
+
+    #[test]
+    fn cost_needs_both_token_counts() {
+        let pricing = ModelPricing {
+            prompt: 3.0,
+            completion: 15.0,
+        };
+        let usage = TokenUsage {
+            input_tokens: Some(1_000),
+            output_tokens: Some(100),
+        };
+        let cost = estimate_cost(&usage, &pricing).unwrap();
+        assert!((cost - 0.0045).abs() < 1e-12);
+        assert!(estimate_cost(
+            &TokenUsage {
+                input_tokens: Some(1_000),
+                output_tokens: None,
+            },
+            &pricing
+        )
+        .is_none());
+    }
+
+    struct PongProvider;
+
+    #[async_trait]
+    impl Provider for PongProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("pong".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            let tool_calls = match request.tools {
+                Some(_) => vec![ToolCall {
+                    id: "call_1".into(),
+                    name: TOOL_PROBE_NAME.into(),
+                    arguments: "{}".into(),
+                }],
+                None => Vec::new(),
+            };
+            Ok(ChatResponse {
+                text: Some("pong".into()),
+                tool_calls,
+                usage: Some(TokenUsage {
+                    input_tokens: Some(10),
+                    output_tokens: Some(1),
+                }),
+                reasoning_content: None,
+                reasoning: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_reports_latency_tools_and_cost() {
+        let target = ProbeTarget {
+            provider: "mock".into(),
+            model: "pong-1".into(),
+            source: "default".into(),
+        };
+        let pricing = ModelPricing {
+            prompt: 1.0,
+            completion: 2.0,
+        };
+        let report = probe(&PongProvider, &target, Some(&pricing)).await;
+        assert!(report.ok());
+        assert!(report.latency_ms.is_some());
+        assert!(!report.streaming);
+        assert_eq!(report.tool_calling, PongProvider.supports_native_tools());
+        assert_eq!(report.input_tokens, Some(10));
+        assert!((report.cost_usd.unwrap() - 0.000_012).abs() < 1e-12);
+    }
+}
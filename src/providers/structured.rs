@@ -0,0 +1,216 @@
+//! Structured (JSON) output with schema validation.
+//!
+//! [`chat_json`] asks the provider for JSON via
+//! [`Provider::chat_with_response_format`], parses the reply and checks it
+//! against the caller's schema with [`SchemaCleanr::validate_instance`]. When
+//! the reply is not valid JSON or violates the schema, the errors are fed back
+//! to the model and the request is retried.
+
+use super::traits::{ChatMessage, Provider, ResponseFormat};
+use crate::tools::SchemaCleanr;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Default number of model calls before giving up on a structured reply.
+pub const DEFAULT_STRUCTURED_OUTPUT_ATTEMPTS: u32 = 3;
+
+/// Violations are capped in feedback so a badly wrong reply cannot blow up the prompt.
+const MAX_FEEDBACK_ERRORS: usize = 10;
+
+/// Returned by [`chat_json`] when no attempt produced a conforming reply.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "Model output did not match the requested format after {attempts} attempt(s): {}",
+    .errors.join("; ")
+)]
+pub struct StructuredOutputError {
+    pub attempts: u32,
+    /// Violations found in the last reply.
+    pub errors: Vec<String>,
+}
+
+/// Request a JSON reply matching `format`, retrying with validation feedback
+/// up to `max_attempts` times. Returns the parsed value.
+pub async fn chat_json(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+    max_attempts: u32,
+) -> anyhow::Result<Value> {
+    if let Some(schema) = format.schema() {
+        SchemaCleanr::validate(schema)?;
+    }
+
+    let mut conversation = messages.to_vec();
+    let mut last_errors = Vec::new();
+    for attempt in 1..=max_attempts.max(1) {
+        let reply = provider
+            .chat_with_response_format(&conversation, format, model, temperature)
+            .await?;
+        let errors = match parse_json_output(&reply) {
+            Ok(value) => {
+                let errors = format
+                    .schema()
+                    .map(|schema| SchemaCleanr::validate_instance(schema, &value))
+                    .unwrap_or_default();
+                if errors.is_empty() {
+                    return Ok(value);
+                }
+                errors
+            }
+            Err(e) => vec![format!("reply is not valid JSON: {e}")],
+        };
+
+        tracing::debug!(
+            attempt,
+            violations = errors.len(),
+            "Structured output failed validation"
+        );
+        conversation.push(ChatMessage::assistant(reply));
+        conversation.push(ChatMessage::user(validation_feedback(&errors)));
+        last_errors = errors;
+    }
+
+    Err(StructuredOutputError {
+        attempts: max_attempts.max(1),
+        errors: last_errors,
+    }
+    .into())
+}
+
+/// Parse a model reply as JSON, tolerating surrounding whitespace and a
+/// markdown code fence.
+pub fn parse_json_output(reply: &str) -> serde_json::Result<Value> {
+    let trimmed = reply.trim();
+    let unfenced = trimmed
+        .strip_prefix("```")
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map_or(trimmed, |inner| {
+            inner.trim_start_matches("json").trim_start_matches("JSON")
+        });
+    serde_json::from_str(unfenced.trim())
+}
+
+fn validation_feedback(errors: &[String]) -> String {
+    let mut feedback =
+        String::from("Your previous reply did not match the requested JSON format:\n");
+    for error in errors.iter().take(MAX_FEEDBACK_ERRORS) {
+        feedback.push_str("- ");
+        feedback.push_str(error);
+        feedback.push('\n');
+    }
+    if errors.len() > MAX_FEEDBACK_ERRORS {
+        let _ = writeln!(
+            feedback,
+            "- ... and {} more",
+            errors.len() - MAX_FEEDBACK_ERRORS
+        );
+    }
+    feedback.push_str("Reply again with only the corrected JSON.");
+    feedback
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    struct ScriptedProvider {
+        replies: Mutex<Vec<&'static str>>,
+        seen: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat_with_history is overridden")
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.seen.lock().push(messages.to_vec());
+            Ok(self.replies.lock().remove(0).to_string())
+        }
+    }
+
+    fn schema_format() -> ResponseFormat {
+        ResponseFormat::JsonSchema {
+            name: "answer".into(),
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": {"answer": {"type": "integer"}},
+                "required": ["answer"]
+            }),
+            strict: true,
+        }
+    }
+
+    #[test]
+    fn parse_json_output_strips_code_fences() {
+        assert_eq!(
+            parse_json_output("```json\n{\"a\": 1}\n```").unwrap(),
+            serde_json::json!({"a": 1})
+        );
+        assert_eq!(parse_json_output(" [1] ").unwrap(), serde_json::json!([1]));
+        assert!(parse_json_output("sure! {\"a\": 1}").is_err());
+    }
+
+    #[tokio::test]
+    async fn chat_json_retries_with_validation_feedback() {
+        let provider = ScriptedProvider {
+            replies: Mutex::new(vec![
+                "not json",
+                r#"{"answer": "four"}"#,
+                r#"{"answer": 4}"#,
+            ]),
+            seen: Mutex::new(Vec::new()),
+        };
+        let messages = [ChatMessage::user("2+2?")];
+
+        let value = chat_json(&provider, &messages, &schema_format(), "m", 0.0, 3)
+            .await
+            .unwrap();
+        assert_eq!(value, serde_json::json!({"answer": 4}));
+
+        let seen = provider.seen.lock();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(seen[0][0].role, "system");
+        assert!(seen[0][0].content.contains("JSON Schema"));
+        let feedback = &seen[2].last().unwrap().content;
+        assert!(feedback.contains("$.answer: expected integer, got string"));
+    }
+
+    #[tokio::test]
+    async fn chat_json_gives_up_after_max_attempts() {
+        let provider = ScriptedProvider {
+            replies: Mutex::new(vec!["{}", "{}"]),
+            seen: Mutex::new(Vec::new()),
+        };
+        let err = chat_json(
+            &provider,
+            &[ChatMessage::user("2+2?")],
+            &schema_format(),
+            "m",
+            0.0,
+            2,
+        )
+        .await
+        .unwrap_err();
+        let err = err.downcast_ref::<StructuredOutputError>().unwrap();
+        assert_eq!(err.attempts, 2);
+        assert_eq!(err.errors, vec!["$: missing required property 'answer'"]);
+    }
+}
@@ -9,6 +9,38 @@ use std::fmt::Write;
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Non-text content (images) sent alongside `content` to vision models.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
+}
+
+/// A non-text content part of a multimodal chat message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Image referenced by an `http(s)` or `data:` URL.
+    ImageUrl { url: String },
+    /// Inline image bytes, base64-encoded.
+    ImageData { mime_type: String, data: String },
+}
+
+impl ContentPart {
+    /// Inline image from raw bytes (e.g. a file in the media store).
+    pub fn image_bytes(mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        use base64::Engine;
+        Self::ImageData {
+            mime_type: mime_type.into(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+
+    /// URL form of the image; inline data becomes a `data:` URI.
+    pub fn image_url(&self) -> String {
+        match self {
+            Self::ImageUrl { url } => url.clone(),
+            Self::ImageData { mime_type, data } => format!("data:{mime_type};base64,{data}"),
+        }
+    }
 }
 
 impl ChatMessage {
@@ -16,6 +48,7 @@ impl ChatMessage {
         Self {
             role: "system".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -23,6 +56,7 @@ impl ChatMessage {
         Self {
             role: "user".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -30,6 +64,7 @@ impl ChatMessage {
         Self {
             role: "assistant".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -37,8 +72,52 @@ impl ChatMessage {
         Self {
             role: "tool".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
+
+    /// Assistant turn that requested native tool calls. The content is a JSON
+    /// envelope (`content`, `tool_calls`, optional `reasoning_content`) that
+    /// native-tool providers decode back into a structured `tool_calls`
+    /// message; other providers see it as plain text.
+    pub fn assistant_tool_calls(
+        text: &str,
+        tool_calls: &[ToolCall],
+        reasoning_content: Option<&str>,
+    ) -> Self {
+        let text = text.trim();
+        let mut payload = serde_json::json!({
+            "content": (!text.is_empty()).then_some(text),
+            "tool_calls": tool_calls,
+        });
+        if let Some(reasoning_content) = reasoning_content {
+            payload["reasoning_content"] = serde_json::json!(reasoning_content);
+        }
+        Self::assistant(payload.to_string())
+    }
+
+    /// Result of the native tool call `tool_call_id`, encoded as a JSON
+    /// envelope (`tool_call_id`, `content`) for native-tool providers.
+    pub fn tool_result(tool_call_id: &str, content: &str) -> Self {
+        Self::tool(
+            serde_json::json!({
+                "tool_call_id": tool_call_id,
+                "content": content,
+            })
+            .to_string(),
+        )
+    }
+
+    /// Attach image parts to this message.
+    pub fn with_parts(mut self, parts: Vec<ContentPart>) -> Self {
+        self.parts = parts;
+        self
+    }
+
+    /// True when the message carries image content.
+    pub fn has_images(&self) -> bool {
+        !self.parts.is_empty()
+    }
 }
 
 /// A tool call requested by the LLM.
@@ -70,6 +149,10 @@ pub struct ChatResponse {
     /// sent back in subsequent API requests — some providers reject tool-call
     /// history that omits this field.
     pub reasoning_content: Option<String>,
+    /// Human-readable reasoning trace (OpenAI o-series, extended thinking,
+    /// DeepSeek-R1 `<think>` blocks) split out of `text`. Recorded in the
+    /// runtime trace only; never part of a channel reply.
+    pub reasoning: Option<String>,
 }
 
 impl ChatResponse {
@@ -84,6 +167,37 @@ impl ChatResponse {
     }
 }
 
+const THINK_OPEN_TAG: &str = "<think>";
+const THINK_CLOSE_TAG: &str = "</think>";
+
+/// Split inline `<think>...</think>` blocks (DeepSeek-R1 and similar) out of
+/// model output. Returns the remaining text and the joined reasoning, if any.
+/// An unterminated `<think>` consumes the rest of the output.
+pub fn split_think_blocks(text: &str) -> (String, Option<String>) {
+    if !text.contains(THINK_OPEN_TAG) {
+        return (text.to_string(), None);
+    }
+    let mut visible = String::with_capacity(text.len());
+    let mut reasoning = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(THINK_OPEN_TAG) {
+        visible.push_str(&rest[..start]);
+        let inner = &rest[start + THINK_OPEN_TAG.len()..];
+        let (thought, after) = match inner.find(THINK_CLOSE_TAG) {
+            Some(end) => (&inner[..end], &inner[end + THINK_CLOSE_TAG.len()..]),
+            None => (inner, ""),
+        };
+        let thought = thought.trim();
+        if !thought.is_empty() {
+            reasoning.push(thought);
+        }
+        rest = after;
+    }
+    visible.push_str(rest);
+    let reasoning = (!reasoning.is_empty()).then(|| reasoning.join("\n\n"));
+    (visible.trim().to_string(), reasoning)
+}
+
 /// Request payload for provider chat calls.
 #[derive(Debug, Clone, Copy)]
 pub struct ChatRequest<'a> {
@@ -91,6 +205,49 @@ pub struct ChatRequest<'a> {
     pub tools: Option<&'a [ToolSpec]>,
 }
 
+impl ChatRequest<'_> {
+    /// True when any message carries image content.
+    pub fn has_images(&self) -> bool {
+        self.messages.iter().any(ChatMessage::has_images)
+    }
+}
+
+/// Requested shape of a model reply for structured (JSON) output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// Any single JSON object.
+    JsonObject,
+    /// JSON matching `schema`; `strict` asks providers that support it to
+    /// enforce the schema server-side.
+    JsonSchema {
+        name: String,
+        schema: serde_json::Value,
+        strict: bool,
+    },
+}
+
+impl ResponseFormat {
+    /// Caller-supplied schema, if any.
+    pub fn schema(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::JsonObject => None,
+            Self::JsonSchema { schema, .. } => Some(schema),
+        }
+    }
+
+    /// System-prompt instructions for providers without a native JSON mode.
+    pub fn instructions(&self) -> String {
+        match self {
+            Self::JsonObject => {
+                "Respond with a single valid JSON object and nothing else: no prose, no markdown code fences.".to_string()
+            }
+            Self::JsonSchema { schema, .. } => format!(
+                "Respond with a single valid JSON value that conforms to this JSON Schema and nothing else: no prose, no markdown code fences.\n\n{schema}"
+            ),
+        }
+    }
+}
+
 /// A tool result to feed back to the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultMessage {
@@ -340,20 +497,10 @@ pub trait Provider: Send + Sync {
                         )
                     }
                 };
-                let mut modified_messages = request.messages.to_vec();
-
                 // Inject tool instructions into an existing system message.
                 // If none exists, prepend one to the conversation.
-                if let Some(system_message) =
-                    modified_messages.iter_mut().find(|m| m.role == "system")
-                {
-                    if !system_message.content.is_empty() {
-                        system_message.content.push_str("\n\n");
-                    }
-                    system_message.content.push_str(&tool_instructions);
-                } else {
-                    modified_messages.insert(0, ChatMessage::system(tool_instructions));
-                }
+                let modified_messages =
+                    with_system_instructions(request.messages, &tool_instructions);
 
                 let text = self
                     .chat_with_history(&modified_messages, model, temperature)
@@ -363,6 +510,7 @@ pub trait Provider: Send + Sync {
                     tool_calls: Vec::new(),
                     usage: None,
                     reasoning_content: None,
+                    reasoning: None,
                 });
             }
         }
@@ -375,9 +523,25 @@ pub trait Provider: Send + Sync {
             tool_calls: Vec::new(),
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         })
     }
 
+    /// Chat constrained to JSON output. Providers with a native JSON mode
+    /// (e.g. OpenAI `response_format`) override this; the default appends
+    /// [`ResponseFormat::instructions`] to the system prompt. Callers should
+    /// still validate the reply (see [`super::structured::chat_json`]).
+    async fn chat_with_response_format(
+        &self,
+        messages: &[ChatMessage],
+        format: &ResponseFormat,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let messages = with_system_instructions(messages, &format.instructions());
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
     /// Whether provider supports native tool calls over API.
     fn supports_native_tools(&self) -> bool {
         self.capabilities().native_tool_calling
@@ -410,6 +574,7 @@ pub trait Provider: Send + Sync {
             tool_calls: Vec::new(),
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         })
     }
 
@@ -453,6 +618,20 @@ pub trait Provider: Send + Sync {
     }
 }
 
+/// Append `instructions` to the first system message, or prepend one.
+pub fn with_system_instructions(messages: &[ChatMessage], instructions: &str) -> Vec<ChatMessage> {
+    let mut messages = messages.to_vec();
+    if let Some(system_message) = messages.iter_mut().find(|m| m.role == "system") {
+        if !system_message.content.is_empty() {
+            system_message.content.push_str("\n\n");
+        }
+        system_message.content.push_str(instructions);
+    } else {
+        messages.insert(0, ChatMessage::system(instructions));
+    }
+    messages
+}
+
 /// Build tool instructions text for prompt-guided tool calling.
 ///
 /// Generates a formatted text block describing available tools and how to
@@ -554,18 +733,41 @@ pub enum ProviderErrorKind {
     Auth,
     Billing,
     RateLimit,
+    /// The prompt does not fit the model's context window.
+    ContextLength,
     Capacity,
     Network,
     InvalidRequest,
     Unknown,
 }
 
+/// Phrases providers use when a request exceeds the model's context window.
+const CONTEXT_LENGTH_HINTS: &[&str] = &[
+    "context_length_exceeded",
+    "exceeds the context window",
+    "context window of this model",
+    "maximum context length",
+    "context length exceeded",
+    "too many tokens",
+    "token limit exceeded",
+    "prompt is too long",
+    "input is too long",
+];
+
+/// Whether a provider error reports that the prompt exceeded the context window.
+pub fn is_context_length_error(error_msg: &str) -> bool {
+    let lower = error_msg.to_lowercase();
+    CONTEXT_LENGTH_HINTS.iter().any(|hint| lower.contains(hint))
+}
+
 /// Classify a provider error for smart retry behavior.
 pub fn classify_provider_error(error_msg: &str) -> ProviderErrorKind {
     let lower = error_msg.to_lowercase();
     if lower.contains("401") || lower.contains("unauthorized") || lower.contains("invalid api key")
     {
         ProviderErrorKind::Auth
+    } else if is_context_length_error(&lower) {
+        ProviderErrorKind::ContextLength
     } else if lower.contains("402") || lower.contains("billing") || lower.contains("quota") {
         ProviderErrorKind::Billing
     } else if lower.contains("429") || lower.contains("rate limit") || lower.contains("too many") {
@@ -630,6 +832,7 @@ mod tests {
             tool_calls: vec![],
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -643,6 +846,7 @@ mod tests {
             }],
             usage: None,
             reasoning_content: None,
+            reasoning: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
@@ -665,6 +869,7 @@ mod tests {
                 output_tokens: Some(50),
             }),
             reasoning_content: None,
+            reasoning: None,
         };
         assert_eq!(resp.usage.as_ref().unwrap().input_tokens, Some(100));
         assert_eq!(resp.usage.as_ref().unwrap().output_tokens, Some(50));
@@ -1097,6 +1302,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn split_think_blocks_extracts_reasoning() {
+        assert_eq!(
+            split_think_blocks("plain answer"),
+            ("plain answer".into(), None)
+        );
+
+        let (text, reasoning) =
+            split_think_blocks("<think>first</think>Answer <think> second </think>done");
+        assert_eq!(text, "Answer done");
+        assert_eq!(reasoning.as_deref(), Some("first\n\nsecond"));
+
+        let (text, reasoning) = split_think_blocks("Partial <think>still thinking");
+        assert_eq!(text, "Partial");
+        assert_eq!(reasoning.as_deref(), Some("still thinking"));
+    }
+
+    #[test]
+    fn classify_provider_error_context_length() {
+        assert_eq!(
+            classify_provider_error(
+                "openai API error (400 Bad Request): context_length_exceeded: too many tokens"
+            ),
+            ProviderErrorKind::ContextLength
+        );
+        assert_eq!(
+            classify_provider_error("This model's maximum context length is 8192 tokens"),
+            ProviderErrorKind::ContextLength
+        );
+    }
+
     #[test]
     fn classify_provider_error_invalid_request() {
         assert_eq!(
@@ -21,6 +21,14 @@ impl DefaultRouter {
         }
     }
 
+    /// Create a router pre-populated with `bindings`.
+    pub fn with_bindings(default_agent_id: &str, bindings: Vec<RouteBinding>) -> Self {
+        Self {
+            default_agent_id: default_agent_id.to_string(),
+            bindings: Mutex::new(bindings),
+        }
+    }
+
     /// Build a session key from the route context.
     fn build_session_key(agent_id: &str, context: &RouteContext) -> String {
         match context.chat_type {
@@ -71,9 +79,9 @@ impl DefaultRouter {
 #[async_trait]
 impl Router for DefaultRouter {
     async fn resolve_route(&self, context: &RouteContext) -> Result<RouteMatch> {
-        let (agent_id, matched_by) = match self.find_match(context) {
-            Some((binding, matched_by)) => (binding.agent_id, matched_by),
-            None => (self.default_agent_id.clone(), MatchedBy::Default),
+        let (agent_id, matched_by, binding_id) = match self.find_match(context) {
+            Some((binding, matched_by)) => (binding.agent_id, matched_by, Some(binding.id)),
+            None => (self.default_agent_id.clone(), MatchedBy::Default, None),
         };
 
         let session_key = Self::build_session_key(&agent_id, context);
@@ -82,6 +90,7 @@ impl Router for DefaultRouter {
             agent_id,
             session_key,
             matched_by,
+            binding_id,
         })
     }
 
@@ -132,6 +141,7 @@ mod tests {
         let result = router.resolve_route(&ctx).await.unwrap();
         assert_eq!(result.agent_id, "default-agent");
         assert!(matches!(result.matched_by, MatchedBy::Default));
+        assert_eq!(result.binding_id, None);
     }
 
     #[tokio::test]
@@ -152,6 +162,7 @@ mod tests {
         let result = router.resolve_route(&ctx).await.unwrap();
         assert_eq!(result.agent_id, "special-agent");
         assert!(matches!(result.matched_by, MatchedBy::Peer));
+        assert_eq!(result.binding_id.as_deref(), Some("b1"));
     }
 
     #[tokio::test]
@@ -4,9 +4,97 @@ pub mod default;
 pub mod traits;
 
 pub use default::DefaultRouter;
-pub use traits::{ChatType, MatchedBy, RouteBinding, RouteContext, RouteMatch, Router};
+pub use traits::{ChatType, RouteBinding, RouteContext, RouteMatch, Router};
+
+use crate::agent::registry::{route_bindings, DEFAULT_AGENT_ID};
+use crate::config::{Config, RouteBindingConfig};
+use anyhow::{bail, Result};
 
 /// Create a default in-memory router with the given fallback agent ID.
 pub fn create_router(default_agent_id: &str) -> Box<dyn Router> {
     Box::new(DefaultRouter::new(default_agent_id))
 }
+
+/// Handle `zeroclaw route <subcommand>` CLI commands.
+pub async fn handle_command(command: crate::RouteCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::RouteCommands::List => {
+            let mut bindings = route_bindings(config);
+            if bindings.is_empty() {
+                println!(
+                    "No route bindings. Every conversation uses the '{DEFAULT_AGENT_ID}' agent."
+                );
+                println!();
+                println!("Add one with: zeroclaw route add telegram <agent> --pattern <sender>");
+                return Ok(());
+            }
+            bindings.sort_by(|a, b| (&a.channel, a.priority).cmp(&(&b.channel, b.priority)));
+            println!("Route bindings ({}):\n", bindings.len());
+            println!(
+                "  {:<16} {:<12} {:<20} {:<12} PRIORITY",
+                "ID", "CHANNEL", "PATTERN", "AGENT"
+            );
+            for binding in &bindings {
+                println!(
+                    "  {:<16} {:<12} {:<20} {:<12} {}",
+                    binding.id,
+                    binding.channel,
+                    binding.pattern,
+                    binding.agent_id,
+                    binding.priority
+                );
+            }
+            println!("\nUnmatched conversations use the '{DEFAULT_AGENT_ID}' agent.");
+            Ok(())
+        }
+        crate::RouteCommands::Add {
+            channel,
+            agent,
+            pattern,
+            priority,
+            id,
+        } => {
+            let mut updated = config.clone();
+            let id = id.unwrap_or_else(|| binding_id_for_new(&updated));
+            if route_bindings(&updated).iter().any(|b| b.id == id.trim()) {
+                bail!("A route binding with ID '{id}' already exists");
+            }
+            updated.routes.push(RouteBindingConfig {
+                id: id.trim().to_string(),
+                channel: channel.trim().to_string(),
+                pattern: pattern.trim().to_string(),
+                agent: agent.trim().to_string(),
+                priority,
+            });
+            updated.validate()?;
+            updated.save().await?;
+            println!("✅ Added route '{id}': {channel} {pattern} → {agent} (priority {priority})");
+            println!("   Restart channels or the gateway to apply it.");
+            Ok(())
+        }
+        crate::RouteCommands::Remove { id } => {
+            let mut updated = config.clone();
+            let Some(index) = updated.routes.iter().position(|r| r.id == id.trim()) else {
+                if route_bindings(config).iter().any(|b| b.id == id.trim()) {
+                    bail!("Route '{id}' is defined in [[agents.routes]]; edit config.toml to remove it");
+                }
+                bail!("No route binding with ID '{id}'");
+            };
+            updated.routes.remove(index);
+            updated.save().await?;
+            println!("✅ Removed route '{id}'");
+            Ok(())
+        }
+    }
+}
+
+/// First free `route-<n>` ID. One of `taken.len() + 1` candidates is always
+/// free.
+fn binding_id_for_new(config: &Config) -> String {
+    let taken: Vec<String> = route_bindings(config).into_iter().map(|b| b.id).collect();
+    let start = config.routes.len() + 1;
+    (start..=start + taken.len())
+        .map(|n| format!("route-{n}"))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
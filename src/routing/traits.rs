@@ -39,6 +39,8 @@ pub struct RouteMatch {
     pub agent_id: String,
     pub session_key: String,
     pub matched_by: MatchedBy,
+    /// ID of the binding that matched; `None` when the default agent applies.
+    pub binding_id: Option<String>,
 }
 
 /// A binding that maps a channel pattern to an agent.
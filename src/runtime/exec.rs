@@ -0,0 +1,433 @@
+//! Shell command execution for the `shell` tool.
+//!
+//! Commands run with piped output by default. With `[runtime] pty = true`
+//! (or `"pty": true` on a call) they run under a pseudo-terminal instead, so
+//! tools that insist on a TTY still behave; escape sequences and carriage
+//! return redraws are stripped from what they print. Either way the command
+//! gets [`NON_INTERACTIVE_ENV`], stdin is closed once the optional response
+//! has been fed, and its whole process group is killed at the timeout.
+
+use crate::config::RuntimeConfig;
+use std::io;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Default hard limit for one shell command.
+pub const DEFAULT_SHELL_TIMEOUT_SECS: u64 = 60;
+
+/// Environment hints that keep common tools from prompting or paging.
+pub const NON_INTERACTIVE_ENV: [(&str, &str); 5] = [
+    ("CI", "1"),
+    ("GIT_PAGER", "cat"),
+    ("PAGER", "cat"),
+    ("GIT_TERMINAL_PROMPT", "0"),
+    ("DEBIAN_FRONTEND", "noninteractive"),
+];
+
+/// How the shell tool runs commands on a runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellOptions {
+    /// Run commands under a pseudo-terminal unless a call opts out.
+    pub pty: bool,
+    /// Seconds before a command and its process group are killed.
+    pub timeout_secs: u64,
+    /// Stdin responses that may be fed without `approved: true`.
+    pub approved_stdin: Vec<String>,
+}
+
+impl Default for ShellOptions {
+    fn default() -> Self {
+        Self::from_config(&RuntimeConfig::default())
+    }
+}
+
+impl ShellOptions {
+    pub fn from_config(config: &RuntimeConfig) -> Self {
+        Self {
+            pty: config.pty,
+            timeout_secs: config.shell_timeout_secs,
+            approved_stdin: config.approved_stdin.clone(),
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.max(1))
+    }
+
+    /// Whether every line of `stdin` is a pre-approved response (trimmed,
+    /// case-insensitive).
+    pub fn stdin_preapproved(&self, stdin: &str) -> bool {
+        stdin.lines().all(|line| {
+            self.approved_stdin
+                .iter()
+                .any(|approved| approved.trim().eq_ignore_ascii_case(line.trim()))
+        })
+    }
+}
+
+/// Output of a finished command. Under a PTY, stderr arrives in `stdout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub success: bool,
+}
+
+/// Run `cmd`, feed it `stdin` and kill it after `timeout`. A timeout is
+/// reported as [`io::ErrorKind::TimedOut`].
+pub async fn run_shell(
+    cmd: Command,
+    stdin: Option<&str>,
+    pty: bool,
+    timeout: Duration,
+) -> io::Result<ShellOutput> {
+    if pty {
+        #[cfg(unix)]
+        return pty::run(cmd, stdin, timeout).await;
+        #[cfg(not(unix))]
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "PTY execution needs a Unix host",
+        ));
+    }
+    run_piped(cmd, stdin, timeout).await
+}
+
+async fn run_piped(
+    mut cmd: Command,
+    stdin: Option<&str>,
+    timeout: Duration,
+) -> io::Result<ShellOutput> {
+    cmd.stdin(if stdin.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    })
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true);
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let mut child = cmd.spawn()?;
+    let pid = child.id();
+    let input = stdin.map(with_trailing_newline);
+    let pipe = child.stdin.take();
+    let run = async move {
+        if let (Some(input), Some(mut pipe)) = (input, pipe) {
+            // A command that exits without reading stdin closes the pipe.
+            let _ = pipe.write_all(input.as_bytes()).await;
+        }
+        child.wait_with_output().await
+    };
+    match tokio::time::timeout(timeout, run).await {
+        Ok(output) => {
+            let output = output?;
+            Ok(ShellOutput {
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                success: output.status.success(),
+            })
+        }
+        Err(_) => {
+            #[cfg(unix)]
+            signal_process_group(pid, libc::SIGKILL);
+            #[cfg(not(unix))]
+            let _ = pid;
+            Err(timed_out(timeout))
+        }
+    }
+}
+
+fn with_trailing_newline(input: &str) -> String {
+    if input.ends_with('\n') {
+        input.to_string()
+    } else {
+        format!("{input}\n")
+    }
+}
+
+fn timed_out(timeout: Duration) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("timed out after {}s", timeout.as_secs()),
+    )
+}
+
+/// Signal the whole process group so `sh -c` children are reached too.
+#[cfg(unix)]
+pub(crate) fn signal_process_group(pid: Option<u32>, signal: i32) {
+    if let Some(pid) = pid.and_then(|pid| i32::try_from(pid).ok()) {
+        // SAFETY: kill(2) with a negative pid only sends a signal to the
+        // process group we created for this child.
+        unsafe {
+            libc::kill(-pid, signal);
+        }
+    }
+}
+
+/// Drop ANSI escape sequences, bells and backspaces from terminal output,
+/// and apply carriage-return redraws (progress bars) so only the final
+/// state of each line remains.
+pub fn strip_terminal_codes(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => match chars.next() {
+                // CSI: parameter and intermediate bytes, then a final byte.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: terminated by BEL or ESC \.
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' => out.truncate(out.rfind('\n').map_or(0, |i| i + 1)),
+            '\x08' => {
+                if !out.ends_with('\n') {
+                    out.pop();
+                }
+            }
+            '\x07' => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(unix)]
+mod pty {
+    use super::{signal_process_group, strip_terminal_codes, timed_out, ShellOutput};
+    use std::io::{self, Read, Write};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::process::Stdio;
+    use std::time::Duration;
+    use tokio::process::Command;
+    use tokio::task::JoinHandle;
+
+    const COLUMNS: u16 = 200;
+    const ROWS: u16 = 50;
+    /// Terminal output kept in memory; the rest is read and discarded.
+    const MAX_CAPTURE_BYTES: usize = 4 * 1_048_576;
+    /// Time background jobs get to release the terminal after the command
+    /// exits before their process group is killed.
+    const DRAIN_GRACE: Duration = Duration::from_secs(2);
+    /// End-of-file (`VEOF`, Ctrl-D) in canonical mode.
+    const EOF: u8 = 0x04;
+
+    pub(super) async fn run(
+        mut cmd: Command,
+        stdin: Option<&str>,
+        timeout: Duration,
+    ) -> io::Result<ShellOutput> {
+        let (master, slave) = open()?;
+        cmd.stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave))
+            .kill_on_drop(true);
+        // SAFETY: setsid(2) and ioctl(2) are async-signal-safe. The child
+        // leads a new session and process group with the PTY as its
+        // controlling terminal.
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        let mut child = cmd.spawn()?;
+        // Close our copies of the slave so reads end once the session does.
+        drop(cmd);
+        let pid = child.id();
+
+        let mut writer = std::fs::File::from(master.try_clone()?);
+        let mut reader = tokio::task::spawn_blocking(move || read_all(master.into()));
+        let mut input = stdin.map(super::with_trailing_newline).unwrap_or_default();
+        input.push(char::from(EOF));
+        // Echo is off, so the response does not reappear in the output.
+        let _ = writer.write_all(input.as_bytes());
+        drop(writer);
+
+        let status = match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                signal_process_group(pid, libc::SIGKILL);
+                let _ = child.wait().await;
+                let _ = drain(&mut reader, pid).await;
+                return Err(timed_out(timeout));
+            }
+        };
+        let captured = drain(&mut reader, pid).await?;
+        Ok(ShellOutput {
+            stdout: strip_terminal_codes(&String::from_utf8_lossy(&captured)),
+            stderr: String::new(),
+            success: status.success(),
+        })
+    }
+
+    /// Wait for the reader, killing background jobs that keep the terminal
+    /// open past [`DRAIN_GRACE`].
+    async fn drain(reader: &mut JoinHandle<Vec<u8>>, pid: Option<u32>) -> io::Result<Vec<u8>> {
+        if let Ok(captured) = tokio::time::timeout(DRAIN_GRACE, &mut *reader).await {
+            return captured.map_err(io::Error::other);
+        }
+        signal_process_group(pid, libc::SIGKILL);
+        tokio::time::timeout(DRAIN_GRACE, reader)
+            .await
+            .map_err(|_| io::Error::other("terminal output did not close"))?
+            .map_err(io::Error::other)
+    }
+
+    /// Open a PTY pair with echo off. Both ends are close-on-exec; the child
+    /// only receives the slave through its standard descriptors.
+    fn open() -> io::Result<(OwnedFd, OwnedFd)> {
+        let mut master = -1;
+        let mut slave = -1;
+        let size = libc::winsize {
+            ws_row: ROWS,
+            ws_col: COLUMNS,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        // SAFETY: the descriptor out-pointers and `size` are valid for the
+        // call; the name and termios arguments may be null.
+        let opened = unsafe {
+            libc::openpty(
+                &raw mut master,
+                &raw mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &raw const size,
+            )
+        };
+        if opened != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: openpty returned two fresh descriptors that nothing else owns.
+        let (master, slave) =
+            unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+        // SAFETY: both descriptors are open; `attrs` is a valid termios
+        // out-pointer for the slave terminal.
+        unsafe {
+            libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+            libc::fcntl(slave.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+            let mut attrs: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(slave.as_raw_fd(), &raw mut attrs) == 0 {
+                attrs.c_lflag &= !libc::ECHO;
+                libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &raw const attrs);
+            }
+        }
+        Ok((master, slave))
+    }
+
+    fn read_all(mut master: std::fs::File) -> Vec<u8> {
+        let mut captured = Vec::new();
+        let mut buf = [0_u8; 8192];
+        loop {
+            match master.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let room = MAX_CAPTURE_BYTES.saturating_sub(captured.len());
+                    captured.extend_from_slice(&buf[..n.min(room)]);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                // Linux reports EIO once every slave descriptor is closed.
+                Err(_) => break,
+            }
+        }
+        captured
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        cmd
+    }
+
+    #[test]
+    fn strips_escape_sequences_and_redraws() {
+        let raw = "\x1b[1;32mok\x1b[0m\r\n\x1b]0;title\x0710%\r50%\r100%\r\nab\x08c\x07";
+        assert_eq!(strip_terminal_codes(raw), "ok\n100%\nac");
+    }
+
+    #[test]
+    fn preapproved_stdin_matches_every_line() {
+        let options = ShellOptions {
+            approved_stdin: vec!["y".into(), "yes".into()],
+            ..ShellOptions::default()
+        };
+        assert!(options.stdin_preapproved(" Y "));
+        assert!(options.stdin_preapproved("y\nyes\n"));
+        assert!(!options.stdin_preapproved("y\nrm -rf /"));
+    }
+
+    #[tokio::test]
+    async fn piped_run_feeds_stdin_and_times_out() {
+        let output = run_shell(
+            sh("read answer; echo \"got $answer\"; echo oops >&2"),
+            Some("y"),
+            false,
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.stdout, "got y\n");
+        assert_eq!(output.stderr, "oops\n");
+        assert!(output.success);
+
+        let err = run_shell(sh("sleep 5"), None, false, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pty_run_gives_the_command_a_terminal() {
+        let output = run_shell(
+            sh("test -t 0 && test -t 1 && printf '\\033[31mtty\\033[0m\\n'; read answer; echo \"got $answer\""),
+            Some("yes"),
+            true,
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.stdout, "tty\ngot yes\n");
+        assert!(output.success);
+
+        // Stdin is at end-of-file, so a prompt without a response fails fast.
+        let prompt = run_shell(sh("read answer"), None, true, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(!prompt.success);
+
+        let err = run_shell(sh("sleep 5"), None, true, Duration::from_millis(200))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+}
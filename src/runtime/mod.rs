@@ -1,6 +1,8 @@
+pub mod exec;
 pub mod native;
 pub mod traits;
 
+pub use exec::ShellOptions;
 pub use native::NativeRuntime;
 pub use traits::RuntimeAdapter;
 
@@ -9,7 +11,9 @@ use crate::config::RuntimeConfig;
 /// Factory: create the right runtime from config
 pub fn create_runtime(config: &RuntimeConfig) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
     match config.kind.as_str() {
-        "native" => Ok(Box::new(NativeRuntime::new())),
+        "native" => Ok(Box::new(NativeRuntime::with_shell_options(
+            ShellOptions::from_config(config),
+        ))),
         other if other.trim().is_empty() => {
             anyhow::bail!("runtime.kind cannot be empty. Supported values: native")
         }
@@ -32,6 +36,19 @@ mod tests {
         assert!(rt.has_shell_access());
     }
 
+    #[test]
+    fn factory_native_carries_shell_options() {
+        let cfg = RuntimeConfig {
+            pty: true,
+            shell_timeout_secs: 15,
+            ..RuntimeConfig::default()
+        };
+        let options = create_runtime(&cfg).unwrap().shell_options();
+        assert!(options.pty);
+        assert_eq!(options.timeout_secs, 15);
+        assert_eq!(options.approved_stdin, ["y", "yes"]);
+    }
+
     #[test]
     fn factory_unknown_errors() {
         let cfg = RuntimeConfig {
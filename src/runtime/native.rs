@@ -1,12 +1,21 @@
+use super::exec::ShellOptions;
 use super::traits::RuntimeAdapter;
 use std::path::{Path, PathBuf};
 
 /// Native runtime — full access, runs on Mac/Linux/Docker/Raspberry Pi
-pub struct NativeRuntime;
+pub struct NativeRuntime {
+    shell: ShellOptions,
+}
 
 impl NativeRuntime {
     pub fn new() -> Self {
-        Self
+        Self::with_shell_options(ShellOptions::default())
+    }
+
+    /// Native runtime with `[runtime]` shell settings (PTY, timeout,
+    /// pre-approved stdin).
+    pub fn with_shell_options(shell: ShellOptions) -> Self {
+        Self { shell }
     }
 }
 
@@ -34,6 +43,14 @@ impl RuntimeAdapter for NativeRuntime {
         true
     }
 
+    fn supports_pty(&self) -> bool {
+        cfg!(unix)
+    }
+
+    fn shell_options(&self) -> ShellOptions {
+        self.shell.clone()
+    }
+
     fn build_shell_command(
         &self,
         command: &str,
@@ -1,3 +1,4 @@
+use super::exec::ShellOptions;
 use std::path::{Path, PathBuf};
 
 /// Runtime adapter that abstracts platform differences for the agent.
@@ -52,6 +53,22 @@ pub trait RuntimeAdapter: Send + Sync {
         0
     }
 
+    /// Report whether shell commands can run under a pseudo-terminal.
+    ///
+    /// When `false` (the default), the shell tool rejects PTY requests
+    /// instead of silently falling back to pipes.
+    fn supports_pty(&self) -> bool {
+        false
+    }
+
+    /// Return how the shell tool should run commands on this runtime.
+    ///
+    /// Covers PTY use, the hard timeout and the stdin responses that may be
+    /// fed without explicit approval. Defaults to [`ShellOptions::default`].
+    fn shell_options(&self) -> ShellOptions {
+        ShellOptions::default()
+    }
+
     /// Build a shell command process configured for this runtime.
     ///
     /// Constructs a [`tokio::process::Command`] that will execute `command`
@@ -115,6 +132,13 @@ mod tests {
         assert_eq!(runtime.memory_budget(), 0);
     }
 
+    #[test]
+    fn default_shell_options_have_no_pty() {
+        let runtime = DummyRuntime;
+        assert!(!runtime.supports_pty());
+        assert_eq!(runtime.shell_options(), ShellOptions::default());
+    }
+
     #[test]
     fn runtime_reports_capabilities() {
         let runtime = DummyRuntime;
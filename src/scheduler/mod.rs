@@ -0,0 +1,401 @@
+//! Recurring agent jobs from `[[schedule.tasks]]`.
+//!
+//! Tasks run inside the long-lived `zeroclaw channel start` process so their
+//! results can be delivered through the live channels. Each run sends the
+//! task's prompt through the full agent (tools, memory) and forwards the
+//! reply to the configured channel and recipient.
+
+pub mod reminders;
+
+pub use crate::util::time::CronSchedule;
+pub use reminders::{ReminderStore, ReminderTarget};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::channels::{Channel, SendMessage};
+use crate::config::{Config, ScheduleConfig, ScheduledTaskConfig};
+use crate::util::time::TimeService;
+
+/// Health component name reported by the scheduler loop.
+const HEALTH_COMPONENT: &str = "scheduler";
+
+/// `channel` value that shows the result as a desktop notification
+/// (`[tools.desktop]`) instead of sending it through a channel.
+pub const DESKTOP_TARGET: &str = "desktop";
+
+/// An enabled task with its parsed schedule.
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    pub name: String,
+    pub schedule: CronSchedule,
+    pub config: ScheduledTaskConfig,
+}
+
+/// Name used for a task in the CLI and logs; unnamed tasks are `task-<n>`.
+pub fn task_name(task: &ScheduledTaskConfig, index: usize) -> String {
+    task.name
+        .clone()
+        .unwrap_or_else(|| format!("task-{}", index + 1))
+}
+
+fn find_task(config: &ScheduleConfig, name: &str) -> Result<usize> {
+    config
+        .tasks
+        .iter()
+        .enumerate()
+        .position(|(i, task)| task_name(task, i) == name)
+        .with_context(|| format!("No scheduled task named '{name}' (see `zeroclaw schedule list`)"))
+}
+
+/// Enabled tasks with a valid cron expression. Invalid ones are logged and skipped.
+pub fn load_tasks(config: &ScheduleConfig) -> Vec<ScheduledTask> {
+    config
+        .tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| task.enabled)
+        .filter_map(|(i, task)| {
+            let name = task_name(task, i);
+            match CronSchedule::parse(&task.cron) {
+                Ok(schedule) => Some(ScheduledTask {
+                    name,
+                    schedule,
+                    config: task.clone(),
+                }),
+                Err(e) => {
+                    tracing::warn!("Skipping scheduled task '{name}': {e:#}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// The soonest fire time after `now`, with the indices of every task due then.
+fn next_due(
+    time: TimeService,
+    tasks: &[ScheduledTask],
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, Vec<usize>)> {
+    let upcoming: Vec<(usize, DateTime<Utc>)> = tasks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, task)| Some((i, time.next_cron(&task.schedule, now)?)))
+        .collect();
+    let at = upcoming.iter().map(|(_, at)| *at).min()?;
+    let due = upcoming
+        .iter()
+        .filter(|(_, next)| *next == at)
+        .map(|(i, _)| *i)
+        .collect();
+    Some((at, due))
+}
+
+/// Run scheduled tasks until the process exits. Tasks fire concurrently, so
+/// a slow run never delays the next one.
+pub(crate) async fn run(config: Config, channels: Arc<HashMap<String, Arc<dyn Channel>>>) {
+    let tasks = load_tasks(&config.schedule);
+    if !config.schedule.enabled || tasks.is_empty() {
+        return;
+    }
+    let time = TimeService::from_config(&config);
+    let config = Arc::new(config);
+    crate::health::mark_component_ok(HEALTH_COMPONENT);
+    tracing::info!(
+        "Scheduler started with {} task(s) ({} time)",
+        tasks.len(),
+        time.zone_name()
+    );
+
+    loop {
+        let now = Utc::now();
+        let Some((at, due)) = next_due(time, &tasks, now) else {
+            tracing::warn!("No scheduled task can fire again; scheduler stopping");
+            return;
+        };
+        tokio::time::sleep((at - now).to_std().unwrap_or_default()).await;
+
+        for i in due {
+            let task = tasks[i].clone();
+            let config = Arc::clone(&config);
+            let channels = Arc::clone(&channels);
+            tokio::spawn(async move {
+                tracing::info!("Running scheduled task '{}'", task.name);
+                match Box::pin(run_task(&config, &task.config, &channels)).await {
+                    Ok(_) => crate::health::mark_component_ok(HEALTH_COMPONENT),
+                    Err(e) => {
+                        tracing::error!("Scheduled task '{}' failed: {e:#}", task.name);
+                        crate::health::mark_component_error(
+                            HEALTH_COMPONENT,
+                            format!("{}: {e}", task.name),
+                        );
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Run one task through the agent and deliver the reply. Returns the reply.
+pub(crate) async fn run_task(
+    config: &Config,
+    task: &ScheduledTaskConfig,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+) -> Result<String> {
+    let label = format!(
+        "scheduled task '{}'",
+        task.name.as_deref().unwrap_or(&task.cron)
+    );
+    crate::infra::checkpoint::auto_checkpoint(config, &label).await;
+    let output = Box::pin(crate::agent::process_message(config.clone(), &task.prompt)).await?;
+    if task.channel.as_deref() == Some(DESKTOP_TARGET) {
+        let title = task.name.as_deref().unwrap_or("Scheduled task");
+        crate::tools::desktop::notify(&config.tools.desktop, title, &output).await?;
+    } else {
+        deliver(task, channels, &output).await?;
+    }
+    Ok(output)
+}
+
+async fn deliver(
+    task: &ScheduledTaskConfig,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+    output: &str,
+) -> Result<()> {
+    let Some(channel_name) = task.channel.as_deref() else {
+        tracing::info!("Scheduled task result (no channel configured):\n{output}");
+        return Ok(());
+    };
+    let channel = channels
+        .get(channel_name)
+        .with_context(|| format!("Channel '{channel_name}' is not configured"))?;
+    let recipient = task
+        .recipient
+        .as_deref()
+        .with_context(|| format!("No recipient set for channel '{channel_name}'"))?;
+    channel
+        .send(&SendMessage::new(output, recipient))
+        .await
+        .with_context(|| format!("Failed to deliver to {channel_name}:{recipient}"))
+}
+
+/// Handle `zeroclaw schedule <subcommand>` CLI commands.
+pub async fn handle_command(command: crate::ScheduleCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::ScheduleCommands::List => {
+            if config.schedule.tasks.is_empty() {
+                println!("No scheduled tasks.");
+                println!();
+                println!(
+                    "Add one with: zeroclaw schedule add \"0 9 * * *\" \"Summarize my inbox\""
+                );
+                return Ok(());
+            }
+            let time = TimeService::from_config(config);
+            let now = Utc::now();
+            println!("Scheduled tasks ({}):\n", config.schedule.tasks.len());
+            for (i, task) in config.schedule.tasks.iter().enumerate() {
+                let next = match CronSchedule::parse(&task.cron) {
+                    Err(_) => "invalid cron".to_string(),
+                    Ok(_) if !task.enabled => "paused".to_string(),
+                    Ok(schedule) => time.next_cron(&schedule, now).map_or_else(
+                        || "never".to_string(),
+                        |at| time.localize(at).format("%Y-%m-%d %H:%M").to_string(),
+                    ),
+                };
+                let target = match (&task.channel, &task.recipient) {
+                    (Some(channel), Some(recipient)) => format!("{channel}:{recipient}"),
+                    (Some(channel), None) => channel.clone(),
+                    _ => "log".to_string(),
+                };
+                println!(
+                    "  {:<16} {:<16} next: {:<16} → {target}",
+                    task_name(task, i),
+                    task.cron,
+                    next
+                );
+                println!(
+                    "  {:<16} {}",
+                    "",
+                    crate::util::truncate_with_ellipsis(&task.prompt, 60)
+                );
+            }
+            if !config.schedule.enabled {
+                println!("\n⚠️  [schedule] enabled = false — tasks will not run");
+            }
+            Ok(())
+        }
+        crate::ScheduleCommands::Add {
+            cron,
+            prompt,
+            name,
+            channel,
+            to,
+        } => {
+            let schedule = CronSchedule::parse(&cron)?;
+            if TimeService::from_config(config)
+                .next_cron(&schedule, Utc::now())
+                .is_none()
+            {
+                bail!("Cron expression '{cron}' never fires");
+            }
+            if prompt.trim().is_empty() {
+                bail!("Prompt must not be empty");
+            }
+
+            let mut updated = config.clone();
+            let name = name.unwrap_or_else(|| task_name_for_new(&updated.schedule));
+            if find_task(&updated.schedule, &name).is_ok() {
+                bail!("A scheduled task named '{name}' already exists");
+            }
+            updated.schedule.tasks.push(ScheduledTaskConfig {
+                name: Some(name.clone()),
+                cron,
+                prompt,
+                channel,
+                recipient: to,
+                enabled: true,
+            });
+            updated.save().await?;
+            println!("✅ Added scheduled task '{name}' ({schedule})");
+            println!("   Tasks run while `zeroclaw channel start` is running.");
+            Ok(())
+        }
+        crate::ScheduleCommands::Remove { name } => {
+            let mut updated = config.clone();
+            let index = find_task(&updated.schedule, &name)?;
+            updated.schedule.tasks.remove(index);
+            updated.save().await?;
+            println!("✅ Removed scheduled task '{name}'");
+            Ok(())
+        }
+        crate::ScheduleCommands::RunNow { name } => {
+            let index = find_task(&config.schedule, &name)?;
+            let task = &config.schedule.tasks[index];
+            let channels = crate::channels::configured_channels_by_name(config);
+            let output = Box::pin(run_task(config, task, &channels)).await?;
+            println!("{output}");
+            Ok(())
+        }
+    }
+}
+
+/// First free `task-<n>` name. One of `len + 1` candidates is always free.
+fn task_name_for_new(config: &ScheduleConfig) -> String {
+    let start = config.tasks.len() + 1;
+    (start..=start + config.tasks.len())
+        .map(|n| format!("task-{n}"))
+        .find(|candidate| find_task(config, candidate).is_err())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+
+    fn task(name: Option<&str>, cron: &str) -> ScheduledTaskConfig {
+        ScheduledTaskConfig {
+            name: name.map(str::to_string),
+            cron: cron.into(),
+            prompt: "Summarize my inbox".into(),
+            channel: None,
+            recipient: None,
+            enabled: true,
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: parking_lot::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            self.sent
+                .lock()
+                .push((message.recipient.clone(), message.content.clone()));
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn load_tasks_names_unnamed_tasks_and_skips_disabled_or_invalid() {
+        let mut paused = task(Some("paused"), "0 9 * * *");
+        paused.enabled = false;
+        let config = ScheduleConfig {
+            enabled: true,
+            tasks: vec![
+                task(None, "0 9 * * *"),
+                task(Some("broken"), "not cron"),
+                paused,
+                task(Some("digest"), "@hourly"),
+            ],
+        };
+
+        let names: Vec<String> = load_tasks(&config).into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["task-1", "digest"]);
+        assert_eq!(find_task(&config, "paused").unwrap(), 2);
+        assert!(find_task(&config, "missing").is_err());
+        assert_eq!(task_name_for_new(&config), "task-5");
+    }
+
+    #[test]
+    fn next_due_groups_tasks_firing_together() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 8, 30, 0).unwrap();
+        let config = ScheduleConfig {
+            enabled: true,
+            tasks: vec![
+                task(Some("a"), "0 9 * * *"),
+                task(Some("b"), "0 12 * * *"),
+                task(Some("c"), "0 9 * * mon"),
+                task(Some("never"), "0 0 30 2 *"),
+            ],
+        };
+        let tasks = load_tasks(&config);
+        let time = TimeService::new(Some("UTC")).unwrap();
+
+        let (at, due) = next_due(time, &tasks, now).unwrap();
+        assert_eq!(at, Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap());
+        assert_eq!(due, vec![0, 2]);
+        assert!(next_due(time, &tasks[3..], now).is_none());
+    }
+
+    #[tokio::test]
+    async fn deliver_sends_to_configured_channel_and_recipient() {
+        let recorder = Arc::new(RecordingChannel::default());
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("recording".into(), recorder.clone());
+
+        let mut target = task(Some("digest"), "@daily");
+        deliver(&target, &channels, "no channel, just logged")
+            .await
+            .unwrap();
+
+        target.channel = Some("recording".into());
+        target.recipient = Some("+15550001".into());
+        deliver(&target, &channels, "3 unread").await.unwrap();
+        assert_eq!(
+            recorder.sent.lock().as_slice(),
+            &[("+15550001".to_string(), "3 unread".to_string())]
+        );
+
+        target.channel = Some("telegram".into());
+        let err = deliver(&target, &channels, "x").await.unwrap_err();
+        assert!(err.to_string().contains("not configured"));
+    }
+}
@@ -0,0 +1,480 @@
+//! One-shot reminders set by the agent's `reminder` tool.
+//!
+//! Reminders live in `<workspace>/state/reminders.db` so they survive
+//! restarts. [`run`] fires them from the `zeroclaw channel start` process,
+//! delivering each one to the channel and chat it was set from. Delivered,
+//! failed and cancelled reminders are kept for `zeroclaw reminders list --all`.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::channels::{Channel, SendMessage};
+use crate::config::Config;
+
+const REMINDERS_DB_FILE: &str = "reminders.db";
+/// Health component name reported by the reminder loop.
+const HEALTH_COMPONENT: &str = "reminders";
+/// Longest the loop sleeps before re-checking the table, so reminders added
+/// by another process (or with the clock changed) still fire.
+const MAX_IDLE_SECS: u64 = 60;
+
+pub fn reminders_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(REMINDERS_DB_FILE)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderStatus {
+    Pending,
+    Delivered,
+    Failed,
+    Cancelled,
+}
+
+impl ReminderStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "delivered" => Self::Delivered,
+            "failed" => Self::Failed,
+            "cancelled" => Self::Cancelled,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// Where a reminder is delivered: the chat it was set from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReminderTarget {
+    pub channel: String,
+    pub recipient: String,
+    pub thread_ts: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub id: String,
+    pub message: String,
+    pub due_at: DateTime<Utc>,
+    pub target: ReminderTarget,
+    pub status: ReminderStatus,
+}
+
+impl Reminder {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let due_at: String = row.get(2)?;
+        let status: String = row.get(6)?;
+        Ok(Self {
+            id: row.get(0)?,
+            message: row.get(1)?,
+            due_at: DateTime::parse_from_rfc3339(&due_at)
+                .map(|at| at.with_timezone(&Utc))
+                .unwrap_or_default(),
+            target: ReminderTarget {
+                channel: row.get(3)?,
+                recipient: row.get(4)?,
+                thread_ts: row.get(5)?,
+            },
+            status: ReminderStatus::parse(&status),
+        })
+    }
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, message, due_at, channel, recipient, thread_ts, status FROM reminders";
+
+pub struct ReminderStore {
+    conn: Mutex<Connection>,
+    /// Wakes the delivery loop when a reminder is added or cancelled.
+    changed: Notify,
+}
+
+impl ReminderStore {
+    /// Open (creating if missing) the reminder table at `db_path`.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS reminders (
+                id         TEXT PRIMARY KEY,
+                message    TEXT NOT NULL,
+                due_at     TEXT NOT NULL,
+                channel    TEXT NOT NULL,
+                recipient  TEXT NOT NULL,
+                thread_ts  TEXT,
+                status     TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_reminders_due ON reminders(status, due_at);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            changed: Notify::new(),
+        })
+    }
+
+    /// Open the store for `config`'s workspace.
+    pub fn for_config(config: &Config) -> Result<Self> {
+        Self::open(&reminders_db_path(&config.workspace_dir))
+    }
+
+    pub fn add(
+        &self,
+        message: &str,
+        due_at: DateTime<Utc>,
+        target: ReminderTarget,
+    ) -> Result<Reminder> {
+        if message.trim().is_empty() {
+            bail!("Reminder message must not be empty");
+        }
+        let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        self.conn.lock().execute(
+            "INSERT INTO reminders (id, message, due_at, channel, recipient, thread_ts, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'pending', ?7)",
+            params![
+                id,
+                message,
+                due_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                target.channel,
+                target.recipient,
+                target.thread_ts,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        self.changed.notify_one();
+        Ok(Reminder {
+            id,
+            message: message.to_string(),
+            due_at,
+            target,
+            status: ReminderStatus::Pending,
+        })
+    }
+
+    /// Pending reminders (or all of them with `include_done`), soonest first.
+    pub fn list(&self, include_done: bool) -> Result<Vec<Reminder>> {
+        let filter = if include_done {
+            ""
+        } else {
+            " WHERE status = 'pending'"
+        };
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!("{SELECT_COLUMNS}{filter} ORDER BY due_at"))?;
+        let rows = stmt.query_map([], Reminder::from_row)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Pending reminders for one chat, soonest first.
+    pub fn list_for(&self, target: &ReminderTarget) -> Result<Vec<Reminder>> {
+        Ok(self
+            .list(false)?
+            .into_iter()
+            .filter(|r| {
+                r.target.channel == target.channel && r.target.recipient == target.recipient
+            })
+            .collect())
+    }
+
+    /// Cancel a pending reminder by id (or unique id prefix). Returns it, or
+    /// `None` when no pending reminder matches.
+    pub fn cancel(&self, id: &str) -> Result<Option<Reminder>> {
+        let id = id.trim();
+        if id.is_empty() {
+            bail!("Reminder id must not be empty");
+        }
+        let matches: Vec<Reminder> = self
+            .list(false)?
+            .into_iter()
+            .filter(|r| r.id.starts_with(id))
+            .collect();
+        let reminder = match matches.as_slice() {
+            [] => return Ok(None),
+            [one] => one.clone(),
+            _ => bail!("Reminder id '{id}' is ambiguous; use more characters"),
+        };
+        self.set_status(&reminder.id, ReminderStatus::Cancelled)?;
+        self.changed.notify_one();
+        Ok(Some(reminder))
+    }
+
+    fn set_status(&self, id: &str, status: ReminderStatus) -> Result<()> {
+        self.conn.lock().execute(
+            "UPDATE reminders SET status = ?1 WHERE id = ?2",
+            params![status.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Pending reminders due at or before `now`.
+    fn due(&self, now: DateTime<Utc>) -> Result<Vec<Reminder>> {
+        Ok(self
+            .list(false)?
+            .into_iter()
+            .filter(|r| r.due_at <= now)
+            .collect())
+    }
+
+    /// Due time of the soonest pending reminder.
+    fn next_due_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock();
+        let due_at: Option<String> = conn
+            .query_row(
+                "SELECT due_at FROM reminders WHERE status = 'pending' ORDER BY due_at LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(due_at
+            .and_then(|at| DateTime::parse_from_rfc3339(&at).ok())
+            .map(|at| at.with_timezone(&Utc)))
+    }
+}
+
+fn reminder_text(reminder: &Reminder) -> String {
+    format!("⏰ Reminder: {}", reminder.message)
+}
+
+/// Deliver every reminder due at `now`. A reminder whose channel is not
+/// running, or whose send fails, is marked failed rather than retried.
+async fn deliver_due(
+    store: &ReminderStore,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let due = store.due(now)?;
+    for reminder in &due {
+        let target = &reminder.target;
+        let result = match channels.get(&target.channel) {
+            Some(channel) => channel
+                .send(
+                    &SendMessage::new(reminder_text(reminder), &target.recipient)
+                        .in_thread(target.thread_ts.clone()),
+                )
+                .await
+                .with_context(|| format!("Failed to deliver to {}", target.channel)),
+            None => Err(anyhow::anyhow!(
+                "Channel '{}' is not running",
+                target.channel
+            )),
+        };
+        let status = match result {
+            Ok(()) => {
+                tracing::info!("Delivered reminder {} to {}", reminder.id, target.channel);
+                ReminderStatus::Delivered
+            }
+            Err(e) => {
+                tracing::warn!("Reminder {} failed: {e:#}", reminder.id);
+                crate::health::mark_component_error(
+                    HEALTH_COMPONENT,
+                    format!("{}: {e}", reminder.id),
+                );
+                ReminderStatus::Failed
+            }
+        };
+        store.set_status(&reminder.id, status)?;
+    }
+    Ok(due.len())
+}
+
+/// Fire reminders until the process exits. Sleeps until the next reminder
+/// is due, waking early when one is added or cancelled.
+pub(crate) async fn run(
+    store: Arc<ReminderStore>,
+    channels: Arc<HashMap<String, Arc<dyn Channel>>>,
+) {
+    crate::health::mark_component_ok(HEALTH_COMPONENT);
+    loop {
+        if let Err(e) = deliver_due(&store, &channels, Utc::now()).await {
+            tracing::error!("Reminder delivery failed: {e:#}");
+            crate::health::mark_component_error(HEALTH_COMPONENT, e.to_string());
+        }
+        let idle = Duration::from_secs(MAX_IDLE_SECS);
+        let wait = match store.next_due_at() {
+            Ok(Some(at)) => (at - Utc::now()).to_std().unwrap_or_default().min(idle),
+            Ok(None) | Err(_) => idle,
+        };
+        tokio::select! {
+            () = tokio::time::sleep(wait) => {}
+            () = store.changed.notified() => {}
+        }
+    }
+}
+
+fn format_target(target: &ReminderTarget) -> String {
+    format!("{}:{}", target.channel, target.recipient)
+}
+
+/// `zeroclaw reminders list`.
+pub fn run_list(config: &Config, all: bool) -> Result<()> {
+    let store = ReminderStore::for_config(config)?;
+    let reminders = store.list(all)?;
+    if reminders.is_empty() {
+        println!("No {}reminders.", if all { "" } else { "pending " });
+        println!();
+        println!("Ask the agent in a channel, e.g. \"remind me at 5pm to call Sam\".");
+        return Ok(());
+    }
+    let time = crate::util::time::TimeService::from_config(config);
+    println!("Reminders ({}):\n", reminders.len());
+    for reminder in &reminders {
+        println!(
+            "  {:<9} {:<16} {:<10} → {}",
+            reminder.id,
+            time.localize(reminder.due_at).format("%Y-%m-%d %H:%M"),
+            reminder.status.as_str(),
+            format_target(&reminder.target)
+        );
+        println!(
+            "  {:<9} {}",
+            "",
+            crate::util::truncate_with_ellipsis(&reminder.message, 60)
+        );
+    }
+    Ok(())
+}
+
+/// `zeroclaw reminders cancel <id>`.
+pub fn run_cancel(config: &Config, id: &str) -> Result<()> {
+    let store = ReminderStore::for_config(config)?;
+    match store.cancel(id)? {
+        Some(reminder) => {
+            println!("✅ Cancelled reminder {}", reminder.id);
+            Ok(())
+        }
+        None => bail!("No pending reminder with id '{id}' (see `zeroclaw reminders list`)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use async_trait::async_trait;
+
+    fn target(channel: &str) -> ReminderTarget {
+        ReminderTarget {
+            channel: channel.into(),
+            recipient: "+15550001".into(),
+            thread_ts: None,
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: parking_lot::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            self.sent
+                .lock()
+                .push((message.recipient.clone(), message.content.clone()));
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reminders_persist_and_cancel_by_prefix() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = reminders_db_path(tmp.path());
+        let store = ReminderStore::open(&path).unwrap();
+        let soon = Utc::now() + chrono::Duration::minutes(5);
+        let later = soon + chrono::Duration::hours(1);
+        let second = store.add("stretch", later, target("recording")).unwrap();
+        let first = store.add("call Sam", soon, target("recording")).unwrap();
+        assert!(store.add("  ", soon, target("recording")).is_err());
+
+        let reopened = ReminderStore::open(&path).unwrap();
+        let ids: Vec<String> = reopened
+            .list(false)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec![first.id.clone(), second.id.clone()]);
+        assert_eq!(
+            reopened.next_due_at().unwrap().unwrap().timestamp(),
+            soon.timestamp()
+        );
+
+        let cancelled = reopened.cancel(&first.id[..4]).unwrap().unwrap();
+        assert_eq!(cancelled.id, first.id);
+        assert!(reopened.cancel(&first.id).unwrap().is_none());
+        assert_eq!(reopened.list(false).unwrap().len(), 1);
+        assert_eq!(reopened.list(true).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn due_reminders_are_delivered_once_to_their_chat() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = ReminderStore::open(&reminders_db_path(tmp.path())).unwrap();
+        let recorder = Arc::new(RecordingChannel::default());
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("recording".into(), recorder.clone());
+
+        let now = Utc::now();
+        store
+            .add(
+                "call Sam",
+                now - chrono::Duration::seconds(1),
+                target("recording"),
+            )
+            .unwrap();
+        store
+            .add(
+                "offline",
+                now - chrono::Duration::seconds(1),
+                target("telegram"),
+            )
+            .unwrap();
+        store
+            .add(
+                "tomorrow",
+                now + chrono::Duration::days(1),
+                target("recording"),
+            )
+            .unwrap();
+
+        assert_eq!(deliver_due(&store, &channels, now).await.unwrap(), 2);
+        assert_eq!(deliver_due(&store, &channels, now).await.unwrap(), 0);
+        assert_eq!(
+            recorder.sent.lock().as_slice(),
+            &[("+15550001".to_string(), "⏰ Reminder: call Sam".to_string())]
+        );
+        let statuses: Vec<ReminderStatus> = store
+            .list(true)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.status)
+            .collect();
+        assert!(statuses.contains(&ReminderStatus::Delivered));
+        assert!(statuses.contains(&ReminderStatus::Failed));
+        assert!(statuses.contains(&ReminderStatus::Pending));
+    }
+}
@@ -0,0 +1,256 @@
+//! Append-only security audit log (`[security.audit]`).
+//!
+//! Security-relevant decisions (DM access, approvals, policy blocks) are
+//! written as JSON lines to `<zeroclaw_dir>/<log_path>`. The file is rotated
+//! to `<log_path>.1` once it exceeds `max_size_mb`. With `sign_events`, each
+//! line carries an HMAC-SHA256 over its unsigned JSON, keyed by
+//! `<zeroclaw_dir>/.audit_key`, so edits to past entries are detectable.
+
+use crate::config::AuditConfig;
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const AUDIT_KEY_FILE: &str = ".audit_key";
+
+/// One audit log line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// RFC 3339 timestamp.
+    pub timestamp: String,
+    /// Subsystem that made the decision (e.g. `"dm_policy"`).
+    pub category: String,
+    /// Outcome, e.g. `"allow"`, `"deny"`, `"pairing_challenge"`.
+    pub decision: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Correlation ID of the request that triggered the decision.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Hex HMAC-SHA256 of the event without this field (`sign_events`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(category: impl Into<String>, decision: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            category: category.into(),
+            decision: decision.into(),
+            channel: None,
+            actor: None,
+            detail: None,
+            request_id: crate::observability::correlation::current(),
+            signature: None,
+        }
+    }
+
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// Writes [`AuditEvent`]s to the configured audit log.
+pub struct AuditLogger {
+    path: Option<PathBuf>,
+    max_bytes: u64,
+    signing_key: Option<Vec<u8>>,
+    lock: Mutex<()>,
+}
+
+impl AuditLogger {
+    /// Build a logger for `config`, resolving `log_path` against `zeroclaw_dir`.
+    pub fn new(config: &AuditConfig, zeroclaw_dir: &Path) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+        let signing_key = if config.sign_events {
+            Some(load_or_create_key(&zeroclaw_dir.join(AUDIT_KEY_FILE))?)
+        } else {
+            None
+        };
+        Ok(Self {
+            path: Some(zeroclaw_dir.join(&config.log_path)),
+            max_bytes: u64::from(config.max_size_mb.max(1)) * 1024 * 1024,
+            signing_key,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// A logger that drops every event.
+    pub fn disabled() -> Self {
+        Self {
+            path: None,
+            max_bytes: 0,
+            signing_key: None,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Append `event`, rotating the file first when it is over the size cap.
+    pub fn log(&self, mut event: AuditEvent) -> Result<()> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        if let Some(key) = self.signing_key.as_ref() {
+            event.signature = None;
+            event.signature = Some(sign(key, &serde_json::to_string(&event)?));
+        }
+        let mut line = serde_json::to_string(&event)?;
+        line.push('\n');
+
+        let _guard = self.lock.lock();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::metadata(path).is_ok_and(|meta| meta.len() >= self.max_bytes) {
+            let mut rotated = path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(path, rotated)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Log `event`, reporting failures as warnings instead of errors.
+    pub fn record(&self, event: AuditEvent) {
+        if let Err(error) = self.log(event) {
+            tracing::warn!("Failed to write audit event: {error:#}");
+        }
+    }
+
+    /// Check an event's signature against this logger's key.
+    pub fn verify(&self, event: &AuditEvent) -> bool {
+        let (Some(key), Some(signature)) = (self.signing_key.as_ref(), event.signature.as_ref())
+        else {
+            return false;
+        };
+        let mut unsigned = event.clone();
+        unsigned.signature = None;
+        serde_json::to_string(&unsigned)
+            .is_ok_and(|json| super::pairing::constant_time_eq(&sign(key, &json), signature))
+    }
+}
+
+fn sign(key: &[u8], payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn load_or_create_key(path: &Path) -> Result<Vec<u8>> {
+    if let Ok(existing) = std::fs::read_to_string(path) {
+        return hex::decode(existing.trim())
+            .with_context(|| format!("Corrupt audit key at {}", path.display()));
+    }
+    let key: Vec<u8> = uuid::Uuid::new_v4()
+        .as_bytes()
+        .iter()
+        .chain(uuid::Uuid::new_v4().as_bytes())
+        .copied()
+        .collect();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, hex::encode(&key))
+        .with_context(|| format!("Failed to write audit key {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_events(path: &Path) -> Vec<AuditEvent> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn appends_json_lines_and_skips_when_disabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = AuditLogger::new(&AuditConfig::default(), tmp.path()).unwrap();
+        logger
+            .log(
+                AuditEvent::new("dm_policy", "deny")
+                    .with_channel("whatsapp")
+                    .with_actor("+1555"),
+            )
+            .unwrap();
+        logger.log(AuditEvent::new("dm_policy", "allow")).unwrap();
+
+        let events = read_events(&tmp.path().join("audit.log"));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].channel.as_deref(), Some("whatsapp"));
+        assert!(events[0].signature.is_none());
+
+        let config = AuditConfig {
+            enabled: false,
+            log_path: "off.log".into(),
+            ..AuditConfig::default()
+        };
+        let disabled = AuditLogger::new(&config, tmp.path()).unwrap();
+        disabled.log(AuditEvent::new("dm_policy", "deny")).unwrap();
+        assert!(!disabled.is_enabled());
+        assert!(!tmp.path().join("off.log").exists());
+    }
+
+    #[test]
+    fn signed_events_verify_and_detect_tampering() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = AuditConfig {
+            sign_events: true,
+            ..AuditConfig::default()
+        };
+        let logger = AuditLogger::new(&config, tmp.path()).unwrap();
+        logger
+            .log(AuditEvent::new("dm_policy", "deny").with_actor("mallory"))
+            .unwrap();
+
+        let mut event = read_events(&tmp.path().join("audit.log")).remove(0);
+        assert!(logger.verify(&event));
+        event.decision = "allow".into();
+        assert!(!logger.verify(&event));
+
+        let reloaded = AuditLogger::new(&config, tmp.path()).unwrap();
+        event.decision = "deny".into();
+        assert!(reloaded.verify(&event));
+    }
+}
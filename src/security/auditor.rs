@@ -0,0 +1,405 @@
+//! Config and filesystem security auditor (`zeroclaw security audit`).
+//!
+//! [`ConfigSecurityAuditor`] inspects the loaded config and the files behind
+//! it for common misconfigurations and returns severity-ranked
+//! [`AuditFinding`]s. The CLI exits non-zero when any finding reaches the
+//! `--fail-on` severity, so the command can gate CI and provisioning scripts.
+
+use super::pairing::is_public_bind;
+use super::traits::{AuditFinding, AuditSeverity, DmAccessPolicy, SecurityAuditor};
+use super::SecretStore;
+use crate::config::Config;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Dotted config keys that hold secrets and should be encrypted at rest.
+const SECRET_KEYS: [&str; 9] = [
+    "api_key",
+    "assistant.calendar.password",
+    "assistant.email.password",
+    "channels_config.whatsapp.access_token",
+    "channels_config.whatsapp.app_secret",
+    "channels_config.github.access_token",
+    "channels_config.github.webhook_secret",
+    "transcription.api_key",
+    "tts.api_key",
+];
+
+/// Roots that expose far more than a project directory.
+const BROAD_ROOTS: [&str; 8] = ["/", "~", "~/", "/home", "/Users", "/etc", "/usr", "/var"];
+
+/// Audits the active [`Config`] and its on-disk files.
+pub struct ConfigSecurityAuditor {
+    config: Config,
+}
+
+impl ConfigSecurityAuditor {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+fn finding(
+    severity: AuditSeverity,
+    category: &str,
+    message: impl Into<String>,
+    recommendation: impl Into<String>,
+) -> AuditFinding {
+    AuditFinding {
+        severity,
+        category: category.to_string(),
+        message: message.into(),
+        recommendation: Some(recommendation.into()),
+    }
+}
+
+#[cfg(unix)]
+fn check_config_permissions(path: &Path, findings: &mut Vec<AuditFinding>) {
+    use std::os::unix::fs::PermissionsExt;
+    let Ok(meta) = std::fs::metadata(path) else {
+        return;
+    };
+    let mode = meta.permissions().mode() & 0o777;
+    if mode & 0o022 != 0 {
+        findings.push(finding(
+            AuditSeverity::Critical,
+            "filesystem",
+            format!(
+                "{} is writable by other users (mode {mode:o})",
+                path.display()
+            ),
+            format!("chmod 600 {}", path.display()),
+        ));
+    } else if mode & 0o004 != 0 {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "filesystem",
+            format!("{} is world-readable (mode {mode:o})", path.display()),
+            format!("chmod 600 {}", path.display()),
+        ));
+    }
+}
+
+#[cfg(not(unix))]
+fn check_config_permissions(_path: &Path, _findings: &mut Vec<AuditFinding>) {}
+
+fn lookup<'a>(value: &'a toml::Value, dotted: &str) -> Option<&'a toml::Value> {
+    dotted
+        .split('.')
+        .try_fold(value, |current, segment| current.get(segment))
+}
+
+fn check_plaintext_secrets(config: &Config, findings: &mut Vec<AuditFinding>) {
+    let Ok(raw) = std::fs::read_to_string(&config.config_path) else {
+        return;
+    };
+    let Ok(document) = toml::from_str::<toml::Table>(&raw) else {
+        return;
+    };
+    let document = toml::Value::Table(document);
+    for key in SECRET_KEYS {
+        let Some(value) = lookup(&document, key).and_then(toml::Value::as_str) else {
+            continue;
+        };
+        if value.trim().is_empty() || SecretStore::is_secure_encrypted(value) {
+            continue;
+        }
+        let (severity, recommendation) = if SecretStore::is_encrypted(value) {
+            (
+                AuditSeverity::Warning,
+                "Re-save the config (e.g. `zeroclaw config set`) to migrate to enc2:",
+            )
+        } else {
+            (
+                AuditSeverity::Critical,
+                "Set secrets.encrypt = true and re-save, or move the value to an environment variable",
+            )
+        };
+        findings.push(finding(
+            severity,
+            "secrets",
+            format!("`{key}` is stored without authenticated encryption"),
+            recommendation,
+        ));
+    }
+    if !config.secrets.encrypt {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "secrets",
+            "secrets.encrypt is disabled; secrets are written to config.toml in plaintext",
+            "Set secrets.encrypt = true",
+        ));
+    }
+}
+
+fn check_gateway(config: &Config, findings: &mut Vec<AuditFinding>) {
+    let gateway = &config.gateway;
+    if !is_public_bind(&gateway.host) {
+        if !gateway.require_pairing {
+            findings.push(finding(
+                AuditSeverity::Info,
+                "gateway",
+                "Gateway pairing is disabled (localhost bind only)",
+                "Set gateway.require_pairing = true if other local users share this host",
+            ));
+        }
+        return;
+    }
+    if !gateway.require_pairing {
+        findings.push(finding(
+            AuditSeverity::Critical,
+            "gateway",
+            format!(
+                "Gateway binds to public address {} without pairing",
+                gateway.host
+            ),
+            "Set gateway.require_pairing = true or bind to 127.0.0.1",
+        ));
+    } else if gateway.allow_public_bind {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "gateway",
+            format!("Gateway binds to public address {}", gateway.host),
+            "Prefer a tunnel or reverse proxy in front of a localhost bind",
+        ));
+    }
+    if gateway.trust_forwarded_headers {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "gateway",
+            "Forwarded client IP headers are trusted on a public bind",
+            "Only enable gateway.trust_forwarded_headers behind a trusted reverse proxy",
+        ));
+    }
+}
+
+fn check_autonomy(config: &Config, findings: &mut Vec<AuditFinding>) {
+    let autonomy = &config.autonomy;
+    let home = directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+    for root in &autonomy.allowed_roots {
+        let trimmed = root.trim();
+        let expanded = shellexpand::tilde(trimmed);
+        let is_home = home
+            .as_deref()
+            .is_some_and(|home| Path::new(expanded.as_ref()) == home);
+        if BROAD_ROOTS.contains(&trimmed) || is_home {
+            findings.push(finding(
+                AuditSeverity::Critical,
+                "filesystem",
+                format!("autonomy.allowed_roots includes over-broad root `{trimmed}`"),
+                "Limit allowed_roots to specific project directories",
+            ));
+        }
+    }
+    if !autonomy.workspace_only {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "filesystem",
+            "autonomy.workspace_only is disabled",
+            "Keep workspace_only = true and grant extra directories via allowed_roots",
+        ));
+    }
+    if autonomy.allowed_commands.iter().any(|c| c.trim() == "*") {
+        findings.push(finding(
+            AuditSeverity::Critical,
+            "autonomy",
+            "autonomy.allowed_commands allows every command (`*`)",
+            "List the specific executables the agent needs",
+        ));
+    }
+    if !autonomy.block_high_risk_commands {
+        findings.push(finding(
+            AuditSeverity::Warning,
+            "autonomy",
+            "High-risk shell commands are not blocked",
+            "Set autonomy.block_high_risk_commands = true",
+        ));
+    }
+}
+
+fn check_allowlists(config: &Config, findings: &mut Vec<AuditFinding>) {
+    if let Some(whatsapp) = config.channels_config.whatsapp.as_ref() {
+        let policy = config.channels_config.dm_policies.get("whatsapp");
+        let gated = policy.is_some_and(|p| p.mode != DmAccessPolicy::Allow);
+        if whatsapp.allowed_numbers.iter().any(|n| n == "*") && !gated {
+            findings.push(finding(
+                AuditSeverity::Warning,
+                "channels",
+                "WhatsApp accepts messages from any number",
+                "Restrict channels_config.whatsapp.allowed_numbers or set a DM policy mode of allowlist/pairing",
+            ));
+        } else if whatsapp.allowed_numbers.is_empty() {
+            findings.push(finding(
+                AuditSeverity::Info,
+                "channels",
+                "WhatsApp allowlist is empty; all inbound messages are rejected",
+                "Add numbers to channels_config.whatsapp.allowed_numbers",
+            ));
+        }
+    }
+    if let Some(github) = config.channels_config.github.as_ref() {
+        if github.allowed_users.iter().any(|u| u == "*") {
+            findings.push(finding(
+                AuditSeverity::Warning,
+                "channels",
+                "GitHub channel answers anyone who mentions it",
+                "Restrict channels_config.github.allowed_users to trusted logins",
+            ));
+        }
+    }
+    for (channel, policy) in &config.channels_config.dm_policies {
+        if policy.mode == DmAccessPolicy::Allow && policy.max_messages_per_hour == 0 {
+            findings.push(finding(
+                AuditSeverity::Warning,
+                "channels",
+                format!("DM policy for `{channel}` is open with no rate limit"),
+                "Set max_messages_per_hour or switch mode to allowlist/pairing",
+            ));
+        }
+    }
+    if !config.security.audit.enabled {
+        findings.push(finding(
+            AuditSeverity::Info,
+            "audit",
+            "Security audit logging is disabled",
+            "Set security.audit.enabled = true",
+        ));
+    }
+}
+
+/// Sort findings most severe first, keeping category order stable.
+fn rank(findings: &mut [AuditFinding]) {
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.category.cmp(&b.category))
+    });
+}
+
+#[async_trait]
+impl SecurityAuditor for ConfigSecurityAuditor {
+    async fn audit(&self) -> Result<Vec<AuditFinding>> {
+        let mut findings = Vec::new();
+        check_config_permissions(&self.config.config_path, &mut findings);
+        check_plaintext_secrets(&self.config, &mut findings);
+        check_gateway(&self.config, &mut findings);
+        check_autonomy(&self.config, &mut findings);
+        check_allowlists(&self.config, &mut findings);
+        rank(&mut findings);
+        Ok(findings)
+    }
+
+    fn name(&self) -> &str {
+        "config"
+    }
+}
+
+/// Handle `zeroclaw security audit`.
+pub async fn run_audit(config: &Config, json: bool, fail_on: AuditSeverity) -> Result<()> {
+    let findings = ConfigSecurityAuditor::new(config.clone()).audit().await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&findings)?);
+    } else if findings.is_empty() {
+        println!("✅ No security findings.");
+    } else {
+        println!("🛡️  ZeroClaw Security Audit");
+        println!();
+        for item in &findings {
+            let icon = match item.severity {
+                AuditSeverity::Critical => "❌",
+                AuditSeverity::Warning => "⚠️ ",
+                AuditSeverity::Info => "ℹ️ ",
+            };
+            println!(
+                "  {icon} [{}] {}: {}",
+                item.severity.as_str(),
+                item.category,
+                item.message
+            );
+            if let Some(recommendation) = &item.recommendation {
+                println!("       → {recommendation}");
+            }
+        }
+        println!();
+    }
+
+    let failing = findings.iter().filter(|f| f.severity >= fail_on).count();
+    if failing > 0 {
+        anyhow::bail!(
+            "Security audit failed: {failing} finding(s) at or above {}",
+            fail_on.as_str()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_in(dir: &Path) -> Config {
+        let mut config = Config::default();
+        config.config_path = dir.join("config.toml");
+        config.workspace_dir = dir.join("workspace");
+        config
+    }
+
+    #[tokio::test]
+    async fn default_config_has_no_blocking_findings() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_in(tmp.path());
+        let findings = ConfigSecurityAuditor::new(config).audit().await.unwrap();
+        assert!(
+            findings.iter().all(|f| f.severity == AuditSeverity::Info),
+            "{findings:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn flags_public_gateway_broad_roots_and_plaintext_secrets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config_in(tmp.path());
+        config.gateway.host = "0.0.0.0".into();
+        config.gateway.require_pairing = false;
+        config.autonomy.allowed_roots = vec!["/".into(), "projects".into()];
+        std::fs::write(&config.config_path, "api_key = \"sk-plaintext-value\"\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&config.config_path, std::fs::Permissions::from_mode(0o644))
+                .unwrap();
+        }
+
+        let findings = ConfigSecurityAuditor::new(config).audit().await.unwrap();
+        let has = |category: &str, needle: &str| {
+            findings
+                .iter()
+                .any(|f| f.category == category && f.message.contains(needle))
+        };
+        assert!(has("gateway", "without pairing"));
+        assert!(has("filesystem", "over-broad root `/`"));
+        assert!(has("secrets", "`api_key`"));
+        #[cfg(unix)]
+        assert!(has("filesystem", "world-readable"));
+        assert_eq!(findings[0].severity, AuditSeverity::Critical);
+        assert!(findings
+            .windows(2)
+            .all(|pair| pair[0].severity >= pair[1].severity));
+    }
+
+    #[tokio::test]
+    async fn run_audit_fails_at_threshold() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = config_in(tmp.path());
+        config.autonomy.workspace_only = false;
+
+        assert!(run_audit(&config, true, AuditSeverity::Critical)
+            .await
+            .is_ok());
+        let err = run_audit(&config, true, AuditSeverity::Warning)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("1 finding(s)"));
+    }
+}
@@ -0,0 +1,123 @@
+//! Per-session working directory for the shell and file tools.
+//!
+//! A `cd` in one `shell` call carries over to the next call in the same
+//! session, and `file_read`/`file_write` resolve relative paths against it.
+//! Sessions are keyed by [`with_session`]; channel and gateway turns run
+//! inside it with their sender or session key, and everything else (the CLI
+//! agent) shares one default session. Directories are only stored after
+//! [`SecurityPolicy::is_resolved_path_allowed`](super::SecurityPolicy::is_resolved_path_allowed)
+//! accepts them.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Sessions whose directory is remembered; the least recently changed one
+/// is forgotten (and falls back to the workspace) beyond this.
+const MAX_SESSIONS: usize = 256;
+
+tokio::task_local! {
+    static SESSION: String;
+}
+
+/// Run `future` (one conversation turn) with `key` as its session.
+pub async fn with_session<F: Future>(key: String, future: F) -> F::Output {
+    SESSION.scope(key, future).await
+}
+
+/// Key of the session the current task runs in; empty for the default one.
+pub fn current_session() -> String {
+    SESSION.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Working directories by session. Clones share one table, so the gateway
+/// keeps directories across the policies it builds for each turn.
+#[derive(Debug, Default, Clone)]
+pub struct WorkingDirs {
+    dirs: Arc<Mutex<HashMap<String, (PathBuf, Instant)>>>,
+}
+
+impl WorkingDirs {
+    /// The current session's directory, if it was changed and still exists.
+    pub fn get(&self) -> Option<PathBuf> {
+        let session = current_session();
+        let mut dirs = self.dirs.lock();
+        let dir = dirs.get(&session).map(|(dir, _)| dir.clone())?;
+        if dir.is_dir() {
+            Some(dir)
+        } else {
+            dirs.remove(&session);
+            None
+        }
+    }
+
+    pub fn set(&self, dir: PathBuf) {
+        let mut dirs = self.dirs.lock();
+        dirs.insert(current_session(), (dir, Instant::now()));
+        if dirs.len() > MAX_SESSIONS {
+            if let Some(oldest) = dirs
+                .iter()
+                .min_by_key(|(_, (_, changed))| *changed)
+                .map(|(session, _)| session.clone())
+            {
+                dirs.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Resolve `.` and `..` in `path` without touching the filesystem.
+pub(super) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn directories_are_kept_per_session() {
+        let dirs = WorkingDirs::default();
+        let tmp = tempfile::tempdir().unwrap();
+        let a = tmp.path().join("a");
+        std::fs::create_dir(&a).unwrap();
+
+        with_session("telegram_alice".into(), async { dirs.set(a.clone()) }).await;
+        assert_eq!(
+            with_session("telegram_alice".into(), async { dirs.get() }).await,
+            Some(a.clone())
+        );
+        assert_eq!(
+            with_session("telegram_bob".into(), async { dirs.get() }).await,
+            None
+        );
+        assert_eq!(dirs.get(), None);
+
+        std::fs::remove_dir(&a).unwrap();
+        assert_eq!(
+            with_session("telegram_alice".into(), async { dirs.get() }).await,
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_resolves_dots_lexically() {
+        assert_eq!(
+            normalize(Path::new("/work/src/./../tests/..")),
+            PathBuf::from("/work")
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! DM access policy enforcement for channel senders.
+//!
+//! [`ChannelDmPolicyManager`] evaluates every inbound channel message against
+//! `[channels_config.dm_policies.<channel>]`:
+//!
+//! - `open`: everyone is answered, limited to `max_messages_per_hour` per sender.
+//! - `allowlist`: only allowlisted or previously paired senders are answered.
+//! - `pairing`: unknown senders get a challenge; the operator sees a one-time
+//!   code in the daemon log and hands it to the sender out of band. Replying
+//!   with the code pairs the sender permanently.
+//!
+//! Paired senders persist in `dm_pairings.json` next to `config.toml`,
+//! outside the workspace the agent can write to, and every non-trivial
+//! decision is written to the audit log.
+
+use super::audit::{AuditEvent, AuditLogger};
+use super::pairing::{constant_time_eq, generate_code};
+use super::rate_limit::TokenBucketLimiter;
+use super::traits::{DmAccessPolicy, DmPolicyManager};
+use crate::config::DmPolicyConfig;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// File (in the config directory) holding paired senders per channel.
+pub const DM_PAIRINGS_FILE: &str = "dm_pairings.json";
+
+/// Wrong codes allowed before a sender is locked out and the code rotates.
+const MAX_PAIRING_ATTEMPTS: u32 = 5;
+/// Lockout after too many wrong pairing codes.
+const PAIRING_LOCKOUT: Duration = Duration::from_secs(900);
+/// Bound on tracked senders per rate limiter.
+const MAX_TRACKED_SENDERS: usize = 4096;
+
+/// Outcome of evaluating one inbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DmDecision {
+    /// Process the message normally.
+    Allow,
+    /// Drop the message silently.
+    Deny,
+    /// Drop the message and send `reply` to the sender (pairing challenge or
+    /// confirmation).
+    Reply(String),
+    /// Drop the message; the sender exceeded the open-mode rate limit.
+    RateLimited { retry_after_secs: u64 },
+}
+
+#[derive(Debug)]
+struct PendingPairing {
+    code: String,
+    attempts: u32,
+    locked_until: Option<Instant>,
+}
+
+/// Config-driven [`DmPolicyManager`] used by the channel dispatcher.
+pub struct ChannelDmPolicyManager {
+    policies: HashMap<String, DmPolicyConfig>,
+    limiters: HashMap<String, TokenBucketLimiter>,
+    paired: Mutex<BTreeMap<String, BTreeSet<String>>>,
+    pending: Mutex<HashMap<String, PendingPairing>>,
+    state_path: Option<PathBuf>,
+    audit: Arc<AuditLogger>,
+}
+
+impl ChannelDmPolicyManager {
+    pub fn new(policies: HashMap<String, DmPolicyConfig>, audit: Arc<AuditLogger>) -> Self {
+        let limiters = policies
+            .iter()
+            .filter(|(_, policy)| policy.max_messages_per_hour > 0)
+            .map(|(channel, policy)| {
+                (
+                    channel.clone(),
+                    TokenBucketLimiter::new(
+                        policy.max_messages_per_hour,
+                        Duration::from_secs(3600),
+                        MAX_TRACKED_SENDERS,
+                    ),
+                )
+            })
+            .collect();
+        Self {
+            policies,
+            limiters,
+            paired: Mutex::new(BTreeMap::new()),
+            pending: Mutex::new(HashMap::new()),
+            state_path: None,
+            audit,
+        }
+    }
+
+    /// Persist paired senders in `config_dir`, loading any saved ones.
+    pub fn with_config_dir(mut self, config_dir: &Path) -> Self {
+        let path = config_dir.join(DM_PAIRINGS_FILE);
+        if let Ok(raw) = std::fs::read_to_string(&path) {
+            match serde_json::from_str(&raw) {
+                Ok(saved) => *self.paired.lock() = saved,
+                Err(error) => tracing::warn!("Ignoring corrupt {}: {error}", path.display()),
+            }
+        }
+        self.state_path = Some(path);
+        self
+    }
+
+    fn is_listed(&self, channel: &str, sender: &str) -> bool {
+        self.policies.get(channel).is_some_and(|policy| {
+            policy
+                .allowlist
+                .iter()
+                .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(sender))
+        }) || self
+            .paired
+            .lock()
+            .get(channel)
+            .is_some_and(|senders| senders.contains(sender))
+    }
+
+    fn audit(&self, channel: &str, sender: &str, decision: &str, detail: Option<String>) {
+        let mut event = AuditEvent::new("dm_policy", decision)
+            .with_channel(channel)
+            .with_actor(sender);
+        event.detail = detail;
+        self.audit.record(event);
+    }
+
+    /// Decide whether `sender`'s message on `channel` reaches the agent.
+    pub fn evaluate(&self, channel: &str, sender: &str, content: &str) -> DmDecision {
+        let Some(policy) = self.policies.get(channel) else {
+            return DmDecision::Allow;
+        };
+        let listed = self.is_listed(channel, sender);
+        match policy.mode {
+            DmAccessPolicy::Allow => {
+                if listed {
+                    return DmDecision::Allow;
+                }
+                let Some(limiter) = self.limiters.get(channel) else {
+                    return DmDecision::Allow;
+                };
+                let decision = limiter.check(sender);
+                if decision.is_allowed() {
+                    DmDecision::Allow
+                } else {
+                    self.audit(channel, sender, "rate_limited", None);
+                    DmDecision::RateLimited {
+                        retry_after_secs: decision.retry_after_secs(),
+                    }
+                }
+            }
+            DmAccessPolicy::Block => {
+                if listed {
+                    DmDecision::Allow
+                } else {
+                    self.audit(channel, sender, "deny", Some("not on allowlist".into()));
+                    DmDecision::Deny
+                }
+            }
+            DmAccessPolicy::PairingRequired => {
+                if listed {
+                    DmDecision::Allow
+                } else {
+                    self.challenge(channel, sender, content)
+                }
+            }
+        }
+    }
+
+    fn challenge(&self, channel: &str, sender: &str, content: &str) -> DmDecision {
+        let key = format!("{channel}:{sender}");
+        let mut pending = self.pending.lock();
+        let entry = pending.entry(key).or_insert_with(|| {
+            let code = generate_code();
+            tracing::warn!(
+                channel,
+                sender,
+                "🔐 DM pairing requested; share code {code} with this sender to approve"
+            );
+            println!("  🔐 DM pairing code for {sender} on {channel}: {code}");
+            self.audit(channel, sender, "pairing_challenge", None);
+            PendingPairing {
+                code,
+                attempts: 0,
+                locked_until: None,
+            }
+        });
+
+        if let Some(until) = entry.locked_until {
+            if Instant::now() < until {
+                return DmDecision::Deny;
+            }
+            entry.locked_until = None;
+            entry.attempts = 0;
+        }
+
+        let candidate = content.trim();
+        if constant_time_eq(candidate, &entry.code) {
+            pending.remove(&format!("{channel}:{sender}"));
+            drop(pending);
+            self.approve(channel, sender);
+            return DmDecision::Reply("✅ Paired. You can now message this assistant.".into());
+        }
+
+        let looks_like_code =
+            candidate.len() == entry.code.len() && candidate.chars().all(|c| c.is_ascii_digit());
+        if looks_like_code {
+            entry.attempts += 1;
+            if entry.attempts >= MAX_PAIRING_ATTEMPTS {
+                entry.locked_until = Some(Instant::now() + PAIRING_LOCKOUT);
+                entry.code = generate_code();
+                tracing::warn!(
+                    channel,
+                    sender,
+                    "DM pairing locked after {MAX_PAIRING_ATTEMPTS} wrong codes; new code {}",
+                    entry.code
+                );
+                self.audit(channel, sender, "pairing_lockout", None);
+                return DmDecision::Deny;
+            }
+            self.audit(channel, sender, "pairing_failed", None);
+            return DmDecision::Reply("❌ That pairing code is not valid.".into());
+        }
+
+        DmDecision::Reply(
+            "🔐 This assistant requires pairing. Ask its operator for your pairing code and \
+             reply with it here."
+                .into(),
+        )
+    }
+
+    /// Pair `sender` on `channel` and persist the pairing.
+    pub fn approve(&self, channel: &str, sender: &str) {
+        let snapshot = {
+            let mut paired = self.paired.lock();
+            paired
+                .entry(channel.to_string())
+                .or_default()
+                .insert(sender.to_string());
+            paired.clone()
+        };
+        self.audit(channel, sender, "paired", None);
+        self.persist(&snapshot);
+    }
+
+    fn persist(&self, snapshot: &BTreeMap<String, BTreeSet<String>>) {
+        let Some(path) = self.state_path.as_ref() else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| {
+                let json = serde_json::to_string_pretty(snapshot).map_err(std::io::Error::other)?;
+                std::fs::write(path, json)
+            });
+        if let Err(error) = result {
+            tracing::warn!("Failed to persist {}: {error}", path.display());
+        }
+    }
+}
+
+impl DmPolicyManager for ChannelDmPolicyManager {
+    fn get_policy(&self, channel: &str) -> DmAccessPolicy {
+        self.policies
+            .get(channel)
+            .map(|policy| policy.mode)
+            .unwrap_or_default()
+    }
+
+    fn set_policy(&mut self, channel: &str, policy: DmAccessPolicy) {
+        self.policies.entry(channel.to_string()).or_default().mode = policy;
+    }
+
+    fn is_allowed(&self, channel: &str, sender: &str) -> bool {
+        self.get_policy(channel) == DmAccessPolicy::Allow || self.is_listed(channel, sender)
+    }
+
+    fn name(&self) -> &str {
+        "config"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(mode: DmAccessPolicy, allowlist: &[&str], per_hour: u32) -> ChannelDmPolicyManager {
+        let policy = DmPolicyConfig {
+            mode,
+            allowlist: allowlist.iter().map(|s| (*s).to_string()).collect(),
+            max_messages_per_hour: per_hour,
+        };
+        ChannelDmPolicyManager::new(
+            HashMap::from([("whatsapp".to_string(), policy)]),
+            Arc::new(AuditLogger::disabled()),
+        )
+    }
+
+    #[test]
+    fn allowlist_mode_admits_only_listed_senders() {
+        let m = manager(DmAccessPolicy::Block, &["+1555"], 0);
+        assert_eq!(m.evaluate("whatsapp", "+1555", "hi"), DmDecision::Allow);
+        assert_eq!(m.evaluate("whatsapp", "+1666", "hi"), DmDecision::Deny);
+        assert_eq!(m.evaluate("cli", "anyone", "hi"), DmDecision::Allow);
+        assert!(!m.is_allowed("whatsapp", "+1666"));
+    }
+
+    #[test]
+    fn open_mode_rate_limits_unlisted_senders() {
+        let m = manager(DmAccessPolicy::Allow, &["+1555"], 2);
+        assert_eq!(m.evaluate("whatsapp", "+1666", "a"), DmDecision::Allow);
+        assert_eq!(m.evaluate("whatsapp", "+1666", "b"), DmDecision::Allow);
+        assert!(matches!(
+            m.evaluate("whatsapp", "+1666", "c"),
+            DmDecision::RateLimited { retry_after_secs } if retry_after_secs > 0
+        ));
+        for _ in 0..5 {
+            assert_eq!(m.evaluate("whatsapp", "+1555", "x"), DmDecision::Allow);
+        }
+    }
+
+    #[test]
+    fn pairing_mode_challenges_then_pairs_and_persists() {
+        let tmp = tempfile::tempdir().unwrap();
+        let m = manager(DmAccessPolicy::PairingRequired, &[], 0).with_config_dir(tmp.path());
+
+        let DmDecision::Reply(challenge) = m.evaluate("whatsapp", "+1666", "hello") else {
+            panic!("expected pairing challenge");
+        };
+        assert!(challenge.contains("requires pairing"));
+        let code = m.pending.lock()["whatsapp:+1666"].code.clone();
+
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        assert!(matches!(
+            m.evaluate("whatsapp", "+1666", wrong),
+            DmDecision::Reply(reply) if reply.contains("not valid")
+        ));
+        assert!(matches!(
+            m.evaluate("whatsapp", "+1666", &code),
+            DmDecision::Reply(reply) if reply.contains("Paired")
+        ));
+        assert_eq!(m.evaluate("whatsapp", "+1666", "hi"), DmDecision::Allow);
+        assert!(tmp.path().join(DM_PAIRINGS_FILE).exists());
+
+        let reloaded = manager(DmAccessPolicy::PairingRequired, &[], 0).with_config_dir(tmp.path());
+        assert_eq!(
+            reloaded.evaluate("whatsapp", "+1666", "hi"),
+            DmDecision::Allow
+        );
+    }
+
+    #[test]
+    fn pairing_locks_out_after_repeated_wrong_codes() {
+        let m = manager(DmAccessPolicy::PairingRequired, &[], 0);
+        m.evaluate("whatsapp", "+1666", "hello");
+        let code = m.pending.lock()["whatsapp:+1666"].code.clone();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        for _ in 0..MAX_PAIRING_ATTEMPTS - 1 {
+            m.evaluate("whatsapp", "+1666", wrong);
+        }
+        assert_eq!(m.evaluate("whatsapp", "+1666", wrong), DmDecision::Deny);
+        assert_eq!(m.evaluate("whatsapp", "+1666", &code), DmDecision::Deny);
+    }
+}
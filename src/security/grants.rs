@@ -0,0 +1,302 @@
+//! Time-limited filesystem grants.
+//!
+//! A grant ("allow ~/projects/foo for 1 hour") lets the file and shell tools
+//! reach a directory outside the workspace without permanently broadening
+//! `[autonomy].allowed_roots`. Grants are approved with `zeroclaw grants add`
+//! and stored in `path_grants.json` next to `config.toml` and the audit log,
+//! outside the workspace the agent can write to. The file is read once when
+//! the [`SecurityPolicy`](super::SecurityPolicy) is built and consulted by
+//! [`SecurityPolicy::is_resolved_path_allowed`](super::SecurityPolicy::is_resolved_path_allowed)
+//! after the workspace and allowed roots, so running agents pick up new
+//! grants and revocations when they restart. Expired grants stop applying
+//! immediately and are pruned on the next write.
+
+use super::audit::{AuditEvent, AuditLogger};
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File (in the config directory) holding path grants.
+pub const PATH_GRANTS_FILE: &str = "path_grants.json";
+
+/// One approved path grant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathGrant {
+    pub id: String,
+    /// Canonical directory (or file) the grant covers, including everything
+    /// below it.
+    pub path: PathBuf,
+    pub granted_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl PathGrant {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// Path grants loaded from the config directory.
+#[derive(Debug, Clone, Default)]
+pub struct PathGrants {
+    /// `None` (the default) never grants anything.
+    state_path: Option<PathBuf>,
+    grants: Vec<PathGrant>,
+}
+
+impl PathGrants {
+    /// Load the grants stored in `config_dir`.
+    pub fn load(config_dir: &Path) -> Self {
+        let state_path = config_dir.join(PATH_GRANTS_FILE);
+        let grants = Self::read(&state_path);
+        Self {
+            state_path: Some(state_path),
+            grants,
+        }
+    }
+
+    /// Load the grants stored next to `config.toml`.
+    pub fn for_config(config: &Config) -> Self {
+        config
+            .config_path
+            .parent()
+            .map(Self::load)
+            .unwrap_or_default()
+    }
+
+    fn read(path: &Path) -> Vec<PathGrant> {
+        let Ok(raw) = std::fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&raw).unwrap_or_else(|error| {
+            tracing::warn!("Ignoring corrupt {}: {error}", path.display());
+            Vec::new()
+        })
+    }
+
+    fn save(&mut self, grants: Vec<PathGrant>) -> Result<()> {
+        let Some(path) = &self.state_path else {
+            bail!("Path grants need a config directory");
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&grants)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        self.grants = grants;
+        Ok(())
+    }
+
+    /// Unexpired grants, soonest expiry first.
+    pub fn active(&self) -> Vec<PathGrant> {
+        let now = Utc::now();
+        let mut grants: Vec<PathGrant> = self
+            .grants
+            .iter()
+            .filter(|grant| grant.is_active(now))
+            .cloned()
+            .collect();
+        grants.sort_by_key(|grant| grant.expires_at);
+        grants
+    }
+
+    /// Whether an unexpired grant covers the canonical path `resolved`.
+    pub fn covers(&self, resolved: &Path) -> bool {
+        let now = Utc::now();
+        self.grants
+            .iter()
+            .any(|grant| grant.is_active(now) && resolved.starts_with(&grant.path))
+    }
+
+    /// Grant `path` (which must exist) for `ttl`, pruning expired grants.
+    pub fn grant(
+        &mut self,
+        path: &Path,
+        ttl: chrono::Duration,
+        reason: Option<String>,
+    ) -> Result<PathGrant> {
+        if ttl <= chrono::Duration::zero() {
+            bail!("Grant duration must be positive");
+        }
+        let canonical = path
+            .canonicalize()
+            .with_context(|| format!("Cannot grant {}: path not found", path.display()))?;
+        let now = Utc::now();
+        let grant = PathGrant {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            path: canonical,
+            granted_at: now,
+            expires_at: now + ttl,
+            reason,
+        };
+        let mut grants = self.active();
+        grants.push(grant.clone());
+        self.save(grants)?;
+        Ok(grant)
+    }
+
+    /// Revoke unexpired grants whose id starts with `id` (or all with
+    /// `"all"`), returning the revoked grants.
+    pub fn revoke(&mut self, id: &str) -> Result<Vec<PathGrant>> {
+        let id = id.trim();
+        if id.is_empty() {
+            bail!("Grant id must not be empty");
+        }
+        let (revoked, kept): (Vec<_>, Vec<_>) = self
+            .active()
+            .into_iter()
+            .partition(|grant| id == "all" || grant.id.starts_with(id));
+        if id != "all" && revoked.len() > 1 {
+            bail!("Grant id '{id}' is ambiguous; use more characters");
+        }
+        if !revoked.is_empty() {
+            self.save(kept)?;
+        }
+        Ok(revoked)
+    }
+}
+
+fn audit_logger(config: &Config) -> AuditLogger {
+    let zeroclaw_dir = config
+        .config_path
+        .parent()
+        .map_or_else(|| config.workspace_dir.clone(), PathBuf::from);
+    AuditLogger::new(&config.security.audit, &zeroclaw_dir).unwrap_or_else(|error| {
+        tracing::warn!("Audit log unavailable, grant changes won't be recorded: {error:#}");
+        AuditLogger::disabled()
+    })
+}
+
+fn record(config: &Config, decision: &str, grant: &PathGrant) {
+    let mut event = AuditEvent::new("path_grant", decision).with_actor("cli");
+    event.detail = Some(format!(
+        "{} {} until {}",
+        grant.id,
+        grant.path.display(),
+        grant.expires_at.to_rfc3339()
+    ));
+    audit_logger(config).record(event);
+}
+
+/// `zeroclaw grants add <path> --for <duration>`.
+pub fn run_add(config: &Config, path: &str, ttl: &str, reason: Option<String>) -> Result<()> {
+    let ttl = crate::infra::logging::parse_since(ttl)
+        .map_err(|_| anyhow::anyhow!("Invalid --for '{ttl}' (expected e.g. 30m, 1h, 2d)"))?;
+    let path = super::policy::expand_user_path(path);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+    let grant = PathGrants::for_config(config).grant(&path, ttl, reason)?;
+    record(config, "grant", &grant);
+    let time = crate::util::time::TimeService::from_config(config);
+    println!(
+        "✅ Granted {} until {} (id {})",
+        grant.path.display(),
+        time.localize(grant.expires_at).format("%Y-%m-%d %H:%M"),
+        grant.id
+    );
+    Ok(())
+}
+
+/// `zeroclaw grants list`.
+pub fn run_list(config: &Config) -> Result<()> {
+    let grants = PathGrants::for_config(config).active();
+    if grants.is_empty() {
+        println!("No active path grants.");
+        println!();
+        println!("Grant one with e.g. `zeroclaw grants add ~/projects/foo --for 1h`.");
+        return Ok(());
+    }
+    let time = crate::util::time::TimeService::from_config(config);
+    println!("Path grants ({}):\n", grants.len());
+    for grant in &grants {
+        println!(
+            "  {:<9} until {:<16} {}",
+            grant.id,
+            time.localize(grant.expires_at).format("%Y-%m-%d %H:%M"),
+            grant.path.display()
+        );
+        if let Some(reason) = &grant.reason {
+            println!("  {:<9} {reason}", "");
+        }
+    }
+    Ok(())
+}
+
+/// `zeroclaw grants revoke <id>`.
+pub fn run_revoke(config: &Config, id: &str) -> Result<()> {
+    let revoked = PathGrants::for_config(config).revoke(id)?;
+    if revoked.is_empty() {
+        bail!("No active grant with id '{id}' (see `zeroclaw grants list`)");
+    }
+    for grant in &revoked {
+        record(config, "revoke", grant);
+        println!("✅ Revoked grant {} ({})", grant.id, grant.path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grants_cover_subpaths_until_they_expire_or_are_revoked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let project = project.canonicalize().unwrap();
+        let mut grants = PathGrants::load(&tmp.path().join("config"));
+
+        assert!(!grants.covers(&project.join("src")));
+        let grant = grants
+            .grant(
+                &project,
+                chrono::Duration::hours(1),
+                Some("refactor".into()),
+            )
+            .unwrap();
+        assert!(grants.covers(&project.join("src")));
+        assert!(!grants.covers(tmp.path()));
+        assert!(!PathGrants::default().covers(&project));
+        assert_eq!(
+            PathGrants::load(&tmp.path().join("config")).active(),
+            vec![grant.clone()]
+        );
+
+        // Expired grants stop applying and are dropped on the next write.
+        let mut stored = grants.grants.clone();
+        stored[0].expires_at = Utc::now() - chrono::Duration::seconds(1);
+        grants.save(stored).unwrap();
+        assert!(!grants.covers(&project));
+        assert!(grants.active().is_empty());
+
+        let second = grants
+            .grant(&project, chrono::Duration::minutes(5), None)
+            .unwrap();
+        assert_eq!(grants.grants.len(), 1);
+        assert!(grants.revoke(&grant.id).unwrap().is_empty());
+        assert_eq!(grants.revoke(&second.id[..4]).unwrap(), vec![second]);
+        assert!(!grants.covers(&project));
+
+        assert!(grants
+            .grant(
+                &tmp.path().join("missing"),
+                chrono::Duration::hours(1),
+                None
+            )
+            .is_err());
+        assert!(grants
+            .grant(&project, chrono::Duration::zero(), None)
+            .is_err());
+    }
+}
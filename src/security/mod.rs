@@ -3,12 +3,25 @@
 //! This module provides the security infrastructure for ZeroClaw. The core type
 //! [`SecurityPolicy`] defines autonomy levels, workspace boundaries, and
 //! access-control rules that are enforced across the tool and runtime subsystems.
-//! [`PairingGuard`] implements device pairing for channel authentication, and
-//! [`SecretStore`] handles encrypted credential storage.
+//! [`PairingGuard`] implements device pairing for channel authentication,
+//! [`SecretStore`] handles encrypted credential storage, and [`redaction`]
+//! scrubs secrets from everything sent to providers and channels.
+//! [`dm_policy`] gates channel senders, recording decisions via [`audit`].
+//! [`cwd`] keeps each session's working directory for the shell and file
+//! tools. [`simulate`] replays hypothetical tool calls against the policy, and
+//! [`grants`] holds time-limited path grants outside the workspace.
 
+pub mod audit;
+pub mod auditor;
+pub mod cwd;
+pub mod dm_policy;
+pub mod grants;
 pub mod pairing;
 pub mod policy;
+pub mod rate_limit;
+pub mod redaction;
 pub mod secrets;
+pub mod simulate;
 pub mod traits;
 
 #[allow(unused_imports)]
@@ -7,12 +7,17 @@
 //
 // Already-paired tokens are persisted in config so restarts don't require
 // re-pairing.
+//
+// Additional devices are paired through a short-lived pairing window opened
+// by `zeroclaw pair`: the window carries its own one-time code and an optional
+// device label that is stored alongside the issued token.
 
+use crate::config::PairedToken;
 use parking_lot::Mutex;
 use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Maximum failed pairing attempts before lockout.
 const MAX_PAIR_ATTEMPTS: u32 = 5;
@@ -24,6 +29,29 @@ const MAX_TRACKED_CLIENTS: usize = 1024;
 /// Per-client failed attempt counter with optional lockout timestamp.
 type FailedAttempts = HashMap<String, (u32, Option<Instant>)>;
 
+/// A short-lived pairing window opened on demand (e.g. by `zeroclaw pair`).
+#[derive(Debug)]
+struct PairingWindow {
+    code: String,
+    label: Option<String>,
+    expires_at: Instant,
+    /// Set once a client pairs through this window; the code is then spent.
+    paired: bool,
+}
+
+/// Observable state of the most recent pairing window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PairingWindowStatus {
+    /// No window has been opened.
+    Closed,
+    /// Waiting for a client to present the code.
+    Open { remaining_secs: u64 },
+    /// A client paired through the window.
+    Paired { label: Option<String> },
+    /// The window timed out before anyone paired.
+    Expired,
+}
+
 /// Manages pairing state for the gateway.
 ///
 /// Bearer tokens are stored as SHA-256 hashes to prevent plaintext exposure
@@ -36,10 +64,12 @@ pub struct PairingGuard {
     require_pairing: bool,
     /// One-time pairing code (generated on startup, consumed on first pair).
     pairing_code: Arc<Mutex<Option<String>>>,
-    /// Set of SHA-256 hashed bearer tokens (persisted across restarts).
-    paired_tokens: Arc<Mutex<HashSet<String>>>,
+    /// Paired tokens keyed by SHA-256 hash (persisted across restarts).
+    paired_tokens: Arc<Mutex<HashMap<String, PairedToken>>>,
     /// Brute-force protection: per-client failed attempt counter + lockout time.
     failed_attempts: Arc<Mutex<FailedAttempts>>,
+    /// Most recent on-demand pairing window, if any.
+    window: Arc<Mutex<Option<PairingWindow>>>,
 }
 
 impl PairingGuard {
@@ -52,14 +82,29 @@ impl PairingGuard {
     /// - Plaintext (`zc_...`): hashed on load for backward compatibility
     /// - Already hashed (64-char hex): stored as-is
     pub fn new(require_pairing: bool, existing_tokens: &[String]) -> Self {
-        let tokens: HashSet<String> = existing_tokens
+        let records: Vec<PairedToken> = existing_tokens
+            .iter()
+            .map(|t| PairedToken::from(t.as_str()))
+            .collect();
+        Self::from_records(require_pairing, &records)
+    }
+
+    /// Create a pairing guard from persisted `gateway.paired_tokens` entries,
+    /// keeping their labels and creation timestamps.
+    pub fn from_records(require_pairing: bool, existing_tokens: &[PairedToken]) -> Self {
+        let tokens: HashMap<String, PairedToken> = existing_tokens
             .iter()
-            .map(|t| {
-                if is_token_hash(t) {
-                    t.clone()
+            .map(|entry| {
+                let hash = if is_token_hash(&entry.token) {
+                    entry.token.clone()
                 } else {
-                    hash_token(t)
-                }
+                    hash_token(&entry.token)
+                };
+                let record = PairedToken {
+                    token: hash.clone(),
+                    ..entry.clone()
+                };
+                (hash, record)
             })
             .collect();
         let code = if require_pairing && tokens.is_empty() {
@@ -72,6 +117,7 @@ impl PairingGuard {
             pairing_code: Arc::new(Mutex::new(code)),
             paired_tokens: Arc::new(Mutex::new(tokens)),
             failed_attempts: Arc::new(Mutex::new(HashMap::new())),
+            window: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -85,6 +131,48 @@ impl PairingGuard {
         self.require_pairing
     }
 
+    /// Open a pairing window with a fresh one-time code, replacing any
+    /// previous window. Tokens issued through it carry `label`.
+    pub fn open_window(&self, label: Option<String>, ttl: Duration) -> String {
+        let code = generate_code();
+        *self.window.lock() = Some(PairingWindow {
+            code: code.clone(),
+            label,
+            expires_at: Instant::now() + ttl,
+            paired: false,
+        });
+        code
+    }
+
+    /// Current state of the most recent pairing window.
+    pub fn window_status(&self) -> PairingWindowStatus {
+        match self.window.lock().as_ref() {
+            None => PairingWindowStatus::Closed,
+            Some(w) if w.paired => PairingWindowStatus::Paired {
+                label: w.label.clone(),
+            },
+            Some(w) => match w.expires_at.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => PairingWindowStatus::Open {
+                    remaining_secs: remaining.as_secs().max(1),
+                },
+                _ => PairingWindowStatus::Expired,
+            },
+        }
+    }
+
+    /// Issue a new token and remember its metadata. Returns the plaintext.
+    fn issue_token(&self, label: Option<String>) -> String {
+        let token = generate_token();
+        let hash = hash_token(&token);
+        let record = PairedToken {
+            token: hash.clone(),
+            label,
+            created_at: Some(chrono::Utc::now().to_rfc3339()),
+        };
+        self.paired_tokens.lock().insert(hash, record);
+        token
+    }
+
     fn try_pair_blocking(&self, code: &str, client_id: &str) -> Result<Option<String>, u64> {
         // Check brute force lockout for this specific client
         {
@@ -108,9 +196,7 @@ impl PairingGuard {
                         let mut attempts = self.failed_attempts.lock();
                         attempts.remove(client_id);
                     }
-                    let token = generate_token();
-                    let mut tokens = self.paired_tokens.lock();
-                    tokens.insert(hash_token(&token));
+                    let token = self.issue_token(None);
 
                     // Consume the pairing code so it cannot be reused
                     *pairing_code = None;
@@ -120,6 +206,20 @@ impl PairingGuard {
             }
         }
 
+        {
+            let mut window = self.window.lock();
+            if let Some(w) = window.as_mut() {
+                let live = !w.paired && Instant::now() < w.expires_at;
+                if live && constant_time_eq(code.trim(), w.code.trim()) {
+                    self.failed_attempts.lock().remove(client_id);
+                    let token = self.issue_token(w.label.clone());
+                    // Spend the window code; status now reports Paired
+                    w.paired = true;
+                    return Ok(Some(token));
+                }
+            }
+        }
+
         // Increment failed attempts for this client
         {
             let mut attempts = self.failed_attempts.lock();
@@ -171,7 +271,7 @@ impl PairingGuard {
         }
         let hashed = hash_token(token);
         let tokens = self.paired_tokens.lock();
-        tokens.contains(&hashed)
+        tokens.contains_key(&hashed)
     }
 
     /// Returns true if the gateway is already paired (has at least one token).
@@ -183,12 +283,25 @@ impl PairingGuard {
     /// Get all paired token hashes (for persisting to config).
     pub fn tokens(&self) -> Vec<String> {
         let tokens = self.paired_tokens.lock();
-        tokens.iter().cloned().collect()
+        tokens.keys().cloned().collect()
+    }
+
+    /// Get all paired tokens with their labels, oldest first (for persisting
+    /// to config). Entries without a timestamp predate labeling and sort first.
+    pub fn token_records(&self) -> Vec<PairedToken> {
+        let tokens = self.paired_tokens.lock();
+        let mut records: Vec<PairedToken> = tokens.values().cloned().collect();
+        records.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.token.cmp(&b.token))
+        });
+        records
     }
 }
 
 /// Generate a 6-digit numeric pairing code using cryptographically secure randomness.
-fn generate_code() -> String {
+pub(crate) fn generate_code() -> String {
     // UUID v4 uses getrandom (backed by /dev/urandom on Linux, BCryptGenRandom
     // on Windows) — a CSPRNG. We extract 4 bytes from it for a uniform random
     // number in [0, 1_000_000).
@@ -366,6 +479,63 @@ mod tests {
         assert!(!guard.is_authenticated("wrong"));
     }
 
+    // ── Pairing window ───────────────────────────────────────
+
+    #[test]
+    async fn window_code_pairs_once_with_label() {
+        let guard = PairingGuard::new(true, &["zc_existing".into()]);
+        assert_eq!(guard.window_status(), PairingWindowStatus::Closed);
+
+        let code = guard.open_window(Some("phone".into()), Duration::from_secs(60));
+        assert!(matches!(
+            guard.window_status(),
+            PairingWindowStatus::Open { .. }
+        ));
+
+        let token = guard.try_pair(&code, "device").await.unwrap().unwrap();
+        assert!(guard.is_authenticated(&token));
+        assert_eq!(
+            guard.window_status(),
+            PairingWindowStatus::Paired {
+                label: Some("phone".into())
+            }
+        );
+        // The window code is spent after one successful pair
+        assert!(guard.try_pair(&code, "device").await.unwrap().is_none());
+
+        let labeled = guard
+            .token_records()
+            .into_iter()
+            .find(|r| r.token == hash_token(&token))
+            .unwrap();
+        assert_eq!(labeled.label.as_deref(), Some("phone"));
+        assert!(labeled.created_at.is_some());
+    }
+
+    #[test]
+    async fn expired_window_rejects_code() {
+        let guard = PairingGuard::new(true, &["zc_existing".into()]);
+        let code = guard.open_window(None, Duration::ZERO);
+        assert_eq!(guard.window_status(), PairingWindowStatus::Expired);
+        assert!(guard.try_pair(&code, "device").await.unwrap().is_none());
+    }
+
+    #[test]
+    async fn from_records_preserves_metadata_and_hashes_plaintext() {
+        let records = vec![PairedToken {
+            token: "zc_valid".into(),
+            label: Some("laptop".into()),
+            created_at: Some("2026-01-01T00:00:00Z".into()),
+        }];
+        let guard = PairingGuard::from_records(true, &records);
+        assert!(guard.is_authenticated("zc_valid"));
+
+        let stored = guard.token_records();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].token, hash_token("zc_valid"));
+        assert_eq!(stored[0].label.as_deref(), Some("laptop"));
+    }
+
     // ── Token hashing ────────────────────────────────────────
 
     #[test]
@@ -1,3 +1,5 @@
+use super::cwd::{normalize, WorkingDirs};
+use super::grants::PathGrants;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -91,7 +93,11 @@ pub struct SecurityPolicy {
     pub require_approval_for_medium_risk: bool,
     pub block_high_risk_commands: bool,
     pub shell_env_passthrough: Vec<String>,
+    pub env_files: Vec<PathBuf>,
     pub tracker: ActionTracker,
+    pub working_dirs: WorkingDirs,
+    /// Time-limited extra roots approved with `zeroclaw grants add`.
+    pub grants: PathGrants,
 }
 
 impl Default for SecurityPolicy {
@@ -143,7 +149,10 @@ impl Default for SecurityPolicy {
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
             shell_env_passthrough: vec![],
+            env_files: vec![],
             tracker: ActionTracker::new(),
+            working_dirs: WorkingDirs::default(),
+            grants: PathGrants::default(),
         }
     }
 }
@@ -152,7 +161,7 @@ fn home_dir() -> Option<PathBuf> {
     std::env::var_os("HOME").map(PathBuf::from)
 }
 
-fn expand_user_path(path: &str) -> PathBuf {
+pub(crate) fn expand_user_path(path: &str) -> PathBuf {
     if path == "~" {
         if let Some(home) = home_dir() {
             return home;
@@ -696,8 +705,14 @@ impl SecurityPolicy {
     /// - Blocks shell redirections (`<`, `>`, `>>`) that can bypass path policy
     /// - Blocks dangerous arguments (e.g. `find -exec`, `git config`)
     pub fn is_command_allowed(&self, command: &str) -> bool {
+        self.command_denial(command).is_none()
+    }
+
+    /// The rule that makes [`is_command_allowed`](Self::is_command_allowed)
+    /// reject `command`, or `None` when it is allowed.
+    pub fn command_denial(&self, command: &str) -> Option<String> {
         if self.autonomy == AutonomyLevel::ReadOnly {
-            return false;
+            return Some("autonomy.level = \"readonly\" blocks all shell commands".into());
         }
 
         // Block subshell/expansion operators — these allow hiding arbitrary
@@ -710,14 +725,16 @@ impl SecurityPolicy {
             || command.contains("<(")
             || command.contains(">(")
         {
-            return false;
+            return Some(
+                "subshells and variable expansion (`, $(, ${, $VAR, <(, >() are blocked".into(),
+            );
         }
 
         // Block shell redirections (`<`, `>`, `>>`) — they can read/write
         // arbitrary paths and bypass path checks.
         // Ignore quoted literals, e.g. `echo "a>b"` and `echo "a<b"`.
         if contains_unquoted_char(command, '>') || contains_unquoted_char(command, '<') {
-            return false;
+            return Some("shell redirections (<, >, >>) are blocked".into());
         }
 
         // Block `tee` — it can write to arbitrary files, bypassing the
@@ -726,13 +743,13 @@ impl SecurityPolicy {
             .split_whitespace()
             .any(|w| w == "tee" || w.ends_with("/tee"))
         {
-            return false;
+            return Some("`tee` is blocked because it writes arbitrary files".into());
         }
 
         // Block background command chaining (`&`), which can hide extra
         // sub-commands and outlive timeout expectations. Keep `&&` allowed.
         if contains_unquoted_single_ampersand(command) {
-            return false;
+            return Some("background chaining with `&` is blocked".into());
         }
 
         // Split on unquoted command separators and validate each sub-command.
@@ -745,7 +762,8 @@ impl SecurityPolicy {
             let base_raw = words.next().unwrap_or("");
             let base_cmd = base_raw.rsplit('/').next().unwrap_or("");
 
-            if base_cmd.is_empty() {
+            // `cd` is a shell builtin; `cd_target_violation` checks where it goes.
+            if base_cmd.is_empty() || base_raw == "cd" {
                 continue;
             }
 
@@ -754,13 +772,15 @@ impl SecurityPolicy {
                 .iter()
                 .any(|allowed| allowed == base_cmd)
             {
-                return false;
+                return Some(format!("`{base_cmd}` is not in autonomy.allowed_commands"));
             }
 
             // Validate arguments for the command
             let args: Vec<String> = words.map(|w| w.to_ascii_lowercase()).collect();
             if !self.is_args_safe(base_cmd, &args) {
-                return false;
+                return Some(format!(
+                    "`{base_cmd}` arguments that run sub-commands or change config are blocked"
+                ));
             }
         }
 
@@ -770,7 +790,7 @@ impl SecurityPolicy {
             s.split_whitespace().next().is_some_and(|w| !w.is_empty())
         });
 
-        has_cmd
+        (!has_cmd).then(|| "no command to run".into())
     }
 
     /// Check for dangerous arguments that allow sub-command execution.
@@ -819,6 +839,10 @@ impl SecurityPolicy {
             let Some(executable) = words.next() else {
                 continue;
             };
+            // `cd ..` is fine; `cd_target_violation` resolves `cd` targets.
+            if executable == "cd" {
+                continue;
+            }
 
             // Cover inline forms like `cat</etc/passwd`.
             if let Some(target) = redirection_target(strip_wrapping_quotes(executable)) {
@@ -871,9 +895,15 @@ impl SecurityPolicy {
 
     /// Check if a file path is allowed (no path traversal, within workspace)
     pub fn is_path_allowed(&self, path: &str) -> bool {
+        self.path_denial(path).is_none()
+    }
+
+    /// The rule that makes [`is_path_allowed`](Self::is_path_allowed) reject
+    /// `path`, or `None` when it is allowed.
+    pub fn path_denial(&self, path: &str) -> Option<String> {
         // Block null bytes (can truncate paths in C-backed syscalls)
         if path.contains('\0') {
-            return false;
+            return Some("paths with null bytes are blocked".into());
         }
 
         // Block path traversal: check for ".." as a path component
@@ -881,19 +911,19 @@ impl SecurityPolicy {
             .components()
             .any(|c| matches!(c, std::path::Component::ParentDir))
         {
-            return false;
+            return Some("`..` path traversal is blocked".into());
         }
 
         // Block URL-encoded traversal attempts (e.g. ..%2f)
         let lower = path.to_lowercase();
         if lower.contains("..%2f") || lower.contains("%2f..") {
-            return false;
+            return Some("URL-encoded path traversal is blocked".into());
         }
 
         // Reject "~user" forms because the shell expands them at runtime and
         // they can escape workspace policy.
         if path.starts_with('~') && path != "~" && !path.starts_with("~/") {
-            return false;
+            return Some("`~user` paths are blocked".into());
         }
 
         // Expand "~" for consistent matching with forbidden paths and allowlists.
@@ -901,21 +931,22 @@ impl SecurityPolicy {
 
         // Block absolute paths when workspace_only is set
         if self.workspace_only && expanded_path.is_absolute() {
-            return false;
+            return Some("absolute paths are blocked while autonomy.workspace_only = true".into());
         }
 
         // Block forbidden paths using path-component-aware matching
         for forbidden in &self.forbidden_paths {
             let forbidden_path = expand_user_path(forbidden);
             if expanded_path.starts_with(forbidden_path) {
-                return false;
+                return Some(format!("`{forbidden}` is in autonomy.forbidden_paths"));
             }
         }
 
-        true
+        None
     }
 
-    /// Validate that a resolved path is inside the workspace or an allowed root.
+    /// Validate that a resolved path is inside the workspace, an allowed root,
+    /// or an active path grant.
     /// Call this AFTER joining `workspace_dir` + relative path and canonicalizing.
     pub fn is_resolved_path_allowed(&self, resolved: &Path) -> bool {
         // Must be under workspace_dir (prevents symlink escapes).
@@ -937,14 +968,15 @@ impl SecurityPolicy {
             }
         }
 
-        false
+        // Finally, unexpired `zeroclaw grants` entries.
+        self.grants.covers(resolved)
     }
 
     pub fn resolved_path_violation_message(&self, resolved: &Path) -> String {
         let guidance = if self.allowed_roots.is_empty() {
-            "Add the directory to [autonomy].allowed_roots (for example: allowed_roots = [\"/absolute/path\"]), or move the file into the workspace."
+            "Add the directory to [autonomy].allowed_roots (for example: allowed_roots = [\"/absolute/path\"]), grant temporary access with `zeroclaw grants add <dir> --for 1h`, or move the file into the workspace."
         } else {
-            "Add a matching parent directory to [autonomy].allowed_roots, or move the file into the workspace."
+            "Add a matching parent directory to [autonomy].allowed_roots, grant temporary access with `zeroclaw grants add <dir> --for 1h`, or move the file into the workspace."
         };
 
         format!(
@@ -954,6 +986,74 @@ impl SecurityPolicy {
         )
     }
 
+    // ── Working Directory ──────────────────────────────────────────────
+    // Each session keeps the directory its last `cd` ended in. Targets are
+    // checked before a command runs and the directory it actually ends in
+    // is checked again before it is stored, so the working directory never
+    // leaves the workspace or allowed roots.
+
+    /// Directory the current session's shell commands run in and relative
+    /// tool paths resolve against: the last `cd` target, else the workspace.
+    pub fn current_dir(&self) -> PathBuf {
+        self.working_dirs
+            .get()
+            .unwrap_or_else(|| self.workspace_dir.clone())
+    }
+
+    /// Resolve a relative tool path against [`current_dir`](Self::current_dir).
+    pub fn resolve_tool_path(&self, path: &str) -> PathBuf {
+        self.current_dir().join(path)
+    }
+
+    /// Store `dir` as the session's working directory. Returns `false` (and
+    /// keeps the previous one) unless it is a directory inside the workspace
+    /// or an allowed root.
+    pub fn set_current_dir(&self, dir: &Path) -> bool {
+        let Ok(resolved) = dir.canonicalize() else {
+            return false;
+        };
+        if !resolved.is_dir() || !self.is_resolved_path_allowed(&resolved) {
+            return false;
+        }
+        self.working_dirs.set(resolved);
+        true
+    }
+
+    /// Return the first `cd` target in `command` that leaves the workspace
+    /// and allowed roots, following earlier `cd`s from `cwd`. A bare `cd`
+    /// means `$HOME` and `cd -` an unknown directory, so both are rejected
+    /// unless they resolve inside the allowlist.
+    pub fn cd_target_violation(&self, command: &str, cwd: &Path) -> Option<String> {
+        let mut dir = cwd.to_path_buf();
+        for segment in split_unquoted_segments(command) {
+            let mut words = skip_env_assignments(&segment).split_whitespace();
+            if words.next() != Some("cd") {
+                continue;
+            }
+            let target = words
+                .map(strip_wrapping_quotes)
+                .find(|word| !matches!(*word, "-L" | "-P" | "-e" | "-LP" | "-PL"))
+                .unwrap_or("~");
+            if target == "-" {
+                return Some(target.to_string());
+            }
+            let next = normalize(&dir.join(expand_user_path(target)));
+            let resolved = next.canonicalize().unwrap_or_else(|_| next.clone());
+            if !self.is_resolved_path_allowed(&resolved) {
+                return Some(target.to_string());
+            }
+            dir = next;
+        }
+        None
+    }
+
+    /// Whether any segment of `command` runs `cd`.
+    pub fn changes_directory(command: &str) -> bool {
+        split_unquoted_segments(command)
+            .iter()
+            .any(|segment| skip_env_assignments(segment).split_whitespace().next() == Some("cd"))
+    }
+
     /// Check if autonomy level permits any action at all
     pub fn can_act(&self) -> bool {
         self.autonomy != AutonomyLevel::ReadOnly
@@ -1031,9 +1131,37 @@ impl SecurityPolicy {
             require_approval_for_medium_risk: autonomy_config.require_approval_for_medium_risk,
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
             shell_env_passthrough: autonomy_config.shell_env_passthrough.clone(),
+            env_files: autonomy_config
+                .env_files
+                .iter()
+                .map(|path| {
+                    let expanded = expand_user_path(path);
+                    if expanded.is_absolute() {
+                        expanded
+                    } else {
+                        workspace_dir.join(expanded)
+                    }
+                })
+                .collect(),
             tracker: ActionTracker::new(),
+            working_dirs: WorkingDirs::default(),
+            grants: PathGrants::default(),
         }
     }
+
+    /// Apply path grants loaded with [`PathGrants::for_config`].
+    #[must_use]
+    pub fn with_path_grants(mut self, grants: PathGrants) -> Self {
+        self.grants = grants;
+        self
+    }
+
+    /// Share working directories with earlier turns (see [`WorkingDirs`]).
+    #[must_use]
+    pub fn with_working_dirs(mut self, working_dirs: WorkingDirs) -> Self {
+        self.working_dirs = working_dirs;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -1403,6 +1531,21 @@ mod tests {
         assert_eq!(policy.allowed_roots[1], workspace.join("shared-data"));
     }
 
+    #[test]
+    fn from_config_resolves_env_files() {
+        let autonomy_config = crate::config::AutonomyConfig {
+            env_files: vec![".env".into(), "/etc/project.env".into()],
+            ..crate::config::AutonomyConfig::default()
+        };
+        let workspace = PathBuf::from("/tmp/test-workspace");
+        let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
+
+        assert_eq!(
+            policy.env_files,
+            vec![workspace.join(".env"), PathBuf::from("/etc/project.env")]
+        );
+    }
+
     #[test]
     fn resolved_path_violation_message_includes_allowed_roots_guidance() {
         let p = default_policy();
@@ -1660,6 +1803,38 @@ mod tests {
         assert!(!p.is_command_allowed("FOO=bar rm -rf /"));
     }
 
+    #[test]
+    fn cd_targets_are_checked_against_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir(workspace.join("src")).unwrap();
+        let p = SecurityPolicy {
+            workspace_dir: workspace.clone(),
+            ..SecurityPolicy::default()
+        };
+        assert!(p.is_command_allowed("cd src && ls"));
+        assert_eq!(p.forbidden_path_argument("cd .. && ls"), None);
+
+        assert_eq!(p.cd_target_violation("cd src && cd ..", &workspace), None);
+        assert_eq!(p.cd_target_violation("cd ..", &workspace.join("src")), None);
+        assert_eq!(
+            p.cd_target_violation("cd src; cd ../..", &workspace),
+            Some("../..".into())
+        );
+        assert_eq!(
+            p.cd_target_violation("ls && cd /etc", &workspace),
+            Some("/etc".into())
+        );
+        assert_eq!(p.cd_target_violation("cd -", &workspace), Some("-".into()));
+        assert!(SecurityPolicy::changes_directory("FOO=1 cd src"));
+        assert!(!SecurityPolicy::changes_directory("echo cd"));
+
+        assert!(p.set_current_dir(&workspace.join("src")));
+        assert_eq!(p.current_dir(), workspace.join("src"));
+        assert!(!p.set_current_dir(Path::new("/")));
+        assert_eq!(p.resolve_tool_path("a.rs"), workspace.join("src/a.rs"));
+    }
+
     #[test]
     fn forbidden_path_argument_detects_absolute_path() {
         let p = default_policy();
@@ -2158,6 +2333,67 @@ mod tests {
         let _ = std::fs::remove_dir_all(&root);
     }
 
+    #[test]
+    fn path_grants_are_loaded_when_the_policy_is_built() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_dir = tmp.path().join("config");
+        let workspace = config_dir.join("workspace");
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        let target = project.canonicalize().unwrap();
+        let build = || {
+            SecurityPolicy::from_config(&crate::config::AutonomyConfig::default(), &workspace)
+                .with_path_grants(PathGrants::load(&config_dir))
+        };
+
+        let before = build();
+        let mut grants = PathGrants::load(&config_dir);
+        let grant = grants
+            .grant(&project, chrono::Duration::hours(1), None)
+            .unwrap();
+        assert!(!before.is_resolved_path_allowed(&target));
+
+        let granted = build();
+        assert!(granted.is_resolved_path_allowed(&target));
+
+        grants.revoke(&grant.id).unwrap();
+        assert!(!build().is_resolved_path_allowed(&target));
+    }
+
+    #[test]
+    fn path_grants_written_inside_the_workspace_are_ignored() {
+        use crate::security::grants::PATH_GRANTS_FILE;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config_dir = tmp.path().join("config");
+        let workspace = config_dir.join("workspace");
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(workspace.join("state")).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        let target = project.canonicalize().unwrap();
+
+        // What an agent could write with file_write inside its workspace.
+        let now = chrono::Utc::now();
+        let forged = serde_json::json!([{
+            "id": "forged01",
+            "path": target,
+            "granted_at": now,
+            "expires_at": now + chrono::Duration::hours(1),
+        }]);
+        for path in [
+            workspace.join("state").join(PATH_GRANTS_FILE),
+            workspace.join(PATH_GRANTS_FILE),
+        ] {
+            std::fs::write(path, forged.to_string()).unwrap();
+        }
+
+        let policy =
+            SecurityPolicy::from_config(&crate::config::AutonomyConfig::default(), &workspace)
+                .with_path_grants(PathGrants::load(&config_dir));
+        assert!(!policy.is_resolved_path_allowed(&target));
+    }
+
     #[test]
     fn is_path_allowed_blocks_null_bytes() {
         let policy = default_policy();
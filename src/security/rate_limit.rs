@@ -0,0 +1,403 @@
+//! Token-bucket rate limiting shared by the gateway and channels.
+//!
+//! Each key (client IP, paired-token hash, ...) owns a bucket holding up to
+//! `limit` tokens that refills continuously at `limit / window`, so a client
+//! may burst up to the full limit and then proceeds at the sustained rate.
+//! Buckets are kept in LRU order; once `max_keys` buckets exist, the least
+//! recently used key is evicted. A limit of zero disables the limiter.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How often full (idle) buckets are swept from the map.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Outcome of a rate limit check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RateDecision {
+    Allowed,
+    /// Rejected; a token becomes available after `retry_after`.
+    Limited {
+        retry_after: Duration,
+    },
+}
+
+impl RateDecision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed)
+    }
+
+    /// Whole seconds for a `Retry-After` header (at least 1 when limited).
+    pub fn retry_after_secs(&self) -> u64 {
+        match self {
+            Self::Allowed => 0,
+            Self::Limited { retry_after } => {
+                let partial = u64::from(retry_after.subsec_nanos() > 0);
+                (retry_after.as_secs() + partial).max(1)
+            }
+        }
+    }
+}
+
+/// Cumulative counters for one limiter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RateLimitStats {
+    pub allowed: u64,
+    pub limited: u64,
+    pub evicted: u64,
+    pub tracked_keys: usize,
+}
+
+/// Persisted bucket state, so restarts don't hand every client a fresh burst.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketSnapshot {
+    pub key: String,
+    pub tokens: f64,
+    /// Unix time (milliseconds) at which `tokens` was measured.
+    pub updated_at_ms: u64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    lru_tick: u64,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    map: HashMap<String, Bucket>,
+    /// LRU index: access tick → key (oldest first).
+    lru: BTreeMap<u64, String>,
+    tick: u64,
+    last_sweep: Option<Instant>,
+}
+
+/// Keyed token-bucket limiter with bounded cardinality.
+#[derive(Debug)]
+pub struct TokenBucketLimiter {
+    limit: u32,
+    capacity: f64,
+    refill_per_sec: f64,
+    max_keys: usize,
+    buckets: Mutex<Buckets>,
+    allowed: AtomicU64,
+    limited: AtomicU64,
+    evicted: AtomicU64,
+}
+
+fn unix_millis(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+impl TokenBucketLimiter {
+    /// Allow `limit` requests per `window` per key, tracking at most
+    /// `max_keys` keys.
+    pub fn new(limit: u32, window: Duration, max_keys: usize) -> Self {
+        let capacity = f64::from(limit);
+        let refill_per_sec = if window.is_zero() {
+            f64::INFINITY
+        } else {
+            capacity / window.as_secs_f64()
+        };
+        Self {
+            limit,
+            capacity,
+            refill_per_sec,
+            max_keys: max_keys.max(1),
+            buckets: Mutex::new(Buckets::default()),
+            allowed: AtomicU64::new(0),
+            limited: AtomicU64::new(0),
+            evicted: AtomicU64::new(0),
+        }
+    }
+
+    /// Tokens a bucket would hold at `now` after refilling.
+    fn refilled_tokens(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        (bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity)
+    }
+
+    /// Drop buckets that have refilled completely; they are indistinguishable
+    /// from a fresh bucket.
+    fn sweep_full(&self, buckets: &mut Buckets, now: Instant) {
+        let Buckets { map, lru, .. } = buckets;
+        map.retain(|_, bucket| {
+            let full = self.refilled_tokens(bucket, now) >= self.capacity;
+            if full {
+                lru.remove(&bucket.lru_tick);
+            }
+            !full
+        });
+    }
+
+    /// Touch (or create) the bucket for `key`, evicting LRU keys as needed.
+    fn touch<'a>(&self, buckets: &'a mut Buckets, key: &str, now: Instant) -> &'a mut Bucket {
+        buckets.tick += 1;
+        let tick = buckets.tick;
+
+        if let Some(bucket) = buckets.map.get_mut(key) {
+            buckets.lru.remove(&bucket.lru_tick);
+            bucket.lru_tick = tick;
+        } else {
+            if buckets.map.len() >= self.max_keys {
+                // Opportunistic cleanup before evicting live buckets.
+                self.sweep_full(buckets, now);
+            }
+            while buckets.map.len() >= self.max_keys {
+                let Some((_, oldest)) = buckets.lru.pop_first() else {
+                    break;
+                };
+                buckets.map.remove(&oldest);
+                self.evicted.fetch_add(1, Ordering::Relaxed);
+            }
+            buckets.map.insert(
+                key.to_owned(),
+                Bucket {
+                    tokens: self.capacity,
+                    refilled_at: now,
+                    lru_tick: tick,
+                },
+            );
+        }
+        buckets.lru.insert(tick, key.to_owned());
+        buckets.map.get_mut(key).expect("bucket exists after touch")
+    }
+
+    /// Take one token for `key`.
+    pub fn check(&self, key: &str) -> RateDecision {
+        if self.limit == 0 {
+            return RateDecision::Allowed;
+        }
+
+        let now = Instant::now();
+        let mut guard = self.buckets.lock();
+        let buckets = &mut *guard;
+
+        if buckets
+            .last_sweep
+            .is_none_or(|at| now.saturating_duration_since(at) >= SWEEP_INTERVAL)
+        {
+            self.sweep_full(buckets, now);
+            buckets.last_sweep = Some(now);
+        }
+
+        let refill_per_sec = self.refill_per_sec;
+        let bucket = self.touch(buckets, key, now);
+        bucket.tokens = self.refilled_tokens(bucket, now);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            self.allowed.fetch_add(1, Ordering::Relaxed);
+            RateDecision::Allowed
+        } else {
+            self.limited.fetch_add(1, Ordering::Relaxed);
+            let wait = (1.0 - bucket.tokens) / refill_per_sec;
+            RateDecision::Limited {
+                retry_after: Duration::from_secs_f64(wait),
+            }
+        }
+    }
+
+    /// Convenience wrapper around [`check`](Self::check).
+    pub fn allow(&self, key: &str) -> bool {
+        self.check(key).is_allowed()
+    }
+
+    pub fn stats(&self) -> RateLimitStats {
+        RateLimitStats {
+            allowed: self.allowed.load(Ordering::Relaxed),
+            limited: self.limited.load(Ordering::Relaxed),
+            evicted: self.evicted.load(Ordering::Relaxed),
+            tracked_keys: self.buckets.lock().map.len(),
+        }
+    }
+
+    /// Export buckets that are not yet full, oldest access first.
+    pub fn snapshot(&self) -> Vec<BucketSnapshot> {
+        let now = Instant::now();
+        let wall_now = SystemTime::now();
+        let guard = self.buckets.lock();
+        guard
+            .lru
+            .values()
+            .filter_map(|key| {
+                let bucket = guard.map.get(key)?;
+                let tokens = self.refilled_tokens(bucket, now);
+                (tokens < self.capacity).then(|| BucketSnapshot {
+                    key: key.clone(),
+                    tokens,
+                    updated_at_ms: unix_millis(wall_now),
+                })
+            })
+            .collect()
+    }
+
+    /// Load persisted buckets, crediting the refill accrued since they were
+    /// saved. Entries that would already be full are skipped.
+    pub fn restore(&self, snapshots: &[BucketSnapshot]) {
+        if self.limit == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let wall_now_ms = unix_millis(SystemTime::now());
+        let mut guard = self.buckets.lock();
+        for snap in snapshots {
+            let age = Duration::from_millis(wall_now_ms.saturating_sub(snap.updated_at_ms));
+            let tokens =
+                (snap.tokens.max(0.0) + age.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+            if !tokens.is_finite() || tokens >= self.capacity {
+                continue;
+            }
+            let bucket = self.touch(&mut guard, &snap.key, now);
+            bucket.tokens = tokens;
+            bucket.refilled_at = now;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_up_to_limit_then_limited() {
+        let limiter = TokenBucketLimiter::new(2, Duration::from_secs(60), 100);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-1"));
+        let decision = limiter.check("ip-1");
+        assert!(!decision.is_allowed());
+        // One token refills every 30s at 2/min
+        let secs = decision.retry_after_secs();
+        assert!((29..=30).contains(&secs), "unexpected retry_after {secs}");
+    }
+
+    #[test]
+    fn refills_after_window() {
+        let limiter = TokenBucketLimiter::new(5, Duration::from_millis(50), 100);
+        for _ in 0..5 {
+            assert!(limiter.allow("burst-ip"));
+        }
+        assert!(!limiter.allow("burst-ip"));
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(limiter.allow("burst-ip"));
+    }
+
+    #[test]
+    fn zero_limit_always_allows() {
+        let limiter = TokenBucketLimiter::new(0, Duration::from_secs(60), 10);
+        for _ in 0..100 {
+            assert!(limiter.allow("any-key"));
+        }
+        assert_eq!(limiter.stats().tracked_keys, 0);
+    }
+
+    #[test]
+    fn keys_are_independent() {
+        let limiter = TokenBucketLimiter::new(1, Duration::from_secs(60), 100);
+        assert!(limiter.allow("ip-1"));
+        assert!(!limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+    }
+
+    #[test]
+    fn evicts_least_recently_used_key_at_capacity() {
+        let limiter = TokenBucketLimiter::new(10, Duration::from_secs(60), 3);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+        assert!(limiter.allow("ip-3"));
+        // Touch ip-1 so ip-2 becomes the LRU entry
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-4"));
+
+        let guard = limiter.buckets.lock();
+        assert_eq!(guard.map.len(), 3);
+        assert_eq!(guard.lru.len(), 3);
+        assert!(!guard.map.contains_key("ip-2"), "ip-2 should be evicted");
+        assert!(guard.map.contains_key("ip-1"));
+        assert!(guard.map.contains_key("ip-4"));
+        drop(guard);
+        assert_eq!(limiter.stats().evicted, 1);
+    }
+
+    #[test]
+    fn sweep_removes_full_buckets() {
+        let limiter = TokenBucketLimiter::new(10, Duration::from_millis(10), 100);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+        std::thread::sleep(Duration::from_millis(20));
+
+        // Force the next check to sweep
+        limiter.buckets.lock().last_sweep =
+            Instant::now().checked_sub(SWEEP_INTERVAL + Duration::from_secs(1));
+        assert!(limiter.allow("ip-3"));
+
+        let guard = limiter.buckets.lock();
+        assert_eq!(guard.map.len(), 1);
+        assert!(guard.map.contains_key("ip-3"));
+        assert_eq!(guard.lru.len(), 1);
+    }
+
+    #[test]
+    fn stats_count_allowed_and_limited() {
+        let limiter = TokenBucketLimiter::new(1, Duration::from_secs(60), 10);
+        assert!(limiter.allow("a"));
+        assert!(!limiter.allow("a"));
+        assert!(!limiter.allow("a"));
+        let stats = limiter.stats();
+        assert_eq!(stats.allowed, 1);
+        assert_eq!(stats.limited, 2);
+        assert_eq!(stats.tracked_keys, 1);
+    }
+
+    #[test]
+    fn snapshot_restore_preserves_spent_budget() {
+        let limiter = TokenBucketLimiter::new(2, Duration::from_secs(60), 10);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-1"));
+        let snapshot = limiter.snapshot();
+        assert_eq!(snapshot.len(), 1);
+
+        let restored = TokenBucketLimiter::new(2, Duration::from_secs(60), 10);
+        restored.restore(&snapshot);
+        assert!(!restored.allow("ip-1"), "restart must not reset the budget");
+        assert!(restored.allow("ip-2"));
+    }
+
+    #[test]
+    fn restore_skips_entries_that_have_refilled() {
+        let limiter = TokenBucketLimiter::new(2, Duration::from_secs(60), 10);
+        limiter.restore(&[BucketSnapshot {
+            key: "old".into(),
+            tokens: 0.0,
+            updated_at_ms: unix_millis(SystemTime::now()) - 120_000,
+        }]);
+        assert_eq!(limiter.stats().tracked_keys, 0);
+    }
+
+    #[test]
+    fn concurrent_access_respects_max_keys() {
+        use std::sync::Arc;
+
+        let limiter = Arc::new(TokenBucketLimiter::new(1000, Duration::from_secs(60), 1000));
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let limiter = limiter.clone();
+            handles.push(std::thread::spawn(move || {
+                for j in 0..200 {
+                    limiter.allow(&format!("thread-{i}-req-{j}"));
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(limiter.stats().tracked_keys <= 1000);
+    }
+}
@@ -0,0 +1,283 @@
+//! Policy simulation (`zeroclaw policy simulate`).
+//!
+//! Runs hypothetical shell commands and file paths through the same
+//! [`SecurityPolicy`] checks the `shell`, `file_read` and `file_write` tools
+//! apply and reports the rule that decided each one, so users can see why
+//! the agent was blocked. Nothing is executed and no action budget is spent.
+
+use super::cwd::normalize;
+use super::policy::{AutonomyLevel, CommandRiskLevel, SecurityPolicy};
+use crate::config::Config;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Outcome of a simulated tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Allow,
+    Deny,
+    ApprovalRequired,
+}
+
+impl Decision {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::ApprovalRequired => "approval_required",
+        }
+    }
+}
+
+/// Decision for one simulated tool call and the rule that produced it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Verdict {
+    pub tool: &'static str,
+    pub input: String,
+    pub decision: Decision,
+    pub rule: String,
+}
+
+fn risk_name(risk: CommandRiskLevel) -> &'static str {
+    match risk {
+        CommandRiskLevel::Low => "low",
+        CommandRiskLevel::Medium => "medium",
+        CommandRiskLevel::High => "high",
+    }
+}
+
+fn autonomy_name(level: AutonomyLevel) -> &'static str {
+    match level {
+        AutonomyLevel::ReadOnly => "readonly",
+        AutonomyLevel::Supervised => "supervised",
+        AutonomyLevel::Full => "full",
+    }
+}
+
+/// Evaluate `command` as a `shell` call. Hard denials are reported before
+/// approval requirements, since approving would not let the command run.
+pub fn simulate_command(policy: &SecurityPolicy, command: &str, approved: bool) -> Verdict {
+    let verdict = |decision, rule: String| Verdict {
+        tool: "shell",
+        input: command.to_string(),
+        decision,
+        rule,
+    };
+
+    if let Some(rule) = policy.command_denial(command) {
+        return verdict(Decision::Deny, rule);
+    }
+
+    let risk = policy.command_risk_level(command);
+    let supervised = policy.autonomy == AutonomyLevel::Supervised;
+    let approval_rule = match risk {
+        CommandRiskLevel::High if policy.block_high_risk_commands => {
+            return verdict(
+                Decision::Deny,
+                "autonomy.block_high_risk_commands = true blocks high-risk commands".into(),
+            );
+        }
+        CommandRiskLevel::High if supervised => {
+            Some("autonomy.level = \"supervised\" requires approval for high-risk commands")
+        }
+        CommandRiskLevel::Medium if supervised && policy.require_approval_for_medium_risk => Some(
+            "autonomy.require_approval_for_medium_risk = true requires approval for \
+             medium-risk commands",
+        ),
+        _ => None,
+    };
+
+    if let Some(path) = policy.forbidden_path_argument(command) {
+        let reason = policy.path_denial(&path).unwrap_or_default();
+        return verdict(Decision::Deny, format!("path argument `{path}`: {reason}"));
+    }
+    if let Some(target) = policy.cd_target_violation(command, &policy.workspace_dir) {
+        return verdict(
+            Decision::Deny,
+            format!("cd target `{target}` is outside the workspace and autonomy.allowed_roots"),
+        );
+    }
+
+    match approval_rule {
+        Some(rule) if !approved => verdict(Decision::ApprovalRequired, rule.into()),
+        Some(rule) => verdict(Decision::Allow, format!("approved; {rule}")),
+        None => verdict(
+            Decision::Allow,
+            format!(
+                "allowed by autonomy.allowed_commands ({} risk)",
+                risk_name(risk)
+            ),
+        ),
+    }
+}
+
+/// Evaluate `path` as a `file_read` and a `file_write` call.
+pub fn simulate_path(policy: &SecurityPolicy, path: &str) -> [Verdict; 2] {
+    let verdict = |tool, decision, rule: String| Verdict {
+        tool,
+        input: path.to_string(),
+        decision,
+        rule,
+    };
+
+    let denial = policy.path_denial(path).or_else(|| {
+        let full = policy.resolve_tool_path(path);
+        let resolved = full.canonicalize().unwrap_or_else(|_| normalize(&full));
+        (!policy.is_resolved_path_allowed(&resolved))
+            .then(|| policy.resolved_path_violation_message(&resolved))
+    });
+    let allowed = "inside the workspace, autonomy.allowed_roots or a path grant".to_string();
+
+    let read = match &denial {
+        Some(rule) => verdict("file_read", Decision::Deny, rule.clone()),
+        None => verdict("file_read", Decision::Allow, allowed.clone()),
+    };
+    let write = if policy.can_act() {
+        match denial {
+            Some(rule) => verdict("file_write", Decision::Deny, rule),
+            None => verdict("file_write", Decision::Allow, allowed),
+        }
+    } else {
+        verdict(
+            "file_write",
+            Decision::Deny,
+            "autonomy.level = \"readonly\" blocks file writes".into(),
+        )
+    };
+    [read, write]
+}
+
+/// Run `zeroclaw policy simulate`.
+pub fn run(
+    config: &Config,
+    profile: Option<&str>,
+    commands: &[String],
+    paths: &[String],
+    approved: bool,
+    json: bool,
+) -> Result<()> {
+    if commands.is_empty() && paths.is_empty() {
+        anyhow::bail!("Nothing to simulate: pass --command and/or --path");
+    }
+    let autonomy = match profile {
+        Some(name) => config.autonomy.resolve(name)?,
+        None => config.autonomy.clone(),
+    };
+    let policy = SecurityPolicy::from_config(&autonomy, &config.workspace_dir)
+        .with_path_grants(super::grants::PathGrants::for_config(config));
+
+    let mut verdicts: Vec<Verdict> = commands
+        .iter()
+        .map(|command| simulate_command(&policy, command, approved))
+        .collect();
+    for path in paths {
+        verdicts.extend(simulate_path(&policy, path));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&verdicts)?);
+        return Ok(());
+    }
+
+    println!("🧪 ZeroClaw Policy Simulation");
+    println!(
+        "   autonomy: {}{}  workspace: {}",
+        autonomy_name(policy.autonomy),
+        profile
+            .map(|name| format!(" (profile {name})"))
+            .unwrap_or_default(),
+        policy.workspace_dir.display()
+    );
+    println!();
+    for item in &verdicts {
+        let icon = match item.decision {
+            Decision::Allow => "✅",
+            Decision::Deny => "❌",
+            Decision::ApprovalRequired => "⏸️ ",
+        };
+        println!(
+            "  {icon} [{}] {} {}",
+            item.decision.as_str(),
+            item.tool,
+            item.input
+        );
+        println!("       → {}", item.rule);
+    }
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy_in(workspace: &std::path::Path) -> SecurityPolicy {
+        SecurityPolicy {
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        }
+    }
+
+    #[test]
+    fn commands_report_the_deciding_rule() {
+        let tmp = tempfile::tempdir().unwrap();
+        let policy = policy_in(tmp.path());
+
+        let rm = simulate_command(&policy, "rm -rf /tmp/x", false);
+        assert_eq!(rm.decision, Decision::Deny);
+        assert_eq!(rm.rule, "`rm` is not in autonomy.allowed_commands");
+
+        let cat = simulate_command(&policy, "cat /etc/passwd", false);
+        assert_eq!(cat.decision, Decision::Deny);
+        assert!(
+            cat.rule.starts_with("path argument `/etc/passwd`"),
+            "{}",
+            cat.rule
+        );
+
+        let push = simulate_command(&policy, "git push", false);
+        assert_eq!(push.decision, Decision::ApprovalRequired);
+        assert!(push.rule.contains("require_approval_for_medium_risk"));
+        assert_eq!(
+            simulate_command(&policy, "git push", true).decision,
+            Decision::Allow
+        );
+
+        let ls = simulate_command(&policy, "ls -la", false);
+        assert_eq!(ls.decision, Decision::Allow);
+        assert!(ls.rule.contains("low risk"));
+
+        let readonly = SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..policy_in(tmp.path())
+        };
+        let ls = simulate_command(&readonly, "ls", false);
+        assert_eq!(ls.decision, Decision::Deny);
+        assert!(ls.rule.contains("readonly"));
+    }
+
+    #[test]
+    fn paths_are_checked_for_reads_and_writes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let policy = policy_in(tmp.path());
+
+        let [read, write] = simulate_path(&policy, "/etc/passwd");
+        assert_eq!((read.tool, read.decision), ("file_read", Decision::Deny));
+        assert_eq!(write.decision, Decision::Deny);
+        assert!(read.rule.contains("workspace_only"), "{}", read.rule);
+
+        let [read, write] = simulate_path(&policy, "notes/today.md");
+        assert_eq!(read.decision, Decision::Allow);
+        assert_eq!(write.decision, Decision::Allow);
+
+        let readonly = SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..policy_in(tmp.path())
+        };
+        let [read, write] = simulate_path(&readonly, "notes/today.md");
+        assert_eq!(read.decision, Decision::Allow);
+        assert_eq!(write.decision, Decision::Deny);
+        assert!(write.rule.contains("readonly"));
+    }
+}
@@ -8,6 +8,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::process::Command;
 
@@ -85,6 +86,18 @@ impl Sandbox for NoopSandbox {
 mod tests {
     use super::*;
 
+    #[test]
+    fn audit_severity_orders_and_parses() {
+        assert!(AuditSeverity::Critical > AuditSeverity::Warning);
+        assert!(AuditSeverity::Warning > AuditSeverity::Info);
+        assert_eq!(AuditSeverity::parse("WARN"), Some(AuditSeverity::Warning));
+        assert_eq!(
+            AuditSeverity::parse("critical").unwrap().as_str(),
+            "critical"
+        );
+        assert_eq!(AuditSeverity::parse("fatal"), None);
+    }
+
     #[test]
     fn noop_sandbox_name() {
         assert_eq!(NoopSandbox.name(), "none");
@@ -141,20 +154,50 @@ pub struct AuditFinding {
     pub recommendation: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Finding severity, ordered `Info < Warning < Critical`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AuditSeverity {
     Info,
     Warning,
     Critical,
 }
 
+impl AuditSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    /// Parse `info`, `warning`/`warn`, or `critical` (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
 /// DM (Direct Message) access policy per channel.
 ///
-/// Controls whether unknown senders can reach the agent.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Controls whether unknown senders can reach the agent. Configured as
+/// `mode = "open" | "allowlist" | "pairing"` under
+/// `[channels_config.dm_policies.<channel>]`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum DmAccessPolicy {
+    /// Anyone may message the agent, subject to a per-sender rate limit.
+    #[default]
+    #[serde(rename = "open", alias = "allow")]
     Allow,
+    /// Only allowlisted (or previously paired) senders are answered.
+    #[serde(rename = "allowlist", alias = "block")]
     Block,
+    /// Unknown senders must present an operator-issued pairing code first.
+    #[serde(rename = "pairing", alias = "pairing_required")]
     PairingRequired,
 }
 
@@ -0,0 +1,363 @@
+//! Transcript export — renders a session and its transcript as Markdown or
+//! a standalone HTML page for sharing and archiving agent runs.
+
+use anyhow::{bail, Context, Result};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::traits::{Session, TranscriptEntry};
+use crate::config::Config;
+use crate::gateway::pair::local_gateway_base;
+
+/// Output format for [`render_transcript`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    /// Parse `markdown`/`md` or `html`/`htm` (case-insensitive).
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+/// Suggested file name for an exported session, e.g. `session-gateway-webhook.md`.
+pub fn export_file_name(session: &Session, format: ExportFormat) -> String {
+    let slug: String = format!("{}-{}", session.key.agent_id, session.key.context)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("session-{slug}.{}", format.extension())
+}
+
+/// Render `session` and `entries` in `format`.
+pub fn render_transcript(
+    session: &Session,
+    entries: &[TranscriptEntry],
+    format: ExportFormat,
+) -> String {
+    match format {
+        ExportFormat::Markdown => render_markdown(session, entries),
+        ExportFormat::Html => render_html(session, entries),
+    }
+}
+
+fn session_title(session: &Session) -> String {
+    format!("{}:{}", session.key.agent_id, session.key.context)
+}
+
+fn role_label(role: &str) -> String {
+    match role {
+        "user" => "👤 User".into(),
+        "assistant" => "🤖 Assistant".into(),
+        "system" => "⚙️ System".into(),
+        "tool" => "🔧 Tool".into(),
+        "plan" => "📋 Plan".into(),
+        other => other.to_string(),
+    }
+}
+
+fn timestamp(entry: &TranscriptEntry) -> String {
+    entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Fence that does not collide with backtick runs inside `content`.
+fn code_fence(content: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        if c == '`' {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn render_markdown(session: &Session, entries: &[TranscriptEntry]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Session `{}`\n", session_title(session));
+    let _ = writeln!(out, "- Agent: {}", session.key.agent_id);
+    if let Some(model) = &session.model {
+        let _ = writeln!(out, "- Model: {model}");
+    }
+    let _ = writeln!(
+        out,
+        "- Started: {}",
+        session.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    let _ = writeln!(
+        out,
+        "- Last activity: {}",
+        session.last_activity.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    let _ = writeln!(out, "- Messages: {}", entries.len());
+
+    for entry in entries {
+        let _ = write!(
+            out,
+            "\n---\n\n### {} · {}\n\n",
+            role_label(&entry.role),
+            timestamp(entry)
+        );
+        if entry.role == "tool" {
+            let fence = code_fence(&entry.content);
+            let _ = writeln!(out, "{fence}\n{}\n{fence}", entry.content.trim_end());
+        } else {
+            let _ = writeln!(out, "{}", entry.content.trim_end());
+        }
+        for call in entry.tool_calls.iter().flatten() {
+            let fence = code_fence(call);
+            let _ = writeln!(out, "\n**Tool call**\n\n{fence}json\n{call}\n{fence}");
+        }
+    }
+    out
+}
+
+fn escape_html(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:system-ui,sans-serif;max-width:860px;margin:2rem auto;padding:0 1rem}\
+header{border-bottom:1px solid #d0d7de;margin-bottom:1rem}\
+.entry{border:1px solid #d0d7de;border-radius:6px;margin:1rem 0;padding:.75rem 1rem}\
+.entry.user{background:#f6f8fa}.entry.tool{background:#fff8c5}\
+.meta{color:#57606a;font-size:.85rem;margin-bottom:.5rem}\
+.content{white-space:pre-wrap}pre{background:#f6f8fa;padding:.5rem;overflow-x:auto}";
+
+fn render_html(session: &Session, entries: &[TranscriptEntry]) -> String {
+    let title = escape_html(&session_title(session));
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Session {title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n\
+         <header>\n<h1>Session <code>{title}</code></h1>\n<ul>\n"
+    );
+    let _ = writeln!(
+        out,
+        "<li>Agent: {}</li>",
+        escape_html(&session.key.agent_id)
+    );
+    if let Some(model) = &session.model {
+        let _ = writeln!(out, "<li>Model: {}</li>", escape_html(model));
+    }
+    let _ = writeln!(
+        out,
+        "<li>Started: {}</li>\n<li>Last activity: {}</li>\n<li>Messages: {}</li>\n</ul>\n\
+         </header>",
+        session.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        session.last_activity.format("%Y-%m-%d %H:%M:%S UTC"),
+        entries.len()
+    );
+
+    for entry in entries {
+        let role = escape_html(&entry.role);
+        let _ = write!(
+            out,
+            "<section class=\"entry {role}\">\n<div class=\"meta\">{} · {}</div>\n",
+            escape_html(&role_label(&entry.role)),
+            timestamp(entry)
+        );
+        if entry.role == "tool" {
+            let _ = writeln!(out, "<pre>{}</pre>", escape_html(entry.content.trim_end()));
+        } else {
+            let _ = writeln!(
+                out,
+                "<div class=\"content\">{}</div>",
+                escape_html(entry.content.trim_end())
+            );
+        }
+        for call in entry.tool_calls.iter().flatten() {
+            let _ = writeln!(
+                out,
+                "<details open><summary>Tool call</summary><pre>{}</pre></details>",
+                escape_html(call)
+            );
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// `zeroclaw session export` — download an export from the running gateway
+/// and write it to `output` (or the suggested file name).
+pub async fn run_export(
+    config: &Config,
+    key: &str,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+    token: Option<String>,
+) -> Result<()> {
+    let base = local_gateway_base(&config.gateway.host, config.gateway.port);
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let mut url = reqwest::Url::parse(&base).context("invalid gateway URL")?;
+    url.path_segments_mut()
+        .map_err(|()| anyhow::anyhow!("invalid gateway URL"))?
+        .extend(["api", "sessions", key, "export"]);
+    url.query_pairs_mut()
+        .append_pair("format", format.extension());
+
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await.with_context(|| {
+        format!("Gateway is not reachable at {base}. Start it first with `zeroclaw gateway`.")
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let error = body
+            .get("error")
+            .and_then(|e| e.as_str())
+            .unwrap_or("unknown error");
+        bail!("Session export failed ({status}): {error}");
+    }
+
+    let file_name = response
+        .headers()
+        .get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split("filename=\"").nth(1))
+        .and_then(|v| v.split('"').next())
+        .map(str::to_string);
+    let body = response.text().await?;
+    let path = output
+        .or_else(|| file_name.map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(format!("session.{}", format.extension())));
+    tokio::fs::write(&path, body)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("✅ Exported session '{key}' to {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::SessionKey;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn sample() -> (Session, Vec<TranscriptEntry>) {
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let session = Session {
+            key: SessionKey {
+                agent_id: "gateway".into(),
+                context: "webhook".into(),
+            },
+            created_at: at,
+            last_activity: at,
+            model: Some("gpt-4o".into()),
+            metadata: HashMap::new(),
+        };
+        let entries = vec![
+            TranscriptEntry {
+                role: "user".into(),
+                content: "List <files>".into(),
+                timestamp: at,
+                tool_calls: None,
+            },
+            TranscriptEntry {
+                role: "assistant".into(),
+                content: "Checking.".into(),
+                timestamp: at,
+                tool_calls: Some(vec![
+                    r#"{"name":"shell","arguments":{"command":"ls"}}"#.into()
+                ]),
+            },
+            TranscriptEntry {
+                role: "tool".into(),
+                content: "Cargo.toml\n```\nsrc".into(),
+                timestamp: at,
+                tool_calls: None,
+            },
+        ];
+        (session, entries)
+    }
+
+    #[test]
+    fn markdown_export_includes_messages_tool_calls_and_timestamps() {
+        let (session, entries) = sample();
+        let md = render_transcript(&session, &entries, ExportFormat::Markdown);
+
+        assert!(md.starts_with("# Session `gateway:webhook`"));
+        assert!(md.contains("- Model: gpt-4o"));
+        assert!(md.contains("### 👤 User · 2026-03-01 12:00:00 UTC"));
+        assert!(md.contains("**Tool call**\n\n```json\n{\"name\":\"shell\""));
+        // Tool output containing a fence gets a longer fence.
+        assert!(md.contains("````\nCargo.toml\n```\nsrc\n````"));
+    }
+
+    #[test]
+    fn html_export_is_standalone_and_escaped() {
+        let (session, entries) = sample();
+        let html = render_transcript(&session, &entries, ExportFormat::Html);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<style>"));
+        assert!(html.contains("List &lt;files&gt;"));
+        assert!(!html.contains("<files>"));
+        assert!(html.contains("<section class=\"entry tool\">"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn format_parsing_and_file_names() {
+        assert_eq!(ExportFormat::parse("MD"), Some(ExportFormat::Markdown));
+        assert_eq!(ExportFormat::parse("html"), Some(ExportFormat::Html));
+        assert_eq!(ExportFormat::parse("pdf"), None);
+
+        let (session, _) = sample();
+        assert_eq!(
+            export_file_name(&session, ExportFormat::Html),
+            "session-gateway-webhook.html"
+        );
+    }
+}
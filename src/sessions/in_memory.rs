@@ -110,6 +110,13 @@ impl SessionStore for InMemorySessionStore {
         Ok(())
     }
 
+    async fn truncate_transcript(&self, key: &SessionKey, len: usize) -> Result<()> {
+        if let Some(entries) = self.transcripts.lock().get_mut(key) {
+            entries.truncate(len);
+        }
+        Ok(())
+    }
+
     async fn get_transcript(
         &self,
         key: &SessionKey,
@@ -267,4 +274,32 @@ mod tests {
         assert_eq!(last_two[0].content, "message 3");
         assert_eq!(last_two[1].content, "message 4");
     }
+
+    #[tokio::test]
+    async fn truncate_transcript_keeps_leading_entries() {
+        let store = InMemorySessionStore::new();
+        let key = test_key();
+        for role in ["user", "assistant", "user", "assistant"] {
+            store
+                .append_transcript(
+                    &key,
+                    TranscriptEntry {
+                        role: role.to_string(),
+                        content: role.to_string(),
+                        timestamp: Utc::now(),
+                        tool_calls: None,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        store.truncate_transcript(&key, 2).await.unwrap();
+        let kept = store.get_transcript(&key, None).await.unwrap();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].role, "assistant");
+
+        store.truncate_transcript(&key, 10).await.unwrap();
+        assert_eq!(store.get_transcript(&key, None).await.unwrap().len(), 2);
+    }
 }
@@ -1,12 +1,26 @@
 //! Session management — tracks agent conversation state and transcripts.
 
+pub mod export;
 pub mod in_memory;
+pub mod sqlite;
 pub mod traits;
 
+pub use export::{render_transcript, ExportFormat};
 pub use in_memory::InMemorySessionStore;
-pub use traits::{Session, SessionFilter, SessionKey, SessionStore, TranscriptEntry};
+pub use sqlite::{sessions_db_path, SqliteSessionStore};
+pub use traits::{SessionFilter, SessionKey, SessionStore, TranscriptEntry};
 
 /// Create a default in-memory session store.
 pub fn create_session_store() -> Box<dyn SessionStore> {
     Box::new(InMemorySessionStore::new())
 }
+
+/// Create the persistent session store for `workspace_dir`
+/// (`<workspace>/state/sessions.db`).
+pub fn create_persistent_session_store(
+    workspace_dir: &std::path::Path,
+) -> anyhow::Result<Box<dyn SessionStore>> {
+    Ok(Box::new(SqliteSessionStore::open(&sessions_db_path(
+        workspace_dir,
+    ))?))
+}
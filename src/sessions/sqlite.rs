@@ -0,0 +1,305 @@
+//! SQLite session store under `<workspace>/state/`, so transcripts survive
+//! restarts (`zeroclaw agent --resume` / `--session <name>`).
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+use super::traits::{Session, SessionFilter, SessionKey, SessionStore, TranscriptEntry};
+
+const SESSIONS_DB_FILE: &str = "sessions.db";
+
+pub fn sessions_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(SESSIONS_DB_FILE)
+}
+
+/// A session store persisted in one SQLite file.
+pub struct SqliteSessionStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteSessionStore {
+    /// Open (or create) the store at `db_path`.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                agent_id      TEXT NOT NULL,
+                context       TEXT NOT NULL,
+                created_at    TEXT NOT NULL,
+                last_activity TEXT NOT NULL,
+                model         TEXT,
+                metadata      TEXT NOT NULL DEFAULT '{}',
+                PRIMARY KEY (agent_id, context)
+             );
+             CREATE TABLE IF NOT EXISTS transcript_entries (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                agent_id   TEXT NOT NULL,
+                context    TEXT NOT NULL,
+                role       TEXT NOT NULL,
+                content    TEXT NOT NULL,
+                timestamp  TEXT NOT NULL,
+                tool_calls TEXT
+             );
+             CREATE INDEX IF NOT EXISTS idx_transcript_session
+                ON transcript_entries(agent_id, context, id);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+fn parse_time(raw: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(raw).map_or_else(|_| Utc::now(), |t| t.with_timezone(&Utc))
+}
+
+fn session_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Session> {
+    let metadata: String = row.get(5)?;
+    Ok(Session {
+        key: SessionKey {
+            agent_id: row.get(0)?,
+            context: row.get(1)?,
+        },
+        created_at: parse_time(&row.get::<_, String>(2)?),
+        last_activity: parse_time(&row.get::<_, String>(3)?),
+        model: row.get(4)?,
+        metadata: serde_json::from_str(&metadata).unwrap_or_default(),
+    })
+}
+
+const SESSION_COLUMNS: &str = "agent_id, context, created_at, last_activity, model, metadata";
+
+#[async_trait]
+impl SessionStore for SqliteSessionStore {
+    async fn create(&self, key: &SessionKey) -> Result<Session> {
+        let now = Utc::now();
+        self.conn.lock().execute(
+            "INSERT INTO sessions (agent_id, context, created_at, last_activity, metadata)
+             VALUES (?1, ?2, ?3, ?3, '{}')
+             ON CONFLICT(agent_id, context) DO UPDATE SET last_activity = excluded.last_activity",
+            params![key.agent_id, key.context, now.to_rfc3339()],
+        )?;
+        self.get(key)
+            .await?
+            .context("session disappeared after insert")
+    }
+
+    async fn get(&self, key: &SessionKey) -> Result<Option<Session>> {
+        let conn = self.conn.lock();
+        Ok(conn
+            .query_row(
+                &format!(
+                    "SELECT {SESSION_COLUMNS} FROM sessions WHERE agent_id = ?1 AND context = ?2"
+                ),
+                params![key.agent_id, key.context],
+                session_from_row,
+            )
+            .optional()?)
+    }
+
+    async fn update_activity(&self, key: &SessionKey) -> Result<()> {
+        let updated = self.conn.lock().execute(
+            "UPDATE sessions SET last_activity = ?3 WHERE agent_id = ?1 AND context = ?2",
+            params![key.agent_id, key.context, Utc::now().to_rfc3339()],
+        )?;
+        if updated == 0 {
+            bail!("session not found: {}:{}", key.agent_id, key.context);
+        }
+        Ok(())
+    }
+
+    async fn list(&self, filter: &SessionFilter) -> Result<Vec<Session>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {SESSION_COLUMNS} FROM sessions ORDER BY last_activity DESC"
+        ))?;
+        let sessions = stmt
+            .query_map([], session_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(sessions
+            .into_iter()
+            .filter(|s| {
+                filter
+                    .agent_id
+                    .as_ref()
+                    .is_none_or(|agent_id| s.key.agent_id == *agent_id)
+                    && filter.since.is_none_or(|since| s.last_activity >= since)
+            })
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    async fn delete(&self, key: &SessionKey) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM transcript_entries WHERE agent_id = ?1 AND context = ?2",
+            params![key.agent_id, key.context],
+        )?;
+        conn.execute(
+            "DELETE FROM sessions WHERE agent_id = ?1 AND context = ?2",
+            params![key.agent_id, key.context],
+        )?;
+        Ok(())
+    }
+
+    async fn append_transcript(&self, key: &SessionKey, entry: TranscriptEntry) -> Result<()> {
+        let tool_calls = entry
+            .tool_calls
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
+        self.conn.lock().execute(
+            "INSERT INTO transcript_entries (agent_id, context, role, content, timestamp, tool_calls)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                key.agent_id,
+                key.context,
+                entry.role,
+                entry.content,
+                entry.timestamp.to_rfc3339(),
+                tool_calls
+            ],
+        )?;
+        Ok(())
+    }
+
+    async fn truncate_transcript(&self, key: &SessionKey, len: usize) -> Result<()> {
+        let len = i64::try_from(len).unwrap_or(i64::MAX);
+        self.conn.lock().execute(
+            "DELETE FROM transcript_entries WHERE agent_id = ?1 AND context = ?2 AND id NOT IN (
+                SELECT id FROM transcript_entries WHERE agent_id = ?1 AND context = ?2
+                ORDER BY id LIMIT ?3
+             )",
+            params![key.agent_id, key.context, len],
+        )?;
+        Ok(())
+    }
+
+    async fn get_transcript(
+        &self,
+        key: &SessionKey,
+        limit: Option<usize>,
+    ) -> Result<Vec<TranscriptEntry>> {
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let conn = self.conn.lock();
+        // Newest `limit` entries, returned oldest first.
+        let mut stmt = conn.prepare(
+            "SELECT role, content, timestamp, tool_calls FROM (
+                SELECT id, role, content, timestamp, tool_calls FROM transcript_entries
+                WHERE agent_id = ?1 AND context = ?2 ORDER BY id DESC LIMIT ?3
+             ) ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![key.agent_id, key.context, limit], |row| {
+            let tool_calls: Option<String> = row.get(3)?;
+            Ok(TranscriptEntry {
+                role: row.get(0)?,
+                content: row.get(1)?,
+                timestamp: parse_time(&row.get::<_, String>(2)?),
+                tool_calls: tool_calls.and_then(|raw| serde_json::from_str(&raw).ok()),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn key(context: &str) -> SessionKey {
+        SessionKey {
+            agent_id: "cli".into(),
+            context: context.into(),
+        }
+    }
+
+    fn entry(role: &str, content: &str) -> TranscriptEntry {
+        TranscriptEntry {
+            role: role.into(),
+            content: content.into(),
+            timestamp: Utc::now(),
+            tool_calls: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn transcripts_survive_reopening_the_store() {
+        let tmp = TempDir::new().unwrap();
+        let path = sessions_db_path(tmp.path());
+        {
+            let store = SqliteSessionStore::open(&path).unwrap();
+            store.create(&key("main")).await.unwrap();
+            store
+                .append_transcript(&key("main"), entry("user", "hi"))
+                .await
+                .unwrap();
+            let mut answer = entry("assistant", "hello");
+            answer.tool_calls = Some(vec![r#"{"name":"shell"}"#.into()]);
+            store.append_transcript(&key("main"), answer).await.unwrap();
+        }
+
+        let store = SqliteSessionStore::open(&path).unwrap();
+        assert!(store.get(&key("main")).await.unwrap().is_some());
+        let transcript = store.get_transcript(&key("main"), None).await.unwrap();
+        assert_eq!(transcript.len(), 2);
+        assert_eq!(transcript[0].content, "hi");
+        assert_eq!(transcript[1].tool_calls.as_deref().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn limit_truncate_list_and_delete() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(&sessions_db_path(tmp.path())).unwrap();
+        store.create(&key("old")).await.unwrap();
+        store.create(&key("new")).await.unwrap();
+        for n in 0..5 {
+            store
+                .append_transcript(&key("new"), entry("user", &n.to_string()))
+                .await
+                .unwrap();
+        }
+
+        let recent = store.get_transcript(&key("new"), Some(2)).await.unwrap();
+        let contents: Vec<&str> = recent.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, ["3", "4"]);
+
+        store.truncate_transcript(&key("new"), 3).await.unwrap();
+        assert_eq!(
+            store.get_transcript(&key("new"), None).await.unwrap().len(),
+            3
+        );
+
+        store.update_activity(&key("new")).await.unwrap();
+        let listed = store
+            .list(&SessionFilter {
+                agent_id: Some("cli".into()),
+                since: None,
+                limit: Some(1),
+            })
+            .await
+            .unwrap();
+        assert_eq!(listed[0].key.context, "new");
+        assert!(store.update_activity(&key("missing")).await.is_err());
+
+        store.delete(&key("new")).await.unwrap();
+        assert!(store.get(&key("new")).await.unwrap().is_none());
+        assert!(store
+            .get_transcript(&key("new"), None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -61,6 +61,10 @@ pub trait SessionStore: Send + Sync {
     /// Append an entry to the session transcript.
     async fn append_transcript(&self, key: &SessionKey, entry: TranscriptEntry) -> Result<()>;
 
+    /// Keep only the first `len` transcript entries (e.g. to undo the last
+    /// exchange). A no-op when the transcript is already that short.
+    async fn truncate_transcript(&self, key: &SessionKey, len: usize) -> Result<()>;
+
     /// Retrieve transcript entries for a session, optionally limited.
     async fn get_transcript(
         &self,
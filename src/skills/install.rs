@@ -0,0 +1,393 @@
+//! Skill packs: Markdown skills fetched from a git repository or a local
+//! path into the workspace `skills/` directory.
+//!
+//! A pack is a single `.md` file or a directory whose top level (or
+//! `skills/` subdirectory) holds skill files with frontmatter. Installed
+//! packs are recorded in `skills/.installed.json` so they can be updated
+//! from their original source or removed as a unit.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::markdown::{is_valid_skill_name, parse_frontmatter, SkillFrontmatter};
+
+/// Install records, kept next to the skills they describe.
+pub const REGISTRY_FILE: &str = ".installed.json";
+
+/// Skills directory inside a workspace.
+pub fn skills_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("skills")
+}
+
+/// Packs installed into a workspace, keyed by pack name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkillRegistry {
+    #[serde(default)]
+    pub packs: BTreeMap<String, InstalledPack>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPack {
+    /// Git URL or absolute local path the pack was installed from.
+    pub source: String,
+    /// Skill names the pack contributed.
+    pub skills: Vec<String>,
+    pub installed_at: DateTime<Utc>,
+}
+
+impl SkillRegistry {
+    pub fn load(skills_dir: &Path) -> Result<Self> {
+        let path = skills_dir.join(REGISTRY_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&raw).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    pub fn save(&self, skills_dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(skills_dir)?;
+        let path = skills_dir.join(REGISTRY_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Pack that installed `skill`, if any.
+    pub fn owner_of(&self, skill: &str) -> Option<&str> {
+        self.packs
+            .iter()
+            .find(|(_, pack)| pack.skills.iter().any(|s| s == skill))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Outcome of installing or updating one pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallReport {
+    pub pack: String,
+    pub skills: Vec<String>,
+}
+
+fn is_git_source(source: &str) -> bool {
+    ["https://", "http://", "git@", "ssh://", "git://"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+        || (source.ends_with(".git") && !Path::new(source).exists())
+}
+
+/// Pack name derived from its source: the last path segment without `.git`
+/// or `.md`.
+pub fn pack_name(source: &str) -> Result<String> {
+    let trimmed = source.trim().trim_end_matches(['/', '\\']);
+    let last = trimmed.rsplit(['/', '\\', ':']).next().unwrap_or(trimmed);
+    let name = last
+        .strip_suffix(".git")
+        .or_else(|| last.strip_suffix(".md"))
+        .unwrap_or(last);
+    if !is_valid_skill_name(name) {
+        bail!("cannot derive a pack name from '{source}'");
+    }
+    Ok(name.to_string())
+}
+
+/// Skill files in a pack, with their parsed frontmatter and raw contents.
+fn collect_pack_skills(root: &Path) -> Result<Vec<(SkillFrontmatter, String)>> {
+    let candidates = if root.is_file() {
+        vec![root.to_path_buf()]
+    } else if root.is_dir() {
+        let mut paths = Vec::new();
+        for dir in [root.to_path_buf(), root.join("skills")] {
+            if !dir.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("md") {
+                    paths.push(path);
+                }
+            }
+        }
+        paths.sort();
+        paths
+    } else {
+        bail!("skill source {} does not exist", root.display());
+    };
+
+    let mut skills: Vec<(SkillFrontmatter, String)> = Vec::new();
+    for path in candidates {
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        // READMEs and other docs without frontmatter are not skills.
+        let Some((meta, _)) =
+            parse_frontmatter(&raw).with_context(|| format!("in {}", path.display()))?
+        else {
+            continue;
+        };
+        if skills
+            .iter()
+            .any(|(existing, _)| existing.name == meta.name)
+        {
+            bail!(
+                "skill '{}' is defined more than once in the pack",
+                meta.name
+            );
+        }
+        skills.push((meta, raw));
+    }
+
+    if skills.is_empty() {
+        bail!(
+            "no skills with frontmatter (`name`, `description`) found in {}",
+            root.display()
+        );
+    }
+    Ok(skills)
+}
+
+fn git_clone(url: &str, dest: &Path) -> Result<()> {
+    let output = Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", url])
+        .arg(dest)
+        .output()
+        .context("failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "git clone {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Install (or reinstall) the pack at `source` into the workspace.
+///
+/// Skills already present that belong to another pack, or were written by
+/// hand, are never overwritten. Skills a previous install of the same pack
+/// contributed but the new version dropped are removed.
+pub fn install_pack(workspace_dir: &Path, source: &str) -> Result<InstallReport> {
+    let dir = skills_dir(workspace_dir);
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let (source, skills) = if is_git_source(source) {
+        let staging = dir.join(format!(".fetch-{}", uuid::Uuid::new_v4()));
+        let fetched = git_clone(source, &staging).and_then(|()| collect_pack_skills(&staging));
+        let _ = std::fs::remove_dir_all(&staging);
+        (source.to_string(), fetched?)
+    } else {
+        let path = std::fs::canonicalize(source)
+            .with_context(|| format!("skill source {source} does not exist"))?;
+        let skills = collect_pack_skills(&path)?;
+        (path.display().to_string(), skills)
+    };
+    let pack = pack_name(&source)?;
+
+    let mut registry = SkillRegistry::load(&dir)?;
+    for (meta, _) in &skills {
+        let target = dir.join(format!("{}.md", meta.name));
+        match registry.owner_of(&meta.name) {
+            Some(owner) if owner != pack => {
+                bail!(
+                    "skill '{}' is already installed by pack '{owner}'",
+                    meta.name
+                )
+            }
+            None if target.exists() => {
+                bail!(
+                    "skill '{}' already exists at {}; remove it first",
+                    meta.name,
+                    target.display()
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let names: Vec<String> = skills.iter().map(|(meta, _)| meta.name.clone()).collect();
+    if let Some(previous) = registry.packs.get(&pack) {
+        for stale in previous.skills.iter().filter(|s| !names.contains(s)) {
+            let _ = std::fs::remove_file(dir.join(format!("{stale}.md")));
+        }
+    }
+    for (meta, raw) in &skills {
+        let target = dir.join(format!("{}.md", meta.name));
+        std::fs::write(&target, raw)
+            .with_context(|| format!("failed to write {}", target.display()))?;
+    }
+
+    registry.packs.insert(
+        pack.clone(),
+        InstalledPack {
+            source,
+            skills: names.clone(),
+            installed_at: Utc::now(),
+        },
+    );
+    registry.save(&dir)?;
+
+    Ok(InstallReport {
+        pack,
+        skills: names,
+    })
+}
+
+/// Reinstall one pack (or all of them) from the recorded source.
+pub fn update_packs(workspace_dir: &Path, pack: Option<&str>) -> Result<Vec<InstallReport>> {
+    let registry = SkillRegistry::load(&skills_dir(workspace_dir))?;
+    let sources: Vec<String> = match pack {
+        Some(name) => match registry.packs.get(name) {
+            Some(installed) => vec![installed.source.clone()],
+            None => bail!("skill pack '{name}' is not installed"),
+        },
+        None => registry.packs.values().map(|p| p.source.clone()).collect(),
+    };
+
+    sources
+        .iter()
+        .map(|source| install_pack(workspace_dir, source))
+        .collect()
+}
+
+/// Remove an installed pack, or a standalone skill file, by name. Returns
+/// the skill names removed.
+pub fn remove(workspace_dir: &Path, name: &str) -> Result<Vec<String>> {
+    let dir = skills_dir(workspace_dir);
+    let mut registry = SkillRegistry::load(&dir)?;
+
+    if let Some(pack) = registry.packs.remove(name) {
+        for skill in &pack.skills {
+            let _ = std::fs::remove_file(dir.join(format!("{skill}.md")));
+        }
+        registry.save(&dir)?;
+        return Ok(pack.skills);
+    }
+
+    if let Some(owner) = registry.owner_of(name) {
+        bail!("skill '{name}' belongs to pack '{owner}'; remove the pack instead");
+    }
+    if !is_valid_skill_name(name) {
+        bail!("invalid skill name '{name}'");
+    }
+    let path = dir.join(format!("{name}.md"));
+    if !path.is_file() {
+        bail!("no installed skill pack or skill named '{name}'");
+    }
+    std::fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+    Ok(vec![name.to_string()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_skill(dir: &Path, file: &str, name: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join(file),
+            format!("---\nname: {name}\ndescription: The {name} skill\n---\nDo {name}.\n"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn pack_name_comes_from_last_source_segment() {
+        assert_eq!(
+            pack_name("https://github.com/acme/ops-skills.git").unwrap(),
+            "ops-skills"
+        );
+        assert_eq!(
+            pack_name("git@github.com:acme/ops-skills.git").unwrap(),
+            "ops-skills"
+        );
+        assert_eq!(pack_name("/tmp/packs/writing/").unwrap(), "writing");
+        assert_eq!(pack_name("./triage.md").unwrap(), "triage");
+        assert!(pack_name("/").is_err());
+    }
+
+    #[test]
+    fn install_copies_skills_and_records_pack() {
+        let workspace = tempfile::tempdir().unwrap();
+        let source = tempfile::tempdir().unwrap();
+        let pack_dir = source.path().join("ops");
+        write_skill(&pack_dir, "deploy.md", "deploy");
+        write_skill(&pack_dir.join("skills"), "rollback.md", "rollback");
+        std::fs::write(pack_dir.join("README.md"), "# Ops pack").unwrap();
+
+        let report = install_pack(workspace.path(), pack_dir.to_str().unwrap()).unwrap();
+        assert_eq!(report.pack, "ops");
+        assert_eq!(report.skills, vec!["deploy", "rollback"]);
+
+        let dir = skills_dir(workspace.path());
+        assert!(dir.join("deploy.md").is_file());
+        assert!(dir.join("rollback.md").is_file());
+        assert!(!dir.join("README.md").exists());
+        let registry = SkillRegistry::load(&dir).unwrap();
+        assert_eq!(registry.owner_of("rollback"), Some("ops"));
+    }
+
+    #[test]
+    fn install_rejects_invalid_frontmatter_and_conflicts() {
+        let workspace = tempfile::tempdir().unwrap();
+        let source = tempfile::tempdir().unwrap();
+
+        let broken = source.path().join("broken");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("x.md"), "---\nname: x\n---\n").unwrap();
+        assert!(install_pack(workspace.path(), broken.to_str().unwrap()).is_err());
+
+        let empty = source.path().join("empty");
+        std::fs::create_dir_all(&empty).unwrap();
+        std::fs::write(empty.join("README.md"), "# nothing").unwrap();
+        assert!(install_pack(workspace.path(), empty.to_str().unwrap()).is_err());
+
+        write_skill(&skills_dir(workspace.path()), "deploy.md", "deploy");
+        let ops = source.path().join("ops");
+        write_skill(&ops, "deploy.md", "deploy");
+        let err = install_pack(workspace.path(), ops.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn update_refreshes_from_source_and_drops_stale_skills() {
+        let workspace = tempfile::tempdir().unwrap();
+        let source = tempfile::tempdir().unwrap();
+        let pack_dir = source.path().join("ops");
+        write_skill(&pack_dir, "deploy.md", "deploy");
+        write_skill(&pack_dir, "rollback.md", "rollback");
+        install_pack(workspace.path(), pack_dir.to_str().unwrap()).unwrap();
+
+        std::fs::remove_file(pack_dir.join("rollback.md")).unwrap();
+        write_skill(&pack_dir, "canary.md", "canary");
+        let reports = update_packs(workspace.path(), Some("ops")).unwrap();
+        assert_eq!(reports[0].skills, vec!["canary", "deploy"]);
+
+        let dir = skills_dir(workspace.path());
+        assert!(dir.join("canary.md").is_file());
+        assert!(!dir.join("rollback.md").exists());
+        assert!(update_packs(workspace.path(), Some("missing")).is_err());
+    }
+
+    #[test]
+    fn remove_deletes_packs_and_standalone_skills() {
+        let workspace = tempfile::tempdir().unwrap();
+        let source = tempfile::tempdir().unwrap();
+        let pack_dir = source.path().join("ops");
+        write_skill(&pack_dir, "deploy.md", "deploy");
+        install_pack(workspace.path(), pack_dir.to_str().unwrap()).unwrap();
+        let dir = skills_dir(workspace.path());
+        write_skill(&dir, "notes.md", "notes");
+
+        assert!(remove(workspace.path(), "deploy")
+            .unwrap_err()
+            .to_string()
+            .contains("pack 'ops'"));
+        assert_eq!(remove(workspace.path(), "ops").unwrap(), vec!["deploy"]);
+        assert!(!dir.join("deploy.md").exists());
+        assert_eq!(remove(workspace.path(), "notes").unwrap(), vec!["notes"]);
+        assert!(remove(workspace.path(), "notes").is_err());
+    }
+}
@@ -1,8 +1,95 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use async_trait::async_trait;
+use std::path::Path;
 
 use super::traits::{InstallSpec, Skill, SkillCommand, SkillContext, SkillLoader, SkillSource};
 
+/// Metadata block at the top of a skill file:
+///
+/// ```markdown
+/// ---
+/// name: release-notes
+/// description: Draft release notes from merged PRs
+/// version: 0.2.0
+/// ---
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkillFrontmatter {
+    pub name: String,
+    pub description: String,
+    pub version: Option<String>,
+}
+
+/// Split a skill file into its frontmatter and body. Returns `None` for
+/// files without a leading `---` block; a block that is present but
+/// incomplete or malformed is an error.
+pub fn parse_frontmatter(raw: &str) -> Result<Option<(SkillFrontmatter, &str)>> {
+    let raw = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let Some(rest) = raw
+        .strip_prefix("---\n")
+        .or_else(|| raw.strip_prefix("---\r\n"))
+    else {
+        return Ok(None);
+    };
+
+    let mut name = None;
+    let mut description = None;
+    let mut version = None;
+    let mut offset = 0;
+    let mut body = None;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim();
+        if line == "---" {
+            body = Some(&rest[offset..]);
+            break;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            bail!("invalid frontmatter line '{line}' (expected `key: value`)");
+        };
+        let value = value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_string();
+        match key.trim() {
+            "name" => name = Some(value),
+            "description" => description = Some(value),
+            "version" => version = Some(value),
+            // Unknown keys are tolerated so packs can carry extra metadata.
+            _ => {}
+        }
+    }
+
+    let Some(body) = body else {
+        bail!("frontmatter is not closed with `---`");
+    };
+    let name = name.filter(|n| is_valid_skill_name(n));
+    let Some(name) = name else {
+        bail!("frontmatter needs a `name` using only [A-Za-z0-9_-]");
+    };
+    let Some(description) = description.filter(|d| !d.is_empty()) else {
+        bail!("skill '{name}' frontmatter needs a `description`");
+    };
+    Ok(Some((
+        SkillFrontmatter {
+            name,
+            description,
+            version,
+        },
+        body.trim_start_matches(['\r', '\n']),
+    )))
+}
+
+pub(crate) fn is_valid_skill_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
 /// A skill loaded from a markdown file.
 pub struct MarkdownSkill {
     skill_name: String,
@@ -59,6 +146,49 @@ impl Skill for MarkdownSkill {
 /// Default loader that scans workspace paths for markdown skill files.
 pub struct DefaultSkillLoader;
 
+/// Load every `*.md` skill in `<base_path>/skills/`, sorted by file name.
+/// Files with frontmatter take their name and description from it; others
+/// are named after the file.
+pub fn load_workspace_skills(base_path: &Path) -> Result<Vec<MarkdownSkill>> {
+    let skills_dir = base_path.join("skills");
+    if !skills_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<_> = std::fs::read_dir(&skills_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    paths.sort();
+
+    let mut skills = Vec::new();
+    for path in paths {
+        let raw = std::fs::read_to_string(&path)?;
+        let source = SkillSource::Workspace(base_path.to_path_buf());
+        let skill = match parse_frontmatter(&raw) {
+            Ok(Some((meta, body))) => {
+                MarkdownSkill::new(meta.name, meta.description, source, body.to_string())
+            }
+            Ok(None) => {
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let description = format!("Skill loaded from {}", path.display());
+                MarkdownSkill::new(name, description, source, raw)
+            }
+            Err(e) => {
+                tracing::warn!("Skipping skill {}: {e}", path.display());
+                continue;
+            }
+        };
+        skills.push(skill);
+    }
+    Ok(skills)
+}
+
 #[async_trait]
 impl SkillLoader for DefaultSkillLoader {
     async fn load_skills(&self, sources: &[SkillSource]) -> Result<Vec<Box<dyn Skill>>> {
@@ -66,28 +196,8 @@ impl SkillLoader for DefaultSkillLoader {
 
         for source in sources {
             if let SkillSource::Workspace(base_path) = source {
-                let skills_dir = base_path.join("skills");
-                if skills_dir.is_dir() {
-                    let entries = std::fs::read_dir(&skills_dir)?;
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.extension().and_then(|e| e.to_str()) == Some("md") {
-                            let content = std::fs::read_to_string(&path)?;
-                            let name = path
-                                .file_stem()
-                                .and_then(|s| s.to_str())
-                                .unwrap_or("unknown")
-                                .to_string();
-                            let description = format!("Skill loaded from {}", path.display());
-                            let skill = MarkdownSkill::new(
-                                name,
-                                description,
-                                SkillSource::Workspace(base_path.clone()),
-                                content,
-                            );
-                            skills.push(Box::new(skill));
-                        }
-                    }
+                for skill in load_workspace_skills(base_path)? {
+                    skills.push(Box::new(skill));
                 }
             }
         }
@@ -171,6 +281,46 @@ mod tests {
         std::fs::remove_dir_all(&tmp).ok();
     }
 
+    #[test]
+    fn frontmatter_is_parsed_and_stripped() {
+        let raw = "---\nname: release-notes\ndescription: \"Draft release notes\"\nversion: 0.2.0\nauthor: someone\n---\n\n# Release notes\n";
+        let (meta, body) = parse_frontmatter(raw).unwrap().unwrap();
+        assert_eq!(meta.name, "release-notes");
+        assert_eq!(meta.description, "Draft release notes");
+        assert_eq!(meta.version.as_deref(), Some("0.2.0"));
+        assert_eq!(body, "# Release notes\n");
+
+        assert!(parse_frontmatter("# No frontmatter").unwrap().is_none());
+    }
+
+    #[test]
+    fn frontmatter_requires_valid_name_description_and_closing_fence() {
+        assert!(parse_frontmatter("---\ndescription: x\n---\n").is_err());
+        assert!(parse_frontmatter("---\nname: ../evil\ndescription: x\n---\n").is_err());
+        assert!(parse_frontmatter("---\nname: ok\n---\n").is_err());
+        assert!(parse_frontmatter("---\nname: ok\ndescription: x\n").is_err());
+        assert!(parse_frontmatter("---\nnot a pair\n---\n").is_err());
+    }
+
+    #[test]
+    fn workspace_loader_uses_frontmatter_and_skips_invalid_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills_dir = tmp.path().join("skills");
+        std::fs::create_dir_all(&skills_dir).unwrap();
+        std::fs::write(
+            skills_dir.join("notes.md"),
+            "---\nname: release-notes\ndescription: Draft notes\n---\nBody",
+        )
+        .unwrap();
+        std::fs::write(skills_dir.join("broken.md"), "---\nname: broken\n").unwrap();
+
+        let skills = load_workspace_skills(tmp.path()).unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name(), "release-notes");
+        assert_eq!(skills[0].description(), "Draft notes");
+        assert_eq!(skills[0].prompt_content(), "Body");
+    }
+
     #[tokio::test]
     async fn install_skill_is_noop() {
         let loader = DefaultSkillLoader;
@@ -1,11 +1,172 @@
+pub mod install;
 pub mod markdown;
+pub mod selector;
 pub mod traits;
 
-pub use markdown::{DefaultSkillLoader, MarkdownSkill};
-pub use traits::{
-    InstallMethod, InstallSpec, Skill, SkillCommand, SkillContext, SkillLoader, SkillSource,
-};
+pub use install::{install_pack, skills_dir, update_packs, InstallReport, SkillRegistry};
+pub use markdown::{load_workspace_skills, DefaultSkillLoader, MarkdownSkill};
+pub use selector::SkillSelector;
+pub use traits::{Skill, SkillLoader};
+
+use anyhow::Result;
 
 pub fn create_skill_loader() -> Box<dyn SkillLoader> {
     Box::new(DefaultSkillLoader)
 }
+
+/// Handle `zeroclaw skill <subcommand>` CLI commands.
+pub async fn handle_command(
+    command: crate::SkillCommands,
+    config: &crate::config::Config,
+) -> Result<()> {
+    let workspace_dir = config.workspace_dir.clone();
+    match command {
+        crate::SkillCommands::List => {
+            let skills = load_workspace_skills(&workspace_dir)?;
+            if skills.is_empty() {
+                println!("No skills installed.");
+                println!();
+                println!("Install a pack with: zeroclaw skill install <git-url|path>");
+                return Ok(());
+            }
+            let registry = SkillRegistry::load(&skills_dir(&workspace_dir))?;
+            println!("Installed skills ({}):\n", skills.len());
+            for skill in &skills {
+                let origin = registry
+                    .owner_of(skill.name())
+                    .map_or_else(|| "local".to_string(), |pack| format!("pack: {pack}"));
+                println!("  {:<24} {} [{origin}]", skill.name(), skill.description());
+            }
+            Ok(())
+        }
+        crate::SkillCommands::Install { source } => {
+            let report = tokio::task::spawn_blocking(move || install_pack(&workspace_dir, &source))
+                .await??;
+            print_report("Installed", &report);
+            Ok(())
+        }
+        crate::SkillCommands::Update { name } => {
+            let reports =
+                tokio::task::spawn_blocking(move || update_packs(&workspace_dir, name.as_deref()))
+                    .await??;
+            if reports.is_empty() {
+                println!("No skill packs installed.");
+            }
+            for report in &reports {
+                print_report("Updated", report);
+            }
+            Ok(())
+        }
+        crate::SkillCommands::Remove { name } => {
+            let removed = install::remove(&workspace_dir, &name)?;
+            println!("✅ Removed {name} ({})", removed.join(", "));
+            Ok(())
+        }
+    }
+}
+
+fn print_report(verb: &str, report: &InstallReport) {
+    println!(
+        "✅ {verb} pack '{}' ({} skill{}): {}",
+        report.pack,
+        report.skills.len(),
+        if report.skills.len() == 1 { "" } else { "s" },
+        report.skills.join(", ")
+    );
+}
+
+/// Render workspace skills as a system prompt block. Read from disk on
+/// every call so newly installed skills apply to the next prompt build.
+pub fn render_skills_prompt(workspace_dir: &std::path::Path) -> String {
+    let skills = load_skills_or_warn(workspace_dir);
+    render_skills(&skills.iter().collect::<Vec<_>>())
+}
+
+/// Like [`render_skills_prompt`], but keeps only the skills `selector`
+/// picks as relevant to `query`.
+pub async fn render_selected_skills_prompt(
+    workspace_dir: &std::path::Path,
+    selector: &SkillSelector,
+    query: &str,
+) -> String {
+    let skills = load_skills_or_warn(workspace_dir);
+    render_skills(&selector.select(query, &skills).await)
+}
+
+fn load_skills_or_warn(workspace_dir: &std::path::Path) -> Vec<MarkdownSkill> {
+    load_workspace_skills(workspace_dir).unwrap_or_else(|e| {
+        tracing::warn!("Failed to load workspace skills: {e}");
+        Vec::new()
+    })
+}
+
+fn render_skills(skills: &[&MarkdownSkill]) -> String {
+    use std::fmt::Write;
+
+    if skills.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("## Skills\n\n");
+    for skill in skills {
+        let _ = writeln!(
+            out,
+            "### {}\n\n{}\n\n{}\n",
+            skill.name(),
+            skill.description(),
+            skill.prompt_content().trim()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_skills_prompt_lists_workspace_skills() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(render_skills_prompt(tmp.path()).is_empty());
+
+        let dir = skills_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("deploy.md"),
+            "---\nname: deploy\ndescription: Ship a release\n---\nRun the deploy checklist.\n",
+        )
+        .unwrap();
+
+        let prompt = render_skills_prompt(tmp.path());
+        assert!(prompt.starts_with("## Skills"));
+        assert!(prompt.contains("### deploy\n\nShip a release\n\nRun the deploy checklist."));
+        assert!(!prompt.contains("name: deploy"));
+    }
+
+    #[tokio::test]
+    async fn render_selected_skills_prompt_keeps_relevant_skills_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = skills_dir(tmp.path());
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, description) in [
+            ("deploy", "Ship a release to production"),
+            ("email", "Draft email replies"),
+        ] {
+            std::fs::write(
+                dir.join(format!("{name}.md")),
+                format!("---\nname: {name}\ndescription: {description}\n---\nSteps.\n"),
+            )
+            .unwrap();
+        }
+
+        let config = crate::config::AgentConfig {
+            max_skills: 1,
+            ..crate::config::AgentConfig::default()
+        };
+        let selector = SkillSelector::from_config(&config).unwrap();
+        let prompt =
+            render_selected_skills_prompt(tmp.path(), &selector, "reply to this email").await;
+        assert!(prompt.contains("### email"));
+        assert!(!prompt.contains("### deploy"));
+    }
+}
@@ -0,0 +1,385 @@
+//! Per-message skill selection.
+//!
+//! Injecting every installed skill into the system prompt is wasteful on
+//! small-context models. The selector classifies the user message, embeds it
+//! alongside each skill's name and description, and keeps only the `top_k`
+//! closest skills.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::markdown::MarkdownSkill;
+use super::traits::Skill;
+use crate::agent::classifier::KeywordQueryClassifier;
+use crate::agent::traits::{QueryClassification, QueryClassifier};
+use crate::config::AgentConfig;
+use crate::memory::sqlite::cosine_similarity;
+use crate::providers::EmbeddingProvider;
+
+/// Skills kept per message when `compact_context` is on and `max_skills` is unset.
+const COMPACT_MAX_SKILLS: usize = 3;
+/// Skills scoring at or below this similarity are never injected.
+const MIN_SCORE: f32 = 0.05;
+/// Dimensions of [`LexicalEmbedding`] vectors.
+const LEXICAL_DIMENSIONS: usize = 256;
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "this", "that", "you", "your", "can", "how", "what", "please",
+];
+
+/// Offline embedding: a hashed bag of lowercase words. Good enough to rank a
+/// handful of skill descriptions without a network round-trip.
+#[derive(Debug, Clone, Default)]
+pub struct LexicalEmbedding;
+
+#[async_trait]
+impl EmbeddingProvider for LexicalEmbedding {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| lexical_embedding(text)).collect())
+    }
+    fn dimensions(&self) -> usize {
+        LEXICAL_DIMENSIONS
+    }
+    fn model_name(&self) -> &str {
+        "lexical"
+    }
+    fn name(&self) -> &str {
+        "lexical"
+    }
+}
+
+fn lexical_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; LEXICAL_DIMENSIONS];
+    for token in text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2 && !STOP_WORDS.contains(w))
+    {
+        vector[bucket(stem(token))] += 1.0;
+    }
+    vector
+}
+
+/// Fold the most common English suffixes so "deploys"/"deploying" match "deploy".
+fn stem(word: &str) -> &str {
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.len() >= 3 {
+                return stem;
+            }
+        }
+    }
+    word
+}
+
+/// FNV-1a, so buckets are stable across runs and platforms.
+fn bucket(token: &str) -> usize {
+    let hash = token.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    #[allow(clippy::cast_possible_truncation)]
+    let index = (hash % LEXICAL_DIMENSIONS as u64) as usize;
+    index
+}
+
+/// Extra terms appended to the query so the classified intent pulls in
+/// skills that describe it, even when the message doesn't use those words.
+fn intent_terms(classification: &QueryClassification) -> Option<&'static str> {
+    match classification {
+        QueryClassification::CodeGeneration => Some("code programming implement debug"),
+        QueryClassification::Analysis => Some("analysis review explain report"),
+        QueryClassification::Search => Some("search research find web"),
+        QueryClassification::Action => Some("run execute command automate"),
+        _ => None,
+    }
+}
+
+fn skill_text(skill: &MarkdownSkill) -> String {
+    format!("{}\n{}", skill.name(), skill.description())
+}
+
+/// Picks the skills relevant to a user message.
+pub struct SkillSelector {
+    classifier: Box<dyn QueryClassifier>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    top_k: usize,
+    /// Skill embeddings keyed by name + description.
+    cache: Mutex<HashMap<String, Vec<f32>>>,
+}
+
+impl SkillSelector {
+    pub fn new(
+        classifier: Box<dyn QueryClassifier>,
+        embedder: Arc<dyn EmbeddingProvider>,
+        top_k: usize,
+    ) -> Self {
+        Self {
+            classifier,
+            embedder,
+            top_k: top_k.max(1),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Selector for the `[agent]` settings, or `None` when every skill
+    /// should be injected.
+    pub fn from_config(config: &AgentConfig) -> Option<Self> {
+        let top_k = match config.max_skills {
+            0 if config.compact_context => COMPACT_MAX_SKILLS,
+            n => n,
+        };
+        (top_k > 0).then(|| {
+            Self::new(
+                Box::new(KeywordQueryClassifier),
+                Arc::new(LexicalEmbedding),
+                top_k,
+            )
+        })
+    }
+
+    /// Return at most `top_k` skills, most relevant first. Skill sets that
+    /// already fit are returned whole, in their original order.
+    pub async fn select<'a>(
+        &self,
+        query: &str,
+        skills: &'a [MarkdownSkill],
+    ) -> Vec<&'a MarkdownSkill> {
+        if skills.len() <= self.top_k {
+            return skills.iter().collect();
+        }
+
+        let classification = self.classifier.classify(query);
+        let mut query_text = query.to_string();
+        if let Some(terms) = intent_terms(&classification) {
+            query_text.push('\n');
+            query_text.push_str(terms);
+        }
+
+        let (query_vec, skill_vecs) = if self.embedder.dimensions() == 0 {
+            Self::embed_lexical(&query_text, skills)
+        } else {
+            match self.embed(&query_text, skills).await {
+                Ok(vectors) => vectors,
+                Err(e) => {
+                    tracing::warn!("Skill selection falling back to lexical matching: {e}");
+                    Self::embed_lexical(&query_text, skills)
+                }
+            }
+        };
+
+        let mut ranked: Vec<(f32, &MarkdownSkill)> = skills
+            .iter()
+            .zip(&skill_vecs)
+            .map(|(skill, vector)| {
+                // A classifier that names a skill outright always wins.
+                let pinned = matches!(
+                    &classification,
+                    QueryClassification::Custom(name) if name == skill.name()
+                );
+                let score = if pinned {
+                    f32::INFINITY
+                } else {
+                    cosine_similarity(&query_vec, vector)
+                };
+                (score, skill)
+            })
+            .filter(|(score, _)| *score > MIN_SCORE)
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranked.truncate(self.top_k);
+        ranked.into_iter().map(|(_, skill)| skill).collect()
+    }
+
+    async fn embed(
+        &self,
+        query: &str,
+        skills: &[MarkdownSkill],
+    ) -> Result<(Vec<f32>, Vec<Vec<f32>>)> {
+        let mut missing: Vec<String> = {
+            let cache = self.cache.lock();
+            skills
+                .iter()
+                .map(skill_text)
+                .filter(|text| !cache.contains_key(text))
+                .collect()
+        };
+        missing.dedup();
+
+        let mut inputs = Vec::with_capacity(missing.len() + 1);
+        inputs.push(query.to_string());
+        inputs.extend(missing.iter().cloned());
+        let mut vectors = self.embedder.embed(&inputs).await?;
+        if vectors.len() != inputs.len() || vectors.iter().any(Vec::is_empty) {
+            bail!(
+                "embedding provider '{}' returned {} vectors for {} inputs",
+                self.embedder.name(),
+                vectors.len(),
+                inputs.len()
+            );
+        }
+
+        let query_vec = vectors.remove(0);
+        let mut cache = self.cache.lock();
+        cache.extend(missing.into_iter().zip(vectors));
+        let skill_vecs = skills
+            .iter()
+            .map(|skill| cache.get(&skill_text(skill)).cloned().unwrap_or_default())
+            .collect();
+        Ok((query_vec, skill_vecs))
+    }
+
+    fn embed_lexical(query: &str, skills: &[MarkdownSkill]) -> (Vec<f32>, Vec<Vec<f32>>) {
+        (
+            lexical_embedding(query),
+            skills
+                .iter()
+                .map(|skill| lexical_embedding(&skill_text(skill)))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::traits::NoopQueryClassifier;
+    use crate::providers::NoopEmbeddingProvider;
+    use crate::skills::traits::SkillSource;
+
+    fn skill(name: &str, description: &str) -> MarkdownSkill {
+        MarkdownSkill::new(
+            name.into(),
+            description.into(),
+            SkillSource::Bundled,
+            String::new(),
+        )
+    }
+
+    fn catalog() -> Vec<MarkdownSkill> {
+        vec![
+            skill("deploy", "Ship a release to production servers"),
+            skill("email", "Draft and send email replies"),
+            skill("review", "Review a pull request for bugs"),
+            skill("calendar", "Schedule meetings on the calendar"),
+        ]
+    }
+
+    fn names(selected: &[&MarkdownSkill]) -> Vec<String> {
+        selected.iter().map(|s| s.name().to_string()).collect()
+    }
+
+    /// One axis per topic word; counts how often the provider is called.
+    struct TopicEmbedding {
+        calls: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for TopicEmbedding {
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            *self.calls.lock() += 1;
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let text = text.to_lowercase();
+                    ["release", "email", "bug", "meeting"]
+                        .iter()
+                        .map(|topic| if text.contains(topic) { 1.0 } else { 0.0 })
+                        .collect()
+                })
+                .collect())
+        }
+        fn dimensions(&self) -> usize {
+            4
+        }
+        fn model_name(&self) -> &str {
+            "topic"
+        }
+        fn name(&self) -> &str {
+            "topic"
+        }
+    }
+
+    struct PinClassifier;
+
+    impl QueryClassifier for PinClassifier {
+        fn classify(&self, _query: &str) -> QueryClassification {
+            QueryClassification::Custom("calendar".into())
+        }
+        fn name(&self) -> &str {
+            "pin"
+        }
+    }
+
+    #[tokio::test]
+    async fn selector_ranks_skills_with_embedding_provider_and_caches_them() {
+        let embedder = Arc::new(TopicEmbedding {
+            calls: Mutex::new(0),
+        });
+        let selector = SkillSelector::new(Box::new(NoopQueryClassifier), embedder.clone(), 2);
+        let skills = catalog();
+
+        let selected = selector
+            .select("Any bug in the email about the release?", &skills)
+            .await;
+        assert_eq!(names(&selected).len(), 2);
+        assert!(!names(&selected).contains(&"calendar".to_string()));
+
+        let selected = selector.select("book a meeting", &skills).await;
+        assert_eq!(names(&selected), vec!["calendar"]);
+        // Second call only embedded the query; skill vectors came from cache.
+        assert_eq!(*embedder.calls.lock(), 2);
+        assert_eq!(selector.cache.lock().len(), skills.len());
+    }
+
+    #[tokio::test]
+    async fn selector_falls_back_to_lexical_matching_without_embeddings() {
+        let selector = SkillSelector::new(
+            Box::new(KeywordQueryClassifier),
+            Arc::new(NoopEmbeddingProvider),
+            1,
+        );
+        let skills = catalog();
+
+        let selected = selector
+            .select("deploying the new release now", &skills)
+            .await;
+        assert_eq!(names(&selected), vec!["deploy"]);
+
+        // Nothing relevant: inject no skills at all.
+        assert!(selector.select("good morning", &skills).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn selector_keeps_small_sets_and_honours_pinned_classification() {
+        let skills = catalog();
+        let selector =
+            SkillSelector::new(Box::new(NoopQueryClassifier), Arc::new(LexicalEmbedding), 4);
+        assert_eq!(selector.select("hello", &skills).await.len(), 4);
+
+        let selector = SkillSelector::new(Box::new(PinClassifier), Arc::new(LexicalEmbedding), 1);
+        let selected = selector.select("send the email", &skills).await;
+        assert_eq!(names(&selected), vec!["calendar"]);
+    }
+
+    #[test]
+    fn from_config_enables_selection_for_max_skills_or_compact_context() {
+        assert!(SkillSelector::from_config(&AgentConfig::default()).is_none());
+
+        let compact = AgentConfig {
+            compact_context: true,
+            ..AgentConfig::default()
+        };
+        assert_eq!(
+            SkillSelector::from_config(&compact).unwrap().top_k,
+            COMPACT_MAX_SKILLS
+        );
+
+        let explicit = AgentConfig {
+            max_skills: 5,
+            ..AgentConfig::default()
+        };
+        assert_eq!(SkillSelector::from_config(&explicit).unwrap().top_k, 5);
+    }
+}
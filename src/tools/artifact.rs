@@ -0,0 +1,396 @@
+//! Artifact store for large tool outputs (`[tools.artifacts]`).
+//!
+//! When a tool returns more than `max_inline_chars`, [`ArtifactedTool`] saves
+//! the full text to the local media store under `<workspace>/media/artifacts/`
+//! and gives the model a preview plus the artifact ID instead. The
+//! `artifact_read` tool pages through stored text by character offset, so
+//! test logs and build output stay available without filling the context.
+
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::ArtifactsConfig;
+use crate::media::{MediaId, MediaMetadata, MediaStore};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Artifact directory, relative to the workspace.
+pub const ARTIFACT_DIR: &str = "media/artifacts";
+
+/// Stored tool outputs, pruned to the `max_entries` newest.
+pub struct ArtifactStore {
+    dir: PathBuf,
+    media: Box<dyn MediaStore>,
+    config: ArtifactsConfig,
+}
+
+impl ArtifactStore {
+    pub fn new(workspace_dir: &Path, config: ArtifactsConfig) -> Self {
+        let dir = workspace_dir.join(ARTIFACT_DIR);
+        Self {
+            media: crate::media::create_media_store(&dir),
+            dir,
+            config,
+        }
+    }
+
+    /// Store `content` produced by `tool`. Returns the artifact ID.
+    pub async fn save(&self, tool: &str, content: &str) -> Result<String> {
+        let entry = self
+            .media
+            .store(
+                content.as_bytes(),
+                MediaMetadata {
+                    filename: Some(format!("{tool}.txt")),
+                    mime_type: Some("text/plain".into()),
+                    size_bytes: Some(content.len() as u64),
+                    source_url: None,
+                },
+            )
+            .await?;
+        if let Err(e) = self.prune().await {
+            tracing::warn!("Failed to prune tool output artifacts: {e:#}");
+        }
+        Ok(entry.id.0)
+    }
+
+    /// Text of the artifact `id`, or `None` if there is no such artifact.
+    pub async fn load(&self, id: &str) -> Result<Option<String>> {
+        // The media store matches IDs by file name prefix, so only accept
+        // the UUIDs it hands out.
+        if uuid::Uuid::parse_str(id).is_err() || !self.dir.is_dir() {
+            return Ok(None);
+        }
+        let Some(entry) = self.media.get(&MediaId(id.to_string())).await? else {
+            return Ok(None);
+        };
+        let bytes = tokio::fs::read(&entry.path).await?;
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    async fn prune(&self) -> Result<()> {
+        let mut entries = self.media.list().await?;
+        if entries.len() <= self.config.max_entries {
+            return Ok(());
+        }
+        entries.sort_by_cached_key(|entry| {
+            std::fs::metadata(&entry.path)
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+        });
+        let excess = entries.len() - self.config.max_entries;
+        for entry in entries.iter().take(excess) {
+            self.media.delete(&entry.id).await?;
+        }
+        Ok(())
+    }
+
+    /// `text` itself if it fits inline; otherwise a preview that points at
+    /// the stored full text.
+    async fn spill(&self, tool: &str, text: String) -> String {
+        let total = text.chars().count();
+        if total <= self.config.max_inline_chars {
+            return text;
+        }
+        let shown = self.config.preview_chars;
+        let preview: String = text.chars().take(shown).collect();
+        match self.save(tool, &text).await {
+            Ok(id) => format!(
+                "{preview}\n\n[Output truncated: showing the first {shown} of {total} characters. \
+                 Full output saved as artifact {id}; read the rest with \
+                 artifact_read (id=\"{id}\", offset={shown}).]"
+            ),
+            Err(e) => {
+                tracing::warn!("Failed to store {tool} output as an artifact: {e:#}");
+                format!(
+                    "{preview}\n\n[Output truncated: showing the first {shown} of {total} characters; \
+                     the full output could not be stored.]"
+                )
+            }
+        }
+    }
+}
+
+/// A tool whose oversized output (or error message) is moved into the
+/// artifact store.
+pub struct ArtifactedTool {
+    inner: Box<dyn Tool>,
+    store: Arc<ArtifactStore>,
+}
+
+impl ArtifactedTool {
+    pub fn new(inner: Box<dyn Tool>, store: Arc<ArtifactStore>) -> Self {
+        Self { inner, store }
+    }
+}
+
+#[async_trait]
+impl Tool for ArtifactedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner.parameters_schema()
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    fn end_session(&self, session: &str) {
+        self.inner.end_session(session);
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let mut result = self.inner.execute(args).await?;
+        let name = self.inner.name();
+        result.output = self
+            .store
+            .spill(name, std::mem::take(&mut result.output))
+            .await;
+        if let Some(error) = result.error.as_mut() {
+            error.message = self
+                .store
+                .spill(name, std::mem::take(&mut error.message))
+                .await;
+        }
+        Ok(result)
+    }
+}
+
+/// Pages through stored tool outputs.
+pub struct ArtifactReadTool {
+    store: Arc<ArtifactStore>,
+}
+
+impl ArtifactReadTool {
+    pub fn new(store: Arc<ArtifactStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Tool for ArtifactReadTool {
+    fn name(&self) -> &str {
+        "artifact_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read a large tool output that was truncated and saved as an artifact. \
+         Pass the artifact ID from the truncation note; offset and limit count characters"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Artifact ID from a truncated tool result"
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Character offset to start reading from",
+                    "default": 0
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": format!(
+                        "Characters to return (default {}, at most {})",
+                        self.store.config.preview_chars, self.store.config.max_inline_chars
+                    )
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))?;
+        let offset = args
+            .get("offset")
+            .and_then(|v| v.as_u64())
+            .map_or(0, |n| usize::try_from(n).unwrap_or(usize::MAX));
+        let limit = args
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map_or(self.store.config.preview_chars, |n| {
+                usize::try_from(n).unwrap_or(usize::MAX)
+            })
+            .clamp(1, self.store.config.max_inline_chars);
+
+        let text = match self.store.load(id).await {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                return Ok(ToolResult::failure(ToolError::not_found(format!(
+                    "No artifact with ID {id}"
+                ))))
+            }
+            Err(e) => {
+                return Ok(ToolResult::failure(ToolError::failed(format!(
+                    "Failed to read artifact {id}: {e}"
+                ))))
+            }
+        };
+
+        let total = text.chars().count();
+        let start = offset.min(total);
+        let page: String = text.chars().skip(start).take(limit).collect();
+        let end = start + page.chars().count();
+        let footer = if end < total {
+            format!("[Characters {start}-{end} of {total}; continue with offset={end}]")
+        } else {
+            format!("[Characters {start}-{end} of {total}; end of artifact]")
+        };
+        Ok(ToolResult {
+            success: true,
+            output: format!("{page}\n\n{footer}"),
+            error: None,
+        })
+    }
+}
+
+/// Route every tool's oversized output through `store` and add
+/// `artifact_read`.
+pub fn artifact_tools(tools: Vec<Box<dyn Tool>>, store: &Arc<ArtifactStore>) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = tools
+        .into_iter()
+        .map(|tool| Box::new(ArtifactedTool::new(tool, Arc::clone(store))) as Box<dyn Tool>)
+        .collect();
+    tools.push(Box::new(ArtifactReadTool::new(Arc::clone(store))));
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text argument"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object", "properties": {"text": {"type": "string"}}})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: args["text"].as_str().unwrap_or_default().to_string(),
+                error: None,
+            })
+        }
+    }
+
+    fn store(workspace: &Path, max_entries: usize) -> Arc<ArtifactStore> {
+        Arc::new(ArtifactStore::new(
+            workspace,
+            ArtifactsConfig {
+                enabled: true,
+                max_inline_chars: 100,
+                preview_chars: 10,
+                max_entries,
+            },
+        ))
+    }
+
+    fn artifact_id(output: &str) -> String {
+        let start = output.find("artifact_read (id=\"").unwrap() + "artifact_read (id=\"".len();
+        output[start..start + 36].to_string()
+    }
+
+    #[tokio::test]
+    async fn large_outputs_are_stored_and_paged() {
+        let tmp = TempDir::new().unwrap();
+        let tools = artifact_tools(vec![Box::new(EchoTool)], &store(tmp.path(), 10));
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["echo", "artifact_read"]);
+
+        let small = tools[0].execute(json!({"text": "short"})).await.unwrap();
+        assert_eq!(small.output, "short");
+
+        let log = "abcdefghijklmnopqrstuvwxy".repeat(6);
+        let spilled = tools[0].execute(json!({"text": log})).await.unwrap();
+        assert!(spilled
+            .output
+            .starts_with("abcdefghij\n\n[Output truncated"));
+        assert!(spilled.output.contains("first 10 of 150 characters"));
+
+        let id = artifact_id(&spilled.output);
+        let page = tools[1]
+            .execute(json!({"id": id, "offset": 10, "limit": 5}))
+            .await
+            .unwrap();
+        assert!(page.success);
+        assert_eq!(
+            page.output,
+            "klmno\n\n[Characters 10-15 of 150; continue with offset=15]"
+        );
+        let tail = tools[1]
+            .execute(json!({"id": id, "offset": 140}))
+            .await
+            .unwrap();
+        assert!(tail
+            .output
+            .ends_with("[Characters 140-150 of 150; end of artifact]"));
+    }
+
+    #[tokio::test]
+    async fn unknown_or_malformed_ids_are_not_found() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(tmp.path(), 10);
+        store.save("shell", "stored").await.unwrap();
+        let read = ArtifactReadTool::new(store);
+        for id in [
+            "",
+            "../../etc/passwd",
+            "6f1c2a9e-0000-4000-8000-000000000000",
+        ] {
+            let result = read.execute(json!({"id": id})).await.unwrap();
+            assert!(!result.success, "{id:?} should not resolve");
+            assert_eq!(
+                result.error.unwrap().kind,
+                crate::tools::ToolErrorKind::NotFound
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_newest_artifacts_are_kept() {
+        let tmp = TempDir::new().unwrap();
+        let store = store(tmp.path(), 2);
+        let first = store.save("shell", "one").await.unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let second = store.save("shell", "two").await.unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let third = store.save("shell", "three").await.unwrap();
+
+        assert_eq!(store.load(&first).await.unwrap(), None);
+        assert_eq!(store.load(&second).await.unwrap().as_deref(), Some("two"));
+        assert_eq!(store.load(&third).await.unwrap().as_deref(), Some("three"));
+    }
+}
@@ -0,0 +1,604 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::{CalendarConfig, CalendarScope};
+use crate::security::SecurityPolicy;
+use crate::util::time::TimeService;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Proxy service key for CalDAV requests (`[proxy] services`).
+const CALENDAR_SERVICE_KEY: &str = "tool.calendar";
+const REQUEST_TIMEOUT_SECS: u64 = 20;
+const MAX_DAYS: u64 = 31;
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+/// Start of a calendar event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventTime {
+    At(DateTime<Utc>),
+    AllDay(NaiveDate),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CalendarEvent {
+    summary: String,
+    /// Always set on parsed events.
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    location: String,
+    description: String,
+    attendees: Vec<String>,
+    cancelled: bool,
+}
+
+/// List events from the user's CalDAV calendars (read-only)
+pub struct CalendarListTool {
+    config: CalendarConfig,
+    security: Arc<SecurityPolicy>,
+    time: TimeService,
+}
+
+impl CalendarListTool {
+    pub fn new(config: CalendarConfig, security: Arc<SecurityPolicy>, time: TimeService) -> Self {
+        Self {
+            config,
+            security,
+            time,
+        }
+    }
+
+    async fn list(&self, args: &serde_json::Value) -> anyhow::Result<String> {
+        let now = Utc::now();
+        let first_day = match args.get("day").and_then(|v| v.as_str()) {
+            Some(day) => self.time.parse_date_at(day, now)?,
+            None => self.time.localize(now).date_naive(),
+        };
+        let days = args
+            .get("days")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1)
+            .clamp(1, MAX_DAYS);
+        let start = self.time.start_of_day(first_day)?;
+        let end = self.time.start_of_day(first_day + Days::new(days))?;
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+
+        let client = crate::config::build_runtime_proxy_client_with_timeouts(
+            CALENDAR_SERVICE_KEY,
+            REQUEST_TIMEOUT_SECS,
+            10,
+        );
+        let mut events = Vec::new();
+        for url in &self.config.calendars {
+            let body = self.report(&client, url, start, end).await?;
+            for ics in calendar_data(&body)? {
+                events.extend(parse_events(&ics, self.time));
+            }
+        }
+        events.retain(|event| {
+            !event.cancelled
+                && self.overlaps(event, start, end)
+                && query
+                    .as_ref()
+                    .is_none_or(|q| event.summary.to_lowercase().contains(q))
+        });
+        events.sort_by_cached_key(|event| (self.start_instant(event), event.summary.clone()));
+        events.dedup();
+
+        let last_day = first_day + Days::new(days - 1);
+        let range = if days == 1 {
+            first_day.format("%a %Y-%m-%d").to_string()
+        } else {
+            format!(
+                "{} – {}",
+                first_day.format("%a %Y-%m-%d"),
+                last_day.format("%a %Y-%m-%d")
+            )
+        };
+        if events.is_empty() {
+            return Ok(format!("No events on {range}."));
+        }
+        let details = self.config.allows(CalendarScope::EventDetails);
+        let mut output = format!("{} event(s) on {range}:\n", events.len());
+        for event in events.iter().take(self.config.max_results) {
+            let _ = writeln!(output, "- {}", self.describe(event, details));
+        }
+        if events.len() > self.config.max_results {
+            let _ = writeln!(
+                output,
+                "... and {} more",
+                events.len() - self.config.max_results
+            );
+        }
+        Ok(output)
+    }
+
+    async fn report(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<String> {
+        let range = format!(
+            r#"start="{}" end="{}""#,
+            start.format("%Y%m%dT%H%M%SZ"),
+            end.format("%Y%m%dT%H%M%SZ")
+        );
+        // `expand` asks the server to turn recurring events into instances.
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<C:calendar-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:caldav">
+  <D:prop><C:calendar-data><C:expand {range}/></C:calendar-data></D:prop>
+  <C:filter><C:comp-filter name="VCALENDAR"><C:comp-filter name="VEVENT"><C:time-range {range}/></C:comp-filter></C:comp-filter></C:filter>
+</C:calendar-query>"#
+        );
+        let response = client
+            .request(reqwest::Method::from_bytes(b"REPORT")?, url)
+            .basic_auth(&self.config.username, self.config.password.as_deref())
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("CalDAV request to {url} failed"))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("CalDAV server returned {status} for {url}");
+        }
+        Ok(response.text().await?)
+    }
+
+    fn instant(&self, time: EventTime) -> Option<DateTime<Utc>> {
+        match time {
+            EventTime::At(at) => Some(at),
+            EventTime::AllDay(date) => self.time.start_of_day(date).ok(),
+        }
+    }
+
+    fn start_instant(&self, event: &CalendarEvent) -> Option<DateTime<Utc>> {
+        event.start.and_then(|start| self.instant(start))
+    }
+
+    fn overlaps(&self, event: &CalendarEvent, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        let Some(event_start) = self.start_instant(event) else {
+            return false;
+        };
+        let event_end = event
+            .end
+            .and_then(|end| self.instant(end))
+            .unwrap_or(event_start);
+        event_start < end && (event_end > start || event_start >= start)
+    }
+
+    fn describe(&self, event: &CalendarEvent, details: bool) -> String {
+        let title = if event.summary.is_empty() {
+            "(no title)"
+        } else {
+            &event.summary
+        };
+        let mut line = match (event.start, event.end) {
+            (None, _) => title.to_string(),
+            (Some(EventTime::AllDay(date)), _) => {
+                format!("{} (all day): {title}", date.format("%a %Y-%m-%d"))
+            }
+            (Some(EventTime::At(start)), end) => {
+                let start = self.time.localize(start);
+                let mut when = start.format("%a %Y-%m-%d %H:%M").to_string();
+                if let Some(EventTime::At(end)) = end {
+                    let end = self.time.localize(end);
+                    if end.date_naive() == start.date_naive() {
+                        let _ = write!(when, "–{}", end.format("%H:%M"));
+                    } else {
+                        let _ = write!(when, " – {}", end.format("%a %Y-%m-%d %H:%M"));
+                    }
+                }
+                format!("{when}: {title}")
+            }
+        };
+        if details {
+            if !event.location.is_empty() {
+                let _ = write!(line, "\n  Location: {}", event.location);
+            }
+            if !event.attendees.is_empty() {
+                let _ = write!(line, "\n  Attendees: {}", event.attendees.join(", "));
+            }
+            if !event.description.is_empty() {
+                let _ = write!(
+                    line,
+                    "\n  Notes: {}",
+                    truncate_with_ellipsis(&event.description, MAX_DESCRIPTION_CHARS)
+                );
+            }
+        }
+        line
+    }
+}
+
+/// The `calendar-data` payloads of a CalDAV multistatus response.
+fn calendar_data(xml: &str) -> anyhow::Result<Vec<String>> {
+    let mut reader = Reader::from_str(xml);
+    let mut payloads = Vec::new();
+    let mut current: Option<String> = None;
+    loop {
+        match reader.read_event().context("Invalid CalDAV response")? {
+            Event::Start(e) if e.local_name().as_ref() == b"calendar-data" => {
+                current = Some(String::new());
+            }
+            Event::Text(text) => {
+                if let Some(data) = current.as_mut() {
+                    data.push_str(&text.unescape().context("Invalid CalDAV response")?);
+                }
+            }
+            Event::CData(cdata) => {
+                if let Some(data) = current.as_mut() {
+                    data.push_str(&String::from_utf8_lossy(&cdata.into_inner()));
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"calendar-data" => {
+                payloads.extend(current.take());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(payloads)
+}
+
+/// `VEVENT`s of an iCalendar document. Floating times are read in the
+/// configured zone.
+fn parse_events(ics: &str, time: TimeService) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut components: Vec<String> = Vec::new();
+    let mut event: Option<CalendarEvent> = None;
+    for line in unfold(ics) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        match name.as_str() {
+            "BEGIN" => {
+                if value.eq_ignore_ascii_case("VEVENT") {
+                    event = Some(CalendarEvent::default());
+                }
+                components.push(value.to_ascii_uppercase());
+                continue;
+            }
+            "END" => {
+                if components.pop().as_deref() == Some("VEVENT") {
+                    events.extend(event.take().filter(|e| e.start.is_some()));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        // Properties of nested components (e.g. VALARM) are skipped.
+        if components.last().map(String::as_str) != Some("VEVENT") {
+            continue;
+        }
+        let Some(event) = event.as_mut() else {
+            continue;
+        };
+        match name.as_str() {
+            "SUMMARY" => event.summary = unescape_text(&value),
+            "LOCATION" => event.location = unescape_text(&value),
+            "DESCRIPTION" => event.description = unescape_text(&value),
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            "DTSTART" => event.start = parse_event_time(&params, &value, time),
+            "DTEND" => event.end = parse_event_time(&params, &value, time),
+            "ATTENDEE" => {
+                let name = param(&params, "CN")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| value.trim_start_matches("mailto:").to_string());
+                event.attendees.push(name);
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Content lines with RFC 5545 folding undone.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in ics.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+/// `NAME;PARAM=x:VALUE` → upper-cased name, raw params, value. Colons
+/// inside quoted parameter values do not end the parameters.
+fn split_property(line: &str) -> Option<(String, String, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((
+        name.to_ascii_uppercase(),
+        params.to_string(),
+        value.to_string(),
+    ))
+}
+
+fn param<'a>(params: &'a str, key: &str) -> Option<&'a str> {
+    params.split(';').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        name.eq_ignore_ascii_case(key)
+            .then(|| value.trim_matches('"'))
+    })
+}
+
+fn parse_event_time(params: &str, value: &str, time: TimeService) -> Option<EventTime> {
+    let value = value.trim();
+    if value.len() == 8 || param(params, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::AllDay);
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(EventTime::At(Utc.from_utc_datetime(&naive)));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let at = match param(params, "TZID").and_then(|tz| tz.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => tz
+            .from_local_datetime(&naive)
+            .earliest()?
+            .with_timezone(&Utc),
+        None => time.resolve(naive).ok()?,
+    };
+    Some(EventTime::At(at))
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.trim().to_string()
+}
+
+#[async_trait]
+impl Tool for CalendarListTool {
+    fn name(&self) -> &str {
+        "calendar_list"
+    }
+
+    fn description(&self) -> &str {
+        "List events from the user's calendars (read-only). Use 'day' ('today', 'tomorrow', 'friday', 'next monday', 'YYYY-MM-DD') and optionally 'days' to cover several days; 'query' filters by title."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "day": {
+                    "type": "string",
+                    "description": "First day to list, in the user's timezone (default: today)"
+                },
+                "days": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_DAYS,
+                    "description": "Number of days to cover (default: 1)"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Only events whose title contains this text"
+                }
+            }
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.config.allows(CalendarScope::Events) {
+            return Ok(ToolResult::failure(ToolError::permission_denied(
+                "Calendar access has not been approved (assistant.calendar.scopes)",
+            )));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+        match self.list(&args).await {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ICS: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:1\r\n\
+SUMMARY:Design review\\, round 2\r\n\
+DTSTART:20260303T150000Z\r\n\
+DTEND:20260303T160000Z\r\n\
+LOCATION:Room 4\r\n\
+DESCRIPTION:Bring the\\nmockups\r\n\
+ATTENDEE;CN=\"Ana: PM\";ROLE=REQ-PARTICIPANT:mailto:ana@example.com\r\n\
+ATTENDEE:mailto:bo@example.com\r\n\
+BEGIN:VALARM\r\n\
+DESCRIPTION:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:2\r\n\
+SUMMARY:Offsite\r\n\
+DTSTART;VALUE=DATE:20260303\r\n\
+DTEND;VALUE=DATE:20260304\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:3\r\n\
+SUMMARY:Stand\r\n up\r\n\
+DTSTART;TZID=Europe/Berlin:20260303T090000\r\n\
+STATUS:CANCELLED\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    fn utc_time() -> TimeService {
+        TimeService::new(Some("UTC")).unwrap()
+    }
+
+    fn config(url: &str, scopes: Vec<CalendarScope>) -> CalendarConfig {
+        CalendarConfig {
+            calendars: vec![url.into()],
+            username: "me".into(),
+            password: Some("pw".into()),
+            scopes,
+            max_results: 50,
+        }
+    }
+
+    #[test]
+    fn parses_events_from_icalendar() {
+        let events = parse_events(ICS, utc_time());
+        assert_eq!(events.len(), 3);
+
+        let review = &events[0];
+        assert_eq!(review.summary, "Design review, round 2");
+        assert_eq!(
+            review.start,
+            Some(EventTime::At(
+                Utc.with_ymd_and_hms(2026, 3, 3, 15, 0, 0).unwrap()
+            ))
+        );
+        assert_eq!(review.description, "Bring the\nmockups");
+        assert_eq!(review.attendees, vec!["Ana: PM", "bo@example.com"]);
+
+        assert_eq!(
+            events[1].start,
+            Some(EventTime::AllDay(
+                NaiveDate::from_ymd_opt(2026, 3, 3).unwrap()
+            ))
+        );
+        // Folded line, TZID start, cancelled status.
+        assert_eq!(events[2].summary, "Standup");
+        assert_eq!(
+            events[2].start,
+            Some(EventTime::At(
+                Utc.with_ymd_and_hms(2026, 3, 3, 8, 0, 0).unwrap()
+            ))
+        );
+        assert!(events[2].cancelled);
+    }
+
+    #[test]
+    fn extracts_calendar_data_from_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:cal="urn:ietf:params:xml:ns:caldav">
+  <d:response><d:propstat><d:prop>
+    <cal:calendar-data>BEGIN:VCALENDAR&#13;
+END:VCALENDAR</cal:calendar-data>
+  </d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+        let data = calendar_data(xml).unwrap();
+        assert_eq!(data.len(), 1);
+        assert!(data[0].starts_with("BEGIN:VCALENDAR"));
+    }
+
+    #[tokio::test]
+    async fn lists_events_within_approved_scopes() {
+        let server = MockServer::start().await;
+        let multistatus = format!(
+            r#"<d:multistatus xmlns:d="DAV:" xmlns:c="urn:ietf:params:xml:ns:caldav"><d:response><d:propstat><d:prop><c:calendar-data><![CDATA[{ICS}]]></c:calendar-data></d:prop></d:propstat></d:response></d:multistatus>"#
+        );
+        Mock::given(method("REPORT"))
+            .and(header("Depth", "1"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(multistatus))
+            .mount(&server)
+            .await;
+        let url = format!("{}/cal/", server.uri());
+        let security = Arc::new(SecurityPolicy::default());
+
+        let tool = CalendarListTool::new(
+            config(&url, vec![CalendarScope::Events]),
+            Arc::clone(&security),
+            utc_time(),
+        );
+        let result = tool.execute(json!({"day": "2026-03-03"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("2 event(s) on Tue 2026-03-03"));
+        // All-day events sort first.
+        assert!(result.output.find("Offsite") < result.output.find("Design review"));
+        assert!(result.output.contains("Tue 2026-03-03 (all day): Offsite"));
+        assert!(result
+            .output
+            .contains("Tue 2026-03-03 15:00–16:00: Design review, round 2"));
+        assert!(!result.output.contains("Room 4"));
+
+        let tool = CalendarListTool::new(
+            config(
+                &url,
+                vec![CalendarScope::Events, CalendarScope::EventDetails],
+            ),
+            Arc::clone(&security),
+            utc_time(),
+        );
+        let result = tool
+            .execute(json!({"day": "2026-03-03", "query": "review"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("1 event(s)"));
+        assert!(result.output.contains("Location: Room 4"));
+        assert!(result.output.contains("Attendees: Ana: PM, bo@example.com"));
+    }
+
+    #[tokio::test]
+    async fn refuses_without_approved_scope() {
+        let tool = CalendarListTool::new(
+            config("https://example.com/cal/", Vec::new()),
+            Arc::new(SecurityPolicy::default()),
+            utc_time(),
+        );
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error_message()
+            .unwrap()
+            .contains("assistant.calendar.scopes"));
+    }
+}
@@ -0,0 +1,455 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use grep_regex::RegexMatcherBuilder;
+use grep_searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkMatch};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const DEFAULT_MAX_RESULTS: usize = 50;
+const MAX_RESULTS_LIMIT: usize = 500;
+const MAX_CONTEXT_LINES: usize = 10;
+/// Matched lines longer than this are cut to keep results readable.
+const MAX_LINE_CHARS: usize = 300;
+
+/// Regex/literal search across workspace files, ripgrep-style.
+///
+/// Uses the `grep`/`ignore` crates directly so `rg` does not need to be
+/// installed or allowlisted. Respects `.gitignore` and skips hidden and
+/// binary files.
+pub struct CodeSearchTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl CodeSearchTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+/// Search parameters after validation.
+struct SearchRequest {
+    pattern: String,
+    literal: bool,
+    case_insensitive: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    context: usize,
+    max_results: usize,
+}
+
+struct SearchOutcome {
+    lines: Vec<String>,
+    matches: usize,
+    files: usize,
+    truncated: bool,
+}
+
+fn string_list(args: &serde_json::Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl Tool for CodeSearchTool {
+    fn name(&self) -> &str {
+        "code_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search workspace files for a regex or literal string (ripgrep-style). Supports include/exclude globs, context lines and a result cap; respects .gitignore and skips binary files."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "description": "Regular expression (or literal text when literal=true) to search for"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory or file to search, relative to the workspace (default: \".\")"
+                },
+                "literal": {
+                    "type": "boolean",
+                    "description": "Treat pattern as a literal string instead of a regex (default: false)"
+                },
+                "case_insensitive": {
+                    "type": "boolean",
+                    "description": "Match case-insensitively (default: false)"
+                },
+                "include": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only search files matching these globs (e.g. [\"*.rs\", \"src/**\"])"
+                },
+                "exclude": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Skip files matching these globs"
+                },
+                "context": {
+                    "type": "integer",
+                    "description": "Lines of context before and after each match (default: 0, max: 10)"
+                },
+                "max_results": {
+                    "type": "integer",
+                    "description": "Maximum number of matching lines to return (default: 50, max: 500)"
+                }
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let pattern = args
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' parameter"))?;
+        let path = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: too many actions in the last hour",
+            )));
+        }
+        if !self.security.is_path_allowed(path) {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                "Path not allowed by security policy: {path}"
+            ))));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+
+        let root = match tokio::fs::canonicalize(self.security.resolve_tool_path(path)).await {
+            Ok(p) => p,
+            Err(e) => {
+                return Ok(ToolResult::failure(ToolError::io(
+                    format!("Failed to resolve search path: {e}"),
+                    &e,
+                )))
+            }
+        };
+        if !self.security.is_resolved_path_allowed(&root) {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(
+                self.security.resolved_path_violation_message(&root),
+            )));
+        }
+
+        let request = SearchRequest {
+            pattern: pattern.to_string(),
+            literal: args
+                .get("literal")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            case_insensitive: args
+                .get("case_insensitive")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            include: string_list(&args, "include"),
+            exclude: string_list(&args, "exclude"),
+            context: args
+                .get("context")
+                .and_then(|v| v.as_u64())
+                .map_or(0, |n| usize::try_from(n).unwrap_or(MAX_CONTEXT_LINES))
+                .min(MAX_CONTEXT_LINES),
+            max_results: args
+                .get("max_results")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_MAX_RESULTS, |n| {
+                    usize::try_from(n).unwrap_or(MAX_RESULTS_LIMIT)
+                })
+                .clamp(1, MAX_RESULTS_LIMIT),
+        };
+
+        let display_root = self
+            .security
+            .workspace_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.security.workspace_dir.clone());
+        let outcome =
+            tokio::task::spawn_blocking(move || search(&root, &display_root, &request)).await?;
+
+        Ok(match outcome {
+            Ok(outcome) if outcome.matches == 0 => ToolResult {
+                success: true,
+                output: "No matches found".into(),
+                error: None,
+            },
+            Ok(outcome) => {
+                let mut output = format!(
+                    "{} match(es) in {} file(s)\n{}",
+                    outcome.matches,
+                    outcome.files,
+                    outcome.lines.join("\n")
+                );
+                if outcome.truncated {
+                    let _ = write!(
+                        output,
+                        "\n(results truncated at {} matches; narrow the search or raise max_results)",
+                        outcome.matches
+                    );
+                }
+                ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                }
+            }
+            Err(e) => ToolResult::failure(ToolError::failed(e.to_string())),
+        })
+    }
+}
+
+fn search(
+    root: &Path,
+    display_root: &Path,
+    request: &SearchRequest,
+) -> anyhow::Result<SearchOutcome> {
+    let matcher = RegexMatcherBuilder::new()
+        .case_insensitive(request.case_insensitive)
+        .fixed_strings(request.literal)
+        .build(&request.pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid pattern: {e}"))?;
+
+    let mut overrides = OverrideBuilder::new(root);
+    for glob in &request.include {
+        overrides.add(glob)?;
+    }
+    for glob in &request.exclude {
+        overrides.add(&format!("!{glob}"))?;
+    }
+
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .before_context(request.context)
+        .after_context(request.context)
+        .build();
+
+    let mut outcome = SearchOutcome {
+        lines: Vec::new(),
+        matches: 0,
+        files: 0,
+        truncated: false,
+    };
+    let walker = WalkBuilder::new(root)
+        .overrides(overrides.build()?)
+        .sort_by_file_path(Path::cmp)
+        .build();
+    for entry in walker {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if outcome.matches >= request.max_results {
+            outcome.truncated = true;
+            break;
+        }
+
+        let display = display_path(entry.path(), display_root);
+        let before = outcome.matches;
+        let mut sink = Collector {
+            path: &display,
+            max_results: request.max_results,
+            outcome: &mut outcome,
+        };
+        // Unreadable files are skipped rather than failing the whole search.
+        let _ = searcher.search_path(&matcher, entry.path(), &mut sink);
+        if outcome.matches > before {
+            outcome.files += 1;
+        }
+    }
+    Ok(outcome)
+}
+
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Collects `path:line:text` match lines and `path-line-text` context lines.
+struct Collector<'a> {
+    path: &'a str,
+    max_results: usize,
+    outcome: &'a mut SearchOutcome,
+}
+
+impl Collector<'_> {
+    fn push(&mut self, line_number: Option<u64>, bytes: &[u8], separator: char) {
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.trim_end_matches(['\r', '\n']);
+        let text = match text.char_indices().nth(MAX_LINE_CHARS) {
+            Some((idx, _)) => format!("{}...", &text[..idx]),
+            None => text.to_string(),
+        };
+        self.outcome.lines.push(format!(
+            "{}{separator}{}{separator}{text}",
+            self.path,
+            line_number.unwrap_or_default()
+        ));
+    }
+}
+
+impl Sink for Collector<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        if self.outcome.matches >= self.max_results {
+            self.outcome.truncated = true;
+            return Ok(false);
+        }
+        self.outcome.matches += 1;
+        self.push(mat.line_number(), mat.bytes(), ':');
+        Ok(self.outcome.matches < self.max_results)
+    }
+
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        ctx: &SinkContext<'_>,
+    ) -> Result<bool, Self::Error> {
+        self.push(ctx.line_number(), ctx.bytes(), '-');
+        Ok(true)
+    }
+
+    fn context_break(&mut self, _searcher: &Searcher) -> Result<bool, Self::Error> {
+        self.outcome.lines.push("--".into());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_security(workspace: PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn workspace() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(
+            tmp.path().join("src/main.rs"),
+            "fn main() {\n    let answer = 42;\n    println!(\"{answer}\");\n}\n",
+        )
+        .unwrap();
+        std::fs::write(tmp.path().join("notes.md"), "The Answer is 42.\n").unwrap();
+        std::fs::write(tmp.path().join("blob.bin"), b"answer\x00\x01\x02").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn code_search_schema_requires_pattern() {
+        let tool = CodeSearchTool::new(test_security(std::env::temp_dir()));
+        assert_eq!(tool.name(), "code_search");
+        let schema = tool.parameters_schema();
+        assert!(schema["properties"]["include"].is_object());
+        assert_eq!(schema["required"], json!(["pattern"]));
+    }
+
+    #[tokio::test]
+    async fn code_search_finds_matches_with_globs_and_context() {
+        let tmp = workspace();
+        let tool = CodeSearchTool::new(test_security(tmp.path().to_path_buf()));
+
+        let result = tool
+            .execute(json!({"pattern": "answer", "case_insensitive": true}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("3 match(es) in 2 file(s)"));
+        assert!(result.output.contains("notes.md:1:The Answer is 42."));
+        assert!(!result.output.contains("blob.bin"));
+
+        let result = tool
+            .execute(json!({
+                "pattern": "answer = 42",
+                "literal": true,
+                "include": ["*.rs"],
+                "context": 1
+            }))
+            .await
+            .unwrap();
+        let main_rs = Path::new("src").join("main.rs").display().to_string();
+        assert!(result.output.contains(&format!("{main_rs}-1-fn main() {{")));
+        assert!(result
+            .output
+            .contains(&format!("{main_rs}:2:    let answer = 42;")));
+
+        let result = tool
+            .execute(json!({"pattern": "42", "exclude": ["*.md"]}))
+            .await
+            .unwrap();
+        assert!(!result.output.contains("notes.md"));
+    }
+
+    #[tokio::test]
+    async fn code_search_caps_results_and_reports_no_matches() {
+        let tmp = workspace();
+        let tool = CodeSearchTool::new(test_security(tmp.path().to_path_buf()));
+
+        let result = tool
+            .execute(json!({"pattern": "answer", "case_insensitive": true, "max_results": 1}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("1 match(es)"));
+        assert!(result.output.contains("results truncated"));
+
+        let result = tool
+            .execute(json!({"pattern": "does-not-exist"}))
+            .await
+            .unwrap();
+        assert_eq!(result.output, "No matches found");
+
+        let result = tool.execute(json!({"pattern": "("})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("Invalid pattern"));
+    }
+
+    #[tokio::test]
+    async fn code_search_blocks_paths_outside_workspace() {
+        let tmp = workspace();
+        let tool = CodeSearchTool::new(test_security(tmp.path().to_path_buf()));
+        let result = tool
+            .execute(json!({"pattern": "root", "path": "../"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("not allowed"));
+    }
+}
@@ -0,0 +1,528 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::DatabaseConfig;
+use crate::security::policy::ToolOperation;
+use crate::security::{AutonomyLevel, SecurityPolicy};
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+const QUERY_TIMEOUT_SECS: u64 = 30;
+/// Leading keywords accepted on `read_only` databases. SQLite connections are
+/// opened read-only and Postgres reads run in a `READ ONLY` transaction, so a
+/// data-modifying `WITH` is still refused by the server.
+const READ_KEYWORDS: &[&str] = &["SELECT", "WITH", "VALUES", "EXPLAIN"];
+
+/// Run SQL against the databases configured in `[[tools.databases]]`.
+///
+/// Statements on `read_only` databases must be a single `SELECT`-style query.
+/// Writes on other databases count as actions and need `approved=true` in
+/// supervised mode. Results are capped at `max_rows` rows and `max_bytes`
+/// bytes of row data.
+pub struct DbQueryTool {
+    databases: Vec<DatabaseConfig>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl DbQueryTool {
+    pub fn new(databases: Vec<DatabaseConfig>, security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            databases,
+            security,
+        }
+    }
+}
+
+/// Result rows, cut at the configured caps.
+#[derive(Debug, Default)]
+struct Rows {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    bytes: usize,
+    truncated: bool,
+}
+
+impl Rows {
+    /// Append a row unless a cap is reached. Returns `false` once full.
+    fn push(&mut self, row: Vec<Value>, database: &DatabaseConfig) -> bool {
+        let size = serde_json::to_string(&row).map_or(0, |s| s.len());
+        if self.rows.len() >= database.max_rows || self.bytes + size > database.max_bytes {
+            self.truncated = true;
+            return false;
+        }
+        self.bytes += size;
+        self.rows.push(row);
+        true
+    }
+
+    fn into_json(self) -> Value {
+        json!({
+            "columns": self.columns,
+            "row_count": self.rows.len(),
+            "rows": self.rows,
+            "truncated": self.truncated,
+        })
+    }
+}
+
+/// Strip comments and return the statement's leading keyword (upper-cased).
+/// Fails on empty input and on more than one statement.
+fn leading_keyword(sql: &str) -> Result<String, String> {
+    let mut code = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    let mut ended = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '-' if chars.peek() == Some(&'-') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                code.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                code.push(' ');
+            }
+            '$' if !code.ends_with(|p: char| p == '_' || p.is_alphanumeric())
+                && dollar_quote_tag(&chars).is_some() =>
+            {
+                if ended {
+                    return Err("Only one SQL statement per call is allowed".into());
+                }
+                // Postgres dollar quoting: `$tag$ ... $tag$` is one string,
+                // whatever quotes or semicolons it contains.
+                let tag = dollar_quote_tag(&chars).unwrap_or_default();
+                let delimiter = format!("${tag}$");
+                code.push_str(&delimiter);
+                for _ in 0..=tag.chars().count() {
+                    chars.next();
+                }
+                let mut body = String::new();
+                for q in chars.by_ref() {
+                    body.push(q);
+                    if body.ends_with(&delimiter) {
+                        break;
+                    }
+                }
+                code.push_str(&body);
+            }
+            '\'' | '"' | '`' => {
+                if ended {
+                    return Err("Only one SQL statement per call is allowed".into());
+                }
+                code.push(c);
+                for q in chars.by_ref() {
+                    code.push(q);
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            ';' => ended = true,
+            c if ended && !c.is_whitespace() => {
+                return Err("Only one SQL statement per call is allowed".into());
+            }
+            c => code.push(c),
+        }
+    }
+    let keyword: String = code
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .chars()
+        .take_while(char::is_ascii_alphabetic)
+        .collect();
+    if keyword.is_empty() {
+        return Err("SQL statement is empty".into());
+    }
+    Ok(keyword.to_ascii_uppercase())
+}
+
+/// Tag of a Postgres dollar quote opening right after a `$` (empty for `$$`),
+/// or `None` when the `$` starts something else, such as a `$1` parameter.
+fn dollar_quote_tag(rest: &std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+    let mut tag = String::new();
+    for c in rest.clone() {
+        match c {
+            '$' => return Some(tag),
+            c if c == '_' || c.is_alphabetic() || (c.is_ascii_digit() && !tag.is_empty()) => {
+                tag.push(c);
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn is_read_keyword(keyword: &str) -> bool {
+    READ_KEYWORDS.contains(&keyword)
+}
+
+/// Filesystem path for a `sqlite:` URL; relative paths resolve against
+/// `workspace`.
+fn sqlite_path(url: &str, workspace: &Path) -> Option<PathBuf> {
+    let rest = url.trim().strip_prefix("sqlite:")?;
+    let rest = rest.strip_prefix("//").unwrap_or(rest);
+    if rest.is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(shellexpand::tilde(rest).as_ref());
+    Some(if path.is_absolute() {
+        path
+    } else {
+        workspace.join(path)
+    })
+}
+
+fn sqlite_query(
+    path: &Path,
+    sql: &str,
+    write: bool,
+    database: &DatabaseConfig,
+) -> anyhow::Result<Value> {
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, OpenFlags};
+
+    let flags = if write {
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX
+    };
+    let conn = Connection::open_with_flags(path, flags)
+        .with_context(|| format!("Failed to open SQLite database '{}'", database.name))?;
+    conn.busy_timeout(Duration::from_secs(5))?;
+
+    let mut stmt = conn.prepare(sql)?;
+    if !write && !stmt.readonly() {
+        bail!("Statement would modify the database");
+    }
+    if stmt.column_count() == 0 {
+        let affected = stmt.execute([])?;
+        return Ok(json!({ "rows_affected": affected }));
+    }
+    let mut result = Rows {
+        columns: stmt.column_names().iter().map(|c| c.to_string()).collect(),
+        ..Rows::default()
+    };
+    let column_count = result.columns.len();
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let mut values = Vec::with_capacity(column_count);
+        for i in 0..column_count {
+            values.push(match row.get_ref(i)? {
+                ValueRef::Null => Value::Null,
+                ValueRef::Integer(n) => json!(n),
+                ValueRef::Real(f) => json!(f),
+                ValueRef::Text(t) => json!(String::from_utf8_lossy(t)),
+                ValueRef::Blob(b) => json!(format!("<blob {} bytes>", b.len())),
+            });
+        }
+        if !result.push(values, database) {
+            break;
+        }
+    }
+    Ok(result.into_json())
+}
+
+#[cfg(feature = "db-postgres")]
+async fn postgres_query(
+    database: &DatabaseConfig,
+    sql: &str,
+    write: bool,
+) -> anyhow::Result<Value> {
+    use tokio_postgres::SimpleQueryMessage;
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let tls = tokio_postgres_rustls::MakeRustlsConnect::new(tls_config);
+    let (mut client, connection) = tokio_postgres::connect(database.url.trim(), tls)
+        .await
+        .with_context(|| format!("Failed to connect to database '{}'", database.name))?;
+    let connection = tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            tracing::debug!("Postgres connection closed: {e}");
+        }
+    });
+
+    // Simple-query mode returns every value as text, which keeps the output
+    // independent of column types. It also runs every statement in the
+    // string, so the server first parses the SQL as a prepared statement,
+    // which it refuses for more than one command; otherwise a `COMMIT` that
+    // slipped past `leading_keyword` could end the read-only transaction.
+    let messages = if write {
+        client.prepare(sql).await?;
+        client.simple_query(sql).await?
+    } else {
+        let tx = client.build_transaction().read_only(true).start().await?;
+        tx.prepare(sql).await?;
+        let messages = tx.simple_query(sql).await?;
+        tx.rollback().await?;
+        messages
+    };
+    drop(client);
+    connection.abort();
+
+    let mut result = Rows::default();
+    let mut affected = None;
+    for message in messages {
+        match message {
+            SimpleQueryMessage::RowDescription(columns) if result.columns.is_empty() => {
+                result.columns = columns.iter().map(|c| c.name().to_string()).collect();
+            }
+            SimpleQueryMessage::Row(row) => {
+                if result.columns.is_empty() {
+                    result.columns = row.columns().iter().map(|c| c.name().to_string()).collect();
+                }
+                let values = (0..row.len())
+                    .map(|i| row.get(i).map_or(Value::Null, |v| json!(v)))
+                    .collect();
+                if !result.truncated {
+                    result.push(values, database);
+                }
+            }
+            SimpleQueryMessage::CommandComplete(n) => affected = Some(n),
+            _ => {}
+        }
+    }
+    if result.columns.is_empty() {
+        return Ok(json!({ "rows_affected": affected.unwrap_or(0) }));
+    }
+    Ok(result.into_json())
+}
+
+/// Without Postgres support every query fails up front; the ready future
+/// keeps the call site identical to the `db-postgres` build.
+#[cfg(not(feature = "db-postgres"))]
+fn postgres_query(
+    database: &DatabaseConfig,
+    _sql: &str,
+    _write: bool,
+) -> std::future::Ready<anyhow::Result<Value>> {
+    std::future::ready(Err(anyhow::anyhow!(
+        "Database '{}' is Postgres, but this build lacks Postgres support \
+         (rebuild with `--features db-postgres`)",
+        database.name
+    )))
+}
+
+#[async_trait]
+impl Tool for DbQueryTool {
+    fn name(&self) -> &str {
+        "db_query"
+    }
+
+    fn description(&self) -> &str {
+        "Run one SQL statement against a configured database and return the rows as JSON. Read-only databases accept only SELECT queries."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let names: Vec<&str> = self.databases.iter().map(|d| d.name.as_str()).collect();
+        json!({
+            "type": "object",
+            "properties": {
+                "database": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "Configured database name"
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "A single SQL statement"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve a write in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["database", "sql"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let name = args
+            .get("database")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'database' parameter"))?;
+        let sql = args
+            .get("sql")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'sql' parameter"))?
+            .to_string();
+        let approved = args
+            .get("approved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let Some(database) = self.databases.iter().find(|d| d.name == name).cloned() else {
+            return Ok(ToolResult::failure(ToolError::not_found(format!(
+                "Unknown database '{name}'"
+            ))));
+        };
+        let keyword = match leading_keyword(&sql) {
+            Ok(keyword) => keyword,
+            Err(e) => return Ok(ToolResult::failure(ToolError::failed(e))),
+        };
+        let write = !is_read_keyword(&keyword);
+        if write && database.read_only {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                "Database '{name}' is read-only; only SELECT queries are allowed"
+            ))));
+        }
+        if write && self.security.autonomy == AutonomyLevel::Supervised && !approved {
+            return Ok(ToolResult::failure(ToolError::permission_denied(format!(
+                "{keyword} on '{name}' requires explicit approval (approved=true)"
+            ))));
+        }
+        let operation = if write {
+            ToolOperation::Act
+        } else {
+            ToolOperation::Read
+        };
+        if let Err(e) = self.security.enforce_tool_operation(operation, "db_query") {
+            return Ok(ToolResult::failure(ToolError::from_policy_violation(e)));
+        }
+
+        let query = async {
+            if let Some(path) = sqlite_path(&database.url, &self.security.workspace_dir) {
+                let database = database.clone();
+                tokio::task::spawn_blocking(move || sqlite_query(&path, &sql, write, &database))
+                    .await?
+            } else {
+                postgres_query(&database, &sql, write).await
+            }
+        };
+        match tokio::time::timeout(Duration::from_secs(QUERY_TIMEOUT_SECS), query).await {
+            Ok(Ok(output)) => Ok(ToolResult {
+                success: true,
+                output: output.to_string(),
+                error: None,
+            }),
+            Ok(Err(e)) => Ok(ToolResult::failure(ToolError::failed(format!(
+                "Query on '{name}' failed: {e:#}"
+            )))),
+            Err(_) => Ok(ToolResult::failure(ToolError::timeout(format!(
+                "Query on '{name}' timed out after {QUERY_TIMEOUT_SECS}s"
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn database(name: &str, read_only: bool) -> DatabaseConfig {
+        DatabaseConfig {
+            name: name.into(),
+            url: "sqlite://data.db".into(),
+            read_only,
+            max_rows: 2,
+            max_bytes: 64 * 1024,
+        }
+    }
+
+    fn tool(workspace: &Path, autonomy: AutonomyLevel) -> DbQueryTool {
+        let conn = rusqlite::Connection::open(workspace.join("data.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id INTEGER, name TEXT);
+             INSERT INTO items VALUES (1, 'a'), (2, 'b'), (3, 'c');",
+        )
+        .unwrap();
+        DbQueryTool::new(
+            vec![database("ro", true), database("rw", false)],
+            Arc::new(SecurityPolicy {
+                autonomy,
+                workspace_dir: workspace.to_path_buf(),
+                ..SecurityPolicy::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn leading_keyword_skips_comments_and_rejects_multiple_statements() {
+        assert_eq!(
+            leading_keyword("-- count\n/* all */ select 1;").unwrap(),
+            "SELECT"
+        );
+        assert_eq!(leading_keyword("(SELECT ';')").unwrap(), "SELECT");
+        assert!(leading_keyword("SELECT 1; DROP TABLE items").is_err());
+        assert!(leading_keyword("  -- nothing").is_err());
+    }
+
+    #[test]
+    fn leading_keyword_treats_dollar_quotes_as_strings() {
+        assert_eq!(leading_keyword("SELECT $$a;b$$, $1").unwrap(), "SELECT");
+        assert_eq!(leading_keyword("SELECT $t$ ' $x$ ; $t$").unwrap(), "SELECT");
+        assert!(leading_keyword("SELECT $a$ ' $a$; COMMIT; DELETE FROM t; --'").is_err());
+    }
+
+    #[tokio::test]
+    async fn select_is_capped_at_max_rows() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(tmp.path(), AutonomyLevel::Supervised);
+        let result = tool
+            .execute(json!({"database": "ro", "sql": "SELECT id, name FROM items ORDER BY id"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["columns"], json!(["id", "name"]));
+        assert_eq!(output["rows"], json!([[1, "a"], [2, "b"]]));
+        assert_eq!(output["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn read_only_database_rejects_writes() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(tmp.path(), AutonomyLevel::Full);
+        let result = tool
+            .execute(json!({"database": "ro", "sql": "DELETE FROM items"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("read-only"));
+
+        // A write hidden behind a read keyword is refused by the connection.
+        let result = tool
+            .execute(json!({"database": "ro", "sql": "WITH x AS (SELECT 1) DELETE FROM items"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn writes_need_approval_in_supervised_mode() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(tmp.path(), AutonomyLevel::Supervised);
+        let sql = "UPDATE items SET name = 'z' WHERE id = 1";
+        let denied = tool
+            .execute(json!({"database": "rw", "sql": sql}))
+            .await
+            .unwrap();
+        assert!(denied.error_message().unwrap().contains("approved=true"));
+
+        let result = tool
+            .execute(json!({"database": "rw", "sql": sql, "approved": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, r#"{"rows_affected":1}"#);
+    }
+}
@@ -0,0 +1,350 @@
+//! Clipboard and notification tools for the machine running ZeroClaw
+//! (`[tools.desktop]`).
+//!
+//! The platform backends (`arboard`, `notify-rust`) need the
+//! `desktop-tools` build feature; without it [`desktop_tools`] registers
+//! nothing and [`notify`] reports how to enable it.
+
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::{Config, DesktopToolsConfig};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Clipboard text returned to the model is cut to this many characters.
+const MAX_CLIPBOARD_READ_CHARS: usize = 20_000;
+/// Larger clipboard writes are refused.
+const MAX_CLIPBOARD_WRITE_BYTES: usize = 1024 * 1024;
+/// Notification bodies are cut to this many characters.
+const MAX_NOTIFICATION_CHARS: usize = 500;
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Show a desktop notification. Used by the `notify` tool and by
+/// `[[schedule.tasks]]` with `channel = "desktop"`.
+pub async fn notify(config: &DesktopToolsConfig, title: &str, body: &str) -> Result<()> {
+    if !config.notifications_enabled() {
+        bail!("Desktop notifications are disabled ([tools.desktop])");
+    }
+    let app_name = config.app_name.clone();
+    let title = truncate_with_ellipsis(title.trim(), MAX_TITLE_CHARS);
+    let body = truncate_with_ellipsis(body.trim(), MAX_NOTIFICATION_CHARS);
+    tokio::task::spawn_blocking(move || backend::notify(&app_name, &title, &body)).await?
+}
+
+#[cfg(feature = "desktop-tools")]
+mod backend {
+    use anyhow::{Context, Result};
+    use std::sync::Mutex;
+
+    /// On X11 and Wayland the copied text is served by the process that owns
+    /// the clipboard, so the handle is kept for the life of the process.
+    static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+    fn with_clipboard<T>(f: impl FnOnce(&mut arboard::Clipboard) -> Result<T>) -> Result<T> {
+        let mut guard = CLIPBOARD
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if guard.is_none() {
+            *guard = Some(arboard::Clipboard::new().context("Clipboard is unavailable")?);
+        }
+        match guard.as_mut() {
+            Some(clipboard) => f(clipboard),
+            None => unreachable!("clipboard initialized above"),
+        }
+    }
+
+    pub fn read_clipboard() -> Result<String> {
+        with_clipboard(|clipboard| {
+            clipboard
+                .get_text()
+                .context("Clipboard does not contain text")
+        })
+    }
+
+    pub fn write_clipboard(text: &str) -> Result<()> {
+        with_clipboard(|clipboard| {
+            clipboard
+                .set_text(text)
+                .context("Failed to write the clipboard")
+        })
+    }
+
+    pub fn notify(app_name: &str, title: &str, body: &str) -> Result<()> {
+        notify_rust::Notification::new()
+            .appname(app_name)
+            .summary(title)
+            .body(body)
+            .show()
+            .context("Failed to show notification")?;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "desktop-tools"))]
+mod backend {
+    use anyhow::{bail, Result};
+
+    const MISSING: &str =
+        "This build lacks desktop support (rebuild with `--features desktop-tools`)";
+
+    pub fn read_clipboard() -> Result<String> {
+        bail!(MISSING)
+    }
+
+    pub fn write_clipboard(_text: &str) -> Result<()> {
+        bail!(MISSING)
+    }
+
+    pub fn notify(_app_name: &str, _title: &str, _body: &str) -> Result<()> {
+        bail!(MISSING)
+    }
+}
+
+/// Read text from the system clipboard
+pub struct ClipboardReadTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ClipboardReadTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardReadTool {
+    fn name(&self) -> &str {
+        "clipboard_read"
+    }
+
+    fn description(&self) -> &str {
+        "Read the text currently on the user's clipboard."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({ "type": "object", "properties": {} })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+        match tokio::task::spawn_blocking(backend::read_clipboard).await? {
+            Ok(text) => Ok(ToolResult {
+                success: true,
+                output: truncate_with_ellipsis(&text, MAX_CLIPBOARD_READ_CHARS),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+        }
+    }
+}
+
+/// Put text on the system clipboard
+pub struct ClipboardWriteTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl ClipboardWriteTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+}
+
+#[async_trait]
+impl Tool for ClipboardWriteTool {
+    fn name(&self) -> &str {
+        "clipboard_write"
+    }
+
+    fn description(&self) -> &str {
+        "Copy text to the user's clipboard, replacing its contents. Use when the user asks to copy something."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Text to copy"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let text = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'text' parameter"))?
+            .to_string();
+        if text.len() > MAX_CLIPBOARD_WRITE_BYTES {
+            return Ok(ToolResult::failure(ToolError::failed(format!(
+                "Text is larger than {MAX_CLIPBOARD_WRITE_BYTES} bytes"
+            ))));
+        }
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, "clipboard_write")
+        {
+            return Ok(ToolResult::failure(ToolError::from_policy_violation(error)));
+        }
+        let chars = text.chars().count();
+        match tokio::task::spawn_blocking(move || backend::write_clipboard(&text)).await? {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!("Copied {chars} characters to the clipboard."),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+        }
+    }
+}
+
+/// Show a native desktop notification
+pub struct NotifyTool {
+    config: DesktopToolsConfig,
+    security: Arc<SecurityPolicy>,
+}
+
+impl NotifyTool {
+    pub fn new(config: DesktopToolsConfig, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+}
+
+#[async_trait]
+impl Tool for NotifyTool {
+    fn name(&self) -> &str {
+        "notify"
+    }
+
+    fn description(&self) -> &str {
+        "Show a desktop notification on the machine running the assistant. Use for proactive alerts the user should see even when not looking at the chat."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "title": {
+                    "type": "string",
+                    "description": "Short title (default: the app name)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Notification text"
+                }
+            },
+            "required": ["message"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' parameter"))?;
+        let title = args
+            .get("title")
+            .and_then(|v| v.as_str())
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or(&self.config.app_name);
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+        match notify(&self.config, title, message).await {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: "Notification shown.".into(),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+        }
+    }
+}
+
+/// The desktop tools enabled by `[tools.desktop]`.
+pub fn desktop_tools(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    let desktop = &config.tools.desktop;
+    if !desktop.enabled {
+        return Vec::new();
+    }
+    if !cfg!(feature = "desktop-tools") {
+        tracing::warn!(
+            "[tools.desktop] is enabled, but this build lacks desktop support \
+             (rebuild with `--features desktop-tools`)"
+        );
+        return Vec::new();
+    }
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    if desktop.clipboard_enabled() {
+        tools.push(Box::new(ClipboardReadTool::new(security.clone())));
+        tools.push(Box::new(ClipboardWriteTool::new(security.clone())));
+    }
+    if desktop.notifications_enabled() {
+        tools.push(Box::new(NotifyTool::new(desktop.clone(), security.clone())));
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    #[test]
+    fn desktop_tools_are_opt_in() {
+        let security = Arc::new(SecurityPolicy::default());
+        let mut config = Config::default();
+        assert!(desktop_tools(&config, &security).is_empty());
+
+        config.tools.desktop.enabled = true;
+        config.tools.desktop.clipboard = false;
+        let names: Vec<String> = desktop_tools(&config, &security)
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect();
+        if cfg!(feature = "desktop-tools") {
+            assert_eq!(names, vec!["notify"]);
+        } else {
+            assert!(names.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn notify_requires_enabled_config() {
+        let err = notify(&DesktopToolsConfig::default(), "t", "b")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("[tools.desktop]"));
+    }
+
+    #[tokio::test]
+    async fn clipboard_write_is_blocked_in_read_only_mode() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            ..SecurityPolicy::default()
+        });
+        let result = ClipboardWriteTool::new(security)
+            .execute(json!({"text": "hello"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("read-only"));
+    }
+}
@@ -0,0 +1,575 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::{EmailConfig, EmailScope};
+use crate::security::SecurityPolicy;
+use crate::util::time::TimeService;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use futures_util::StreamExt;
+use mailparse::{MailHeaderMap, ParsedMail};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+
+type ImapSession = async_imap::Session<TlsStream<TcpStream>>;
+
+/// Whole search (connect, login, search, fetch) must finish within this.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
+/// Message text fetched per search hit.
+const MAX_TEXT_FETCH_BYTES: usize = 64 * 1024;
+const SNIPPET_CHARS: usize = 400;
+/// Text returned when a single message is read by `uid`.
+const MAX_MESSAGE_CHARS: usize = 8_000;
+
+static HTML_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?s)<(script|style)[^>]*>.*?</(script|style)>|<[^>]*>").unwrap()
+});
+
+/// Search the user's IMAP mailboxes (read-only)
+///
+/// Mailboxes are opened with `EXAMINE` and bodies fetched with `BODY.PEEK`,
+/// so searching never changes flags. Message text is only read with the
+/// `read_bodies` scope.
+pub struct EmailSearchTool {
+    config: EmailConfig,
+    security: Arc<SecurityPolicy>,
+    time: TimeService,
+}
+
+impl EmailSearchTool {
+    pub fn new(config: EmailConfig, security: Arc<SecurityPolicy>, time: TimeService) -> Self {
+        Self {
+            config,
+            security,
+            time,
+        }
+    }
+
+    fn mailbox<'a>(&'a self, args: &'a Value) -> anyhow::Result<&'a str> {
+        let Some(requested) = str_arg(args, "mailbox") else {
+            return self
+                .config
+                .mailboxes
+                .first()
+                .map(String::as_str)
+                .context("No mailboxes configured");
+        };
+        self.config
+            .mailboxes
+            .iter()
+            .find(|m| {
+                m.as_str() == requested
+                    || (m.eq_ignore_ascii_case("INBOX") && requested.eq_ignore_ascii_case("INBOX"))
+            })
+            .map(String::as_str)
+            .with_context(|| {
+                format!(
+                    "Mailbox '{requested}' is not approved; available: {}",
+                    self.config.mailboxes.join(", ")
+                )
+            })
+    }
+
+    async fn connect(&self) -> anyhow::Result<ImapSession> {
+        let host = self.config.imap_host.trim();
+        let tcp = TcpStream::connect((host, self.config.imap_port))
+            .await
+            .with_context(|| format!("Failed to connect to {host}:{}", self.config.imap_port))?;
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+            .with_context(|| format!("Invalid IMAP host name '{host}'"))?;
+        let tls = tokio_rustls::TlsConnector::from(Arc::new(tls_config))
+            .connect(server_name, tcp)
+            .await
+            .with_context(|| format!("TLS handshake with {host} failed"))?;
+
+        let mut client = async_imap::Client::new(tls);
+        let _greeting = client.read_response().await;
+        client
+            .login(
+                &self.config.username,
+                self.config.password.as_deref().unwrap_or_default(),
+            )
+            .await
+            .map_err(|(e, _)| anyhow::anyhow!("IMAP login failed: {e}"))
+    }
+
+    async fn run(&self, args: &Value) -> anyhow::Result<String> {
+        let mailbox = self.mailbox(args)?;
+        let uid = args.get("uid").and_then(Value::as_u64);
+        if uid.is_some() && !self.config.allows(EmailScope::ReadBodies) {
+            bail!("Reading message text has not been approved (assistant.email.scopes)");
+        }
+        let criteria = search_criteria(args, &self.time, Utc::now())?;
+        let limit = args
+            .get("limit")
+            .and_then(Value::as_u64)
+            .and_then(|l| usize::try_from(l).ok())
+            .unwrap_or(self.config.max_results)
+            .clamp(1, self.config.max_results);
+
+        let mut session = self.connect().await?;
+        let result = match uid {
+            Some(uid) => self.read(&mut session, mailbox, uid).await,
+            None => self.search(&mut session, mailbox, &criteria, limit).await,
+        };
+        let _ = session.logout().await;
+        result
+    }
+
+    async fn search(
+        &self,
+        session: &mut ImapSession,
+        mailbox: &str,
+        criteria: &str,
+        limit: usize,
+    ) -> anyhow::Result<String> {
+        session
+            .examine(mailbox)
+            .await
+            .with_context(|| format!("Failed to open mailbox '{mailbox}'"))?;
+        let mut uids: Vec<u32> = session
+            .uid_search(criteria)
+            .await
+            .context("IMAP search failed")?
+            .into_iter()
+            .collect();
+        if uids.is_empty() {
+            return Ok(format!("No messages in {mailbox} match."));
+        }
+        // Highest UIDs are the most recently delivered.
+        uids.sort_unstable_by(|a, b| b.cmp(a));
+        let total = uids.len();
+        uids.truncate(limit);
+
+        let bodies = self.config.allows(EmailScope::ReadBodies);
+        let mut messages = self.fetch(session, &uids, &fetch_query(bodies)).await?;
+        messages.sort_by(|a, b| b.uid.cmp(&a.uid));
+
+        let mut output = format!("{total} message(s) in {mailbox} match");
+        if total > messages.len() {
+            let _ = write!(output, " (showing the newest {})", messages.len());
+        }
+        output.push_str(":\n");
+        for message in &messages {
+            let _ = writeln!(output, "- {}", message.summary_line(&self.time));
+            if bodies && !message.text.is_empty() {
+                let snippet = collapse_whitespace(&message.text);
+                let _ = writeln!(
+                    output,
+                    "  {}",
+                    truncate_with_ellipsis(&snippet, SNIPPET_CHARS)
+                );
+            }
+        }
+        Ok(output)
+    }
+
+    async fn read(
+        &self,
+        session: &mut ImapSession,
+        mailbox: &str,
+        uid: u64,
+    ) -> anyhow::Result<String> {
+        session
+            .examine(mailbox)
+            .await
+            .with_context(|| format!("Failed to open mailbox '{mailbox}'"))?;
+        let uid = u32::try_from(uid).context("'uid' is out of range")?;
+        let message = self
+            .fetch(session, &[uid], &fetch_query(true))
+            .await?
+            .into_iter()
+            .next()
+            .with_context(|| format!("No message with uid {uid} in {mailbox}"))?;
+        Ok(format!(
+            "{}\n\n{}",
+            message.summary_line(&self.time),
+            truncate_with_ellipsis(message.text.trim(), MAX_MESSAGE_CHARS)
+        ))
+    }
+
+    async fn fetch(
+        &self,
+        session: &mut ImapSession,
+        uids: &[u32],
+        query: &str,
+    ) -> anyhow::Result<Vec<EmailMessage>> {
+        let uid_set = uids
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let stream = session
+            .uid_fetch(uid_set, query)
+            .await
+            .context("IMAP fetch failed")?;
+        let mut stream = std::pin::pin!(stream);
+        let mut messages = Vec::new();
+        while let Some(fetch) = stream.next().await {
+            let fetch = fetch.context("IMAP fetch failed")?;
+            let Some(uid) = fetch.uid else {
+                continue;
+            };
+            messages.push(EmailMessage::parse(
+                uid,
+                fetch.header().unwrap_or_default(),
+                fetch.text(),
+                fetch.internal_date().map(|d| d.with_timezone(&Utc)),
+            ));
+        }
+        Ok(messages)
+    }
+}
+
+#[derive(Debug, Default)]
+struct EmailMessage {
+    uid: u32,
+    from: String,
+    subject: String,
+    date: Option<DateTime<Utc>>,
+    /// Plain text of the first text part; empty unless bodies were fetched.
+    text: String,
+}
+
+impl EmailMessage {
+    fn parse(
+        uid: u32,
+        header: &[u8],
+        text: Option<&[u8]>,
+        internal_date: Option<DateTime<Utc>>,
+    ) -> Self {
+        let raw = [header, text.unwrap_or_default()].concat();
+        let Ok(mail) = mailparse::parse_mail(&raw) else {
+            return Self {
+                uid,
+                date: internal_date,
+                ..Self::default()
+            };
+        };
+        let header = |name: &str| mail.headers.get_first_value(name).unwrap_or_default();
+        let date = mail
+            .headers
+            .get_first_value("Date")
+            .and_then(|d| mailparse::dateparse(&d).ok())
+            .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+            .or(internal_date);
+        Self {
+            uid,
+            from: header("From"),
+            subject: header("Subject"),
+            date,
+            text: if text.is_some() {
+                body_text(&mail).unwrap_or_default()
+            } else {
+                String::new()
+            },
+        }
+    }
+
+    fn summary_line(&self, time: &TimeService) -> String {
+        let date = self.date.map_or_else(
+            || "(no date)".to_string(),
+            |d| time.localize(d).format("%Y-%m-%d %H:%M").to_string(),
+        );
+        let subject = if self.subject.trim().is_empty() {
+            "(no subject)"
+        } else {
+            self.subject.trim()
+        };
+        format!(
+            "[uid {}] {date} | From: {} | Subject: {subject}",
+            self.uid,
+            self.from.trim()
+        )
+    }
+}
+
+/// Text of the first `text/plain` part, else of the first `text/html` part
+/// with tags stripped.
+fn body_text(mail: &ParsedMail<'_>) -> Option<String> {
+    fn find<'a>(mail: &'a ParsedMail<'a>, mime: &str) -> Option<&'a ParsedMail<'a>> {
+        if mail.subparts.is_empty() {
+            return (mail.ctype.mimetype == mime).then_some(mail);
+        }
+        mail.subparts.iter().find_map(|part| find(part, mime))
+    }
+    if let Some(plain) = find(mail, "text/plain") {
+        return plain.get_body().ok();
+    }
+    let html = find(mail, "text/html")?.get_body().ok()?;
+    Some(collapse_whitespace(&HTML_RE.replace_all(&html, " ")))
+}
+
+/// `UID FETCH` items; `PEEK` keeps messages unread.
+fn fetch_query(bodies: bool) -> String {
+    if bodies {
+        format!("(UID INTERNALDATE BODY.PEEK[HEADER] BODY.PEEK[TEXT]<0.{MAX_TEXT_FETCH_BYTES}>)")
+    } else {
+        "(UID INTERNALDATE BODY.PEEK[HEADER])".to_string()
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// IMAP quoted string; line breaks cannot be quoted.
+fn quote(value: &str) -> anyhow::Result<String> {
+    if value.contains(['\r', '\n']) {
+        bail!("Search terms must not contain line breaks");
+    }
+    Ok(format!(
+        "\"{}\"",
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+/// IMAP date (`2-Mar-2026`) for `SINCE`/`BEFORE`.
+fn imap_date(date: NaiveDate) -> String {
+    date.format("%-d-%b-%Y").to_string()
+}
+
+/// `UID SEARCH` criteria for the tool arguments.
+fn search_criteria(args: &Value, time: &TimeService, now: DateTime<Utc>) -> anyhow::Result<String> {
+    let mut criteria = Vec::new();
+    for (key, keyword) in [("from", "FROM"), ("subject", "SUBJECT"), ("query", "TEXT")] {
+        if let Some(value) = str_arg(args, key) {
+            criteria.push(format!("{keyword} {}", quote(value)?));
+        }
+    }
+    for (key, keyword) in [("since", "SINCE"), ("before", "BEFORE")] {
+        if let Some(value) = str_arg(args, key) {
+            let date = time
+                .parse_date_at(value, now)
+                .with_context(|| format!("Invalid '{key}' date"))?;
+            criteria.push(format!("{keyword} {}", imap_date(date)));
+        }
+    }
+    if args.get("unread_only").and_then(Value::as_bool) == Some(true) {
+        criteria.push("UNSEEN".into());
+    }
+    if criteria.is_empty() {
+        return Ok("ALL".into());
+    }
+    let joined = criteria.join(" ");
+    if joined.is_ascii() {
+        Ok(joined)
+    } else {
+        Ok(format!("CHARSET UTF-8 {joined}"))
+    }
+}
+
+#[async_trait]
+impl Tool for EmailSearchTool {
+    fn name(&self) -> &str {
+        "email_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the user's email (read-only). Filter by 'query' (text anywhere), 'from', 'subject', 'since'/'before' dates ('yesterday', 'monday', 'YYYY-MM-DD') and 'unread_only'. Returns the newest matches with their uid; pass 'uid' to read one message in full when message text access is approved."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Text to find in headers or body"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Sender name or address contains this"
+                },
+                "subject": {
+                    "type": "string",
+                    "description": "Subject contains this"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only messages on or after this day"
+                },
+                "before": {
+                    "type": "string",
+                    "description": "Only messages before this day"
+                },
+                "unread_only": {
+                    "type": "boolean",
+                    "description": "Only unread messages"
+                },
+                "mailbox": {
+                    "type": "string",
+                    "description": "Mailbox to search (default: the first approved mailbox)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Most messages to return"
+                },
+                "uid": {
+                    "type": "integer",
+                    "description": "Read this message in full instead of searching"
+                }
+            }
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if !self.config.allows(EmailScope::Search) {
+            return Ok(ToolResult::failure(ToolError::permission_denied(
+                "Email access has not been approved (assistant.email.scopes)",
+            )));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+        match tokio::time::timeout(SEARCH_TIMEOUT, self.run(&args)).await {
+            Ok(Ok(output)) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            }),
+            Ok(Err(e)) => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+            Err(_) => Ok(ToolResult::failure(ToolError::timeout(format!(
+                "Email search timed out after {}s",
+                SEARCH_TIMEOUT.as_secs()
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(scopes: Vec<EmailScope>) -> EmailConfig {
+        EmailConfig {
+            imap_host: "imap.example.com".into(),
+            imap_port: 993,
+            username: "me@example.com".into(),
+            password: Some("pw".into()),
+            mailboxes: vec!["INBOX".into(), "Archive".into()],
+            scopes,
+            max_results: 10,
+        }
+    }
+
+    fn tool(scopes: Vec<EmailScope>) -> EmailSearchTool {
+        EmailSearchTool::new(
+            config(scopes),
+            Arc::new(SecurityPolicy::default()),
+            TimeService::new(Some("UTC")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn builds_search_criteria() {
+        let time = TimeService::new(Some("UTC")).unwrap();
+        // Monday.
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let criteria = |args: Value| search_criteria(&args, &time, now).unwrap();
+
+        assert_eq!(criteria(json!({})), "ALL");
+        assert_eq!(
+            criteria(
+                json!({"query": "invoice", "from": "acme", "since": "yesterday", "unread_only": true})
+            ),
+            "FROM \"acme\" TEXT \"invoice\" SINCE 1-Mar-2026 UNSEEN"
+        );
+        assert_eq!(
+            criteria(json!({"subject": "say \"hi\" \\o/"})),
+            "SUBJECT \"say \\\"hi\\\" \\\\o/\""
+        );
+        assert_eq!(
+            criteria(json!({"subject": "Rechnung für März"})),
+            "CHARSET UTF-8 SUBJECT \"Rechnung für März\""
+        );
+        assert!(search_criteria(&json!({"query": "a\r\nLOGOUT"}), &time, now).is_err());
+    }
+
+    #[test]
+    fn parses_headers_and_text() {
+        let header = b"From: Acme Billing <billing@acme.test>\r\n\
+Subject: =?UTF-8?Q?Invoice_=E2=84=96_44?=\r\n\
+Date: Mon, 02 Mar 2026 10:15:00 +0000\r\n\
+Content-Type: multipart/alternative; boundary=\"b\"\r\n\r\n";
+        let text = b"--b\r\n\
+Content-Type: text/html\r\n\r\n\
+<p>Ignored</p>\r\n\
+--b\r\n\
+Content-Type: text/plain\r\n\r\n\
+Amount due:\r\n  $120\r\n\
+--b--\r\n";
+        let message = EmailMessage::parse(7, header, Some(text), None);
+        assert_eq!(message.subject, "Invoice № 44");
+        assert_eq!(message.from, "Acme Billing <billing@acme.test>");
+        assert_eq!(collapse_whitespace(&message.text), "Amount due: $120");
+        assert_eq!(
+            message.summary_line(&TimeService::new(Some("UTC")).unwrap()),
+            "[uid 7] 2026-03-02 10:15 | From: Acme Billing <billing@acme.test> | Subject: Invoice № 44"
+        );
+
+        let headers_only = EmailMessage::parse(8, header, None, None);
+        assert!(headers_only.text.is_empty());
+    }
+
+    #[test]
+    fn only_approved_mailboxes_are_opened() {
+        let tool = tool(vec![EmailScope::Search]);
+        assert_eq!(tool.mailbox(&json!({})).unwrap(), "INBOX");
+        assert_eq!(tool.mailbox(&json!({"mailbox": "inbox"})).unwrap(), "INBOX");
+        assert_eq!(
+            tool.mailbox(&json!({"mailbox": "Archive"})).unwrap(),
+            "Archive"
+        );
+        let err = tool.mailbox(&json!({"mailbox": "Private"})).unwrap_err();
+        assert!(err.to_string().contains("not approved"));
+    }
+
+    #[tokio::test]
+    async fn scopes_gate_access() {
+        let result = tool(Vec::new()).execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error_message()
+            .unwrap()
+            .contains("assistant.email.scopes"));
+
+        // Reading a message needs `read_bodies`; refused before connecting.
+        let result = tool(vec![EmailScope::Search])
+            .execute(json!({"uid": 7}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error_message()
+            .unwrap()
+            .contains("Reading message text"));
+    }
+}
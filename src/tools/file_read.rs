@@ -1,4 +1,4 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{Tool, ToolError, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -48,6 +48,10 @@ impl Tool for FileReadTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path = args
             .get("path")
@@ -58,7 +62,9 @@ impl Tool for FileReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: too many actions in the last hour",
+                )),
             });
         }
 
@@ -67,7 +73,9 @@ impl Tool for FileReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Path not allowed by security policy: {path}")),
+                error: Some(ToolError::policy_blocked(format!(
+                    "Path not allowed by security policy: {path}"
+                ))),
             });
         }
 
@@ -78,11 +86,13 @@ impl Tool for FileReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: action budget exhausted",
+                )),
             });
         }
 
-        let full_path = self.security.workspace_dir.join(path);
+        let full_path = self.security.resolve_tool_path(path);
 
         // Resolve path before reading to block symlink escapes.
         let resolved_path = match tokio::fs::canonicalize(&full_path).await {
@@ -91,7 +101,10 @@ impl Tool for FileReadTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Failed to resolve file path: {e}")),
+                    error: Some(ToolError::io(
+                        format!("Failed to resolve file path: {e}"),
+                        &e,
+                    )),
                 });
             }
         };
@@ -100,10 +113,10 @@ impl Tool for FileReadTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(
+                error: Some(ToolError::policy_blocked(
                     self.security
                         .resolved_path_violation_message(&resolved_path),
-                ),
+                )),
             });
         }
 
@@ -114,10 +127,10 @@ impl Tool for FileReadTool {
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
-                        error: Some(format!(
+                        error: Some(ToolError::failed(format!(
                             "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
                             meta.len()
-                        )),
+                        ))),
                     });
                 }
             }
@@ -125,7 +138,10 @@ impl Tool for FileReadTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Failed to read file metadata: {e}")),
+                    error: Some(ToolError::io(
+                        format!("Failed to read file metadata: {e}"),
+                        &e,
+                    )),
                 });
             }
         }
@@ -289,6 +305,23 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_read_resolves_relative_to_the_working_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().canonicalize().unwrap();
+        tokio::fs::create_dir(workspace.join("src")).await.unwrap();
+        tokio::fs::write(workspace.join("src/lib.rs"), "pub fn x() {}")
+            .await
+            .unwrap();
+
+        let security = test_security(workspace.clone());
+        assert!(security.set_current_dir(&workspace.join("src")));
+        let tool = FileReadTool::new(security);
+        let result = tool.execute(json!({"path": "lib.rs"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("1: pub fn x() {}"));
+    }
+
     #[tokio::test]
     async fn file_read_nonexistent_file() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_read_missing");
@@ -298,7 +331,10 @@ mod tests {
         let tool = FileReadTool::new(test_security(dir.clone()));
         let result = tool.execute(json!({"path": "nope.txt"})).await.unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("Failed to resolve"));
+        assert!(result
+            .error_message()
+            .unwrap()
+            .contains("Failed to resolve"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -315,7 +351,7 @@ mod tests {
             .await
             .unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        assert!(result.error_message().unwrap().contains("not allowed"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -325,7 +361,7 @@ mod tests {
         let tool = FileReadTool::new(test_security(std::env::temp_dir()));
         let result = tool.execute(json!({"path": "/etc/passwd"})).await.unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        assert!(result.error_message().unwrap().contains("not allowed"));
     }
 
     #[tokio::test]
@@ -346,8 +382,7 @@ mod tests {
 
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Rate limit exceeded"));
 
@@ -440,8 +475,7 @@ mod tests {
 
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("escapes workspace"));
 
@@ -475,7 +509,7 @@ mod tests {
             .unwrap();
 
         assert!(!result.success);
-        let error = result.error.unwrap_or_default();
+        let error = result.error_message().unwrap_or_default();
         assert!(error.contains("escapes workspace"));
         assert!(error.contains("allowed_roots"));
 
@@ -498,17 +532,17 @@ mod tests {
         // Both reads fail (file doesn't exist) but should consume budget
         let r1 = tool.execute(json!({"path": "nope1.txt"})).await.unwrap();
         assert!(!r1.success);
-        assert!(r1.error.as_ref().unwrap().contains("Failed to resolve"));
+        assert!(r1.error_message().unwrap().contains("Failed to resolve"));
 
         let r2 = tool.execute(json!({"path": "nope2.txt"})).await.unwrap();
         assert!(!r2.success);
-        assert!(r2.error.as_ref().unwrap().contains("Failed to resolve"));
+        assert!(r2.error_message().unwrap().contains("Failed to resolve"));
 
         // Third attempt should be rate limited even though file doesn't exist
         let r3 = tool.execute(json!({"path": "nope3.txt"})).await.unwrap();
         assert!(!r3.success);
         assert!(
-            r3.error.as_ref().unwrap().contains("Rate limit"),
+            r3.error_message().unwrap().contains("Rate limit"),
             "Expected rate limit error, got: {:?}",
             r3.error
         );
@@ -605,7 +639,7 @@ mod tests {
         let tool = FileReadTool::new(test_security(dir.clone()));
         let result = tool.execute(json!({"path": "huge.bin"})).await.unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("File too large"));
+        assert!(result.error_message().unwrap().contains("File too large"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -732,6 +766,7 @@ mod tests {
                         tool_calls: vec![],
                         usage: None,
                         reasoning_content: None,
+                        reasoning: None,
                     });
                 }
                 Ok(guard.remove(0))
@@ -792,6 +827,7 @@ mod tests {
                 }],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             },
             // Turn 1 continued: provider sees tool result and answers
             ChatResponse {
@@ -799,6 +835,7 @@ mod tests {
                 tool_calls: vec![],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             },
         ]);
 
@@ -885,12 +922,14 @@ mod tests {
                 }],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             },
             ChatResponse {
                 text: Some("The file appears to be binary data.".into()),
                 tool_calls: vec![],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             },
         ]);
 
@@ -1017,7 +1056,7 @@ mod tests {
             .await
             .unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        assert!(result.error_message().unwrap().contains("not allowed"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -0,0 +1,466 @@
+//! Automatic `file_write` backups and the `file_undo` tool
+//! (`[tools.file_backups]`).
+//!
+//! Before `file_write` replaces a file, its previous contents are copied to
+//! `<workspace>/.zeroclaw/backups/<id>` and an entry is added to
+//! `index.json` there. `file_undo` and `zeroclaw workspace undo-last` revert
+//! the newest writes first: the backup is copied back, or a file the write
+//! created is removed. Only the newest `max_entries` writes are kept.
+
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::FileBackupsConfig;
+use crate::security::grants::PathGrants;
+use crate::security::SecurityPolicy;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const BACKUP_DIR: &str = ".zeroclaw/backups";
+const INDEX_FILE: &str = "index.json";
+/// Most writes one `file_undo` call may revert.
+const MAX_UNDO_COUNT: u64 = 20;
+
+/// One backed-up `file_write`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub id: String,
+    pub timestamp: String,
+    /// Resolved path of the written file.
+    pub path: PathBuf,
+    /// Whether the file existed before the write.
+    pub existed: bool,
+    /// Backup file name in the backup directory; `None` when the file did
+    /// not exist or exceeded `max_file_bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<String>,
+}
+
+/// Backup area rooted at `<workspace>/.zeroclaw/backups`.
+pub struct FileBackups {
+    dir: PathBuf,
+    workspace_dir: PathBuf,
+    max_entries: usize,
+    max_file_bytes: u64,
+    security: Arc<SecurityPolicy>,
+    lock: parking_lot::Mutex<()>,
+}
+
+impl FileBackups {
+    pub fn new(config: &FileBackupsConfig, security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            dir: security.workspace_dir.join(BACKUP_DIR),
+            workspace_dir: security.workspace_dir.clone(),
+            max_entries: config.max_entries.max(1),
+            max_file_bytes: config.max_file_bytes,
+            security,
+            lock: parking_lot::Mutex::new(()),
+        }
+    }
+
+    /// Record the current state of `target` before it is overwritten,
+    /// dropping the oldest entries beyond `max_entries`.
+    pub fn snapshot(&self, target: &Path) -> Result<()> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let _guard = self.lock.lock();
+        let (existed, backup) = match std::fs::metadata(target) {
+            Ok(meta) if meta.len() > self.max_file_bytes => (true, None),
+            Ok(_) => {
+                std::fs::create_dir_all(&self.dir)?;
+                std::fs::copy(target, self.dir.join(&id))
+                    .with_context(|| format!("Failed to back up {}", target.display()))?;
+                (true, Some(id.clone()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (false, None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = self.read_index()?;
+        entries.push(BackupEntry {
+            id,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            path: target.to_path_buf(),
+            existed,
+            backup,
+        });
+        if entries.len() > self.max_entries {
+            let excess = entries.len() - self.max_entries;
+            for old in entries.drain(..excess) {
+                if let Some(backup) = old.backup.as_deref().and_then(|b| self.backup_path(b)) {
+                    let _ = std::fs::remove_file(backup);
+                }
+            }
+        }
+        self.write_index(&entries)
+    }
+
+    /// Backed-up writes, oldest first.
+    pub fn entries(&self) -> Result<Vec<BackupEntry>> {
+        let _guard = self.lock.lock();
+        self.read_index()
+    }
+
+    /// Revert the newest `count` writes, newest first. Returns one line per
+    /// reverted write.
+    pub fn undo_last(&self, count: usize) -> Result<Vec<String>> {
+        let _guard = self.lock.lock();
+        let mut entries = self.read_index()?;
+        let mut reverted = Vec::new();
+        while reverted.len() < count {
+            let Some(entry) = entries.pop() else {
+                break;
+            };
+            match self.restore(&entry) {
+                Ok(line) => reverted.push(line),
+                Err(e) => {
+                    entries.push(entry);
+                    self.write_index(&entries)?;
+                    return Err(e);
+                }
+            }
+        }
+        self.write_index(&entries)?;
+        Ok(reverted)
+    }
+
+    fn restore(&self, entry: &BackupEntry) -> Result<String> {
+        let shown = self.display_path(&entry.path);
+        // The index lives in the workspace, so never trust it to point
+        // somewhere file_write could not have written.
+        let lexical = !entry
+            .path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir));
+        let allowed = entry.path.parent().is_some_and(|parent| {
+            let resolved = parent
+                .canonicalize()
+                .unwrap_or_else(|_| parent.to_path_buf());
+            self.security.is_resolved_path_allowed(&resolved)
+        });
+        if !lexical || !allowed {
+            anyhow::bail!("Refusing to restore {shown}: path is outside the allowed roots");
+        }
+        if std::fs::symlink_metadata(&entry.path).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            anyhow::bail!("Refusing to restore {shown} through a symlink");
+        }
+        match (&entry.backup, entry.existed) {
+            (Some(backup), _) => {
+                let backup = self.backup_path(backup).with_context(|| {
+                    format!("Refusing to restore {shown}: invalid backup name {backup:?}")
+                })?;
+                if let Some(parent) = entry.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(&backup, &entry.path)
+                    .with_context(|| format!("Failed to restore {shown}"))?;
+                let _ = std::fs::remove_file(backup);
+                Ok(format!("restored {shown}"))
+            }
+            (None, false) => {
+                match std::fs::remove_file(&entry.path) {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(e).with_context(|| format!("Failed to remove {shown}"));
+                    }
+                    _ => {}
+                }
+                Ok(format!("removed {shown} (created by the write)"))
+            }
+            (None, true) => Ok(format!(
+                "skipped {shown}: the previous version exceeded max_file_bytes and was not backed up"
+            )),
+        }
+    }
+
+    /// Location of a backup named in the index. Only the ids `snapshot`
+    /// generates are accepted, so a tampered index cannot name a file
+    /// outside the backup directory.
+    fn backup_path(&self, backup: &str) -> Option<PathBuf> {
+        let id = uuid::Uuid::parse_str(backup).ok()?;
+        (id.to_string() == backup).then(|| self.dir.join(backup))
+    }
+
+    fn display_path(&self, path: &Path) -> String {
+        let workspace = self
+            .workspace_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace_dir.clone());
+        path.strip_prefix(&workspace)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    fn read_index(&self) -> Result<Vec<BackupEntry>> {
+        let path = self.dir.join(INDEX_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_index(&self, entries: &[BackupEntry]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(INDEX_FILE);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(entries)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
+
+/// Revert recent `file_write` calls from their backups.
+pub struct FileUndoTool {
+    backups: Arc<FileBackups>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl FileUndoTool {
+    pub fn new(backups: Arc<FileBackups>, security: Arc<SecurityPolicy>) -> Self {
+        Self { backups, security }
+    }
+}
+
+#[async_trait]
+impl Tool for FileUndoTool {
+    fn name(&self) -> &str {
+        "file_undo"
+    }
+
+    fn description(&self) -> &str {
+        "Revert the most recent file_write call(s), newest first: restores the previous file contents, or removes files the write created"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "count": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_UNDO_COUNT,
+                    "description": "Number of most recent writes to revert (default 1)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let count = args
+            .get("count")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1);
+        if !(1..=MAX_UNDO_COUNT).contains(&count) {
+            return Ok(ToolResult::failure(ToolError::failed(format!(
+                "count must be between 1 and {MAX_UNDO_COUNT}"
+            ))));
+        }
+
+        if !self.security.can_act() {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(
+                "Action blocked: autonomy is read-only",
+            )));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+
+        match self.backups.undo_last(usize::try_from(count)?) {
+            Ok(reverted) if reverted.is_empty() => Ok(ToolResult::failure(ToolError::not_found(
+                "Nothing to undo: no file_write backups recorded",
+            ))),
+            Ok(reverted) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Reverted {} write(s):\n- {}",
+                    reverted.len(),
+                    reverted.join("\n- ")
+                ),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult::failure(ToolError::failed(format!(
+                "Undo failed: {e:#}"
+            )))),
+        }
+    }
+}
+
+/// `zeroclaw workspace undo-last [--count N]`: revert the newest file writes.
+pub fn run_undo_last(config: &crate::config::Config, count: usize) -> Result<()> {
+    let reverted = cli_backups(config).undo_last(count)?;
+    if reverted.is_empty() {
+        println!("Nothing to undo: no file_write backups in {BACKUP_DIR}");
+        return Ok(());
+    }
+    for line in &reverted {
+        println!("↩️  {line}");
+    }
+    Ok(())
+}
+
+/// `zeroclaw workspace backups`: list the writes that can be undone.
+pub fn run_list(config: &crate::config::Config) -> Result<()> {
+    let backups = cli_backups(config);
+    let entries = backups.entries()?;
+    if entries.is_empty() {
+        println!("No file_write backups in {BACKUP_DIR}");
+        return Ok(());
+    }
+    println!("{} write(s), newest first:", entries.len());
+    for entry in entries.iter().rev() {
+        let state = match (&entry.backup, entry.existed) {
+            (Some(_), _) => "overwrote",
+            (None, false) => "created",
+            (None, true) => "overwrote (no backup, too large)",
+        };
+        println!(
+            "  {}  {:<32} {state}",
+            entry.timestamp,
+            backups.display_path(&entry.path)
+        );
+    }
+    Ok(())
+}
+
+fn cli_backups(config: &crate::config::Config) -> FileBackups {
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_path_grants(PathGrants::for_config(config)),
+    );
+    FileBackups::new(&config.tools.file_backups, security)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+
+    fn backups(workspace: &Path, max_entries: usize) -> Arc<FileBackups> {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let config = FileBackupsConfig {
+            max_entries,
+            ..FileBackupsConfig::default()
+        };
+        Arc::new(FileBackups::new(&config, security))
+    }
+
+    #[test]
+    fn undo_restores_overwritten_files_and_removes_created_ones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let store = backups(&root, 10);
+        let notes = root.join("notes.md");
+        let fresh = root.join("fresh.md");
+        std::fs::write(&notes, "original").unwrap();
+
+        store.snapshot(&notes).unwrap();
+        std::fs::write(&notes, "edited").unwrap();
+        store.snapshot(&fresh).unwrap();
+        std::fs::write(&fresh, "new").unwrap();
+
+        let reverted = store.undo_last(1).unwrap();
+        assert_eq!(reverted, vec!["removed fresh.md (created by the write)"]);
+        assert!(!fresh.exists());
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "edited");
+
+        assert_eq!(store.undo_last(5).unwrap(), vec!["restored notes.md"]);
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "original");
+        assert!(store.undo_last(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn retention_drops_oldest_entries_and_their_backups() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let store = backups(&root, 2);
+        let file = root.join("a.txt");
+        for version in ["v1", "v2", "v3"] {
+            std::fs::write(&file, version).unwrap();
+            store.snapshot(&file).unwrap();
+        }
+
+        let entries = store.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        let backup_files = std::fs::read_dir(root.join(BACKUP_DIR))
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name() != INDEX_FILE)
+            .count();
+        assert_eq!(backup_files, 2);
+    }
+
+    #[test]
+    fn undo_refuses_paths_outside_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let store = backups(&root, 10);
+        store
+            .snapshot(Path::new("/etc/hostname-zeroclaw-test"))
+            .unwrap();
+
+        let err = store.undo_last(1).unwrap_err();
+        assert!(err.to_string().contains("outside the allowed roots"));
+        assert_eq!(store.entries().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn undo_rejects_tampered_backup_names() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let store = backups(&root, 10);
+        let secret = root.join("secret.txt");
+        let target = root.join("notes.md");
+        std::fs::write(&secret, "secret").unwrap();
+        std::fs::write(&target, "notes").unwrap();
+
+        for forged in ["../../secret.txt", secret.to_str().unwrap()] {
+            let entry = BackupEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                path: target.clone(),
+                existed: true,
+                backup: Some(forged.to_string()),
+            };
+            store.write_index(&[entry]).unwrap();
+
+            let err = store.undo_last(1).unwrap_err();
+            assert!(err.to_string().contains("invalid backup name"));
+            assert_eq!(std::fs::read_to_string(&target).unwrap(), "notes");
+            assert!(secret.exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn file_undo_tool_reports_empty_history_and_blocks_read_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let store = backups(&root, 10);
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: root.clone(),
+            ..SecurityPolicy::default()
+        });
+
+        let tool = FileUndoTool::new(store.clone(), security);
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("Nothing to undo"));
+
+        let read_only = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: root,
+            ..SecurityPolicy::default()
+        });
+        let tool = FileUndoTool::new(store, read_only);
+        let result = tool.execute(json!({"count": 1})).await.unwrap();
+        assert!(result.error_message().unwrap().contains("read-only"));
+    }
+}
@@ -1,4 +1,5 @@
-use super::traits::{Tool, ToolResult};
+use super::file_undo::FileBackups;
+use super::traits::{Tool, ToolError, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -7,11 +8,21 @@ use std::sync::Arc;
 /// Write file contents with path sandboxing
 pub struct FileWriteTool {
     security: Arc<SecurityPolicy>,
+    backups: Option<Arc<FileBackups>>,
 }
 
 impl FileWriteTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            backups: None,
+        }
+    }
+
+    /// Back up each file before it is overwritten so `file_undo` can revert it.
+    pub fn with_backups(mut self, backups: Arc<FileBackups>) -> Self {
+        self.backups = Some(backups);
+        self
     }
 }
 
@@ -57,7 +68,9 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
+                error: Some(ToolError::policy_blocked(
+                    "Action blocked: autonomy is read-only",
+                )),
             });
         }
 
@@ -65,7 +78,9 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: too many actions in the last hour",
+                )),
             });
         }
 
@@ -74,17 +89,19 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Path not allowed by security policy: {path}")),
+                error: Some(ToolError::policy_blocked(format!(
+                    "Path not allowed by security policy: {path}"
+                ))),
             });
         }
 
-        let full_path = self.security.workspace_dir.join(path);
+        let full_path = self.security.resolve_tool_path(path);
 
         let Some(parent) = full_path.parent() else {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Invalid path: missing parent directory".into()),
+                error: Some(ToolError::failed("Invalid path: missing parent directory")),
             });
         };
 
@@ -98,7 +115,10 @@ impl Tool for FileWriteTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Failed to resolve file path: {e}")),
+                    error: Some(ToolError::io(
+                        format!("Failed to resolve file path: {e}"),
+                        &e,
+                    )),
                 });
             }
         };
@@ -107,10 +127,10 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(
+                error: Some(ToolError::policy_blocked(
                     self.security
                         .resolved_path_violation_message(&resolved_parent),
-                ),
+                )),
             });
         }
 
@@ -118,7 +138,7 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Invalid path: missing file name".into()),
+                error: Some(ToolError::failed("Invalid path: missing file name")),
             });
         };
 
@@ -130,10 +150,10 @@ impl Tool for FileWriteTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!(
+                    error: Some(ToolError::policy_blocked(format!(
                         "Refusing to write through symlink: {}",
                         resolved_target.display()
-                    )),
+                    ))),
                 });
             }
         }
@@ -142,10 +162,19 @@ impl Tool for FileWriteTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: action budget exhausted",
+                )),
             });
         }
 
+        // A failed backup must not block the write itself.
+        if let Some(backups) = &self.backups {
+            if let Err(e) = backups.snapshot(&resolved_target) {
+                tracing::warn!("Failed to back up {path} before writing: {e:#}");
+            }
+        }
+
         match tokio::fs::write(&resolved_target, content).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
@@ -155,7 +184,7 @@ impl Tool for FileWriteTool {
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Failed to write file: {e}")),
+                error: Some(ToolError::io(format!("Failed to write file: {e}"), &e)),
             }),
         }
     }
@@ -283,7 +312,7 @@ mod tests {
             .await
             .unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        assert!(result.error_message().unwrap().contains("not allowed"));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -296,7 +325,7 @@ mod tests {
             .await
             .unwrap();
         assert!(!result.success);
-        assert!(result.error.as_ref().unwrap().contains("not allowed"));
+        assert!(result.error_message().unwrap().contains("not allowed"));
     }
 
     #[tokio::test]
@@ -353,8 +382,7 @@ mod tests {
 
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("escapes workspace"));
         assert!(!outside.join("hijack.txt").exists());
@@ -375,7 +403,7 @@ mod tests {
             .unwrap();
 
         assert!(!result.success);
-        assert!(result.error.as_deref().unwrap_or("").contains("read-only"));
+        assert!(result.error_message().unwrap_or("").contains("read-only"));
         assert!(!dir.join("out.txt").exists());
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
@@ -399,8 +427,7 @@ mod tests {
 
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Rate limit exceeded"));
         assert!(!dir.join("out.txt").exists());
@@ -437,7 +464,7 @@ mod tests {
 
         assert!(!result.success, "writing through symlink must be blocked");
         assert!(
-            result.error.as_deref().unwrap_or("").contains("symlink"),
+            result.error_message().unwrap_or("").contains("symlink"),
             "error should mention symlink"
         );
 
@@ -465,4 +492,31 @@ mod tests {
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
+
+    #[tokio::test]
+    async fn file_write_with_backups_can_be_undone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().canonicalize().unwrap();
+        tokio::fs::write(workspace.join("notes.md"), "original")
+            .await
+            .unwrap();
+        let security = test_security(workspace.clone());
+        let backups = Arc::new(FileBackups::new(
+            &crate::config::FileBackupsConfig::default(),
+            security.clone(),
+        ));
+
+        let tool = FileWriteTool::new(security).with_backups(backups.clone());
+        let result = tool
+            .execute(json!({"path": "notes.md", "content": "rewritten"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        assert_eq!(backups.undo_last(1).unwrap(), vec!["restored notes.md"]);
+        let content = tokio::fs::read_to_string(workspace.join("notes.md"))
+            .await
+            .unwrap();
+        assert_eq!(content, "original");
+    }
 }
@@ -0,0 +1,690 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::security::policy::ToolOperation;
+use crate::security::{AutonomyLevel, SecurityPolicy};
+use async_trait::async_trait;
+use git2::{
+    BranchType, Cred, DiffFormat, DiffOptions, IndexAddOption, PushOptions, RemoteCallbacks,
+    Repository, ResetType, Signature, Sort, Status, StatusOptions,
+};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Patch text beyond this many bytes is truncated in `diff` output.
+const MAX_PATCH_BYTES: usize = 64 * 1024;
+const DEFAULT_LOG_COUNT: usize = 20;
+const MAX_LOG_COUNT: usize = 200;
+
+/// Structured git operations through libgit2 (no shelling out).
+///
+/// Read operations (`status`, `diff`, `log`, `branch` listing) are always
+/// allowed; `commit` and branch changes count as actions; `push` and `reset`
+/// additionally require `approved=true` in supervised mode.
+pub struct GitTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl GitTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// Resolve the repository root for `path`, enforcing workspace policy on
+    /// both the requested path and the discovered working directory.
+    fn resolve_repo(&self, path: &str) -> Result<PathBuf, ToolError> {
+        if !self.security.is_path_allowed(path) {
+            return Err(ToolError::policy_blocked(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+        let full = self.security.workspace_dir.join(path);
+        let repo = Repository::discover(&full).map_err(|e| {
+            ToolError::not_found(format!("Not a git repository ({path}): {}", e.message()))
+        })?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| ToolError::failed("Bare repositories are not supported"))?;
+        let resolved = workdir
+            .canonicalize()
+            .map_err(|e| ToolError::io(format!("Failed to resolve repository path: {e}"), &e))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(ToolError::policy_blocked(
+                self.security.resolved_path_violation_message(&resolved),
+            ));
+        }
+        Ok(resolved)
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn string_list(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn description(&self) -> &str {
+        "Run structured git operations (status, diff, log, commit, branch, push, reset) on a workspace repository and return JSON"
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["status", "diff", "log", "commit", "branch", "push", "reset"],
+                    "description": "Git operation to run"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Repository path relative to the workspace (default: \".\")"
+                },
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "diff: limit to these paths; commit: stage only these paths (default: all changes)"
+                },
+                "staged": {
+                    "type": "boolean",
+                    "description": "diff: compare HEAD to the index instead of the index to the working tree"
+                },
+                "max_count": {
+                    "type": "integer",
+                    "description": "log: number of commits to return (default 20, max 200)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "commit: commit message"
+                },
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create", "checkout"],
+                    "description": "branch: what to do (default: list)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "branch: branch name for create/checkout; push: branch to push (default: current)"
+                },
+                "remote": {
+                    "type": "string",
+                    "description": "push: remote name (default: origin)"
+                },
+                "target": {
+                    "type": "string",
+                    "description": "reset: revision to reset to (default: HEAD)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["soft", "mixed", "hard"],
+                    "description": "reset: reset mode (default: mixed)"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve push/reset in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let operation = str_arg(&args, "operation")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'operation' parameter"))?
+            .to_string();
+        let approved = args
+            .get("approved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let branch_action = str_arg(&args, "action").unwrap_or("list");
+
+        let (kind, gated) = match operation.as_str() {
+            "status" | "diff" | "log" => (ToolOperation::Read, false),
+            "branch" if branch_action == "list" => (ToolOperation::Read, false),
+            "commit" | "branch" => (ToolOperation::Act, false),
+            "push" | "reset" => (ToolOperation::Act, true),
+            other => {
+                return Ok(ToolResult::failure(ToolError::failed(format!(
+                    "Unknown git operation: {other}"
+                ))))
+            }
+        };
+        if gated && self.security.autonomy == AutonomyLevel::Supervised && !approved {
+            return Ok(ToolResult::failure(ToolError::permission_denied(format!(
+                "git {operation} requires explicit approval (approved=true)"
+            ))));
+        }
+
+        let repo_path = match self.resolve_repo(str_arg(&args, "path").unwrap_or(".")) {
+            Ok(path) => path,
+            Err(e) => return Ok(ToolResult::failure(e)),
+        };
+        for path in string_list(&args, "paths") {
+            if !self.security.is_path_allowed(&path) {
+                return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                    "Path not allowed by security policy: {path}"
+                ))));
+            }
+        }
+        if let Err(e) = self
+            .security
+            .enforce_tool_operation(kind, &format!("git {operation}"))
+        {
+            return Ok(ToolResult::failure(ToolError::from_policy_violation(e)));
+        }
+
+        let result = tokio::task::spawn_blocking(move || {
+            let repo = Repository::open(&repo_path)?;
+            match operation.as_str() {
+                "status" => status(&repo),
+                "diff" => diff(&repo, &args),
+                "log" => log(&repo, &args),
+                "commit" => commit(&repo, &args),
+                "branch" => branch(&repo, &args),
+                "push" => push(&repo, &args),
+                _ => reset(&repo, &args),
+            }
+        })
+        .await?;
+
+        Ok(match result {
+            Ok(value) => ToolResult {
+                success: true,
+                output: serde_json::to_string_pretty(&value)?,
+                error: None,
+            },
+            Err(e) => ToolResult::failure(ToolError::failed(format!("git error: {e:#}"))),
+        })
+    }
+}
+
+fn head_branch(repo: &Repository) -> Option<String> {
+    match repo.head() {
+        Ok(head) => head.shorthand().map(str::to_string),
+        // Unborn branch: HEAD points at a ref that has no commit yet.
+        Err(_) => repo
+            .find_reference("HEAD")
+            .ok()?
+            .symbolic_target()?
+            .strip_prefix("refs/heads/")
+            .map(str::to_string),
+    }
+}
+
+fn head_commit(repo: &Repository) -> Option<git2::Commit<'_>> {
+    repo.head().ok()?.peel_to_commit().ok()
+}
+
+fn index_state(status: Status) -> Option<&'static str> {
+    if status.is_index_new() {
+        Some("added")
+    } else if status.is_index_modified() {
+        Some("modified")
+    } else if status.is_index_deleted() {
+        Some("deleted")
+    } else if status.is_index_renamed() {
+        Some("renamed")
+    } else if status.is_index_typechange() {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn worktree_state(status: Status) -> Option<&'static str> {
+    if status.is_wt_new() {
+        Some("untracked")
+    } else if status.is_wt_modified() {
+        Some("modified")
+    } else if status.is_wt_deleted() {
+        Some("deleted")
+    } else if status.is_wt_renamed() {
+        Some("renamed")
+    } else if status.is_wt_typechange() {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn status(repo: &Repository) -> anyhow::Result<Value> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+    let entries: Vec<Value> = repo
+        .statuses(Some(&mut options))?
+        .iter()
+        .filter(|entry| !entry.status().is_ignored())
+        .map(|entry| {
+            let status = entry.status();
+            json!({
+                "path": entry.path().unwrap_or_default(),
+                "index": index_state(status),
+                "worktree": worktree_state(status),
+                "conflicted": status.is_conflicted(),
+            })
+        })
+        .collect();
+    Ok(json!({
+        "branch": head_branch(repo),
+        "head": head_commit(repo).map(|c| c.id().to_string()),
+        "clean": entries.is_empty(),
+        "entries": entries,
+    }))
+}
+
+fn diff(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let mut options = DiffOptions::new();
+    for path in string_list(args, "paths") {
+        options.pathspec(path);
+    }
+    let staged = args.get("staged").and_then(Value::as_bool).unwrap_or(false);
+    let diff = if staged {
+        let head_tree = head_commit(repo).map(|c| c.tree()).transpose()?;
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut options))?
+    } else {
+        options.include_untracked(true).show_untracked_content(true);
+        repo.diff_index_to_workdir(None, Some(&mut options))?
+    };
+
+    let files: Vec<Value> = diff
+        .deltas()
+        .map(|delta| {
+            json!({
+                "path": delta
+                    .new_file()
+                    .path()
+                    .or_else(|| delta.old_file().path())
+                    .map(|p| p.to_string_lossy().into_owned()),
+                "status": format!("{:?}", delta.status()).to_ascii_lowercase(),
+            })
+        })
+        .collect();
+    let stats = diff.stats()?;
+
+    let mut patch = String::new();
+    let mut truncated = false;
+    diff.print(DiffFormat::Patch, |_delta, _hunk, line| {
+        if patch.len() >= MAX_PATCH_BYTES {
+            truncated = true;
+            return false;
+        }
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .or_else(|e| if truncated { Ok(()) } else { Err(e) })?;
+
+    Ok(json!({
+        "staged": staged,
+        "files_changed": stats.files_changed(),
+        "insertions": stats.insertions(),
+        "deletions": stats.deletions(),
+        "files": files,
+        "patch": patch,
+        "truncated": truncated,
+    }))
+}
+
+fn log(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let max_count = args
+        .get("max_count")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_LOG_COUNT, |n| {
+            usize::try_from(n).unwrap_or(MAX_LOG_COUNT)
+        })
+        .clamp(1, MAX_LOG_COUNT);
+    if head_commit(repo).is_none() {
+        return Ok(json!({ "branch": head_branch(repo), "commits": [] }));
+    }
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(Sort::TIME)?;
+
+    let mut commits = Vec::new();
+    for oid in walk.take(max_count) {
+        let commit = repo.find_commit(oid?)?;
+        let author = commit.author();
+        let time =
+            chrono::DateTime::from_timestamp(commit.time().seconds(), 0).map(|t| t.to_rfc3339());
+        commits.push(json!({
+            "id": commit.id().to_string(),
+            "summary": commit.summary().unwrap_or_default(),
+            "author": author.name().unwrap_or_default(),
+            "email": author.email().unwrap_or_default(),
+            "time": time,
+            "parents": commit.parent_count(),
+        }));
+    }
+    Ok(json!({ "branch": head_branch(repo), "commits": commits }))
+}
+
+fn commit(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let message =
+        str_arg(args, "message").ok_or_else(|| anyhow::anyhow!("commit requires a 'message'"))?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow::anyhow!("Bare repositories are not supported"))?
+        .to_path_buf();
+
+    let mut index = repo.index()?;
+    let paths = string_list(args, "paths");
+    if paths.is_empty() {
+        index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+        index.update_all(["*"], None)?;
+    } else {
+        for path in &paths {
+            if workdir.join(path).exists() {
+                index.add_path(Path::new(path))?;
+            } else {
+                index.remove_path(Path::new(path))?;
+            }
+        }
+    }
+    index.write()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+
+    let parent = head_commit(repo);
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+        anyhow::bail!("Nothing to commit: working tree matches HEAD");
+    }
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("ZeroClaw", "zeroclaw@localhost"))?;
+    let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+
+    let files_changed = match &parent {
+        Some(p) => repo
+            .diff_tree_to_tree(Some(&p.tree()?), Some(&tree), None)?
+            .deltas()
+            .len(),
+        None => tree.len(),
+    };
+    Ok(json!({
+        "commit": oid.to_string(),
+        "branch": head_branch(repo),
+        "summary": message.lines().next().unwrap_or_default(),
+        "files_changed": files_changed,
+    }))
+}
+
+fn branch(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let action = str_arg(args, "action").unwrap_or("list");
+    if action == "list" {
+        let current = head_branch(repo);
+        let mut branches = Vec::new();
+        for entry in repo.branches(Some(BranchType::Local))? {
+            let (branch, _) = entry?;
+            let name = branch.name()?.unwrap_or_default().to_string();
+            branches.push(json!({
+                "current": current.as_deref() == Some(name.as_str()),
+                "commit": branch.get().target().map(|oid| oid.to_string()),
+                "name": name,
+            }));
+        }
+        return Ok(json!({ "current": current, "branches": branches }));
+    }
+
+    let name = str_arg(args, "name")
+        .ok_or_else(|| anyhow::anyhow!("branch {action} requires a 'name'"))?;
+    match action {
+        "create" => {
+            let head = head_commit(repo)
+                .ok_or_else(|| anyhow::anyhow!("Cannot branch before the first commit"))?;
+            let branch = repo.branch(name, &head, false)?;
+            Ok(json!({
+                "created": name,
+                "commit": branch.get().target().map(|oid| oid.to_string()),
+            }))
+        }
+        "checkout" => {
+            let refname = format!("refs/heads/{name}");
+            let target = repo.revparse_single(&refname)?;
+            repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+            repo.set_head(&refname)?;
+            Ok(json!({ "checked_out": name, "commit": target.id().to_string() }))
+        }
+        other => anyhow::bail!("Unknown branch action: {other}"),
+    }
+}
+
+fn push(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let remote_name = str_arg(args, "remote").unwrap_or("origin");
+    let branch = match str_arg(args, "name") {
+        Some(name) => name.to_string(),
+        None => head_branch(repo).ok_or_else(|| anyhow::anyhow!("HEAD is detached"))?,
+    };
+    let mut remote = repo.find_remote(remote_name)?;
+    let config = repo.config()?;
+
+    // Declared before the callbacks that borrow it, so it outlives them.
+    let mut rejected = None;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(|url, username, allowed| {
+        if allowed.is_ssh_key() {
+            if let Some(user) = username {
+                return Cred::ssh_key_from_agent(user);
+            }
+        }
+        if allowed.is_user_pass_plaintext() {
+            return Cred::credential_helper(&config, url, username);
+        }
+        Cred::default()
+    });
+    callbacks.push_update_reference(|refname, status| {
+        if let Some(message) = status {
+            rejected = Some(format!("{refname}: {message}"));
+        }
+        Ok(())
+    });
+
+    let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+    remote.push(&[refspec.as_str()], Some(&mut options))?;
+    drop(options);
+    if let Some(reason) = rejected {
+        anyhow::bail!("Push rejected: {reason}");
+    }
+    Ok(json!({ "pushed": branch, "remote": remote_name }))
+}
+
+fn reset(repo: &Repository, args: &Value) -> anyhow::Result<Value> {
+    let target = str_arg(args, "target").unwrap_or("HEAD");
+    let (mode, reset_type) = match str_arg(args, "mode").unwrap_or("mixed") {
+        "soft" => ("soft", ResetType::Soft),
+        "mixed" => ("mixed", ResetType::Mixed),
+        "hard" => ("hard", ResetType::Hard),
+        other => anyhow::bail!("Unknown reset mode: {other}"),
+    };
+    let object = repo.revparse_single(target)?;
+    let commit = object.peel_to_commit()?;
+    repo.reset(commit.as_object(), reset_type, None)?;
+    Ok(json!({
+        "reset_to": commit.id().to_string(),
+        "mode": mode,
+        "branch": head_branch(repo),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn policy(workspace: &Path, autonomy: AutonomyLevel) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn init_repo(dir: &Path) -> Repository {
+        let repo = Repository::init(dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    }
+
+    async fn run(tool: &GitTool, args: Value) -> ToolResult {
+        tool.execute(args).await.unwrap()
+    }
+
+    fn output(result: &ToolResult) -> Value {
+        assert!(result.success, "git tool failed: {:?}", result.error);
+        serde_json::from_str(&result.output).unwrap()
+    }
+
+    #[tokio::test]
+    async fn status_commit_diff_and_log_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("README.md"), "hello\n").unwrap();
+        let tool = GitTool::new(policy(tmp.path(), AutonomyLevel::Supervised));
+
+        let status = output(&run(&tool, json!({"operation": "status"})).await);
+        assert_eq!(status["clean"], false);
+        assert_eq!(status["entries"][0]["path"], "README.md");
+        assert_eq!(status["entries"][0]["worktree"], "untracked");
+
+        let committed = output(
+            &run(
+                &tool,
+                json!({"operation": "commit", "message": "Initial commit"}),
+            )
+            .await,
+        );
+        assert_eq!(committed["files_changed"], 1);
+        assert_eq!(
+            output(&run(&tool, json!({"operation": "status"})).await)["clean"],
+            true
+        );
+
+        std::fs::write(tmp.path().join("README.md"), "hello\nworld\n").unwrap();
+        let diff = output(&run(&tool, json!({"operation": "diff"})).await);
+        assert_eq!(diff["insertions"], 1);
+        assert!(diff["patch"].as_str().unwrap().contains("+world"));
+
+        output(
+            &run(
+                &tool,
+                json!({"operation": "commit", "message": "Add world", "paths": ["README.md"]}),
+            )
+            .await,
+        );
+        let nothing = run(&tool, json!({"operation": "commit", "message": "noop"})).await;
+        assert!(nothing
+            .error_message()
+            .unwrap()
+            .contains("Nothing to commit"));
+
+        let log = output(&run(&tool, json!({"operation": "log", "max_count": 5})).await);
+        let commits = log["commits"].as_array().unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0]["summary"], "Add world");
+        assert_eq!(commits[1]["summary"], "Initial commit");
+    }
+
+    #[tokio::test]
+    async fn branches_can_be_created_and_checked_out() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("a.txt"), "a").unwrap();
+        let tool = GitTool::new(policy(tmp.path(), AutonomyLevel::Full));
+        output(&run(&tool, json!({"operation": "commit", "message": "a"})).await);
+
+        output(
+            &run(
+                &tool,
+                json!({"operation": "branch", "action": "create", "name": "feature"}),
+            )
+            .await,
+        );
+        output(
+            &run(
+                &tool,
+                json!({"operation": "branch", "action": "checkout", "name": "feature"}),
+            )
+            .await,
+        );
+        let listed = output(&run(&tool, json!({"operation": "branch"})).await);
+        assert_eq!(listed["current"], "feature");
+        assert_eq!(listed["branches"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reset_and_push_require_approval_when_supervised() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        std::fs::write(tmp.path().join("a.txt"), "a").unwrap();
+        let tool = GitTool::new(policy(tmp.path(), AutonomyLevel::Supervised));
+        output(&run(&tool, json!({"operation": "commit", "message": "a"})).await);
+
+        for operation in ["reset", "push"] {
+            let denied = run(&tool, json!({"operation": operation})).await;
+            assert!(!denied.success);
+            assert!(denied.error_message().unwrap().contains("approved=true"));
+        }
+        let reset = output(
+            &run(
+                &tool,
+                json!({"operation": "reset", "mode": "hard", "approved": true}),
+            )
+            .await,
+        );
+        assert_eq!(reset["mode"], "hard");
+
+        let read_only = GitTool::new(policy(tmp.path(), AutonomyLevel::ReadOnly));
+        assert!(
+            run(&read_only, json!({"operation": "status"}))
+                .await
+                .success
+        );
+        let blocked = run(&read_only, json!({"operation": "commit", "message": "x"})).await;
+        assert!(blocked.error_message().unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        init_repo(tmp.path());
+        let tool = GitTool::new(policy(tmp.path(), AutonomyLevel::Full));
+        let result = run(&tool, json!({"operation": "status", "path": "../"})).await;
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("not allowed"));
+    }
+}
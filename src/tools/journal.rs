@@ -0,0 +1,655 @@
+//! Write-ahead journal for side-effecting tool calls (`[agent] journal_actions`).
+//!
+//! Before a journaled tool runs, an `intent` record is appended (and synced)
+//! to `<workspace>/state/journal.jsonl`; a `complete` record follows once the
+//! tool returns. An intent without a completion means the process died while
+//! the action was running. For `file_write`, the previous file contents are
+//! copied to `state/journal-backups/<id>` first, so such a partial write can
+//! be rolled back with `zeroclaw journal rollback`. Since the journal lives in
+//! the workspace, rollback re-checks every recorded path against the
+//! security policy and only accepts backups named by their entry id.
+
+use super::traits::{Tool, ToolResult};
+use crate::security::grants::PathGrants;
+use crate::security::SecurityPolicy;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+pub const JOURNAL_FILE: &str = "journal.jsonl";
+const BACKUP_DIR: &str = "journal-backups";
+/// Tools whose calls change state outside the conversation.
+pub const JOURNALED_TOOLS: [&str; 5] = ["shell", "process", "file_write", "memory_store", "git"];
+/// Longest argument summary kept per record.
+const SUMMARY_MAX_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalPhase {
+    Intent,
+    Complete,
+    /// Resolved by `zeroclaw journal rollback` or `clear`.
+    Resolved,
+}
+
+/// File state captured before a `file_write`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    pub path: PathBuf,
+    /// Backup of the previous contents; `None` if the file did not exist.
+    pub backup: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    pub id: String,
+    pub phase: JournalPhase,
+    pub timestamp: String,
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<FileSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+/// Append-only action journal rooted at `<workspace>/state`.
+pub struct ActionJournal {
+    path: PathBuf,
+    backup_dir: PathBuf,
+    security: Arc<SecurityPolicy>,
+    lock: parking_lot::Mutex<()>,
+}
+
+impl ActionJournal {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        let state_dir = security.workspace_dir.join("state");
+        Self {
+            path: state_dir.join(JOURNAL_FILE),
+            backup_dir: state_dir.join(BACKUP_DIR),
+            security,
+            lock: parking_lot::Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record the intent to run `tool` with `args`. Returns the entry id.
+    pub fn begin(&self, tool: &str, args: &serde_json::Value) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let file = if tool == "file_write" {
+            match args.get("path").and_then(serde_json::Value::as_str) {
+                Some(path) => self.snapshot(&id, path)?,
+                None => None,
+            }
+        } else {
+            None
+        };
+        self.append(&JournalRecord {
+            id: id.clone(),
+            phase: JournalPhase::Intent,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: tool.to_string(),
+            summary: summarize_args(tool, args),
+            file,
+            success: None,
+        })?;
+        Ok(id)
+    }
+
+    /// Mark entry `id` as finished and drop its backup.
+    pub fn complete(&self, id: &str, tool: &str, success: bool) -> Result<()> {
+        self.append(&JournalRecord {
+            id: id.to_string(),
+            phase: JournalPhase::Complete,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: tool.to_string(),
+            summary: String::new(),
+            file: None,
+            success: Some(success),
+        })?;
+        if let Some(backup) = self.backup_path(id) {
+            let _ = std::fs::remove_file(backup);
+        }
+        Ok(())
+    }
+
+    /// Intents with no completion: actions interrupted by a crash (or still
+    /// running in another process).
+    pub fn pending(&self) -> Result<Vec<JournalRecord>> {
+        let records = self.read_all()?;
+        let finished: HashSet<&str> = records
+            .iter()
+            .filter(|r| r.phase != JournalPhase::Intent)
+            .map(|r| r.id.as_str())
+            .collect();
+        Ok(records
+            .iter()
+            .filter(|r| r.phase == JournalPhase::Intent && !finished.contains(r.id.as_str()))
+            .cloned()
+            .collect())
+    }
+
+    /// Undo a pending `file_write`: restore the backup, or remove a file the
+    /// write created. Other tools cannot be undone and are only resolved.
+    pub fn rollback(&self, record: &JournalRecord) -> Result<String> {
+        let outcome = match record.file.as_ref() {
+            Some(FileSnapshot {
+                path,
+                backup: Some(backup),
+            }) => {
+                let path = self.rollback_target(path)?;
+                if self.backup_path(&record.id).as_ref() != Some(backup) {
+                    anyhow::bail!(
+                        "Refusing to restore {}: unexpected backup {}",
+                        path.display(),
+                        backup.display()
+                    );
+                }
+                std::fs::copy(backup, &path)
+                    .with_context(|| format!("Failed to restore {}", path.display()))?;
+                format!("restored {}", path.display())
+            }
+            Some(FileSnapshot { path, backup: None }) => {
+                let path = self.rollback_target(path)?;
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        return Err(e)
+                            .with_context(|| format!("Failed to remove {}", path.display()))
+                    }
+                }
+                format!("removed partially written {}", path.display())
+            }
+            None => format!("{} cannot be rolled back; marked resolved", record.tool),
+        };
+        self.resolve(record)?;
+        Ok(outcome)
+    }
+
+    /// Mark a pending entry as handled without changing the workspace.
+    pub fn resolve(&self, record: &JournalRecord) -> Result<()> {
+        self.append(&JournalRecord {
+            id: record.id.clone(),
+            phase: JournalPhase::Resolved,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: record.tool.clone(),
+            summary: String::new(),
+            file: None,
+            success: None,
+        })?;
+        if let Some(backup) = self.backup_path(&record.id) {
+            let _ = std::fs::remove_file(backup);
+        }
+        Ok(())
+    }
+
+    /// Rewrite the journal keeping only pending intents.
+    pub fn compact(&self) -> Result<()> {
+        let pending = self.pending()?;
+        let _guard = self.lock.lock();
+        if pending.is_empty() {
+            return match std::fs::remove_file(&self.path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        let mut body = String::new();
+        for record in &pending {
+            body.push_str(&serde_json::to_string(record)?);
+            body.push('\n');
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp, body)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Back up `path` before a `file_write`, resolved against the session's
+    /// working directory as file_write does. Paths the policy would refuse
+    /// are not snapshotted, since file_write will not touch them either.
+    fn snapshot(&self, id: &str, path: &str) -> Result<Option<FileSnapshot>> {
+        if !self.security.is_path_allowed(path) {
+            return Ok(None);
+        }
+        let Some(target) = self.allowed_target(&self.security.resolve_tool_path(path)) else {
+            return Ok(None);
+        };
+        let backup = if target.is_file() {
+            std::fs::create_dir_all(&self.backup_dir)?;
+            let backup = self.backup_dir.join(id);
+            std::fs::copy(&target, &backup)
+                .with_context(|| format!("Failed to back up {}", target.display()))?;
+            Some(backup)
+        } else {
+            None
+        };
+        Ok(Some(FileSnapshot {
+            path: target,
+            backup,
+        }))
+    }
+
+    /// `path` with its parent resolved, if tools may write there: no `..`,
+    /// inside the workspace, an allowed root or a grant, and not a symlink.
+    fn allowed_target(&self, path: &Path) -> Option<PathBuf> {
+        if path.components().any(|c| matches!(c, Component::ParentDir)) {
+            return None;
+        }
+        let parent = path.parent()?;
+        let resolved = parent
+            .canonicalize()
+            .unwrap_or_else(|_| parent.to_path_buf());
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return None;
+        }
+        let target = resolved.join(path.file_name()?);
+        if std::fs::symlink_metadata(&target).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            return None;
+        }
+        Some(target)
+    }
+
+    fn rollback_target(&self, path: &Path) -> Result<PathBuf> {
+        self.allowed_target(path).with_context(|| {
+            format!(
+                "Refusing to roll back {}: path is outside the allowed roots",
+                path.display()
+            )
+        })
+    }
+
+    /// Backup location for entry `id`. Only ids `begin` generates are
+    /// accepted, so a forged record cannot point outside the backup dir.
+    fn backup_path(&self, id: &str) -> Option<PathBuf> {
+        let parsed = uuid::Uuid::parse_str(id).ok()?;
+        (parsed.to_string() == id).then(|| self.backup_dir.join(id))
+    }
+
+    fn append(&self, record: &JournalRecord) -> Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let _guard = self.lock.lock();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open journal {}", self.path.display()))?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn read_all(&self) -> Result<Vec<JournalRecord>> {
+        let raw = match std::fs::read_to_string(&self.path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        // A torn final line from a crash mid-append is skipped.
+        Ok(raw
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+fn summarize_args(tool: &str, args: &serde_json::Value) -> String {
+    let summary = match tool {
+        "shell" => args.get("command").and_then(serde_json::Value::as_str),
+        "file_write" => args.get("path").and_then(serde_json::Value::as_str),
+        "memory_store" => args.get("key").and_then(serde_json::Value::as_str),
+        _ => None,
+    }
+    .map_or_else(|| args.to_string(), str::to_string);
+    crate::util::truncate_with_ellipsis(
+        &crate::security::redaction::redact_outbound(&summary),
+        SUMMARY_MAX_CHARS,
+    )
+}
+
+/// Wraps a tool so every call is journaled before and after execution.
+pub struct JournaledTool {
+    inner: Box<dyn Tool>,
+    journal: Arc<ActionJournal>,
+}
+
+impl JournaledTool {
+    pub fn new(inner: Box<dyn Tool>, journal: Arc<ActionJournal>) -> Self {
+        Self { inner, journal }
+    }
+}
+
+#[async_trait]
+impl Tool for JournaledTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner.parameters_schema()
+    }
+
+    fn is_read_only(&self) -> bool {
+        self.inner.is_read_only()
+    }
+
+    fn end_session(&self, session: &str) {
+        self.inner.end_session(session);
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        // A journal that cannot be written must not block the action itself.
+        let entry = match self.journal.begin(self.inner.name(), &args) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::warn!("Failed to journal {} call: {e:#}", self.inner.name());
+                None
+            }
+        };
+        let result = self.inner.execute(args).await;
+        if let Some(id) = entry {
+            let success = result.as_ref().is_ok_and(|r| r.success);
+            if let Err(e) = self.journal.complete(&id, self.inner.name(), success) {
+                tracing::warn!("Failed to complete journal entry {id}: {e:#}");
+            }
+        }
+        result
+    }
+}
+
+/// Wrap the side-effecting tools in `tools` with `journal`, warning about
+/// actions a previous run left incomplete.
+pub fn journal_tools(
+    tools: Vec<Box<dyn Tool>>,
+    journal: &Arc<ActionJournal>,
+) -> Vec<Box<dyn Tool>> {
+    match journal.pending() {
+        Ok(pending) if !pending.is_empty() => tracing::warn!(
+            "{} tool action(s) from a previous run did not complete; \
+             review with `zeroclaw journal status`",
+            pending.len()
+        ),
+        Ok(_) => {
+            // Nothing is outstanding, so completed history can be dropped.
+            if let Err(e) = journal.compact() {
+                tracing::warn!("Failed to compact action journal: {e:#}");
+            }
+        }
+        Err(e) => tracing::warn!("Failed to read action journal: {e:#}"),
+    }
+    tools
+        .into_iter()
+        .map(|tool| {
+            if JOURNALED_TOOLS.contains(&tool.name()) {
+                Box::new(JournaledTool::new(tool, Arc::clone(journal))) as Box<dyn Tool>
+            } else {
+                tool
+            }
+        })
+        .collect()
+}
+
+/// `zeroclaw journal status`: list actions a previous run left incomplete.
+pub fn run_status(config: &crate::config::Config) -> Result<()> {
+    let pending = cli_journal(config).pending()?;
+    if pending.is_empty() {
+        println!("✅ No incomplete tool actions");
+        return Ok(());
+    }
+    println!("⚠️  {} incomplete tool action(s):", pending.len());
+    for record in &pending {
+        let undo = if record.file.is_some() {
+            "rollback available"
+        } else {
+            "no rollback"
+        };
+        println!(
+            "  {}  {}  {:<12} {}  ({undo})",
+            short_id(&record.id),
+            record.timestamp,
+            record.tool,
+            record.summary
+        );
+    }
+    println!();
+    println!("Entries may belong to a process that is still running.");
+    println!("Undo file writes with `zeroclaw journal rollback`, or dismiss with `zeroclaw journal clear`.");
+    Ok(())
+}
+
+/// `zeroclaw journal rollback [id]`: undo incomplete file writes, newest first.
+pub fn run_rollback(config: &crate::config::Config, id: Option<&str>) -> Result<()> {
+    let journal = cli_journal(config);
+    let pending = select_pending(journal.pending()?, id)?;
+    for record in pending.iter().rev() {
+        println!(
+            "↩️  {}: {}",
+            short_id(&record.id),
+            journal.rollback(record)?
+        );
+    }
+    journal.compact()
+}
+
+/// `zeroclaw journal clear [id]`: dismiss incomplete entries without changes.
+pub fn run_clear(config: &crate::config::Config, id: Option<&str>) -> Result<()> {
+    let journal = cli_journal(config);
+    let pending = select_pending(journal.pending()?, id)?;
+    for record in &pending {
+        journal.resolve(record)?;
+    }
+    journal.compact()?;
+    println!("✅ Cleared {} journal entries", pending.len());
+    Ok(())
+}
+
+fn cli_journal(config: &crate::config::Config) -> ActionJournal {
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_path_grants(PathGrants::for_config(config)),
+    );
+    ActionJournal::new(security)
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+fn select_pending(pending: Vec<JournalRecord>, id: Option<&str>) -> Result<Vec<JournalRecord>> {
+    let Some(id) = id else {
+        return Ok(pending);
+    };
+    let selected: Vec<_> = pending
+        .into_iter()
+        .filter(|r| r.id.starts_with(id))
+        .collect();
+    if selected.is_empty() {
+        anyhow::bail!("No incomplete journal entry matches '{id}'");
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn journal(workspace: &Path) -> ActionJournal {
+        ActionJournal::new(Arc::new(SecurityPolicy {
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[test]
+    fn completed_actions_are_not_pending() {
+        let tmp = tempfile::tempdir().unwrap();
+        let journal = journal(tmp.path());
+        let done = journal.begin("shell", &json!({"command": "ls"})).unwrap();
+        journal.complete(&done, "shell", true).unwrap();
+        let crashed = journal
+            .begin("shell", &json!({"command": "make install"}))
+            .unwrap();
+
+        let pending = journal.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, crashed);
+        assert_eq!(pending[0].summary, "make install");
+
+        journal.compact().unwrap();
+        assert_eq!(journal.pending().unwrap().len(), 1);
+        journal.resolve(&pending[0]).unwrap();
+        journal.compact().unwrap();
+        assert!(!journal.path().exists());
+    }
+
+    #[test]
+    fn rollback_restores_overwritten_file_and_removes_created_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let journal = journal(tmp.path());
+        std::fs::write(tmp.path().join("notes.md"), "original").unwrap();
+
+        journal
+            .begin("file_write", &json!({"path": "notes.md", "content": "new"}))
+            .unwrap();
+        journal
+            .begin("file_write", &json!({"path": "fresh.md", "content": "x"}))
+            .unwrap();
+        // Simulate a crash midway through both writes.
+        std::fs::write(tmp.path().join("notes.md"), "half-writ").unwrap();
+        std::fs::write(tmp.path().join("fresh.md"), "x").unwrap();
+
+        for record in journal.pending().unwrap() {
+            journal.rollback(&record).unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join("notes.md")).unwrap(),
+            "original"
+        );
+        assert!(!tmp.path().join("fresh.md").exists());
+        assert!(journal.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn snapshot_follows_the_session_working_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("notes.md"), "top").unwrap();
+        std::fs::write(root.join("src/notes.md"), "nested").unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: root.clone(),
+            ..SecurityPolicy::default()
+        });
+        assert!(security.set_current_dir(&root.join("src")));
+        let journal = ActionJournal::new(security);
+
+        journal
+            .begin("file_write", &json!({"path": "notes.md", "content": "x"}))
+            .unwrap();
+        std::fs::write(root.join("src/notes.md"), "half-writ").unwrap();
+
+        let pending = journal.pending().unwrap();
+        assert_eq!(
+            pending[0].file.as_ref().unwrap().path,
+            root.join("src/notes.md")
+        );
+        journal.rollback(&pending[0]).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(root.join("src/notes.md")).unwrap(),
+            "nested"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("notes.md")).unwrap(),
+            "top"
+        );
+    }
+
+    #[test]
+    fn begin_does_not_snapshot_paths_the_policy_refuses() {
+        let tmp = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+        let journal = journal(tmp.path());
+
+        for path in ["../secret.txt", secret.to_str().unwrap()] {
+            journal
+                .begin("file_write", &json!({"path": path, "content": "x"}))
+                .unwrap();
+        }
+        assert!(journal.pending().unwrap().iter().all(|r| r.file.is_none()));
+        assert!(!tmp.path().join("state").join(BACKUP_DIR).exists());
+    }
+
+    #[test]
+    fn rollback_refuses_forged_paths_and_backups() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let victim = outside.path().join("victim.txt");
+        let notes = root.join("notes.md");
+        std::fs::write(&victim, "victim").unwrap();
+        std::fs::write(&notes, "notes").unwrap();
+        let journal = journal(&root);
+
+        let forged = |path: &Path, backup: Option<PathBuf>| JournalRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            phase: JournalPhase::Intent,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: "file_write".into(),
+            summary: String::new(),
+            file: Some(FileSnapshot {
+                path: path.to_path_buf(),
+                backup,
+            }),
+            success: None,
+        };
+
+        let delete_outside = forged(&victim, None);
+        let err = journal.rollback(&delete_outside).unwrap_err();
+        assert!(err.to_string().contains("outside the allowed roots"));
+        assert!(victim.exists());
+
+        let copy_outside = forged(&notes, Some(victim.clone()));
+        let err = journal.rollback(&copy_outside).unwrap_err();
+        assert!(err.to_string().contains("unexpected backup"));
+        assert_eq!(std::fs::read_to_string(&notes).unwrap(), "notes");
+
+        let mut bad_id = forged(&notes, None);
+        bad_id.id = "../../../victim".into();
+        journal.resolve(&bad_id).unwrap();
+        assert!(victim.exists());
+    }
+
+    #[test]
+    fn torn_trailing_line_is_ignored() {
+        let tmp = tempfile::tempdir().unwrap();
+        let journal = journal(tmp.path());
+        journal
+            .begin("git", &json!({"operation": "commit"}))
+            .unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap();
+        file.write_all(b"{\"id\":\"abc\",\"pha").unwrap();
+        assert_eq!(journal.pending().unwrap().len(), 1);
+    }
+}
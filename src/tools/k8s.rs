@@ -0,0 +1,704 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::config::KubernetesToolConfig;
+use crate::security::policy::ToolOperation;
+use crate::security::{AutonomyLevel, SecurityPolicy};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::Utc;
+use k8s_openapi::api::apps::v1::Deployment;
+use k8s_openapi::api::core::v1::{ContainerStatus, Event, Namespace, Node, Pod, Service};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, Time};
+use kube::api::{
+    Api, ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, LogParams, Patch,
+    PatchParams,
+};
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::Client;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+const MAX_LIST_ITEMS: u32 = 200;
+const MAX_EVENTS: usize = 30;
+/// Log text beyond this many characters is truncated.
+const MAX_LOG_CHARS: usize = 60_000;
+const MAX_REPLICAS: i64 = 100;
+
+/// Read-mostly Kubernetes operations through the API server (no `kubectl`).
+///
+/// `get`, `logs`, `describe` and `top` are always allowed; `delete_pod`,
+/// `restart` and `scale` count as actions and require `approved=true` in
+/// supervised mode.
+pub struct K8sTool {
+    config: KubernetesToolConfig,
+    security: Arc<SecurityPolicy>,
+}
+
+impl K8sTool {
+    pub fn new(config: KubernetesToolConfig, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+
+    /// Build a client for `context` (or the configured default) and return
+    /// it with the context's default namespace.
+    async fn client(&self, context: Option<&str>) -> anyhow::Result<(Client, String)> {
+        let context = context.or(self.config.context.as_deref());
+        let options = KubeConfigOptions {
+            context: context.map(str::to_string),
+            ..KubeConfigOptions::default()
+        };
+        let mut config = match (&self.config.kubeconfig, context) {
+            (Some(path), _) => {
+                let path = shellexpand::tilde(path).into_owned();
+                let kubeconfig = Kubeconfig::read_from(&path)
+                    .with_context(|| format!("Failed to read kubeconfig {path}"))?;
+                kube::Config::from_custom_kubeconfig(kubeconfig, &options).await?
+            }
+            (None, Some(_)) => kube::Config::from_kubeconfig(&options).await?,
+            (None, None) => kube::Config::infer().await?,
+        };
+        config.read_timeout = Some(Duration::from_secs(REQUEST_TIMEOUT_SECS));
+        let namespace = config.default_namespace.clone();
+        Ok((Client::try_from(config)?, namespace))
+    }
+}
+
+fn str_arg<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+fn age(meta: &ObjectMeta) -> Option<String> {
+    meta.creation_timestamp.as_ref().map(since)
+}
+
+/// Compact age such as `3d`, `5h`, `12m`, `40s`.
+fn since(time: &Time) -> String {
+    let secs = (Utc::now() - time.0).num_seconds().max(0);
+    match secs {
+        s if s >= 86_400 => format!("{}d", s / 86_400),
+        s if s >= 3_600 => format!("{}h", s / 3_600),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+/// Why a container is not running cleanly, e.g. `CrashLoopBackOff`.
+fn container_problem(status: &ContainerStatus) -> Option<String> {
+    let state = status.state.as_ref()?;
+    if let Some(waiting) = &state.waiting {
+        return waiting.reason.clone();
+    }
+    state.terminated.as_ref().map(|t| {
+        t.reason
+            .clone()
+            .unwrap_or_else(|| format!("Exit {}", t.exit_code))
+    })
+}
+
+fn pod_summary(pod: &Pod) -> Value {
+    let statuses = pod
+        .status
+        .as_ref()
+        .and_then(|s| s.container_statuses.as_deref())
+        .unwrap_or_default();
+    let ready = statuses.iter().filter(|c| c.ready).count();
+    let restarts: i32 = statuses.iter().map(|c| c.restart_count).sum();
+    let phase = pod.status.as_ref().and_then(|s| s.phase.clone());
+    let status = statuses
+        .iter()
+        .find_map(container_problem)
+        .or_else(|| {
+            pod.metadata
+                .deletion_timestamp
+                .as_ref()
+                .map(|_| "Terminating".to_string())
+        })
+        .or(phase);
+    json!({
+        "name": pod.metadata.name,
+        "namespace": pod.metadata.namespace,
+        "ready": format!("{ready}/{}", statuses.len()),
+        "status": status,
+        "restarts": restarts,
+        "node": pod.spec.as_ref().and_then(|s| s.node_name.clone()),
+        "age": age(&pod.metadata),
+    })
+}
+
+fn deployment_summary(deployment: &Deployment) -> Value {
+    let desired = deployment
+        .spec
+        .as_ref()
+        .and_then(|s| s.replicas)
+        .unwrap_or(1);
+    let status = deployment.status.as_ref();
+    json!({
+        "name": deployment.metadata.name,
+        "namespace": deployment.metadata.namespace,
+        "ready": format!("{}/{desired}", status.and_then(|s| s.ready_replicas).unwrap_or(0)),
+        "up_to_date": status.and_then(|s| s.updated_replicas).unwrap_or(0),
+        "available": status.and_then(|s| s.available_replicas).unwrap_or(0),
+        "age": age(&deployment.metadata),
+    })
+}
+
+fn service_summary(service: &Service) -> Value {
+    let spec = service.spec.as_ref();
+    let ports: Vec<String> = spec
+        .and_then(|s| s.ports.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .map(|p| format!("{}/{}", p.port, p.protocol.as_deref().unwrap_or("TCP")))
+        .collect();
+    json!({
+        "name": service.metadata.name,
+        "namespace": service.metadata.namespace,
+        "type": spec.and_then(|s| s.type_.clone()),
+        "cluster_ip": spec.and_then(|s| s.cluster_ip.clone()),
+        "ports": ports,
+        "age": age(&service.metadata),
+    })
+}
+
+fn node_summary(node: &Node) -> Value {
+    let status = node.status.as_ref();
+    let ready = status
+        .and_then(|s| s.conditions.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .find(|c| c.type_ == "Ready")
+        .is_some_and(|c| c.status == "True");
+    let roles: Vec<&str> = node
+        .metadata
+        .labels
+        .iter()
+        .flatten()
+        .filter_map(|(key, _)| key.strip_prefix("node-role.kubernetes.io/"))
+        .collect();
+    json!({
+        "name": node.metadata.name,
+        "ready": ready,
+        "roles": roles,
+        "version": status.and_then(|s| s.node_info.as_ref()).map(|i| i.kubelet_version.clone()),
+        "age": age(&node.metadata),
+    })
+}
+
+fn event_summary(event: &Event) -> Value {
+    let last = event
+        .last_timestamp
+        .as_ref()
+        .or(event.first_timestamp.as_ref())
+        .map(since);
+    json!({
+        "type": event.type_,
+        "reason": event.reason,
+        "object": format!(
+            "{}/{}",
+            event.involved_object.kind.as_deref().unwrap_or_default().to_lowercase(),
+            event.involved_object.name.as_deref().unwrap_or_default()
+        ),
+        "message": event.message,
+        "count": event.count,
+        "last_seen": last,
+    })
+}
+
+/// Most recent events first, capped at [`MAX_EVENTS`].
+async fn events(api: &Api<Event>, object: Option<&str>) -> anyhow::Result<Vec<Value>> {
+    let mut params = ListParams::default();
+    if let Some(name) = object {
+        params = params.fields(&format!("involvedObject.name={name}"));
+    }
+    let mut items = api.list(&params).await?.items;
+    items.sort_by_key(|e| {
+        std::cmp::Reverse(
+            e.last_timestamp
+                .as_ref()
+                .or(e.first_timestamp.as_ref())
+                .map(|t| t.0),
+        )
+    });
+    Ok(items.iter().take(MAX_EVENTS).map(event_summary).collect())
+}
+
+fn container_details(statuses: &[ContainerStatus]) -> Vec<Value> {
+    statuses
+        .iter()
+        .map(|c| {
+            let last = c
+                .last_state
+                .as_ref()
+                .and_then(|s| s.terminated.as_ref())
+                .map(|t| {
+                    json!({
+                        "reason": t.reason,
+                        "exit_code": t.exit_code,
+                        "message": t.message,
+                        "finished": t.finished_at.as_ref().map(since),
+                    })
+                });
+            json!({
+                "name": c.name,
+                "image": c.image,
+                "ready": c.ready,
+                "restarts": c.restart_count,
+                "problem": container_problem(c),
+                "last_termination": last,
+            })
+        })
+        .collect()
+}
+
+/// `top` rows from the metrics API (`metrics.k8s.io`).
+fn metrics_rows(items: &[DynamicObject], per_container: bool) -> Vec<Value> {
+    items
+        .iter()
+        .map(|item| {
+            let usage = if per_container {
+                let containers = item.data["containers"].as_array().cloned().unwrap_or_default();
+                containers
+                    .iter()
+                    .map(|c| json!({"container": c["name"], "cpu": c["usage"]["cpu"], "memory": c["usage"]["memory"]}))
+                    .collect()
+            } else {
+                vec![json!({"cpu": item.data["usage"]["cpu"], "memory": item.data["usage"]["memory"]})]
+            };
+            json!({ "name": item.metadata.name, "usage": usage })
+        })
+        .collect()
+}
+
+impl K8sTool {
+    async fn run(&self, operation: &str, args: &Value) -> anyhow::Result<Value> {
+        let (client, default_namespace) = self.client(str_arg(args, "context")).await?;
+        let all_namespaces = args
+            .get("all_namespaces")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let namespace = str_arg(args, "namespace")
+            .unwrap_or(&default_namespace)
+            .to_string();
+        let name = str_arg(args, "name");
+        let need_name = || name.with_context(|| format!("'{operation}' requires 'name'"));
+        let list_params = || {
+            let params = ListParams::default().limit(MAX_LIST_ITEMS);
+            match str_arg(args, "label_selector") {
+                Some(selector) => params.labels(selector),
+                None => params,
+            }
+        };
+        let resource = str_arg(args, "resource").unwrap_or("pods");
+
+        match operation {
+            "get" => {
+                let items: Vec<Value> = match resource {
+                    "pods" => {
+                        let api: Api<Pod> = scoped(&client, &namespace, all_namespaces);
+                        api.list(&list_params())
+                            .await?
+                            .iter()
+                            .map(pod_summary)
+                            .collect()
+                    }
+                    "deployments" => {
+                        let api: Api<Deployment> = scoped(&client, &namespace, all_namespaces);
+                        api.list(&list_params())
+                            .await?
+                            .iter()
+                            .map(deployment_summary)
+                            .collect()
+                    }
+                    "services" => {
+                        let api: Api<Service> = scoped(&client, &namespace, all_namespaces);
+                        api.list(&list_params())
+                            .await?
+                            .iter()
+                            .map(service_summary)
+                            .collect()
+                    }
+                    "nodes" => Api::<Node>::all(client)
+                        .list(&list_params())
+                        .await?
+                        .iter()
+                        .map(node_summary)
+                        .collect(),
+                    "namespaces" => Api::<Namespace>::all(client)
+                        .list(&list_params())
+                        .await?
+                        .iter()
+                        .map(|ns| {
+                            json!({
+                                "name": ns.metadata.name,
+                                "status": ns.status.as_ref().and_then(|s| s.phase.clone()),
+                                "age": age(&ns.metadata),
+                            })
+                        })
+                        .collect(),
+                    "events" => {
+                        let api: Api<Event> = scoped(&client, &namespace, all_namespaces);
+                        events(&api, name).await?
+                    }
+                    other => anyhow::bail!("Unsupported resource '{other}'"),
+                };
+                Ok(json!({ "resource": resource, "count": items.len(), "items": items }))
+            }
+            "logs" => {
+                let pod = need_name()?;
+                let tail = args
+                    .get("tail_lines")
+                    .and_then(Value::as_u64)
+                    .and_then(|n| usize::try_from(n).ok())
+                    .unwrap_or(100)
+                    .clamp(1, self.config.max_log_lines);
+                let params = LogParams {
+                    container: str_arg(args, "container").map(str::to_string),
+                    previous: args
+                        .get("previous")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                    tail_lines: Some(tail as i64),
+                    ..LogParams::default()
+                };
+                let logs = Api::<Pod>::namespaced(client, &namespace)
+                    .logs(pod, &params)
+                    .await?;
+                Ok(json!({
+                    "pod": pod,
+                    "namespace": namespace,
+                    "logs": truncate_with_ellipsis(&logs, MAX_LOG_CHARS),
+                }))
+            }
+            "describe" => {
+                let target = need_name()?;
+                let event_api: Api<Event> = Api::namespaced(client.clone(), &namespace);
+                let mut described = match resource {
+                    "pods" => {
+                        let pod = Api::<Pod>::namespaced(client, &namespace)
+                            .get(target)
+                            .await?;
+                        let status = pod.status.as_ref();
+                        let mut summary = pod_summary(&pod);
+                        summary["containers"] = json!(container_details(
+                            status
+                                .and_then(|s| s.container_statuses.as_deref())
+                                .unwrap_or_default()
+                        ));
+                        summary["conditions"] = json!(status
+                            .and_then(|s| s.conditions.as_deref())
+                            .unwrap_or_default()
+                            .iter()
+                            .filter(|c| c.status != "True")
+                            .map(|c| json!({"type": c.type_, "reason": c.reason, "message": c.message}))
+                            .collect::<Vec<_>>());
+                        summary["message"] = json!(status.and_then(|s| s.message.clone()));
+                        summary
+                    }
+                    "deployments" => {
+                        let deployment = Api::<Deployment>::namespaced(client, &namespace)
+                            .get(target)
+                            .await?;
+                        let mut summary = deployment_summary(&deployment);
+                        summary["images"] = json!(deployment
+                            .spec
+                            .as_ref()
+                            .and_then(|s| s.template.spec.as_ref())
+                            .map(|s| s
+                                .containers
+                                .iter()
+                                .map(|c| c.image.clone())
+                                .collect::<Vec<_>>()));
+                        summary["conditions"] = json!(deployment
+                            .status
+                            .as_ref()
+                            .and_then(|s| s.conditions.as_deref())
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|c| json!({"type": c.type_, "status": c.status, "reason": c.reason, "message": c.message}))
+                            .collect::<Vec<_>>());
+                        summary
+                    }
+                    other => {
+                        anyhow::bail!("'describe' supports pods and deployments, not '{other}'")
+                    }
+                };
+                described["events"] = json!(events(&event_api, Some(target)).await?);
+                Ok(described)
+            }
+            "top" => {
+                let (kind, plural) = if resource == "nodes" {
+                    ("NodeMetrics", "nodes")
+                } else {
+                    ("PodMetrics", "pods")
+                };
+                let gvk = GroupVersionKind::gvk("metrics.k8s.io", "v1beta1", kind);
+                let ar = ApiResource::from_gvk_with_plural(&gvk, plural);
+                let api: Api<DynamicObject> = if plural == "nodes" || all_namespaces {
+                    Api::all_with(client, &ar)
+                } else {
+                    Api::namespaced_with(client, &namespace, &ar)
+                };
+                let items = api
+                    .list(&list_params())
+                    .await
+                    .context("Metrics API unavailable (is metrics-server installed?)")?
+                    .items;
+                Ok(json!({ "resource": plural, "items": metrics_rows(&items, plural == "pods") }))
+            }
+            "delete_pod" => {
+                let pod = need_name()?;
+                Api::<Pod>::namespaced(client, &namespace)
+                    .delete(pod, &DeleteParams::default())
+                    .await?;
+                Ok(json!({ "deleted": pod, "namespace": namespace }))
+            }
+            "restart" => {
+                let deployment = need_name()?;
+                Api::<Deployment>::namespaced(client, &namespace)
+                    .restart(deployment)
+                    .await?;
+                Ok(json!({ "restarted": deployment, "namespace": namespace }))
+            }
+            "scale" => {
+                let deployment = need_name()?;
+                let replicas = args
+                    .get("replicas")
+                    .and_then(Value::as_i64)
+                    .filter(|n| (0..=MAX_REPLICAS).contains(n))
+                    .with_context(|| format!("'scale' requires 'replicas' (0-{MAX_REPLICAS})"))?;
+                let patch = json!({ "spec": { "replicas": replicas } });
+                Api::<Deployment>::namespaced(client, &namespace)
+                    .patch_scale(deployment, &PatchParams::default(), &Patch::Merge(&patch))
+                    .await?;
+                Ok(json!({ "scaled": deployment, "namespace": namespace, "replicas": replicas }))
+            }
+            other => anyhow::bail!("Unknown k8s operation: {other}"),
+        }
+    }
+}
+
+fn scoped<K>(client: &Client, namespace: &str, all_namespaces: bool) -> Api<K>
+where
+    K: kube::Resource<Scope = k8s_openapi::NamespaceResourceScope>,
+    <K as kube::Resource>::DynamicType: Default,
+{
+    if all_namespaces {
+        Api::all(client.clone())
+    } else {
+        Api::namespaced(client.clone(), namespace)
+    }
+}
+
+#[async_trait]
+impl Tool for K8sTool {
+    fn name(&self) -> &str {
+        "k8s"
+    }
+
+    fn description(&self) -> &str {
+        "Inspect a Kubernetes cluster: list resources, read pod logs, describe pods/deployments with events, and show resource usage. Can also delete pods, restart or scale deployments with approval. Returns JSON."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["get", "logs", "describe", "top", "delete_pod", "restart", "scale"],
+                    "description": "Operation to run"
+                },
+                "resource": {
+                    "type": "string",
+                    "enum": ["pods", "deployments", "services", "nodes", "namespaces", "events"],
+                    "description": "get/describe/top: resource type (default: pods)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Pod or deployment name (logs, describe, delete_pod, restart, scale); get events: object name filter"
+                },
+                "namespace": {
+                    "type": "string",
+                    "description": "Namespace (default: the context's namespace)"
+                },
+                "all_namespaces": {
+                    "type": "boolean",
+                    "description": "get/top: list across all namespaces",
+                    "default": false
+                },
+                "label_selector": {
+                    "type": "string",
+                    "description": "get/top: label selector, e.g. app=api"
+                },
+                "context": {
+                    "type": "string",
+                    "description": "Kubeconfig context (default: configured or current context)"
+                },
+                "container": {
+                    "type": "string",
+                    "description": "logs: container name (default: the only container)"
+                },
+                "previous": {
+                    "type": "boolean",
+                    "description": "logs: read the previous (crashed) container instance",
+                    "default": false
+                },
+                "tail_lines": {
+                    "type": "integer",
+                    "description": "logs: number of lines from the end (default: 100)"
+                },
+                "replicas": {
+                    "type": "integer",
+                    "description": "scale: desired replica count"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve delete_pod/restart/scale in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let operation = str_arg(&args, "operation")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'operation' parameter"))?
+            .to_string();
+        let approved = args
+            .get("approved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let kind = match operation.as_str() {
+            "get" | "logs" | "describe" | "top" => ToolOperation::Read,
+            "delete_pod" | "restart" | "scale" => ToolOperation::Act,
+            other => {
+                return Ok(ToolResult::failure(ToolError::failed(format!(
+                    "Unknown k8s operation: {other}"
+                ))))
+            }
+        };
+        if let Some(context) = str_arg(&args, "context") {
+            if !self.config.allows_context(context) {
+                return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                    "Context '{context}' is not in [tools.kubernetes] allowed_contexts"
+                ))));
+            }
+        }
+        if kind == ToolOperation::Act
+            && self.security.autonomy == AutonomyLevel::Supervised
+            && !approved
+        {
+            return Ok(ToolResult::failure(ToolError::permission_denied(format!(
+                "k8s {operation} requires explicit approval (approved=true)"
+            ))));
+        }
+        if let Err(e) = self
+            .security
+            .enforce_tool_operation(kind, &format!("k8s {operation}"))
+        {
+            return Ok(ToolResult::failure(ToolError::from_policy_violation(e)));
+        }
+
+        match self.run(&operation, &args).await {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output: output.to_string(),
+                error: None,
+            }),
+            Err(e) => match e.downcast_ref::<kube::Error>() {
+                Some(kube::Error::Api(response)) if response.code == 404 => Ok(
+                    ToolResult::failure(ToolError::not_found(response.message.clone())),
+                ),
+                Some(kube::Error::Api(response)) if response.code == 403 => Ok(
+                    ToolResult::failure(ToolError::policy_blocked(response.message.clone())),
+                ),
+                _ => Ok(ToolResult::failure(ToolError::failed(format!("{e:#}")))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(autonomy: AutonomyLevel) -> K8sTool {
+        K8sTool::new(
+            KubernetesToolConfig {
+                enabled: true,
+                allowed_contexts: vec!["staging".into()],
+                ..KubernetesToolConfig::default()
+            },
+            Arc::new(SecurityPolicy {
+                autonomy,
+                ..SecurityPolicy::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn pod_summary_reports_crashloop_and_restarts() {
+        let pod: Pod = serde_json::from_value(json!({
+            "metadata": {"name": "api-7f9", "namespace": "default"},
+            "spec": {"containers": [{"name": "api"}], "nodeName": "node-1"},
+            "status": {
+                "phase": "Running",
+                "containerStatuses": [{
+                    "name": "api",
+                    "image": "api:1.2",
+                    "imageID": "",
+                    "ready": false,
+                    "restartCount": 7,
+                    "state": {"waiting": {"reason": "CrashLoopBackOff"}},
+                    "lastState": {"terminated": {"exitCode": 1, "reason": "Error"}}
+                }]
+            }
+        }))
+        .unwrap();
+        let summary = pod_summary(&pod);
+        assert_eq!(summary["ready"], "0/1");
+        assert_eq!(summary["status"], "CrashLoopBackOff");
+        assert_eq!(summary["restarts"], 7);
+
+        let statuses = pod.status.unwrap().container_statuses.unwrap();
+        let details = container_details(&statuses);
+        assert_eq!(details[0]["last_termination"]["exit_code"], 1);
+    }
+
+    #[tokio::test]
+    async fn mutations_need_approval_in_supervised_mode() {
+        let result = tool(AutonomyLevel::Supervised)
+            .execute(json!({"operation": "delete_pod", "name": "api-7f9"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("approved=true"));
+    }
+
+    #[tokio::test]
+    async fn mutations_are_blocked_in_read_only_mode() {
+        let result = tool(AutonomyLevel::ReadOnly)
+            .execute(json!({"operation": "scale", "name": "api", "replicas": 0, "approved": true}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn context_must_be_allowed() {
+        let result = tool(AutonomyLevel::Full)
+            .execute(json!({"operation": "get", "context": "prod"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error_message().unwrap().contains("allowed_contexts"));
+    }
+}
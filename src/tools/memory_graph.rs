@@ -0,0 +1,235 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::memory::{EntityNode, Memory, Relation};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Deepest traversal the agent may request.
+const MAX_DEPTH: u64 = 3;
+/// Entities described per call.
+const MAX_NODES: usize = 20;
+/// Memory entries quoted for the requested entity.
+const MAX_SNIPPETS: usize = 5;
+const SNIPPET_CHARS: usize = 200;
+
+/// Let the agent traverse the memory entity graph (`[memory] entity_graph`)
+pub struct MemoryGraphTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemoryGraphTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+
+    fn describe(node: &EntityNode, output: &mut String) {
+        let _ = writeln!(output, "{} ({})", node.name, node.kind.as_str());
+        let mut mentions = Vec::new();
+        for edge in &node.edges {
+            if edge.relation == Relation::MentionedIn {
+                mentions.push(edge.other.as_str());
+            } else {
+                let arrow = if edge.incoming { "<-" } else { "->" };
+                let _ = writeln!(
+                    output,
+                    "- {} {arrow} {} (memory: {})",
+                    edge.relation.as_str(),
+                    edge.other,
+                    edge.memory_key
+                );
+            }
+        }
+        if !mentions.is_empty() {
+            let _ = writeln!(output, "- mentioned_in: {}", mentions.join(", "));
+        }
+    }
+
+    /// Quote the first lines of the memories that mention `node`.
+    async fn quote_mentions(&self, node: &EntityNode, output: &mut String) {
+        let keys = node
+            .edges
+            .iter()
+            .filter(|e| e.relation == Relation::MentionedIn)
+            .map(|e| e.other.as_str())
+            .take(MAX_SNIPPETS);
+        for key in keys {
+            if let Ok(Some(entry)) = self.memory.get(key).await {
+                let snippet: String = entry.content.chars().take(SNIPPET_CHARS).collect();
+                let _ = writeln!(output, "  [{key}] {}", snippet.replace('\n', " "));
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryGraphTool {
+    fn name(&self) -> &str {
+        "memory_graph"
+    }
+
+    fn description(&self) -> &str {
+        "Look up a person, project or place in memory and follow its relations (works_on, mentioned_in). Returns the related entities and the memories that mention it."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "entity": {
+                    "type": "string",
+                    "description": "Name to look up (case-insensitive), e.g. \"Alice\""
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "How many relation hops to follow (1-3, default: 1)"
+                }
+            },
+            "required": ["entity"]
+        })
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let entity = args
+            .get("entity")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'entity' parameter"))?;
+        let depth = args
+            .get("depth")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1)
+            .clamp(1, MAX_DEPTH);
+
+        let mut output = String::new();
+        let mut seen = HashSet::from([entity.trim().to_lowercase()]);
+        let mut queue = VecDeque::from([(entity.trim().to_string(), 0)]);
+        let mut described = 0;
+        while let Some((name, hops)) = queue.pop_front() {
+            if described == MAX_NODES {
+                break;
+            }
+            let node = match self.memory.entity(&name).await {
+                Ok(Some(node)) => node,
+                Ok(None) => continue,
+                Err(e) => {
+                    return Ok(ToolResult::failure(ToolError::failed(format!(
+                        "Memory graph lookup failed: {e}"
+                    ))))
+                }
+            };
+            if described > 0 {
+                output.push('\n');
+            }
+            Self::describe(&node, &mut output);
+            if described == 0 {
+                self.quote_mentions(&node, &mut output).await;
+            }
+            described += 1;
+            if hops < depth {
+                for edge in node
+                    .edges
+                    .iter()
+                    .filter(|e| e.relation != Relation::MentionedIn)
+                {
+                    if seen.insert(edge.other.to_lowercase()) {
+                        queue.push_back((edge.other.clone(), hops + 1));
+                    }
+                }
+            }
+        }
+
+        if described == 0 {
+            return Ok(ToolResult {
+                success: true,
+                output: format!("No entity named \"{}\" in memory.", entity.trim()),
+                error: None,
+            });
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn graph_mem() -> (TempDir, Arc<dyn Memory>) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_entity_graph(true);
+        (tmp, Arc::new(mem))
+    }
+
+    #[tokio::test]
+    async fn graph_follows_relations_to_the_requested_depth() {
+        let (_tmp, mem) = graph_mem();
+        mem.store(
+            "team",
+            "Alice works on Zeroclaw. Bob works on Zeroclaw too",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "bob_home",
+            "Bob lives in Lisbon",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        let tool = MemoryGraphTool::new(mem);
+
+        let near = tool.execute(json!({"entity": "alice"})).await.unwrap();
+        assert!(near.success);
+        assert!(near.output.starts_with("Alice (person)\n"));
+        assert!(near
+            .output
+            .contains("- works_on -> Zeroclaw (memory: team)"));
+        assert!(near.output.contains("  [team] Alice works on Zeroclaw"));
+        assert!(near.output.contains("Zeroclaw (project)"));
+        assert!(!near.output.contains("Bob (person)"));
+
+        let far = tool
+            .execute(json!({"entity": "Alice", "depth": 2}))
+            .await
+            .unwrap();
+        assert!(far.output.contains("Bob (person)"));
+        assert!(far.output.contains("- mentioned_in: bob_home, team"));
+
+        let missing = tool.execute(json!({"entity": "Carol"})).await.unwrap();
+        assert_eq!(missing.output, "No entity named \"Carol\" in memory.");
+    }
+
+    #[tokio::test]
+    async fn graph_is_empty_when_extraction_is_off() {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        mem.store(
+            "team",
+            "Alice works on Zeroclaw",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        let result = MemoryGraphTool::new(mem)
+            .execute(json!({"entity": "Alice"}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("No entity named"));
+    }
+}
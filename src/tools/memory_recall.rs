@@ -1,4 +1,4 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{Tool, ToolError, ToolResult};
 use crate::memory::Memory;
 use async_trait::async_trait;
 use serde_json::json;
@@ -43,6 +43,10 @@ impl Tool for MemoryRecallTool {
         })
     }
 
+    fn is_read_only(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let query = args
             .get("query")
@@ -82,7 +86,7 @@ impl Tool for MemoryRecallTool {
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Memory recall failed: {e}")),
+                error: Some(ToolError::failed(format!("Memory recall failed: {e}"))),
             }),
         }
     }
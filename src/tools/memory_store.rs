@@ -1,4 +1,4 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{Tool, ToolError, ToolResult};
 use crate::memory::{Memory, MemoryCategory};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
@@ -74,7 +74,7 @@ impl Tool for MemoryStoreTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(error),
+                error: Some(ToolError::from_policy_violation(error)),
             });
         }
 
@@ -87,7 +87,7 @@ impl Tool for MemoryStoreTool {
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Failed to store memory: {e}")),
+                error: Some(ToolError::failed(format!("Failed to store memory: {e}"))),
             }),
         }
     }
@@ -194,8 +194,7 @@ mod tests {
             .unwrap();
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("read-only mode"));
         assert!(mem.get("lang").await.unwrap().is_none());
@@ -215,8 +214,7 @@ mod tests {
             .unwrap();
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Rate limit exceeded"));
         assert!(mem.get("lang").await.unwrap().is_none());
@@ -5,33 +5,75 @@
 //! which requires a name, description, JSON parameter schema, and an async
 //! `execute` method returning a structured [`ToolResult`].
 //!
-//! Tools are assembled into a registry by [`default_tools`] (shell, file
-//! read/write, memory store/recall). Security policy enforcement is injected via
+//! Tools are assembled into a registry by [`default_tools`] (shell, background
+//! processes, file read/write, code search, memory store/recall). Security
+//! policy enforcement is injected via
 //! [`SecurityPolicy`](crate::security::SecurityPolicy) at construction time.
+//! [`all_tools`] additionally adds the read-only `[assistant]` tools
+//! (`email_search`, `calendar_list`) whose scopes have been approved and the
+//! opt-in `[tools.desktop]` tools (`clipboard_read`, `clipboard_write`,
+//! `notify`), `db_query` for `[[tools.databases]]` and `k8s` for
+//! `[tools.kubernetes]`, `memory_graph` for `[memory] entity_graph`, gives `file_write` automatic backups plus `file_undo`
+//! (`[tools.file_backups]`), wraps side-effecting tools in the [`journal`] when
+//! `[agent] journal_actions` is on, and moves oversized outputs into the
+//! [`artifact`] store read back by `artifact_read` (`[tools.artifacts]`).
 //!
 //! # Extension
 //!
 //! To add a new tool, implement [`Tool`] in a new submodule and register it in
 //! [`default_tools_with_runtime`]. See `AGENTS.md` §7.3 for the full change playbook.
 
+pub mod artifact;
+pub mod calendar;
+pub mod code_search;
+pub mod db_query;
+pub mod desktop;
+#[cfg(feature = "email-tool")]
+pub mod email;
 pub mod file_read;
+pub mod file_undo;
 pub mod file_write;
+#[cfg(feature = "git-tool")]
+pub mod git;
+pub mod journal;
+#[cfg(feature = "k8s-tool")]
+pub mod k8s;
+pub mod memory_graph;
 pub mod memory_recall;
 pub mod memory_store;
+pub mod process;
+pub mod reminder;
 pub mod schema;
 pub mod shell;
 pub mod traits;
 
+#[allow(unused_imports)]
+pub use artifact::ArtifactReadTool;
+pub use calendar::CalendarListTool;
+pub use code_search::CodeSearchTool;
+pub use db_query::DbQueryTool;
+#[allow(unused_imports)]
+pub use desktop::{ClipboardReadTool, ClipboardWriteTool, NotifyTool};
+#[cfg(feature = "email-tool")]
+pub use email::EmailSearchTool;
 pub use file_read::FileReadTool;
+pub use file_undo::FileUndoTool;
 pub use file_write::FileWriteTool;
+#[cfg(feature = "git-tool")]
+pub use git::GitTool;
+#[cfg(feature = "k8s-tool")]
+pub use k8s::K8sTool;
+pub use memory_graph::MemoryGraphTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
+pub use process::ProcessTool;
+pub use reminder::ReminderTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use shell::ShellTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{RetryHint, ToolError, ToolErrorKind, ToolResult, ToolSpec};
 
 use crate::config::Config;
 use crate::memory::Memory;
@@ -39,7 +81,8 @@ use crate::runtime::{NativeRuntime, RuntimeAdapter};
 use crate::security::SecurityPolicy;
 use std::sync::Arc;
 
-/// Create the default tool registry (5 essential tools).
+/// Create the default tool registry (7 essential tools, plus `git` with the
+/// `git-tool` feature).
 pub fn default_tools(security: Arc<SecurityPolicy>, memory: Arc<dyn Memory>) -> Vec<Box<dyn Tool>> {
     default_tools_with_runtime(security, Arc::new(NativeRuntime::new()), memory)
 }
@@ -50,13 +93,19 @@ pub fn default_tools_with_runtime(
     runtime: Arc<dyn RuntimeAdapter>,
     memory: Arc<dyn Memory>,
 ) -> Vec<Box<dyn Tool>> {
-    vec![
-        Box::new(ShellTool::new(security.clone(), runtime)),
+    #[allow(unused_mut)]
+    let mut tools: Vec<Box<dyn Tool>> = vec![
+        Box::new(ShellTool::new(security.clone(), runtime.clone())),
+        Box::new(ProcessTool::new(security.clone(), runtime)),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
+        Box::new(CodeSearchTool::new(security.clone())),
         Box::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Box::new(MemoryRecallTool::new(memory)),
-    ]
+    ];
+    #[cfg(feature = "git-tool")]
+    tools.push(Box::new(GitTool::new(security)));
+    tools
 }
 
 /// Create full tool registry — delegates to [`default_tools`].
@@ -64,11 +113,25 @@ pub fn default_tools_with_runtime(
 /// Simplified after the minimal-binary strip removed browser, HTTP, delegate,
 /// and composio config types.
 pub fn all_tools(
-    _config: Arc<Config>,
+    config: Arc<Config>,
     security: &Arc<SecurityPolicy>,
     memory: Arc<dyn Memory>,
 ) -> Vec<Box<dyn Tool>> {
-    default_tools(security.clone(), memory)
+    let mut tools = default_tools(security.clone(), memory.clone());
+    if config.memory.entity_graph {
+        tools.push(Box::new(MemoryGraphTool::new(memory)));
+    }
+    tools.extend(assistant_tools(&config, security));
+    tools.extend(desktop::desktop_tools(&config, security));
+    if !config.tools.databases.is_empty() {
+        tools.push(Box::new(DbQueryTool::new(
+            config.tools.databases.clone(),
+            security.clone(),
+        )));
+    }
+    tools.extend(kubernetes_tools(&config, security));
+    let tools = with_file_backups(&config, security, tools);
+    with_artifacts(&config, with_journal(&config, security, tools))
 }
 
 /// Create full tool registry with explicit runtime — delegates to
@@ -76,12 +139,132 @@ pub fn all_tools(
 ///
 /// Simplified after the minimal-binary strip.
 pub fn all_tools_with_runtime(
-    _config: Arc<Config>,
+    config: Arc<Config>,
     security: &Arc<SecurityPolicy>,
     runtime: Arc<dyn RuntimeAdapter>,
     memory: Arc<dyn Memory>,
 ) -> Vec<Box<dyn Tool>> {
-    default_tools_with_runtime(security.clone(), runtime, memory)
+    let mut tools = default_tools_with_runtime(security.clone(), runtime, memory.clone());
+    if config.memory.entity_graph {
+        tools.push(Box::new(MemoryGraphTool::new(memory)));
+    }
+    tools.extend(assistant_tools(&config, security));
+    tools.extend(desktop::desktop_tools(&config, security));
+    if !config.tools.databases.is_empty() {
+        tools.push(Box::new(DbQueryTool::new(
+            config.tools.databases.clone(),
+            security.clone(),
+        )));
+    }
+    tools.extend(kubernetes_tools(&config, security));
+    let tools = with_file_backups(&config, security, tools);
+    with_artifacts(&config, with_journal(&config, security, tools))
+}
+
+/// `email_search` and `calendar_list` for the `[assistant]` accounts with at
+/// least one approved scope.
+pub fn assistant_tools(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    let time = crate::util::time::TimeService::from_config(config);
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    if let Some(email) = config.assistant.email.as_ref() {
+        if email.scopes.is_empty() {
+            tracing::info!("assistant.email has no approved scopes; email_search is disabled");
+        } else {
+            #[cfg(feature = "email-tool")]
+            tools.push(Box::new(EmailSearchTool::new(
+                email.clone(),
+                security.clone(),
+                time,
+            )));
+            #[cfg(not(feature = "email-tool"))]
+            tracing::warn!(
+                "assistant.email is configured, but this build lacks IMAP support \
+                 (rebuild with `--features email-tool`)"
+            );
+        }
+    }
+    if let Some(calendar) = config.assistant.calendar.as_ref() {
+        if calendar.scopes.is_empty() {
+            tracing::info!("assistant.calendar has no approved scopes; calendar_list is disabled");
+        } else {
+            tools.push(Box::new(CalendarListTool::new(
+                calendar.clone(),
+                security.clone(),
+                time,
+            )));
+        }
+    }
+    tools
+}
+
+/// The `k8s` tool when `[tools.kubernetes]` is enabled.
+pub fn kubernetes_tools(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    if !config.tools.kubernetes.enabled {
+        return Vec::new();
+    }
+    #[cfg(feature = "k8s-tool")]
+    {
+        let tool: Box<dyn Tool> = Box::new(K8sTool::new(
+            config.tools.kubernetes.clone(),
+            security.clone(),
+        ));
+        vec![tool]
+    }
+    #[cfg(not(feature = "k8s-tool"))]
+    {
+        let _ = security;
+        tracing::warn!(
+            "[tools.kubernetes] is enabled, but this build lacks Kubernetes support \
+             (rebuild with `--features k8s-tool`)"
+        );
+        Vec::new()
+    }
+}
+
+/// Give `file_write` automatic backups and add `file_undo` when
+/// `[tools.file_backups]` is enabled.
+fn with_file_backups(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    mut tools: Vec<Box<dyn Tool>>,
+) -> Vec<Box<dyn Tool>> {
+    if !config.tools.file_backups.enabled {
+        return tools;
+    }
+    let backups = Arc::new(file_undo::FileBackups::new(
+        &config.tools.file_backups,
+        security.clone(),
+    ));
+    for tool in &mut tools {
+        if tool.name() == "file_write" {
+            *tool = Box::new(FileWriteTool::new(security.clone()).with_backups(backups.clone()));
+        }
+    }
+    tools.push(Box::new(FileUndoTool::new(backups, security.clone())));
+    tools
+}
+
+fn with_journal(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    tools: Vec<Box<dyn Tool>>,
+) -> Vec<Box<dyn Tool>> {
+    if !config.agent.journal_actions {
+        return tools;
+    }
+    let journal = Arc::new(journal::ActionJournal::new(security.clone()));
+    journal::journal_tools(tools, &journal)
+}
+
+fn with_artifacts(config: &Config, tools: Vec<Box<dyn Tool>>) -> Vec<Box<dyn Tool>> {
+    if !config.tools.artifacts.enabled {
+        return tools;
+    }
+    let store = Arc::new(artifact::ArtifactStore::new(
+        &config.workspace_dir,
+        config.tools.artifacts.clone(),
+    ));
+    artifact::artifact_tools(tools, &store)
 }
 
 #[cfg(test)]
@@ -101,7 +284,7 @@ mod tests {
         let mem: Arc<dyn Memory> =
             Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
         let tools = default_tools(security, mem);
-        assert_eq!(tools.len(), 5);
+        assert_eq!(tools.len(), if cfg!(feature = "git-tool") { 8 } else { 7 });
     }
 
     #[test]
@@ -119,6 +302,8 @@ mod tests {
         assert!(names.contains(&"shell"));
         assert!(names.contains(&"file_read"));
         assert!(names.contains(&"file_write"));
+        assert!(names.contains(&"code_search"));
+        assert!(names.contains(&"process"));
         assert!(names.contains(&"memory_store"));
         assert!(names.contains(&"memory_recall"));
     }
@@ -188,6 +373,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn assistant_tools_need_approved_scopes() {
+        let security = Arc::new(SecurityPolicy::default());
+        let mut config = Config::default();
+        config.assistant.calendar = Some(crate::config::CalendarConfig {
+            calendars: vec!["https://caldav.example.com/me/default/".into()],
+            username: "me".into(),
+            password: None,
+            scopes: Vec::new(),
+            max_results: 50,
+        });
+        assert!(assistant_tools(&config, &security).is_empty());
+
+        if let Some(calendar) = config.assistant.calendar.as_mut() {
+            calendar.scopes = vec![crate::config::CalendarScope::Events];
+        }
+        let tools = assistant_tools(&config, &security);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["calendar_list"]);
+    }
+
     #[test]
     fn tool_result_serde() {
         let result = ToolResult {
@@ -207,12 +413,13 @@ mod tests {
         let result = ToolResult {
             success: false,
             output: String::new(),
-            error: Some("boom".into()),
+            error: Some(ToolError::failed("boom")),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
         assert!(!parsed.success);
-        assert_eq!(parsed.error.as_deref(), Some("boom"));
+        assert_eq!(parsed.error_message(), Some("boom"));
+        assert_eq!(parsed.error.unwrap().kind, ToolErrorKind::ExecutionFailed);
     }
 
     #[test]
@@ -228,4 +435,3 @@ mod tests {
         assert_eq!(parsed.description, "A test tool");
     }
 }
-
@@ -0,0 +1,634 @@
+use super::shell::apply_sandboxed_env;
+use super::traits::{Tool, ToolError, ToolResult};
+#[cfg(unix)]
+use crate::runtime::exec::signal_process_group;
+use crate::runtime::RuntimeAdapter;
+use crate::security::cwd::current_session;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+
+/// Maximum number of background processes running at once per session.
+const MAX_RUNNING_PROCESSES: usize = 4;
+/// Finished processes kept per session (for their logs) before the oldest is
+/// dropped.
+const MAX_FINISHED_PROCESSES: usize = 8;
+/// Output lines retained per process; older lines are discarded.
+const MAX_LOG_LINES: usize = 2000;
+const DEFAULT_LOG_LINES: usize = 50;
+const MAX_LOG_LINES_PER_CALL: usize = 500;
+/// Time a stopped process gets to exit after SIGTERM before it is killed.
+const STOP_GRACE_SECS: u64 = 5;
+
+/// Ring buffer of combined stdout/stderr lines.
+#[derive(Default)]
+struct LogBuffer {
+    lines: VecDeque<(u64, String)>,
+    next_seq: u64,
+    /// Sequence number of the first line not yet returned by `logs`.
+    read_seq: u64,
+}
+
+impl LogBuffer {
+    fn push(&mut self, line: String) {
+        if self.lines.len() == MAX_LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back((self.next_seq, line));
+        self.next_seq += 1;
+    }
+
+    /// Last `limit` lines, or only lines not returned before when `new_only`.
+    fn read(&mut self, limit: usize, new_only: bool) -> Vec<String> {
+        let from = if new_only { self.read_seq } else { 0 };
+        let selected: Vec<&(u64, String)> =
+            self.lines.iter().filter(|(seq, _)| *seq >= from).collect();
+        let skip = selected.len().saturating_sub(limit);
+        let out = selected
+            .into_iter()
+            .skip(skip)
+            .map(|(_, line)| line.clone())
+            .collect();
+        self.read_seq = self.next_seq;
+        out
+    }
+}
+
+struct ManagedProcess {
+    /// Session that started the process; only it may see or stop it.
+    session: String,
+    name: String,
+    command: String,
+    pid: Option<u32>,
+    started: Instant,
+    child: Child,
+    exit: Option<String>,
+    logs: Arc<Mutex<LogBuffer>>,
+}
+
+impl ManagedProcess {
+    /// Refresh and return whether the process is still running.
+    fn poll(&mut self) -> bool {
+        if self.exit.is_none() {
+            match self.child.try_wait() {
+                Ok(Some(status)) => self.exit = Some(status.to_string()),
+                Ok(None) => {}
+                Err(e) => self.exit = Some(format!("unknown ({e})")),
+            }
+        }
+        self.exit.is_none()
+    }
+
+    /// Kill the process group without waiting for it to exit.
+    fn kill(&mut self) {
+        if self.poll() {
+            #[cfg(unix)]
+            signal_process_group(self.pid, libc::SIGKILL);
+            let _ = self.child.start_kill();
+        }
+    }
+}
+
+/// Start, inspect and stop long-running background commands.
+///
+/// Commands go through the same policy checks and environment sandboxing as
+/// the `shell` tool. One instance serves every sender of a channel, so each
+/// process belongs to the session that started it (its
+/// [`with_session`](crate::security::cwd::with_session) key): other sessions
+/// cannot list, read or stop it. Processes are killed when their session ends
+/// or the tool is dropped.
+pub struct ProcessTool {
+    security: Arc<SecurityPolicy>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    processes: Mutex<BTreeMap<u32, ManagedProcess>>,
+    next_id: Mutex<u32>,
+}
+
+impl ProcessTool {
+    pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
+        Self {
+            security,
+            runtime,
+            processes: Mutex::new(BTreeMap::new()),
+            next_id: Mutex::new(1),
+        }
+    }
+
+    async fn start(&self, args: &serde_json::Value) -> anyhow::Result<ToolResult> {
+        let command = args
+            .get("command")
+            .and_then(|v| v.as_str())
+            .filter(|c| !c.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
+        let approved = args
+            .get("approved")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: too many actions in the last hour",
+            )));
+        }
+        if let Err(reason) = self.security.validate_command_execution(command, approved) {
+            return Ok(ToolResult::failure(ToolError::from_policy_violation(
+                reason,
+            )));
+        }
+        if let Some(path) = self.security.forbidden_path_argument(command) {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                "Path blocked by security policy: {path}"
+            ))));
+        }
+        let session = current_session();
+        let running = self.running_count(&session);
+        if running >= MAX_RUNNING_PROCESSES {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                "Too many background processes ({running}/{MAX_RUNNING_PROCESSES}); stop one first"
+            ))));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(ToolError::transient(
+                "Rate limit exceeded: action budget exhausted",
+            )));
+        }
+
+        let mut cmd = match self
+            .runtime
+            .build_shell_command(command, &self.security.current_dir())
+        {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                return Ok(ToolResult::failure(ToolError::failed(format!(
+                    "Failed to build runtime command: {e}"
+                ))))
+            }
+        };
+        apply_sandboxed_env(&mut cmd, &self.security).await;
+        cmd.stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        cmd.process_group(0);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ToolResult::failure(ToolError::io(
+                    format!("Failed to start process: {e}"),
+                    &e,
+                )))
+            }
+        };
+        let logs = Arc::new(Mutex::new(LogBuffer::default()));
+        if let Some(stdout) = child.stdout.take() {
+            spawn_log_reader(stdout, "", logs.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            spawn_log_reader(stderr, "[stderr] ", logs.clone());
+        }
+
+        let id = {
+            let mut next = self.next_id.lock();
+            let id = *next;
+            *next += 1;
+            id
+        };
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(command)
+            .to_string();
+        let pid = child.id();
+        let mut processes = self.processes.lock();
+        processes.insert(
+            id,
+            ManagedProcess {
+                session: session.clone(),
+                name: name.clone(),
+                command: command.to_string(),
+                pid,
+                started: Instant::now(),
+                child,
+                exit: None,
+                logs,
+            },
+        );
+        prune_finished(&mut processes, &session);
+
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Started process {id} ({name}), pid {}. Use action \"logs\" with id {id} to read its output.",
+                pid.map_or_else(|| "unknown".to_string(), |p| p.to_string())
+            ),
+            error: None,
+        })
+    }
+
+    fn running_count(&self, session: &str) -> usize {
+        self.processes
+            .lock()
+            .values_mut()
+            .filter(|p| p.session == session)
+            .map(ManagedProcess::poll)
+            .filter(|running| *running)
+            .count()
+    }
+
+    fn list(&self) -> ToolResult {
+        let session = current_session();
+        let mut processes = self.processes.lock();
+        let mut own = processes
+            .iter_mut()
+            .filter(|(_, p)| p.session == session)
+            .peekable();
+        if own.peek().is_none() {
+            return ToolResult {
+                success: true,
+                output: "No background processes".into(),
+                error: None,
+            };
+        }
+        let mut output = String::new();
+        for (id, process) in own {
+            let state = if process.poll() {
+                "running".to_string()
+            } else {
+                format!("exited: {}", process.exit.as_deref().unwrap_or_default())
+            };
+            let _ = writeln!(
+                output,
+                "[{id}] {} | {state} | pid {} | up {}s | {}",
+                process.name,
+                process
+                    .pid
+                    .map_or_else(|| "-".to_string(), |p| p.to_string()),
+                process.started.elapsed().as_secs(),
+                process.command
+            );
+        }
+        ToolResult {
+            success: true,
+            output: output.trim_end().to_string(),
+            error: None,
+        }
+    }
+
+    fn logs(&self, id: u32, args: &serde_json::Value) -> ToolResult {
+        let limit = args
+            .get("lines")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_LOG_LINES, |n| {
+                usize::try_from(n).unwrap_or(MAX_LOG_LINES_PER_CALL)
+            })
+            .clamp(1, MAX_LOG_LINES_PER_CALL);
+        let new_only = args
+            .get("new_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let session = current_session();
+        let mut processes = self.processes.lock();
+        let Some(process) = processes.get_mut(&id).filter(|p| p.session == session) else {
+            return ToolResult::failure(ToolError::not_found(format!(
+                "No background process with id {id}"
+            )));
+        };
+        let running = process.poll();
+        let lines = process.logs.lock().read(limit, new_only);
+        let state = if running {
+            "running".to_string()
+        } else {
+            format!("exited: {}", process.exit.as_deref().unwrap_or_default())
+        };
+        let body = if lines.is_empty() {
+            "(no new output)".to_string()
+        } else {
+            lines.join("\n")
+        };
+        ToolResult {
+            success: true,
+            output: format!("[{id}] {} ({state})\n{body}", process.name),
+            error: None,
+        }
+    }
+
+    async fn stop(&self, id: u32) -> ToolResult {
+        let session = current_session();
+        let owned = {
+            let mut processes = self.processes.lock();
+            match processes.get(&id) {
+                Some(p) if p.session == session => processes.remove(&id),
+                _ => None,
+            }
+        };
+        let Some(mut process) = owned else {
+            return ToolResult::failure(ToolError::not_found(format!(
+                "No background process with id {id}"
+            )));
+        };
+        if process.poll() {
+            #[cfg(unix)]
+            signal_process_group(process.pid, libc::SIGTERM);
+            #[cfg(not(unix))]
+            let _ = process.child.start_kill();
+
+            let graceful = Duration::from_secs(STOP_GRACE_SECS);
+            if tokio::time::timeout(graceful, process.child.wait())
+                .await
+                .is_err()
+            {
+                #[cfg(unix)]
+                signal_process_group(process.pid, libc::SIGKILL);
+                let _ = process.child.kill().await;
+            }
+            process.poll();
+        }
+        ToolResult {
+            success: true,
+            output: format!(
+                "Stopped process {id} ({}): {}",
+                process.name,
+                process.exit.as_deref().unwrap_or("killed")
+            ),
+            error: None,
+        }
+    }
+}
+
+impl Drop for ProcessTool {
+    fn drop(&mut self) {
+        for (_, mut process) in std::mem::take(&mut *self.processes.lock()) {
+            process.kill();
+        }
+    }
+}
+
+/// Drop `session`'s oldest finished processes beyond
+/// [`MAX_FINISHED_PROCESSES`].
+fn prune_finished(processes: &mut BTreeMap<u32, ManagedProcess>, session: &str) {
+    let finished: Vec<u32> = processes
+        .iter_mut()
+        .filter(|(_, p)| p.session == session)
+        .filter_map(|(id, p)| (!p.poll()).then_some(*id))
+        .collect();
+    for id in finished
+        .iter()
+        .take(finished.len().saturating_sub(MAX_FINISHED_PROCESSES))
+    {
+        processes.remove(id);
+    }
+}
+
+fn spawn_log_reader<R>(reader: R, prefix: &'static str, logs: Arc<Mutex<LogBuffer>>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            logs.lock().push(format!("{prefix}{line}"));
+        }
+    });
+}
+
+#[async_trait]
+impl Tool for ProcessTool {
+    fn name(&self) -> &str {
+        "process"
+    }
+
+    fn description(&self) -> &str {
+        "Manage long-running background commands (dev servers, watchers): start, list, read logs, and stop. Processes are stopped automatically when the session ends."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["start", "list", "logs", "stop"],
+                    "description": "What to do"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "start: shell command to run in the background"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "start: short label for the process (default: the command)"
+                },
+                "id": {
+                    "type": "integer",
+                    "description": "logs/stop: process id returned by start"
+                },
+                "lines": {
+                    "type": "integer",
+                    "description": "logs: number of most recent lines to return (default: 50, max: 500)"
+                },
+                "new_only": {
+                    "type": "boolean",
+                    "description": "logs: only return output produced since the previous logs call"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let id = || {
+            args.get("id")
+                .and_then(|v| v.as_u64())
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))
+        };
+
+        match action {
+            "start" => self.start(&args).await,
+            "list" => Ok(self.list()),
+            "logs" => Ok(self.logs(id()?, &args)),
+            "stop" => Ok(self.stop(id()?).await),
+            other => Ok(ToolResult::failure(ToolError::failed(format!(
+                "Unknown process action: {other}"
+            )))),
+        }
+    }
+
+    fn end_session(&self, session: &str) {
+        let mut processes = self.processes.lock();
+        processes.retain(|_, process| {
+            if process.session != session {
+                return true;
+            }
+            process.kill();
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::AutonomyLevel;
+    use crate::tools::ToolErrorKind;
+
+    fn test_tool(allowed: &[&str]) -> ProcessTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: allowed.iter().map(|c| (*c).to_string()).collect(),
+            ..SecurityPolicy::default()
+        });
+        ProcessTool::new(security, Arc::new(NativeRuntime::new()))
+    }
+
+    #[test]
+    fn log_buffer_keeps_tail_and_tracks_new_lines() {
+        let mut logs = LogBuffer::default();
+        for i in 0..(MAX_LOG_LINES + 5) {
+            logs.push(format!("line {i}"));
+        }
+        assert_eq!(logs.lines.len(), MAX_LOG_LINES);
+        let tail = logs.read(2, false);
+        assert_eq!(
+            tail,
+            vec![
+                format!("line {}", MAX_LOG_LINES + 3),
+                format!("line {}", MAX_LOG_LINES + 4)
+            ]
+        );
+        assert!(logs.read(10, true).is_empty());
+        logs.push("fresh".into());
+        assert_eq!(logs.read(10, true), vec!["fresh"]);
+    }
+
+    #[tokio::test]
+    async fn process_start_logs_list_and_stop() {
+        let tool = test_tool(&["echo", "sleep"]);
+        let started = tool
+            .execute(
+                json!({"action": "start", "command": "echo ready && sleep 30", "name": "server"}),
+            )
+            .await
+            .unwrap();
+        assert!(started.success, "{:?}", started.error);
+        assert!(started.output.starts_with("Started process 1 (server)"));
+
+        let mut output = String::new();
+        for _ in 0..50 {
+            output = tool
+                .execute(json!({"action": "logs", "id": 1}))
+                .await
+                .unwrap()
+                .output;
+            if output.contains("ready") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(output.contains("ready"), "{output}");
+
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(listed.output.contains("[1] server | running"));
+
+        let stopped = tool
+            .execute(json!({"action": "stop", "id": 1}))
+            .await
+            .unwrap();
+        assert!(stopped.output.starts_with("Stopped process 1"));
+        let listed = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert_eq!(listed.output, "No background processes");
+    }
+
+    #[tokio::test]
+    async fn process_enforces_policy_and_concurrency_cap() {
+        let tool = test_tool(&["sleep"]);
+        let denied = tool
+            .execute(json!({"action": "start", "command": "rm -rf /"}))
+            .await
+            .unwrap();
+        assert!(!denied.success);
+
+        for _ in 0..MAX_RUNNING_PROCESSES {
+            let started = tool
+                .execute(json!({"action": "start", "command": "sleep 30"}))
+                .await
+                .unwrap();
+            assert!(started.success, "{:?}", started.error);
+        }
+        let capped = tool
+            .execute(json!({"action": "start", "command": "sleep 30"}))
+            .await
+            .unwrap();
+        assert!(!capped.success);
+        assert!(capped
+            .error_message()
+            .unwrap()
+            .contains("Too many background processes"));
+
+        let missing = tool
+            .execute(json!({"action": "logs", "id": 99}))
+            .await
+            .unwrap();
+        assert_eq!(missing.error.unwrap().kind, ToolErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn processes_are_private_to_their_session() {
+        use crate::security::cwd::with_session;
+
+        let tool = test_tool(&["sleep"]);
+        let started = with_session(
+            "alice".into(),
+            tool.execute(json!({"action": "start", "command": "sleep 30"})),
+        )
+        .await
+        .unwrap();
+        assert!(started.success, "{:?}", started.error);
+
+        for action in [
+            json!({"action": "logs", "id": 1}),
+            json!({"action": "stop", "id": 1}),
+        ] {
+            let denied = with_session("bob".into(), tool.execute(action))
+                .await
+                .unwrap();
+            assert_eq!(denied.error.unwrap().kind, ToolErrorKind::NotFound);
+        }
+        let listed = with_session("bob".into(), tool.execute(json!({"action": "list"})))
+            .await
+            .unwrap();
+        assert_eq!(listed.output, "No background processes");
+        let listed = with_session("alice".into(), tool.execute(json!({"action": "list"})))
+            .await
+            .unwrap();
+        assert!(listed.output.contains("[1] sleep 30 | running"));
+
+        tool.end_session("bob");
+        assert_eq!(tool.processes.lock().len(), 1);
+        tool.end_session("alice");
+        assert!(tool.processes.lock().is_empty());
+    }
+}
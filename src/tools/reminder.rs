@@ -0,0 +1,253 @@
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::scheduler::reminders::{Reminder, ReminderStore, ReminderTarget};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use crate::util::time::TimeService;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    /// The chat the current channel turn came from; reminders set during the
+    /// turn are delivered back to it.
+    static REMINDER_ORIGIN: ReminderTarget;
+}
+
+/// Run `future` (a channel turn) with `origin` as the delivery target for
+/// reminders it sets.
+pub async fn with_origin<F: Future>(origin: ReminderTarget, future: F) -> F::Output {
+    REMINDER_ORIGIN.scope(origin, future).await
+}
+
+fn current_origin() -> Option<ReminderTarget> {
+    REMINDER_ORIGIN.try_with(Clone::clone).ok()
+}
+
+fn describe(reminder: &Reminder, time: TimeService) -> String {
+    format!(
+        "{} at {}: {}",
+        reminder.id,
+        time.localize(reminder.due_at).format("%Y-%m-%d %H:%M %:z"),
+        reminder.message
+    )
+}
+
+/// Let the agent schedule one-shot reminders for the user it is talking to
+pub struct ReminderTool {
+    store: Arc<ReminderStore>,
+    security: Arc<SecurityPolicy>,
+    time: TimeService,
+}
+
+impl ReminderTool {
+    pub fn new(
+        store: Arc<ReminderStore>,
+        security: Arc<SecurityPolicy>,
+        time: TimeService,
+    ) -> Self {
+        Self {
+            store,
+            security,
+            time,
+        }
+    }
+
+    fn set(&self, args: &serde_json::Value, origin: ReminderTarget) -> anyhow::Result<String> {
+        let message = args
+            .get("message")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'message' parameter"))?;
+        let now = Utc::now();
+        let due_at = match (
+            args.get("at").and_then(|v| v.as_str()),
+            args.get("in_minutes").and_then(serde_json::Value::as_u64),
+        ) {
+            (Some(at), _) => self.time.parse_at(at, now)?,
+            (None, Some(minutes)) => i64::try_from(minutes)
+                .ok()
+                .and_then(chrono::Duration::try_minutes)
+                .and_then(|delay| now.checked_add_signed(delay))
+                .ok_or_else(|| anyhow::anyhow!("'in_minutes' is too large"))?,
+            (None, None) => anyhow::bail!("Provide 'at' or 'in_minutes'"),
+        };
+        if due_at <= now {
+            anyhow::bail!("Reminder time is in the past");
+        }
+        let reminder = self.store.add(message, due_at, origin)?;
+        Ok(format!("Reminder set: {}", describe(&reminder, self.time)))
+    }
+
+    fn list(&self, origin: &ReminderTarget) -> anyhow::Result<String> {
+        let reminders = self.store.list_for(origin)?;
+        if reminders.is_empty() {
+            return Ok("No pending reminders.".to_string());
+        }
+        let mut output = format!("{} pending reminder(s):\n", reminders.len());
+        for reminder in &reminders {
+            let _ = writeln!(output, "- {}", describe(reminder, self.time));
+        }
+        Ok(output)
+    }
+
+    fn cancel(&self, args: &serde_json::Value, origin: &ReminderTarget) -> anyhow::Result<String> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))?;
+        // Only reminders set from this chat can be cancelled from it.
+        let owned = self
+            .store
+            .list_for(origin)?
+            .into_iter()
+            .any(|r| r.id.starts_with(id.trim()));
+        if !owned {
+            anyhow::bail!("No pending reminder '{id}' in this conversation");
+        }
+        match self.store.cancel(id)? {
+            Some(reminder) => Ok(format!("Cancelled reminder {}", reminder.id)),
+            None => anyhow::bail!("No pending reminder '{id}' in this conversation"),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ReminderTool {
+    fn name(&self) -> &str {
+        "reminder"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a one-shot reminder that is sent back to this conversation at the given time, list pending reminders, or cancel one. Use action 'set' with 'message' and either 'at' (e.g. '5pm', 'tomorrow 9am', 'next monday 09:00', 'in 20 minutes', or RFC 3339) or 'in_minutes'."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["set", "list", "cancel"],
+                    "description": "What to do (default: set)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Reminder text sent to the user (for 'set')"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "When to remind, in the user's timezone: '5pm', 'tomorrow 9am', 'friday 14:00', 'in 2 hours', 'YYYY-MM-DD HH:MM', or RFC 3339"
+                },
+                "in_minutes": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Alternative to 'at': minutes from now"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Reminder id to cancel (for 'cancel')"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(origin) = current_origin() else {
+            return Ok(ToolResult::failure(ToolError::failed(
+                "Reminders can only be managed from a channel conversation",
+            )));
+        };
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("set");
+        if action != "list" {
+            if let Err(error) = self
+                .security
+                .enforce_tool_operation(ToolOperation::Act, "reminder")
+            {
+                return Ok(ToolResult::failure(ToolError::from_policy_violation(error)));
+            }
+        }
+
+        let result = match action {
+            "set" => self.set(&args, origin),
+            "list" => self.list(&origin),
+            "cancel" => self.cancel(&args, &origin),
+            other => Err(anyhow::anyhow!("Unknown action '{other}'")),
+        };
+        Ok(match result {
+            Ok(output) => ToolResult {
+                success: true,
+                output,
+                error: None,
+            },
+            Err(e) => ToolResult::failure(ToolError::failed(e.to_string())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::reminders::reminders_db_path;
+
+    fn tool(tmp: &tempfile::TempDir) -> ReminderTool {
+        let store = ReminderStore::open(&reminders_db_path(tmp.path())).unwrap();
+        ReminderTool::new(
+            Arc::new(store),
+            Arc::new(SecurityPolicy::default()),
+            TimeService::new(Some("UTC")).unwrap(),
+        )
+    }
+
+    fn origin(recipient: &str) -> ReminderTarget {
+        ReminderTarget {
+            channel: "whatsapp".into(),
+            recipient: recipient.into(),
+            thread_ts: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reminders_are_scoped_to_the_originating_chat() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tool = tool(&tmp);
+
+        let outside = tool
+            .execute(json!({"message": "x", "in_minutes": 5}))
+            .await
+            .unwrap();
+        assert!(!outside.success);
+
+        let set = with_origin(
+            origin("+1555"),
+            tool.execute(json!({"message": "call Sam", "in_minutes": 30})),
+        )
+        .await
+        .unwrap();
+        assert!(set.success, "{:?}", set.error_message());
+        let id = tool.store.list(false).unwrap()[0].id.clone();
+        assert_eq!(tool.store.list(false).unwrap()[0].target, origin("+1555"));
+
+        let other_chat = with_origin(
+            origin("+1666"),
+            tool.execute(json!({"action": "cancel", "id": id})),
+        )
+        .await
+        .unwrap();
+        assert!(!other_chat.success);
+
+        let listed = with_origin(origin("+1555"), tool.execute(json!({"action": "list"})))
+            .await
+            .unwrap();
+        assert!(listed.output.contains("call Sam"));
+        let cancelled = with_origin(
+            origin("+1555"),
+            tool.execute(json!({"action": "cancel", "id": id})),
+        )
+        .await
+        .unwrap();
+        assert!(cancelled.success);
+        assert!(tool.store.list(false).unwrap().is_empty());
+    }
+}
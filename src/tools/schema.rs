@@ -12,6 +12,8 @@
 //! 4. Strips nullable variants from unions and `type` arrays
 //! 5. Converts `const` to single-value `enum`
 //! 6. Detects circular references and stops recursion safely
+//! 7. In [`CleaningStrategy::Strict`] mode, collapses remaining unions and
+//!    repairs `type` / `properties` / `required` for minimal JSON Schema parsers
 //!
 //! # Example
 //!
@@ -99,18 +101,41 @@ pub enum CleaningStrategy {
     OpenAI,
     /// Conservative: Remove only universally unsupported keywords
     Conservative,
+    /// Strict: Gemini keyword removal plus structural normalization for
+    /// servers with minimal JSON Schema support (local OpenAI-compatible
+    /// runtimes): no `anyOf`/`oneOf`/`allOf`, a single `type` everywhere,
+    /// `properties` on every object and `required` limited to known properties.
+    Strict,
 }
 
 impl CleaningStrategy {
     /// Get the list of unsupported keywords for this strategy.
     pub fn unsupported_keywords(self) -> &'static [&'static str] {
         match self {
-            Self::Gemini => GEMINI_UNSUPPORTED_KEYWORDS,
+            Self::Gemini | Self::Strict => GEMINI_UNSUPPORTED_KEYWORDS,
             Self::Anthropic => &["$ref", "$defs", "definitions"], // Anthropic doesn't resolve refs
             Self::OpenAI => &[],                                  // OpenAI is most permissive
             Self::Conservative => &["$ref", "$defs", "definitions", "additionalProperties"],
         }
     }
+
+    /// Pick the strategy for a provider name (`default_provider` style,
+    /// including `custom:<url>`).
+    pub fn for_provider(provider: &str) -> Self {
+        let provider = provider.trim().to_ascii_lowercase();
+        if provider.starts_with("custom:") {
+            return Self::Strict;
+        }
+        match provider.as_str() {
+            "gemini" | "google" | "google-gemini" | "vertex" | "vertex-ai" => Self::Gemini,
+            "anthropic" | "claude" | "bedrock" => Self::Anthropic,
+            "openai" | "azure-openai" | "azure" | "openrouter" => Self::OpenAI,
+            "ollama" | "llamacpp" | "llama.cpp" | "vllm" | "sglang" | "lmstudio" | "osaurus" => {
+                Self::Strict
+            }
+            _ => Self::Conservative,
+        }
+    }
 }
 
 /// JSON Schema cleaner optimized for LLM tool calling.
@@ -135,6 +160,16 @@ impl SchemaCleanr {
         Self::clean(schema, CleaningStrategy::OpenAI)
     }
 
+    /// Clean and normalize schema for providers with limited JSON Schema support.
+    pub fn clean_strict(schema: Value) -> Value {
+        Self::clean(schema, CleaningStrategy::Strict)
+    }
+
+    /// Clean a tool parameter schema for `provider` (see [`CleaningStrategy::for_provider`]).
+    pub fn clean_for_provider(schema: Value, provider: &str) -> Value {
+        Self::clean(schema, CleaningStrategy::for_provider(provider))
+    }
+
     /// Clean schema with specified strategy.
     pub fn clean(schema: Value, strategy: CleaningStrategy) -> Value {
         // Extract $defs for reference resolution
@@ -144,7 +179,12 @@ impl SchemaCleanr {
             HashMap::new()
         };
 
-        Self::clean_with_defs(schema, &defs, strategy, &mut HashSet::new())
+        let cleaned = Self::clean_with_defs(schema, &defs, strategy, &mut HashSet::new());
+        if strategy == CleaningStrategy::Strict {
+            Self::normalize_strict(cleaned)
+        } else {
+            cleaned
+        }
     }
 
     /// Validate that a schema is suitable for LLM tool calling.
@@ -170,6 +210,22 @@ impl SchemaCleanr {
         Ok(())
     }
 
+    /// Check a JSON value against `schema`, returning one message per violation
+    /// (empty when the value conforms).
+    ///
+    /// Covers the subset models are asked to follow for structured output:
+    /// `type`, `enum`/`const`, `properties`/`required`/`additionalProperties`,
+    /// `items`, `anyOf`/`oneOf`/`allOf`, length/size bounds and local `$ref`s.
+    pub fn validate_instance(schema: &Value, instance: &Value) -> Vec<String> {
+        let defs = schema
+            .as_object()
+            .map(Self::extract_defs)
+            .unwrap_or_default();
+        let mut errors = Vec::new();
+        Self::check_instance(schema, instance, &defs, "$", &mut errors, 0);
+        errors
+    }
+
     // --------------------------------------------------------------------
     // Internal implementation
     // --------------------------------------------------------------------
@@ -522,6 +578,386 @@ impl SchemaCleanr {
         }
     }
 
+    /// Strict-mode repair pass, applied after keyword removal.
+    fn normalize_strict(schema: Value) -> Value {
+        let Value::Object(mut obj) = schema else {
+            return schema;
+        };
+
+        // Collapse unions: object variants are merged, otherwise the first
+        // variant wins (nullable variants are already stripped).
+        if let Some(Value::Array(variants)) = obj.remove("allOf") {
+            obj = Self::merge_object_variants(obj, variants, true);
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(variants)) = obj.remove(key) {
+                obj = Self::merge_object_variants(obj, variants, false);
+            }
+        }
+
+        for key in ["properties", "patternProperties"] {
+            if let Some(Value::Object(props)) = obj.remove(key) {
+                let props = props
+                    .into_iter()
+                    .map(|(name, value)| (name, Self::normalize_strict(value)))
+                    .collect();
+                obj.insert(key.to_string(), Value::Object(props));
+            }
+        }
+        if let Some(items) = obj.remove("items") {
+            let items = match items {
+                // Tuple form: keep the first item schema.
+                Value::Array(mut tuple) if !tuple.is_empty() => {
+                    Self::normalize_strict(tuple.swap_remove(0))
+                }
+                Value::Array(_) => json!({"type": "string"}),
+                other => Self::normalize_strict(other),
+            };
+            obj.insert("items".to_string(), items);
+        }
+
+        // Single `type`, inferred when missing.
+        let inferred = match obj.get("type") {
+            Some(Value::Array(types)) => types.iter().find_map(Value::as_str).map(str::to_string),
+            Some(Value::String(_)) => None,
+            _ if obj.contains_key("properties") => Some("object".to_string()),
+            _ if obj.contains_key("items") => Some("array".to_string()),
+            _ => match obj
+                .get("enum")
+                .and_then(Value::as_array)
+                .and_then(|v| v.first())
+            {
+                Some(Value::Bool(_)) => Some("boolean".to_string()),
+                Some(Value::Number(n)) if n.is_i64() || n.is_u64() => Some("integer".to_string()),
+                Some(Value::Number(_)) => Some("number".to_string()),
+                _ => Some("string".to_string()),
+            },
+        };
+        if let Some(t) = inferred {
+            obj.insert("type".to_string(), Value::String(t));
+        }
+
+        match obj.get("type").and_then(Value::as_str) {
+            Some("object") if !matches!(obj.get("properties"), Some(Value::Object(_))) => {
+                obj.insert("properties".to_string(), Value::Object(Map::new()));
+            }
+            Some("array") if !obj.contains_key("items") => {
+                obj.insert("items".to_string(), json!({"type": "string"}));
+            }
+            _ => {}
+        }
+
+        // `required` must be a de-duplicated list of declared properties.
+        if let Some(required) = obj.remove("required") {
+            let declared = obj.get("properties").and_then(Value::as_object);
+            let mut seen = HashSet::new();
+            let names: Vec<Value> = required
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .filter(|name| declared.is_some_and(|props| props.contains_key(*name)))
+                .filter(|name| seen.insert(name.to_string()))
+                .map(|name| Value::String(name.to_string()))
+                .collect();
+            if !names.is_empty() {
+                obj.insert("required".to_string(), Value::Array(names));
+            }
+        }
+
+        Value::Object(obj)
+    }
+
+    /// Fold union `variants` into `base`. With `require_all` (allOf) the
+    /// variants' `required` lists are unioned, otherwise intersected.
+    fn merge_object_variants(
+        mut base: Map<String, Value>,
+        variants: Vec<Value>,
+        require_all: bool,
+    ) -> Map<String, Value> {
+        let variants: Vec<Map<String, Value>> = variants
+            .into_iter()
+            .map(Self::normalize_strict)
+            .filter_map(|v| match v {
+                Value::Object(obj) => Some(obj),
+                _ => None,
+            })
+            .collect();
+        let all_objects = !variants.is_empty()
+            && variants
+                .iter()
+                .all(|v| v.get("type").and_then(Value::as_str) == Some("object"));
+
+        if !all_objects {
+            if let Some(first) = variants.into_iter().next() {
+                for (key, value) in first {
+                    base.entry(key).or_insert(value);
+                }
+            }
+            return base;
+        }
+
+        let mut properties = match base.remove("properties") {
+            Some(Value::Object(props)) => props,
+            _ => Map::new(),
+        };
+        let mut required: Option<Vec<String>> = None;
+        for variant in &variants {
+            if let Some(Value::Object(props)) = variant.get("properties") {
+                for (name, schema) in props {
+                    properties
+                        .entry(name.clone())
+                        .or_insert_with(|| schema.clone());
+                }
+            }
+            let names: Vec<String> = variant
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect();
+            required = Some(match required {
+                None => names,
+                Some(mut acc) if require_all => {
+                    acc.extend(names);
+                    acc
+                }
+                Some(acc) => acc.into_iter().filter(|n| names.contains(n)).collect(),
+            });
+        }
+
+        base.insert("type".to_string(), Value::String("object".to_string()));
+        base.insert("properties".to_string(), Value::Object(properties));
+        if let Some(required) = required {
+            let existing = base.remove("required");
+            let mut merged: Vec<Value> = existing
+                .as_ref()
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+            merged.extend(required.into_iter().map(Value::String));
+            base.insert("required".to_string(), Value::Array(merged));
+        }
+        base
+    }
+
+    /// Recursive worker for [`Self::validate_instance`].
+    fn check_instance(
+        schema: &Value,
+        instance: &Value,
+        defs: &HashMap<String, Value>,
+        path: &str,
+        errors: &mut Vec<String>,
+        depth: usize,
+    ) {
+        const MAX_DEPTH: usize = 64;
+        let Some(obj) = schema.as_object() else {
+            if schema == &Value::Bool(false) {
+                errors.push(format!("{path}: no value is allowed here"));
+            }
+            return;
+        };
+        if depth > MAX_DEPTH {
+            errors.push(format!("{path}: schema nesting too deep"));
+            return;
+        }
+
+        if let Some(Value::String(ref_value)) = obj.get("$ref") {
+            match Self::parse_local_ref(ref_value).and_then(|name| defs.get(name.as_str())) {
+                Some(definition) => {
+                    Self::check_instance(definition, instance, defs, path, errors, depth + 1);
+                }
+                None => errors.push(format!("{path}: cannot resolve $ref {ref_value}")),
+            }
+            return;
+        }
+
+        if let Some(expected) = obj.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| Self::matches_type(t, instance)) {
+                errors.push(format!(
+                    "{path}: expected {}, got {}",
+                    allowed.join(" or "),
+                    Self::type_name(instance)
+                ));
+                return;
+            }
+        }
+        if let Some(Value::Array(options)) = obj.get("enum") {
+            if !options.contains(instance) {
+                errors.push(format!(
+                    "{path}: value is not one of {}",
+                    Value::Array(options.clone())
+                ));
+            }
+        }
+        if let Some(expected) = obj.get("const") {
+            if expected != instance {
+                errors.push(format!("{path}: expected constant {expected}"));
+            }
+        }
+
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(variants)) = obj.get(key) {
+                let matched = variants.iter().any(|variant| {
+                    let mut scratch = Vec::new();
+                    Self::check_instance(variant, instance, defs, path, &mut scratch, depth + 1);
+                    scratch.is_empty()
+                });
+                if !matched {
+                    errors.push(format!("{path}: value matches none of the {key} variants"));
+                }
+            }
+        }
+        if let Some(Value::Array(variants)) = obj.get("allOf") {
+            for variant in variants {
+                Self::check_instance(variant, instance, defs, path, errors, depth + 1);
+            }
+        }
+
+        match instance {
+            Value::Object(fields) => {
+                if let Some(Value::Array(required)) = obj.get("required") {
+                    for name in required.iter().filter_map(Value::as_str) {
+                        if !fields.contains_key(name) {
+                            errors.push(format!("{path}: missing required property '{name}'"));
+                        }
+                    }
+                }
+                let properties = obj.get("properties").and_then(Value::as_object);
+                for (name, value) in fields {
+                    let child = format!("{path}.{name}");
+                    match properties.and_then(|props| props.get(name)) {
+                        Some(property) => {
+                            Self::check_instance(property, value, defs, &child, errors, depth + 1);
+                        }
+                        None => match obj.get("additionalProperties") {
+                            Some(Value::Bool(false)) => {
+                                errors.push(format!("{path}: unexpected property '{name}'"));
+                            }
+                            Some(extra @ Value::Object(_)) => {
+                                Self::check_instance(extra, value, defs, &child, errors, depth + 1);
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+                Self::check_bound(
+                    obj,
+                    "minProperties",
+                    "maxProperties",
+                    fields.len(),
+                    "properties",
+                    path,
+                    errors,
+                );
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = obj.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        let child = format!("{path}[{index}]");
+                        Self::check_instance(item_schema, item, defs, &child, errors, depth + 1);
+                    }
+                }
+                Self::check_bound(
+                    obj,
+                    "minItems",
+                    "maxItems",
+                    items.len(),
+                    "items",
+                    path,
+                    errors,
+                );
+            }
+            Value::String(text) => {
+                let length = text.chars().count();
+                Self::check_bound(
+                    obj,
+                    "minLength",
+                    "maxLength",
+                    length,
+                    "characters",
+                    path,
+                    errors,
+                );
+            }
+            Value::Number(number) => {
+                let value = number.as_f64().unwrap_or_default();
+                if let Some(minimum) = obj.get("minimum").and_then(Value::as_f64) {
+                    if value < minimum {
+                        errors.push(format!("{path}: {value} is below minimum {minimum}"));
+                    }
+                }
+                if let Some(maximum) = obj.get("maximum").and_then(Value::as_f64) {
+                    if value > maximum {
+                        errors.push(format!("{path}: {value} is above maximum {maximum}"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_bound(
+        obj: &Map<String, Value>,
+        min_key: &str,
+        max_key: &str,
+        actual: usize,
+        unit: &str,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        let actual = actual as u64;
+        if let Some(min) = obj.get(min_key).and_then(Value::as_u64) {
+            if actual < min {
+                errors.push(format!(
+                    "{path}: expected at least {min} {unit}, got {actual}"
+                ));
+            }
+        }
+        if let Some(max) = obj.get(max_key).and_then(Value::as_u64) {
+            if actual > max {
+                errors.push(format!(
+                    "{path}: expected at most {max} {unit}, got {actual}"
+                ));
+            }
+        }
+    }
+
+    fn matches_type(expected: &str, instance: &Value) -> bool {
+        match expected {
+            "object" => instance.is_object(),
+            "array" => instance.is_array(),
+            "string" => instance.is_string(),
+            "boolean" => instance.is_boolean(),
+            "null" => instance.is_null(),
+            "number" => instance.is_number(),
+            "integer" => {
+                instance.is_i64()
+                    || instance.is_u64()
+                    || instance.as_f64().is_some_and(|n| n.fract() == 0.0)
+            }
+            _ => true,
+        }
+    }
+
+    fn type_name(instance: &Value) -> &'static str {
+        match instance {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
     /// Preserve metadata (description, title, default) from source to target.
     fn preserve_meta(source: &Map<String, Value>, mut target: Value) -> Value {
         if let Value::Object(target_obj) = &mut target {
@@ -816,6 +1252,115 @@ mod tests {
         assert!(cleaned.get("oneOf").is_some());
     }
 
+    #[test]
+    fn test_strict_collapses_unions_and_repairs_required() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "target": {
+                    "oneOf": [
+                        {"type": "object", "properties": {"path": {"type": "string"}}, "required": ["path"]},
+                        {"type": "object", "properties": {"url": {"type": "string"}}, "required": ["url"]}
+                    ]
+                },
+                "mode": {"anyOf": [{"type": "string"}, {"type": "integer"}]},
+                "count": {"type": ["integer", "null"], "minimum": 0},
+                "options": {"type": "object"},
+                "tags": {"type": "array"},
+                "level": {"enum": [1, 2, 3]}
+            },
+            "required": ["target", "target", "missing"]
+        });
+
+        let cleaned = SchemaCleanr::clean_strict(schema);
+
+        let target = &cleaned["properties"]["target"];
+        assert_eq!(target["type"], "object");
+        assert!(target.get("oneOf").is_none());
+        assert!(target["properties"].get("path").is_some());
+        assert!(target["properties"].get("url").is_some());
+        assert!(target.get("required").is_none());
+
+        let mode = &cleaned["properties"]["mode"];
+        assert!(mode.get("anyOf").is_none());
+        assert_eq!(mode["type"], "string");
+
+        assert_eq!(cleaned["properties"]["count"]["type"], "integer");
+        assert!(cleaned["properties"]["count"].get("minimum").is_none());
+        assert_eq!(cleaned["properties"]["options"]["properties"], json!({}));
+        assert_eq!(
+            cleaned["properties"]["tags"]["items"],
+            json!({"type": "string"})
+        );
+        assert_eq!(cleaned["properties"]["level"]["type"], "integer");
+        assert_eq!(cleaned["required"], json!(["target"]));
+    }
+
+    #[test]
+    fn test_strategy_for_provider() {
+        assert_eq!(
+            CleaningStrategy::for_provider("gemini"),
+            CleaningStrategy::Gemini
+        );
+        assert_eq!(
+            CleaningStrategy::for_provider("anthropic"),
+            CleaningStrategy::Anthropic
+        );
+        assert_eq!(
+            CleaningStrategy::for_provider("azure-openai"),
+            CleaningStrategy::OpenAI
+        );
+        assert_eq!(
+            CleaningStrategy::for_provider("custom:http://127.0.0.1:8080/v1"),
+            CleaningStrategy::Strict
+        );
+        assert_eq!(
+            CleaningStrategy::for_provider("ollama"),
+            CleaningStrategy::Strict
+        );
+        assert_eq!(
+            CleaningStrategy::for_provider("something-new"),
+            CleaningStrategy::Conservative
+        );
+    }
+
+    #[test]
+    fn test_validate_instance_reports_violations() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "status": {"$ref": "#/$defs/Status"},
+                "note": {"type": ["string", "null"]}
+            },
+            "required": ["name", "status"],
+            "additionalProperties": false,
+            "$defs": {
+                "Status": {"type": "string", "enum": ["open", "closed"]}
+            }
+        });
+
+        let valid = json!({"name": "a", "tags": ["x"], "status": "open", "note": null});
+        assert!(SchemaCleanr::validate_instance(&schema, &valid).is_empty());
+
+        let invalid = json!({"name": "", "tags": [1], "status": "pending", "extra": true});
+        let errors = SchemaCleanr::validate_instance(&schema, &invalid);
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(errors.iter().any(|e| e.starts_with("$.name:")));
+        assert!(errors.iter().any(|e| e.starts_with("$.tags[0]:")));
+        assert!(errors.iter().any(|e| e.starts_with("$.status:")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("unexpected property 'extra'")));
+
+        let missing = SchemaCleanr::validate_instance(&schema, &json!({"name": "a"}));
+        assert_eq!(
+            missing,
+            vec!["$: missing required property 'status'".to_string()]
+        );
+    }
+
     #[test]
     fn test_clean_nested_unknown_schema_keyword() {
         let schema = json!({
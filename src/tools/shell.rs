@@ -1,17 +1,19 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{Tool, ToolError, ToolResult};
+use crate::runtime::exec::{run_shell, NON_INTERACTIVE_ENV};
 use crate::runtime::RuntimeAdapter;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
 
-/// Maximum shell command execution time before kill.
-const SHELL_TIMEOUT_SECS: u64 = 60;
 /// Maximum output size in bytes (1MB).
 const MAX_OUTPUT_BYTES: usize = 1_048_576;
 
+/// File a `cd`-ing command writes its final directory to on exit.
+const CWD_FILE_ENV: &str = "ZEROCLAW_CWD_FILE";
+
 /// Find the largest byte index <= `index` that is a valid UTF-8 char boundary.
 /// Equivalent to `str::floor_char_boundary` (stabilized in Rust 1.91) but
 /// compatible with the project MSRV (1.87).
@@ -71,6 +73,147 @@ fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
     out
 }
 
+/// Match `name` against a `shell_env_passthrough` entry, where `*` matches
+/// any run of characters. Only used for `env_files` variables; the process
+/// environment is passed through by exact name.
+fn env_pattern_matches(pattern: &str, name: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut remaining) = name.strip_prefix(prefix) else {
+        return false;
+    };
+    let mut parts = rest.split('*').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(idx) => remaining = &remaining[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn is_passthrough_env_var(security: &SecurityPolicy, name: &str) -> bool {
+    security
+        .shell_env_passthrough
+        .iter()
+        .any(|pattern| env_pattern_matches(pattern.trim(), name))
+}
+
+/// Parse `KEY=value` lines from a dotenv file.
+///
+/// Supports comments, `export` prefixes, single quotes (literal) and double
+/// quotes (with `\n`, `\t` and `\"` escapes). Invalid names are skipped.
+fn parse_env_file(content: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if is_valid_env_var_name(key) {
+            vars.push((key.to_string(), parse_env_value(value.trim())));
+        }
+    }
+    vars
+}
+
+fn parse_env_value(raw: &str) -> String {
+    if let Some(inner) = raw.strip_prefix('\'') {
+        return inner.split('\'').next().unwrap_or_default().to_string();
+    }
+    if let Some(inner) = raw.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some(other) => out.push(other),
+                    None => break,
+                },
+                other => out.push(other),
+            }
+        }
+        return out;
+    }
+    // Unquoted values may carry a trailing ` # comment`.
+    raw.split(" #")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Variables from `[autonomy].env_files` that match `shell_env_passthrough`.
+/// Later files override earlier ones; missing files are skipped.
+async fn load_env_file_vars(security: &SecurityPolicy) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for path in &security.env_files {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => vars.extend(
+                parse_env_file(&content)
+                    .into_iter()
+                    .filter(|(key, _)| is_passthrough_env_var(security, key)),
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to read env file {}: {e}", path.display()),
+        }
+    }
+    vars
+}
+
+/// Clear `cmd`'s environment, then re-add only the safe baseline, exact
+/// `shell_env_passthrough` names and matching `env_files` variables.
+pub(super) async fn apply_sandboxed_env(
+    cmd: &mut tokio::process::Command,
+    security: &SecurityPolicy,
+) {
+    cmd.env_clear();
+
+    for var in collect_allowed_shell_env_vars(security) {
+        if let Ok(val) = std::env::var(&var) {
+            cmd.env(&var, val);
+        }
+    }
+    // Project `.env` files take precedence over the process environment.
+    for (key, val) in load_env_file_vars(security).await {
+        cmd.env(key, val);
+    }
+}
+
+impl ShellTool {
+    /// Store the directory a `cd`-ing command ended in (read from, then
+    /// removed with, `cwd_file`) and describe the outcome for the output.
+    async fn persist_working_dir(&self, cwd_file: &Path, previous: &Path) -> Option<String> {
+        let recorded = tokio::fs::read_to_string(cwd_file).await.ok();
+        let _ = tokio::fs::remove_file(cwd_file).await;
+        let dir = PathBuf::from(recorded?.trim_end_matches('\n'));
+        if dir == previous {
+            return None;
+        }
+        Some(if self.security.set_current_dir(&dir) {
+            format!("[working directory: {}]", dir.display())
+        } else {
+            format!(
+                "[working directory unchanged: {} is outside the workspace and allowed roots; still in {}]",
+                dir.display(),
+                previous.display()
+            )
+        })
+    }
+}
+
 #[async_trait]
 impl Tool for ShellTool {
     fn name(&self) -> &str {
@@ -78,7 +221,7 @@ impl Tool for ShellTool {
     }
 
     fn description(&self) -> &str {
-        "Execute a shell command in the workspace directory"
+        "Execute a shell command in the session's working directory (the workspace until a `cd`; `cd` carries over to later calls)"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -93,6 +236,14 @@ impl Tool for ShellTool {
                     "type": "boolean",
                     "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
                     "default": false
+                },
+                "stdin": {
+                    "type": "string",
+                    "description": "Response to feed to a prompt (e.g. \"y\"), one line per answer. Stdin is closed afterwards. Responses outside the pre-approved list need approved=true"
+                },
+                "pty": {
+                    "type": "boolean",
+                    "description": "Run under a pseudo-terminal, for commands that need a TTY. Defaults to [runtime] pty"
                 }
             },
             "required": ["command"]
@@ -108,12 +259,20 @@ impl Tool for ShellTool {
             .get("approved")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let stdin = args.get("stdin").and_then(|v| v.as_str());
+        let options = self.runtime.shell_options();
+        let pty = args
+            .get("pty")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(options.pty);
 
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: too many actions in the last hour",
+                )),
             });
         }
 
@@ -123,7 +282,7 @@ impl Tool for ShellTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(reason),
+                    error: Some(ToolError::from_policy_violation(reason)),
                 });
             }
         }
@@ -132,49 +291,87 @@ impl Tool for ShellTool {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Path blocked by security policy: {path}")),
+                error: Some(ToolError::policy_blocked(format!(
+                    "Path blocked by security policy: {path}"
+                ))),
             });
         }
 
+        let cwd = self.security.current_dir();
+        if let Some(target) = self.security.cd_target_violation(command, &cwd) {
+            return Ok(ToolResult::failure(ToolError::policy_blocked(format!(
+                "cd blocked by security policy: {target} is outside the workspace and allowed roots"
+            ))));
+        }
+
+        if let Some(input) = stdin {
+            if !approved && !options.stdin_preapproved(input) {
+                return Ok(ToolResult::failure(ToolError::permission_denied(format!(
+                    "Stdin response needs approval: only {:?} are pre-approved; set approved=true",
+                    options.approved_stdin
+                ))));
+            }
+        }
+
+        if pty && !self.runtime.supports_pty() {
+            return Ok(ToolResult::failure(ToolError::failed(format!(
+                "The {} runtime cannot run commands under a PTY",
+                self.runtime.name()
+            ))));
+        }
+
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
+                error: Some(ToolError::transient(
+                    "Rate limit exceeded: action budget exhausted",
+                )),
             });
         }
 
         // Execute with timeout to prevent hanging commands.
         // Clear the environment to prevent leaking API keys and other secrets
-        // (CWE-200), then re-add only safe, functional variables.
-        let mut cmd = match self
-            .runtime
-            .build_shell_command(command, &self.security.workspace_dir)
-        {
+        // (CWE-200), then re-add only safe, functional variables plus hints
+        // that keep tools from prompting or paging.
+        // A command that `cd`s reports where it ended up so the next call
+        // starts there.
+        let cwd_file = SecurityPolicy::changes_directory(command).then(|| {
+            std::env::temp_dir().join(format!("zeroclaw-cwd-{}", uuid::Uuid::new_v4().simple()))
+        });
+        let script = if cwd_file.is_some() {
+            format!("trap 'pwd -P > \"${CWD_FILE_ENV}\"' EXIT\n{command}")
+        } else {
+            command.to_string()
+        };
+        let mut cmd = match self.runtime.build_shell_command(&script, &cwd) {
             Ok(cmd) => cmd,
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Failed to build runtime command: {e}")),
+                    error: Some(ToolError::failed(format!(
+                        "Failed to build runtime command: {e}"
+                    ))),
                 });
             }
         };
-        cmd.env_clear();
-
-        for var in collect_allowed_shell_env_vars(&self.security) {
-            if let Ok(val) = std::env::var(&var) {
-                cmd.env(&var, val);
-            }
+        apply_sandboxed_env(&mut cmd, &self.security).await;
+        cmd.envs(NON_INTERACTIVE_ENV);
+        if let Some(path) = &cwd_file {
+            cmd.env(CWD_FILE_ENV, path);
         }
 
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
-
+        let timeout = options.timeout();
+        let result = run_shell(cmd, stdin, pty, timeout).await;
+        let cwd_note = match &cwd_file {
+            Some(path) => self.persist_working_dir(path, &cwd).await,
+            None => None,
+        };
         match result {
-            Ok(Ok(output)) => {
-                let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            Ok(output) => {
+                let mut stdout = output.stdout;
+                let mut stderr = output.stderr;
 
                 // Truncate output to prevent OOM
                 if stdout.len() > MAX_OUTPUT_BYTES {
@@ -185,28 +382,35 @@ impl Tool for ShellTool {
                     stderr.truncate(floor_char_boundary(&stderr, MAX_OUTPUT_BYTES));
                     stderr.push_str("\n... [stderr truncated at 1MB]");
                 }
+                if let Some(note) = cwd_note {
+                    if !stdout.is_empty() && !stdout.ends_with('\n') {
+                        stdout.push('\n');
+                    }
+                    stdout.push_str(&note);
+                }
 
                 Ok(ToolResult {
-                    success: output.status.success(),
+                    success: output.success,
                     output: stdout,
                     error: if stderr.is_empty() {
                         None
                     } else {
-                        Some(stderr)
+                        Some(ToolError::failed(stderr))
                     },
                 })
             }
-            Ok(Err(e)) => Ok(ToolResult {
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Failed to execute command: {e}")),
+                error: Some(ToolError::timeout(format!(
+                    "Command timed out after {}s and was killed",
+                    timeout.as_secs()
+                ))),
             }),
-            Err(_) => Ok(ToolResult {
+            Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!(
-                    "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
-                )),
+                error: Some(ToolError::io(format!("Failed to execute command: {e}"), &e)),
             }),
         }
     }
@@ -274,7 +478,7 @@ mod tests {
             .await
             .expect("disallowed command execution should return a result");
         assert!(!result.success);
-        let error = result.error.as_deref().unwrap_or("");
+        let error = result.error_message().unwrap_or("");
         assert!(error.contains("not allowed") || error.contains("high-risk"));
     }
 
@@ -287,10 +491,13 @@ mod tests {
             .expect("readonly command execution should return a result");
         assert!(!result.success);
         assert!(result
-            .error
-            .as_ref()
+            .error_message()
             .expect("error field should be present for blocked command")
             .contains("not allowed"));
+        assert_eq!(
+            result.error.unwrap().kind,
+            crate::tools::ToolErrorKind::PolicyBlocked
+        );
     }
 
     #[tokio::test]
@@ -327,8 +534,7 @@ mod tests {
             .expect("absolute path argument should be blocked");
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Path blocked"));
     }
@@ -342,8 +548,7 @@ mod tests {
             .expect("option-assigned forbidden path should be blocked");
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Path blocked"));
     }
@@ -357,8 +562,7 @@ mod tests {
             .expect("short option attached forbidden path should be blocked");
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Path blocked"));
     }
@@ -372,8 +576,7 @@ mod tests {
             .expect("tilde-user path should be blocked");
         assert!(!result.success);
         assert!(result
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("Path blocked"));
     }
@@ -386,11 +589,7 @@ mod tests {
             .await
             .expect("input redirection bypass should be blocked");
         assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("not allowed"));
+        assert!(result.error_message().unwrap_or("").contains("not allowed"));
     }
 
     fn test_security_with_env_cmd() -> Arc<SecurityPolicy> {
@@ -476,6 +675,90 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn shell_sets_non_interactive_env_hints() {
+        let tool = ShellTool::new(test_security_with_env_cmd(), test_runtime());
+        let result = tool
+            .execute(json!({"command": "env"}))
+            .await
+            .expect("env command should succeed");
+        assert!(result.success);
+        assert!(result.output.contains("CI=1"));
+        assert!(result.output.contains("GIT_PAGER=cat"));
+    }
+
+    #[tokio::test]
+    async fn shell_feeds_only_preapproved_stdin_without_approval() {
+        let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
+        let fed = tool
+            .execute(json!({"command": "cat", "stdin": "yes"}))
+            .await
+            .unwrap();
+        assert!(fed.success);
+        assert_eq!(fed.output, "yes\n");
+
+        let blocked = tool
+            .execute(json!({"command": "cat", "stdin": "hunter2"}))
+            .await
+            .unwrap();
+        assert!(!blocked.success);
+        assert_eq!(
+            blocked.error.unwrap().kind,
+            crate::tools::traits::ToolErrorKind::PermissionDenied
+        );
+
+        let approved = tool
+            .execute(json!({"command": "cat", "stdin": "hunter2", "approved": true}))
+            .await
+            .unwrap();
+        assert_eq!(approved.output, "hunter2\n");
+    }
+
+    #[tokio::test]
+    async fn shell_cd_persists_within_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let workspace = tmp.path().canonicalize().unwrap();
+        std::fs::create_dir_all(workspace.join("src/nested")).unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace.clone(),
+            allowed_commands: vec!["pwd".into()],
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security.clone(), test_runtime());
+
+        let moved = tool
+            .execute(json!({"command": "cd src/nested"}))
+            .await
+            .unwrap();
+        assert!(moved.success);
+        assert!(moved.output.contains("[working directory:"));
+        let here = tool.execute(json!({"command": "pwd"})).await.unwrap();
+        assert_eq!(
+            here.output.trim(),
+            workspace.join("src/nested").display().to_string()
+        );
+
+        let up = tool
+            .execute(json!({"command": "cd .. && pwd"}))
+            .await
+            .unwrap();
+        assert!(up
+            .output
+            .starts_with(&workspace.join("src").display().to_string()));
+        assert_eq!(security.current_dir(), workspace.join("src"));
+
+        let escaped = tool.execute(json!({"command": "cd ../.."})).await.unwrap();
+        assert!(!escaped.success);
+        assert_eq!(
+            escaped.error.unwrap().kind,
+            crate::tools::traits::ToolErrorKind::PolicyBlocked
+        );
+        let blocked = tool.execute(json!({"command": "cd /etc"})).await.unwrap();
+        assert!(!blocked.success);
+        assert_eq!(security.current_dir(), workspace.join("src"));
+    }
+
     #[tokio::test]
     async fn shell_blocks_plain_variable_expansion() {
         let tool = ShellTool::new(test_security_with_env_cmd(), test_runtime());
@@ -484,11 +767,7 @@ mod tests {
             .await
             .expect("plain variable expansion should be blocked");
         assert!(!result.success);
-        assert!(result
-            .error
-            .as_deref()
-            .unwrap_or("")
-            .contains("not allowed"));
+        assert!(result.error_message().unwrap_or("").contains("not allowed"));
     }
 
     #[tokio::test(flavor = "current_thread")]
@@ -509,6 +788,22 @@ mod tests {
             .contains("ZEROCLAW_TEST_PASSTHROUGH=db://unit-test"));
     }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn shell_env_passthrough_wildcards_do_not_match_process_env() {
+        let _guard = EnvGuard::set("ZEROCLAW_TEST_WILDCARD_KEY", "leaked");
+        let tool = ShellTool::new(
+            test_security_with_env_passthrough(&["ZEROCLAW_TEST_WILDCARD_*"]),
+            test_runtime(),
+        );
+
+        let result = tool
+            .execute(json!({"command": "env"}))
+            .await
+            .expect("env command execution should succeed");
+        assert!(result.success);
+        assert!(!result.output.contains("ZEROCLAW_TEST_WILDCARD_KEY"));
+    }
+
     #[test]
     fn invalid_shell_env_passthrough_names_are_filtered() {
         let security = SecurityPolicy {
@@ -527,6 +822,70 @@ mod tests {
         assert!(!vars.contains(&"1NOPE".to_string()));
     }
 
+    #[test]
+    fn env_passthrough_patterns_support_wildcards() {
+        assert!(env_pattern_matches("DATABASE_URL", "DATABASE_URL"));
+        assert!(!env_pattern_matches("DATABASE_URL", "DATABASE_URL2"));
+        assert!(env_pattern_matches("DATABASE_*", "DATABASE_URL"));
+        assert!(env_pattern_matches("*_TOKEN", "GITHUB_TOKEN"));
+        assert!(env_pattern_matches("AWS_*_KEY", "AWS_SECRET_ACCESS_KEY"));
+        assert!(!env_pattern_matches("AWS_*_KEY", "AWS_REGION"));
+        assert!(env_pattern_matches("*", "ANYTHING"));
+    }
+
+    #[test]
+    fn parse_env_file_handles_quotes_comments_and_export() {
+        let vars = parse_env_file(
+            "# comment\n\
+             DATABASE_URL=postgres://localhost/db # local\n\
+             export API_TOKEN='abc#123'\n\
+             GREETING=\"hello\\nworld\"\n\
+             BAD-NAME=nope\n\
+             no_equals_sign\n",
+        );
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost/db".to_string()
+                ),
+                ("API_TOKEN".to_string(), "abc#123".to_string()),
+                ("GREETING".to_string(), "hello\nworld".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn shell_injects_filtered_env_file_vars() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let env_file = tmp.path().join(".env");
+        std::fs::write(
+            &env_file,
+            "ZEROCLAW_TEST_DB_URL=postgres://env-file\nZEROCLAW_TEST_SECRET=hidden\n",
+        )
+        .unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: tmp.path().to_path_buf(),
+            allowed_commands: vec!["env".into()],
+            shell_env_passthrough: vec!["ZEROCLAW_TEST_DB_*".into()],
+            env_files: vec![env_file, tmp.path().join("missing.env")],
+            ..SecurityPolicy::default()
+        });
+
+        let tool = ShellTool::new(security, test_runtime());
+        let result = tool
+            .execute(json!({"command": "env"}))
+            .await
+            .expect("env command execution should succeed");
+        assert!(result.success);
+        assert!(result
+            .output
+            .contains("ZEROCLAW_TEST_DB_URL=postgres://env-file"));
+        assert!(!result.output.contains("ZEROCLAW_TEST_SECRET"));
+    }
+
     #[tokio::test]
     async fn shell_requires_approval_for_medium_risk_command() {
         let security = Arc::new(SecurityPolicy {
@@ -543,10 +902,13 @@ mod tests {
             .expect("unapproved command should return a result");
         assert!(!denied.success);
         assert!(denied
-            .error
-            .as_deref()
+            .error_message()
             .unwrap_or("")
             .contains("explicit approval"));
+        assert_eq!(
+            denied.error.as_ref().map(|e| e.kind),
+            Some(crate::tools::ToolErrorKind::PermissionDenied)
+        );
 
         let allowed = tool
             .execute(json!({
@@ -565,7 +927,11 @@ mod tests {
 
     #[test]
     fn shell_timeout_constant_is_reasonable() {
-        assert_eq!(SHELL_TIMEOUT_SECS, 60, "shell timeout must be 60 seconds");
+        assert_eq!(
+            test_runtime().shell_options().timeout_secs,
+            60,
+            "shell timeout must be 60 seconds"
+        );
     }
 
     #[test]
@@ -619,7 +985,7 @@ mod tests {
             .await
             .expect("rate-limited command should return a result");
         assert!(!result.success);
-        assert!(result.error.as_deref().unwrap_or("").contains("Rate limit"));
+        assert!(result.error_message().unwrap_or("").contains("Rate limit"));
     }
 
     #[tokio::test]
@@ -644,7 +1010,7 @@ mod tests {
             .execute(json!({"command": "echo error_msg >&2"}))
             .await
             .unwrap();
-        assert!(result.error.as_deref().unwrap_or("").contains("error_msg"));
+        assert!(result.error_message().unwrap_or("").contains("error_msg"));
     }
 
     #[tokio::test]
@@ -669,8 +1035,8 @@ mod tests {
             .unwrap();
         assert!(!r2.success);
         assert!(
-            r2.error.as_deref().unwrap_or("").contains("Rate limit")
-                || r2.error.as_deref().unwrap_or("").contains("budget")
+            r2.error_message().unwrap_or("").contains("Rate limit")
+                || r2.error_message().unwrap_or("").contains("budget")
         );
     }
 }
@@ -6,9 +6,177 @@ use serde::{Deserialize, Serialize};
 pub struct ToolResult {
     pub success: bool,
     pub output: String,
-    pub error: Option<String>,
+    pub error: Option<ToolError>,
 }
 
+impl ToolResult {
+    /// Failed result carrying `error` and no output.
+    pub fn failure(error: ToolError) -> Self {
+        Self {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+        }
+    }
+
+    /// Error message, if the tool reported one.
+    pub fn error_message(&self) -> Option<&str> {
+        self.error.as_ref().map(|e| e.message.as_str())
+    }
+}
+
+/// Failure class of a tool error, used for retry decisions and metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolErrorKind {
+    /// The action needs explicit approval (e.g. `approved=true` in supervised mode).
+    PermissionDenied,
+    /// The operation did not finish in time.
+    Timeout,
+    /// A referenced file, process, or resource does not exist.
+    NotFound,
+    /// Security policy forbids the action outright.
+    PolicyBlocked,
+    /// A temporary condition (rate limit, network hiccup); retrying may succeed.
+    TransientFailure,
+    /// The tool ran but failed (bad arguments, non-zero exit, I/O error).
+    ExecutionFailed,
+}
+
+impl ToolErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PermissionDenied => "permission_denied",
+            Self::Timeout => "timeout",
+            Self::NotFound => "not_found",
+            Self::PolicyBlocked => "policy_blocked",
+            Self::TransientFailure => "transient_failure",
+            Self::ExecutionFailed => "execution_failed",
+        }
+    }
+
+    /// What the caller should do next about this class of failure.
+    pub fn retry_hint(self) -> RetryHint {
+        match self {
+            Self::Timeout | Self::TransientFailure => RetryHint::Retry,
+            Self::PermissionDenied => RetryHint::AskApproval,
+            Self::NotFound | Self::PolicyBlocked | Self::ExecutionFailed => RetryHint::GiveUp,
+        }
+    }
+}
+
+/// Suggested follow-up for a failed tool call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryHint {
+    Retry,
+    AskApproval,
+    GiveUp,
+}
+
+impl RetryHint {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::AskApproval => "ask_approval",
+            Self::GiveUp => "give_up",
+        }
+    }
+}
+
+/// Structured tool error: a failure class plus a human-readable message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolError {
+    pub kind: ToolErrorKind,
+    pub message: String,
+}
+
+impl ToolError {
+    pub fn new(kind: ToolErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::PermissionDenied, message)
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::Timeout, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::NotFound, message)
+    }
+
+    pub fn policy_blocked(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::PolicyBlocked, message)
+    }
+
+    pub fn transient(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::TransientFailure, message)
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self::new(ToolErrorKind::ExecutionFailed, message)
+    }
+
+    /// Classify an I/O error: missing files are `NotFound`, timeouts are
+    /// `Timeout`, interrupted calls are transient, anything else failed.
+    pub fn io(message: impl Into<String>, err: &std::io::Error) -> Self {
+        let kind = match err.kind() {
+            std::io::ErrorKind::NotFound => ToolErrorKind::NotFound,
+            std::io::ErrorKind::TimedOut => ToolErrorKind::Timeout,
+            std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock => {
+                ToolErrorKind::TransientFailure
+            }
+            _ => ToolErrorKind::ExecutionFailed,
+        };
+        Self::new(kind, message)
+    }
+
+    /// Classify a [`SecurityPolicy`](crate::security::SecurityPolicy) rejection:
+    /// approval requests are `PermissionDenied`, rate limits are transient,
+    /// everything else is `PolicyBlocked`.
+    pub fn from_policy_violation(reason: impl Into<String>) -> Self {
+        let reason = reason.into();
+        let lower = reason.to_ascii_lowercase();
+        let kind = if lower.contains("approval") {
+            ToolErrorKind::PermissionDenied
+        } else if lower.contains("rate limit") {
+            ToolErrorKind::TransientFailure
+        } else {
+            ToolErrorKind::PolicyBlocked
+        };
+        Self::new(kind, reason)
+    }
+
+    pub fn retry_hint(&self) -> RetryHint {
+        self.kind.retry_hint()
+    }
+
+    /// Render for the model transcript, e.g.
+    /// `Error [policy_blocked, next: give_up]: Path not allowed`.
+    pub fn to_tool_output(&self) -> String {
+        format!(
+            "Error [{}, next: {}]: {}",
+            self.kind.as_str(),
+            self.retry_hint().as_str(),
+            self.message
+        )
+    }
+}
+
+impl std::fmt::Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ToolError {}
+
 /// Description of a tool for the LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolSpec {
@@ -29,6 +197,17 @@ pub trait Tool: Send + Sync {
     /// JSON schema for parameters
     fn parameters_schema(&self) -> serde_json::Value;
 
+    /// Whether the tool only reads state. Read-only tools still run during a
+    /// planning turn; calls to any other tool are recorded as plan steps.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+
+    /// The conversation keyed `session` (see
+    /// [`with_session`](crate::security::cwd::with_session)) has ended;
+    /// release anything the tool kept for it.
+    fn end_session(&self, _session: &str) {}
+
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
@@ -106,16 +285,29 @@ mod tests {
 
     #[test]
     fn tool_result_serialization_roundtrip() {
-        let result = ToolResult {
-            success: false,
-            output: String::new(),
-            error: Some("boom".into()),
-        };
+        let result = ToolResult::failure(ToolError::timeout("boom"));
 
         let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""kind":"timeout""#));
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
 
         assert!(!parsed.success);
-        assert_eq!(parsed.error.as_deref(), Some("boom"));
+        assert_eq!(parsed.error_message(), Some("boom"));
+        assert_eq!(parsed.error.unwrap().kind, ToolErrorKind::Timeout);
+    }
+
+    #[test]
+    fn tool_error_kinds_map_to_retry_hints() {
+        assert_eq!(ToolError::transient("x").retry_hint(), RetryHint::Retry);
+        assert_eq!(ToolError::timeout("x").retry_hint(), RetryHint::Retry);
+        assert_eq!(
+            ToolError::permission_denied("x").retry_hint(),
+            RetryHint::AskApproval
+        );
+        assert_eq!(ToolError::not_found("x").retry_hint(), RetryHint::GiveUp);
+        assert_eq!(
+            ToolError::policy_blocked("Path not allowed").to_tool_output(),
+            "Error [policy_blocked, next: give_up]: Path not allowed"
+        );
     }
 }
@@ -0,0 +1,94 @@
+//! Cloudflare quick tunnels (`cloudflared tunnel --url ...`).
+//!
+//! Quick tunnels need no account; `cloudflared` prints a random
+//! `https://<name>.trycloudflare.com` URL on stderr. The URL is new on every
+//! start, so restarts are reported to the supervisor as a URL change.
+
+use super::traits::Tunnel;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::watch;
+
+const QUICK_TUNNEL_DOMAIN: &str = ".trycloudflare.com";
+
+pub struct CloudflareTunnel {
+    binary: String,
+}
+
+impl CloudflareTunnel {
+    pub fn new(binary: Option<String>) -> Self {
+        Self {
+            binary: binary.unwrap_or_else(|| "cloudflared".into()),
+        }
+    }
+}
+
+/// Extract the quick tunnel URL from a line of `cloudflared` output.
+pub fn parse_quick_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '/'))
+        .collect();
+    let host = url.trim_start_matches("https://");
+    (host.ends_with(QUICK_TUNNEL_DOMAIN) && host.len() > QUICK_TUNNEL_DOMAIN.len()).then_some(url)
+}
+
+#[async_trait]
+impl Tunnel for CloudflareTunnel {
+    fn name(&self) -> &str {
+        "cloudflare"
+    }
+
+    async fn run(&self, local_url: &str, public_url: &watch::Sender<Option<String>>) -> Result<()> {
+        let mut command = Command::new(&self.binary);
+        command.args(["tunnel", "--no-autoupdate", "--url", local_url]);
+        if local_url.starts_with("https://") {
+            // The gateway's certificate is issued for its public name, not 127.0.0.1.
+            command.arg("--no-tls-verify");
+        }
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start {}", self.binary))?;
+
+        let stderr = child
+            .stderr
+            .take()
+            .context("cloudflared stderr unavailable")?;
+        let mut lines = BufReader::new(stderr).lines();
+        while let Some(line) = lines.next_line().await? {
+            tracing::debug!(target: "tunnel", "cloudflared: {line}");
+            if let Some(url) = parse_quick_tunnel_url(&line) {
+                public_url.send_replace(Some(url));
+            }
+        }
+
+        let status = child.wait().await?;
+        anyhow::bail!("cloudflared exited with {status}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quick_tunnel_banner_line() {
+        let line = "2024-05-01T10:00:00Z INF |  https://calm-river-1234.trycloudflare.com   |";
+        assert_eq!(
+            parse_quick_tunnel_url(line).as_deref(),
+            Some("https://calm-river-1234.trycloudflare.com")
+        );
+        assert!(
+            parse_quick_tunnel_url("INF Visit https://www.cloudflare.com/website-terms/").is_none()
+        );
+        assert!(parse_quick_tunnel_url("https://.trycloudflare.com").is_none());
+    }
+}
@@ -0,0 +1,180 @@
+//! Reverse tunnels exposing the gateway for platform webhooks (`[tunnel]`).
+//!
+//! The gateway spawns the configured [`Tunnel`] next to its listener via
+//! [`spawn_tunnel`], which restarts the tunnel when it exits and publishes
+//! the current public URL on a `watch` channel. [`spawn_webhook_registration`]
+//! follows that channel and calls [`Channel::register_webhook`] for every
+//! webhook channel whenever the URL changes.
+
+pub mod cloudflare;
+pub mod tailscale;
+pub mod traits;
+
+pub use cloudflare::CloudflareTunnel;
+pub use tailscale::TailscaleTunnel;
+pub use traits::Tunnel;
+
+use crate::channels::Channel;
+use crate::config::TunnelConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// First restart delay after a tunnel exits; doubles up to the max.
+const RESTART_BACKOFF_INITIAL_SECS: u64 = 2;
+const RESTART_BACKOFF_MAX_SECS: u64 = 60;
+/// A tunnel that stayed up this long resets the restart backoff.
+const HEALTHY_RUN_SECS: u64 = 300;
+
+/// Build the tunnel selected by `[tunnel] provider`, or `None` when disabled.
+pub fn create_tunnel(config: &TunnelConfig) -> anyhow::Result<Option<Arc<dyn Tunnel>>> {
+    match config.provider.trim().to_ascii_lowercase().as_str() {
+        "none" | "" => Ok(None),
+        "cloudflare" | "cloudflared" => {
+            Ok(Some(Arc::new(CloudflareTunnel::new(config.binary.clone()))))
+        }
+        "tailscale" => Ok(Some(Arc::new(TailscaleTunnel::new(config.binary.clone())))),
+        other => anyhow::bail!("Unknown tunnel provider '{other}'"),
+    }
+}
+
+/// Keep `tunnel` running for `local_url`, restarting it with backoff until
+/// shutdown. The receiver holds `None` while the tunnel is down.
+pub fn spawn_tunnel(tunnel: Arc<dyn Tunnel>, local_url: String) -> watch::Receiver<Option<String>> {
+    let (tx, rx) = watch::channel(None);
+    tokio::spawn(async move {
+        let mut backoff = RESTART_BACKOFF_INITIAL_SECS;
+        loop {
+            let started = std::time::Instant::now();
+            let shutdown = crate::infra::shutdown::global();
+            tokio::select! {
+                result = tunnel.run(&local_url, &tx) => match result {
+                    Ok(()) => tracing::warn!("{} tunnel exited", tunnel.name()),
+                    Err(e) => tracing::warn!("{} tunnel failed: {e:#}", tunnel.name()),
+                },
+                () = shutdown.cancelled() => {}
+            }
+            tx.send_replace(None);
+            if tx.is_closed() || shutdown.is_shutting_down() {
+                break;
+            }
+            if started.elapsed() >= Duration::from_secs(HEALTHY_RUN_SECS) {
+                backoff = RESTART_BACKOFF_INITIAL_SECS;
+            }
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX_SECS);
+        }
+        if let Err(e) = tunnel.stop().await {
+            tracing::debug!("{} tunnel cleanup failed: {e:#}", tunnel.name());
+        }
+    });
+    rx
+}
+
+/// Register each new public URL with every channel in `channels`.
+pub fn spawn_webhook_registration(
+    mut public_url: watch::Receiver<Option<String>>,
+    channels: Vec<Arc<dyn Channel>>,
+) {
+    tokio::spawn(async move {
+        let mut registered: Option<String> = None;
+        loop {
+            let current = public_url.borrow_and_update().clone();
+            if let Some(url) = current.filter(|url| registered.as_ref() != Some(url)) {
+                println!("🌐 Tunnel URL: {url}");
+                for channel in &channels {
+                    match channel.register_webhook(&url).await {
+                        Ok(()) => tracing::info!("Registered {} webhook at {url}", channel.name()),
+                        Err(e) => tracing::warn!(
+                            "Failed to register {} webhook at {url}: {e:#}",
+                            channel.name()
+                        ),
+                    }
+                }
+                registered = Some(url);
+            }
+            if public_url.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    #[test]
+    fn create_tunnel_selects_provider() {
+        let mut config = TunnelConfig::default();
+        assert!(create_tunnel(&config).unwrap().is_none());
+        config.provider = "Cloudflare".into();
+        assert_eq!(
+            create_tunnel(&config).unwrap().unwrap().name(),
+            "cloudflare"
+        );
+        config.provider = "tailscale".into();
+        assert_eq!(create_tunnel(&config).unwrap().unwrap().name(), "tailscale");
+        config.provider = "ngrok".into();
+        assert!(create_tunnel(&config).is_err());
+    }
+
+    struct RecordingChannel {
+        urls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, _message: &crate::channels::SendMessage) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<crate::channels::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn register_webhook(&self, public_url: &str) -> anyhow::Result<()> {
+            self.urls.lock().push(public_url.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn webhooks_are_re_registered_only_when_url_changes() {
+        let urls = Arc::new(Mutex::new(Vec::new()));
+        let (tx, rx) = watch::channel(None);
+        spawn_webhook_registration(
+            rx,
+            vec![Arc::new(RecordingChannel {
+                urls: Arc::clone(&urls),
+            })],
+        );
+
+        for value in [
+            Some("https://a.trycloudflare.com"),
+            None,
+            Some("https://a.trycloudflare.com"),
+            Some("https://b.trycloudflare.com"),
+        ] {
+            tx.send_replace(value.map(String::from));
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(
+            *urls.lock(),
+            vec![
+                "https://a.trycloudflare.com".to_string(),
+                "https://b.trycloudflare.com".to_string()
+            ]
+        );
+    }
+}
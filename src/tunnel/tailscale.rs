@@ -0,0 +1,124 @@
+//! Tailscale Funnel (`tailscale funnel --bg ...`).
+//!
+//! Funnel serves the node's MagicDNS name, so the public URL is stable; it is
+//! re-read periodically in case the node is renamed or the Funnel is removed.
+
+use super::traits::Tunnel;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::watch;
+
+/// How often the node's DNS name and Funnel state are re-checked.
+const STATUS_POLL_SECS: u64 = 60;
+
+pub struct TailscaleTunnel {
+    binary: String,
+}
+
+impl TailscaleTunnel {
+    pub fn new(binary: Option<String>) -> Self {
+        Self {
+            binary: binary.unwrap_or_else(|| "tailscale".into()),
+        }
+    }
+
+    async fn tailscale(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new(&self.binary)
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", self.binary))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} {} failed: {}",
+                self.binary,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Funnel target for a local gateway URL. HTTPS backends use
+/// `https+insecure` because the gateway certificate does not cover localhost.
+pub fn funnel_target(local_url: &str) -> String {
+    match local_url.strip_prefix("https://") {
+        Some(rest) => format!("https+insecure://{rest}"),
+        None => local_url.to_string(),
+    }
+}
+
+/// Public base URL from `tailscale status --json` output.
+pub fn parse_public_url(status_json: &str) -> Result<String> {
+    let status: serde_json::Value =
+        serde_json::from_str(status_json).context("Invalid tailscale status JSON")?;
+    let dns_name = status
+        .pointer("/Self/DNSName")
+        .and_then(serde_json::Value::as_str)
+        .map(|name| name.trim_end_matches('.'))
+        .filter(|name| !name.is_empty())
+        .context("tailscale status has no MagicDNS name; enable MagicDNS and HTTPS")?;
+    Ok(format!("https://{dns_name}"))
+}
+
+#[async_trait]
+impl Tunnel for TailscaleTunnel {
+    fn name(&self) -> &str {
+        "tailscale"
+    }
+
+    async fn run(&self, local_url: &str, public_url: &watch::Sender<Option<String>>) -> Result<()> {
+        let target = funnel_target(local_url);
+        self.tailscale(&["funnel", "--bg", &target]).await?;
+        loop {
+            let url = parse_public_url(&self.tailscale(&["status", "--json"]).await?)?;
+            public_url.send_if_modified(|current| {
+                let changed = current.as_deref() != Some(url.as_str());
+                if changed {
+                    *current = Some(url.clone());
+                }
+                changed
+            });
+            tokio::time::sleep(Duration::from_secs(STATUS_POLL_SECS)).await;
+            let funnel = self.tailscale(&["funnel", "status"]).await?;
+            if funnel.contains("No serve config") {
+                anyhow::bail!("Tailscale Funnel was turned off");
+            }
+        }
+    }
+
+    async fn stop(&self) -> Result<()> {
+        self.tailscale(&["funnel", "reset"]).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_url_comes_from_magic_dns_name() {
+        let json = r#"{"Self": {"DNSName": "box.tail1234.ts.net.", "Online": true}}"#;
+        assert_eq!(
+            parse_public_url(json).unwrap(),
+            "https://box.tail1234.ts.net"
+        );
+        assert!(parse_public_url(r#"{"Self": {"DNSName": ""}}"#).is_err());
+    }
+
+    #[test]
+    fn https_backends_skip_certificate_verification() {
+        assert_eq!(
+            funnel_target("https://127.0.0.1:42617"),
+            "https+insecure://127.0.0.1:42617"
+        );
+        assert_eq!(
+            funnel_target("http://127.0.0.1:42617"),
+            "http://127.0.0.1:42617"
+        );
+    }
+}
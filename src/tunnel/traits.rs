@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use tokio::sync::watch;
+
+/// A reverse tunnel exposing a local gateway URL on the public internet.
+#[async_trait]
+pub trait Tunnel: Send + Sync {
+    /// Provider name (e.g. `"cloudflare"`).
+    fn name(&self) -> &str;
+
+    /// Run the tunnel for `local_url` until it exits, publishing its public
+    /// base URL on `public_url` as soon as it is known (and again if it
+    /// changes). Returning, with or without an error, means the tunnel is down.
+    async fn run(
+        &self,
+        local_url: &str,
+        public_url: &watch::Sender<Option<String>>,
+    ) -> anyhow::Result<()>;
+
+    /// Release provider-side state (e.g. turn a Funnel off) after shutdown.
+    async fn stop(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
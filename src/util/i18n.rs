@@ -0,0 +1,313 @@
+//! User-facing strings in the configured `locale`.
+//!
+//! The top-level `locale` config key (a BCP 47 tag such as `"de"` or
+//! `"pt-BR"`) selects the catalog for fixed text the user sees — channel
+//! error replies, the CLI's interactive prompts and plan approval — and tells
+//! the agent which language to answer in. Tags match a catalog by their
+//! primary language; languages without one fall back to English for fixed
+//! text, while the agent is still asked to answer in the configured language.
+
+use std::sync::{OnceLock, RwLock};
+
+/// Locale used when `locale` is unset.
+pub const DEFAULT_LOCALE: &str = "en";
+
+static RUNTIME_LOCALE: OnceLock<RwLock<Locale>> = OnceLock::new();
+
+/// A catalog entry. `{name}` placeholders are filled in by [`Locale::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    /// Channel reply when a session's message queue is full.
+    QueueOverflow,
+    /// Channel reply when the routed provider cannot be created
+    /// (`{provider}`, `{details}`).
+    ProviderUnavailable,
+    /// Channel reply after a context overflow that compacted the history.
+    ContextExceededCompacted,
+    /// Channel reply after a context overflow.
+    ContextExceeded,
+    /// Channel reply when a turn fails (`{error}`).
+    ErrorReply,
+    /// Channel reply when the model does not answer in time.
+    Timeout,
+    /// Reply when an incoming voice note cannot be transcribed.
+    VoiceNoteFailed,
+    /// Reply when the gateway cannot answer a message.
+    ReplyFailed,
+    /// First line of `zeroclaw agent` interactive mode.
+    InteractiveBanner,
+    /// Second line of interactive mode.
+    InteractiveHelp,
+    /// Interactive mode notice when plan mode starts enabled.
+    PlanModeOn,
+    /// Plan approval question; answered with [`Locale::is_yes`].
+    PlanConfirm,
+    /// Printed when a plan is declined.
+    PlanNotExecuted,
+}
+
+/// Languages with a catalog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Language {
+    #[default]
+    English,
+    German,
+    Spanish,
+    French,
+}
+
+impl Language {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "en" => Some(Self::English),
+            "de" => Some(Self::German),
+            "es" => Some(Self::Spanish),
+            "fr" => Some(Self::French),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "German",
+            Self::Spanish => "Spanish",
+            Self::French => "French",
+        }
+    }
+
+    /// Answers accepted as "yes" at [`Text::PlanConfirm`]; English is
+    /// always accepted.
+    fn yes_words(self) -> &'static [&'static str] {
+        match self {
+            Self::English => &[],
+            Self::German => &["j", "ja"],
+            Self::Spanish => &["s", "si", "sí"],
+            Self::French => &["o", "oui"],
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn text(self, text: Text) -> &'static str {
+        match (self, text) {
+            (Self::English, Text::QueueOverflow) => "I'm still working through your earlier messages. Please wait for my reply before sending more.",
+            (Self::German, Text::QueueOverflow) => "Ich bearbeite noch deine vorherigen Nachrichten. Bitte warte auf meine Antwort, bevor du weitere sendest.",
+            (Self::Spanish, Text::QueueOverflow) => "Todavía estoy procesando tus mensajes anteriores. Espera mi respuesta antes de enviar más.",
+            (Self::French, Text::QueueOverflow) => "Je traite encore tes messages précédents. Attends ma réponse avant d'en envoyer d'autres.",
+
+            (Self::English, Text::ProviderUnavailable) => "⚠️ Failed to initialize provider `{provider}`. Please run `/models` to choose another provider.\nDetails: {details}",
+            (Self::German, Text::ProviderUnavailable) => "⚠️ Anbieter `{provider}` konnte nicht initialisiert werden. Bitte wähle mit `/models` einen anderen Anbieter.\nDetails: {details}",
+            (Self::Spanish, Text::ProviderUnavailable) => "⚠️ No se pudo inicializar el proveedor `{provider}`. Usa `/models` para elegir otro proveedor.\nDetalles: {details}",
+            (Self::French, Text::ProviderUnavailable) => "⚠️ Impossible d'initialiser le fournisseur `{provider}`. Utilise `/models` pour en choisir un autre.\nDétails : {details}",
+
+            (Self::English, Text::ContextExceededCompacted) => "⚠️ Context window exceeded for this conversation. I compacted recent history and kept the latest context. Please resend your last message.",
+            (Self::German, Text::ContextExceededCompacted) => "⚠️ Das Kontextfenster dieser Unterhaltung ist voll. Ich habe den Verlauf verdichtet und den neuesten Kontext behalten. Bitte sende deine letzte Nachricht erneut.",
+            (Self::Spanish, Text::ContextExceededCompacted) => "⚠️ Se superó la ventana de contexto de esta conversación. Compacté el historial reciente y conservé el contexto más nuevo. Vuelve a enviar tu último mensaje.",
+            (Self::French, Text::ContextExceededCompacted) => "⚠️ La fenêtre de contexte de cette conversation est dépassée. J'ai compacté l'historique récent en gardant le contexte le plus récent. Renvoie ton dernier message.",
+
+            (Self::English, Text::ContextExceeded) => "⚠️ Context window exceeded for this conversation. Please resend your last message.",
+            (Self::German, Text::ContextExceeded) => "⚠️ Das Kontextfenster dieser Unterhaltung ist voll. Bitte sende deine letzte Nachricht erneut.",
+            (Self::Spanish, Text::ContextExceeded) => "⚠️ Se superó la ventana de contexto de esta conversación. Vuelve a enviar tu último mensaje.",
+            (Self::French, Text::ContextExceeded) => "⚠️ La fenêtre de contexte de cette conversation est dépassée. Renvoie ton dernier message.",
+
+            (Self::English | Self::Spanish, Text::ErrorReply) => "⚠️ Error: {error}",
+            (Self::German, Text::ErrorReply) => "⚠️ Fehler: {error}",
+            (Self::French, Text::ErrorReply) => "⚠️ Erreur : {error}",
+
+            (Self::English, Text::Timeout) => "⚠️ Request timed out while waiting for the model. Please try again.",
+            (Self::German, Text::Timeout) => "⚠️ Zeitüberschreitung beim Warten auf das Modell. Bitte versuche es erneut.",
+            (Self::Spanish, Text::Timeout) => "⚠️ Se agotó el tiempo de espera del modelo. Inténtalo de nuevo.",
+            (Self::French, Text::Timeout) => "⚠️ Délai dépassé en attendant le modèle. Réessaie.",
+
+            (Self::English, Text::VoiceNoteFailed) => "Sorry, I couldn't transcribe your voice message.",
+            (Self::German, Text::VoiceNoteFailed) => "Entschuldigung, ich konnte deine Sprachnachricht nicht transkribieren.",
+            (Self::Spanish, Text::VoiceNoteFailed) => "Lo siento, no pude transcribir tu mensaje de voz.",
+            (Self::French, Text::VoiceNoteFailed) => "Désolé, je n'ai pas pu transcrire ton message vocal.",
+
+            (Self::English, Text::ReplyFailed) => "Sorry, I couldn't process your message right now.",
+            (Self::German, Text::ReplyFailed) => "Entschuldigung, ich konnte deine Nachricht gerade nicht verarbeiten.",
+            (Self::Spanish, Text::ReplyFailed) => "Lo siento, no pude procesar tu mensaje en este momento.",
+            (Self::French, Text::ReplyFailed) => "Désolé, je n'ai pas pu traiter ton message pour le moment.",
+
+            (Self::English, Text::InteractiveBanner) => "🦀 ZeroClaw Interactive Mode",
+            (Self::German, Text::InteractiveBanner) => "🦀 ZeroClaw – interaktiver Modus",
+            (Self::Spanish, Text::InteractiveBanner) => "🦀 ZeroClaw – modo interactivo",
+            (Self::French, Text::InteractiveBanner) => "🦀 ZeroClaw – mode interactif",
+
+            (Self::English, Text::InteractiveHelp) => "Type /help for commands, /quit to exit.",
+            (Self::German, Text::InteractiveHelp) => "Gib /help für Befehle ein, /quit zum Beenden.",
+            (Self::Spanish, Text::InteractiveHelp) => "Escribe /help para ver los comandos, /quit para salir.",
+            (Self::French, Text::InteractiveHelp) => "Tape /help pour les commandes, /quit pour quitter.",
+
+            (Self::English, Text::PlanModeOn) => "Plan mode is on: messages produce a plan; /approve executes it.",
+            (Self::German, Text::PlanModeOn) => "Planmodus ist an: Nachrichten erzeugen einen Plan; /approve führt ihn aus.",
+            (Self::Spanish, Text::PlanModeOn) => "El modo plan está activado: los mensajes generan un plan; /approve lo ejecuta.",
+            (Self::French, Text::PlanModeOn) => "Le mode plan est activé : les messages produisent un plan ; /approve l'exécute.",
+
+            (Self::English, Text::PlanConfirm) => "Execute this plan? [y/N] ",
+            (Self::German, Text::PlanConfirm) => "Diesen Plan ausführen? [j/N] ",
+            (Self::Spanish, Text::PlanConfirm) => "¿Ejecutar este plan? [s/N] ",
+            (Self::French, Text::PlanConfirm) => "Exécuter ce plan ? [o/N] ",
+
+            (Self::English, Text::PlanNotExecuted) => "Plan not executed.",
+            (Self::German, Text::PlanNotExecuted) => "Plan nicht ausgeführt.",
+            (Self::Spanish, Text::PlanNotExecuted) => "Plan no ejecutado.",
+            (Self::French, Text::PlanNotExecuted) => "Plan non exécuté.",
+        }
+    }
+}
+
+/// The configured locale and its catalog.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Locale {
+    /// The configured tag; `None` when `locale` is unset.
+    tag: Option<String>,
+    language: Language,
+}
+
+impl Locale {
+    /// Locale for the `locale` config value. Unset or blank means English
+    /// with no language instruction for the agent.
+    pub fn new(tag: Option<&str>) -> Self {
+        let tag = tag.map(str::trim).filter(|tag| !tag.is_empty());
+        Self {
+            language: tag.and_then(Language::from_tag).unwrap_or_default(),
+            tag: tag.map(str::to_string),
+        }
+    }
+
+    /// The configured tag, or [`DEFAULT_LOCALE`].
+    pub fn tag(&self) -> &str {
+        self.tag.as_deref().unwrap_or(DEFAULT_LOCALE)
+    }
+
+    pub fn text(&self, text: Text) -> &'static str {
+        self.language.text(text)
+    }
+
+    /// [`Self::text`] with each `{name}` in `args` replaced by its value.
+    pub fn format(&self, text: Text, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.text(text).to_string(), |out, (name, value)| {
+                out.replace(&format!("{{{name}}}"), value)
+            })
+    }
+
+    /// Whether `answer` to [`Text::PlanConfirm`] means yes.
+    pub fn is_yes(&self, answer: &str) -> bool {
+        let answer = answer.trim().to_lowercase();
+        matches!(answer.as_str(), "y" | "yes")
+            || self.language.yes_words().contains(&answer.as_str())
+    }
+
+    /// System prompt section asking the agent to answer in this locale's
+    /// language; empty when `locale` is unset.
+    pub fn prompt_section(&self) -> String {
+        let Some(tag) = self.tag.as_deref() else {
+            return String::new();
+        };
+        let language = Language::from_tag(tag).map_or_else(
+            || format!("the language of locale `{tag}`"),
+            |language| language.name().to_string(),
+        );
+        format!(
+            "## Language\n\n\
+             The user's locale is `{tag}`. Reply in {language} by default; if the user writes \
+             in another language, reply in theirs. Keep code, commands and file names unchanged.\n\n"
+        )
+    }
+}
+
+/// Whether `tag` looks like a BCP 47 language tag (`de`, `pt-BR`, `zh-Hant-TW`).
+pub fn is_valid_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary_ok = subtags.next().is_some_and(|primary| {
+        (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic())
+    });
+    primary_ok
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+fn runtime_locale_state() -> &'static RwLock<Locale> {
+    RUNTIME_LOCALE.get_or_init(|| RwLock::new(Locale::default()))
+}
+
+/// Install the process-wide locale used by [`runtime_locale`].
+pub fn set_runtime_locale(locale: Locale) {
+    match runtime_locale_state().write() {
+        Ok(mut guard) => *guard = locale,
+        Err(poisoned) => *poisoned.into_inner() = locale,
+    }
+}
+
+/// The locale from the loaded config (English until one is loaded).
+pub fn runtime_locale() -> Locale {
+    match runtime_locale_state().read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// [`Locale::text`] in the runtime locale.
+pub fn text(text: Text) -> &'static str {
+    runtime_locale().text(text)
+}
+
+/// [`Locale::format`] in the runtime locale.
+pub fn format(text: Text, args: &[(&str, &str)]) -> String {
+    runtime_locale().format(text, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locales_pick_a_catalog_by_primary_language() {
+        let german = Locale::new(Some("de-AT"));
+        assert_eq!(german.tag(), "de-AT");
+        assert_eq!(german.text(Text::PlanNotExecuted), "Plan nicht ausgeführt.");
+        assert_eq!(
+            german.format(Text::ErrorReply, &[("error", "boom")]),
+            "⚠️ Fehler: boom"
+        );
+        assert!(german.is_yes(" Ja\n"));
+        assert!(german.is_yes("y"));
+        assert!(!german.is_yes("nein"));
+
+        let unset = Locale::new(None);
+        assert_eq!(unset.tag(), DEFAULT_LOCALE);
+        assert_eq!(unset.text(Text::PlanNotExecuted), "Plan not executed.");
+        assert!(!unset.is_yes("ja"));
+
+        // No Japanese catalog: English text, but the tag is kept.
+        let japanese = Locale::new(Some("ja"));
+        assert_eq!(japanese.tag(), "ja");
+        assert_eq!(japanese.text(Text::PlanNotExecuted), "Plan not executed.");
+    }
+
+    #[test]
+    fn prompt_section_names_the_language_only_when_configured() {
+        assert_eq!(Locale::new(None).prompt_section(), "");
+        assert_eq!(Locale::new(Some("  ")).prompt_section(), "");
+        assert!(Locale::new(Some("es-MX"))
+            .prompt_section()
+            .contains("The user's locale is `es-MX`. Reply in Spanish by default"));
+        assert!(Locale::new(Some("ja"))
+            .prompt_section()
+            .contains("Reply in the language of locale `ja` by default"));
+    }
+
+    #[test]
+    fn language_tags_are_validated() {
+        for tag in ["en", "de-AT", "pt-BR", "zh-Hant-TW", "yue"] {
+            assert!(is_valid_tag(tag), "{tag} should be valid");
+        }
+        for tag in ["", "e", "english", "de_AT", "de-", "fr-toolongsubtag"] {
+            assert!(!is_valid_tag(tag), "{tag} should be invalid");
+        }
+    }
+}
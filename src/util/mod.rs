@@ -2,6 +2,10 @@
 //!
 //! This module contains reusable helper functions used across the codebase.
 
+pub mod i18n;
+pub mod time;
+pub mod tokenizer;
+
 /// Truncate a string to at most `max_chars` characters, appending "..." if truncated.
 ///
 /// This function safely handles multi-byte UTF-8 characters (emoji, CJK, accented characters)
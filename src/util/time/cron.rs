@@ -0,0 +1,282 @@
+//! Five-field cron expressions: `minute hour day-of-month month day-of-week`.
+//!
+//! Each field accepts `*`, single values, ranges (`1-5`), steps (`*/15`,
+//! `0-30/10`) and comma-separated lists. Months and weekdays also take
+//! three-letter names (`jan`, `mon`). Weekday `0` and `7` are both Sunday.
+//! As in classic cron, when both day fields are restricted a day matching
+//! either one fires. `@hourly`, `@daily`, `@weekly`, `@monthly` and
+//! `@yearly` are accepted as shorthands.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone};
+use std::fmt;
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+/// Upper bound on the search for the next run; covers Feb 29 schedules.
+const MAX_LOOKAHEAD_DAYS: u32 = 366 * 4 + 1;
+
+/// A parsed cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, dom, month, dow] = fields[..] else {
+            bail!(
+                "cron expression '{trimmed}' must have 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            );
+        };
+
+        let context = |name: &str| format!("invalid {name} field in cron expression '{trimmed}'");
+        let mut days_of_week =
+            parse_field(dow, 0, 7, WEEKDAY_NAMES).with_context(|| context("weekday"))?;
+        // Fold 7 (Sunday) onto 0.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            expression: trimmed.to_string(),
+            minutes: parse_field(minute, 0, 59, &[]).with_context(|| context("minute"))?,
+            hours: parse_field(hour, 0, 23, &[]).with_context(|| context("hour"))?,
+            days_of_month: parse_field(dom, 1, 31, &[]).with_context(|| context("day"))?,
+            months: parse_field(month, 1, 12, MONTH_NAMES).with_context(|| context("month"))?,
+            days_of_week,
+            day_of_month_restricted: !dom.starts_with('*'),
+            day_of_week_restricted: !dow.starts_with('*'),
+        })
+    }
+
+    /// The expression as written (macros are not expanded).
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// First fire time strictly after `after`, in `after`'s time zone.
+    /// Local times skipped by a DST jump are skipped; repeated ones fire once.
+    /// Returns `None` for schedules that can never fire (e.g. `0 0 30 2 *`).
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local();
+        let mut date = start.date();
+        for _ in 0..=MAX_LOOKAHEAD_DAYS {
+            if self.matches_date(date) {
+                for hour in (0..24).filter(|h| has_bit(self.hours, *h)) {
+                    for minute in (0..60).filter(|m| has_bit(self.minutes, *m)) {
+                        let candidate = date.and_hms_opt(hour, minute, 0)?;
+                        if candidate <= start {
+                            continue;
+                        }
+                        if let Some(at) = tz.from_local_datetime(&candidate).earliest() {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has_bit(self.months, date.month()) {
+            return false;
+        }
+        let dom = has_bit(self.days_of_month, date.day());
+        let dow = has_bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has_bit(mask: u64, bit: u32) -> bool {
+    mask & (1 << bit) != 0
+}
+
+/// Parse one field into a bitmask of allowed values within `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64> {
+    let mut mask = 0_u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .with_context(|| format!("invalid step '{step}'"))?;
+                if step == 0 {
+                    bail!("step must be greater than 0");
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (
+                parse_value(lo, min, max, names)?,
+                parse_value(hi, min, max, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // `5/15` means "from 5, every 15".
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start > end {
+            bail!("range '{range}' is reversed");
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(raw: &str, min: u32, max: u32, names: &[&str]) -> Result<u32> {
+    let lower = raw.to_ascii_lowercase();
+    let value = match names.iter().position(|name| *name == lower) {
+        // Month names start at 1, weekday names at 0 — both match `min`.
+        #[allow(clippy::cast_possible_truncation)]
+        Some(index) => index as u32 + min,
+        None => raw
+            .parse()
+            .with_context(|| format!("'{raw}' is not a number"))?,
+    };
+    if value < min || value > max {
+        bail!("{value} is outside {min}-{max}");
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, Utc};
+
+    fn at(raw: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn next(expr: &str, after: &str) -> String {
+        CronSchedule::parse(expr)
+            .unwrap()
+            .next_after(&at(after))
+            .unwrap()
+            .format("%Y-%m-%d %H:%M %a")
+            .to_string()
+    }
+
+    #[test]
+    fn next_after_walks_common_schedules() {
+        // 2026-03-02 is a Monday.
+        assert_eq!(
+            next("0 9 * * *", "2026-03-02 08:59:30"),
+            "2026-03-02 09:00 Mon"
+        );
+        assert_eq!(
+            next("0 9 * * *", "2026-03-02 09:00:00"),
+            "2026-03-03 09:00 Tue"
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-03-02 10:07:00"),
+            "2026-03-02 10:15 Mon"
+        );
+        assert_eq!(
+            next("30 8 * * mon-fri", "2026-03-06 09:00:00"),
+            "2026-03-09 08:30 Mon"
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-03-02 00:00:00"),
+            "2026-03-08 00:00 Sun"
+        );
+        assert_eq!(
+            next("@monthly", "2026-03-02 00:00:00"),
+            "2026-04-01 00:00 Wed"
+        );
+        assert_eq!(
+            next("0 12 29 feb *", "2026-03-02 00:00:00"),
+            "2028-02-29 12:00 Tue"
+        );
+        assert_eq!(
+            next("5/20 1,3 * * *", "2026-03-02 01:30:00"),
+            "2026-03-02 01:45 Mon"
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 15th, or any Friday: Friday 2026-03-06 comes first.
+        assert_eq!(
+            next("0 0 15 * fri", "2026-03-02 00:00:00"),
+            "2026-03-06 00:00 Fri"
+        );
+        assert_eq!(
+            next("0 0 15 * fri", "2026-03-13 00:00:00"),
+            "2026-03-15 00:00 Sun"
+        );
+    }
+
+    #[test]
+    fn parse_rejects_malformed_expressions() {
+        for bad in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "10-5 * * * *",
+            "x * * * *",
+            "@often",
+        ] {
+            assert!(
+                CronSchedule::parse(bad).is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn impossible_schedules_never_fire() {
+        let schedule = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert!(schedule.next_after(&at("2026-01-01 00:00:00")).is_none());
+        assert_eq!(schedule.to_string(), "0 0 30 2 *");
+    }
+}
@@ -0,0 +1,474 @@
+//! Cron and natural-language time parsing shared by the scheduler and tools.
+//!
+//! [`TimeService`] resolves wall-clock times in the zone named by the
+//! top-level `timezone` config key (an IANA name such as `"Europe/Berlin"`),
+//! or in the host's zone when it is unset. It computes [`CronSchedule`] fire
+//! times and parses the times users type:
+//!
+//! - relative: `in 20 minutes`, `in 2h`, `in an hour`, `in 3 days`
+//! - clock times: `5pm`, `17:30`, `noon` (today, or tomorrow once passed)
+//! - days: `today 5pm`, `tomorrow at 9am`, `friday 14:00`, `next monday 9am`,
+//!   `2026-03-05 10:00`; a day without a time means 09:00
+//! - RFC 3339 timestamps, taken as-is
+
+mod cron;
+
+pub use cron::CronSchedule;
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use chrono::{
+    DateTime, Datelike, Days, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// Time of day used when a phrase names a day but no time.
+const DEFAULT_HOUR: u32 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Zone {
+    Local,
+    Named(Tz),
+}
+
+/// A day named in a time phrase.
+#[derive(Debug, Clone, Copy)]
+enum Day {
+    Date(NaiveDate),
+    /// The next such weekday; `after_today` skips today (`next monday`).
+    Weekday {
+        weekday: Weekday,
+        after_today: bool,
+    },
+}
+
+/// Timezone-aware time parsing. Cheap to copy into every tool that needs it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeService {
+    zone: Zone,
+}
+
+impl Default for TimeService {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+impl TimeService {
+    /// Times in the host's timezone.
+    pub fn local() -> Self {
+        Self { zone: Zone::Local }
+    }
+
+    /// Times in the IANA zone `timezone`; `None`, empty or `"local"` mean the
+    /// host's timezone.
+    pub fn new(timezone: Option<&str>) -> Result<Self> {
+        let zone = match timezone.map(str::trim) {
+            None | Some("") => Zone::Local,
+            Some(name) if name.eq_ignore_ascii_case("local") => Zone::Local,
+            Some(name) => Zone::Named(name.parse::<Tz>().map_err(|_| {
+                anyhow::anyhow!(
+                    "Unknown timezone '{name}'; use an IANA name such as \"Europe/Berlin\""
+                )
+            })?),
+        };
+        Ok(Self { zone })
+    }
+
+    /// The service for `config.timezone`. An invalid zone (rejected by
+    /// `Config::validate`) falls back to the host's timezone.
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.timezone.as_deref()).unwrap_or_else(|e| {
+            tracing::warn!("{e:#}; using the host timezone");
+            Self::local()
+        })
+    }
+
+    /// IANA name of the zone, or `"local"` for the host's timezone.
+    pub fn zone_name(self) -> &'static str {
+        match self.zone {
+            Zone::Local => "local",
+            Zone::Named(tz) => tz.name(),
+        }
+    }
+
+    /// `at` as wall-clock time in the configured zone.
+    pub fn localize(self, at: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.zone {
+            Zone::Local => at.with_timezone(&Local).fixed_offset(),
+            Zone::Named(tz) => at.with_timezone(&tz).fixed_offset(),
+        }
+    }
+
+    /// The instant of wall-clock `naive` in the configured zone. Times
+    /// repeated by a DST change resolve to the first; skipped ones are errors.
+    pub fn resolve(self, naive: NaiveDateTime) -> Result<DateTime<Utc>> {
+        let at = match self.zone {
+            Zone::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|at| at.with_timezone(&Utc)),
+            Zone::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|at| at.with_timezone(&Utc)),
+        };
+        at.with_context(|| {
+            format!(
+                "{} does not exist in the {} timezone (DST change)",
+                naive.format("%Y-%m-%d %H:%M"),
+                self.zone_name()
+            )
+        })
+    }
+
+    /// First fire time of `schedule` strictly after `after`, evaluated in the
+    /// configured zone.
+    pub fn next_cron(self, schedule: &CronSchedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.zone {
+            Zone::Local => schedule
+                .next_after(&after.with_timezone(&Local))
+                .map(|at| at.with_timezone(&Utc)),
+            Zone::Named(tz) => schedule
+                .next_after(&after.with_timezone(&tz))
+                .map(|at| at.with_timezone(&Utc)),
+        }
+    }
+
+    /// Parse a time phrase relative to now. See the module docs for the
+    /// accepted forms.
+    pub fn parse(self, text: &str) -> Result<DateTime<Utc>> {
+        self.parse_at(text, Utc::now())
+    }
+
+    /// Parse a time phrase relative to `now`.
+    pub fn parse_at(self, text: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let text = text.trim();
+        if let Ok(exact) = DateTime::parse_from_rfc3339(text) {
+            return Ok(exact.with_timezone(&Utc));
+        }
+        for format in ["%Y-%m-%dT%H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"] {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(text, format) {
+                return self.resolve(naive);
+            }
+        }
+
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split_whitespace()
+            .filter(|word| !matches!(*word, "at" | "on"))
+            .collect();
+        let parsed = match words.split_first() {
+            Some((&"in", rest)) => self.parse_relative(rest, now),
+            _ => self.parse_day_and_time(&words, now),
+        };
+        parsed.with_context(|| {
+            format!(
+                "Unrecognized time '{text}'; try 'in 20 minutes', '5pm', 'tomorrow 9am' or 'next monday 09:00'"
+            )
+        })
+    }
+
+    /// The calendar day named by `text` in the configured zone: `today`,
+    /// `tomorrow`, `yesterday`, a weekday (this week's, from today on),
+    /// `next <weekday>`, `YYYY-MM-DD`, or any time phrase [`Self::parse_at`]
+    /// accepts.
+    pub fn parse_date_at(self, text: &str, now: DateTime<Utc>) -> Result<NaiveDate> {
+        let today = self.localize(now).date_naive();
+        let lower = text.trim().to_lowercase();
+        let day = match lower.as_str() {
+            "today" => Some(today),
+            "tomorrow" => Some(today + Days::new(1)),
+            "yesterday" => Some(today - Days::new(1)),
+            other => other.parse::<Weekday>().ok().map(|weekday| {
+                let ahead = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                today + Days::new(u64::from(ahead))
+            }),
+        };
+        match day {
+            Some(day) => Ok(day),
+            None => Ok(self.localize(self.parse_at(text, now)?).date_naive()),
+        }
+    }
+
+    /// The first instant of `date` in the configured zone.
+    pub fn start_of_day(self, date: NaiveDate) -> Result<DateTime<Utc>> {
+        // Days that start inside a DST gap begin at 01:00.
+        self.resolve(date.and_time(NaiveTime::MIN)).or_else(|_| {
+            self.resolve(date.and_time(NaiveTime::from_hms_opt(1, 0, 0).unwrap_or(NaiveTime::MIN)))
+        })
+    }
+
+    /// `<amount> <unit>` after `in`. Minutes and hours are exact durations;
+    /// days and weeks keep the wall-clock time across DST changes.
+    fn parse_relative(self, words: &[&str], now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let (amount, unit) = match words {
+            ["half", "an", "hour"] => (30, "minute"),
+            [amount, unit] => (parse_amount(amount)?, *unit),
+            [compact] => {
+                let split = compact
+                    .find(|c: char| !c.is_ascii_digit())
+                    .context("missing unit")?;
+                let (amount, unit) = compact.split_at(split);
+                (parse_amount(amount)?, unit)
+            }
+            _ => bail!("expected 'in <number> <unit>'"),
+        };
+        let amount = i64::from(amount);
+        let exact = |delay: Option<Duration>| {
+            delay
+                .and_then(|delay| now.checked_add_signed(delay))
+                .context("time is too far in the future")
+        };
+        let days = match unit.trim_end_matches('s') {
+            "m" | "min" | "minute" => return exact(Duration::try_minutes(amount)),
+            "h" | "hr" | "hour" => return exact(Duration::try_hours(amount)),
+            "d" | "day" => amount,
+            "w" | "wk" | "week" => amount * 7,
+            other => bail!("unknown unit '{other}'"),
+        };
+        let naive = self
+            .localize(now)
+            .naive_local()
+            .checked_add_days(Days::new(days.unsigned_abs()))
+            .context("time is too far in the future")?;
+        self.resolve(naive)
+    }
+
+    fn parse_day_and_time(self, words: &[&str], now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+        let today = self.localize(now).date_naive();
+        let mut day = None;
+        let mut time_words = Vec::new();
+        let mut words = words.iter().copied();
+        while let Some(word) = words.next() {
+            let parsed = match word {
+                "today" => Some(Day::Date(today)),
+                "tomorrow" => Some(Day::Date(today + Days::new(1))),
+                "next" | "this" => {
+                    let weekday = words
+                        .next()
+                        .and_then(|next| next.parse::<Weekday>().ok())
+                        .with_context(|| format!("expected a weekday after '{word}'"))?;
+                    Some(Day::Weekday {
+                        weekday,
+                        after_today: word == "next",
+                    })
+                }
+                other => other
+                    .parse::<Weekday>()
+                    .ok()
+                    .map(|weekday| Day::Weekday {
+                        weekday,
+                        after_today: false,
+                    })
+                    .or_else(|| {
+                        NaiveDate::parse_from_str(other, "%Y-%m-%d")
+                            .ok()
+                            .map(Day::Date)
+                    }),
+            };
+            match parsed {
+                Some(_) if day.is_some() => bail!("more than one day given"),
+                Some(parsed) => day = Some(parsed),
+                None => time_words.push(word),
+            }
+        }
+        let time = if time_words.is_empty() {
+            None
+        } else {
+            Some(parse_time_of_day(&time_words.concat())?)
+        };
+
+        match (day, time) {
+            (None, None) => bail!("no time given"),
+            (None, Some(time)) => {
+                let at = self.resolve(today.and_time(time))?;
+                if at > now {
+                    Ok(at)
+                } else {
+                    self.resolve((today + Days::new(1)).and_time(time))
+                }
+            }
+            (Some(Day::Date(date)), time) => {
+                self.resolve(date.and_time(time.unwrap_or_else(default_time)))
+            }
+            (
+                Some(Day::Weekday {
+                    weekday,
+                    after_today,
+                }),
+                time,
+            ) => {
+                let time = time.unwrap_or_else(default_time);
+                let first = u64::from(after_today);
+                for offset in first..first + 8 {
+                    let date = today + Days::new(offset);
+                    if date.weekday() != weekday {
+                        continue;
+                    }
+                    let at = self.resolve(date.and_time(time))?;
+                    if at > now {
+                        return Ok(at);
+                    }
+                }
+                bail!("no upcoming {weekday}")
+            }
+        }
+    }
+}
+
+fn default_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0).unwrap_or(NaiveTime::MIN)
+}
+
+fn parse_amount(raw: &str) -> Result<u32> {
+    match raw {
+        "a" | "an" | "one" => Ok(1),
+        other => other
+            .parse()
+            .with_context(|| format!("'{other}' is not a number")),
+    }
+}
+
+/// `5pm`, `5:30pm`, `17:00`, `noon` or `midnight`. A bare hour needs `am`/`pm`.
+fn parse_time_of_day(raw: &str) -> Result<NaiveTime> {
+    let (clock, pm) = match raw {
+        "noon" | "midday" => ("12:00", None),
+        "midnight" => ("0:00", None),
+        other => match (other.strip_suffix("am"), other.strip_suffix("pm")) {
+            (Some(clock), _) => (clock, Some(false)),
+            (_, Some(clock)) => (clock, Some(true)),
+            _ => (other, None),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour, minute),
+        None if pm.is_some() => (clock, "0"),
+        None => bail!("'{raw}' is not a time; use '17:00' or '5pm'"),
+    };
+    let mut hour: u32 = hour
+        .parse()
+        .with_context(|| format!("'{raw}' is not a time"))?;
+    let minute: u32 = minute
+        .parse()
+        .with_context(|| format!("'{raw}' is not a time"))?;
+    if let Some(pm) = pm {
+        if !(1..=12).contains(&hour) {
+            bail!("'{raw}' is not a time");
+        }
+        hour = hour % 12 + if pm { 12 } else { 0 };
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0).with_context(|| format!("'{raw}' is not a time"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_relative_clock_and_day_phrases() {
+        let time = TimeService::new(Some("UTC")).unwrap();
+        // 2026-03-02 is a Monday.
+        let now = utc(2026, 3, 2, 16, 0);
+        let parse = |text: &str| time.parse_at(text, now).unwrap();
+
+        assert_eq!(parse("in 20 minutes"), utc(2026, 3, 2, 16, 20));
+        assert_eq!(parse("in 2h"), utc(2026, 3, 2, 18, 0));
+        assert_eq!(parse("in an hour"), utc(2026, 3, 2, 17, 0));
+        assert_eq!(parse("in half an hour"), utc(2026, 3, 2, 16, 30));
+        assert_eq!(parse("in 3 days"), utc(2026, 3, 5, 16, 0));
+        assert_eq!(parse("5pm"), utc(2026, 3, 2, 17, 0));
+        assert_eq!(parse("at 9am"), utc(2026, 3, 3, 9, 0));
+        assert_eq!(parse("Tomorrow at 9:30am"), utc(2026, 3, 3, 9, 30));
+        assert_eq!(parse("monday 17:00"), utc(2026, 3, 2, 17, 0));
+        assert_eq!(parse("monday 9am"), utc(2026, 3, 9, 9, 0));
+        assert_eq!(parse("next monday 9am"), utc(2026, 3, 9, 9, 0));
+        assert_eq!(parse("friday"), utc(2026, 3, 6, 9, 0));
+        assert_eq!(parse("noon tomorrow"), utc(2026, 3, 3, 12, 0));
+        assert_eq!(parse("2026-03-05 10:00"), utc(2026, 3, 5, 10, 0));
+        assert_eq!(parse("2026-03-05T10:00"), utc(2026, 3, 5, 10, 0));
+        assert_eq!(parse("2026-03-05T10:00:00+02:00"), utc(2026, 3, 5, 8, 0));
+
+        for bad in [
+            "",
+            "soonish",
+            "in 5 fortnights",
+            "in minutes",
+            "tomorrow 25:00",
+            "tomorrow 5",
+            "13pm",
+            "next",
+            "monday friday",
+        ] {
+            assert!(time.parse_at(bad, now).is_err(), "{bad:?} should fail");
+        }
+    }
+
+    #[test]
+    fn parses_date_phrases() {
+        let time = TimeService::new(Some("America/New_York")).unwrap();
+        // Monday 2026-03-02 23:30 in New York.
+        let now = utc(2026, 3, 3, 4, 30);
+        let date = |text: &str| time.parse_date_at(text, now).unwrap();
+
+        assert_eq!(date("today"), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(
+            date("Tomorrow"),
+            NaiveDate::from_ymd_opt(2026, 3, 3).unwrap()
+        );
+        assert_eq!(date("monday"), NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(date("friday"), NaiveDate::from_ymd_opt(2026, 3, 6).unwrap());
+        assert_eq!(
+            date("next monday"),
+            NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+        );
+        assert_eq!(
+            date("2026-04-01"),
+            NaiveDate::from_ymd_opt(2026, 4, 1).unwrap()
+        );
+        assert!(time.parse_date_at("someday", now).is_err());
+
+        let start = time
+            .start_of_day(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap())
+            .unwrap();
+        assert_eq!(start, utc(2026, 3, 2, 5, 0));
+    }
+
+    #[test]
+    fn named_timezone_applies_to_phrases_and_cron() {
+        let berlin = TimeService::new(Some("Europe/Berlin")).unwrap();
+        assert_eq!(berlin.zone_name(), "Europe/Berlin");
+        // 17:00 in Berlin has just passed, so "5pm" is tomorrow.
+        let now = utc(2026, 3, 2, 16, 0);
+        assert_eq!(berlin.parse_at("5pm", now).unwrap(), utc(2026, 3, 3, 16, 0));
+        // Clocks spring forward on 2026-03-29: 02:30 never happens and a day
+        // later keeps the wall-clock time.
+        assert!(berlin.parse_at("2026-03-29 02:30", now).is_err());
+        assert_eq!(
+            berlin
+                .parse_at("in 1 day", utc(2026, 3, 28, 12, 0))
+                .unwrap(),
+            utc(2026, 3, 29, 11, 0)
+        );
+
+        let daily = CronSchedule::parse("0 9 * * *").unwrap();
+        assert_eq!(
+            berlin.next_cron(&daily, utc(2026, 3, 2, 0, 0)),
+            Some(utc(2026, 3, 2, 8, 0))
+        );
+
+        assert!(TimeService::new(Some("Mars/Olympus_Mons")).is_err());
+        assert_eq!(
+            TimeService::new(Some("local")).unwrap(),
+            TimeService::local()
+        );
+        assert_eq!(TimeService::new(None).unwrap().zone_name(), "local");
+    }
+}
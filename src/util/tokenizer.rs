@@ -0,0 +1,291 @@
+//! Token counting and context-window sizes per model.
+//!
+//! OpenAI models are measured by splitting text with the same pre-tokenizer
+//! rules as tiktoken's `cl100k_base` / `o200k_base` (contractions, letter
+//! runs, 1–3 digit groups, punctuation runs, whitespace) and costing each
+//! piece the way BPE merges typically resolve it. The merge tables are not
+//! bundled, so counts are close to tiktoken rather than exact. Other models
+//! fall back to a ~4 characters per token heuristic.
+
+use crate::providers::ChatMessage;
+
+/// Context window assumed for models not in the table below.
+pub const DEFAULT_CONTEXT_WINDOW: usize = 32_768;
+
+/// Tokens held back from the context window for the model's reply.
+const RESERVED_OUTPUT_TOKENS: usize = 4_096;
+
+/// Per-message framing overhead (role markers), as in OpenAI's chat format.
+const TOKENS_PER_MESSAGE: usize = 3;
+
+/// Tokens that prime the assistant reply.
+const REPLY_PRIMING_TOKENS: usize = 3;
+
+/// Which counting strategy applies to a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// GPT-4o, GPT-4.1, GPT-5 and the o-series.
+    O200k,
+    /// GPT-4, GPT-3.5 and OpenAI embedding models.
+    Cl100k,
+    /// ~4 characters per token.
+    Heuristic,
+}
+
+impl Tokenizer {
+    /// Pick the tokenizer for `model`. Provider prefixes such as
+    /// `openai/gpt-4o` are ignored.
+    pub fn for_model(model: &str) -> Self {
+        let name = base_model_name(model);
+        if ["gpt-4o", "chatgpt-4o", "gpt-4.1", "gpt-4.5", "gpt-5"]
+            .iter()
+            .any(|p| name.starts_with(p))
+            || is_o_series(&name)
+        {
+            Self::O200k
+        } else if ["gpt-4", "gpt-3.5", "text-embedding-", "davinci", "babbage"]
+            .iter()
+            .any(|p| name.starts_with(p))
+        {
+            Self::Cl100k
+        } else {
+            Self::Heuristic
+        }
+    }
+
+    pub fn count(self, text: &str) -> usize {
+        match self {
+            Self::Heuristic => estimate_tokens(text),
+            Self::Cl100k | Self::O200k => pretokenize(text)
+                .into_iter()
+                .map(|piece| self.piece_cost(piece))
+                .sum(),
+        }
+    }
+
+    /// Approximate BPE tokens for one pre-tokenized piece.
+    fn piece_cost(self, piece: &str) -> usize {
+        // o200k's larger vocabulary merges longer words and non-Latin text.
+        let (word_chars, non_ascii_per_token) = match self {
+            Self::O200k => (7, 2),
+            _ => (6, 1),
+        };
+        let first = piece.chars().next().unwrap_or(' ');
+        let letters = piece.trim_start_matches(|c: char| !c.is_alphanumeric());
+        if !letters.is_empty() && letters.chars().next().is_some_and(char::is_alphabetic) {
+            let ascii = letters.chars().filter(char::is_ascii).count();
+            let non_ascii = letters.chars().count() - ascii;
+            return (ascii.div_ceil(word_chars) + non_ascii.div_ceil(non_ascii_per_token)).max(1);
+        }
+        if first.is_numeric() {
+            return 1;
+        }
+        if first.is_whitespace() && piece.chars().all(char::is_whitespace) {
+            let newlines = piece.matches('\n').count();
+            return if newlines > 0 {
+                newlines.div_ceil(2)
+            } else {
+                piece.chars().count().div_ceil(8)
+            };
+        }
+        // Punctuation/symbols: common pairs (").", "``") merge, long runs don't.
+        piece.trim().chars().count().div_ceil(2).max(1)
+    }
+}
+
+/// Heuristic token estimate (~4 characters per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Tokens in `text` for `model`.
+pub fn count_tokens(model: &str, text: &str) -> usize {
+    Tokenizer::for_model(model).count(text)
+}
+
+/// Tokens a chat request with `messages` consumes for `model`, including
+/// per-message framing.
+pub fn count_message_tokens(model: &str, messages: &[ChatMessage]) -> usize {
+    let tokenizer = Tokenizer::for_model(model);
+    messages
+        .iter()
+        .map(|m| TOKENS_PER_MESSAGE + tokenizer.count(&m.role) + tokenizer.count(&m.content))
+        .sum::<usize>()
+        + REPLY_PRIMING_TOKENS
+}
+
+/// Context window (input + output tokens) of `model`.
+pub fn context_window(model: &str) -> usize {
+    let name = base_model_name(model);
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| name.starts_with(p));
+    if starts(&["gpt-4.1"]) {
+        1_047_576
+    } else if starts(&["gpt-5"]) {
+        400_000
+    } else if is_o_series(&name) || name.contains("claude") {
+        200_000
+    } else if starts(&["gpt-4o", "chatgpt-4o", "gpt-4-turbo", "gpt-4.5"]) {
+        128_000
+    } else if starts(&["gpt-4-32k"]) {
+        32_768
+    } else if starts(&["gpt-4"]) {
+        8_192
+    } else if starts(&["gpt-3.5"]) {
+        16_385
+    } else if name.contains("gemini") {
+        1_048_576
+    } else if name.contains("llama-3") || name.contains("llama3") || name.contains("glm") {
+        128_000
+    } else if name.contains("deepseek") {
+        64_000
+    } else {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+/// Prompt tokens available for `model` after reserving room for the reply.
+pub fn prompt_budget(model: &str) -> usize {
+    let window = context_window(model);
+    window.saturating_sub(RESERVED_OUTPUT_TOKENS.min(window / 4))
+}
+
+fn base_model_name(model: &str) -> String {
+    model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// `o1`, `o3-mini`, `o4-mini`, ...
+fn is_o_series(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next() == Some('o') && chars.next().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Split `text` into pieces following tiktoken's cl100k pattern.
+fn pretokenize(text: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map_or(text.len(), |(b, _)| *b);
+    let is_newline = |c: char| c == '\n' || c == '\r';
+    let run = |mut i: usize, pred: &dyn Fn(char) -> bool| {
+        while i < chars.len() && pred(chars[i].1) {
+            i += 1;
+        }
+        i
+    };
+
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let end = if c == '\'' && contraction_len(&chars[i + 1..]) > 0 {
+            i + 1 + contraction_len(&chars[i + 1..])
+        } else if c.is_alphabetic() {
+            run(i, &|c| c.is_alphabetic())
+        } else if !c.is_numeric() && !is_newline(c) && next.is_some_and(char::is_alphabetic) {
+            // One leading space/symbol joins the following word (" world").
+            run(i + 1, &|c| c.is_alphabetic())
+        } else if c.is_numeric() {
+            let digits = run(i, &|c| c.is_numeric());
+            digits.min(i + 3)
+        } else if !c.is_whitespace() || (c == ' ' && next.is_some_and(is_symbol)) {
+            let start = if c == ' ' { i + 1 } else { i };
+            let symbols = run(start, &is_symbol);
+            run(symbols, &is_newline)
+        } else {
+            let ws_end = run(i, &|c| c.is_whitespace());
+            let last_newline = (i..ws_end).rev().find(|&j| is_newline(chars[j].1));
+            match last_newline {
+                Some(j) => j + 1,
+                // Leave the final space to prefix the next word.
+                None if ws_end < chars.len() && ws_end - i > 1 => ws_end - 1,
+                None => ws_end,
+            }
+        };
+        pieces.push(&text[byte_at(i)..byte_at(end)]);
+        i = end;
+    }
+    pieces
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_whitespace() && !c.is_alphanumeric()
+}
+
+/// Length of an English contraction suffix (`s`, `ll`, `ve`, ...) at the
+/// start of `rest`, or 0.
+fn contraction_len(rest: &[(usize, char)]) -> usize {
+    let lower: String = rest
+        .iter()
+        .take(2)
+        .map(|(_, c)| c.to_ascii_lowercase())
+        .collect();
+    if ["ll", "ve", "re"].iter().any(|s| lower.starts_with(s)) {
+        2
+    } else if lower.starts_with(['s', 'd', 'm', 't']) {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pretokenize_follows_cl100k_split_rules() {
+        assert_eq!(
+            pretokenize("Hello, world! 1234567"),
+            ["Hello", ",", " world", "!", " ", "123", "456", "7"]
+        );
+        assert_eq!(
+            pretokenize("don't  stop\n\n  go"),
+            ["don", "'t", " ", " stop", "\n\n", " ", " go"]
+        );
+        assert_eq!(pretokenize("fn main() {}"), ["fn", " main", "()", " {}"]);
+        assert_eq!(pretokenize("").len(), 0);
+    }
+
+    #[test]
+    fn counts_track_tiktoken_for_openai_models() {
+        // tiktoken cl100k_base: "Hello, world!" is 4 tokens.
+        assert_eq!(count_tokens("gpt-4", "Hello, world!"), 4);
+        assert_eq!(count_tokens("openai/gpt-4o", "1234567"), 3);
+        // Unknown models use the character heuristic.
+        assert_eq!(count_tokens("llama3.2", "Hello, world!"), 4);
+        assert_eq!(count_tokens("mystery-model", "abcdefgh"), 2);
+
+        let messages = [ChatMessage::system("Be brief."), ChatMessage::user("Hi")];
+        // 2 × 3 framing + role + content, + 3 priming.
+        assert_eq!(
+            count_message_tokens("gpt-4o", &messages),
+            2 * 3 + 1 + 3 + 1 + 1 + 3
+        );
+    }
+
+    #[test]
+    fn tokenizer_and_context_window_by_model() {
+        assert_eq!(Tokenizer::for_model("gpt-4o-mini"), Tokenizer::O200k);
+        assert_eq!(Tokenizer::for_model("o3-mini"), Tokenizer::O200k);
+        assert_eq!(Tokenizer::for_model("gpt-3.5-turbo"), Tokenizer::Cl100k);
+        assert_eq!(
+            Tokenizer::for_model("anthropic/claude-sonnet-4"),
+            Tokenizer::Heuristic
+        );
+
+        assert_eq!(
+            context_window("anthropic/claude-sonnet-4-20250514"),
+            200_000
+        );
+        assert_eq!(context_window("gpt-4o"), 128_000);
+        assert_eq!(context_window("gpt-4"), 8_192);
+        assert_eq!(context_window("google/gemini-2.5-pro"), 1_048_576);
+        assert_eq!(context_window("some-local-model"), DEFAULT_CONTEXT_WINDOW);
+        assert_eq!(prompt_budget("gpt-4"), 8_192 - 2_048);
+        assert_eq!(prompt_budget("gpt-4o"), 128_000 - 4_096);
+    }
+}
@@ -18,7 +18,7 @@ use zeroclaw::memory;
 use zeroclaw::memory::Memory;
 use zeroclaw::observability::{NoopObserver, Observer};
 use zeroclaw::providers::{ChatRequest, ChatResponse, Provider, ToolCall};
-use zeroclaw::tools::{Tool, ToolResult};
+use zeroclaw::tools::{Tool, ToolError, ToolResult};
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Mock infrastructure
@@ -61,6 +61,7 @@ impl Provider for MockProvider {
                 tool_calls: vec![],
                 usage: None,
                 reasoning_content: None,
+                reasoning: None,
             });
         }
         Ok(guard.remove(0))
@@ -117,7 +118,9 @@ impl Tool for FailingTool {
         Ok(ToolResult {
             success: false,
             output: String::new(),
-            error: Some("Service unavailable: connection timeout".into()),
+            error: Some(ToolError::transient(
+                "Service unavailable: connection timeout",
+            )),
         })
     }
 }
@@ -183,6 +186,7 @@ fn text_response(text: &str) -> ChatResponse {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }
 }
 
@@ -192,6 +196,7 @@ fn tool_response(calls: Vec<ToolCall>) -> ChatResponse {
         tool_calls: calls,
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }
 }
 
@@ -361,6 +366,7 @@ async fn agent_handles_empty_provider_response() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }]));
 
     let mut agent = build_agent(provider, vec![Box::new(EchoTool)]);
@@ -376,6 +382,7 @@ async fn agent_handles_none_text_response() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     }]));
 
     let mut agent = build_agent(provider, vec![Box::new(EchoTool)]);
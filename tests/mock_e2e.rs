@@ -0,0 +1,82 @@
+//! End-to-end runs through the built-in `mock` provider (`--features mock`).
+//!
+//! The agent is driven by a JSONL script from `api_url`, exactly as a user or
+//! CI job would configure it, so real tools and the real autonomy policy run
+//! while the model side stays deterministic.
+
+#![cfg(feature = "mock")]
+
+use zeroclaw::Config;
+
+fn mock_config(workspace: &std::path::Path, script: &str) -> Config {
+    let script_path = workspace.join("script.jsonl");
+    std::fs::write(&script_path, script).unwrap();
+    let mut config = Config {
+        workspace_dir: workspace.to_path_buf(),
+        config_path: workspace.join("config.toml"),
+        default_provider: Some("mock".into()),
+        api_url: Some(script_path.to_string_lossy().into_owned()),
+        ..Config::default()
+    };
+    config.observability.local_stats = false;
+    config
+}
+
+#[tokio::test]
+async fn scripted_tool_call_reads_a_workspace_file() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("notes.txt"), "launch code 42\n").unwrap();
+    let config = mock_config(
+        tmp.path(),
+        r#"{"tool_calls": [{"name": "file_read", "arguments": {"path": "notes.txt"}}]}
+{"expect": "launch code 42", "text": "The note says 42."}
+"#,
+    );
+
+    let reply = zeroclaw::agent::process_message(config, "What does notes.txt say?")
+        .await
+        .unwrap();
+    assert_eq!(reply, "The note says 42.");
+}
+
+#[tokio::test]
+async fn read_only_autonomy_blocks_scripted_shell_command() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mut config = mock_config(
+        tmp.path(),
+        r#"{"tool_calls": [{"name": "shell", "arguments": {"command": "touch created.txt"}}]}
+{"expect": "not allowed", "text": "I am not allowed to do that."}
+"#,
+    );
+    let mut autonomy = toml::Value::try_from(&config.autonomy).unwrap();
+    autonomy["level"] = "readonly".into();
+    config.autonomy = autonomy.try_into().unwrap();
+
+    let reply = zeroclaw::agent::process_message(config, "Create created.txt")
+        .await
+        .unwrap();
+    assert_eq!(reply, "I am not allowed to do that.");
+    assert!(!tmp.path().join("created.txt").exists());
+}
+
+#[tokio::test]
+async fn plan_mode_runs_read_only_tools_and_plans_auto_approved_shell() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("notes.txt"), "create created.txt\n").unwrap();
+    let mut config = mock_config(
+        tmp.path(),
+        r#"{"tool_calls": [{"name": "file_read", "arguments": {"path": "notes.txt"}}, {"name": "shell", "arguments": {"command": "touch created.txt"}}]}
+{"text": "Create created.txt as the notes ask."}
+"#,
+    );
+    let mut autonomy = toml::Value::try_from(&config.autonomy).unwrap();
+    autonomy["level"] = "full".into();
+    config.autonomy = autonomy.try_into().unwrap();
+    config.autonomy.auto_approve.push("shell".into());
+
+    let mut agent = zeroclaw::agent::Agent::from_config(&config).unwrap();
+    let plan = agent.plan("Do what notes.txt says").await.unwrap();
+    let planned: Vec<&str> = plan.steps.iter().map(|s| s.tool.as_str()).collect();
+    assert_eq!(planned, ["shell"]);
+    assert!(!tmp.path().join("created.txt").exists());
+}
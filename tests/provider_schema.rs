@@ -154,6 +154,7 @@ fn chat_response_text_only() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     assert_eq!(resp.text_or_empty(), "Hello world");
@@ -171,6 +172,7 @@ fn chat_response_with_tool_calls() {
         }],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     assert!(resp.has_tool_calls());
@@ -185,6 +187,7 @@ fn chat_response_text_or_empty_handles_none() {
         tool_calls: vec![],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     assert_eq!(resp.text_or_empty(), "");
@@ -208,6 +211,7 @@ fn chat_response_multiple_tool_calls() {
         ],
         usage: None,
         reasoning_content: None,
+        reasoning: None,
     };
 
     assert!(resp.has_tool_calls());